@@ -2,4 +2,5 @@ pub use self::token_response::TokenResponse;
 
 pub mod claims;
 pub mod token_response;
+pub mod two_factor;
 pub mod user;
@@ -0,0 +1,61 @@
+use bigneon_db::models::{User, UserTwoFactorCredential};
+use config::{Config, Environment};
+use db::Connection;
+use errors::*;
+
+/// Second step of signing in for users who have enabled two factor authentication, the code
+/// can come from their authenticator app or be one of their recovery codes
+pub fn verify_login(
+    user: &User,
+    two_factor_code: &Option<String>,
+    config: &Config,
+    connection: &Connection,
+) -> Result<(), BigNeonError> {
+    let credential =
+        match UserTwoFactorCredential::find_enabled_for_user(user.id, connection.get())? {
+            Some(credential) => credential,
+            None => return Ok(()),
+        };
+    match two_factor_code {
+        Some(code) => {
+            if verify_code(&credential, code, config, connection)? {
+                Ok(())
+            } else {
+                Err(AuthError::new(
+                    AuthErrorType::Unauthorized,
+                    "Two factor authentication code is invalid".to_string(),
+                )
+                .into())
+            }
+        }
+        None => Err(AuthError::new(
+            AuthErrorType::TwoFactorRequired,
+            "Two factor authentication code required".to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Checks a code for a user's credential, refusing all codes while it is locked after too many
+/// invalid codes
+pub fn verify_code(
+    credential: &UserTwoFactorCredential,
+    code: &str,
+    config: &Config,
+    connection: &Connection,
+) -> Result<bool, BigNeonError> {
+    if credential.is_locked() {
+        return Err(AuthError::new(
+            AuthErrorType::Unauthorized,
+            "Too many invalid two factor authentication codes, try again later".to_string(),
+        )
+        .into());
+    }
+    let verified = credential.verify(code, &config.api_keys_encryption_key, connection.get())?;
+    // The invalid attempt is kept when the request's transaction is rolled back
+    if !verified && config.environment != Environment::Test {
+        connection.commit_transaction()?;
+        connection.begin_transaction()?;
+    }
+    Ok(verified)
+}
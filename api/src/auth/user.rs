@@ -63,7 +63,9 @@ impl User {
             logging_data.insert("organization_scopes", json!(organization_scopes));
             logging_data.insert("organization_id", json!(organization.id));
 
-            if organization_scopes.contains(&scope) {
+            if organization_scopes.contains(&scope)
+                && !self.blocked_by_two_factor_policy(scope, organization, connection)?
+            {
                 // User is an event limited access user so their organization event ids must include this event
                 if event.is_some() {
                     // If the user's roles include an event limited role
@@ -98,6 +100,44 @@ impl User {
         Ok(false)
     }
 
+    /// Organizations can require members to enable two factor authentication before using
+    /// financial scopes, scopes held globally are not affected
+    fn blocked_by_two_factor_policy(
+        &self,
+        scope: Scopes,
+        organization: &Organization,
+        connection: &PgConnection,
+    ) -> Result<bool, BigNeonError> {
+        Ok(scope.is_financial()
+            && organization.two_factor_required
+            && !self.user.two_factor_enabled(connection)?)
+    }
+
+    fn organization_access_denied(
+        &self,
+        scope: Scopes,
+        organization: &Organization,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        if self.blocked_by_two_factor_policy(scope, organization, conn)?
+            && organization
+                .get_scopes_for_user(&self.user, conn)?
+                .contains(&scope)
+        {
+            return Err(AuthError::new(
+                AuthErrorType::Forbidden,
+                "Two factor authentication must be enabled to access financial information"
+                    .to_string(),
+            )
+            .into());
+        }
+        Err(AuthError::new(
+            AuthErrorType::Unauthorized,
+            "User does not have the required permissions".to_string(),
+        )
+        .into())
+    }
+
     pub fn has_scope(&self, scope: Scopes) -> Result<bool, BigNeonError> {
         self.check_scope_access(scope, None, None, None, false)
     }
@@ -151,11 +191,7 @@ impl User {
         if self.check_scope_access(scope, Some(organization), Some(event), Some(conn), true)? {
             return Ok(());
         }
-        Err(AuthError::new(
-            AuthErrorType::Unauthorized,
-            "User does not have the required permissions".to_string(),
-        )
-        .into())
+        self.organization_access_denied(scope, organization, conn)
     }
 
     pub fn requires_scope_for_organization(
@@ -167,10 +203,6 @@ impl User {
         if self.check_scope_access(scope, Some(organization), None, Some(conn), true)? {
             return Ok(());
         }
-        Err(AuthError::new(
-            AuthErrorType::Unauthorized,
-            "User does not have the required permissions".to_string(),
-        )
-        .into())
+        self.organization_access_denied(scope, organization, conn)
     }
 }
@@ -0,0 +1,153 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::pg::PgConnection;
+use errors::*;
+use utils::communication::*;
+use uuid::Uuid;
+
+pub fn default_template_id(template_kind: EmailTemplateKinds, config: &Config) -> String {
+    match template_kind {
+        EmailTemplateKinds::PurchaseCompleted => {
+            config.sendgrid_template_bn_purchase_completed.clone()
+        }
+        EmailTemplateKinds::Refund => config.sendgrid_template_bn_refund.clone(),
+        EmailTemplateKinds::TransferTickets => config.sendgrid_template_bn_transfer_tickets.clone(),
+    }
+}
+
+/// The organization whose branding applies to an order's emails, orders spanning several
+/// organizations keep the default branding
+pub fn organization_id_for_order(
+    order: &Order,
+    conn: &PgConnection,
+) -> Result<Option<Uuid>, BigNeonError> {
+    let organizations = order.organizations(conn)?;
+    Ok(match organizations.len() {
+        1 => Some(organizations[0].id),
+        _ => None,
+    })
+}
+
+/// The organization whose branding applies to emails about these tickets, tickets from
+/// several organizations keep the default branding
+pub fn organization_id_for_tickets(
+    ticket_ids: &[Uuid],
+    conn: &PgConnection,
+) -> Result<Option<Uuid>, BigNeonError> {
+    let mut organization_id = None;
+    for ticket_id in ticket_ids {
+        let ticket = TicketInstance::find(*ticket_id, conn)?;
+        let ticket_organization_id = Organization::find_by_asset_id(ticket.asset_id, conn)?.id;
+        match organization_id {
+            Some(id) if id != ticket_organization_id => return Ok(None),
+            _ => organization_id = Some(ticket_organization_id),
+        }
+    }
+    Ok(organization_id)
+}
+
+pub fn apply_organization_template(
+    communication: &mut Communication,
+    template: &OrganizationEmailTemplate,
+) {
+    if let Some(ref template_id) = template.template_id {
+        communication.template_id = Some(template_id.clone());
+    }
+    if let Some(ref reply_to_email) = template.reply_to_email {
+        communication.reply_to = Some(CommAddress::from(reply_to_email.clone()));
+    }
+
+    let branding = [
+        ("logo_url", &template.logo_url),
+        ("primary_color", &template.primary_color),
+        ("footer_text", &template.footer_text),
+    ];
+    if let Some(ref mut template_data) = communication.template_data {
+        for data in template_data.iter_mut() {
+            for (key, value) in branding.iter() {
+                if let Some(value) = value {
+                    data.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Applies the organization's overrides for this kind of email, if it has any
+pub fn apply_organization_branding(
+    communication: &mut Communication,
+    organization_id: Option<Uuid>,
+    template_kind: EmailTemplateKinds,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    if let Some(organization_id) = organization_id {
+        if let Some(template) = OrganizationEmailTemplate::find_by_organization_and_kind(
+            organization_id,
+            template_kind,
+            conn,
+        )? {
+            apply_organization_template(communication, &template);
+        }
+    }
+    Ok(())
+}
+
+/// Builds the communication an organization's customers would be sent using placeholder order data
+pub fn sample_communication(
+    organization_id: Uuid,
+    template_kind: EmailTemplateKinds,
+    recipient: String,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<Communication, BigNeonError> {
+    let mut template_data = TemplateData::new();
+    let title = match template_kind {
+        EmailTemplateKinds::PurchaseCompleted | EmailTemplateKinds::Refund => {
+            template_data.insert("name".to_string(), "Sample".to_string());
+            template_data.insert("ticket_count".to_string(), "2".to_string());
+            template_data.insert("total_fees".to_string(), "2.00".to_string());
+            template_data.insert("total_price".to_string(), "52.00".to_string());
+            template_data.insert(
+                "item_breakdown".to_string(),
+                r#"<table style="width:100%"><tbody><tr><th>Units</th><th>Description</th><th>Unit Price</th><th>Total</th></tr><tr><th align="center">2</th><th>Sample Event - General Admission</th><th align="right">$25.00</th><th align="right">$50.00</th></tr></tbody></table>"#.to_string(),
+            );
+            template_data.insert(
+                "tickets_link".to_string(),
+                format!("{}/hub", config.front_end_url),
+            );
+            if template_kind == EmailTemplateKinds::Refund {
+                template_data.insert("amount_refunded".to_string(), "5200".to_string());
+                "BigNeon Refund".to_string()
+            } else {
+                "BigNeon Purchase Completed".to_string()
+            }
+        }
+        EmailTemplateKinds::TransferTickets => {
+            template_data.insert("sender_name".to_string(), "Sample Sender".to_string());
+            template_data.insert(
+                "receive_tickets_link".to_string(),
+                format!("{}/tickets/receive", config.front_end_url),
+            );
+            "{sender_name} has sent you some tickets".to_string()
+        }
+    };
+
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(CommAddress::from(
+            config.communication_default_source_email.clone(),
+        )),
+        CommAddress::from(recipient),
+        Some(default_template_id(template_kind, config)),
+        Some(vec![template_data]),
+    );
+    apply_organization_branding(
+        &mut communication,
+        Some(organization_id),
+        template_kind,
+        conn,
+    )?;
+    Ok(communication)
+}
@@ -1,19 +1,24 @@
-use bigneon_db::models::enums::OrderItemTypes;
+use bigneon_db::models::enums::{EmailTemplateKinds, OrderItemTypes, Tables};
 use bigneon_db::models::DisplayOrder;
+use communications::mailers::branding;
 use config::Config;
+use diesel::PgConnection;
 use errors::*;
 use utils::communication::*;
+use uuid::Uuid;
 
 pub fn purchase_completed(
     user_first_name: &String,
     user_email: String,
     display_order: DisplayOrder,
+    organization_id: Option<Uuid>,
     config: &Config,
+    conn: &PgConnection,
 ) -> Result<Communication, BigNeonError> {
     let source = CommAddress::from(config.communication_default_source_email.clone());
     let destinations = CommAddress::from(user_email);
     let title = "BigNeon Purchase Completed".to_string();
-    let template_id = config.sendgrid_template_bn_purchase_completed.clone();
+    let template_id = branding::default_template_id(EmailTemplateKinds::PurchaseCompleted, config);
     let mut template_data = TemplateData::new();
     template_data.insert(String::from("name"), user_first_name.clone());
     //Construct an itemised breakdown using a HTML table
@@ -68,7 +73,7 @@ pub fn purchase_completed(
     );
 
     // TODO: Perhaps move this to an event subscription
-    Ok(Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -76,5 +81,13 @@ pub fn purchase_completed(
         destinations,
         Some(template_id),
         Some(vec![template_data]),
-    ))
+    );
+    communication.set_main_table(Tables::Orders, display_order.id);
+    branding::apply_organization_branding(
+        &mut communication,
+        organization_id,
+        EmailTemplateKinds::PurchaseCompleted,
+        conn,
+    )?;
+    Ok(communication)
 }
@@ -0,0 +1,164 @@
+use bigneon_db::models::{Event, EventCommunicationSchedule, EventReschedule, Tables, User, Venue};
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use utils::communication::*;
+use uuid::Uuid;
+
+// SendGrid accepts at most 1000 personalizations in a single request
+const MAX_RECIPIENTS_PER_EMAIL: usize = 1000;
+
+pub fn rescheduled(
+    first_name: &str,
+    email: String,
+    event: &Event,
+    venue: &Option<Venue>,
+    reschedule: &EventReschedule,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("BigNeon: {} has been rescheduled", event.name);
+    let event_start = event
+        .get_all_localized_time_strings(venue)
+        .event_start
+        .unwrap_or_else(|| format!("{} UTC", reschedule.event_start.format("%Y-%m-%d %H:%M")));
+    let body = format!(
+        "Hi {}, {} has been rescheduled to {}. Your tickets remain valid for the new date. \
+         If you can no longer attend you can request a refund before {} UTC at \
+         {}/event_reschedules/{}/refund",
+        first_name,
+        event.name,
+        event_start,
+        reschedule.refund_deadline.format("%Y-%m-%d %H:%M"),
+        config.front_end_url,
+        reschedule.id
+    );
+
+    Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
+
+pub fn cancelled(
+    first_name: &str,
+    email: String,
+    event: &Event,
+    amount_refunded: u32,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("BigNeon: {} has been cancelled", event.name);
+    let body = if amount_refunded > 0 {
+        format!(
+            "Hi {}, unfortunately {} has been cancelled. Your tickets are no longer valid and \
+             {:.2} has been refunded to your original payment method.",
+            first_name,
+            event.name,
+            amount_refunded as f64 / 100.0
+        )
+    } else {
+        format!(
+            "Hi {}, unfortunately {} has been cancelled. Your tickets are no longer valid.",
+            first_name, event.name
+        )
+    };
+
+    Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
+
+/// Sends an organization's reminder or follow-up to each ticket holder with an email address
+pub fn scheduled_communication(
+    event: &Event,
+    schedule: &EventCommunicationSchedule,
+    user_ids: &[Uuid],
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let venue = event.venue(conn)?;
+    let title = schedule.title_for(event, &venue);
+    let message = schedule.message_for(event, &venue);
+    let localized_times = event.get_all_localized_time_strings(&venue);
+
+    let recipients: Vec<(String, String)> = User::find_by_ids(user_ids, conn)?
+        .into_iter()
+        .filter_map(|user| {
+            let first_name = user.first_name.unwrap_or_default();
+            user.email.map(|email| (email, first_name))
+        })
+        .collect();
+
+    for recipients in recipients.chunks(MAX_RECIPIENTS_PER_EMAIL) {
+        let source = CommAddress::from(config.communication_default_source_email.clone());
+        let destinations =
+            CommAddress::from_vec(recipients.iter().map(|(email, _)| email.clone()).collect());
+
+        let mut communication = match schedule.template_id {
+            Some(ref template_id) => {
+                let template_data = recipients
+                    .iter()
+                    .map(|(_, first_name)| {
+                        let mut template_data = TemplateData::new();
+                        template_data.insert("name".to_string(), first_name.clone());
+                        template_data.insert("title".to_string(), title.clone());
+                        template_data.insert("message".to_string(), message.clone());
+                        template_data.insert("event_name".to_string(), event.name.clone());
+                        template_data.insert(
+                            "venue_name".to_string(),
+                            venue.as_ref().map(|v| v.name.clone()).unwrap_or_default(),
+                        );
+                        template_data.insert(
+                            "door_time".to_string(),
+                            localized_times.door_time.clone().unwrap_or_default(),
+                        );
+                        template_data.insert(
+                            "event_start".to_string(),
+                            localized_times.event_start.clone().unwrap_or_default(),
+                        );
+                        template_data
+                    })
+                    .collect();
+                Communication::new(
+                    CommunicationType::EmailTemplate,
+                    title.clone(),
+                    None,
+                    Some(source),
+                    destinations,
+                    Some(template_id.clone()),
+                    Some(template_data),
+                )
+            }
+            None => Communication::new(
+                CommunicationType::Email,
+                title.clone(),
+                Some(message.clone()),
+                Some(source),
+                destinations,
+                None,
+                None,
+            ),
+        };
+        communication.set_main_table(Tables::Events, event.id);
+        communication.queue(conn)?;
+    }
+    Ok(())
+}
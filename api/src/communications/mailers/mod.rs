@@ -1,5 +1,9 @@
+pub mod branding;
 pub mod cart;
+pub mod events;
 pub mod orders;
 pub mod organization_invites;
+pub mod reports;
 pub mod tickets;
 pub mod user;
+pub mod waitlist;
@@ -1,22 +1,25 @@
-use bigneon_db::models::enums::OrderItemTypes;
+use bigneon_db::models::enums::{EmailTemplateKinds, OrderItemTypes, Tables};
 use bigneon_db::models::DisplayOrder;
+use communications::mailers::branding;
 use config::Config;
 use diesel::PgConnection;
 use errors::*;
 use utils::communication::*;
+use uuid::Uuid;
 
 pub fn refund_email(
     user_first_name: &String,
     user_email: String,
     display_order: DisplayOrder,
     amount_refunded: u32,
+    organization_id: Option<Uuid>,
     config: &Config,
     conn: &PgConnection,
 ) -> Result<(), BigNeonError> {
     let source = CommAddress::from(config.communication_default_source_email.clone());
     let destinations = CommAddress::from(user_email);
     let title = "BigNeon Refund".to_string();
-    let template_id = config.sendgrid_template_bn_refund.clone();
+    let template_id = branding::default_template_id(EmailTemplateKinds::Refund, config);
     let mut template_data = TemplateData::new();
     template_data.insert(String::from("name"), user_first_name.clone());
     //Construct an itemised breakdown using a HTML table
@@ -74,7 +77,7 @@ pub fn refund_email(
     );
 
     // TODO: Perhaps move this to an event subscription
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -82,6 +85,13 @@ pub fn refund_email(
         destinations,
         Some(template_id),
         Some(vec![template_data]),
-    )
-    .queue(conn)
+    );
+    communication.set_main_table(Tables::Orders, display_order.id);
+    branding::apply_organization_branding(
+        &mut communication,
+        organization_id,
+        EmailTemplateKinds::Refund,
+        conn,
+    )?;
+    communication.queue(conn)
 }
@@ -0,0 +1,44 @@
+use bigneon_db::models::{Organization, ReportSubscription};
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use utils::communication::*;
+
+pub fn scheduled_report(
+    report_subscription: &ReportSubscription,
+    organization: &Organization,
+    csv: String,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(report_subscription.email.clone());
+    let (start, end) = report_subscription.report_period();
+    let title = format!(
+        "BigNeon: {} report for {}",
+        report_subscription.report_type, organization.name
+    );
+    let body = format!(
+        "Attached is the {} report for {} covering {} to {} UTC.",
+        report_subscription.report_type,
+        organization.name,
+        start.format("%Y-%m-%d %H:%M"),
+        end.format("%Y-%m-%d %H:%M")
+    );
+
+    let mut communication = Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    );
+    communication.add_attachment(CommAttachment::new(
+        report_subscription.file_name(),
+        "text/csv".to_string(),
+        csv.as_bytes(),
+    ));
+    communication.queue(conn)
+}
@@ -1,4 +1,5 @@
-use bigneon_db::models::User;
+use bigneon_db::models::{EmailTemplateKinds, User};
+use communications::mailers::branding;
 use config::Config;
 use diesel::pg::PgConnection;
 use errors::*;
@@ -6,6 +7,7 @@ use utils::communication::CommAddress;
 use utils::communication::Communication;
 use utils::communication::CommunicationType;
 use utils::communication::TemplateData;
+use uuid::Uuid;
 
 pub fn send_tickets(
     config: &Config,
@@ -15,6 +17,7 @@ pub fn send_tickets(
     transfer_key: &str,
     signature: &str,
     from_user: &User,
+    organization_id: Option<Uuid>,
     conn: &PgConnection,
 ) -> Result<(), BigNeonError> {
     let receive_tickets_link = format!(
@@ -29,11 +32,11 @@ pub fn send_tickets(
     let source = CommAddress::from(config.communication_default_source_email.clone());
     let destinations = CommAddress::from(email);
     let title = "{sender_name} has sent you some tickets".to_string();
-    let template_id = config.sendgrid_template_bn_transfer_tickets.clone();
+    let template_id = branding::default_template_id(EmailTemplateKinds::TransferTickets, config);
     let mut template_data = TemplateData::new();
     template_data.insert("sender_name".to_string(), from_user.full_name());
     template_data.insert("receive_tickets_link".to_string(), receive_tickets_link);
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -41,6 +44,12 @@ pub fn send_tickets(
         destinations,
         Some(template_id),
         Some(vec![template_data]),
-    )
-    .queue(conn)
+    );
+    branding::apply_organization_branding(
+        &mut communication,
+        organization_id,
+        EmailTemplateKinds::TransferTickets,
+        conn,
+    )?;
+    communication.queue(conn)
 }
@@ -0,0 +1,38 @@
+use bigneon_db::models::{Event, TicketType, WaitlistEntry};
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use utils::communication::*;
+
+pub fn tickets_offered(
+    user_email: String,
+    waitlist_entry: &WaitlistEntry,
+    ticket_type: &TicketType,
+    event: &Event,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(user_email);
+    let title = format!("BigNeon: Tickets available for {}", event.name);
+    let expires_at = waitlist_entry
+        .offer_expires_at
+        .map(|e| format!(" before {} UTC", e.format("%Y-%m-%d %H:%M")))
+        .unwrap_or_default();
+    let body = format!(
+        "Good news! {} {} ticket(s) for {} have been reserved for you from the waitlist. \
+         Complete your purchase{} at {}/cart to claim them.",
+        waitlist_entry.quantity, ticket_type.name, event.name, expires_at, config.front_end_url
+    );
+
+    Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
@@ -1,2 +1,3 @@
 pub mod mailers;
+pub mod pushers;
 pub mod smsers;
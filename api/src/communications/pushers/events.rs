@@ -0,0 +1,59 @@
+use bigneon_db::models::{Event, EventCommunicationSchedule, Tables};
+use diesel::pg::PgConnection;
+use errors::*;
+use utils::communication::CommAddress;
+use utils::communication::Communication;
+use utils::communication::CommunicationType;
+use uuid::Uuid;
+
+pub fn event_day_reminder(
+    event: &Event,
+    user_ids: &[Uuid],
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+    let venue = event.venue(conn)?;
+    let body = match Event::localized_time_from_venue(&event.event_start, &venue) {
+        Some(event_start) => format!(
+            "{} starts today at {}, your tickets are in your wallet",
+            event.name,
+            event_start.format("%l:%M %p %Z").to_string().trim()
+        ),
+        None => format!("{} is today, your tickets are in your wallet", event.name),
+    };
+    Communication::new(
+        CommunicationType::Push,
+        event.name.clone(),
+        Some(body),
+        None,
+        CommAddress::from_vec(user_ids.iter().map(|id| id.to_string()).collect()),
+        None,
+        None,
+    )
+    .queue(conn)
+}
+
+pub fn scheduled_communication(
+    event: &Event,
+    schedule: &EventCommunicationSchedule,
+    user_ids: &[Uuid],
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+    let venue = event.venue(conn)?;
+    let mut communication = Communication::new(
+        CommunicationType::Push,
+        schedule.title_for(event, &venue),
+        Some(schedule.message_for(event, &venue)),
+        None,
+        CommAddress::from_vec(user_ids.iter().map(|id| id.to_string()).collect()),
+        None,
+        None,
+    );
+    communication.set_main_table(Tables::Events, event.id);
+    communication.queue(conn)
+}
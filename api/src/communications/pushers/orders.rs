@@ -0,0 +1,29 @@
+use bigneon_db::models::{Order, Tables};
+use diesel::pg::PgConnection;
+use errors::*;
+use utils::communication::CommAddress;
+use utils::communication::Communication;
+use utils::communication::CommunicationType;
+use uuid::Uuid;
+
+pub fn purchase_completed(
+    user_id: Uuid,
+    order: &Order,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let body = format!(
+        "Your order #{} is complete, your tickets are ready in your wallet",
+        order.order_number()
+    );
+    let mut communication = Communication::new(
+        CommunicationType::Push,
+        "Purchase complete".to_string(),
+        Some(body),
+        None,
+        CommAddress::from(user_id.to_string()),
+        None,
+        None,
+    );
+    communication.set_main_table(Tables::Orders, order.id);
+    communication.queue(conn)
+}
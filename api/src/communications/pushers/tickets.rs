@@ -0,0 +1,31 @@
+use bigneon_db::models::User;
+use diesel::pg::PgConnection;
+use errors::*;
+use utils::communication::CommAddress;
+use utils::communication::Communication;
+use utils::communication::CommunicationType;
+use uuid::Uuid;
+
+pub fn transfer_received(
+    from_user: &User,
+    recipient_user_id: Uuid,
+    num_tickets: usize,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let body = format!(
+        "{} has sent you {} ticket{}",
+        from_user.full_name(),
+        num_tickets,
+        if num_tickets == 1 { "" } else { "s" }
+    );
+    Communication::new(
+        CommunicationType::Push,
+        "Tickets received".to_string(),
+        Some(body),
+        None,
+        CommAddress::from(recipient_user_id.to_string()),
+        None,
+        None,
+    )
+    .queue(conn)
+}
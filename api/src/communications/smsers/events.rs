@@ -0,0 +1,37 @@
+use bigneon_db::models::{Event, EventReschedule};
+use config::Config;
+use diesel::pg::PgConnection;
+use errors::*;
+use utils::communication::CommAddress;
+use utils::communication::Communication;
+use utils::communication::CommunicationType;
+
+pub fn rescheduled(
+    config: &Config,
+    phone: String,
+    event: &Event,
+    reschedule: &EventReschedule,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_phone.clone());
+    let destinations = CommAddress::from(phone);
+    let body = format!(
+        "{} has been rescheduled to {} UTC. Can't make it? Request a refund before {} UTC: \
+         {}/event_reschedules/{}/refund",
+        event.name,
+        reschedule.event_start.format("%Y-%m-%d %H:%M"),
+        reschedule.refund_deadline.format("%Y-%m-%d %H:%M"),
+        config.front_end_url,
+        reschedule.id
+    );
+    Communication::new(
+        CommunicationType::Sms,
+        body,
+        None,
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
@@ -24,18 +24,25 @@ pub struct Config {
     pub facebook_app_secret: Option<String>,
     pub globee_api_key: String,
     pub globee_base_url: String,
+    pub paypal_client_id: String,
+    pub paypal_secret: String,
+    pub paypal_base_url: String,
     pub validate_ipns: bool,
     pub api_base_url: String,
     pub google_recaptcha_secret_key: Option<String>,
     pub http_keep_alive: usize,
     pub block_external_comms: bool,
     pub primary_currency: String,
+    pub push_notification_api_key: Option<String>,
+    pub push_notification_api_url: String,
     pub stripe_secret_key: String,
+    pub stripe_webhook_secret: Option<String>,
     pub token_secret: String,
     pub token_issuer: String,
     pub tari_client: Box<TariClient + Send + Sync>,
     pub communication_default_source_email: String,
     pub communication_default_source_phone: String,
+    pub communication_webhook_token: Option<String>,
     pub sendgrid_api_key: String,
     pub sendgrid_template_bn_refund: String,
     pub sendgrid_template_bn_user_registered: String,
@@ -48,6 +55,7 @@ pub struct Config {
     pub twilio_account_id: String,
     pub twilio_api_key: String,
     pub api_keys_encryption_key: String,
+    pub scanner_manifest_secret_key: String,
     pub jwt_expiry_time: u64,
     pub branch_io_base_url: String,
     pub branch_io_branch_key: String,
@@ -64,11 +72,18 @@ const FACEBOOK_APP_ID: &str = "FACEBOOK_APP_ID";
 const FACEBOOK_APP_SECRET: &str = "FACEBOOK_APP_SECRET";
 const GLOBEE_API_KEY: &str = "GLOBEE_API_KEY";
 const GLOBEE_BASE_URL: &str = "GLOBEE_BASE_URL";
+const PAYPAL_CLIENT_ID: &str = "PAYPAL_CLIENT_ID";
+const PAYPAL_SECRET: &str = "PAYPAL_SECRET";
+const PAYPAL_BASE_URL: &str = "PAYPAL_BASE_URL";
 const VALIDATE_IPNS: &str = "VALIDATE_IPNS";
 const API_BASE_URL: &str = "API_BASE_URL";
 const GOOGLE_RECAPTCHA_SECRET_KEY: &str = "GOOGLE_RECAPTCHA_SECRET_KEY";
 const PRIMARY_CURRENCY: &str = "PRIMARY_CURRENCY";
+// Push notifications are logged locally instead of being sent when no api key is configured
+const PUSH_NOTIFICATION_API_KEY: &str = "PUSH_NOTIFICATION_API_KEY";
+const PUSH_NOTIFICATION_API_URL: &str = "PUSH_NOTIFICATION_API_URL";
 const STRIPE_SECRET_KEY: &str = "STRIPE_SECRET_KEY";
+const STRIPE_WEBHOOK_SECRET: &str = "STRIPE_WEBHOOK_SECRET";
 const TARI_URL: &str = "TARI_URL";
 const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
 const TOKEN_SECRET: &str = "TOKEN_SECRET";
@@ -81,6 +96,8 @@ const FRONT_END_URL: &str = "FRONT_END_URL";
 //Communication settings
 const COMMUNICATION_DEFAULT_SOURCE_EMAIL: &str = "COMMUNICATION_DEFAULT_SOURCE_EMAIL";
 const COMMUNICATION_DEFAULT_SOURCE_PHONE: &str = "COMMUNICATION_DEFAULT_SOURCE_PHONE";
+// Shared secret SendGrid and Twilio include in their delivery status webhook urls
+const COMMUNICATION_WEBHOOK_TOKEN: &str = "COMMUNICATION_WEBHOOK_TOKEN";
 
 //SendGrid settings
 const SENDGRID_API_KEY: &str = "SENDGRID_API_KEY";
@@ -99,6 +116,8 @@ const TWILIO_API_KEY: &str = "TWILIO_API_KEY";
 const TWILIO_ACCOUNT_ID: &str = "TWILIO_ACCOUNT_ID";
 
 const API_KEYS_ENCRYPTION_KEY: &str = "API_KEYS_ENCRYPTION_KEY";
+// Dedicated key for signing door scanner manifests, kept separate from any wallet keys
+const SCANNER_MANIFEST_SECRET_KEY: &str = "SCANNER_MANIFEST_SECRET_KEY";
 
 const JWT_EXPIRY_TIME: &str = "JWT_EXPIRY_TIME";
 const BRANCH_IO_BASE_URL: &str = "BRANCH_IO_BASE_URL";
@@ -132,6 +151,7 @@ impl Config {
         let primary_currency = env::var(&PRIMARY_CURRENCY).unwrap_or_else(|_| "usd".to_string());
         let stripe_secret_key =
             env::var(&STRIPE_SECRET_KEY).unwrap_or_else(|_| "<stripe not enabled>".to_string());
+        let stripe_webhook_secret = env::var(&STRIPE_WEBHOOK_SECRET).ok();
         let token_secret =
             env::var(&TOKEN_SECRET).unwrap_or_else(|_| panic!("{} must be defined.", TOKEN_SECRET));
 
@@ -168,6 +188,15 @@ impl Config {
             _ => "https://test.globee.com/payment-api/v1/".to_string(),
         });
 
+        let paypal_client_id =
+            env::var(&PAYPAL_CLIENT_ID).unwrap_or_else(|_| "<paypal not enabled>".to_string());
+        let paypal_secret =
+            env::var(&PAYPAL_SECRET).unwrap_or_else(|_| "<paypal not enabled>".to_string());
+        let paypal_base_url = env::var(&PAYPAL_BASE_URL).unwrap_or_else(|_| match environment {
+            Environment::Production => "https://api.paypal.com/".to_string(),
+            _ => "https://api.sandbox.paypal.com/".to_string(),
+        });
+
         let branch_io_base_url =
             env::var(&BRANCH_IO_BASE_URL).unwrap_or("https://api2.branch.io/v1".to_string());
         let branch_io_branch_key = env::var(&BRANCH_IO_BRANCH_KEY)
@@ -186,6 +215,7 @@ impl Config {
             .unwrap_or_else(|_| panic!("{} must be defined.", COMMUNICATION_DEFAULT_SOURCE_EMAIL));
         let communication_default_source_phone = env::var(&COMMUNICATION_DEFAULT_SOURCE_PHONE)
             .unwrap_or_else(|_| panic!("{} must be defined.", COMMUNICATION_DEFAULT_SOURCE_PHONE));
+        let communication_webhook_token = env::var(&COMMUNICATION_WEBHOOK_TOKEN).ok();
 
         let sendgrid_api_key = env::var(&SENDGRID_API_KEY)
             .unwrap_or_else(|_| panic!("{} must be defined.", SENDGRID_API_KEY));
@@ -217,15 +247,22 @@ impl Config {
 
         let spotify_auth_token = env::var(&SPOTIFY_AUTH_TOKEN).ok();
 
+        let push_notification_api_key = env::var(&PUSH_NOTIFICATION_API_KEY).ok();
+        let push_notification_api_url = env::var(&PUSH_NOTIFICATION_API_URL)
+            .unwrap_or_else(|_| "https://fcm.googleapis.com/fcm/send".to_string());
+
         let twilio_api_key = env::var(&TWILIO_API_KEY)
-            .unwrap_or_else(|_| panic!("{} must be defined.", TWILIO_API_KEY));;
+            .unwrap_or_else(|_| panic!("{} must be defined.", TWILIO_API_KEY));
 
         let twilio_account_id = env::var(&TWILIO_ACCOUNT_ID)
-            .unwrap_or_else(|_| panic!("{} must be defined.", TWILIO_ACCOUNT_ID));;
+            .unwrap_or_else(|_| panic!("{} must be defined.", TWILIO_ACCOUNT_ID));
 
         let api_keys_encryption_key = env::var(&API_KEYS_ENCRYPTION_KEY)
             .unwrap_or_else(|_| panic!("{} must be defined.", API_KEYS_ENCRYPTION_KEY));
 
+        let scanner_manifest_secret_key = env::var(&SCANNER_MANIFEST_SECRET_KEY)
+            .unwrap_or_else(|_| panic!("{} must be defined.", SCANNER_MANIFEST_SECRET_KEY));
+
         let block_external_comms = match env::var(&BLOCK_EXTERNAL_COMMS)
             .unwrap_or_else(|_| "0".to_string())
             .as_str()
@@ -257,6 +294,9 @@ impl Config {
             facebook_app_secret,
             globee_api_key,
             globee_base_url,
+            paypal_client_id,
+            paypal_secret,
+            paypal_base_url,
             branch_io_base_url,
             validate_ipns,
             api_base_url,
@@ -264,13 +304,17 @@ impl Config {
             http_keep_alive,
             block_external_comms,
             primary_currency,
+            push_notification_api_key,
+            push_notification_api_url,
             stripe_secret_key,
+            stripe_webhook_secret,
             token_secret,
             token_issuer,
             front_end_url,
             tari_client,
             communication_default_source_email,
             communication_default_source_phone,
+            communication_webhook_token,
             sendgrid_api_key,
             sendgrid_template_bn_refund,
             sendgrid_template_bn_user_registered,
@@ -283,6 +327,7 @@ impl Config {
             twilio_api_key,
             twilio_account_id,
             api_keys_encryption_key,
+            scanner_manifest_secret_key,
             jwt_expiry_time,
             branch_io_branch_key,
         }
@@ -0,0 +1,69 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateAddOnRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub quantity: i64,
+    #[serde(default = "default_redeemable")]
+    pub redeemable: bool,
+}
+
+fn default_redeemable() -> bool {
+    true
+}
+
+pub fn index(
+    (connection, path): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let add_ons = AddOn::find_for_event(event.id, connection)?
+        .iter()
+        .map(|a| a.for_display(connection))
+        .collect::<Result<Vec<DisplayAddOn>, _>>()?;
+    Ok(HttpResponse::Ok().json(&add_ons))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateAddOnRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::TicketTypeWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let json = json.into_inner();
+    let add_on = AddOn::create(
+        event.id,
+        json.name,
+        json.description,
+        json.price_in_cents,
+        json.quantity,
+        json.redeemable,
+    )
+    .commit(user.id(), connection)?;
+    Ok(HttpResponse::Created().json(&add_on.for_display(connection)?))
+}
+
+/// Add-on vouchers purchased by the current user
+pub fn vouchers((connection, user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    Ok(HttpResponse::Ok().json(&AddOnVoucher::find_for_user(user.id(), connection)?))
+}
@@ -1,6 +1,6 @@
 use actix_web::{HttpRequest, HttpResponse, State};
-use auth::{claims::RefreshToken, TokenResponse};
-use bigneon_db::models::{deserialize_unless_blank, User};
+use auth::{claims::RefreshToken, two_factor, TokenResponse};
+use bigneon_db::models::{deserialize_unless_blank, User, UserTwoFactorCredential};
 use db::Connection;
 use errors::*;
 use extractors::*;
@@ -19,6 +19,10 @@ pub struct LoginRequest {
     #[serde(rename = "g-recaptcha-response")]
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     captcha_response: Option<String>,
+    /// Required once the password is accepted if the user has enabled two factor authentication,
+    /// either a code from their authenticator app or a recovery code
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    two_factor_code: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -32,8 +36,14 @@ impl LoginRequest {
             email: String::from(email),
             password: String::from(password),
             captcha_response: None,
+            two_factor_code: None,
         }
     }
+
+    pub fn with_two_factor_code(mut self, two_factor_code: &str) -> Self {
+        self.two_factor_code = Some(String::from(two_factor_code));
+        self
+    }
 }
 
 impl RefreshRequest {
@@ -106,6 +116,13 @@ pub fn token(
         );
     }
 
+    two_factor::verify_login(
+        &user,
+        &login_request.two_factor_code,
+        &state.config,
+        &connection,
+    )?;
+
     user.login_domain_event(json!(request_info), connection.get())?;
     jlog!(Info, "User logged in via email and password", {"id": user.id, "email": user.email.clone()});
     let response = TokenResponse::create_from_user(
@@ -135,6 +152,17 @@ pub fn token_refresh(
         return application::unauthorized_with_message("Invalid token", None, None);
     }
 
+    // Enabling two factor authentication also invalidates refresh tokens issued before it
+    if let Some(credential) =
+        UserTwoFactorCredential::find_enabled_for_user(user.id, connection.get())?
+    {
+        if let Some(enabled_at) = credential.enabled_at {
+            if enabled_at.timestamp() as u64 > token.claims.issued {
+                return application::unauthorized_with_message("Invalid token", None, None);
+            }
+        }
+    }
+
     let response = TokenResponse::create_from_refresh_token(
         &state.config.token_secret,
         &state.config.token_issuer,
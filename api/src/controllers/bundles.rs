@@ -0,0 +1,87 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateBundleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub quantity: i64,
+    pub ticket_types: Vec<CreateBundleTicketTypeRequest>,
+    pub pricing: Vec<CreateBundlePricingRequest>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateBundleTicketTypeRequest {
+    pub ticket_type_id: Uuid,
+    #[serde(default = "default_quantity")]
+    pub quantity: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateBundlePricingRequest {
+    pub name: String,
+    pub price_in_cents: i64,
+    pub start_date: NaiveDateTime,
+    pub end_date: NaiveDateTime,
+}
+
+fn default_quantity() -> i64 {
+    1
+}
+
+pub fn index(
+    (connection, path): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    let bundles = Bundle::find_for_organization(organization.id, connection)?
+        .iter()
+        .map(|b| b.for_display(connection))
+        .collect::<Result<Vec<DisplayBundle>, _>>()?;
+    Ok(HttpResponse::Ok().json(&bundles))
+}
+
+pub fn show(
+    (connection, path): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(path.id, connection)?;
+    Ok(HttpResponse::Ok().json(&bundle.for_display(connection)?))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateBundleRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &organization, connection)?;
+
+    let json = json.into_inner();
+    let bundle = Bundle::create(organization.id, json.name, json.description, json.quantity)
+        .commit(user.id(), connection)?;
+    for ticket_type in json.ticket_types {
+        bundle.add_ticket_type(ticket_type.ticket_type_id, ticket_type.quantity, connection)?;
+    }
+    for pricing in json.pricing {
+        bundle.add_pricing(
+            pricing.name,
+            pricing.start_date,
+            pricing.end_date,
+            pricing.price_in_cents,
+            connection,
+        )?;
+    }
+    Ok(HttpResponse::Created().json(&bundle.for_display(connection)?))
+}
@@ -7,6 +7,7 @@ use bigneon_db::models::*;
 use bigneon_db::utils::errors::Optional;
 use bigneon_db::utils::rand::random_alpha_string;
 use config::Config;
+use controllers::payment_methods;
 use db::Connection;
 use diesel::pg::PgConnection;
 use errors::*;
@@ -31,12 +32,22 @@ pub struct CartItem {
     pub quantity: u32,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub redemption_code: Option<String>,
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CartAddOnItem {
+    pub add_on_id: Uuid,
+    pub quantity: u32,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct UpdateCartRequest {
     pub items: Vec<CartItem>,
     pub box_office_pricing: Option<bool>,
+    #[serde(default)]
+    pub add_ons: Vec<CartAddOnItem>,
 }
 
 pub fn update_cart(
@@ -80,6 +91,7 @@ pub fn update_cart(
             quantity: i.quantity,
             ticket_type_id: i.ticket_type_id,
             redemption_code: i.redemption_code.clone(),
+            seat_ids: i.seat_ids.clone(),
         })
         .collect();
 
@@ -97,6 +109,7 @@ pub fn update_cart(
         false,
         connection,
     )?;
+    cart.update_add_on_quantities(user.id(), &add_on_items(&json.add_ons), false, connection)?;
 
     cart.set_user_agent(request_info.user_agent.clone(), false, connection)?;
     Ok(
@@ -114,6 +127,7 @@ pub fn destroy((connection, user): (Connection, User)) -> Result<HttpResponse, B
     // Find the current cart of the user, if it exists.
     let mut cart = Order::find_or_create_cart(&user.user, connection)?;
     cart.update_quantities(user.id(), &[], false, true, connection)?;
+    cart.update_add_on_quantities(user.id(), &[], true, connection)?;
 
     Ok(
         HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(
@@ -166,6 +180,7 @@ pub fn replace_cart(
             quantity: i.quantity,
             ticket_type_id: i.ticket_type_id,
             redemption_code: i.redemption_code.clone(),
+            seat_ids: i.seat_ids.clone(),
         })
         .collect();
 
@@ -183,6 +198,7 @@ pub fn replace_cart(
         true,
         connection,
     )?;
+    cart.update_add_on_quantities(user.id(), &add_on_items(&json.add_ons), true, connection)?;
 
     cart.set_user_agent(request_info.user_agent.clone(), false, connection)?;
     Ok(
@@ -194,6 +210,16 @@ pub fn replace_cart(
     )
 }
 
+fn add_on_items(add_ons: &[CartAddOnItem]) -> Vec<UpdateAddOnItem> {
+    add_ons
+        .iter()
+        .map(|i| UpdateAddOnItem {
+            add_on_id: i.add_on_id,
+            quantity: i.quantity,
+        })
+        .collect()
+}
+
 pub fn show((connection, user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let order = match Order::find_cart_for_user(user.id(), connection)? {
@@ -235,11 +261,60 @@ pub enum PaymentRequest {
     PaymentMethod {
         #[serde(default, deserialize_with = "deserialize_unless_blank")]
         provider: Option<PaymentProviders>,
+        /// A saved payment method, the default is used when neither it nor a provider is given
+        #[serde(default)]
+        payment_method_id: Option<Uuid>,
     },
     // Only for 0 amount carts
     Free,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct AddResaleListingRequest {
+    pub resale_listing_id: Uuid,
+}
+
+pub fn add_resale_listing(
+    (connection, json, user): (Connection, Json<AddResaleListingRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let resale_listing = ResaleListing::find(json.resale_listing_id, connection)?;
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    resale_listing.add_to_cart(&mut cart, user.id(), connection)?;
+    Ok(
+        HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(
+            None,
+            user.id(),
+            connection,
+        )?),
+    )
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AddBundleRequest {
+    pub bundle_id: Uuid,
+    pub quantity: u32,
+}
+
+/// Sets the quantity of a bundle in the cart, a quantity of 0 removes it
+pub fn add_bundle(
+    (connection, json, user): (Connection, Json<AddBundleRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(json.bundle_id, connection)?;
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    bundle.add_to_cart(&mut cart, json.quantity, user.id(), connection)?;
+    Ok(
+        HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(
+            None,
+            user.id(),
+            connection,
+        )?),
+    )
+}
+
 pub fn clear_invalid_items(
     (connection, user): (Connection, User),
 ) -> Result<HttpResponse, BigNeonError> {
@@ -313,6 +388,12 @@ pub fn checkout(
         }
     }
 
+    // Carts take the currency of their items, empty carts fall back to the primary currency
+    let currency = order
+        .currency
+        .clone()
+        .unwrap_or_else(|| state.config.primary_currency.clone());
+
     let payment_response = match &req.method {
         PaymentRequest::Free => {
             info!("CART: Received checkout for free cart");
@@ -346,22 +427,37 @@ pub fn checkout(
                 &request_info,
             )?
         }
-        PaymentRequest::PaymentMethod { provider } => {
+        PaymentRequest::PaymentMethod {
+            provider,
+            payment_method_id,
+        } => {
             info!("CART: Received provider payment");
-            let provider: PaymentProviders = match provider {
-                Some(provider) => provider.clone(),
-                None => match user
-                    .user
-                    .default_payment_method(connection.get())
-                    .optional()?
-                {
-                    Some(payment_method) => payment_method.name,
-                    None => {
+            let payment_method = match (payment_method_id, provider) {
+                (Some(payment_method_id), _) => {
+                    let payment_method = PaymentMethod::find(*payment_method_id, connection.get())?;
+                    if payment_method.user_id != user.id() {
                         return application::unprocessable(
-                            "Could not complete this cart because user has no default payment method",
+                            "Could not complete this cart because the payment method does not belong to the user",
                         );
                     }
-                },
+                    Some(payment_method)
+                }
+                (None, Some(provider)) => user
+                    .user
+                    .payment_method(*provider, connection.get())
+                    .optional()?,
+                (None, None) => user
+                    .user
+                    .default_payment_method(connection.get())
+                    .optional()?,
+            };
+            let payment_method = match payment_method {
+                Some(payment_method) => payment_method,
+                None => {
+                    return application::unprocessable(
+                        "Could not complete this cart because user has no default payment method",
+                    );
+                }
             };
 
             checkout_payment_processor(
@@ -369,9 +465,9 @@ pub fn checkout(
                 &mut order,
                 None,
                 &user,
-                &state.config.primary_currency,
-                provider.clone(),
-                true,
+                &currency,
+                payment_method.name,
+                Some(payment_method),
                 false,
                 false,
                 &state.service_locator,
@@ -384,9 +480,9 @@ pub fn checkout(
             &mut order,
             None,
             &user,
-            &state.config.primary_currency,
+            &currency,
             *provider,
-            false,
+            None,
             false,
             false,
             &state.service_locator,
@@ -403,9 +499,9 @@ pub fn checkout(
             &mut order,
             Some(&token),
             &user,
-            &state.config.primary_currency,
+            &currency,
             *provider,
-            false,
+            None,
             *save_payment_method,
             *set_default,
             &state.service_locator,
@@ -504,7 +600,7 @@ fn checkout_payment_processor(
     auth_user: &User,
     currency: &str,
     provider: PaymentProviders,
-    use_stored_payment: bool,
+    stored_payment_method: Option<PaymentMethod>,
     save_payment_method: bool,
     set_default: bool,
     service_locator: &ServiceLocator,
@@ -525,92 +621,53 @@ fn checkout_payment_processor(
     let client = service_locator.create_payment_processor(provider)?;
     match client.behavior() {
         PaymentProcessorBehavior::RedirectToPaymentPage(behavior) => {
-            return redirect_to_payment_page(&*behavior, &auth_user.user, order, conn.get(), config);
+            return redirect_to_payment_page(
+                &*behavior,
+                &auth_user.user,
+                order,
+                currency,
+                conn.get(),
+                config,
+            );
         }
         PaymentProcessorBehavior::AuthThenComplete(behavior) => {
-            let token = if use_stored_payment {
-                info!("CART: Using stored payment");
-                match auth_user
-                    .user
-                    .payment_method(provider, connection)
-                    .optional()?
-                {
-                    Some(payment_method) => payment_method.provider,
-                    None => {
-                        return application::unprocessable(
-                            "Could not complete this cart because stored provider does not exist",
-                        );
-                    }
+            let (token, source_reference) = match stored_payment_method {
+                Some(payment_method) => {
+                    info!("CART: Using stored payment");
+                    (payment_method.provider, payment_method.source_reference)
                 }
-            } else {
-                info!("CART: Not using stored payment");
-                let token = match token {
-                    Some(t) => t,
-                    None => {
-                        return application::unprocessable(
-                            "Could not complete this cart because no token provided",
-                        );
-                    }
-                };
-
-                if save_payment_method {
-                    info!("CART: User has requested to save the payment method");
-                    match auth_user
-                        .user
-                        .payment_method(provider, connection)
-                        .optional()?
-                    {
-                        Some(payment_method) => {
-                            let behavior = match client.behavior() {
-                        PaymentProcessorBehavior::AuthThenComplete(b) => b,
-                        _ =>  return application::unprocessable(
-                            "Could not complete this cart using saved payment methods is not supported for this payment processor",
-                        )
-                    };
-                            let client_response = behavior.update_repeat_token(
-                                &payment_method.provider,
-                                token,
-                                "Big Neon something",
-                            )?;
-                            let payment_method_parameters = PaymentMethodEditableAttributes {
-                                provider_data: Some(client_response.to_json()?),
-                            };
-                            payment_method.update(
-                                &payment_method_parameters,
-                                auth_user.id(),
-                                connection,
-                            )?;
-
-                            payment_method.provider
-                        }
+                None => {
+                    info!("CART: Not using stored payment");
+                    let token = match token {
+                        Some(t) => t,
                         None => {
-                            let behavior = match client.behavior() {
-                        PaymentProcessorBehavior::AuthThenComplete(b) => b,
-                        _ =>  return application::unprocessable(
-                            "Could not complete this cart using saved payment methods is not supported for this payment processor",
-                        )
-                    };
-                            let repeat_token =
-                                behavior.create_token_for_repeat_charges(token, "Big Neon")?;
-                            let _payment_method = PaymentMethod::create(
-                                auth_user.id(),
-                                provider,
-                                set_default,
-                                repeat_token.token.clone(),
-                                repeat_token.to_json()?,
-                            )
-                            .commit(auth_user.id(), connection)?;
-                            repeat_token.token
+                            return application::unprocessable(
+                                "Could not complete this cart because no token provided",
+                            );
                         }
+                    };
+
+                    if save_payment_method {
+                        info!("CART: User has requested to save the payment method");
+                        let payment_method = payment_methods::save_payment_method(
+                            &*behavior,
+                            &auth_user.user,
+                            provider,
+                            token,
+                            set_default,
+                            connection,
+                        )?;
+                        (payment_method.provider, payment_method.source_reference)
+                    } else {
+                        (token.to_string(), None)
                     }
-                } else {
-                    token.to_string()
                 }
             };
 
             return auth_then_complete(
                 &*behavior,
                 token,
+                source_reference,
                 currency,
                 order,
                 auth_user,
@@ -625,6 +682,7 @@ fn checkout_payment_processor(
 fn auth_then_complete(
     client: &AuthThenCompletePaymentBehavior,
     token: String,
+    source_reference: Option<String>,
     currency: &str,
     order: &mut Order,
     auth_user: &User,
@@ -637,6 +695,7 @@ fn auth_then_complete(
     let amount = order.calculate_total(connection)?;
     let auth_result = client.auth(
         &token,
+        source_reference.as_ref().map(|s| s.as_str()),
         amount,
         currency,
         "Big Neon Tickets",
@@ -688,6 +747,7 @@ fn redirect_to_payment_page(
     client: &RedirectToPaymentPageBehavior,
     user: &DbUser,
     order: &mut Order,
+    currency: &str,
     conn: &PgConnection,
     config: &Config,
 ) -> Result<HttpResponse, BigNeonError> {
@@ -704,6 +764,7 @@ fn redirect_to_payment_page(
     let nonce = random_alpha_string(12);
     let response = client.create_payment_request(
         amount as f64 / 100_f64,
+        currency,
         email,
         order.id,
         ipn,
@@ -726,7 +787,11 @@ fn redirect_to_payment_page(
         conn,
     )?;
 
-    let external_reference = format!("globee-{}", response.id);
+    let external_reference = format!(
+        "{}-{}",
+        client.payment_provider().to_string().to_lowercase(),
+        response.id
+    );
 
     order.add_provider_payment(
         Some(external_reference),
@@ -0,0 +1,147 @@
+use actix_web::{http::StatusCode, Form, HttpResponse, Path, Query, State};
+use auth::user::User;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use bigneon_db::utils::text;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use log::Level::Debug;
+use models::{PathParameters, WebPayload};
+use server::AppState;
+use utils::communication::Communication;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct WebhookParameters {
+    pub token: Option<String>,
+}
+
+/// An entry in the array SendGrid posts to the event webhook
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SendgridEvent {
+    pub event: String,
+    pub sg_message_id: Option<String>,
+    /// Custom argument added to each personalization when the email was sent
+    pub communication_log_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct TwilioStatusCallback {
+    #[serde(rename = "MessageSid")]
+    pub message_sid: String,
+    #[serde(rename = "MessageStatus")]
+    pub message_status: String,
+}
+
+pub fn index(
+    (connection, query_parameters, user): (Connection, Query<PagingParameters>, User),
+) -> Result<WebPayload<CommunicationLog>, BigNeonError> {
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let main_table_id = match query_parameters.get_tag("main_table_id") {
+        Some(id) => Some(id.parse::<Uuid>()?),
+        None => None,
+    };
+    let status = match query_parameters.get_tag("status") {
+        Some(status) => Some(status.parse::<CommunicationLogStatus>()?),
+        None => None,
+    };
+
+    let payload = CommunicationLog::search(
+        query_parameters.get_tag("q"),
+        main_table_id,
+        status,
+        query_parameters.page(),
+        query_parameters.limit(),
+        connection.get(),
+    )?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+/// Queues the logged communication again for the same destination
+pub fn resend(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let connection = connection.get();
+    let log = CommunicationLog::find(path.id, connection)?;
+    Communication::from_log(&log)?.queue(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn sendgrid_webhook(
+    (connection, query, json, state): (
+        Connection,
+        Query<WebhookParameters>,
+        Json<Vec<SendgridEvent>>,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    if !valid_webhook_token(&query, &state) {
+        return application::unauthorized_with_message("Invalid webhook token", None, None);
+    }
+    let connection = connection.get();
+    for event in json.into_inner() {
+        jlog!(Debug, "SendGrid event received", { "event": &event });
+        let status = match event.event.as_str() {
+            "delivered" => CommunicationLogStatus::Delivered,
+            "open" => CommunicationLogStatus::Opened,
+            "bounce" => CommunicationLogStatus::Bounced,
+            "dropped" => CommunicationLogStatus::Dropped,
+            _ => continue,
+        };
+        let log_id = match event
+            .communication_log_id
+            .as_ref()
+            .map(|id| id.parse::<Uuid>())
+        {
+            Some(Ok(id)) => id,
+            _ => continue,
+        };
+        // SendGrid appends a filter id to the message id in event payloads
+        let message_id = event
+            .sg_message_id
+            .as_ref()
+            .and_then(|id| id.split('.').next());
+        // Logs may have been removed since the email was sent, SendGrid retries the whole batch
+        // on an error so unknown ids are skipped rather than failing the other events
+        if let Some(log) = CommunicationLog::find(log_id, connection).optional()? {
+            log.update_status(status, message_id, connection)?;
+        }
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn twilio_webhook(
+    (connection, query, form, state): (
+        Connection,
+        Query<WebhookParameters>,
+        Form<TwilioStatusCallback>,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    if !valid_webhook_token(&query, &state) {
+        return application::unauthorized_with_message("Invalid webhook token", None, None);
+    }
+    let connection = connection.get();
+    jlog!(Debug, "Twilio status callback received", { "callback": &*form });
+    let status = match form.message_status.as_str() {
+        "delivered" => CommunicationLogStatus::Delivered,
+        "undelivered" => CommunicationLogStatus::Bounced,
+        "failed" => CommunicationLogStatus::Failed,
+        _ => return Ok(HttpResponse::Ok().finish()),
+    };
+    if let Some(log) = CommunicationLog::find_by_provider_message_id(&form.message_sid, connection)?
+    {
+        log.update_status(status, None, connection)?;
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+fn valid_webhook_token(query: &WebhookParameters, state: &AppState) -> bool {
+    match (&state.config.communication_webhook_token, &query.token) {
+        (Some(expected), Some(token)) => text::constant_time_eq(expected, token),
+        _ => false,
+    }
+}
@@ -0,0 +1,65 @@
+use actix_web::{HttpResponse, Json, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::{OrganizationDisputePathParameters, PathParameters};
+
+#[derive(Deserialize)]
+pub struct DisputeFilterParameters {
+    pub status: Option<DisputeStatus>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct UpdateDisputeRequest {
+    pub status: Option<DisputeStatus>,
+    pub evidence_notes: Option<String>,
+}
+
+pub fn index(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<DisputeFilterParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+
+    Ok(HttpResponse::Ok().json(&Dispute::find_for_organization(
+        organization.id,
+        query.status,
+        connection,
+    )?))
+}
+
+/// Records evidence submitted to the payment provider and status changes made outside of the
+/// provider's webhooks
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<OrganizationDisputePathParameters>,
+        Json<UpdateDisputeRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+    let mut dispute = Dispute::find(path.dispute_id, connection)?;
+    if !dispute.belongs_to_organization(organization.id, connection)? {
+        return application::not_found();
+    }
+
+    let json = json.into_inner();
+    if let Some(evidence_notes) = json.evidence_notes {
+        dispute = dispute.submit_evidence(evidence_notes, connection)?;
+    }
+    if let Some(status) = json.status {
+        dispute = dispute.update_status(status, connection)?;
+    }
+    Ok(HttpResponse::Ok().json(&dispute))
+}
@@ -0,0 +1,70 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateEventCommunicationScheduleRequest {
+    pub timing: EventCommunicationTimings,
+    pub offset_hours: i32,
+    pub channel: CommunicationChannelType,
+    pub title: String,
+    pub message: String,
+    pub template_id: Option<String>,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgRead, &organization, connection)?;
+
+    Ok(
+        HttpResponse::Ok().json(&EventCommunicationSchedule::find_for_organization(
+            organization.id,
+            connection,
+        )?),
+    )
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateEventCommunicationScheduleRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    let json = json.into_inner();
+    let schedule = EventCommunicationSchedule::create(
+        organization.id,
+        json.timing,
+        json.offset_hours,
+        json.channel,
+        json.title,
+        json.message,
+        json.template_id,
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&schedule))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let schedule = EventCommunicationSchedule::find(path.id, connection)?;
+    let organization = Organization::find(schedule.organization_id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    schedule.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
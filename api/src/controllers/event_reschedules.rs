@@ -0,0 +1,217 @@
+use actix_web::{HttpResponse, Path, State};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use communications::{mailers, smsers};
+use controllers::orders;
+use db::Connection;
+use diesel::Connection as DieselConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use log::Level::Error;
+use models::PathParameters;
+use server::AppState;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct RescheduleEventRequest {
+    pub event_start: NaiveDateTime,
+    pub door_time: Option<NaiveDateTime>,
+    pub event_end: Option<NaiveDateTime>,
+    pub refund_deadline: NaiveDateTime,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RescheduleEventResponse {
+    pub event: Event,
+    pub reschedule: EventReschedule,
+    pub ticket_holders_notified: u32,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RescheduleRefundResponse {
+    pub amount_refunded: u32,
+    pub refunds: Vec<EventRescheduleRefund>,
+    /// Orders that could not be refunded, their tickets are kept and can be refunded again
+    pub failed_order_ids: Vec<Uuid>,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventViewGuests,
+        &organization,
+        &event,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(event.reschedules(connection)?))
+}
+
+pub fn create(
+    (connection, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<RescheduleEventRequest>,
+        User,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
+
+    let (event, reschedule) = event.reschedule(
+        json.event_start,
+        json.door_time,
+        json.event_end,
+        json.refund_deadline,
+        user.id(),
+        connection,
+    )?;
+
+    // Let everyone holding tickets know about the new dates and how to opt out
+    let venue = event.venue(connection)?;
+    let mut ticket_holders_notified = 0;
+    for ticket_holder in reschedule.report(connection)? {
+        if ticket_holder.tickets_kept == 0 {
+            continue;
+        }
+        let mut notified = false;
+        if let Some(email) = ticket_holder.email {
+            mailers::events::rescheduled(
+                ticket_holder
+                    .first_name
+                    .as_ref()
+                    .map(|f| f.as_str())
+                    .unwrap_or("there"),
+                email,
+                &event,
+                &venue,
+                &reschedule,
+                &state.config,
+                connection,
+            )?;
+            notified = true;
+        }
+        if let Some(phone) = ticket_holder.phone {
+            smsers::events::rescheduled(&state.config, phone, &event, &reschedule, connection)?;
+            notified = true;
+        }
+        if notified {
+            ticket_holders_notified += 1;
+        }
+    }
+
+    Ok(HttpResponse::Created().json(RescheduleEventResponse {
+        event,
+        reschedule,
+        ticket_holders_notified,
+    }))
+}
+
+pub fn report(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let reschedule = EventReschedule::find(path.id, connection)?;
+    let event = reschedule.event(connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventViewGuests,
+        &organization,
+        &event,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(reschedule.report(connection)?))
+}
+
+/// Self-service opt out for ticket holders, refunds all of the tickets they bought for the event
+pub fn refund(
+    (conn, path, user, state): (Connection, Path<PathParameters>, User, State<AppState>),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::OrderReadOwn)?;
+    let connection = conn.get();
+    let reschedule = EventReschedule::find(path.id, connection)?;
+    if !reschedule.is_refundable() {
+        return application::unprocessable("The refund period for this event has ended");
+    }
+
+    let tickets_per_order = reschedule.refundable_tickets_for_user(user.id(), connection)?;
+    if tickets_per_order.is_empty() {
+        return application::unprocessable("You have no tickets for this event to refund");
+    }
+
+    // Each order is refunded in its own savepoint and committed once its payments have been
+    // refunded, so a failure leaves the other orders' refunds in place and is reported
+    let mut amount_refunded = 0;
+    let mut refunds = vec![];
+    let mut failed_order_ids = vec![];
+    let mut last_error = None;
+    for (order_id, ticket_instance_ids) in tickets_per_order {
+        let result = connection.transaction::<_, BigNeonError, _>(|| {
+            let order = Order::find(order_id, connection)?;
+            let plan = RefundPlan::for_tickets(&order, &ticket_instance_ids, &[], connection)?;
+            let refund = NewEventRescheduleRefund {
+                event_reschedule_id: reschedule.id,
+                user_id: user.id(),
+                order_id: order.id,
+                ticket_instance_ids: ticket_instance_ids.clone(),
+                refunded_amount_in_cents: plan.amount_in_cents,
+            }
+            .commit(connection)?;
+            orders::refund_order_items(
+                connection,
+                &order,
+                plan.items,
+                Some(plan.amount_in_cents),
+                &user,
+                &state,
+            )?;
+            Ok(refund)
+        });
+        let refund = match result {
+            Ok(refund) => refund,
+            Err(e) => {
+                jlog!(Error, "Could not refund rescheduled event order", {"order_id": order_id, "event_reschedule_id": reschedule.id, "error": e.to_string()});
+                failed_order_ids.push(order_id);
+                last_error = Some(e);
+                continue;
+            }
+        };
+        orders::commit_refund(&conn, &state)?;
+
+        let refunded_amount = orders::checked_amount(refund.refunded_amount_in_cents)?;
+        if let Err(e) =
+            orders::send_refund_email(order_id, refunded_amount, &user, &state, connection)
+        {
+            jlog!(Error, "Could not send refund email", {"order_id": order_id, "error": e.to_string()});
+        }
+        amount_refunded += refunded_amount;
+        refunds.push(refund);
+    }
+
+    if refunds.is_empty() {
+        if let Some(e) = last_error {
+            return Err(e);
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(RescheduleRefundResponse {
+        amount_refunded,
+        refunds,
+        failed_order_ids,
+    }))
+}
@@ -1,13 +1,16 @@
 use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
 use auth::user::User;
 use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::Optional;
 use chrono::prelude::*;
 use chrono::Duration;
 use controllers::organizations::DisplayOrganizationUser;
 use db::Connection;
+use diesel::PgConnection;
 use errors::*;
 use extractors::*;
 use helpers::application;
+use helpers::export::{self, ExportFormat};
 use models::{PathParameters, RedeemTicketPathParameters, UserDisplayTicketType, WebPayload};
 use serde_json::Value;
 use serde_with::{self, CommaSeparator};
@@ -441,6 +444,7 @@ pub fn show(
         localized_times: EventLocalizedTimeStrings,
         tracking_keys: TrackingKeys,
         event_type: EventTypes,
+        currency: String,
     }
 
     let payload = &R {
@@ -481,6 +485,7 @@ pub fn show(
         localized_times,
         tracking_keys,
         event_type: event.event_type,
+        currency: event.currency,
     };
 
     Ok(HttpResponse::Ok().json(&payload))
@@ -536,6 +541,19 @@ pub fn redeem_ticket(
     ),
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
+    // Add-on vouchers are scanned at the door along with the tickets
+    if let Some(voucher) =
+        AddOnVoucher::find(parameters.ticket_instance_id, connection).optional()?
+    {
+        return redeem_add_on_voucher(
+            voucher,
+            parameters.id,
+            &redeem_parameters.redeem_key,
+            &auth_user,
+            connection,
+        );
+    }
+
     let ticket = TicketInstance::find_for_processing(
         parameters.ticket_instance_id,
         parameters.id,
@@ -585,6 +603,102 @@ pub fn redeem_ticket(
     }
 }
 
+fn redeem_add_on_voucher(
+    voucher: AddOnVoucher,
+    event_id: Uuid,
+    redeem_key: &str,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<HttpResponse, BigNeonError> {
+    let event = voucher.event(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::RedeemTicket,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+    if event.id != event_id {
+        return Ok(HttpResponse::BadRequest()
+            .json(json!({"error": "Voucher is not valid for this event.".to_string()})));
+    }
+
+    match voucher.redeem(redeem_key, user.id(), connection)? {
+        AddOnVoucherRedeemResults::VoucherRedeemSuccess => {
+            Ok(HttpResponse::Ok().json(&AddOnVoucher::find(voucher.id, connection)?))
+        }
+        AddOnVoucherRedeemResults::VoucherAlreadyRedeemed => Ok(HttpResponse::Conflict()
+            .json(json!({"error": "Voucher has already been redeemed.".to_string()}))),
+        AddOnVoucherRedeemResults::VoucherInvalid => Ok(
+            HttpResponse::BadRequest().json(json!({"error": "Voucher is invalid.".to_string()}))
+        ),
+    }
+}
+
+pub fn scanner_manifest(
+    (connection, path, user, state): (Connection, Path<PathParameters>, User, State<AppState>),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = connection.get();
+    let event = Event::find(path.id, conn)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventScan,
+        &event.organization(conn)?,
+        &event,
+        conn,
+    )?;
+
+    Ok(HttpResponse::Ok().json(ScannerManifest::for_event(
+        &event,
+        &state.config.scanner_manifest_secret_key,
+        conn,
+    )?))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ScannerSyncRequest {
+    pub device_id: String,
+    pub redemptions: Vec<OfflineScan>,
+}
+
+pub fn scanner_sync(
+    (connection, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<ScannerSyncRequest>,
+        User,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = connection.get();
+    let event = Event::find(path.id, conn)?;
+    user.requires_scope_for_organization_event(
+        Scopes::RedeemTicket,
+        &event.organization(conn)?,
+        &event,
+        conn,
+    )?;
+
+    let json = json.into_inner();
+    let result =
+        OfflineRedemption::reconcile(event.id, &json.device_id, json.redemptions, user.id(), conn)?;
+
+    //Redeem tickets on chain
+    for ticket_instance_id in &result.redeemed {
+        let ticket = TicketInstance::find(*ticket_instance_id, conn)?;
+        let asset = Asset::find(ticket.asset_id, conn)?;
+        if let Some(blockchain_asset_id) = asset.blockchain_asset_id {
+            let wallet = Wallet::find(ticket.wallet_id, conn)?;
+            state.config.tari_client.modify_asset_redeem_token(
+                &wallet.secret_key,
+                &wallet.public_key,
+                &blockchain_asset_id,
+                vec![ticket.token_id as u64],
+            )?;
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
 pub fn show_from_organizations(
     (connection, path, paging, user): (
         Connection,
@@ -732,11 +846,29 @@ pub fn cancel(
     )?;
 
     //Doing this in the DB layer so it can use the DB time as now.
-    let updated_event = event.cancel(connection)?;
+    let updated_event = event.cancel(user.id(), connection)?;
 
     Ok(HttpResponse::Ok().json(&updated_event))
 }
 
+/// Progress of refunding the paid orders of a cancelled event
+pub fn cancellation(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventCancel,
+        &organization,
+        &event,
+        connection,
+    )?;
+
+    let cancellation = event.cancellation(connection)?;
+    Ok(HttpResponse::Ok().json(&cancellation.progress(connection)?))
+}
+
 pub fn list_interested_users(
     (connection, path_parameters, query, user): (
         Connection,
@@ -854,6 +986,7 @@ pub fn update_artists(
 #[derive(Deserialize)]
 pub struct GuestListQueryParameters {
     pub query: String,
+    pub format: Option<ExportFormat>,
 }
 
 impl From<GuestListQueryParameters> for Paging {
@@ -890,6 +1023,11 @@ pub fn guest_list(
         conn,
     )?;
     let tickets = event.guest_list(&query.query, conn)?;
+    if let Some(format) = query.format {
+        if format != ExportFormat::Json {
+            return export::worksheets(format, "guests", &tickets.to_worksheets(conn)?);
+        }
+    }
 
     #[derive(Serialize)]
     struct R {
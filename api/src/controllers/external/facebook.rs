@@ -1,5 +1,5 @@
 use actix_web::{HttpResponse, State};
-use auth::TokenResponse;
+use auth::{two_factor, TokenResponse};
 use bigneon_db::models::{ExternalLogin, User, FACEBOOK_SITE};
 use db::Connection;
 use errors::*;
@@ -21,14 +21,14 @@ struct FacebookGraphResponse {
 
 // TODO: Not covered by tests
 pub fn web_login(
-    (state, connection, auth_token): (State<AppState>, Connection, Json<FacebookWebLoginToken>),
+    (state, conn, auth_token): (State<AppState>, Connection, Json<FacebookWebLoginToken>),
 ) -> Result<HttpResponse, BigNeonError> {
     info!("Finding user");
     let url = format!(
         "{}/me?fields=id,email,first_name,last_name",
         FACEBOOK_GRAPH_URL
     );
-    let connection = connection.get();
+    let connection = conn.get();
     let client = reqwest::Client::new();
     let response = client
         .get(&url)
@@ -84,6 +84,7 @@ pub fn web_login(
             }
         }
     };
+    two_factor::verify_login(&user, &auth_token.two_factor_code, &state.config, &conn)?;
     info!("Saving access token");
     let response = TokenResponse::create_from_user(
         &state.config.token_secret,
@@ -1,4 +1,4 @@
-use actix_web::HttpResponse;
+use actix_web::{HttpRequest, HttpResponse};
 use bigneon_db::prelude::*;
 use chrono::prelude::*;
 use chrono::Duration;
@@ -6,7 +6,10 @@ use db::Connection;
 use errors::BigNeonError;
 use extractors::Json;
 use globee::GlobeeIpnRequest;
-use log::Level::Debug;
+use helpers::application;
+use log::Level::{Debug, Warn};
+use server::AppState;
+use stripe::WebhookEvent;
 use uuid::Uuid;
 
 pub fn globee(
@@ -40,3 +43,56 @@ pub fn globee(
 
     Ok(HttpResponse::Ok().finish())
 }
+
+pub fn stripe(
+    (request, body, conn): (HttpRequest<AppState>, String, Connection),
+) -> Result<HttpResponse, BigNeonError> {
+    let secret = match request.state().config.stripe_webhook_secret {
+        Some(ref secret) => secret.clone(),
+        None => {
+            return application::unauthorized_with_message(
+                "Stripe webhooks are not enabled",
+                None,
+                None,
+            );
+        }
+    };
+    let signature = request
+        .headers()
+        .get("Stripe-Signature")
+        .and_then(|header| header.to_str().ok())
+        .unwrap_or("");
+    let event = match WebhookEvent::construct(&body, signature, &secret) {
+        Ok(event) => event,
+        Err(e) => {
+            jlog!(Warn, "Stripe webhook rejected", { "error": e.to_string() });
+            return application::unauthorized_with_message("Invalid Stripe signature", None, None);
+        }
+    };
+    jlog!(Debug, "Stripe webhook received", { "id": &event.id, "type": &event.event_type });
+
+    let connection = conn.get();
+    let payment = match event.charge_id() {
+        Some(charge_id) => {
+            Payment::find_by_external_reference(PaymentProviders::Stripe, &charge_id, connection)?
+        }
+        None => None,
+    };
+    // As with Globee, record the event and process it as an action so that it can be retried
+    DomainAction::create(
+        None,
+        DomainActionTypes::ProcessStripeWebhook,
+        None,
+        json!(event),
+        Some(Tables::Payments.to_string()),
+        payment.map(|p| p.id),
+        Utc::now().naive_utc(),
+        (Utc::now().naive_utc())
+            .checked_add_signed(Duration::days(30))
+            .unwrap(),
+        5,
+    )
+    .commit(connection)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
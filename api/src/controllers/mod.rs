@@ -1,13 +1,20 @@
+pub mod add_ons;
 pub mod artists;
 pub mod auth;
+pub mod bundles;
 pub mod cart;
 pub mod codes;
+pub mod communications;
 pub mod comps;
+pub mod disputes;
+pub mod event_communication_schedules;
+pub mod event_reschedules;
 pub mod events;
 pub mod external;
 pub mod holds;
 pub mod ipns;
 pub mod orders;
+pub mod organization_email_templates;
 pub mod organization_invites;
 pub mod organizations;
 pub mod password_resets;
@@ -15,12 +22,19 @@ pub mod payment_methods;
 pub mod payments;
 pub mod redemption_codes;
 pub mod regions;
+pub mod report_subscriptions;
 pub mod reports;
+pub mod resale_listings;
+pub mod resale_payouts;
+pub mod seat_maps;
 pub mod settlements;
 pub mod stages;
 pub mod status;
+pub mod tax_rates;
 pub mod ticket_types;
 pub mod tickets;
+pub mod two_factor_authentication;
 pub mod user_invites;
 pub mod users;
 pub mod venues;
+pub mod webhooks;
@@ -2,18 +2,21 @@ use actix_web::{HttpResponse, Path, Query, State};
 use auth::user::User;
 use bigneon_db::models::User as DbUser;
 use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
 use communications::mailers;
 use config::Environment;
 use db::Connection;
+use diesel::pg::PgConnection;
 use diesel::Connection as DieselConnection;
 use errors::BigNeonError;
 use extractors::*;
 use helpers::application;
-use log::Level::Debug;
+use log::Level::{Debug, Info};
 use models::PathParameters;
+use payments::PaymentProcessorBehavior;
 use server::AppState;
-use std::cmp;
 use std::collections::HashMap;
+use utils::ServiceLocator;
 use uuid::Uuid;
 
 pub fn index(
@@ -127,6 +130,337 @@ pub fn refund(
         );
     }
 
+    if !authorized_for_items(&user, Scopes::OrderRefund, &items, connection)? {
+        let mut details_data = HashMap::new();
+        details_data.insert("order_id", json!(path.id));
+        details_data.insert("items", json!(items));
+        return application::unauthorized(Some(user), Some(details_data));
+    }
+
+    let payment_refunds = perform_refund(&conn, &order, items, None, &user, &state)?;
+
+    let mut refund_breakdown: HashMap<PaymentMethods, u32> = HashMap::new();
+    let mut amount_refunded = 0;
+    for payment_refund in &payment_refunds {
+        let amount_in_cents = checked_amount(payment_refund.amount_in_cents)?;
+        *refund_breakdown
+            .entry(payment_refund.payment_method)
+            .or_insert(0) += amount_in_cents;
+        amount_refunded += amount_in_cents;
+    }
+
+    send_refund_email(order.id, amount_refunded, &user, &state, connection)?;
+
+    Ok(HttpResponse::Ok().json(json!(RefundResponse {
+        amount_refunded,
+        refund_breakdown
+    })))
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct RefundTicketsAttributes {
+    pub ticket_instance_ids: Vec<Uuid>,
+    /// Tickets to refund the per ticket fees for while keeping the ticket
+    #[serde(default)]
+    pub fee_ticket_instance_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RefundTicketsResponse {
+    pub amount_refunded: u32,
+    pub items: Vec<RefundItem>,
+    pub payments: Vec<PaymentRefundPlan>,
+}
+
+pub fn refund_tickets(
+    (conn, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<RefundTicketsAttributes>,
+        User,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let refund_attributes = json.into_inner();
+    jlog!(Debug, "Request to refund tickets received", {"order_id": path.id, "request": refund_attributes.clone()});
+    let connection = conn.get();
+    let order = Order::find(path.id, connection)?;
+
+    let plan = RefundPlan::for_tickets(
+        &order,
+        &refund_attributes.ticket_instance_ids,
+        &refund_attributes.fee_ticket_instance_ids,
+        connection,
+    )?;
+
+    if !authorized_for_items(&user, Scopes::OrderRefund, &plan.items, connection)? {
+        let mut details_data = HashMap::new();
+        details_data.insert("order_id", json!(path.id));
+        details_data.insert("items", json!(plan.items));
+        return application::unauthorized(Some(user), Some(details_data));
+    }
+
+    let payment_refunds = perform_refund(
+        &conn,
+        &order,
+        plan.items.clone(),
+        Some(plan.amount_in_cents),
+        &user,
+        &state,
+    )?;
+    let amount_refunded = checked_amount(plan.amount_in_cents)?;
+
+    send_refund_email(order.id, amount_refunded, &user, &state, connection)?;
+
+    Ok(HttpResponse::Ok().json(RefundTicketsResponse {
+        amount_refunded,
+        items: plan.items,
+        payments: payment_refunds,
+    }))
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ExchangeTicketsAttributes {
+    pub ticket_instance_ids: Vec<Uuid>,
+    pub ticket_type_id: Uuid,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExchangeTicketsResponse {
+    pub exchange: OrderExchange,
+    pub amount_charged: u32,
+    pub amount_refunded: u32,
+    pub payments: Vec<PaymentRefundPlan>,
+}
+
+pub fn exchange(
+    (conn, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<ExchangeTicketsAttributes>,
+        User,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let exchange_attributes = json.into_inner();
+    jlog!(Debug, "Request to exchange tickets received", {"order_id": path.id, "request": exchange_attributes.clone()});
+    let connection = conn.get();
+    let mut order = Order::find(path.id, connection)?;
+
+    if order.status != OrderStatus::Paid {
+        return application::unprocessable("Only tickets on paid orders can be exchanged");
+    }
+
+    let plan = RefundPlan::for_tickets(
+        &order,
+        &exchange_attributes.ticket_instance_ids,
+        &[],
+        connection,
+    )?;
+    let ticket_type = TicketType::find(exchange_attributes.ticket_type_id, connection)?;
+    let event = Event::find(ticket_type.event_id, connection)?;
+    let organization = Organization::find(event.organization_id, connection)?;
+    if !authorized_for_items(&user, Scopes::OrderExchange, &plan.items, connection)?
+        || !user.has_scope_for_organization_event(
+            Scopes::OrderExchange,
+            &organization,
+            &event,
+            connection,
+        )?
+    {
+        let mut details_data = HashMap::new();
+        details_data.insert("order_id", json!(path.id));
+        details_data.insert("items", json!(plan.items));
+        details_data.insert("ticket_type_id", json!(ticket_type.id));
+        return application::unauthorized(Some(user), Some(details_data));
+    }
+
+    let exchange = order.exchange_tickets(
+        &exchange_attributes.ticket_instance_ids,
+        ticket_type.id,
+        user.id(),
+        connection,
+    )?;
+
+    // The difference is charged to or refunded to the buyer
+    let balance = exchange.balance_in_cents();
+    let payment_refunds = return_tickets_to_organizations(
+        exchange.exchanged_ticket_instance_ids.clone(),
+        &state,
+        connection,
+        || {
+            if balance > 0 {
+                charge_exchange_balance(&mut order, balance, &user, &state, connection)?;
+                Ok(vec![])
+            } else {
+                let payment_refunds = RefundPlan::allocate(&order, -balance, connection)?;
+                refund_payments(
+                    &payment_refunds,
+                    user.id(),
+                    None,
+                    &state.service_locator,
+                    connection,
+                )?;
+                Ok(payment_refunds)
+            }
+        },
+    )?;
+
+    // Commit changes as payment completed
+    if state.config.environment != Environment::Test {
+        conn.commit_transaction()?;
+        conn.begin_transaction()?;
+    }
+
+    Ok(HttpResponse::Ok().json(ExchangeTicketsResponse {
+        amount_charged: checked_amount(balance.max(0))?,
+        amount_refunded: checked_amount((-balance).max(0))?,
+        exchange,
+        payments: payment_refunds,
+    }))
+}
+
+pub fn exchanges(
+    (conn, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    let order = Order::find(path.id, connection)?;
+
+    if order.user_id == user.id() || order.on_behalf_of_user_id == Some(user.id()) {
+        user.requires_scope(Scopes::OrderReadOwn)?;
+    } else {
+        let mut authorized = false;
+        for organization in order.organizations(connection)? {
+            if user.has_scope_for_organization(Scopes::OrderRead, &organization, connection)? {
+                authorized = true;
+                break;
+            }
+        }
+        if !authorized {
+            let mut details_data = HashMap::new();
+            details_data.insert("order_id", json!(path.id));
+            return application::unauthorized(Some(user), Some(details_data));
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(order.exchanges(connection)?))
+}
+
+/// Collects the amount owed for an exchange. Orders paid externally are settled with an external
+/// payment, card payments are charged to the buyer's saved card with the same provider.
+fn charge_exchange_balance(
+    order: &mut Order,
+    amount_in_cents: i64,
+    user: &User,
+    state: &State<AppState>,
+    connection: &PgConnection,
+) -> Result<Payment, BigNeonError> {
+    let payments = order.payments(connection)?;
+    let card_payment = payments
+        .iter()
+        .filter(|p| {
+            p.status == PaymentStatus::Completed && p.payment_method == PaymentMethods::CreditCard
+        })
+        .last();
+    let provider = match card_payment {
+        Some(payment) => payment.provider,
+        None => {
+            if payments
+                .iter()
+                .any(|p| p.payment_method == PaymentMethods::External)
+            {
+                return Ok(order.add_external_payment(
+                    Some("Ticket exchange".to_string()),
+                    user.id(),
+                    amount_in_cents,
+                    connection,
+                )?);
+            }
+            return Err(application::unprocessable::<HttpResponse>(
+                "Unable to charge the exchange difference for this order's payment method",
+            )
+            .unwrap_err());
+        }
+    };
+
+    let buyer = DbUser::find(
+        order.on_behalf_of_user_id.unwrap_or(order.user_id),
+        connection,
+    )?;
+    let payment_method = match buyer.payment_method(provider, connection).optional()? {
+        Some(payment_method) => payment_method,
+        None => {
+            return Err(application::unprocessable::<HttpResponse>(
+                "Unable to charge the exchange difference, the buyer has no saved payment method",
+            )
+            .unwrap_err());
+        }
+    };
+    let client = state.service_locator.create_payment_processor(provider)?;
+    let behavior = match client.behavior() {
+        PaymentProcessorBehavior::AuthThenComplete(behavior) => behavior,
+        PaymentProcessorBehavior::RedirectToPaymentPage(_) => {
+            return Err(application::unprocessable::<HttpResponse>(
+                "Unable to charge the exchange difference for this payment provider",
+            )
+            .unwrap_err());
+        }
+    };
+
+    let currency = order
+        .currency
+        .clone()
+        .unwrap_or_else(|| state.config.primary_currency.clone());
+    let auth_result = behavior.auth(
+        &payment_method.provider,
+        payment_method.source_reference.as_ref().map(|s| s.as_str()),
+        amount_in_cents,
+        &currency,
+        "Big Neon Tickets",
+        order.purchase_metadata(connection)?,
+    )?;
+    let payment = match order.add_credit_card_payment(
+        user.id(),
+        amount_in_cents,
+        provider,
+        auth_result.id.clone(),
+        PaymentStatus::Authorized,
+        auth_result.to_json()?,
+        connection,
+    ) {
+        Ok(p) => p,
+        Err(e) => {
+            client.refund(&auth_result.id)?;
+            return Err(e.into());
+        }
+    };
+
+    let charge_result = behavior.complete_authed_charge(&auth_result.id)?;
+    if let Err(e) = payment.mark_complete(charge_result.to_json()?, Some(user.id()), connection) {
+        client.refund(&auth_result.id)?;
+        return Err(e.into());
+    }
+    Ok(payment)
+}
+
+/// Amounts are stored as i64 cents while payment processors and receipts take u32
+pub fn checked_amount(amount_in_cents: i64) -> Result<u32, BigNeonError> {
+    if amount_in_cents < 0 || amount_in_cents > i64::from(u32::max_value()) {
+        return Err(application::internal_server_error::<HttpResponse>(&format!(
+            "Amount of {} cents is out of range",
+            amount_in_cents
+        ))
+        .unwrap_err());
+    }
+    Ok(amount_in_cents as u32)
+}
+
+fn authorized_for_items(
+    user: &User,
+    scope: Scopes,
+    items: &[RefundItem],
+    connection: &PgConnection,
+) -> Result<bool, BigNeonError> {
     // Find list of organizations related to order item id events for confirming user access
     let order_item_ids: Vec<Uuid> = items
         .iter()
@@ -137,41 +471,123 @@ pub fn refund(
         organization_map.insert(organization.id, organization);
     }
 
-    // Check for any organizations where user lacks order refund access
-    let mut authorized_to_refund_items = !organization_map.is_empty();
+    // Check for any organizations where user lacks the scope
+    if organization_map.is_empty() {
+        return Ok(false);
+    }
     for event in Event::find_by_order_item_ids(&order_item_ids, connection)? {
-        if let Some(organization) = organization_map.get(&event.organization_id) {
-            if !user.has_scope_for_organization_event(
-                Scopes::OrderRefund,
-                &organization,
-                &event,
-                connection,
-            )? {
-                authorized_to_refund_items = false;
-                break;
+        match organization_map.get(&event.organization_id) {
+            Some(organization) => {
+                if !user.has_scope_for_organization_event(
+                    scope,
+                    &organization,
+                    &event,
+                    connection,
+                )? {
+                    return Ok(false);
+                }
             }
-        } else {
-            authorized_to_refund_items = false;
-            break;
+            None => return Ok(false),
         }
     }
+    Ok(true)
+}
 
-    if !authorized_to_refund_items {
-        let mut details_data = HashMap::new();
-        details_data.insert("order_id", json!(path.id));
-        details_data.insert("items", json!(items));
-        return application::unauthorized(Some(user), Some(details_data));
-    }
+/// Refunds the order items and commits the refund once the payments have been refunded, see
+/// `refund_order_items`
+pub fn perform_refund(
+    conn: &Connection,
+    order: &Order,
+    items: Vec<RefundItem>,
+    expected_amount_in_cents: Option<i64>,
+    user: &User,
+    state: &State<AppState>,
+) -> Result<Vec<PaymentRefundPlan>, BigNeonError> {
+    let payment_refunds = refund_order_items(
+        conn.get(),
+        order,
+        items,
+        expected_amount_in_cents,
+        user,
+        state,
+    )?;
+    commit_refund(conn, state)?;
+    Ok(payment_refunds)
+}
 
+/// Refunds the order items, returns refunded tickets to the organization wallets and splits the
+/// amount due between the order's payments. When an expected amount is given the refund is
+/// aborted if the amount due differs. Nothing is committed, callers refunding several orders
+/// run each in a savepoint and commit with `commit_refund` once it has succeeded.
+pub fn refund_order_items(
+    connection: &PgConnection,
+    order: &Order,
+    items: Vec<RefundItem>,
+    expected_amount_in_cents: Option<i64>,
+    user: &User,
+    state: &State<AppState>,
+) -> Result<Vec<PaymentRefundPlan>, BigNeonError> {
+    // Refunding a bundle refunds its tickets, which are returned to the organizations as well
+    let items = order.expand_bundle_refund_items(items, connection)?;
     let ticket_instance_ids = items
         .iter()
         .filter(|i| i.ticket_instance_id.is_some())
         .map(|i| i.ticket_instance_id.unwrap())
         .collect::<Vec<Uuid>>();
 
-    // Refund amount is fee inclusive if fee no longer applies to the order
-    let refund_due = order.refund(items, user.id(), connection)?;
+    // Refund amount is fee inclusive if fee no longer applies to the order. The amount is checked
+    // against the plan in a savepoint so a mismatch leaves nothing written.
+    let refund_due = connection.transaction::<_, BigNeonError, _>(|| {
+        let refund_due = order.refund(items, user.id(), connection)?;
+        if let Some(expected_amount_in_cents) = expected_amount_in_cents {
+            if i64::from(refund_due) != expected_amount_in_cents {
+                return Err(application::internal_server_error::<HttpResponse>(&format!(
+                    "Unable to refund, {} due does not match planned refund of {}",
+                    refund_due, expected_amount_in_cents
+                ))
+                .unwrap_err());
+            }
+        }
+        Ok(refund_due)
+    })?;
+
+    let payment_refunds =
+        return_tickets_to_organizations(ticket_instance_ids, state, connection, || {
+            let payment_refunds = RefundPlan::allocate(order, i64::from(refund_due), connection)?;
+            refund_payments(
+                &payment_refunds,
+                user.id(),
+                None,
+                &state.service_locator,
+                connection,
+            )?;
+            Ok(payment_refunds)
+        })?;
 
+    Ok(payment_refunds)
+}
+
+/// Commits a refund so it is kept once the payments have been refunded, even if the request
+/// fails afterwards
+pub fn commit_refund(conn: &Connection, state: &State<AppState>) -> Result<(), BigNeonError> {
+    if state.config.environment != Environment::Test {
+        conn.commit_transaction()?;
+        conn.begin_transaction()?;
+    }
+    Ok(())
+}
+
+/// Transfers the refunded tickets back to the organization wallets before running `action`. If
+/// the action fails the transferred tickets are returned to the user wallets.
+fn return_tickets_to_organizations<T, F>(
+    ticket_instance_ids: Vec<Uuid>,
+    state: &State<AppState>,
+    connection: &PgConnection,
+    action: F,
+) -> Result<T, BigNeonError>
+where
+    F: FnOnce() -> Result<T, BigNeonError>,
+{
     // Transfer tickets back to the organization wallets
     let mut tokens_per_asset: HashMap<Uuid, Vec<u64>> = HashMap::new();
     let mut wallet_id_per_asset: HashMap<Uuid, Uuid> = HashMap::new();
@@ -196,10 +612,6 @@ pub fn refund(
     }
     let mut modified_tokens: HashMap<Uuid, Vec<u64>> = HashMap::new();
 
-    let mut refund_breakdown: HashMap<PaymentMethods, u32> = HashMap::new();
-    let mut payment_remaining_balance_map: HashMap<Option<String>, i64> = HashMap::new();
-    let mut amount_refunded = 0;
-
     // Begin transaction, if it fails at this point all transferred tickets are returned to wallets
     match connection.transaction::<_, BigNeonError, _>(|| {
         for (asset_id, token_ids) in &tokens_per_asset {
@@ -239,67 +651,7 @@ pub fn refund(
             }
         }
 
-        // Perform refunds
-
-        // Negative payments / refunds cancel out remaining payment balance
-        for payment in order.payments(connection)? {
-            // Ignore payments that were only authorized
-            if payment.status == PaymentStatus::Authorized {
-                continue;
-            }
-
-            *payment_remaining_balance_map
-                .entry(payment.external_reference)
-                .or_insert(0) += payment.amount;
-        }
-
-        for payment in order.payments(connection)? {
-            if payment.status != PaymentStatus::Completed {
-                continue;
-            }
-
-            let remaining_balance = payment_remaining_balance_map
-                .get(&payment.external_reference)
-                .map(|n| *n)
-                .unwrap_or(0);
-            if remaining_balance == 0 {
-                continue;
-            }
-
-            let amount_to_refund = cmp::min(refund_due - amount_refunded, remaining_balance as u32);
-            let mut refund_data = None;
-            if payment.payment_method == PaymentMethods::CreditCard {
-                let client = &state
-                    .service_locator
-                    .create_payment_processor(payment.provider)?;
-
-                refund_data = match payment.external_reference {
-                    Some(ref external_reference) => Some(
-                        client
-                            .partial_refund(external_reference, amount_to_refund)?
-                            .to_json()?,
-                    ),
-                    None => {
-                        return Err(application::internal_server_error::<HttpResponse>(&format!(
-                            "Unable to refund amount owed payment {} lacks external reference",
-                            payment.id
-                        )).unwrap_err())
-                    }
-                };
-            }
-            payment.log_refund(user.id(), amount_to_refund, refund_data, connection)?;
-            *refund_breakdown.entry(payment.payment_method).or_insert(0) += amount_to_refund;
-            amount_refunded += amount_to_refund;
-        }
-
-        if amount_refunded < refund_due {
-            return Err(application::internal_server_error::<HttpResponse>(&format!(
-                "Unable to refund amount owed {} refunded, {} due",
-                amount_refunded, refund_due
-            )).unwrap_err());
-        }
-
-        Ok(())
+        action()
     }) {
         Err(error) => {
             for (asset_id, token_ids) in &modified_tokens {
@@ -311,9 +663,9 @@ pub fn refund(
                     Some(a) => {
                         let wallet_id = match wallet_id_per_asset.get(asset_id) {
                             Some(w) => w.clone(),
-                            None => return application::internal_server_error(
+                            None => return Err(application::internal_server_error::<HttpResponse>(
                                 "Could not complete this refund because wallet id not found for asset",
-                            ),
+                            ).unwrap_err()),
                         };
                         let user_wallet = Wallet::find(wallet_id, connection)?;
                         state.config.tari_client.transfer_tokens(&organization_wallet.secret_key, &organization_wallet.public_key,
@@ -322,26 +674,95 @@ pub fn refund(
                                                                  user_wallet.public_key.clone(),
                         )?;
                     },
-                    None => return application::internal_server_error(
+                    None => return Err(application::internal_server_error::<HttpResponse>(
                         "Could not complete this refund because the asset is not assigned on the blockchain",
-                    ),
+                    ).unwrap_err()),
                 }
             }
 
             // Return error
-            return Err(error);
+            Err(error)
         }
-        _ => (),
+        Ok(result) => Ok(result),
     }
+}
 
-    // Commit changes as payment completed
-    if state.config.environment != Environment::Test {
-        conn.commit_transaction()?;
-        conn.begin_transaction()?;
+/// Records the negative payment for each planned amount and then refunds it with the payment
+/// provider where supported. Provider refunds are made last so nothing fallible follows them;
+/// when an `idempotency_key` is given a retried refund is only made once by the provider.
+pub fn refund_payments(
+    payment_refunds: &[PaymentRefundPlan],
+    user_id: Uuid,
+    idempotency_key: Option<&str>,
+    service_locator: &ServiceLocator,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let mut provider_refunds = Vec::new();
+    for payment_refund in payment_refunds {
+        let payment = Payment::find(payment_refund.payment_id, connection)?;
+        let amount = checked_amount(payment_refund.amount_in_cents)?;
+        let refunded_by_provider = match payment.payment_method {
+            PaymentMethods::CreditCard => true,
+            PaymentMethods::Provider => {
+                ServiceLocator::is_refund_supported(payment.provider.to_string())
+            }
+            _ => false,
+        };
+        let mut refund_data = None;
+        if refunded_by_provider {
+            let external_reference = match payment.external_reference {
+                Some(ref external_reference) => external_reference.clone(),
+                None => {
+                    return Err(application::internal_server_error::<HttpResponse>(&format!(
+                        "Unable to refund amount owed payment {} lacks external reference",
+                        payment.id
+                    ))
+                    .unwrap_err());
+                }
+            };
+            let payment_idempotency_key =
+                idempotency_key.map(|key| format!("{}-{}", key, payment.id));
+            refund_data = payment_idempotency_key
+                .as_ref()
+                .map(|key| json!({ "idempotency_key": key }));
+            provider_refunds.push((
+                payment.id,
+                service_locator.create_payment_processor(payment.provider)?,
+                external_reference,
+                amount,
+                payment_idempotency_key,
+            ));
+        }
+        // Records the negative payment and a PaymentRefund domain event
+        payment.log_refund(user_id, amount, refund_data, connection)?;
+    }
+
+    for (payment_id, client, external_reference, amount, payment_idempotency_key) in
+        provider_refunds
+    {
+        let result = client.partial_refund(
+            &external_reference,
+            amount,
+            payment_idempotency_key.as_ref().map(|key| key.as_str()),
+        )?;
+        jlog!(Info, "Refunded payment with provider", {
+            "payment_id": payment_id,
+            "amount": amount,
+            "refund_id": result.id
+        });
     }
+    Ok(())
+}
 
+pub fn send_refund_email(
+    order_id: Uuid,
+    amount_refunded: u32,
+    user: &User,
+    state: &State<AppState>,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
     // Reload order
-    let order = Order::find(order.id, connection)?;
+    let order = Order::find(order_id, connection)?;
     let display_order = order.for_display(None, user.id(), connection)?;
     let user = DbUser::find(
         order.on_behalf_of_user_id.unwrap_or(order.user_id),
@@ -355,15 +776,12 @@ pub fn refund(
             email,
             display_order,
             amount_refunded,
+            mailers::branding::organization_id_for_order(&order, connection)?,
             &state.config,
             connection,
         )?;
     }
-
-    Ok(HttpResponse::Ok().json(json!(RefundResponse {
-        amount_refunded,
-        refund_breakdown
-    })))
+    Ok(())
 }
 
 pub fn update(
@@ -0,0 +1,104 @@
+use actix_web::{HttpResponse, Path, State};
+use auth::user::User;
+use bigneon_db::models::*;
+use communications::mailers::branding;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::{OrganizationEmailTemplatePathParameters, PathParameters};
+use server::AppState;
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgRead, &organization, connection)?;
+
+    Ok(
+        HttpResponse::Ok().json(&OrganizationEmailTemplate::find_for_organization(
+            organization.id,
+            connection,
+        )?),
+    )
+}
+
+/// Creates or replaces the organization's overrides for one kind of email
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<OrganizationEmailTemplatePathParameters>,
+        Json<OrganizationEmailTemplateEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    let template_kind: EmailTemplateKinds = path.template_kind.parse()?;
+
+    let attributes = json.into_inner();
+    match OrganizationEmailTemplate::find_by_organization_and_kind(
+        organization.id,
+        template_kind,
+        connection,
+    )? {
+        Some(template) => Ok(HttpResponse::Ok().json(&template.update(attributes, connection)?)),
+        None => Ok(HttpResponse::Created().json(
+            &OrganizationEmailTemplate::create(organization.id, template_kind, attributes)
+                .commit(connection)?,
+        )),
+    }
+}
+
+pub fn destroy(
+    (connection, path, user): (
+        Connection,
+        Path<OrganizationEmailTemplatePathParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    let template_kind: EmailTemplateKinds = path.template_kind.parse()?;
+
+    if let Some(template) = OrganizationEmailTemplate::find_by_organization_and_kind(
+        organization.id,
+        template_kind,
+        connection,
+    )? {
+        template.destroy(connection)?;
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// The communication that would be queued for this kind of email with sample order data, showing
+/// the template, reply to address and branding data the organization's overrides resolve to
+pub fn sample_communication(
+    (connection, path, user, state): (
+        Connection,
+        Path<OrganizationEmailTemplatePathParameters>,
+        User,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgRead, &organization, connection)?;
+    let template_kind: EmailTemplateKinds = path.template_kind.parse()?;
+
+    let recipient = user
+        .user
+        .email
+        .clone()
+        .unwrap_or_else(|| state.config.communication_default_source_email.clone());
+    let communication = branding::sample_communication(
+        organization.id,
+        template_kind,
+        recipient,
+        &state.config,
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(&communication))
+}
@@ -6,6 +6,7 @@ use db::Connection;
 use errors::*;
 use extractors::*;
 use helpers::application;
+use helpers::export::{self, ExportFormat};
 use models::WebPayload;
 use models::{OrganizationUserPathParameters, PathParameters};
 use server::AppState;
@@ -56,6 +57,8 @@ pub struct NewOrganizationRequest {
     pub allowed_payment_providers: Option<Vec<PaymentProviders>>,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub timezone: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -150,6 +153,12 @@ pub fn create(
         facebook_pixel_key: new_organization.facebook_pixel_key.clone(),
         allowed_payment_providers: new_organization.allowed_payment_providers.clone(),
         timezone: new_organization.timezone.clone(),
+        currency: Some(
+            new_organization
+                .currency
+                .clone()
+                .unwrap_or_else(|| state.config.primary_currency.clone()),
+        ),
     };
 
     let mut organization = new_organization_with_fee_schedule.commit(
@@ -432,21 +441,43 @@ pub fn search_fans(
         Query<PagingParameters>,
         User,
     ),
-) -> Result<WebPayload<DisplayFan>, BigNeonError> {
+) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let org = Organization::find(path.id, connection)?;
     user.requires_scope_for_organization(Scopes::OrgFans, &org, &connection)?;
-    let payload = org.search_fans(
-        query.get_tag("query"),
-        query.page(),
-        query.limit(),
-        query
-            .sort
-            .as_ref()
-            .map(|s| s.parse().unwrap_or(FanSortField::LastOrder))
-            .unwrap_or(FanSortField::LastOrder),
-        query.dir.unwrap_or(SortingDir::Desc),
-        connection,
-    )?;
-    Ok(WebPayload::new(StatusCode::OK, payload))
+    let format = match query.get_tag("format") {
+        Some(format) => format.parse()?,
+        None => ExportFormat::Json,
+    };
+    let search = query.get_tag("query");
+    let sort_field = query
+        .sort
+        .as_ref()
+        .map(|s| s.parse().unwrap_or(FanSortField::LastOrder))
+        .unwrap_or(FanSortField::LastOrder);
+    let sort_direction = query.dir.unwrap_or(SortingDir::Desc);
+    if format == ExportFormat::Json {
+        let payload = org.search_fans(
+            search,
+            query.page(),
+            query.limit(),
+            sort_field,
+            sort_direction,
+            connection,
+        )?;
+        return Ok(HttpResponse::Ok().json(payload));
+    }
+
+    // Exports include every fan rather than a single page
+    export::paged_worksheet(format, "fans", connection, |page, limit, connection| {
+        let payload = org.search_fans(
+            search.clone(),
+            page,
+            limit,
+            sort_field,
+            sort_direction,
+            connection,
+        )?;
+        Ok(payload.data.to_worksheets(connection)?.remove(0))
+    })
 }
@@ -55,6 +55,13 @@ pub fn update(
         &parameters.password,
         connection.get(),
     )?;
+    // Users with two factor authentication are not signed in, they sign in with the new
+    // password and their code
+    if user.two_factor_enabled(connection.get())? {
+        return Ok(HttpResponse::Ok().json(json!({
+            "message": "Your password has been reset, please sign in with your two factor authentication code"
+        })));
+    }
 
     Ok(HttpResponse::Ok().json(&TokenResponse::create_from_user(
         &state.config.token_secret,
@@ -1,11 +1,126 @@
-use actix_web::HttpResponse;
+use actix_web::{HttpResponse, Path, State};
 use auth::user::User;
-use bigneon_db::models::ForDisplay;
+use bigneon_db::models::User as DbUser;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
 use db::Connection;
+use diesel::pg::PgConnection;
 use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use payments::{AuthThenCompletePaymentBehavior, PaymentProcessorBehavior};
+use server::AppState;
+
+#[derive(Deserialize)]
+pub struct CreatePaymentMethodRequest {
+    pub provider: PaymentProviders,
+    pub token: String,
+    #[serde(default)]
+    pub set_default: bool,
+}
 
 pub fn index((connection, auth_user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
     let connection = connection.get();
     let payment_methods = &auth_user.user.payment_methods(connection).for_display()?;
     Ok(HttpResponse::Ok().json(payment_methods))
 }
+
+pub fn create(
+    (connection, json, auth_user, state): (
+        Connection,
+        Json<CreatePaymentMethodRequest>,
+        User,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let client = state
+        .service_locator
+        .create_payment_processor(json.provider)?;
+    let behavior = match client.behavior() {
+        PaymentProcessorBehavior::AuthThenComplete(behavior) => behavior,
+        PaymentProcessorBehavior::RedirectToPaymentPage(_) => {
+            return application::unprocessable(
+                "Saving payment methods is not supported for this payment provider",
+            );
+        }
+    };
+
+    let payment_method = save_payment_method(
+        &*behavior,
+        &auth_user.user,
+        json.provider,
+        &json.token,
+        json.set_default,
+        connection,
+    )?;
+    Ok(HttpResponse::Created().json(payment_method.for_display()?))
+}
+
+pub fn set_default(
+    (connection, path, auth_user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let payment_method = PaymentMethod::find(path.id, connection)?;
+    if payment_method.user_id != auth_user.id() {
+        return application::unauthorized(Some(auth_user), None);
+    }
+
+    let payment_method = payment_method.set_default(auth_user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(payment_method.for_display()?))
+}
+
+pub fn destroy(
+    (connection, path, auth_user, state): (Connection, Path<PathParameters>, User, State<AppState>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let payment_method = PaymentMethod::find(path.id, connection)?;
+    if payment_method.user_id != auth_user.id() {
+        return application::unauthorized(Some(auth_user), None);
+    }
+
+    // Cards saved before multiple cards were supported are the provider customer's only card
+    // and are left with the provider
+    if let Some(ref source_reference) = payment_method.source_reference {
+        let client = state
+            .service_locator
+            .create_payment_processor(payment_method.name)?;
+        if let PaymentProcessorBehavior::AuthThenComplete(behavior) = client.behavior() {
+            behavior.remove_card_from_repeat_token(&payment_method.provider, source_reference)?;
+        }
+    }
+
+    payment_method.destroy(auth_user.id(), connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Saves the card with the provider, adding it to the user's existing provider customer if
+/// they already have one
+pub fn save_payment_method(
+    behavior: &AuthThenCompletePaymentBehavior,
+    user: &DbUser,
+    provider: PaymentProviders,
+    token: &str,
+    set_default: bool,
+    conn: &PgConnection,
+) -> Result<PaymentMethod, BigNeonError> {
+    let repeat_token = match user.payment_method(provider, conn).optional()? {
+        Some(payment_method) => {
+            behavior.add_card_to_repeat_token(&payment_method.provider, token)?
+        }
+        None => behavior.create_token_for_repeat_charges(token, "Big Neon")?,
+    };
+
+    let mut payment_method = PaymentMethod::create(
+        user.id,
+        provider,
+        set_default,
+        repeat_token.token.clone(),
+        repeat_token.to_json()?,
+    );
+    if let Some(card) = repeat_token.card {
+        payment_method = payment_method.with_card_details(card);
+    }
+    Ok(payment_method.commit(user.id, conn)?)
+}
@@ -7,6 +7,8 @@ use bigneon_db::prelude::*;
 use errors::*;
 use extractors::OptionalUser;
 use helpers::application;
+use log::Level::Error;
+use payments::{ChargeResult, PaymentProcessorBehavior};
 use server::AppState;
 use uuid::Uuid;
 
@@ -45,24 +47,30 @@ pub fn callback(
         None => return application::not_found(),
     };
 
-    // We specifically don't count this as a payment confirmation, that will be done via the IPN
-    // Just redirect to page accordingly
-
-    if query.success {
-        if payment.status == PaymentStatus::Requested {
-            payment.mark_pending_ipn(user.id(), conn)?;
+    // Providers confirming payments via an IPN are only marked as pending here, providers that
+    // take the payment once the buyer returns complete it straight away
+    let mut success = query.success;
+    let mut raw_data = json!({"path": &path.into_inner(), "query": &query.into_inner()});
+    if success && payment.status == PaymentStatus::Requested {
+        match complete_payment_request(&order, &payment, &state) {
+            Ok(Some(charge)) => payment.mark_complete(charge.to_json()?, user.id(), conn)?,
+            Ok(None) => payment.mark_pending_ipn(user.id(), conn)?,
+            Err(e) => {
+                jlog!(Error, "Could not complete payment request", {"payment_id": payment.id, "error": e.to_string()});
+                success = false;
+                raw_data["error"] = json!(e.to_string());
+            }
         }
+    }
+
+    if success {
         application::redirect(&format!(
             "{}/events/{}/tickets/success",
             state.config.front_end_url,
             order.main_event_id(conn)?
         ))
     } else {
-        payment.mark_cancelled(
-            json!({"path": &path.into_inner(), "query": &query.into_inner()}),
-            None,
-            conn,
-        )?;
+        payment.mark_cancelled(raw_data, None, conn)?;
         // order.reset_to_draft(None, conn)?;
         application::redirect(&format!(
             "{}/events/{}/tickets/confirmation",
@@ -71,3 +79,28 @@ pub fn callback(
         ))
     }
 }
+
+fn complete_payment_request(
+    order: &Order,
+    payment: &Payment,
+    state: &AppState,
+) -> Result<Option<ChargeResult>, BigNeonError> {
+    let external_reference = match payment.external_reference {
+        Some(ref external_reference) => external_reference,
+        None => return Ok(None),
+    };
+    match state
+        .service_locator
+        .create_payment_processor(payment.provider)?
+        .behavior()
+    {
+        PaymentProcessorBehavior::RedirectToPaymentPage(behavior) => {
+            let currency = order
+                .currency
+                .clone()
+                .unwrap_or_else(|| state.config.primary_currency.clone());
+            Ok(behavior.complete_payment_request(external_reference, payment.amount, &currency)?)
+        }
+        PaymentProcessorBehavior::AuthThenComplete(_) => Ok(None),
+    }
+}
@@ -0,0 +1,84 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateReportSubscriptionRequest {
+    pub event_id: Option<Uuid>,
+    pub report_type: ReportTypes,
+    pub frequency: ReportFrequencies,
+    pub email: String,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+
+    Ok(
+        HttpResponse::Ok().json(&ReportSubscription::find_for_organization(
+            organization.id,
+            connection,
+        )?),
+    )
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateReportSubscriptionRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(
+        scope_for_report(json.report_type),
+        &organization,
+        connection,
+    )?;
+
+    let json = json.into_inner();
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        json.event_id,
+        json.report_type,
+        json.frequency,
+        json.email,
+        user.id(),
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&report_subscription))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let report_subscription = ReportSubscription::find(path.id, connection)?;
+    let organization = Organization::find(report_subscription.organization_id, connection)?;
+    user.requires_scope_for_organization(
+        scope_for_report(report_subscription.report_type),
+        &organization,
+        connection,
+    )?;
+
+    report_subscription.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Matches the scope required to view the report on demand
+fn scope_for_report(report_type: ReportTypes) -> Scopes {
+    match report_type {
+        ReportTypes::TicketCount => Scopes::OrgReports,
+        ReportTypes::EventSummary | ReportTypes::WeeklySettlement => Scopes::OrgFinancialReports,
+    }
+}
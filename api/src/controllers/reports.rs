@@ -1,10 +1,11 @@
 use actix_web::{HttpResponse, Path, Query};
 use auth::user::User as AuthUser;
-use bigneon_db::models::{Event, Organization, Report, Scopes};
+use bigneon_db::models::{Event, Exportable, Organization, Report, Scopes};
 use chrono::prelude::*;
 use db::Connection;
 use errors::*;
 use helpers::application;
+use helpers::export::{self, ExportFormat};
 use models::PathParameters;
 use std::str;
 use uuid::Uuid;
@@ -15,6 +16,7 @@ pub struct ReportQueryParameters {
     pub start_utc: Option<NaiveDateTime>,
     pub end_utc: Option<NaiveDateTime>,
     pub event_id: Option<Uuid>,
+    pub format: Option<ExportFormat>,
 }
 
 pub fn get_report(
@@ -67,7 +69,7 @@ pub fn transaction_detail_report(
         query.end_utc,
         connection,
     )?;
-    Ok(HttpResponse::Ok().json(result))
+    export::respond(query.format, "transaction_details", &result, connection)
 }
 
 pub fn event_summary_report(
@@ -101,7 +103,7 @@ pub fn event_summary_report(
         query.end_utc,
         connection,
     )?;
-    Ok(HttpResponse::Ok().json(result))
+    export::respond(query.format, "event_summary", &result, connection)
 }
 
 pub fn audit_report(
@@ -149,6 +151,23 @@ pub fn audit_report(
     // TODO: update this query to do the inventory at end_date
     let ticket_counts = Report::ticket_count_report(query.event_id, Some(path.id), connection)?;
 
+    if let Some(format) = query.format {
+        if format != ExportFormat::Json {
+            let mut worksheets = vec![];
+            for (prefix, result) in vec![
+                ("End Date", &end_date_sales_result),
+                ("All", &all_sales_result),
+            ] {
+                for mut worksheet in result.to_worksheets(connection)? {
+                    worksheet.name = format!("{} {}", prefix, worksheet.name);
+                    worksheets.push(worksheet);
+                }
+            }
+            worksheets.extend(ticket_counts.to_worksheets(connection)?);
+            return export::worksheets(format, "audit", &worksheets);
+        }
+    }
+
     Ok(HttpResponse::Ok().json(json!({
     "end_date_sales":end_date_sales_result,
     "all_sales": all_sales_result,
@@ -172,7 +191,7 @@ pub fn weekly_settlement_report(
 
     let result =
         Report::organization_summary_report(path.id, query.start_utc, query.end_utc, connection)?;
-    Ok(HttpResponse::Ok().json(result))
+    export::respond(query.format, "weekly_settlement", &result, connection)
 }
 
 pub fn ticket_counts(
@@ -199,7 +218,7 @@ pub fn ticket_counts(
     }
 
     let result = Report::ticket_count_report(query.event_id, Some(path.id), connection)?;
-    Ok(HttpResponse::Ok().json(result))
+    export::respond(query.format, "ticket_count", &result, connection)
 }
 
 pub fn reconciliation_summary_report(
@@ -218,7 +237,7 @@ pub fn reconciliation_summary_report(
 
     let result =
         Report::reconciliation_summary_report(path.id, query.start_utc, query.end_utc, connection)?;
-    Ok(HttpResponse::Ok().json(result))
+    export::respond(query.format, "reconciliation_summary", &result, connection)
 }
 
 pub fn reconciliation_detail_report(
@@ -237,5 +256,5 @@ pub fn reconciliation_detail_report(
 
     let result =
         Report::reconciliation_detail_report(path.id, query.start_utc, query.end_utc, connection)?;
-    Ok(HttpResponse::Ok().json(result))
+    export::respond(query.format, "reconciliation_details", &result, connection)
 }
@@ -0,0 +1,53 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateResaleListingRequest {
+    pub ticket_instance_id: Uuid,
+    pub price_in_cents: i64,
+}
+
+pub fn index(
+    (connection, parameters): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+
+    Ok(
+        HttpResponse::Ok().json(&ResaleListing::find_available_for_event(
+            event.id, connection,
+        )?),
+    )
+}
+
+pub fn create(
+    (connection, json, user): (Connection, Json<CreateResaleListingRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let json = json.into_inner();
+
+    let resale_listing =
+        ResaleListing::create(json.ticket_instance_id, user.id(), json.price_in_cents)
+            .commit(connection)?;
+    Ok(HttpResponse::Created().json(&resale_listing))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let resale_listing = ResaleListing::find(parameters.id, connection)?;
+    if resale_listing.seller_user_id != user.id() {
+        return application::unauthorized(Some(user), None);
+    }
+
+    resale_listing.cancel(user.id(), connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
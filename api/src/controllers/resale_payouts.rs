@@ -0,0 +1,34 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use models::{PathParameters, WebPayload};
+
+/// Seller payouts, pending by default, for the payouts to be made outside of Big Neon
+pub fn index(
+    (connection, query_parameters, user): (Connection, Query<PagingParameters>, User),
+) -> Result<WebPayload<ResalePayout>, BigNeonError> {
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let status = match query_parameters.get_tag("status") {
+        Some(status) => status.parse::<ResalePayoutStatus>()?,
+        None => ResalePayoutStatus::Pending,
+    };
+
+    let payload = ResalePayout::find_by_status(
+        status,
+        query_parameters.page(),
+        query_parameters.limit(),
+        connection.get(),
+    )?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+pub fn mark_paid(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let connection = connection.get();
+    let payout = ResalePayout::find(parameters.id, connection)?.mark_paid(user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(&payout))
+}
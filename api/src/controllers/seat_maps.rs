@@ -0,0 +1,100 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreatePriceZoneRequest {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreateSeatRowRequest {
+    pub name: String,
+    pub seat_count: u32,
+    pub price_zone_id: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateSeatingSectionRequest {
+    pub name: String,
+    pub rows: Vec<CreateSeatRowRequest>,
+}
+
+pub fn show(
+    (connection, parameters): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let stage = Stage::find(parameters.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(&stage.seat_map(connection)?))
+}
+
+pub fn create_price_zone(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreatePriceZoneRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let stage = Stage::find(parameters.id, connection)?;
+    requires_venue_write(&stage, &user, connection)?;
+
+    let price_zone = PriceZone::create(stage.id, json.into_inner().name).commit(connection)?;
+    Ok(HttpResponse::Created().json(&price_zone))
+}
+
+pub fn create_seating_section(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateSeatingSectionRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let stage = Stage::find(parameters.id, connection)?;
+    requires_venue_write(&stage, &user, connection)?;
+
+    let json = json.into_inner();
+    let seating_section = SeatingSection::create(stage.id, json.name).commit(connection)?;
+    for row in json.rows {
+        seating_section.add_row(row.name, row.seat_count, row.price_zone_id, connection)?;
+    }
+
+    Ok(HttpResponse::Created().json(&seating_section.for_display(connection)?))
+}
+
+pub fn destroy_seating_section(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let seating_section = SeatingSection::find(parameters.id, connection)?;
+    let stage = Stage::find(seating_section.stage_id, connection)?;
+    requires_venue_write(&stage, &user, connection)?;
+
+    seating_section.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+fn requires_venue_write(
+    stage: &Stage,
+    user: &AuthUser,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let venue = Venue::find(stage.venue_id, connection)?;
+    if !venue.is_private || venue.organization_id.is_none() {
+        user.requires_scope(Scopes::VenueWrite)?;
+    } else {
+        let organization = venue.organization(connection)?.unwrap();
+        user.requires_scope_for_organization(Scopes::VenueWrite, &organization, connection)?;
+    }
+    Ok(())
+}
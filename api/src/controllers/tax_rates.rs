@@ -0,0 +1,66 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateTaxRateRequest {
+    pub region_id: Option<Uuid>,
+    pub name: String,
+    pub rate_percent: f32,
+    #[serde(default)]
+    pub inclusive: bool,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    Ok(HttpResponse::Ok().json(&TaxRate::find_for_organization(
+        organization.id,
+        connection,
+    )?))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateTaxRateRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    let json = json.into_inner();
+    let tax_rate = TaxRate::create(
+        organization.id,
+        json.region_id,
+        json.name,
+        json.rate_percent,
+        json.inclusive,
+    )
+    .commit(user.id(), connection)?;
+    Ok(HttpResponse::Created().json(&tax_rate))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let tax_rate = TaxRate::find(path.id, connection)?;
+    let organization = Organization::find(tax_rate.organization_id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    tax_rate.destroy(user.id(), connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
@@ -20,6 +20,8 @@ pub struct CreateTicketPricingRequest {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub is_box_office_only: Option<bool>,
+    pub quantity_limit: Option<i64>,
+    pub rank: Option<i32>,
 }
 
 #[derive(Deserialize)]
@@ -46,6 +48,8 @@ pub struct UpdateTicketPricingRequest {
     pub end_date: Option<NaiveDateTime>,
     pub price_in_cents: Option<i64>,
     pub is_box_office_only: Option<bool>,
+    pub quantity_limit: Option<i64>,
+    pub rank: Option<i32>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -107,15 +111,27 @@ pub fn create(
     )?;
     //Add each ticket pricing entry for newly created ticket type
     for current_pricing_entry in &data.ticket_pricing {
-        let _pricing_result = ticket_type.add_ticket_pricing(
-            current_pricing_entry.name.clone(),
-            current_pricing_entry.start_date,
-            current_pricing_entry.end_date,
-            current_pricing_entry.price_in_cents,
-            current_pricing_entry.is_box_office_only.unwrap_or(false),
-            None,
-            connection,
-        )?;
+        let _pricing_result = match current_pricing_entry.quantity_limit {
+            Some(quantity_limit) => ticket_type.add_ticket_pricing_tier(
+                current_pricing_entry.name.clone(),
+                current_pricing_entry.start_date,
+                current_pricing_entry.end_date,
+                current_pricing_entry.price_in_cents,
+                quantity_limit,
+                current_pricing_entry.rank.unwrap_or(0),
+                current_pricing_entry.is_box_office_only.unwrap_or(false),
+                connection,
+            )?,
+            None => ticket_type.add_ticket_pricing(
+                current_pricing_entry.name.clone(),
+                current_pricing_entry.start_date,
+                current_pricing_entry.end_date,
+                current_pricing_entry.price_in_cents,
+                current_pricing_entry.is_box_office_only.unwrap_or(false),
+                None,
+                connection,
+            )?,
+        };
     }
 
     ticket_type.validate_ticket_pricing(connection)?;
@@ -278,6 +294,9 @@ pub fn update(
                     "Could not complete capacity increase because the asset has not been assigned on the blockchain",
                 ),
             }
+
+            // Pair the newly issued tickets with the remaining seats of the price zone
+            ticket_type.assign_seats_to_new_tickets(connection)?;
         } else if valid_ticket_count > requested_capacity {
             let nullify_ticket_count = valid_ticket_count - requested_capacity;
             nullify_tickets(
@@ -333,6 +352,8 @@ pub fn update(
                     start_date: current_ticket_pricing.start_date,
                     end_date: current_ticket_pricing.end_date,
                     is_box_office_only: current_ticket_pricing.is_box_office_only,
+                    quantity_limit: current_ticket_pricing.quantity_limit.map(Some),
+                    rank: current_ticket_pricing.rank,
                 };
                 let found_index = ticket_pricing
                     .iter()
@@ -354,18 +375,29 @@ pub fn update(
             ) {
                 //Only create a new pricing entry if all of its required data was provided
                 //Add new ticket pricing
-                let _pricing_result = updated_ticket_type.add_ticket_pricing(
-                    name,
-                    start_date,
-                    end_date,
-                    price_in_cents,
-                    current_ticket_pricing.is_box_office_only.unwrap_or(false),
-                    None,
-                    connection,
-                )?;
+                let _pricing_result = match current_ticket_pricing.quantity_limit {
+                    Some(quantity_limit) => updated_ticket_type.add_ticket_pricing_tier(
+                        name,
+                        start_date,
+                        end_date,
+                        price_in_cents,
+                        quantity_limit,
+                        current_ticket_pricing.rank.unwrap_or(0),
+                        current_ticket_pricing.is_box_office_only.unwrap_or(false),
+                        connection,
+                    )?,
+                    None => updated_ticket_type.add_ticket_pricing(
+                        name,
+                        start_date,
+                        end_date,
+                        price_in_cents,
+                        current_ticket_pricing.is_box_office_only.unwrap_or(false),
+                        None,
+                        connection,
+                    )?,
+                };
             } else {
                 //TODO send error when all data was not specified
-
             }
         }
         updated_ticket_type.validate_ticket_pricing(connection)?;
@@ -380,6 +412,100 @@ pub fn update(
     Ok(HttpResponse::Ok().json(result))
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct UpdatePriceZoneRequest {
+    pub price_zone_id: Option<Uuid>,
+}
+
+pub fn update_price_zone(
+    (connection, path, data, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<UpdatePriceZoneRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.event_id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::TicketTypeWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
+
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != event.id {
+        return application::not_found();
+    }
+    if ticket_type.price_zone_id != data.price_zone_id {
+        ticket_type.set_price_zone(data.price_zone_id, connection)?;
+    }
+
+    let result = AdminDisplayTicketType::from_ticket_type(
+        &(TicketType::find(path.ticket_type_id, connection)?),
+        &FeeSchedule::find(organization.fee_schedule_id, connection)?,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn seats(
+    (connection, path): (Connection, Path<EventTicketPathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+
+    Ok(HttpResponse::Ok().json(ticket_type.seats(connection)?))
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JoinWaitlistRequest {
+    pub quantity: i32,
+}
+
+pub fn join_waitlist(
+    (connection, path, data, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<JoinWaitlistRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+
+    let waitlist_entry =
+        WaitlistEntry::create(ticket_type.id, user.id(), data.quantity).commit(connection)?;
+    Ok(HttpResponse::Created().json(&waitlist_entry))
+}
+
+pub fn leave_waitlist(
+    (connection, path, user): (Connection, Path<EventTicketPathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+
+    match WaitlistEntry::find_active_for_user(ticket_type.id, user.id(), connection)? {
+        Some(waitlist_entry) => {
+            waitlist_entry.cancel(connection)?;
+            application::no_content()
+        }
+        None => application::not_found(),
+    }
+}
+
 fn nullify_tickets(
     state: State<AppState>,
     organization: Organization,
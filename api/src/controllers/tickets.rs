@@ -4,7 +4,7 @@ use auth::user::User;
 use bigneon_db::models::User as DbUser;
 use bigneon_db::prelude::*;
 use chrono::prelude::*;
-use communications::{mailers, smsers};
+use communications::{mailers, pushers, smsers};
 use db::Connection;
 use errors::*;
 use extractors::*;
@@ -143,6 +143,12 @@ pub fn send_via_email_or_phone(
                 user.id,
                 connection,
             )?;
+            pushers::tickets::transfer_received(
+                &auth_user.user,
+                user.id,
+                send_tickets_request.ticket_ids.len(),
+                connection,
+            )?;
         } else {
             let authorization = TicketInstance::authorize_ticket_transfer(
                 auth_user.id(),
@@ -160,6 +166,10 @@ pub fn send_via_email_or_phone(
                 &authorization.transfer_key.to_string(),
                 &authorization.signature,
                 &auth_user.user,
+                mailers::branding::organization_id_for_tickets(
+                    &send_tickets_request.ticket_ids,
+                    connection,
+                )?,
                 connection,
             )?;
         }
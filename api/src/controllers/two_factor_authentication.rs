@@ -0,0 +1,137 @@
+use actix_web::{HttpResponse, State};
+use auth::two_factor;
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use server::AppState;
+use url::percent_encoding::{utf8_percent_encode, PATH_SEGMENT_ENCODE_SET};
+
+#[derive(Deserialize, Serialize)]
+pub struct TwoFactorCodeRequest {
+    pub code: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TwoFactorEnrolmentResponse {
+    pub secret: String,
+    /// Shown as a QR code for authenticator apps to scan
+    pub otpauth_url: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+pub fn create(
+    (state, connection, user): (State<AppState>, Connection, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let secret = UserTwoFactorCredential::begin_enrolment(
+        user.id(),
+        &state.config.api_keys_encryption_key,
+        connection,
+    )?;
+    let account = user.email().unwrap_or_else(|| user.id().to_string());
+    let otpauth_url = format!(
+        "otpauth://totp/{}?secret={}&issuer={}",
+        utf8_percent_encode(
+            &format!("{}:{}", state.config.app_name, account),
+            PATH_SEGMENT_ENCODE_SET
+        ),
+        secret,
+        utf8_percent_encode(&state.config.app_name, PATH_SEGMENT_ENCODE_SET)
+    );
+    Ok(HttpResponse::Created().json(TwoFactorEnrolmentResponse {
+        secret,
+        otpauth_url,
+    }))
+}
+
+pub fn confirm(
+    (state, connection, json, user): (
+        State<AppState>,
+        Connection,
+        Json<TwoFactorCodeRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let credential = match UserTwoFactorCredential::find_by_user_id(user.id(), connection)? {
+        Some(credential) => credential,
+        None => {
+            return application::unprocessable(
+                "Two factor authentication enrolment has not been started",
+            );
+        }
+    };
+    let recovery_codes = credential.confirm(
+        &json.code,
+        &state.config.api_keys_encryption_key,
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(RecoveryCodesResponse { recovery_codes }))
+}
+
+pub fn regenerate_recovery_codes(
+    (state, conn, json, user): (
+        State<AppState>,
+        Connection,
+        Json<TwoFactorCodeRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    let credential = match UserTwoFactorCredential::find_enabled_for_user(user.id(), connection)? {
+        Some(credential) => credential,
+        None => return application::unprocessable("Two factor authentication is not enabled"),
+    };
+    if !two_factor::verify_code(&credential, &json.code, &state.config, &conn)? {
+        return application::unprocessable("Two factor authentication code is invalid");
+    }
+
+    let recovery_codes = credential.regenerate_recovery_codes(connection)?;
+    Ok(HttpResponse::Ok().json(RecoveryCodesResponse { recovery_codes }))
+}
+
+pub fn disable(
+    (state, conn, json, user): (
+        State<AppState>,
+        Connection,
+        Json<TwoFactorCodeRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    let credential = match UserTwoFactorCredential::find_enabled_for_user(user.id(), connection)? {
+        Some(credential) => credential,
+        None => return application::unprocessable("Two factor authentication is not enabled"),
+    };
+
+    // Members with financial access to an organization requiring two factor authentication
+    // need to give up that access before disabling it
+    if !user.user.is_admin() {
+        for organization in user.user.organizations(connection)? {
+            if organization.two_factor_required
+                && organization
+                    .get_scopes_for_user(&user.user, connection)?
+                    .iter()
+                    .any(|s| s.is_financial())
+            {
+                return application::forbidden(
+                    "Two factor authentication is required by one of your organizations",
+                );
+            }
+        }
+    }
+
+    if !two_factor::verify_code(&credential, &json.code, &state.config, &conn)? {
+        return application::unprocessable("Two factor authentication code is invalid");
+    }
+
+    credential.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,99 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::{PathParameters, WebPayload};
+use server::AppState;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateWebhookSubscriptionRequest {
+    pub url: String,
+    pub event_types: Vec<DomainEventTypes>,
+}
+
+/// The signing secret is only returned when the subscription is created
+#[derive(Deserialize, Serialize)]
+pub struct CreatedWebhookSubscriptionResponse {
+    #[serde(flatten)]
+    pub webhook_subscription: DisplayWebhookSubscription,
+    pub secret: String,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    let webhook_subscriptions: Vec<DisplayWebhookSubscription> =
+        WebhookSubscription::find_for_organization(organization.id, connection)?
+            .into_iter()
+            .map(|s| s.into())
+            .collect();
+    Ok(HttpResponse::Ok().json(&webhook_subscriptions))
+}
+
+pub fn create(
+    (state, connection, path, json, user): (
+        State<AppState>,
+        Connection,
+        Path<PathParameters>,
+        Json<CreateWebhookSubscriptionRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    let json = json.into_inner();
+    let new_webhook_subscription =
+        WebhookSubscription::create(organization.id, json.url, json.event_types, user.id());
+    let webhook_subscription =
+        new_webhook_subscription.commit(&state.config.api_keys_encryption_key, connection)?;
+    Ok(
+        HttpResponse::Created().json(&CreatedWebhookSubscriptionResponse {
+            webhook_subscription: webhook_subscription.into(),
+            secret: new_webhook_subscription.secret,
+        }),
+    )
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let webhook_subscription = WebhookSubscription::find(path.id, connection)?;
+    let organization = Organization::find(webhook_subscription.organization_id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    webhook_subscription.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn deliveries(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<DisplayWebhookDelivery>, BigNeonError> {
+    let connection = connection.get();
+    let webhook_subscription = WebhookSubscription::find(path.id, connection)?;
+    let organization = Organization::find(webhook_subscription.organization_id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    let (deliveries, total) =
+        webhook_subscription.deliveries(query.limit(), query.page(), connection)?;
+
+    let mut paging = Paging::new(query.page(), query.limit());
+    paging.total = total as u64;
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::new(deliveries, paging),
+    ))
+}
@@ -19,11 +19,9 @@ use tokio::prelude::*;
 use tokio::runtime::current_thread;
 use tokio::runtime::Runtime;
 use tokio::timer::Timeout;
-//
-//fn example_subscription(_: &DomainEvent) -> Option<NewDomainAction> {
-//    // Other subscriptions should conform to this signature
-//    None
-//}
+
+// Subscriptions should conform to this signature
+type Subscription = fn(&DomainEvent) -> Option<NewDomainAction>;
 
 pub struct DomainActionMonitor {
     config: Config,
@@ -42,17 +40,18 @@ impl DomainActionMonitor {
         }
     }
 
-    //    fn get_publisher() -> DomainEventPublisher {
-    //        let mut publisher = DomainEventPublisher::new();
-    //        publisher.add_subscription(DomainEventTypes::PaymentCreated, example_subscription);
-    //        publisher
-    //    }
+    fn get_publisher() -> DomainEventPublisher<Subscription> {
+        // Webhook deliveries are queued for every published event, add other subscriptions here
+        DomainEventPublisher::new()
+    }
 
     pub fn run_til_empty(&self) -> Result<(), DomainActionError> {
-        //let publisher = DomainActionMonitor::get_publisher();
+        let publisher = DomainActionMonitor::get_publisher();
         let router = DomainActionMonitor::create_router(&self.config);
 
         loop {
+            let num_published =
+                DomainActionMonitor::find_and_publish_events(&self.database, &publisher)?;
             let mut num_processed = 0;
 
             let futures = DomainActionMonitor::find_actions(
@@ -74,61 +73,36 @@ impl DomainActionMonitor {
                 num_processed += 1;
             }
 
-            if num_processed == 0 {
+            if num_processed == 0 && num_published == 0 {
                 break;
             }
         }
         Ok(())
     }
 
-    //    fn find_and_publish_events(database: &Database, publisher: &DomainEventPublisher) -> Result<usize, Domain> {
-    //
-    //        let connection = database.get_connection();
-    //
-    //        let pending_events = DomainEvent::find_unpublished(100, connection.get())?;
-    //
-    //        if pending_events.len() > 0 {
-    //            jlog!(
-    //                    Info,
-    //                    "bigneon::domain_actions",
-    //                    "Found events to process",
-    //                    { "count": pending_events.len() }
-    //                );
-    //
-    //            for event in pending_events {
-    //                publisher.publish(event, connection.get())?;
-    //            }
-    //        }
-    //        Ok(pending_events.len())
-    //
-    //    }
-
-    //    #[allow(unreachable_code)]
-    //    pub fn publish_events_to_actions(
-    //        database: Database,
-    //        interval: u64,
-    //        rx: Receiver<()>,
-    //    ) -> Result<(), DomainActionError> {
-    //        let publisher = DomainActionMonitor::get_publisher();
-    //        loop {
-    //            if rx.try_recv().is_ok() {
-    //                jlog!(
-    //                    Info,
-    //                    "bigneon::domain_actions",
-    //                    "Stopping events processor",
-    //                    {}
-    //                );
-    //                break;
-    //            }
-    //            //Domain Monitor main loop
-    //            let num_published = DomainActionMonitor::find_and_publish_events(&database, &publisher)?;
-    //            if num_published == 0 {
-    //                thread::sleep(Duration::from_secs(interval));
-    //            }
-    //        }
-    //
-    //        Ok(())
-    //    }
+    fn find_and_publish_events(
+        database: &Database,
+        publisher: &DomainEventPublisher<Subscription>,
+    ) -> Result<usize, DomainActionError> {
+        let connection = database.get_connection()?;
+
+        let pending_events = DomainEvent::find_unpublished(100, connection.get())?;
+        let num_events = pending_events.len();
+
+        if num_events > 0 {
+            jlog!(
+                Debug,
+                "bigneon::domain_actions",
+                "Found events to publish",
+                { "count": num_events }
+            );
+
+            for event in pending_events {
+                publisher.publish(event, connection.get())?;
+            }
+        }
+        Ok(num_events)
+    }
 
     fn create_router(conf: &Config) -> DomainActionRouter {
         let mut router = DomainActionRouter::new();
@@ -227,6 +201,7 @@ impl DomainActionMonitor {
         interval: u64,
         rx: Receiver<()>,
     ) -> Result<(), DomainActionError> {
+        let publisher = DomainActionMonitor::get_publisher();
         let router = DomainActionMonitor::create_router(&conf);
 
         let mut runtime = Runtime::new()?;
@@ -243,6 +218,10 @@ impl DomainActionMonitor {
                 );
                 break;
             }
+            // Events are published in the same loop so their actions are picked up on this pass
+            let num_published =
+                DomainActionMonitor::find_and_publish_events(&database, &publisher)?;
+
             //Check for actions that are due to be processed
 
             let futures = DomainActionMonitor::find_actions(
@@ -251,7 +230,7 @@ impl DomainActionMonitor {
                 (conf.database_pool_size / 2) as usize,
             )?;
 
-            if futures.len() == 0 {
+            if futures.len() == 0 && num_published == 0 {
                 thread::sleep(Duration::from_secs(interval));
             } else {
                 for f in futures {
@@ -294,17 +273,6 @@ impl DomainActionMonitor {
                 Ok(())
             }),
         ));
-
-        //        let (tx, rx) = mpsc::channel::<()>();
-        //
-        //        let database = self.database.clone();
-        //
-        //        self.worker_threads.push((
-        //            tx,
-        //            thread::spawn(move || {
-        //                DomainActionMonitor::publish_events_to_actions(database, interval, rx)
-        //            }),
-        //        ));
     }
 
     pub fn stop(&mut self) {
@@ -1,4 +1,11 @@
 pub mod marketing_contacts;
+pub mod process_event_cancellation;
 pub mod process_payment_ipn;
+pub mod process_stripe_webhook;
+pub mod process_waitlist;
 pub mod send_communication;
+pub mod send_event_communication;
+pub mod send_event_day_reminders;
 pub mod send_order_complete;
+pub mod send_scheduled_report;
+pub mod send_webhook;
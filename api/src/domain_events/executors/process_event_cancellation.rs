@@ -0,0 +1,159 @@
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use communications::mailers;
+use config::{Config, Environment};
+use controllers::orders;
+use db::Connection;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+use utils::ServiceLocator;
+
+/// Orders refunded per run, the remaining orders are picked up by the next run to avoid
+/// hitting payment provider rate limits
+const REFUND_BATCH_SIZE: i64 = 25;
+const SECONDS_BETWEEN_BATCHES: i64 = 30;
+
+pub struct ProcessEventCancellationExecutor {
+    config: Config,
+    service_locator: ServiceLocator,
+}
+
+impl DomainActionExecutor for ProcessEventCancellationExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process event cancellation action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessEventCancellationExecutor {
+    pub fn new(config: Config) -> ProcessEventCancellationExecutor {
+        let service_locator = ServiceLocator::new(&config);
+        ProcessEventCancellationExecutor {
+            config,
+            service_locator,
+        }
+    }
+
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        connection: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let conn = connection.get();
+        let cancellation = EventCancellation::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No event cancellation id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+        if cancellation.status != EventCancellationStatus::InProgress {
+            return Ok(());
+        }
+        let event = cancellation.event(conn)?;
+
+        for cancellation_order in cancellation.pending_orders(REFUND_BATCH_SIZE, conn)? {
+            // Each order is refunded in a savepoint so a failed order does not undo the refunds
+            // already made with the payment providers for the rest of the batch
+            match conn
+                .transaction(|| self.refund_order(&cancellation, &cancellation_order, &event, conn))
+            {
+                Ok((order, amount_refunded)) => {
+                    cancellation_order.mark_refunded(
+                        amount_refunded as i64,
+                        cancellation.created_by_user_id,
+                        conn,
+                    )?;
+                    // The email is queued separately so failing to send it never leaves a
+                    // refunded order to be refunded again
+                    if let Err(e) = conn.transaction(|| {
+                        self.send_cancelled_email(&order, &event, amount_refunded, conn)
+                    }) {
+                        jlog!(Error, "Event cancellation email failed", {"event_cancellation_id": cancellation.id, "order_id": cancellation_order.order_id, "error": e.to_string()});
+                    }
+                    // Commit so the refund is recorded even if a later order in the batch fails
+                    if self.config.environment != Environment::Test {
+                        connection.commit_transaction()?;
+                        connection.begin_transaction()?;
+                    }
+                }
+                Err(e) => {
+                    jlog!(Error, "Event cancellation order refund failed", {"event_cancellation_id": cancellation.id, "order_id": cancellation_order.order_id, "error": e.to_string()});
+                    cancellation_order.mark_failed(&e.to_string(), conn)?;
+                }
+            }
+        }
+
+        let cancellation = cancellation.complete_if_finished(conn)?;
+        if cancellation.status == EventCancellationStatus::InProgress {
+            cancellation.queue_processing(
+                Utc::now().naive_utc() + Duration::seconds(SECONDS_BETWEEN_BATCHES),
+                conn,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Refunds the order's tickets and add-ons for the event, the payment provider refunds are
+    /// made last and keyed on the cancellation order so a retried refund is only made once
+    fn refund_order(
+        &self,
+        cancellation: &EventCancellation,
+        cancellation_order: &EventCancellationOrder,
+        event: &Event,
+        conn: &PgConnection,
+    ) -> Result<(Order, u32), BigNeonError> {
+        let order = cancellation_order.order(conn)?;
+        let amount_refunded = order.refund_cancelled_event_tickets(
+            event.id,
+            cancellation.refund_fees,
+            Some(cancellation.created_by_user_id),
+            conn,
+        )?;
+        if amount_refunded > 0 {
+            let payment_refunds = RefundPlan::allocate(&order, amount_refunded as i64, conn)?;
+            orders::refund_payments(
+                &payment_refunds,
+                cancellation.created_by_user_id,
+                Some(&format!("event-cancellation-{}", cancellation_order.id)),
+                &self.service_locator,
+                conn,
+            )?;
+        }
+        Ok((order, amount_refunded))
+    }
+
+    fn send_cancelled_email(
+        &self,
+        order: &Order,
+        event: &Event,
+        amount_refunded: u32,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        let user = User::find(order.on_behalf_of_user_id.unwrap_or(order.user_id), conn)?;
+        if let Some(email) = user.email {
+            mailers::events::cancelled(
+                user.first_name
+                    .as_ref()
+                    .map(|f| f.as_str())
+                    .unwrap_or("there"),
+                email,
+                event,
+                amount_refunded,
+                &self.config,
+                conn,
+            )?;
+        }
+        Ok(())
+    }
+}
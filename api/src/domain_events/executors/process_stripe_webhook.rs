@@ -0,0 +1,155 @@
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use db::Connection;
+use diesel::PgConnection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::{Debug, Error, Warn};
+use serde_json;
+use stripe::WebhookEvent;
+
+pub struct ProcessStripeWebhookExecutor {}
+
+impl DomainActionExecutor for ProcessStripeWebhookExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Stripe webhook processor failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessStripeWebhookExecutor {
+    pub fn new() -> ProcessStripeWebhookExecutor {
+        ProcessStripeWebhookExecutor {}
+    }
+
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let event: WebhookEvent = serde_json::from_value(action.payload.clone())?;
+        let connection = conn.get();
+
+        let payment = match event.charge_id() {
+            Some(charge_id) => Payment::find_by_external_reference(
+                PaymentProviders::Stripe,
+                &charge_id,
+                connection,
+            )?,
+            None => None,
+        };
+        let payment = match payment {
+            Some(payment) => payment,
+            None => {
+                jlog!(Debug, "Stripe webhook: No payment found, ignoring", {"event_id": event.id, "type": event.event_type});
+                return Ok(());
+            }
+        };
+
+        let object = &event.data.object;
+        let raw_data = json!(event);
+        match event.event_type.as_str() {
+            "charge.refunded" => {
+                // Refunds made through Big Neon have already been logged when they were requested
+                let amount_refunded = object["amount_refunded"].as_i64().unwrap_or(0);
+                let unlogged_amount = amount_refunded - payment.refunded_amount(connection)?;
+                if unlogged_amount > 0 {
+                    payment.log_provider_refund(unlogged_amount, raw_data.clone(), connection)?;
+                }
+
+                if object["refunded"].as_bool().unwrap_or(false) {
+                    payment.add_ipn(PaymentStatus::Refunded, raw_data, None, connection)?;
+                    Order::find(payment.order_id, connection)?
+                        .refund_all_tickets(None, connection)?;
+                } else {
+                    payment.add_ipn(payment.status, raw_data, None, connection)?;
+                }
+            }
+            "charge.dispute.created" => {
+                self.find_or_create_dispute(&payment, object, connection)?;
+                payment.add_ipn(PaymentStatus::Disputed, raw_data, None, connection)?;
+            }
+            "charge.dispute.updated" => {
+                let dispute = self.find_or_create_dispute(&payment, object, connection)?;
+                if !dispute.is_closed() {
+                    let status = match object["status"].as_str() {
+                        Some("under_review") | Some("warning_under_review") => {
+                            DisputeStatus::EvidenceSubmitted
+                        }
+                        _ => DisputeStatus::Open,
+                    };
+                    dispute.update_status(status, connection)?;
+                }
+            }
+            "charge.dispute.closed" => match object["status"].as_str() {
+                Some("lost") => {
+                    self.find_or_create_dispute(&payment, object, connection)?
+                        .update_status(DisputeStatus::Lost, connection)?;
+                    let remaining_amount = payment.amount - payment.refunded_amount(connection)?;
+                    let disputed_amount = object["amount"]
+                        .as_i64()
+                        .unwrap_or(remaining_amount)
+                        .min(remaining_amount);
+                    if disputed_amount > 0 {
+                        payment.log_provider_refund(
+                            disputed_amount,
+                            raw_data.clone(),
+                            connection,
+                        )?;
+                    }
+                    payment.add_ipn(PaymentStatus::Refunded, raw_data, None, connection)?;
+                    Order::find(payment.order_id, connection)?
+                        .refund_all_tickets(None, connection)?;
+                }
+                Some("won") | Some("warning_closed") => {
+                    self.find_or_create_dispute(&payment, object, connection)?
+                        .update_status(DisputeStatus::Won, connection)?;
+                    payment.add_ipn(PaymentStatus::Completed, raw_data, None, connection)?;
+                }
+                status => {
+                    jlog!(Warn, "Stripe webhook: Unexpected closed dispute status, ignoring", {"event_id": event.id, "status": status});
+                }
+            },
+            _ => {
+                jlog!(Debug, "Stripe webhook: Unhandled event type, ignoring", {"event_id": event.id, "type": event.event_type});
+            }
+        }
+
+        Ok(())
+    }
+
+    fn find_or_create_dispute(
+        &self,
+        payment: &Payment,
+        object: &serde_json::Value,
+        connection: &PgConnection,
+    ) -> Result<Dispute, BigNeonError> {
+        // Disputes are matched on the provider's id, a dispute without one cannot be tracked
+        let external_reference = object["id"].as_str().ok_or_else(|| {
+            ApplicationError::new("Stripe dispute webhook is missing the dispute id".to_string())
+        })?;
+        if let Some(dispute) = Dispute::find_by_external_reference(external_reference, connection)?
+        {
+            return Ok(dispute);
+        }
+
+        let evidence_due_by = object["evidence_details"]["due_by"]
+            .as_i64()
+            .and_then(|due_by| NaiveDateTime::from_timestamp_opt(due_by, 0));
+        Ok(Dispute::create(
+            payment,
+            external_reference.to_string(),
+            object["amount"].as_i64().unwrap_or(payment.amount),
+            object["reason"].as_str().map(|reason| reason.to_string()),
+            evidence_due_by,
+        )
+        .commit(connection)?)
+    }
+}
@@ -0,0 +1,61 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct ProcessWaitlistExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ProcessWaitlistExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process waitlist action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessWaitlistExecutor {
+    pub fn new(config: Config) -> ProcessWaitlistExecutor {
+        ProcessWaitlistExecutor { config }
+    }
+
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let ticket_type = TicketType::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No ticket type id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+        let event = Event::find(ticket_type.event_id, conn)?;
+
+        for waitlist_entry in WaitlistEntry::make_offers(ticket_type.id, conn)? {
+            let user = User::find(waitlist_entry.user_id, conn)?;
+            if let Some(email) = user.email {
+                mailers::waitlist::tickets_offered(
+                    email,
+                    &waitlist_entry,
+                    &ticket_type,
+                    &event,
+                    &self.config,
+                    conn,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
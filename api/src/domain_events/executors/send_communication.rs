@@ -3,6 +3,9 @@ use config::Config;
 use db::Connection;
 use domain_events::executor_future::ExecutorFuture;
 use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use serde_json;
 use utils::communication::Communication;
 
 pub struct SendCommunicationExecutor {
@@ -13,11 +16,32 @@ impl SendCommunicationExecutor {
     pub fn new(config: Config) -> SendCommunicationExecutor {
         SendCommunicationExecutor { config }
     }
+
+    /// Logged in the action's transaction, so the entries are rolled back if sending fails
+    fn log_communication(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<Vec<CommunicationLog>, BigNeonError> {
+        let communication: Communication = serde_json::from_value(action.payload.clone())?;
+        // Nothing is handed to a provider while external communications are blocked
+        let status = if self.config.block_external_comms {
+            CommunicationLogStatus::Blocked
+        } else {
+            CommunicationLogStatus::Sent
+        };
+        communication.create_logs(Some(action.id), status, conn.get())
+    }
 }
 
 impl DomainActionExecutor for SendCommunicationExecutor {
     fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
-        let future = Communication::send_async(&action, &self.config);
-        ExecutorFuture::new(action, conn, Box::new(future))
+        match self.log_communication(&action, &conn) {
+            Ok(logs) => {
+                let future = Communication::send_async(&action, &logs, &self.config, &conn);
+                ExecutorFuture::new(action, conn, Box::new(future))
+            }
+            Err(e) => ExecutorFuture::new(action, conn, Box::new(future::err(e))),
+        }
     }
 }
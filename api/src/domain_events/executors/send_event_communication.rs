@@ -0,0 +1,87 @@
+use bigneon_db::prelude::*;
+use chrono::NaiveDateTime;
+use communications::{mailers, pushers};
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+use serde_json;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+struct EventCommunicationPayload {
+    event_communication_schedule_id: Uuid,
+    send_at: NaiveDateTime,
+}
+
+pub struct SendEventCommunicationExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendEventCommunicationExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send event communication action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendEventCommunicationExecutor {
+    pub fn new(config: Config) -> SendEventCommunicationExecutor {
+        SendEventCommunicationExecutor { config }
+    }
+
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let event = Event::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No event id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+        let payload: EventCommunicationPayload = serde_json::from_value(action.payload.clone())?;
+        if event.cancelled_at.is_some() || event.status != EventStatus::Published {
+            return Ok(());
+        }
+
+        // The organization may have removed the schedule, or the event times may have moved
+        // since this was queued, in which case a replacement has been queued
+        let schedule =
+            match EventCommunicationSchedule::find_for_organization(event.organization_id, conn)?
+                .into_iter()
+                .find(|s| s.id == payload.event_communication_schedule_id)
+            {
+                Some(schedule) => schedule,
+                None => return Ok(()),
+            };
+        if schedule.send_at(&event) != Some(payload.send_at) {
+            return Ok(());
+        }
+
+        let user_ids = event.ticket_holder_ids(conn)?;
+        match schedule.channel {
+            CommunicationChannelType::Push => {
+                pushers::events::scheduled_communication(&event, &schedule, &user_ids, conn)?
+            }
+            _ => mailers::events::scheduled_communication(
+                &event,
+                &schedule,
+                &user_ids,
+                &self.config,
+                conn,
+            )?,
+        }
+        Ok(())
+    }
+}
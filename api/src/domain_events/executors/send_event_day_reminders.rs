@@ -0,0 +1,62 @@
+use bigneon_db::prelude::*;
+use chrono::NaiveDateTime;
+use communications::pushers;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+use serde_json;
+
+#[derive(Deserialize)]
+struct EventDayReminderPayload {
+    event_start: NaiveDateTime,
+}
+
+pub struct SendEventDayRemindersExecutor;
+
+impl DomainActionExecutor for SendEventDayRemindersExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send event day reminders action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendEventDayRemindersExecutor {
+    pub fn new() -> SendEventDayRemindersExecutor {
+        SendEventDayRemindersExecutor {}
+    }
+
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let event = Event::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No event id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+        let payload: EventDayReminderPayload = serde_json::from_value(action.payload.clone())?;
+
+        // Reminders queued before a cancellation or reschedule are stale, the reschedule
+        // queues its own reminder for the new start time
+        if event.cancelled_at.is_some()
+            || event.status != EventStatus::Published
+            || event.event_start != Some(payload.event_start)
+        {
+            return Ok(());
+        }
+
+        pushers::events::event_day_reminder(&event, &event.ticket_holder_ids(conn)?, conn)?;
+        Ok(())
+    }
+}
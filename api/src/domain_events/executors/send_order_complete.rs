@@ -1,5 +1,5 @@
 use bigneon_db::prelude::*;
-use communications::mailers;
+use communications::{mailers, pushers};
 use config::Config;
 use db::Connection;
 use domain_events::executor_future::ExecutorFuture;
@@ -92,15 +92,48 @@ impl SendOrderCompleteExecutor {
                 }
         }
 
+        // Resold tickets move on chain from the seller's wallet rather than the organization's
+        for oi in order.items(conn)? {
+            if oi.item_type != OrderItemTypes::Resale {
+                continue;
+            }
+            let resale_listing = match ResaleListing::find_for_order_item(oi.id, conn)? {
+                Some(r) => r,
+                None => continue,
+            };
+            let ticket = TicketInstance::find(resale_listing.ticket_instance_id, conn)?;
+            let asset = Asset::find(ticket.asset_id, conn)?;
+            let blockchain_asset_id = asset.blockchain_asset_id.ok_or(ApplicationError::new(
+                "Could not complete this checkout because the asset has not been assigned on the blockchain".to_string(),
+            ))?;
+            let seller_wallet =
+                Wallet::find_default_for_user(resale_listing.seller_user_id, conn)?;
+            tari_client.transfer_tokens(
+                &seller_wallet.secret_key,
+                &seller_wallet.public_key,
+                &blockchain_asset_id,
+                vec![ticket.token_id as u64],
+                new_owner_wallet.public_key.clone(),
+            )?
+        }
+
         let display_order = order.for_display(None, order.user_id, conn)?;
 
         let user = User::find(order.on_behalf_of_user_id.unwrap_or(order.user_id), conn)?;
 
         //Communicate purchase completed to user
         if let (Some(first_name), Some(email)) = (user.first_name, user.email) {
-            mailers::cart::purchase_completed(&first_name, email, display_order, &self.config)?
-                .queue(conn)?;
+            mailers::cart::purchase_completed(
+                &first_name,
+                email,
+                display_order,
+                mailers::branding::organization_id_for_order(&order, conn)?,
+                &self.config,
+                conn,
+            )?
+            .queue(conn)?;
         }
+        pushers::orders::purchase_completed(user.id, &order, conn)?;
         Ok(())
     }
 }
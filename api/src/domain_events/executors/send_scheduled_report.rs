@@ -0,0 +1,79 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use diesel::PgConnection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct SendScheduledReportExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendScheduledReportExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send scheduled report action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendScheduledReportExecutor {
+    pub fn new(config: Config) -> SendScheduledReportExecutor {
+        SendScheduledReportExecutor { config }
+    }
+
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let report_subscription = ReportSubscription::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No report subscription id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+
+        match self.send_report(&report_subscription, conn) {
+            // Queues the report for the next period
+            Ok(_) => {
+                report_subscription.record_delivery(conn)?;
+            }
+            Err(e) => {
+                if action.attempt_count + 1 < action.max_attempt_count {
+                    return Err(e);
+                }
+                // Out of attempts, the subscription still moves on to the next period
+                report_subscription.record_failed_delivery(&e.to_string(), conn)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn send_report(
+        &self,
+        report_subscription: &ReportSubscription,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        let organization = Organization::find(report_subscription.organization_id, conn)?;
+
+        let csv = report_subscription.render_csv(conn)?;
+        mailers::reports::scheduled_report(
+            &report_subscription,
+            &organization,
+            csv,
+            &self.config,
+            conn,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use bigneon_db::prelude::*;
+use bigneon_db::utils::network;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future::{self, FutureResult};
+use futures::Future;
+use hyper::client::connect::dns::{Name, Resolve};
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Request};
+use hyper_tls::HttpsConnector;
+use log::Level::Error;
+use native_tls::TlsConnector;
+use serde_json;
+use std::io;
+use std::net::IpAddr;
+use std::vec;
+use uuid::Uuid;
+
+pub struct SendWebhookExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendWebhookExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(delivery) => {
+                let (action_id, main_table_id) = (action.id, action.main_table_id);
+                let delivery = delivery.map_err(move |e| {
+                    jlog!(Error, "Send webhook action failed", {"action_id": action_id, "main_table_id": main_table_id, "error": e.to_string()});
+                    e
+                });
+                ExecutorFuture::new(action, conn, Box::new(delivery))
+            }
+            Err(e) => {
+                jlog!(Error, "Send webhook action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendWebhookExecutor {
+    pub fn new(config: Config) -> SendWebhookExecutor {
+        SendWebhookExecutor { config }
+    }
+
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<Box<Future<Item = (), Error = BigNeonError>>, BigNeonError> {
+        let conn = conn.get();
+        let webhook_subscription = WebhookSubscription::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No webhook subscription id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+        let domain_event_id: Uuid =
+            serde_json::from_value(action.payload["domain_event_id"].clone())?;
+        let domain_event = DomainEvent::find_by_id(domain_event_id, conn)?;
+
+        // Checked again on delivery as the host may since resolve to an internal address
+        let url = network::validate_public_https_url(&webhook_subscription.url)
+            .map_err(|e| ApplicationError::new(format!("Webhook URL refused: {}", e)))?;
+        let addresses = network::resolve_public_host(&url)
+            .map_err(|e| ApplicationError::new(format!("Webhook URL refused: {}", e)))?;
+
+        let body = serde_json::to_string(&webhook_subscription.payload_for(&domain_event))?;
+        let signature = webhook_subscription.sign(&body, &self.config.api_keys_encryption_key)?;
+        let request = Request::post(url.as_str())
+            .header("Content-Type", "application/json")
+            .header(
+                "X-BigNeon-Event",
+                domain_event.event_type.to_string().as_str(),
+            )
+            .header(
+                "X-BigNeon-Signature",
+                format!("sha256={}", signature).as_str(),
+            )
+            .body(Body::from(body))
+            .map_err(|e| ApplicationError::new(e.to_string()))?;
+
+        // The connection is made to the addresses checked above rather than looking the host up
+        // again. Redirects are not followed as they could point at an internal address.
+        let mut http = HttpConnector::new_with_resolver(CheckedAddresses(addresses));
+        http.enforce_http(false);
+        let tls = TlsConnector::new().map_err(|e| ApplicationError::new(e.to_string()))?;
+        let client: Client<_, Body> = Client::builder().build(HttpsConnector::from((http, tls)));
+
+        Ok(Box::new(
+            client
+                .request(request)
+                .map_err(|e| -> BigNeonError {
+                    ApplicationError::new(format!("Webhook request failed: {}", e)).into()
+                })
+                .and_then(|response| {
+                    // Any other response is treated as a failed attempt and retried
+                    if !response.status().is_success() {
+                        return Err(ApplicationError::new(format!(
+                            "Webhook endpoint responded with status {}",
+                            response.status()
+                        ))
+                        .into());
+                    }
+                    Ok(())
+                }),
+        ))
+    }
+}
+
+/// Resolves every host to the addresses that were checked before the request was made
+#[derive(Clone)]
+struct CheckedAddresses(Vec<IpAddr>);
+
+impl Resolve for CheckedAddresses {
+    type Addrs = vec::IntoIter<IpAddr>;
+    type Future = FutureResult<Self::Addrs, io::Error>;
+
+    fn resolve(&self, _name: Name) -> Self::Future {
+        future::ok(self.0.clone().into_iter())
+    }
+}
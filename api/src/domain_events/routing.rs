@@ -7,9 +7,16 @@ use domain_events::executor_future::ExecutorFuture;
 use domain_events::executors::marketing_contacts::{
     BulkEventFanListImportExecutor, CreateEventListExecutor,
 };
+use domain_events::executors::process_event_cancellation::ProcessEventCancellationExecutor;
 use domain_events::executors::process_payment_ipn::ProcessPaymentIPNExecutor;
+use domain_events::executors::process_stripe_webhook::ProcessStripeWebhookExecutor;
+use domain_events::executors::process_waitlist::ProcessWaitlistExecutor;
 use domain_events::executors::send_communication::SendCommunicationExecutor;
+use domain_events::executors::send_event_communication::SendEventCommunicationExecutor;
+use domain_events::executors::send_event_day_reminders::SendEventDayRemindersExecutor;
 use domain_events::executors::send_order_complete::SendOrderCompleteExecutor;
+use domain_events::executors::send_scheduled_report::SendScheduledReportExecutor;
+use domain_events::executors::send_webhook::SendWebhookExecutor;
 use std::borrow::Borrow;
 use std::collections::HashMap;
 
@@ -62,9 +69,16 @@ impl DomainActionRouter {
                 }
                 MarketingContactsCreateEventList => Box::new(CreateEventListExecutor::new(conf)),
                 PaymentProviderIPN => Box::new(ProcessPaymentIPNExecutor::new(&conf)),
+                ProcessEventCancellation => Box::new(ProcessEventCancellationExecutor::new(conf)),
+                ProcessStripeWebhook => Box::new(ProcessStripeWebhookExecutor::new()),
+                ProcessWaitlist => Box::new(ProcessWaitlistExecutor::new(conf)),
+                SendEventCommunication => Box::new(SendEventCommunicationExecutor::new(conf)),
+                SendEventDayReminders => Box::new(SendEventDayRemindersExecutor::new()),
                 SendPurchaseCompletedCommunication => {
                     Box::new(SendOrderCompleteExecutor::new(conf))
-                } //
+                }
+                SendScheduledReport => Box::new(SendScheduledReportExecutor::new(conf)),
+                SendWebhook => Box::new(SendWebhookExecutor::new(conf)), //
                   // DO NOT add
                   // _ =>
             }
@@ -88,10 +102,34 @@ impl DomainActionRouter {
         self.add_executor(PaymentProviderIPN, find_executor(PaymentProviderIPN))
             .expect("Configuration error");
 
+        self.add_executor(
+            ProcessEventCancellation,
+            find_executor(ProcessEventCancellation),
+        )
+        .expect("Configuration error");
+
+        self.add_executor(ProcessStripeWebhook, find_executor(ProcessStripeWebhook))
+            .expect("Configuration error");
+
+        self.add_executor(ProcessWaitlist, find_executor(ProcessWaitlist))
+            .expect("Configuration error");
+
+        self.add_executor(SendEventCommunication, find_executor(SendEventCommunication))
+            .expect("Configuration error");
+
+        self.add_executor(SendEventDayReminders, find_executor(SendEventDayReminders))
+            .expect("Configuration error");
+
         self.add_executor(
             SendPurchaseCompletedCommunication,
             find_executor(SendPurchaseCompletedCommunication),
         )
         .expect("Configuration error");
+
+        self.add_executor(SendScheduledReport, find_executor(SendScheduledReport))
+            .expect("Configuration error");
+
+        self.add_executor(SendWebhook, find_executor(SendWebhook))
+            .expect("Configuration error");
     }
 }
@@ -4,6 +4,8 @@ use std::fmt;
 #[derive(Debug)]
 pub enum AuthErrorType {
     Forbidden,
+    /// The password was correct but a two factor authentication code is still needed
+    TwoFactorRequired,
     Unauthorized,
 }
 
@@ -200,6 +200,9 @@ impl ConvertToWebError for AuthError {
 
         match self.error_type {
             AuthErrorType::Forbidden => forbidden(&self.reason),
+            AuthErrorType::TwoFactorRequired => HttpResponse::new(StatusCode::UNAUTHORIZED)
+                .into_builder()
+                .json(json!({"error": self.reason, "two_factor_required": true})),
             AuthErrorType::Unauthorized => unauthorized(&self.reason),
         }
     }
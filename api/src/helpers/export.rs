@@ -0,0 +1,113 @@
+use actix_web::HttpResponse;
+use bigneon_db::models::Exportable;
+use bigneon_db::utils::spreadsheets::{self, Worksheet};
+use diesel::PgConnection;
+use errors::*;
+use helpers::application;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Rows loaded per query when exporting results that are too large to load at once
+const EXPORT_PAGE_SIZE: u32 = 1000;
+const CSV_CONTENT_TYPE: &'static str = "text/csv; charset=utf-8";
+const XLSX_CONTENT_TYPE: &'static str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Xlsx,
+}
+
+impl FromStr for ExportFormat {
+    type Err = ApplicationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            "xlsx" => Ok(ExportFormat::Xlsx),
+            _ => Err(ApplicationError::new_with_type(
+                ApplicationErrorType::Unprocessable,
+                format!("Unsupported format {}", s),
+            )),
+        }
+    }
+}
+
+/// Responds with JSON unless a spreadsheet format was requested
+pub fn respond<T: Serialize + Exportable>(
+    format: Option<ExportFormat>,
+    file_name: &str,
+    data: &T,
+    conn: &PgConnection,
+) -> Result<HttpResponse, BigNeonError> {
+    match format.unwrap_or(ExportFormat::Json) {
+        ExportFormat::Json => Ok(HttpResponse::Ok().json(data)),
+        format => worksheets(format, file_name, &data.to_worksheets(conn)?),
+    }
+}
+
+/// Renders worksheets as a spreadsheet download, JSON responses are built by the caller
+pub fn worksheets(
+    format: ExportFormat,
+    file_name: &str,
+    worksheets: &[Worksheet],
+) -> Result<HttpResponse, BigNeonError> {
+    match format {
+        ExportFormat::Json => application::unprocessable("JSON is not a spreadsheet format"),
+        ExportFormat::Csv => Ok(HttpResponse::Ok()
+            .content_type(CSV_CONTENT_TYPE)
+            .header(
+                "Content-Disposition",
+                content_disposition(file_name, format),
+            )
+            .body(spreadsheets::to_csv(worksheets)?)),
+        ExportFormat::Xlsx => Ok(HttpResponse::Ok()
+            .content_type(XLSX_CONTENT_TYPE)
+            .header(
+                "Content-Disposition",
+                content_disposition(file_name, format),
+            )
+            .body(spreadsheets::to_xlsx(worksheets)?)),
+    }
+}
+
+/// Exports a single worksheet loaded a page at a time by `load_page(page, limit, connection)`.
+/// Every page is loaded before responding, the request's connection is released as soon as the
+/// handler returns so it cannot be used while the response is being sent.
+pub fn paged_worksheet<F>(
+    format: ExportFormat,
+    file_name: &str,
+    connection: &PgConnection,
+    mut load_page: F,
+) -> Result<HttpResponse, BigNeonError>
+where
+    F: FnMut(u32, u32, &PgConnection) -> Result<Worksheet, BigNeonError>,
+{
+    if format == ExportFormat::Json {
+        return application::unprocessable("JSON is not a spreadsheet format");
+    }
+
+    let mut worksheet = load_page(0, EXPORT_PAGE_SIZE, connection)?;
+    let mut finished = worksheet.rows.len() < EXPORT_PAGE_SIZE as usize;
+    let mut page = 1;
+    while !finished {
+        let next = load_page(page, EXPORT_PAGE_SIZE, connection)?;
+        finished = next.rows.len() < EXPORT_PAGE_SIZE as usize;
+        worksheet.rows.extend(next.rows);
+        page += 1;
+    }
+    worksheets(format, file_name, &[worksheet])
+}
+
+fn content_disposition(file_name: &str, format: ExportFormat) -> String {
+    let extension = match format {
+        ExportFormat::Json => "json",
+        ExportFormat::Csv => "csv",
+        ExportFormat::Xlsx => "xlsx",
+    };
+    format!("attachment; filename=\"{}.{}\"", file_name, extension)
+}
@@ -1 +1,2 @@
 pub mod application;
+pub mod export;
@@ -7,17 +7,21 @@
 #![deny(unused_must_use)]
 #![cfg_attr(not(debug_assertions), deny(unused_extern_crates))]
 extern crate actix_web;
+extern crate base64;
 extern crate bigneon_db;
 //extern crate bigneon_http;
 //#[macro_use]
 //extern crate bigneon_caching_derive;
 //#[macro_use]
 extern crate branch_rs;
+extern crate bytes;
 extern crate chrono;
 extern crate diesel;
 extern crate dotenv;
 extern crate futures;
 extern crate globee;
+extern crate hyper;
+extern crate hyper_tls;
 extern crate itertools;
 extern crate jsonwebtoken as jwt;
 #[macro_use]
@@ -29,6 +33,8 @@ extern crate log;
 #[macro_use]
 extern crate logging;
 
+extern crate native_tls;
+extern crate paypal;
 extern crate r2d2;
 extern crate regex;
 extern crate reqwest;
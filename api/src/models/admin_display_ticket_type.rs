@@ -20,6 +20,7 @@ pub struct AdminDisplayTicketType {
     pub price_in_cents: i64,
     pub sold_out_behavior: SoldOutBehavior,
     pub is_private: bool,
+    pub price_zone_id: Option<Uuid>,
 }
 
 impl AdminDisplayTicketType {
@@ -56,6 +57,7 @@ impl AdminDisplayTicketType {
             price_in_cents: ticket_type.price_in_cents,
             sold_out_behavior: ticket_type.sold_out_behavior,
             is_private: ticket_type.is_private,
+            price_zone_id: ticket_type.price_zone_id,
         })
     }
 }
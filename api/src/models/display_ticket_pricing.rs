@@ -14,6 +14,8 @@ pub struct DisplayTicketPricing {
     pub price_in_cents: i64,
     pub fee_in_cents: i64,
     pub discount_in_cents: i64,
+    pub quantity_limit: Option<i64>,
+    pub remaining_quantity: Option<i64>,
 }
 
 impl DisplayTicketPricing {
@@ -75,6 +77,8 @@ impl DisplayTicketPricing {
             price_in_cents: ticket_pricing.price_in_cents,
             fee_in_cents,
             discount_in_cents,
+            quantity_limit: ticket_pricing.quantity_limit,
+            remaining_quantity: ticket_pricing.remaining_quantity(conn)?,
         })
     }
 }
@@ -8,4 +8,6 @@ pub struct FacebookWebLoginToken {
     pub signed_request: String,
 
     pub reauthorize_required_in: u64,
+    #[serde(default)]
+    pub two_factor_code: Option<String>,
 }
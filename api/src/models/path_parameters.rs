@@ -39,6 +39,18 @@ pub struct OrganizationUserPathParameters {
     pub user_id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct OrganizationDisputePathParameters {
+    pub id: Uuid, // Organization Id
+    pub dispute_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct OrganizationEmailTemplatePathParameters {
+    pub id: Uuid, // Organization Id
+    pub template_kind: String,
+}
+
 #[derive(Deserialize)]
 pub struct OrganizationInvitePathParameters {
     pub id: Uuid, // Organization Id
@@ -18,6 +18,7 @@ pub struct UserDisplayTicketType {
     pub ticket_pricing: Option<DisplayTicketPricing>,
     pub redemption_code: Option<String>,
     pub event_id: Uuid,
+    pub price_zone_id: Option<Uuid>,
 }
 
 impl UserDisplayTicketType {
@@ -58,6 +59,7 @@ impl UserDisplayTicketType {
             redemption_code: None,
             increment: ticket_type.increment,
             limit_per_person: ticket_type.limit_per_person as u32,
+            price_zone_id: ticket_type.price_zone_id,
         };
 
         if let Some(ref redemption_code) = redemption_code {
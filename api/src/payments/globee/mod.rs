@@ -45,6 +45,7 @@ impl PaymentProcessor for GlobeePaymentProcessor {
         &self,
         _auth_token: &str,
         _amount: u32,
+        _idempotency_key: Option<&str>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError> {
         Err(PaymentProcessorError {
             description: "Refunds are not supported by this gateway".to_string(),
@@ -66,13 +67,14 @@ impl RedirectToPaymentPageBehavior for GlobeePaymentProcessorBehavior {
     fn create_payment_request(
         &self,
         amount: f64,
+        currency: &str,
         email: String,
         payment_id: Uuid,
         ipn_url: Option<String>,
         success_url: Option<String>,
         cancel_url: Option<String>,
     ) -> Result<RedirectInfo, PaymentProcessorError> {
-        let payment_request = PaymentRequest::new(
+        let mut payment_request = PaymentRequest::new(
             amount,
             email,
             Some(payment_id.to_string()),
@@ -80,6 +82,7 @@ impl RedirectToPaymentPageBehavior for GlobeePaymentProcessorBehavior {
             success_url,
             cancel_url,
         );
+        payment_request.currency = Some(currency.to_uppercase());
         let result = self.client.create_payment_request(payment_request)?;
         Ok(RedirectInfo {
             id: result.id,
@@ -87,6 +90,16 @@ impl RedirectToPaymentPageBehavior for GlobeePaymentProcessorBehavior {
             expires_at: result.expires_at,
         })
     }
+
+    fn complete_payment_request(
+        &self,
+        _id: &str,
+        _amount: i64,
+        _currency: &str,
+    ) -> Result<Option<ChargeResult>, PaymentProcessorError> {
+        // Globee confirms payments through its IPN
+        Ok(None)
+    }
 }
 
 impl From<GlobeeError> for PaymentProcessorError {
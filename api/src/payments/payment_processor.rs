@@ -17,16 +17,24 @@ pub trait AuthThenCompletePaymentBehavior {
         description: &str,
     ) -> Result<RepeatChargeToken, PaymentProcessorError>;
 
-    fn update_repeat_token(
+    /// Saves another card against the repeat token, existing cards remain usable
+    fn add_card_to_repeat_token(
         &self,
         repeat_token: &str,
         token: &str,
-        description: &str,
     ) -> Result<RepeatChargeToken, PaymentProcessorError>;
 
+    fn remove_card_from_repeat_token(
+        &self,
+        repeat_token: &str,
+        source_reference: &str,
+    ) -> Result<(), PaymentProcessorError>;
+
+    /// Authorizes a charge, `source_reference` selects a saved card when charging a repeat token
     fn auth(
         &self,
         token: &str,
+        source_reference: Option<&str>,
         amount: i64,
         currency: &str,
         description: &str,
@@ -44,6 +52,7 @@ pub trait RedirectToPaymentPageBehavior {
     fn create_payment_request(
         &self,
         total: f64,
+        currency: &str,
         email: String,
         order_id: Uuid,
         ipn_url: Option<String>,
@@ -51,16 +60,29 @@ pub trait RedirectToPaymentPageBehavior {
         success_url: Option<String>,
         cancel_url: Option<String>,
     ) -> Result<RedirectInfo, PaymentProcessorError>;
+
+    /// Called when the buyer is sent back from the payment page. Providers that confirm payments
+    /// through an IPN, or have not yet settled the payment, return `None`. Others take the payment
+    /// and return the charge once it has been made for the full `amount` in `currency`.
+    fn complete_payment_request(
+        &self,
+        id: &str,
+        amount: i64,
+        currency: &str,
+    ) -> Result<Option<ChargeResult>, PaymentProcessorError>;
 }
 
 pub trait PaymentProcessor {
     fn behavior(&self) -> PaymentProcessorBehavior;
     fn refund(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError>;
 
+    /// Refunds part of a payment. Providers supporting idempotent requests make a refund retried
+    /// with the same `idempotency_key` only once.
     fn partial_refund(
         &self,
         auth_token: &str,
         amount: u32,
+        idempotency_key: Option<&str>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError>;
 
     fn update_metadata(
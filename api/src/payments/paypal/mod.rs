@@ -0,0 +1,214 @@
+use bigneon_db::models::PaymentProviders;
+use chrono::prelude::*;
+use chrono::Duration;
+use itertools::Itertools;
+use payments::*;
+use paypal::*;
+use serde_json;
+use uuid::Uuid;
+
+/// PayPal order links are valid for three hours
+const APPROVAL_EXPIRY_HOURS: i64 = 3;
+/// PayPal limits purchase unit descriptions to 127 characters
+const MAX_DESCRIPTION_LENGTH: usize = 127;
+
+pub struct PaypalPaymentProcessor {
+    client: PaypalClient,
+}
+
+impl PaypalPaymentProcessor {
+    pub fn new(client_id: String, secret: String, base_url: String) -> PaypalPaymentProcessor {
+        PaypalPaymentProcessor {
+            client: PaypalClient::new(client_id, secret, base_url),
+        }
+    }
+
+    /// Refunds the capture of the order the payment was made with
+    fn refund_order(
+        &self,
+        external_reference: &str,
+        amount: Option<i64>,
+        idempotency_key: Option<&str>,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let order = self.client.get_order(order_id(external_reference))?;
+        let capture = match order.captures().into_iter().next() {
+            Some(capture) => capture.clone(),
+            None => {
+                return Err(PaymentProcessorError {
+                    description: "PayPal order has not been captured".to_string(),
+                    cause: None,
+                    validation_response: None,
+                });
+            }
+        };
+        let amount = match (amount, capture.amount) {
+            (Some(amount), Some(captured)) => {
+                Some(Amount::from_cents(amount, &captured.currency_code))
+            }
+            _ => None,
+        };
+        let refund = self
+            .client
+            .refund_capture(&capture.id, amount, idempotency_key)?;
+        Ok(ChargeAuthResult {
+            id: refund.id.clone(),
+            raw: serde_json::to_string(&refund).map_err(PaypalError::from)?,
+        })
+    }
+}
+
+impl PaymentProcessor for PaypalPaymentProcessor {
+    fn behavior(&self) -> PaymentProcessorBehavior {
+        PaymentProcessorBehavior::RedirectToPaymentPage(Box::new(PaypalPaymentProcessorBehavior {
+            client: self.client.clone(),
+        }))
+    }
+
+    fn refund(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        self.refund_order(auth_token, None, None)
+    }
+
+    fn partial_refund(
+        &self,
+        auth_token: &str,
+        amount: u32,
+        idempotency_key: Option<&str>,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        self.refund_order(auth_token, Some(amount as i64), idempotency_key)
+    }
+
+    fn update_metadata(
+        &self,
+        charge_id: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<UpdateMetadataResult, PaymentProcessorError> {
+        // PayPal orders have no metadata, the description shown to the buyer is used instead
+        let description: String = metadata
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .join(", ")
+            .chars()
+            .take(MAX_DESCRIPTION_LENGTH)
+            .collect();
+        let id = order_id(charge_id);
+        self.client.update_order_description(id, &description)?;
+        Ok(UpdateMetadataResult {
+            id: id.to_string(),
+            raw: json!({ "description": description }).to_string(),
+        })
+    }
+}
+
+pub struct PaypalPaymentProcessorBehavior {
+    client: PaypalClient,
+}
+
+impl RedirectToPaymentPageBehavior for PaypalPaymentProcessorBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::Paypal
+    }
+
+    fn create_payment_request(
+        &self,
+        amount: f64,
+        currency: &str,
+        _email: String,
+        order_id: Uuid,
+        _ipn_url: Option<String>,
+        success_url: Option<String>,
+        cancel_url: Option<String>,
+    ) -> Result<RedirectInfo, PaymentProcessorError> {
+        let (success_url, cancel_url) = match (success_url, cancel_url) {
+            (Some(success_url), Some(cancel_url)) => (success_url, cancel_url),
+            _ => {
+                return Err(PaymentProcessorError {
+                    description: "PayPal requires return and cancel urls".to_string(),
+                    cause: None,
+                    validation_response: None,
+                });
+            }
+        };
+        let request = CreateOrderRequest::new(
+            (amount * 100_f64).round() as i64,
+            currency,
+            order_id.to_string(),
+            success_url,
+            cancel_url,
+        );
+        let order = self.client.create_order(&request)?;
+        let redirect_url = match order.approve_url() {
+            Some(url) => url.to_string(),
+            None => {
+                return Err(PaypalError::unexpected_response("Order has no approve link").into());
+            }
+        };
+        Ok(RedirectInfo {
+            id: order.id,
+            redirect_url,
+            expires_at: Utc::now().naive_utc() + Duration::hours(APPROVAL_EXPIRY_HOURS),
+        })
+    }
+
+    fn complete_payment_request(
+        &self,
+        id: &str,
+        amount: i64,
+        currency: &str,
+    ) -> Result<Option<ChargeResult>, PaymentProcessorError> {
+        let order = self.client.capture_order(order_id(id))?;
+        let captures = order.captures();
+        if order.status != "COMPLETED" || captures.is_empty() {
+            return Err(capture_error(format!(
+                "PayPal order {} was not captured, status {}",
+                order.id, order.status
+            )));
+        }
+        match order.captured_amount(currency) {
+            Some(captured) if captured == amount => (),
+            captured => {
+                return Err(capture_error(format!(
+                    "PayPal order {} captured {:?} {} instead of {}",
+                    order.id, captured, currency, amount
+                )));
+            }
+        }
+        // Captures held for review settle later and stay pending until then
+        if captures.iter().any(|c| c.status == "PENDING") {
+            return Ok(None);
+        }
+        if let Some(capture) = captures.iter().find(|c| c.status != "COMPLETED") {
+            return Err(capture_error(format!(
+                "PayPal capture {} has status {}",
+                capture.id, capture.status
+            )));
+        }
+
+        Ok(Some(ChargeResult {
+            id: order.id.clone(),
+            raw: serde_json::to_string(&order).map_err(PaypalError::from)?,
+        }))
+    }
+}
+
+fn capture_error(description: String) -> PaymentProcessorError {
+    PaymentProcessorError {
+        description,
+        cause: None,
+        validation_response: None,
+    }
+}
+
+/// Payments keep the PayPal order id prefixed with the provider name as their external reference
+fn order_id(external_reference: &str) -> &str {
+    external_reference.trim_start_matches("paypal-")
+}
+
+impl From<PaypalError> for PaymentProcessorError {
+    fn from(p: PaypalError) -> Self {
+        PaymentProcessorError {
+            description: p.description.clone(),
+            cause: Some(Box::new(p)),
+            validation_response: None,
+        }
+    }
+}
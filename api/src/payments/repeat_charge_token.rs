@@ -1,5 +1,9 @@
+use bigneon_db::models::CardDetails;
+
 pub struct RepeatChargeToken {
     pub token: String,
+    /// The card saved against the token
+    pub card: Option<CardDetails>,
     pub raw: String,
 }
 
@@ -1,5 +1,6 @@
-use bigneon_db::models::PaymentProviders;
+use bigneon_db::models::{CardDetails, PaymentProviders};
 use payments::*;
+use stripe::Card;
 use stripe::StripeClient;
 use stripe::StripeError;
 
@@ -81,10 +82,11 @@ impl PaymentProcessor for StripePaymentProcessor {
         &self,
         auth_token: &str,
         amount: u32,
+        idempotency_key: Option<&str>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError> {
         Ok(self
             .client
-            .partial_refund(auth_token, amount)
+            .partial_refund(auth_token, amount, idempotency_key)
             .map(|r| ChargeAuthResult {
                 id: r.id,
                 raw: r.raw_data,
@@ -106,45 +108,62 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
             .create_customer(description, token, Vec::<(String, String)>::new())
             .map(|r| RepeatChargeToken {
                 token: r.id,
+                card: r.default_card.as_ref().map(card_details),
                 raw: r.raw_data,
             })?)
     }
 
-    fn update_repeat_token(
+    fn add_card_to_repeat_token(
         &self,
         repeat_token: &str,
         token: &str,
-        description: &str,
     ) -> Result<RepeatChargeToken, PaymentProcessorError> {
         Ok(self
             .client
-            .update_customer(
-                repeat_token,
-                description,
-                token,
-                Vec::<(String, String)>::new(),
-            )
+            .add_customer_source(repeat_token, token)
             .map(|r| RepeatChargeToken {
-                token: r.id,
+                token: repeat_token.to_string(),
+                card: Some(card_details(&r)),
                 raw: r.raw_data,
             })?)
     }
 
+    fn remove_card_from_repeat_token(
+        &self,
+        repeat_token: &str,
+        source_reference: &str,
+    ) -> Result<(), PaymentProcessorError> {
+        Ok(self
+            .client
+            .delete_customer_source(repeat_token, source_reference)?)
+    }
+
     fn auth(
         &self,
         token: &str,
+        source_reference: Option<&str>,
         amount: i64,
         currency: &str,
         description: &str,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError> {
-        Ok(self
-            .client
-            .auth(token, amount, currency, description, metadata)
-            .map(|r| ChargeAuthResult {
-                id: r.id,
-                raw: r.raw_data,
-            })?)
+        let result = match source_reference {
+            Some(source_reference) => self.client.auth_customer_source(
+                token,
+                source_reference,
+                amount,
+                currency,
+                description,
+                metadata,
+            ),
+            None => self
+                .client
+                .auth(token, amount, currency, description, metadata),
+        };
+        Ok(result.map(|r| ChargeAuthResult {
+            id: r.id,
+            raw: r.raw_data,
+        })?)
     }
 
     fn complete_authed_charge(
@@ -157,3 +176,13 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         })?)
     }
 }
+
+fn card_details(card: &Card) -> CardDetails {
+    CardDetails {
+        source_reference: card.id.clone(),
+        brand: card.brand.clone(),
+        last_four: card.last4.clone(),
+        exp_month: card.exp_month,
+        exp_year: card.exp_year,
+    }
+}
@@ -5,7 +5,10 @@ use server::AppState;
 
 pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     // Please try to keep in alphabetical order
-    app.resource("/artists/search", |r| {
+    app.resource("/add_on_vouchers", |r| {
+        r.method(Method::GET).with(add_ons::vouchers);
+    })
+    .resource("/artists/search", |r| {
         r.method(Method::GET).with(artists::search);
     })
     .resource("/artists/{id}/toggle_privacy", |r| {
@@ -23,28 +26,58 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/auth/token/refresh", |r| {
         r.method(Method::POST).with(auth::token_refresh)
     })
+    .resource("/bundles/{id}", |r| {
+        r.method(Method::GET).with(bundles::show);
+    })
     .resource("/cart", |r| {
         r.method(Method::DELETE).with(cart::destroy);
         r.method(Method::POST).with(cart::update_cart);
         r.method(Method::PUT).with(cart::replace_cart);
         r.method(Method::GET).with(cart::show);
     })
+    .resource("/cart/bundles", |r| {
+        r.method(Method::POST).with(cart::add_bundle);
+    })
     .resource("/cart/clear_invalid_items", |r| {
         r.method(Method::DELETE).with(cart::clear_invalid_items);
     })
     .resource("/cart/checkout", |r| {
         r.method(Method::POST).with(cart::checkout);
     })
+    .resource("/cart/resale_listings", |r| {
+        r.method(Method::POST).with(cart::add_resale_listing);
+    })
     .resource("/codes/{id}", |r| {
         r.method(Method::GET).with(codes::show);
         r.method(Method::PUT).with(codes::update);
         r.method(Method::DELETE).with(codes::destroy);
     })
+    .resource("/communications", |r| {
+        r.method(Method::GET).with(communications::index);
+    })
+    .resource("/communications/webhooks/sendgrid", |r| {
+        r.method(Method::POST).with(communications::sendgrid_webhook);
+    })
+    .resource("/communications/webhooks/twilio", |r| {
+        r.method(Method::POST).with(communications::twilio_webhook);
+    })
+    .resource("/communications/{id}/resend", |r| {
+        r.method(Method::POST).with(communications::resend);
+    })
     .resource("/comps/{id}", |r| {
         r.method(Method::GET).with(comps::show);
         r.method(Method::PATCH).with(comps::update);
         r.method(Method::DELETE).with(comps::destroy);
     })
+    .resource("/event_communication_schedules/{id}", |r| {
+        r.method(Method::DELETE).with(event_communication_schedules::destroy);
+    })
+    .resource("/event_reschedules/{id}/refund", |r| {
+        r.method(Method::POST).with(event_reschedules::refund);
+    })
+    .resource("/event_reschedules/{id}/report", |r| {
+        r.method(Method::GET).with(event_reschedules::report);
+    })
     .resource("/events", |r| {
         r.method(Method::GET).with(events::index);
         r.method(Method::POST).with(events::create);
@@ -61,6 +94,9 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(events::add_artist);
         r.method(Method::PUT).with(events::update_artists);
     })
+    .resource("/events/{id}/cancellation", |r| {
+        r.method(Method::GET).with(events::cancellation);
+    })
     .resource("/events/{id}/codes", |r| {
         r.method(Method::GET).with(events::codes);
         r.method(Method::POST).with(codes::create);
@@ -86,12 +122,29 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/events/{id}/publish", |r| {
         r.method(Method::POST).with(events::publish);
     })
+    .resource("/events/{id}/reschedules", |r| {
+        r.method(Method::GET).with(event_reschedules::index);
+        r.method(Method::POST).with(event_reschedules::create);
+    })
     .resource("/events/{id}/unpublish", |r| {
         r.method(Method::POST).with(events::unpublish);
     })
+    .resource("/events/{id}/resale_listings", |r| {
+        r.method(Method::GET).with(resale_listings::index);
+    })
+    .resource("/events/{id}/add_ons", |r| {
+        r.method(Method::GET).with(add_ons::index);
+        r.method(Method::POST).with(add_ons::create);
+    })
     .resource("/events/{id}/redeem/{ticket_instance_id}", |r| {
         r.method(Method::POST).with(events::redeem_ticket);
     })
+    .resource("/events/{id}/scanner_manifest", |r| {
+        r.method(Method::GET).with(events::scanner_manifest);
+    })
+    .resource("/events/{id}/scanner_sync", |r| {
+        r.method(Method::POST).with(events::scanner_sync);
+    })
     .resource("/events/{id}/tickets", |r| {
         r.method(Method::GET).with(tickets::index);
     })
@@ -99,6 +152,25 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(ticket_types::index);
         r.method(Method::POST).with(ticket_types::create);
     })
+    .resource(
+        "/events/{event_id}/ticket_types/{ticket_type_id}/price_zone",
+        |r| {
+            r.method(Method::PUT).with(ticket_types::update_price_zone);
+        },
+    )
+    .resource(
+        "/events/{event_id}/ticket_types/{ticket_type_id}/seats",
+        |r| {
+            r.method(Method::GET).with(ticket_types::seats);
+        },
+    )
+    .resource(
+        "/events/{event_id}/ticket_types/{ticket_type_id}/waitlist",
+        |r| {
+            r.method(Method::POST).with(ticket_types::join_waitlist);
+            r.method(Method::DELETE).with(ticket_types::leave_waitlist);
+        },
+    )
     .resource("/events/{event_id}/ticket_types/{ticket_type_id}", |r| {
         r.method(Method::PATCH).with(ticket_types::update);
         r.method(Method::DELETE).with(ticket_types::cancel);
@@ -129,6 +201,9 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/ipns/globee", |r| {
         r.method(Method::POST).with(ipns::globee);
     })
+    .resource("/ipns/stripe", |r| {
+        r.method(Method::POST).with(ipns::stripe);
+    })
     .resource("/holds/{id}/comps", |r| {
         r.method(Method::GET).with(comps::index);
         r.method(Method::POST).with(comps::create);
@@ -147,9 +222,18 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/orders/{id}/details", |r| {
         r.method(Method::GET).with(orders::details);
     })
+    .resource("/orders/{id}/exchange", |r| {
+        r.method(Method::POST).with(orders::exchange);
+    })
+    .resource("/orders/{id}/exchanges", |r| {
+        r.method(Method::GET).with(orders::exchanges);
+    })
     .resource("/orders/{id}/refund", |r| {
         r.method(Method::PATCH).with(orders::refund);
     })
+    .resource("/orders/{id}/refund_tickets", |r| {
+        r.method(Method::POST).with(orders::refund_tickets);
+    })
     .resource("/orders/{id}/tickets", |r| {
         r.method(Method::GET).with(orders::tickets);
     })
@@ -161,6 +245,30 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(artists::show_from_organizations);
         r.method(Method::POST).with(organizations::add_artist);
     })
+    .resource("/organizations/{id}/bundles", |r| {
+        r.method(Method::GET).with(bundles::index);
+        r.method(Method::POST).with(bundles::create);
+    })
+    .resource("/organizations/{id}/disputes/{dispute_id}", |r| {
+        r.method(Method::PUT).with(disputes::update);
+    })
+    .resource("/organizations/{id}/disputes", |r| {
+        r.method(Method::GET).with(disputes::index);
+    })
+    .resource("/organizations/{id}/email_templates", |r| {
+        r.method(Method::GET).with(organization_email_templates::index);
+    })
+    .resource("/organizations/{id}/email_templates/{template_kind}/sample_communication", |r| {
+        r.method(Method::GET).with(organization_email_templates::sample_communication);
+    })
+    .resource("/organizations/{id}/email_templates/{template_kind}", |r| {
+        r.method(Method::PUT).with(organization_email_templates::update);
+        r.method(Method::DELETE).with(organization_email_templates::destroy);
+    })
+    .resource("/organizations/{id}/event_communication_schedules", |r| {
+        r.method(Method::GET).with(event_communication_schedules::index);
+        r.method(Method::POST).with(event_communication_schedules::create);
+    })
     .resource("/organizations/{id}/events", |r| {
         r.method(Method::GET).with(events::show_from_organizations);
     })
@@ -191,6 +299,14 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(organization_invites::index);
         r.method(Method::POST).with(organization_invites::create);
     })
+    .resource("/organizations/{id}/report_subscriptions", |r| {
+        r.method(Method::GET).with(report_subscriptions::index);
+        r.method(Method::POST).with(report_subscriptions::create);
+    })
+    .resource("/organizations/{id}/tax_rates", |r| {
+        r.method(Method::GET).with(tax_rates::index);
+        r.method(Method::POST).with(tax_rates::create);
+    })
     .resource("/organizations/{id}/users", |r| {
         r.method(Method::POST)
             .with(organizations::add_or_replace_user);
@@ -206,6 +322,10 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(venues::show_from_organizations);
         r.method(Method::POST).with(organizations::add_venue);
     })
+    .resource("/organizations/{id}/webhooks", |r| {
+        r.method(Method::GET).with(webhooks::index);
+        r.method(Method::POST).with(webhooks::create);
+    })
     .resource("/organizations/{id}", |r| {
         r.method(Method::GET).with(organizations::show);
         r.method(Method::PATCH).with(organizations::update);
@@ -221,8 +341,15 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/payments/callback/{nonce}/{id}", |r| {
         r.method(Method::GET).with(payments::callback);
     })
+    .resource("/payment_methods/{id}/default", |r| {
+        r.method(Method::POST).with(payment_methods::set_default);
+    })
+    .resource("/payment_methods/{id}", |r| {
+        r.method(Method::DELETE).with(payment_methods::destroy);
+    })
     .resource("/payment_methods", |r| {
         r.method(Method::GET).with(payment_methods::index);
+        r.method(Method::POST).with(payment_methods::create);
     })
     .resource("/redemption_codes/{code}", |r| {
         r.method(Method::GET).with(redemption_codes::show)
@@ -235,10 +362,39 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(regions::index);
         r.method(Method::POST).with(regions::create)
     })
+    .resource("/report_subscriptions/{id}", |r| {
+        r.method(Method::DELETE).with(report_subscriptions::destroy);
+    })
     .resource("/reports/{id}", |r| {
         r.method(Method::GET).with(reports::get_report);
     })
+    .resource("/resale_listings/{id}", |r| {
+        r.method(Method::DELETE).with(resale_listings::destroy);
+    })
+    .resource("/resale_listings", |r| {
+        r.method(Method::POST).with(resale_listings::create);
+    })
+    .resource("/resale_payouts/{id}/paid", |r| {
+        r.method(Method::POST).with(resale_payouts::mark_paid);
+    })
+    .resource("/resale_payouts", |r| {
+        r.method(Method::GET).with(resale_payouts::index);
+    })
+    .resource("/seating_sections/{id}", |r| {
+        r.method(Method::DELETE)
+            .with(seat_maps::destroy_seating_section);
+    })
     .resource("/status", |r| r.method(Method::GET).with(status::check))
+    .resource("/stages/{id}/price_zones", |r| {
+        r.method(Method::POST).with(seat_maps::create_price_zone);
+    })
+    .resource("/stages/{id}/seat_map", |r| {
+        r.method(Method::GET).with(seat_maps::show);
+    })
+    .resource("/stages/{id}/seating_sections", |r| {
+        r.method(Method::POST)
+            .with(seat_maps::create_seating_section);
+    })
     .resource("/stages/{id}", |r| {
         r.method(Method::GET).with(stages::show);
         r.method(Method::PUT).with(stages::update);
@@ -249,6 +405,9 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         //            r.method(Method::PUT).with(stages::update);
         r.method(Method::DELETE).with(settlements::destroy);
     })
+    .resource("/tax_rates/{id}", |r| {
+        r.method(Method::DELETE).with(tax_rates::destroy);
+    })
     .resource("/tickets/transfer", |r| {
         r.method(Method::POST).with(tickets::transfer_authorization);
     })
@@ -272,6 +431,22 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(users::current_user);
         r.method(Method::PUT).with(users::update_current_user);
     })
+    .resource("/users/me/two_factor", |r| {
+        r.method(Method::POST)
+            .with(two_factor_authentication::create);
+    })
+    .resource("/users/me/two_factor/confirm", |r| {
+        r.method(Method::POST)
+            .with(two_factor_authentication::confirm);
+    })
+    .resource("/users/me/two_factor/disable", |r| {
+        r.method(Method::POST)
+            .with(two_factor_authentication::disable);
+    })
+    .resource("/users/me/two_factor/recovery_codes", |r| {
+        r.method(Method::POST)
+            .with(two_factor_authentication::regenerate_recovery_codes);
+    })
     .resource("/users/register", |r| {
         r.method(Method::POST).with(users::register)
     })
@@ -319,6 +494,12 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(venues::index);
         r.method(Method::POST).with(venues::create);
     })
+    .resource("/webhooks/{id}/deliveries", |r| {
+        r.method(Method::GET).with(webhooks::deliveries);
+    })
+    .resource("/webhooks/{id}", |r| {
+        r.method(Method::DELETE).with(webhooks::destroy);
+    })
     .register()
     .default_resource(|r| {
         r.method(Method::GET)
@@ -5,13 +5,19 @@ use diesel::PgConnection;
 use futures::Future;
 use tokio::prelude::*;
 
+use base64;
 use bigneon_db::models::enums::*;
 use bigneon_db::models::*;
 use config::{Config, Environment};
+use db::Connection;
 use errors::*;
 use futures::future::Either;
+use serde_json;
+use utils::push_notifications;
 use utils::sendgrid::mail as sendgrid;
 use utils::twilio;
+use utils::ServiceLocator;
+use uuid::Uuid;
 
 pub type TemplateData = HashMap<String, String>;
 
@@ -19,6 +25,7 @@ pub type TemplateData = HashMap<String, String>;
 pub enum CommunicationType {
     Email,
     EmailTemplate,
+    Push,
     Sms,
 }
 
@@ -63,15 +70,43 @@ impl CommAddress {
         self.addresses.push(address.clone());
     }
 }
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct CommAttachment {
+    pub filename: String,
+    pub content_type: String,
+    /// Base64 encoded file contents
+    pub content: String,
+}
+
+impl CommAttachment {
+    pub fn new(filename: String, content_type: String, content: &[u8]) -> CommAttachment {
+        CommAttachment {
+            filename,
+            content_type,
+            content: base64::encode(content),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Communication {
     pub comm_type: CommunicationType,
     pub title: String,
     pub body: Option<String>,
     pub source: Option<CommAddress>,
+    #[serde(default)]
+    pub reply_to: Option<CommAddress>,
     pub destinations: CommAddress,
     pub template_id: Option<String>,
     pub template_data: Option<Vec<TemplateData>>,
+    #[serde(default)]
+    pub attachments: Vec<CommAttachment>,
+    /// The record this communication is about, e.g. the order for a purchase receipt
+    #[serde(default)]
+    pub main_table: Option<Tables>,
+    #[serde(default)]
+    pub main_table_id: Option<Uuid>,
 }
 
 impl Communication {
@@ -89,24 +124,83 @@ impl Communication {
             title,
             body,
             source,
+            reply_to: None,
             destinations,
             template_id,
             template_data,
+            attachments: vec![],
+            main_table: None,
+            main_table_id: None,
         }
     }
 
+    /// Rebuilds the logged communication addressed only to the logged destination
+    pub fn from_log(log: &CommunicationLog) -> Result<Communication, BigNeonError> {
+        let mut communication: Communication = serde_json::from_value(log.payload.clone())?;
+        let index = communication
+            .destinations
+            .get()
+            .iter()
+            .position(|d| d == &log.destination)
+            .unwrap_or(0);
+        if let Some(template_data) = communication.template_data.take() {
+            communication.template_data = template_data.into_iter().nth(index).map(|d| vec![d]);
+        }
+        communication.destinations = CommAddress::from(log.destination.clone());
+        Ok(communication)
+    }
+
+    pub fn add_attachment(&mut self, attachment: CommAttachment) {
+        self.attachments.push(attachment);
+    }
+
+    pub fn set_main_table(&mut self, main_table: Tables, main_table_id: Uuid) {
+        self.main_table = Some(main_table);
+        self.main_table_id = Some(main_table_id);
+    }
+
+    pub fn channel(&self) -> CommunicationChannelType {
+        match self.comm_type {
+            CommunicationType::Email => CommunicationChannelType::Email,
+            CommunicationType::EmailTemplate => CommunicationChannelType::Email,
+            CommunicationType::Push => CommunicationChannelType::Push,
+            CommunicationType::Sms => CommunicationChannelType::Sms,
+        }
+    }
+
+    /// Records one log entry per destination, in the same order as the destinations
+    pub fn create_logs(
+        &self,
+        domain_action_id: Option<Uuid>,
+        status: CommunicationLogStatus,
+        conn: &PgConnection,
+    ) -> Result<Vec<CommunicationLog>, BigNeonError> {
+        let mut logs = Vec::new();
+        for destination in self.destinations.get() {
+            let mut log = CommunicationLog::create(
+                domain_action_id,
+                self.channel(),
+                self.template_id.clone(),
+                self.title.clone(),
+                destination,
+                self.main_table.map(|t| t.to_string()),
+                self.main_table_id,
+                json!(self),
+            );
+            log.status = status;
+            logs.push(log.commit(conn)?);
+        }
+        Ok(logs)
+    }
+
     pub fn queue(&self, connection: &PgConnection) -> Result<(), BigNeonError> {
         DomainAction::create(
             None,
             DomainActionTypes::Communication,
-            match self.comm_type {
-                CommunicationType::Email => Some(CommunicationChannelType::Email),
-                CommunicationType::EmailTemplate => Some(CommunicationChannelType::Email),
-                CommunicationType::Sms => Some(CommunicationChannelType::Sms),
-            },
+            Some(self.channel()),
             json!(self),
-            None,
-            None,
+            self.main_table.map(|t| t.to_string()),
+            self.main_table_id,
             Utc::now().naive_utc(),
             (Utc::now().naive_utc())
                 .checked_add_signed(Duration::days(1))
@@ -119,7 +213,9 @@ impl Communication {
 
     pub fn send_async(
         domain_action: &DomainAction,
+        logs: &[CommunicationLog],
         config: &Config,
+        conn: &Connection,
     ) -> impl Future<Item = (), Error = BigNeonError> {
         let communication: Communication =
             match serde_json::from_value(domain_action.payload.clone()) {
@@ -134,33 +230,53 @@ impl Communication {
                     true => Either::A(future::ok(())), //Disable communication system when block_external_comms is true,
                     _ => {
                         let destination_addresses = communication.destinations.get();
-                        let source_address =
-                            communication.source.as_ref().unwrap().get_first().unwrap();
-
+                        let log_ids: Vec<Uuid> = logs.iter().map(|l| l.id).collect();
                         let future = match communication.comm_type {
                             CommunicationType::Email => sendgrid::send_email_async(
                                 &config.sendgrid_api_key,
-                                source_address,
+                                communication.source.as_ref().unwrap().get_first().unwrap(),
                                 destination_addresses,
+                                &log_ids,
                                 communication.title.clone(),
                                 communication.body.clone(),
+                                &communication.attachments,
                             ),
                             CommunicationType::EmailTemplate => {
                                 sendgrid::send_email_template_async(
                                     &config.sendgrid_api_key,
-                                    source_address,
+                                    communication.source.as_ref().unwrap().get_first().unwrap(),
+                                    communication
+                                        .reply_to
+                                        .as_ref()
+                                        .and_then(|r| r.get_first().ok()),
                                     &destination_addresses,
+                                    &log_ids,
                                     communication.template_id.clone().unwrap(),
                                     communication.template_data.as_ref().unwrap(),
                                 )
                             }
-                            CommunicationType::Sms => twilio::send_sms_async(
-                                &config.twilio_account_id,
-                                &config.twilio_api_key,
-                                source_address,
-                                destination_addresses,
-                                &communication.body.unwrap_or(communication.title),
-                            ),
+                            CommunicationType::Sms => {
+                                Box::new(future::result(Communication::send_sms(
+                                    &communication,
+                                    communication.source.as_ref().unwrap().get_first().unwrap(),
+                                    &destination_addresses,
+                                    logs,
+                                    config,
+                                    conn.get(),
+                                )))
+                                    as Box<Future<Item = (), Error = BigNeonError>>
+                            }
+                            // Push destinations are user ids, delivered to each of their devices
+                            CommunicationType::Push => {
+                                Box::new(future::result(Communication::send_push(
+                                    &communication,
+                                    domain_action,
+                                    &destination_addresses,
+                                    config,
+                                    conn,
+                                )))
+                                    as Box<Future<Item = (), Error = BigNeonError>>
+                            }
                         };
                         Either::B(future)
                     }
@@ -169,4 +285,65 @@ impl Communication {
             }
         }
     }
+
+    /// Twilio returns each message's id as it is sent, delivery status callbacks refer to it
+    fn send_sms(
+        communication: &Communication,
+        source_address: String,
+        destination_addresses: &[String],
+        logs: &[CommunicationLog],
+        config: &Config,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        let message_ids = twilio::send_sms(
+            &config.twilio_account_id,
+            &config.twilio_api_key,
+            source_address,
+            destination_addresses,
+            communication.body.as_ref().unwrap_or(&communication.title),
+        )?;
+        for (log, message_id) in logs.iter().zip(message_ids.iter()) {
+            log.set_provider_message_id(message_id, conn)?;
+        }
+        Ok(())
+    }
+
+    /// Devices that fail are retried with the action, devices already notified for it are skipped
+    fn send_push(
+        communication: &Communication,
+        domain_action: &DomainAction,
+        destination_addresses: &[String],
+        config: &Config,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let user_ids = destination_addresses
+            .iter()
+            .map(|a| Uuid::parse_str(a))
+            .collect::<Result<Vec<Uuid>, _>>()?;
+        let notified_since = if domain_action.attempt_count > 0 {
+            Some(domain_action.created_at)
+        } else {
+            None
+        };
+        let notifier = ServiceLocator::new(config).create_push_notifier()?;
+        let failed_token_ids = push_notifications::send_to_users(
+            &*notifier,
+            &user_ids,
+            &communication.title,
+            communication.body.as_ref().unwrap_or(&communication.title),
+            notified_since,
+            conn.get(),
+        )?;
+        if !failed_token_ids.is_empty() {
+            // Keep the devices marked as notified when the action is rolled back for its retry
+            conn.commit_transaction()?;
+            conn.begin_transaction()?;
+            return Err(ApplicationError::new(format!(
+                "Push notification could not be sent to {} devices",
+                failed_token_ids.len()
+            ))
+            .into());
+        }
+        Ok(())
+    }
 }
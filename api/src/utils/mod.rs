@@ -4,6 +4,7 @@ pub mod communication;
 pub mod deep_linker;
 pub mod google_recaptcha;
 pub mod marketing_contacts;
+pub mod push_notifications;
 pub mod sendgrid;
 mod service_locator;
 pub mod spotify;
@@ -0,0 +1,149 @@
+use bigneon_db::models::PushNotificationToken;
+use chrono::NaiveDateTime;
+use diesel::PgConnection;
+use errors::*;
+use log::Level;
+use reqwest;
+use serde_json;
+use uuid::Uuid;
+
+// Provider errors returned for tokens that will never be deliverable again
+const INVALID_TOKEN_ERRORS: &[&str] = &["InvalidRegistration", "NotRegistered", "MismatchSenderId"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PushNotificationResult {
+    Delivered,
+    /// The provider no longer recognises the token, e.g. the app was uninstalled
+    InvalidToken,
+}
+
+pub trait PushNotifier {
+    fn send(
+        &self,
+        token: &PushNotificationToken,
+        title: &str,
+        body: &str,
+    ) -> Result<PushNotificationResult, BigNeonError>;
+}
+
+/// Delivers notifications through an FCM style HTTP API, which also relays to APNs for iOS tokens
+pub struct HttpPushNotifier {
+    api_url: String,
+    api_key: String,
+}
+
+impl HttpPushNotifier {
+    pub fn new(api_url: String, api_key: String) -> HttpPushNotifier {
+        HttpPushNotifier { api_url, api_key }
+    }
+}
+
+#[derive(Deserialize)]
+struct HttpPushResponse {
+    #[serde(default)]
+    results: Vec<HttpPushResult>,
+}
+
+#[derive(Deserialize)]
+struct HttpPushResult {
+    error: Option<String>,
+}
+
+impl PushNotifier for HttpPushNotifier {
+    fn send(
+        &self,
+        token: &PushNotificationToken,
+        title: &str,
+        body: &str,
+    ) -> Result<PushNotificationResult, BigNeonError> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(&self.api_url)
+            .header("Authorization", format!("key={}", self.api_key))
+            .json(&json!({
+                "to": token.token,
+                "notification": { "title": title, "body": body }
+            }))
+            .send()?
+            .text()?;
+        let response: HttpPushResponse = serde_json::from_str(&response)?;
+
+        match response.results.first().and_then(|r| r.error.as_ref()) {
+            Some(error) if INVALID_TOKEN_ERRORS.contains(&error.as_str()) => {
+                Ok(PushNotificationResult::InvalidToken)
+            }
+            Some(error) => Err(ApplicationError::new(format!(
+                "Push notification could not be sent: {}",
+                error
+            ))
+            .into()),
+            None => Ok(PushNotificationResult::Delivered),
+        }
+    }
+}
+
+/// Logs notifications instead of sending them, used when no push provider is configured
+pub struct LocalPushNotifier;
+
+impl PushNotifier for LocalPushNotifier {
+    fn send(
+        &self,
+        token: &PushNotificationToken,
+        title: &str,
+        body: &str,
+    ) -> Result<PushNotificationResult, BigNeonError> {
+        jlog!(Level::Info, "Push notification not sent, no provider configured", {
+            "push_notification_token_id": token.id,
+            "token_source": token.token_source,
+            "title": title,
+            "body": body
+        });
+        Ok(PushNotificationResult::Delivered)
+    }
+}
+
+/// Sends a notification to every device the users have registered, tokens rejected by the
+/// provider are removed so they are not tried again. Devices notified since `notified_since` are
+/// skipped, the ids of tokens that could not be sent to are returned so they can be retried.
+pub fn send_to_users(
+    notifier: &PushNotifier,
+    user_ids: &[Uuid],
+    title: &str,
+    body: &str,
+    notified_since: Option<NaiveDateTime>,
+    conn: &PgConnection,
+) -> Result<Vec<Uuid>, BigNeonError> {
+    let mut failed_token_ids = Vec::new();
+    for user_id in user_ids {
+        for token in PushNotificationToken::find_by_user_id(*user_id, conn)? {
+            if let (Some(notified_since), Some(last_notification_at)) =
+                (notified_since, token.last_notification_at)
+            {
+                if last_notification_at >= notified_since {
+                    continue;
+                }
+            }
+            match notifier.send(&token, title, body) {
+                Ok(PushNotificationResult::Delivered) => {
+                    token.mark_notified(conn)?;
+                }
+                Ok(PushNotificationResult::InvalidToken) => {
+                    jlog!(Level::Info, "Removing invalid push notification token", {
+                        "push_notification_token_id": token.id,
+                        "user_id": token.user_id
+                    });
+                    PushNotificationToken::remove(token.user_id, token.id, conn)?;
+                }
+                // A failing device should not stop the rest of the user's devices being notified
+                Err(e) => {
+                    jlog!(Level::Error, "Could not send push notification", {
+                        "push_notification_token_id": token.id,
+                        "error": e.to_string()
+                    });
+                    failed_token_ids.push(token.id);
+                }
+            }
+        }
+    }
+    Ok(failed_token_ids)
+}
@@ -6,6 +6,7 @@ use serde_json;
 use std::collections::HashMap;
 use tokio::prelude::*;
 use utils::communication::*;
+use uuid::Uuid;
 
 const SENDGRID_API_URL: &'static str = "https://api.sendgrid.com/v3/mail/send";
 
@@ -42,24 +43,31 @@ pub fn send_email_async(
     sg_api_key: &str,
     source_email_address: String,
     dest_email_addresses: Vec<String>,
+    communication_log_ids: &[Uuid],
     title: String,
     body: Option<String>,
+    attachments: &[CommAttachment],
 ) -> Box<Future<Item = (), Error = BigNeonError>> {
     let mut sg_message = SGMailMessage::new();
     sg_message.subject = Some(title);
     sg_message.from = SGEmail::from(source_email_address);
 
-    let mut msg_personalization = SGPersonalization::new();
-    for email_address in dest_email_addresses {
+    // Each recipient gets their own personalization so delivery events can be traced back to them
+    for (i, email_address) in dest_email_addresses.into_iter().enumerate() {
+        let mut msg_personalization = SGPersonalization::new();
         msg_personalization.to.push(SGEmail::from(email_address));
+        msg_personalization.add_communication_log_id(communication_log_ids.get(i));
+        sg_message.personalizations.push(msg_personalization);
     }
-    sg_message.personalizations.push(msg_personalization);
 
     let mut msg_content = SGContent::new();
     if let Some(body) = body {
         msg_content.value = body;
     }
     sg_message.content.push(msg_content);
+    for attachment in attachments {
+        sg_message.attachments.push(SGAttachment::from(attachment));
+    }
 
     Box::new(sg_message.send_async(sg_api_key))
 }
@@ -102,7 +110,9 @@ pub fn send_email_template(
 pub fn send_email_template_async(
     sg_api_key: &str,
     source_email_address: String,
+    reply_to_email_address: Option<String>,
     dest_email_addresses: &[String],
+    communication_log_ids: &[Uuid],
     template_id: String,
     template_data: &[TemplateData],
 ) -> Box<Future<Item = (), Error = BigNeonError>> {
@@ -116,6 +126,7 @@ pub fn send_email_template_async(
     } else {
         let mut sg_message = SGMailMessage::new();
         sg_message.from = SGEmail::from(source_email_address);
+        sg_message.reply_to = reply_to_email_address.map(SGEmail::from);
         sg_message.template_id = Some(template_id);
 
         for i in 0..dest_email_addresses.len() {
@@ -124,6 +135,7 @@ pub fn send_email_template_async(
                 .to
                 .push(SGEmail::from(dest_email_addresses[i].to_string()));
             msg_personalization.add_template_data(template_data[i].clone());
+            msg_personalization.add_communication_log_id(communication_log_ids.get(i));
             sg_message.personalizations.push(msg_personalization);
         }
 
@@ -177,6 +189,24 @@ impl SGContent {
     }
 }
 
+#[derive(Clone, Serialize)]
+pub struct SGAttachment {
+    pub content: String,
+    #[serde(rename = "type")]
+    pub content_type: String,
+    pub filename: String,
+}
+
+impl SGAttachment {
+    pub fn from(attachment: &CommAttachment) -> SGAttachment {
+        SGAttachment {
+            content: attachment.content.clone(),
+            content_type: attachment.content_type.clone(),
+            filename: attachment.filename.clone(),
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct SGPersonalization {
     pub to: Vec<SGEmail>,
@@ -184,6 +214,9 @@ pub struct SGPersonalization {
     pub subject: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dynamic_template_data: Option<TemplateData>,
+    /// Echoed back by SendGrid in event webhooks
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub custom_args: HashMap<String, String>,
 }
 
 impl SGPersonalization {
@@ -192,6 +225,16 @@ impl SGPersonalization {
             to: Vec::new(),
             subject: None,
             dynamic_template_data: None,
+            custom_args: HashMap::new(),
+        }
+    }
+
+    pub fn add_communication_log_id(&mut self, communication_log_id: Option<&Uuid>) {
+        if let Some(communication_log_id) = communication_log_id {
+            self.custom_args.insert(
+                "communication_log_id".to_string(),
+                communication_log_id.to_string(),
+            );
         }
     }
 
@@ -215,21 +258,27 @@ impl SGPersonalization {
 pub struct SGMailMessage {
     pub from: SGEmail,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<SGEmail>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub subject: Option<String>,
     pub content: Vec<SGContent>,
     pub personalizations: Vec<SGPersonalization>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub template_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<SGAttachment>,
 }
 
 impl SGMailMessage {
     pub fn new() -> SGMailMessage {
         SGMailMessage {
             from: SGEmail::new(),
+            reply_to: None,
             subject: None,
             content: Vec::new(),
             personalizations: Vec::new(),
             template_id: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -2,17 +2,24 @@ use bigneon_db::models::PaymentProviders;
 use config::Config;
 use errors::*;
 use payments::globee::GlobeePaymentProcessor;
+use payments::paypal::PaypalPaymentProcessor;
 use payments::stripe::StripePaymentProcessor;
 use payments::PaymentProcessor;
 use utils::deep_linker::BranchDeepLinker;
 use utils::deep_linker::DeepLinker;
+use utils::push_notifications::{HttpPushNotifier, LocalPushNotifier, PushNotifier};
 
 pub struct ServiceLocator {
     stripe_secret_key: String,
     globee_api_key: String,
     globee_base_url: String,
+    paypal_client_id: String,
+    paypal_secret: String,
+    paypal_base_url: String,
     branch_io_base_url: String,
     branch_io_branch_key: String,
+    push_notification_api_key: Option<String>,
+    push_notification_api_url: String,
 }
 
 impl ServiceLocator {
@@ -21,8 +28,13 @@ impl ServiceLocator {
             stripe_secret_key: config.stripe_secret_key.clone(),
             globee_api_key: config.globee_api_key.clone(),
             globee_base_url: config.globee_base_url.clone(),
+            paypal_client_id: config.paypal_client_id.clone(),
+            paypal_secret: config.paypal_secret.clone(),
+            paypal_base_url: config.paypal_base_url.clone(),
             branch_io_base_url: config.branch_io_base_url.clone(),
             branch_io_branch_key: config.branch_io_branch_key.clone(),
+            push_notification_api_key: config.push_notification_api_key.clone(),
+            push_notification_api_url: config.push_notification_api_url.clone(),
         }
     }
 
@@ -38,6 +50,11 @@ impl ServiceLocator {
                 self.globee_api_key.clone(),
                 self.globee_base_url.clone(),
             ))),
+            PaymentProviders::Paypal => Ok(Box::new(PaypalPaymentProcessor::new(
+                self.paypal_client_id.clone(),
+                self.paypal_secret.clone(),
+                self.paypal_base_url.clone(),
+            ))),
             // External is not valid for service locator
             PaymentProviders::External => {
                 return Err(ApplicationError::new("Unknown payment provider".into()).into());
@@ -52,10 +69,21 @@ impl ServiceLocator {
         )))
     }
 
+    pub fn create_push_notifier(&self) -> Result<Box<PushNotifier>, BigNeonError> {
+        match self.push_notification_api_key {
+            Some(ref api_key) => Ok(Box::new(HttpPushNotifier::new(
+                self.push_notification_api_url.clone(),
+                api_key.clone(),
+            ))),
+            None => Ok(Box::new(LocalPushNotifier)),
+        }
+    }
+
     pub fn is_refund_supported(provider: String) -> bool {
         match provider.to_lowercase().as_str() {
             "stripe" => true,
             "globee" => false,
+            "paypal" => true,
             "external" => false,
             _ => false,
         }
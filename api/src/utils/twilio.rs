@@ -12,14 +12,28 @@ pub fn send_sms_async(
     to: Vec<String>,
     body: &str,
 ) -> Box<Future<Item = (), Error = BigNeonError>> {
+    Box::new(future::result(
+        send_sms(account_id, api_key, from, &to, body).map(|_| ()),
+    ))
+}
+
+/// Sends the message to each destination, returning Twilio's message ids in the same order
+pub fn send_sms(
+    account_id: &str,
+    api_key: &str,
+    from: String,
+    to: &[String],
+    body: &str,
+) -> Result<Vec<String>, BigNeonError> {
     let client = twilio::Client::new(account_id, api_key);
+    let mut message_ids = Vec::new();
     for t in to.iter() {
         let message = OutboundMessage::new(&from, t, body);
         let message = match client.send_message(message) {
             Ok(m) => m,
             Err(e) => {
                 jlog!(Level::Error, "Could not send to Twilio", { "error": e });
-                return Box::new(future::err(e.into()));
+                return Err(e.into());
             }
         };
 
@@ -29,9 +43,10 @@ pub fn send_sms_async(
             "body": message.body.map(|b| b[..8].to_string()),
             "status": message.status
         });
+        message_ids.push(message.sid);
     }
 
-    return Box::new(future::ok(()));
+    Ok(message_ids)
 }
 
 impl From<TwilioError> for BigNeonError {
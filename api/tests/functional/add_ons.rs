@@ -0,0 +1,207 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::add_ons::{self, CreateAddOnRequest};
+use bigneon_api::controllers::cart;
+use bigneon_api::controllers::events::{self, TicketRedeemRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::{PathParameters, RedeemTicketPathParameters, RequestInfo};
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn create_request() -> CreateAddOnRequest {
+    CreateAddOnRequest {
+        name: "Parking".to_string(),
+        description: Some("One vehicle".to_string()),
+        price_in_cents: 1500,
+        quantity: 10,
+        redeemable: true,
+    }
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = add_ons::create((
+        database.connection.clone().into(),
+        path,
+        Json(create_request()),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let add_on: DisplayAddOn = serde_json::from_str(&body).unwrap();
+    assert_eq!(add_on.event_id, event.id);
+    assert_eq!(add_on.available, 10);
+    assert_eq!(
+        AddOn::find_for_event(event.id, connection).unwrap().len(),
+        1
+    );
+}
+
+#[test]
+fn create_unauthorized() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let auth_user = support::create_auth_user(Roles::OrgBoxOffice, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = add_ons::create((
+        database.connection.clone().into(),
+        path,
+        Json(create_request()),
+        auth_user,
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let creator = database.create_user().finish();
+    let event = database.create_event().finish();
+    AddOn::create(event.id, "Parking".to_string(), None, 1500, 10, true)
+        .commit(creator.id, connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = add_ons::index((database.connection.clone().into(), path)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found: Vec<DisplayAddOn> = serde_json::from_str(&body).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "Parking");
+}
+
+#[test]
+fn update_cart_with_add_ons() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let creator = database.create_user().finish();
+    let event = database.create_event().finish();
+    let add_on = AddOn::create(event.id, "Parking".to_string(), None, 1500, 10, true)
+        .commit(creator.id, connection)
+        .unwrap();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let input = Json(cart::UpdateCartRequest {
+        items: vec![],
+        box_office_pricing: None,
+        add_ons: vec![cart::CartAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 2,
+        }],
+    });
+    let response: HttpResponse = cart::update_cart((
+        database.connection.clone().into(),
+        input,
+        auth_user,
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    let items = cart.items(connection).unwrap();
+    let add_on_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::AddOn)
+        .unwrap();
+    assert_eq!(add_on_item.quantity, 2);
+    assert_eq!(add_on.available_quantity(connection).unwrap(), 8);
+}
+
+#[test]
+fn redeem_voucher() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let creator = database.create_user().finish();
+    let add_on = AddOn::create(event.id, "Parking".to_string(), None, 1500, 10, true)
+        .commit(creator.id, connection)
+        .unwrap();
+    let user = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_add_on_quantities(
+        user.id,
+        &[UpdateAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 1,
+        }],
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+    let voucher = AddOnVoucher::find_for_user(user.id, connection)
+        .unwrap()
+        .remove(0);
+    let auth_user = support::create_auth_user(Roles::DoorPerson, Some(&organization), &database);
+
+    // Vouchers are scanned with the ticket redemption endpoint
+    let test_request =
+        TestRequest::create_with_uri_custom_params("/", vec!["id", "ticket_instance_id"]);
+    let mut path = Path::<RedeemTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    path.ticket_instance_id = voucher.id;
+    let response: HttpResponse = events::redeem_ticket((
+        database.connection.clone().into(),
+        path,
+        Json(TicketRedeemRequest {
+            redeem_key: voucher.redeem_key.clone(),
+        }),
+        auth_user.clone(),
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let voucher = AddOnVoucher::find(voucher.id, connection).unwrap();
+    assert_eq!(voucher.status, AddOnVoucherStatus::Redeemed);
+
+    let mut path = Path::<RedeemTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    path.ticket_instance_id = voucher.id;
+    let response: HttpResponse = events::redeem_ticket((
+        database.connection.clone().into(),
+        path,
+        Json(TicketRedeemRequest {
+            redeem_key: voucher.redeem_key.clone(),
+        }),
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
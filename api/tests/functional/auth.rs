@@ -1,9 +1,12 @@
+use actix_web::error::ResponseError;
 use actix_web::{http::StatusCode, HttpResponse};
 use bigneon_api::auth::{claims::AccessToken, claims::RefreshToken, TokenResponse};
 use bigneon_api::controllers::auth;
 use bigneon_api::controllers::auth::{LoginRequest, RefreshRequest};
 use bigneon_api::extractors::*;
 use bigneon_api::models::*;
+use bigneon_db::models::UserTwoFactorCredential;
+use bigneon_db::utils::totp;
 use jwt::{decode, encode, Header, Validation};
 use serde_json;
 use support;
@@ -272,3 +275,173 @@ fn token_refreshed_after_password_change() {
     assert_eq!(response.refresh_token, refresh_token);
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
 }
+
+#[test]
+fn token_requires_two_factor_code() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    support::enable_two_factor(&user, &database);
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+
+    let response = auth::token((
+        test_request.request,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ));
+
+    let response: HttpResponse = response.err().unwrap().error_response();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(
+        body,
+        json!({"error": "Two factor authentication code required", "two_factor_required": true})
+            .to_string()
+    );
+}
+
+#[test]
+fn token_with_two_factor_code() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    let (secret, _) = support::enable_two_factor(&user, &database);
+    let credential =
+        UserTwoFactorCredential::find_enabled_for_user(user.id, database.connection.get())
+            .unwrap()
+            .unwrap();
+
+    // The code used when enabling two factor authentication has already been used
+    let used_code =
+        totp::code_for_timestamp(&secret, credential.last_used_step.unwrap() * 30).unwrap();
+    let test_request = TestRequest::create();
+    let json = Json(
+        LoginRequest::new("fake@localhost", "strong_password").with_two_factor_code(&used_code),
+    );
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ));
+    assert_eq!(
+        "Two factor authentication code is invalid",
+        response.err().unwrap().to_string()
+    );
+
+    let next_code =
+        totp::code_for_timestamp(&secret, (credential.last_used_step.unwrap() + 1) * 30).unwrap();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = Json(
+        LoginRequest::new("fake@localhost", "strong_password").with_two_factor_code(&next_code),
+    );
+    let response: TokenResponse = auth::token((
+        test_request.request,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .unwrap();
+
+    let access_token = decode::<AccessToken>(
+        &response.access_token,
+        state.config.token_secret.as_bytes(),
+        &Validation::default(),
+    )
+    .unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+}
+
+#[test]
+fn token_with_recovery_code() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    let (_, recovery_codes) = support::enable_two_factor(&user, &database);
+
+    let test_request = TestRequest::create();
+    let json = Json(
+        LoginRequest::new("fake@localhost", "strong_password")
+            .with_two_factor_code(&recovery_codes[0]),
+    );
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ));
+    assert!(response.is_ok());
+
+    // Recovery codes can only be used once
+    let test_request = TestRequest::create();
+    let json = Json(
+        LoginRequest::new("fake@localhost", "strong_password")
+            .with_two_factor_code(&recovery_codes[0]),
+    );
+    let response = auth::token((
+        test_request.request,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ));
+    assert!(response.is_err());
+}
+
+#[test]
+fn token_locks_two_factor_after_invalid_codes() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    let (_, recovery_codes) = support::enable_two_factor(&user, &database);
+
+    for _ in 0..5 {
+        let test_request = TestRequest::create();
+        let json = Json(
+            LoginRequest::new("fake@localhost", "strong_password")
+                .with_two_factor_code("not a code"),
+        );
+        let response = auth::token((
+            test_request.request,
+            database.connection.clone().into(),
+            json,
+            RequestInfo { user_agent: None },
+        ));
+        assert_eq!(
+            "Two factor authentication code is invalid",
+            response.err().unwrap().to_string()
+        );
+    }
+
+    // Valid codes are refused until the lock expires
+    let test_request = TestRequest::create();
+    let json = Json(
+        LoginRequest::new("fake@localhost", "strong_password")
+            .with_two_factor_code(&recovery_codes[0]),
+    );
+    let response = auth::token((
+        test_request.request,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ));
+    assert_eq!(
+        "Too many invalid two factor authentication codes, try again later",
+        response.err().unwrap().to_string()
+    );
+}
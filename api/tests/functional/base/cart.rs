@@ -38,10 +38,12 @@ pub fn update_box_office_pricing(role: Roles, should_test_succeed: bool) {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: Some(true),
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -120,6 +122,7 @@ pub fn replace_box_office_pricing(role: Roles, should_test_succeed: bool) {
             ticket_type_id: old_ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -139,10 +142,12 @@ pub fn replace_box_office_pricing(role: Roles, should_test_succeed: bool) {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: Some(true),
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -408,6 +408,7 @@ pub fn dashboard(role: Roles, should_test_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -764,6 +765,7 @@ pub fn expected_show_json(
         localized_times: EventLocalizedTimeStrings,
         tracking_keys: TrackingKeys,
         event_type: EventTypes,
+        currency: String,
     }
 
     let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, connection).unwrap();
@@ -839,6 +841,110 @@ pub fn expected_show_json(
             ..Default::default()
         },
         event_type: event.event_type,
+        currency: event.currency,
     })
     .unwrap()
 }
+
+pub fn scanner_manifest(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
+    let purchaser = database.create_user().finish();
+    database.create_purchased_tickets(&purchaser, ticket_type_id, 2);
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = events::scanner_manifest((
+        database.connection.clone().into(),
+        path,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let manifest: ScannerManifest = serde_json::from_str(&body).unwrap();
+        assert_eq!(manifest.event_id, event.id);
+        assert_eq!(manifest.tickets.len(), 2);
+        assert!(manifest.verify());
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
+pub fn scanner_sync(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
+    let purchaser = database.create_user().finish();
+    let tickets = database.create_purchased_tickets(&purchaser, ticket_type_id, 2);
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let scanned_at = Utc::now().naive_utc();
+    let json = Json(ScannerSyncRequest {
+        device_id: "door-1".to_string(),
+        redemptions: vec![
+            OfflineScan {
+                ticket_instance_id: Some(tickets[0].id),
+                add_on_voucher_id: None,
+                redeem_key: tickets[0].redeem_key.clone().unwrap(),
+                scanned_at,
+            },
+            OfflineScan {
+                ticket_instance_id: Some(tickets[1].id),
+                add_on_voucher_id: None,
+                redeem_key: "WrongKey".to_string(),
+                scanned_at,
+            },
+        ],
+    });
+    let response: HttpResponse = events::scanner_sync((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let result: ScannerSyncResult = serde_json::from_str(&body).unwrap();
+        assert_eq!(result.redeemed, vec![tickets[0].id]);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].ticket_instance_id, Some(tickets[1].id));
+        assert_eq!(
+            TicketInstance::find(tickets[0].id, connection)
+                .unwrap()
+                .status,
+            TicketInstanceStatus::Redeemed
+        );
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
@@ -114,6 +114,7 @@ pub fn details(role: Roles, should_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -164,6 +165,7 @@ pub fn details(role: Roles, should_succeed: bool) {
             description: format!("{} - {}", event.name, ticket_type.name),
             ticket_price_in_cents: order_item.unit_price_in_cents,
             fees_price_in_cents: fee_item.unit_price_in_cents,
+            tax_price_in_cents: 0,
             total_price_in_cents: order_item.unit_price_in_cents + fee_item.unit_price_in_cents,
             status: "Purchased".to_string(),
             refundable: true,
@@ -174,6 +176,7 @@ pub fn details(role: Roles, should_succeed: bool) {
             description: format!("{} - {}", event.name, ticket_type.name),
             ticket_price_in_cents: 0,
             fees_price_in_cents: 0,
+            tax_price_in_cents: 0,
             total_price_in_cents: 0,
             status: "Refunded".to_string(),
             refundable: false,
@@ -191,6 +194,7 @@ pub fn details(role: Roles, should_succeed: bool) {
         description: format!("Event Fees - {}", event.name),
         ticket_price_in_cents: 0,
         fees_price_in_cents: event_fee_item.unit_price_in_cents,
+        tax_price_in_cents: 0,
         total_price_in_cents: event_fee_item.unit_price_in_cents,
         status: "Purchased".to_string(),
         refundable: true,
@@ -240,6 +244,7 @@ pub fn refund(role: Roles, should_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -328,3 +333,194 @@ pub fn refund(role: Roles, should_succeed: bool) {
         support::expects_unauthorized(&response);
     }
 }
+
+pub fn refund_tickets(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let creator = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_event_fee()
+        .with_fee_schedule(&database.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+
+    let items = cart.items(&connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type.id))
+        .unwrap();
+    let event_fee_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::EventFees)
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+
+    // Refunding every ticket also refunds the event fee
+    let json = Json(RefundTicketsAttributes {
+        ticket_instance_ids: tickets.iter().map(|t| t.id).collect(),
+        fee_ticket_instance_ids: vec![],
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = cart.id;
+    let response: HttpResponse = orders::refund_tickets((
+        database.connection.clone(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+
+    if should_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let refund_response: RefundTicketsResponse = serde_json::from_str(&body).unwrap();
+        let expected_refund_amount = event_fee_item.unit_price_in_cents
+            + (order_item.unit_price_in_cents + fee_item.unit_price_in_cents) * 2;
+        assert_eq!(
+            refund_response.amount_refunded,
+            expected_refund_amount as u32
+        );
+        assert_eq!(refund_response.items.len(), 2);
+        assert_eq!(refund_response.payments.len(), 1);
+        assert_eq!(
+            refund_response.payments[0].payment_method,
+            PaymentMethods::External
+        );
+        assert_eq!(
+            refund_response.payments[0].amount_in_cents,
+            expected_refund_amount
+        );
+
+        let order_item = OrderItem::find_in_order(cart.id, order_item.id, connection).unwrap();
+        assert_eq!(order_item.refunded_quantity, 2);
+        let event_fee_item = OrderItem::find(event_fee_item.id, connection).unwrap();
+        assert_eq!(event_fee_item.refunded_quantity, 1);
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
+pub fn exchange(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let creator = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_event_fee()
+        .with_fee_schedule(&database.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_type_count(2)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_types[0].id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+
+    let order_item = cart
+        .items(&connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.ticket_type_id == Some(ticket_types[0].id))
+        .unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+
+    let json = Json(ExchangeTicketsAttributes {
+        ticket_instance_ids: vec![tickets[0].id],
+        ticket_type_id: ticket_types[1].id,
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = cart.id;
+    let response: HttpResponse = orders::exchange((
+        database.connection.clone(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+
+    if should_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let exchange_response: ExchangeTicketsResponse = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            exchange_response.exchange.exchanged_ticket_instance_ids,
+            vec![tickets[0].id]
+        );
+        assert_eq!(
+            exchange_response.exchange.ticket_type_id,
+            ticket_types[1].id
+        );
+        // Both ticket types share the same pricing so nothing is owed either way
+        assert_eq!(exchange_response.amount_charged, 0);
+        assert_eq!(exchange_response.amount_refunded, 0);
+
+        let ticket = TicketInstance::find(
+            exchange_response.exchange.ticket_instance_ids[0],
+            connection,
+        )
+        .unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+        let order = Order::find(cart.id, connection).unwrap();
+        assert_eq!(order.exchanges(connection).unwrap().len(), 1);
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
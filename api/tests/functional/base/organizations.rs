@@ -229,6 +229,11 @@ pub fn update(role: Roles, should_succeed: bool) {
         allowed_payment_providers: Some(vec![PaymentProviders::Globee]),
         timezone: Some("Los Angeles".to_string()),
         cc_fee_percent: Some(5.5),
+        max_resale_markup_percent: Some(Some(10.0)),
+        resale_fee_percent: Some(2.5),
+        currency: Some("eur".to_string()),
+        refund_fees_on_cancellation: Some(false),
+        two_factor_required: Some(true),
     });
 
     let response: HttpResponse = organizations::update((
@@ -252,6 +257,11 @@ pub fn update(role: Roles, should_succeed: bool) {
         vec![PaymentProviders::Globee]
     );
     assert_eq!(updated_organization.cc_fee_percent, 5.5);
+    assert_eq!(updated_organization.max_resale_markup_percent, Some(10.0));
+    assert_eq!(updated_organization.resale_fee_percent, 2.5);
+    assert_eq!(updated_organization.currency, "eur".to_string());
+    assert!(!updated_organization.refund_fees_on_cancellation);
+    assert!(updated_organization.two_factor_required);
 }
 
 pub fn remove_user(role: Roles, should_test_succeed: bool) {
@@ -0,0 +1,77 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::seat_maps;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::{DisplaySeatingSection, PriceZone, Roles};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create_price_zone(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let name = "Orchestra";
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(seat_maps::CreatePriceZoneRequest {
+        name: name.to_string(),
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = stage.id;
+    let response: HttpResponse =
+        seat_maps::create_price_zone((database.connection.into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let price_zone: PriceZone = serde_json::from_str(&body).unwrap();
+    assert_eq!(price_zone.name, name);
+    assert_eq!(price_zone.stage_id, stage.id);
+}
+
+pub fn create_seating_section(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let name = "Section A";
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(seat_maps::CreateSeatingSectionRequest {
+        name: name.to_string(),
+        rows: vec![
+            seat_maps::CreateSeatRowRequest {
+                name: "A".to_string(),
+                seat_count: 10,
+                price_zone_id: None,
+            },
+            seat_maps::CreateSeatRowRequest {
+                name: "B".to_string(),
+                seat_count: 12,
+                price_zone_id: None,
+            },
+        ],
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = stage.id;
+    let response: HttpResponse =
+        seat_maps::create_seating_section((database.connection.into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let seating_section: DisplaySeatingSection = serde_json::from_str(&body).unwrap();
+    assert_eq!(seating_section.name, name);
+    assert_eq!(seating_section.seats.len(), 22);
+}
@@ -36,6 +36,8 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         start_date,
         end_date: middle_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     ticket_pricing.push(CreateTicketPricingRequest {
         name: String::from("Base"),
@@ -43,6 +45,8 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         start_date: middle_date,
         end_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -113,6 +117,8 @@ pub fn update(role: Roles, should_test_succeed: bool) {
         end_date,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     request_ticket_pricing.push(UpdateTicketPricingRequest {
         id: None,
@@ -121,6 +127,8 @@ pub fn update(role: Roles, should_test_succeed: bool) {
         end_date: middle_date,
         price_in_cents: Some(15000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -168,6 +176,8 @@ pub fn update(role: Roles, should_test_succeed: bool) {
             end_date: Some(current_ticket_pricing.end_date),
             price_in_cents: Some(current_ticket_pricing.price_in_cents),
             is_box_office_only: Some(false),
+            quantity_limit: None,
+            rank: None,
         });
     }
     let updated_data = UpdateTicketTypeRequest {
@@ -56,6 +56,9 @@ pub fn show_other_user_ticket(role: Roles, should_test_succeed: bool) {
             status: TicketInstanceStatus::Purchased,
             redeem_key: ticket_response.ticket.redeem_key.clone(),
             pending_transfer: false,
+            section_name: None,
+            row_name: None,
+            seat_number: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -154,6 +157,7 @@ pub fn show_redeemable_ticket(role: Roles, should_test_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -37,6 +37,7 @@ pub fn profile(role: Roles, should_test_true: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -127,6 +128,7 @@ pub fn history(role: Roles, should_test_true: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
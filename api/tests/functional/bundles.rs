@@ -0,0 +1,152 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::bundles::{
+    self, CreateBundlePricingRequest, CreateBundleRequest, CreateBundleTicketTypeRequest,
+};
+use bigneon_api::controllers::cart;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn create_request(ticket_types: &[TicketType]) -> CreateBundleRequest {
+    CreateBundleRequest {
+        name: "Festival Pass".to_string(),
+        description: None,
+        quantity: 10,
+        ticket_types: ticket_types
+            .iter()
+            .map(|t| CreateBundleTicketTypeRequest {
+                ticket_type_id: t.id,
+                quantity: 1,
+            })
+            .collect(),
+        pricing: vec![CreateBundlePricingRequest {
+            name: "Early Bird".to_string(),
+            price_in_cents: 5000,
+            start_date: Utc::now().naive_utc() - Duration::days(1),
+            end_date: Utc::now().naive_utc() + Duration::days(2),
+        }],
+    }
+}
+
+fn festival_ticket_types(organization: &Organization, database: &TestDatabase) -> Vec<TicketType> {
+    let connection = database.connection.get();
+    (0..2)
+        .map(|_| {
+            database
+                .create_event()
+                .with_organization(organization)
+                .with_tickets()
+                .with_ticket_pricing()
+                .finish()
+                .ticket_types(true, None, connection)
+                .unwrap()
+                .remove(0)
+        })
+        .collect()
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let ticket_types = festival_ticket_types(&organization, &database);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(create_request(&ticket_types));
+    let response: HttpResponse =
+        bundles::create((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let bundle: DisplayBundle = serde_json::from_str(&body).unwrap();
+    assert_eq!(bundle.organization_id, organization.id);
+    assert_eq!(bundle.price_in_cents, Some(5000));
+    assert_eq!(bundle.available, 10);
+    assert_eq!(bundle.ticket_types.len(), 2);
+    assert_eq!(
+        Bundle::find_for_organization(organization.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn create_unauthorized() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let ticket_types = festival_ticket_types(&organization, &database);
+    let auth_user = support::create_auth_user(Roles::OrgBoxOffice, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(create_request(&ticket_types));
+    let response: HttpResponse =
+        bundles::create((database.connection.clone().into(), path, json, auth_user)).into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn add_to_cart() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let ticket_types = festival_ticket_types(&organization, &database);
+    let creator = database.create_user().finish();
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), None, 10)
+        .commit(creator.id, connection)
+        .unwrap();
+    for ticket_type in &ticket_types {
+        bundle
+            .add_ticket_type(ticket_type.id, 1, connection)
+            .unwrap();
+    }
+    bundle
+        .add_pricing(
+            "Early Bird".to_string(),
+            Utc::now().naive_utc() - Duration::days(1),
+            Utc::now().naive_utc() + Duration::days(2),
+            5000,
+            connection,
+        )
+        .unwrap();
+    let buyer = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&buyer, Roles::User, None, &database);
+
+    let json = Json(cart::AddBundleRequest {
+        bundle_id: bundle.id,
+        quantity: 2,
+    });
+    let response: HttpResponse =
+        cart::add_bundle((database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(buyer.id, connection)
+        .unwrap()
+        .unwrap();
+    let items = cart.items(connection).unwrap();
+    let bundle_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Bundle)
+        .unwrap();
+    assert_eq!(bundle_item.quantity, 2);
+    assert_eq!(bundle_item.unit_price_in_cents, 5000);
+    for ticket_type in &ticket_types {
+        let tickets_item = items
+            .iter()
+            .find(|i| i.ticket_type_id == Some(ticket_type.id))
+            .unwrap();
+        assert_eq!(tickets_item.parent_id, Some(bundle_item.id));
+        assert_eq!(tickets_item.quantity, 2);
+    }
+}
@@ -18,6 +18,7 @@ use globee::Customer;
 use globee::Email;
 use globee::GlobeeIpnRequest;
 use globee::PaymentDetails;
+use paypal::MockServer;
 use serde_json;
 use support::database::TestDatabase;
 use support::test_request::TestRequest;
@@ -132,6 +133,7 @@ fn destroy() {
             ticket_type_id: ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -185,10 +187,12 @@ fn update() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -247,8 +251,10 @@ fn update_with_draft_event() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         box_office_pricing: None,
+        add_ons: vec![],
     });
 
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
@@ -279,16 +285,19 @@ fn update_multiple() {
     let ticket_type_id2 = ticket_types[1].id;
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![
             cart::CartItem {
                 ticket_type_id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             cart::CartItem {
                 ticket_type_id: ticket_type_id2,
                 quantity: 3,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
     });
@@ -371,10 +380,12 @@ fn add_with_increment() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -435,10 +446,12 @@ fn update_with_increment_failure_invalid_quantity() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -477,10 +490,12 @@ fn update_with_existing_cart() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -534,6 +549,7 @@ fn reduce() {
             ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -563,10 +579,12 @@ fn reduce() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 6,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -621,6 +639,7 @@ fn remove() {
             ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -651,10 +670,12 @@ fn remove() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 0,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -705,6 +726,7 @@ fn remove_with_increment() {
             ticket_type_id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -735,10 +757,12 @@ fn remove_with_increment() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 8,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -801,6 +825,7 @@ fn remove_with_increment_failure_invalid_quantity() {
             ticket_type_id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -831,10 +856,12 @@ fn remove_with_increment_failure_invalid_quantity() {
 
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
+        add_ons: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -1011,6 +1038,7 @@ fn clear_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1068,6 +1096,7 @@ fn checkout_fails_for_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1249,3 +1278,113 @@ fn checkout_provider_globee() {
     let order = Order::find(order.id, conn).unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 }
+
+/// Checks out a cart with PayPal, approves the PayPal order and returns the buyer to the payment
+/// callback. The capture made on return is given `capture_status`.
+fn checkout_with_paypal(database: &TestDatabase, capture_status: &str) -> (Order, Payment) {
+    let conn = database.connection.get();
+    let server = MockServer::start();
+    let event = database
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    database
+        .create_cart()
+        .for_user(&user)
+        .for_event(&event)
+        .finish();
+    let base_url = server.base_url.clone();
+    let request = TestRequest::create_with_config("/", vec!["id"], vec![], |config| {
+        config.paypal_base_url = base_url
+    });
+
+    let input = Json(cart::CheckoutCartRequest {
+        method: PaymentRequest::Provider {
+            provider: PaymentProviders::Paypal,
+        },
+    });
+    let user = support::create_auth_user_from_user(&user, Roles::User, None, database);
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user.clone(),
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = unwrap_body_to_string(&response).unwrap();
+    let order: DisplayOrder = serde_json::from_str(body).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+
+    // Buyer approves the order on PayPal and is sent back to the callback
+    let db_payment = Order::find(order.id, conn)
+        .unwrap()
+        .payments(conn)
+        .unwrap()
+        .remove(0);
+    let external_reference = db_payment.external_reference.clone().unwrap();
+    assert!(external_reference.starts_with("paypal-"));
+    let paypal_order_id = external_reference.trim_start_matches("paypal-");
+    assert!(server.approve(paypal_order_id));
+    assert!(server.set_capture_status(paypal_order_id, capture_status));
+
+    let url = format!(
+        "/payments/callback/{}/{}?success=true",
+        db_payment.url_nonce.clone().unwrap(),
+        order.id
+    );
+    let base_url = server.base_url.clone();
+    let request = TestRequest::create_with_config(&url, vec!["nonce", "id"], vec![], |config| {
+        config.paypal_base_url = base_url
+    });
+    let query = Query::<controllers::payments::QueryParams>::extract(&request.request).unwrap();
+    let mut path = Path::<controllers::payments::PathParams>::extract(&request.request).unwrap();
+    path.nonce = db_payment.url_nonce.clone().unwrap();
+    path.id = order.id;
+    let response = controllers::payments::callback((
+        query,
+        path,
+        database.connection.clone().into(),
+        request.extract_state(),
+        OptionalUser(Some(user)),
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::FOUND);
+
+    (
+        Order::find(order.id, conn).unwrap(),
+        Payment::find(db_payment.id, conn).unwrap(),
+    )
+}
+
+#[test]
+fn checkout_provider_paypal() {
+    let database = TestDatabase::new();
+    let (order, payment) = checkout_with_paypal(&database, "COMPLETED");
+
+    // Payment is captured on return rather than waiting for an IPN
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+}
+
+#[test]
+fn checkout_provider_paypal_pending_capture() {
+    let database = TestDatabase::new();
+    let (order, payment) = checkout_with_paypal(&database, "PENDING");
+
+    // Tickets are not issued until PayPal settles the capture
+    assert_eq!(order.status, OrderStatus::PendingPayment);
+    assert_eq!(payment.status, PaymentStatus::PendingIpn);
+}
+
+#[test]
+fn checkout_provider_paypal_declined_capture() {
+    let database = TestDatabase::new();
+    let (order, payment) = checkout_with_paypal(&database, "DECLINED");
+
+    assert_ne!(order.status, OrderStatus::Paid);
+    assert_eq!(payment.status, PaymentStatus::Cancelled);
+}
@@ -0,0 +1,214 @@
+use actix_web::{http::StatusCode, Form, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::communications::{
+    self, SendgridEvent, TwilioStatusCallback, WebhookParameters,
+};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_api::utils::communication::{
+    CommAddress, Communication, CommunicationType, TemplateData,
+};
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+use uuid::Uuid;
+
+fn create_logs(database: &TestDatabase, order_id: Uuid) -> Vec<CommunicationLog> {
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        "Purchase Completed".to_string(),
+        None,
+        Some(CommAddress::from("noreply@example.com".to_string())),
+        CommAddress::from_vec(vec![
+            "fan1@example.com".to_string(),
+            "fan2@example.com".to_string(),
+        ]),
+        Some("d-template".to_string()),
+        Some(vec![TemplateData::new(), TemplateData::new()]),
+    );
+    communication.set_main_table(Tables::Orders, order_id);
+    communication
+        .create_logs(
+            None,
+            CommunicationLogStatus::Sent,
+            database.connection.get(),
+        )
+        .unwrap()
+}
+
+fn webhook_parameters(token: &str) -> Query<WebhookParameters> {
+    let test_request = TestRequest::create_with_uri(&format!("/?token={}", token));
+    Query::<WebhookParameters>::extract(&test_request.request).unwrap()
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let order_id = Uuid::new_v4();
+    let logs = create_logs(&database, order_id);
+    create_logs(&database, Uuid::new_v4());
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+
+    let test_request =
+        TestRequest::create_with_uri(&format!("/?q=fan1&main_table_id={}", order_id));
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response = communications::index((
+        database.connection.clone().into(),
+        query_parameters,
+        auth_user,
+    ))
+    .unwrap();
+    assert_eq!(response.payload().paging.total, 1);
+    assert_eq!(response.payload().data, vec![logs[0].clone()]);
+}
+
+#[test]
+fn index_requires_admin() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response: HttpResponse = communications::index((
+        database.connection.clone().into(),
+        query_parameters,
+        auth_user,
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn resend() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let order_id = Uuid::new_v4();
+    let logs = create_logs(&database, order_id);
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = logs[1].id;
+    let response: HttpResponse =
+        communications::resend((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let domain_actions =
+        DomainAction::find_pending(Some(DomainActionTypes::Communication), connection).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    assert_eq!(domain_actions[0].main_table_id, Some(order_id));
+    let communication: Communication =
+        serde_json::from_value(domain_actions[0].payload.clone()).unwrap();
+    assert_eq!(
+        communication.destinations.get(),
+        vec!["fan2@example.com".to_string()]
+    );
+    assert_eq!(communication.template_data.unwrap().len(), 1);
+}
+
+#[test]
+fn sendgrid_webhook() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let logs = create_logs(&database, Uuid::new_v4());
+
+    let events = vec![
+        SendgridEvent {
+            event: "processed".to_string(),
+            sg_message_id: Some("message1.filter".to_string()),
+            communication_log_id: Some(logs[0].id.to_string()),
+        },
+        SendgridEvent {
+            event: "open".to_string(),
+            sg_message_id: Some("message1.filter".to_string()),
+            communication_log_id: Some(logs[0].id.to_string()),
+        },
+        // Delivered arriving after opened does not move the status back
+        SendgridEvent {
+            event: "delivered".to_string(),
+            sg_message_id: Some("message1.filter".to_string()),
+            communication_log_id: Some(logs[0].id.to_string()),
+        },
+        // Unknown logs are skipped without failing the rest of the batch
+        SendgridEvent {
+            event: "delivered".to_string(),
+            sg_message_id: Some("message3.filter".to_string()),
+            communication_log_id: Some(Uuid::new_v4().to_string()),
+        },
+        SendgridEvent {
+            event: "bounce".to_string(),
+            sg_message_id: Some("message2.filter".to_string()),
+            communication_log_id: Some(logs[1].id.to_string()),
+        },
+    ];
+    let test_request = TestRequest::create();
+    let response: HttpResponse = communications::sendgrid_webhook((
+        database.connection.clone().into(),
+        webhook_parameters("test_communication_webhook_token"),
+        Json(events),
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let log = CommunicationLog::find(logs[0].id, connection).unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Opened);
+    assert_eq!(log.provider_message_id, Some("message1".to_string()));
+    let log = CommunicationLog::find(logs[1].id, connection).unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Bounced);
+}
+
+#[test]
+fn sendgrid_webhook_invalid_token() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let logs = create_logs(&database, Uuid::new_v4());
+
+    let events = vec![SendgridEvent {
+        event: "delivered".to_string(),
+        sg_message_id: None,
+        communication_log_id: Some(logs[0].id.to_string()),
+    }];
+    let test_request = TestRequest::create();
+    let response: HttpResponse = communications::sendgrid_webhook((
+        database.connection.clone().into(),
+        webhook_parameters("wrong"),
+        Json(events),
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let log = CommunicationLog::find(logs[0].id, connection).unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Sent);
+}
+
+#[test]
+fn twilio_webhook() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let logs = create_logs(&database, Uuid::new_v4());
+    logs[0]
+        .set_provider_message_id("SM123", connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = communications::twilio_webhook((
+        database.connection.clone().into(),
+        webhook_parameters("test_communication_webhook_token"),
+        Form(TwilioStatusCallback {
+            message_sid: "SM123".to_string(),
+            message_status: "delivered".to_string(),
+        }),
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let log = CommunicationLog::find(logs[0].id, connection).unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Delivered);
+    assert!(log.status_updated_at.is_some());
+}
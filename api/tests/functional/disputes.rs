@@ -0,0 +1,157 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Json, Path, Query};
+use bigneon_api::controllers::disputes::{self, DisputeFilterParameters, UpdateDisputeRequest};
+use bigneon_api::models::{OrganizationDisputePathParameters, PathParameters};
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn disputed_organization(database: &TestDatabase) -> (Organization, Dispute) {
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = database.create_order().for_event(&event).is_paid().finish();
+    let payment = Payment::find_by_order(order.id, "blah", connection).unwrap();
+    let dispute = Dispute::create(&payment, "dp_test".to_string(), payment.amount, None, None)
+        .commit(connection)
+        .unwrap();
+    (organization, dispute)
+}
+
+fn index(
+    database: &TestDatabase,
+    role: Roles,
+    organization: &Organization,
+    uri: &str,
+) -> HttpResponse {
+    let auth_user = support::create_auth_user(role, Some(organization), database);
+    let test_request = TestRequest::create_with_uri(uri);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let query = Query::<DisputeFilterParameters>::extract(&test_request.request).unwrap();
+    disputes::index((database.connection.clone().into(), path, query, auth_user)).into()
+}
+
+#[test]
+fn index_admin() {
+    let database = TestDatabase::new();
+    let (organization, dispute) = disputed_organization(&database);
+
+    let response = index(&database, Roles::Admin, &organization, "/");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_disputes: Vec<DisplayDispute> = serde_json::from_str(&body).unwrap();
+    assert_eq!(found_disputes.len(), 1);
+    assert_eq!(found_disputes[0].id, dispute.id);
+    assert_eq!(found_disputes[0].status, DisputeStatus::Open);
+}
+
+#[test]
+fn index_filtered_by_status() {
+    let database = TestDatabase::new();
+    let (organization, _) = disputed_organization(&database);
+
+    let response = index(&database, Roles::Admin, &organization, "/?status=Won");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_disputes: Vec<DisplayDispute> = serde_json::from_str(&body).unwrap();
+    assert!(found_disputes.is_empty());
+}
+
+#[test]
+fn index_unauthorized() {
+    let database = TestDatabase::new();
+    let (organization, _) = disputed_organization(&database);
+
+    let response = index(&database, Roles::OrgOwner, &organization, "/");
+    support::expects_unauthorized(&response);
+}
+
+fn update(
+    database: &TestDatabase,
+    role: Roles,
+    organization: &Organization,
+    dispute: &Dispute,
+    request: UpdateDisputeRequest,
+) -> HttpResponse {
+    let auth_user = support::create_auth_user(role, Some(organization), database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "dispute_id"]);
+    let mut path =
+        Path::<OrganizationDisputePathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.dispute_id = dispute.id;
+    disputes::update((
+        database.connection.clone().into(),
+        path,
+        Json(request),
+        auth_user,
+    ))
+    .into()
+}
+
+#[test]
+fn update_submits_evidence() {
+    let database = TestDatabase::new();
+    let (organization, dispute) = disputed_organization(&database);
+
+    let response = update(
+        &database,
+        Roles::Admin,
+        &organization,
+        &dispute,
+        UpdateDisputeRequest {
+            status: None,
+            evidence_notes: Some("Signed delivery receipt".to_string()),
+        },
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let updated_dispute: Dispute = serde_json::from_str(&body).unwrap();
+    assert_eq!(updated_dispute.status, DisputeStatus::EvidenceSubmitted);
+    assert_eq!(
+        updated_dispute.evidence_notes,
+        Some("Signed delivery receipt".to_string())
+    );
+}
+
+#[test]
+fn update_for_other_organization() {
+    let database = TestDatabase::new();
+    let (_, dispute) = disputed_organization(&database);
+    let other_organization = database.create_organization().finish();
+
+    let response = update(
+        &database,
+        Roles::Admin,
+        &other_organization,
+        &dispute,
+        UpdateDisputeRequest {
+            status: Some(DisputeStatus::Won),
+            evidence_notes: None,
+        },
+    );
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn update_unauthorized() {
+    let database = TestDatabase::new();
+    let (organization, dispute) = disputed_organization(&database);
+
+    let response = update(
+        &database,
+        Roles::OrgOwner,
+        &organization,
+        &dispute,
+        UpdateDisputeRequest {
+            status: Some(DisputeStatus::Won),
+            evidence_notes: None,
+        },
+    );
+    support::expects_unauthorized(&response);
+}
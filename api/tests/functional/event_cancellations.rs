@@ -0,0 +1,112 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::config::{Config, Environment};
+use bigneon_api::controllers::events;
+use bigneon_api::domain_events::executors::process_event_cancellation::ProcessEventCancellationExecutor;
+use bigneon_api::models::PathParameters;
+use bigneon_api::utils::communication::{CommAddress, Communication};
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn cancel_refunds_paid_orders() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let order = database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        events::cancel((database.connection.clone(), path, auth_user.clone())).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let domain_actions = DomainAction::find_pending(
+        Some(DomainActionTypes::ProcessEventCancellation),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    let executor = ProcessEventCancellationExecutor::new(Config::new(Environment::Test));
+    executor
+        .perform_job(&domain_actions[0], &database.connection.clone())
+        .unwrap();
+
+    let communications: Vec<Communication> =
+        DomainAction::find_pending(Some(DomainActionTypes::Communication), connection)
+            .unwrap()
+            .into_iter()
+            .map(|action| serde_json::from_value(action.payload).unwrap())
+            .filter(|communication: &Communication| communication.title.contains("cancelled"))
+            .collect();
+    assert_eq!(communications.len(), 1);
+    assert_eq!(
+        communications[0].destinations,
+        CommAddress::from(user.email.clone().unwrap())
+    );
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        events::cancellation((database.connection.clone(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let progress: EventCancellationProgress = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        progress.event_cancellation.status,
+        EventCancellationStatus::Completed
+    );
+    assert_eq!(progress.order_count, 1);
+    assert_eq!(progress.refunded_order_count, 1);
+    assert_eq!(progress.refunded_amount_in_cents, total);
+    assert_eq!(
+        Order::find(order.id, connection)
+            .unwrap()
+            .calculate_total(connection)
+            .unwrap(),
+        0
+    );
+}
+
+#[test]
+fn cancellation_unauthorized() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let event = event
+        .cancel(
+            database.create_user().finish().id,
+            database.connection.get(),
+        )
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::OrgBoxOffice, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        events::cancellation((database.connection.clone(), path, auth_user)).into();
+    support::expects_unauthorized(&response);
+}
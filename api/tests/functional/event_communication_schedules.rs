@@ -0,0 +1,252 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::config::{Config, Environment};
+use bigneon_api::controllers::event_communication_schedules::{
+    self, CreateEventCommunicationScheduleRequest,
+};
+use bigneon_api::domain_events::executors::send_event_communication::SendEventCommunicationExecutor;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_api::utils::communication::{CommAddress, Communication};
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel::PgConnection;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+use uuid::Uuid;
+
+fn create_schedule(organization_id: Uuid, connection: &PgConnection) -> EventCommunicationSchedule {
+    EventCommunicationSchedule::create(
+        organization_id,
+        EventCommunicationTimings::AfterEvent,
+        12,
+        CommunicationChannelType::Email,
+        "Thanks for coming to {event_name}".to_string(),
+        "Tell us how it went".to_string(),
+        None,
+    )
+    .commit(connection)
+    .unwrap()
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+    let schedule = create_schedule(organization.id, connection);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        event_communication_schedules::index((database.connection.clone().into(), path, auth_user))
+            .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_schedules: Vec<EventCommunicationSchedule> = serde_json::from_str(&body).unwrap();
+    assert_eq!(found_schedules, vec![schedule]);
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateEventCommunicationScheduleRequest {
+        timing: EventCommunicationTimings::BeforeDoors,
+        offset_hours: 24,
+        channel: CommunicationChannelType::Push,
+        title: "{event_name} is tomorrow".to_string(),
+        message: "Doors open {door_time}".to_string(),
+        template_id: None,
+    });
+    let response: HttpResponse = event_communication_schedules::create((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let schedule: EventCommunicationSchedule = serde_json::from_str(&body).unwrap();
+    assert_eq!(schedule.organization_id, organization.id);
+    assert_eq!(schedule.timing, EventCommunicationTimings::BeforeDoors);
+    assert_eq!(
+        EventCommunicationSchedule::find_for_organization(organization.id, connection).unwrap(),
+        vec![schedule]
+    );
+}
+
+#[test]
+fn create_requires_org_write() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateEventCommunicationScheduleRequest {
+        timing: EventCommunicationTimings::AfterEvent,
+        offset_hours: 2,
+        channel: CommunicationChannelType::Email,
+        title: "Thanks for coming".to_string(),
+        message: "Tell us how it went".to_string(),
+        template_id: None,
+    });
+    let response: HttpResponse = event_communication_schedules::create((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let schedule = create_schedule(organization.id, connection);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = schedule.id;
+    let response: HttpResponse = event_communication_schedules::destroy((
+        database.connection.clone().into(),
+        path,
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(EventCommunicationSchedule::find(schedule.id, connection).is_err());
+}
+
+#[test]
+fn send_event_communication() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let schedule = create_schedule(organization.id, connection);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(Utc::now().naive_utc() + Duration::days(2))
+        .with_ticket_pricing()
+        .finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let user2 = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user2)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let domain_action = event
+        .queue_scheduled_communication(&schedule, connection)
+        .unwrap()
+        .unwrap();
+
+    let executor = SendEventCommunicationExecutor::new(Config::new(Environment::Test));
+    executor
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+
+    // Ticket holders are sent one communication between them
+    let communications =
+        DomainAction::find_pending(Some(DomainActionTypes::Communication), connection).unwrap();
+    assert_eq!(communications.len(), 1);
+    assert_eq!(communications[0].main_table_id, Some(event.id));
+    let communication: Communication =
+        serde_json::from_value(communications[0].payload.clone()).unwrap();
+    let mut destinations = communication.destinations.get();
+    destinations.sort();
+    let mut expected_destinations = vec![user.email.clone().unwrap(), user2.email.clone().unwrap()];
+    expected_destinations.sort();
+    assert_eq!(destinations, expected_destinations);
+    assert_eq!(
+        communication.title,
+        format!("Thanks for coming to {}", event.name)
+    );
+    assert_eq!(communication.body, Some("Tell us how it went".to_string()));
+}
+
+#[test]
+fn send_event_communication_skips_stale_actions() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let schedule = create_schedule(organization.id, connection);
+    let event_start = Utc::now().naive_utc() + Duration::days(2);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(event_start)
+        .with_ticket_pricing()
+        .finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let domain_action = event
+        .queue_scheduled_communication(&schedule, connection)
+        .unwrap()
+        .unwrap();
+    let executor = SendEventCommunicationExecutor::new(Config::new(Environment::Test));
+
+    // The event has moved since the communication was queued
+    event
+        .update(
+            EventEditableAttributes {
+                event_start: Some(event_start + Duration::days(1)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    executor
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+
+    // The organization no longer sends this communication
+    schedule.destroy(connection).unwrap();
+    let event = Event::find(event.id, connection).unwrap();
+    let domain_action = event
+        .queue_scheduled_communication(&schedule, connection)
+        .unwrap()
+        .unwrap();
+    executor
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+
+    assert!(
+        DomainAction::find_pending(Some(DomainActionTypes::Communication), connection)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,245 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_reschedules::{self, *};
+use bigneon_api::extractors::Json;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn reschedule(
+    database: &TestDatabase,
+    role: Roles,
+    organization: &Organization,
+    event: &Event,
+) -> HttpResponse {
+    let auth_user = support::create_auth_user(role, Some(organization), database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(RescheduleEventRequest {
+        event_start: NaiveDate::from_ymd(2030, 1, 2).and_hms(20, 0, 0),
+        door_time: None,
+        event_end: None,
+        refund_deadline: Utc::now().naive_utc() + Duration::days(7),
+    });
+    event_reschedules::create((
+        database.connection.clone(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into()
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+
+    let response = reschedule(&database, Roles::OrgOwner, &organization, &event);
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let reschedule_response: RescheduleEventResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        reschedule_response.event.event_start,
+        Some(NaiveDate::from_ymd(2030, 1, 2).and_hms(20, 0, 0))
+    );
+    assert_eq!(
+        reschedule_response.event.override_status,
+        Some(EventOverrideStatus::Rescheduled)
+    );
+    assert_eq!(reschedule_response.reschedule.event_id, event.id);
+    assert_eq!(reschedule_response.ticket_holders_notified, 1);
+}
+
+#[test]
+fn create_unauthorized() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+
+    let response = reschedule(&database, Roles::OrgBoxOffice, &organization, &event);
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn refund() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let creator = database.create_user().finish();
+    let event = database
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let order = database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let (_, reschedule) = event
+        .reschedule(
+            NaiveDate::from_ymd(2030, 1, 2).and_hms(20, 0, 0),
+            None,
+            None,
+            Utc::now().naive_utc() + Duration::days(7),
+            creator.id,
+            connection,
+        )
+        .unwrap();
+    let total = order.calculate_total(connection).unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = reschedule.id;
+    let response: HttpResponse = event_reschedules::refund((
+        database.connection.clone(),
+        path,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let refund_response: RescheduleRefundResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(refund_response.amount_refunded, total as u32);
+    assert_eq!(refund_response.refunds.len(), 1);
+    assert_eq!(refund_response.refunds[0].order_id, order.id);
+    assert_eq!(refund_response.refunds[0].ticket_instance_ids.len(), 2);
+
+    let report = reschedule.report(connection).unwrap();
+    assert_eq!(report.len(), 1);
+    assert!(report[0].refunded);
+    assert_eq!(report[0].tickets_kept, 0);
+
+    // Nothing is left to refund
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = reschedule.id;
+    let response: HttpResponse = event_reschedules::refund((
+        database.connection.clone(),
+        path,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn refund_reports_failed_orders() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let creator = database.create_user().finish();
+    let event = database
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    event
+        .organization(connection)
+        .unwrap()
+        .update(
+            OrganizationEditableAttributes {
+                max_resale_markup_percent: Some(Some(10.0)),
+                resale_fee_percent: Some(5.0),
+                ..Default::default()
+            },
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let user = database.create_user().finish();
+    let order = database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let listed_order = database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    // Tickets listed for resale cannot be refunded
+    let listed_ticket_id = TicketInstance::find_ids_for_order(listed_order.id, connection)
+        .unwrap()
+        .remove(0);
+    let listed_order_item = OrderItem::find(
+        TicketInstance::find(listed_ticket_id, connection)
+            .unwrap()
+            .order_item_id
+            .unwrap(),
+        connection,
+    )
+    .unwrap();
+    ResaleListing::create(
+        listed_ticket_id,
+        user.id,
+        listed_order_item.unit_price_in_cents,
+    )
+    .commit(connection)
+    .unwrap();
+    let (_, reschedule) = event
+        .reschedule(
+            NaiveDate::from_ymd(2030, 1, 2).and_hms(20, 0, 0),
+            None,
+            None,
+            Utc::now().naive_utc() + Duration::days(7),
+            creator.id,
+            connection,
+        )
+        .unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = reschedule.id;
+    let response: HttpResponse = event_reschedules::refund((
+        database.connection.clone(),
+        path,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let refund_response: RescheduleRefundResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(refund_response.refunds.len(), 1);
+    assert_eq!(refund_response.refunds[0].order_id, order.id);
+    assert_eq!(refund_response.failed_order_ids, vec![listed_order.id]);
+
+    // The failed order is left as it was
+    let ticket = TicketInstance::find(listed_ticket_id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+    assert!(reschedule
+        .refunds(connection)
+        .unwrap()
+        .iter()
+        .all(|refund| refund.order_id == order.id));
+}
@@ -1246,6 +1246,88 @@ mod holds_tests {
     }
 }
 
+#[cfg(test)]
+mod scanner_manifest_tests {
+    use super::*;
+    #[test]
+    fn scanner_manifest_org_member() {
+        base::events::scanner_manifest(Roles::OrgMember, true);
+    }
+    #[test]
+    fn scanner_manifest_admin() {
+        base::events::scanner_manifest(Roles::Admin, true);
+    }
+    #[test]
+    fn scanner_manifest_user() {
+        base::events::scanner_manifest(Roles::User, false);
+    }
+    #[test]
+    fn scanner_manifest_org_owner() {
+        base::events::scanner_manifest(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn scanner_manifest_door_person() {
+        base::events::scanner_manifest(Roles::DoorPerson, true);
+    }
+    #[test]
+    fn scanner_manifest_promoter() {
+        base::events::scanner_manifest(Roles::Promoter, false);
+    }
+    #[test]
+    fn scanner_manifest_promoter_read_only() {
+        base::events::scanner_manifest(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn scanner_manifest_org_admin() {
+        base::events::scanner_manifest(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn scanner_manifest_box_office() {
+        base::events::scanner_manifest(Roles::OrgBoxOffice, true);
+    }
+}
+
+#[cfg(test)]
+mod scanner_sync_tests {
+    use super::*;
+    #[test]
+    fn scanner_sync_org_member() {
+        base::events::scanner_sync(Roles::OrgMember, true);
+    }
+    #[test]
+    fn scanner_sync_admin() {
+        base::events::scanner_sync(Roles::Admin, true);
+    }
+    #[test]
+    fn scanner_sync_user() {
+        base::events::scanner_sync(Roles::User, false);
+    }
+    #[test]
+    fn scanner_sync_org_owner() {
+        base::events::scanner_sync(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn scanner_sync_door_person() {
+        base::events::scanner_sync(Roles::DoorPerson, true);
+    }
+    #[test]
+    fn scanner_sync_promoter() {
+        base::events::scanner_sync(Roles::Promoter, false);
+    }
+    #[test]
+    fn scanner_sync_promoter_read_only() {
+        base::events::scanner_sync(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn scanner_sync_org_admin() {
+        base::events::scanner_sync(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn scanner_sync_box_office() {
+        base::events::scanner_sync(Roles::OrgBoxOffice, true);
+    }
+}
+
 #[test]
 fn update_promoter_fails_lacks_event_id() {
     let database = TestDatabase::new();
@@ -1318,6 +1400,7 @@ fn dashboard_with_default_range() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1488,6 +1571,47 @@ pub fn show_from_organizations_upcoming() {
     );
 }
 
+#[test]
+fn guest_list_csv() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_name("Guest Event".to_string())
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    database.create_order().for_event(&event).is_paid().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request =
+        TestRequest::create_with_uri(&format!("/events/{}/guest?query=&format=csv", event.id));
+    let query_parameters =
+        Query::<GuestListQueryParameters>::extract(&test_request.request).unwrap();
+    let mut path_parameters = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path_parameters.id = event.id;
+    let response: HttpResponse = events::guest_list((
+        database.connection.clone().into(),
+        query_parameters,
+        path_parameters,
+        auth_user,
+    ))
+    .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let lines: Vec<&str> = body.lines().collect();
+    assert!(lines[0].starts_with("Event,Event Start,Door Time,Ticket Type,"));
+    assert_eq!(lines.len(), 11);
+    assert!(lines[1..].iter().all(|l| l.starts_with("Guest Event,")));
+}
+
 #[derive(Serialize)]
 struct EventVenueEntry {
     id: Uuid,
@@ -0,0 +1,269 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::controllers::ipns;
+use bigneon_api::domain_events::executors::process_stripe_webhook::ProcessStripeWebhookExecutor;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use serde_json;
+use stripe;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn stripe_order(database: &TestDatabase) -> (Order, Payment) {
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let mut order = database.create_order().for_user(&user).quantity(2).finish();
+    let total = order.calculate_total(connection).unwrap();
+    let payment = order
+        .add_credit_card_payment(
+            user.id,
+            total,
+            PaymentProviders::Stripe,
+            "ch_test".to_string(),
+            PaymentStatus::Completed,
+            json!({}),
+            connection,
+        )
+        .unwrap();
+    (Order::find(order.id, connection).unwrap(), payment)
+}
+
+fn send_event(
+    database: &TestDatabase,
+    event_type: &str,
+    object: serde_json::Value,
+    secret: &str,
+) -> HttpResponse {
+    let body = json!({
+        "id": "evt_test",
+        "type": event_type,
+        "created": Utc::now().timestamp(),
+        "data": { "object": object }
+    })
+    .to_string();
+    let signature = stripe::signature_header(&body, secret, Utc::now().timestamp());
+    let test_request = TestRequest::create_with_headers(
+        "/ipns/stripe",
+        vec![],
+        vec![("Stripe-Signature", signature)],
+    );
+    ipns::stripe((
+        test_request.request,
+        body,
+        database.connection.clone().into(),
+    ))
+    .into()
+}
+
+fn process_events(database: &TestDatabase) {
+    let connection = database.connection.get();
+    for action in
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessStripeWebhook), connection)
+            .unwrap()
+    {
+        ProcessStripeWebhookExecutor::new()
+            .perform_job(&action, &database.connection.clone())
+            .unwrap();
+        action.set_done(connection).unwrap();
+    }
+}
+
+#[test]
+fn stripe_invalid_signature() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (_, payment) = stripe_order(&database);
+
+    let response = send_event(
+        &database,
+        "charge.refunded",
+        json!({"id": "ch_test", "object": "charge", "amount_refunded": payment.amount, "refunded": true}),
+        "not_the_secret",
+    );
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessStripeWebhook), connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn stripe_charge_refunded() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (order, payment) = stripe_order(&database);
+    assert_eq!(order.status, OrderStatus::Paid);
+
+    let response = send_event(
+        &database,
+        "charge.refunded",
+        json!({"id": "ch_test", "object": "charge", "amount_refunded": payment.amount, "refunded": true}),
+        "test_stripe_webhook_secret",
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+    let actions =
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessStripeWebhook), connection)
+            .unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].main_table_id, Some(payment.id));
+    process_events(&database);
+
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Refunded);
+    assert_eq!(payment.refunded_amount(connection).unwrap(), payment.amount);
+
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+    assert_eq!(ticket_ids.len(), 2);
+    assert_eq!(
+        RefundedTicket::find_by_ticket_instance_ids(ticket_ids.clone(), connection)
+            .unwrap()
+            .len(),
+        2
+    );
+    for ticket_id in ticket_ids {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+
+    // Receiving the event again does not log the refund twice
+    send_event(
+        &database,
+        "charge.refunded",
+        json!({"id": "ch_test", "object": "charge", "amount_refunded": payment.amount, "refunded": true}),
+        "test_stripe_webhook_secret",
+    );
+    process_events(&database);
+    assert_eq!(payment.refunded_amount(connection).unwrap(), payment.amount);
+}
+
+#[test]
+fn stripe_charge_partially_refunded() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (order, payment) = stripe_order(&database);
+
+    send_event(
+        &database,
+        "charge.refunded",
+        json!({"id": "ch_test", "object": "charge", "amount_refunded": 100, "refunded": false}),
+        "test_stripe_webhook_secret",
+    );
+    process_events(&database);
+
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.refunded_amount(connection).unwrap(), 100);
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, connection).unwrap() {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+    }
+}
+
+#[test]
+fn stripe_dispute_won() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (order, payment) = stripe_order(&database);
+
+    send_event(
+        &database,
+        "charge.dispute.created",
+        json!({"id": "dp_test", "object": "dispute", "charge": "ch_test", "amount": payment.amount, "status": "needs_response"}),
+        "test_stripe_webhook_secret",
+    );
+    process_events(&database);
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Disputed);
+    let dispute = Dispute::find_by_external_reference("dp_test", connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Open);
+    assert_eq!(dispute.held_ticket_instance_ids.len(), 2);
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, connection).unwrap() {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+
+    send_event(
+        &database,
+        "charge.dispute.closed",
+        json!({"id": "dp_test", "object": "dispute", "charge": "ch_test", "amount": payment.amount, "status": "won"}),
+        "test_stripe_webhook_secret",
+    );
+    process_events(&database);
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.refunded_amount(connection).unwrap(), 0);
+    let dispute = Dispute::find(dispute.id, connection).unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Won);
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, connection).unwrap() {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+    }
+}
+
+#[test]
+fn stripe_dispute_closed_with_unexpected_status() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (order, payment) = stripe_order(&database);
+
+    send_event(
+        &database,
+        "charge.dispute.created",
+        json!({"id": "dp_test", "object": "dispute", "charge": "ch_test", "amount": payment.amount, "status": "needs_response"}),
+        "test_stripe_webhook_secret",
+    );
+    process_events(&database);
+    send_event(
+        &database,
+        "charge.dispute.closed",
+        json!({"id": "dp_test", "object": "dispute", "charge": "ch_test", "amount": payment.amount, "status": "charge_refunded"}),
+        "test_stripe_webhook_secret",
+    );
+    process_events(&database);
+
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Disputed);
+    let dispute = Dispute::find_by_external_reference("dp_test", connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Open);
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, connection).unwrap() {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+}
+
+#[test]
+fn stripe_dispute_lost() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (order, payment) = stripe_order(&database);
+
+    send_event(
+        &database,
+        "charge.dispute.closed",
+        json!({"id": "dp_test", "object": "dispute", "charge": "ch_test", "amount": payment.amount, "status": "lost"}),
+        "test_stripe_webhook_secret",
+    );
+    process_events(&database);
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Refunded);
+    assert_eq!(payment.refunded_amount(connection).unwrap(), payment.amount);
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+    assert_eq!(
+        RefundedTicket::find_by_ticket_instance_ids(ticket_ids.clone(), connection)
+            .unwrap()
+            .len(),
+        2
+    );
+    for ticket_id in ticket_ids {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+    let dispute = Dispute::find_by_external_reference("dp_test", connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Lost);
+}
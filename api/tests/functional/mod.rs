@@ -1,21 +1,37 @@
+mod add_ons;
 mod artists;
 mod auth;
 mod base;
+mod bundles;
 mod cart;
 mod codes;
+mod communications;
 mod comps;
+mod disputes;
+mod event_cancellations;
+mod event_communication_schedules;
+mod event_reschedules;
 mod events;
 mod holds;
+mod ipns;
 mod orders;
+mod organization_email_templates;
 mod organization_invites;
 mod organizations;
 mod password_resets;
 mod payment_methods;
 mod redemption_codes;
 mod regions;
+mod report_subscriptions;
+mod resale_listings;
+mod resale_payouts;
+mod seat_maps;
 mod stages;
+mod tax_rates;
 mod ticket_types;
 mod tickets;
+mod two_factor_authentication;
 mod user_invites;
 mod users;
 mod venues;
+mod webhooks;
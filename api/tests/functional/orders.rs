@@ -277,6 +277,88 @@ mod refund_tests {
     }
 }
 
+#[cfg(test)]
+mod refund_tickets_tests {
+    use super::*;
+    #[test]
+    fn refund_tickets_org_member() {
+        base::orders::refund_tickets(Roles::OrgMember, true);
+    }
+    #[test]
+    fn refund_tickets_admin() {
+        base::orders::refund_tickets(Roles::Admin, true);
+    }
+    #[test]
+    fn refund_tickets_user() {
+        base::orders::refund_tickets(Roles::User, false);
+    }
+    #[test]
+    fn refund_tickets_org_owner() {
+        base::orders::refund_tickets(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn refund_tickets_door_person() {
+        base::orders::refund_tickets(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn refund_tickets_promoter() {
+        base::orders::refund_tickets(Roles::Promoter, false);
+    }
+    #[test]
+    fn refund_tickets_promoter_read_only() {
+        base::orders::refund_tickets(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn refund_tickets_org_admin() {
+        base::orders::refund_tickets(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn refund_tickets_box_office() {
+        base::orders::refund_tickets(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod exchange_tests {
+    use super::*;
+    #[test]
+    fn exchange_org_member() {
+        base::orders::exchange(Roles::OrgMember, true);
+    }
+    #[test]
+    fn exchange_admin() {
+        base::orders::exchange(Roles::Admin, true);
+    }
+    #[test]
+    fn exchange_user() {
+        base::orders::exchange(Roles::User, false);
+    }
+    #[test]
+    fn exchange_org_owner() {
+        base::orders::exchange(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn exchange_door_person() {
+        base::orders::exchange(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn exchange_promoter() {
+        base::orders::exchange(Roles::Promoter, false);
+    }
+    #[test]
+    fn exchange_promoter_read_only() {
+        base::orders::exchange(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn exchange_org_admin() {
+        base::orders::exchange(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn exchange_box_office() {
+        base::orders::exchange(Roles::OrgBoxOffice, true);
+    }
+}
+
 #[test]
 pub fn details_with_tickets_user_has_no_access_to() {
     let database = TestDatabase::new();
@@ -312,11 +394,13 @@ pub fn details_with_tickets_user_has_no_access_to() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -368,6 +452,7 @@ pub fn details_with_tickets_user_has_no_access_to() {
             description: format!("{} - {}", event.name, ticket_type.name),
             ticket_price_in_cents: order_item.unit_price_in_cents,
             fees_price_in_cents: fee_item.unit_price_in_cents,
+            tax_price_in_cents: 0,
             total_price_in_cents: order_item.unit_price_in_cents + fee_item.unit_price_in_cents,
             status: "Purchased".to_string(),
             refundable: true,
@@ -378,6 +463,7 @@ pub fn details_with_tickets_user_has_no_access_to() {
             description: format!("{} - {}", event.name, ticket_type.name),
             ticket_price_in_cents: 0,
             fees_price_in_cents: 0,
+            tax_price_in_cents: 0,
             total_price_in_cents: 0,
             status: "Refunded".to_string(),
             refundable: false,
@@ -395,6 +481,7 @@ pub fn details_with_tickets_user_has_no_access_to() {
         description: format!("Event Fees - {}", event.name),
         ticket_price_in_cents: 0,
         fees_price_in_cents: event_fee_item.unit_price_in_cents,
+        tax_price_in_cents: 0,
         total_price_in_cents: event_fee_item.unit_price_in_cents,
         status: "Purchased".to_string(),
         refundable: true,
@@ -442,6 +529,7 @@ pub fn refund_for_non_refundable_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -555,6 +643,7 @@ pub fn refund_hold_ticket() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(hold.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -622,6 +711,7 @@ pub fn refund_hold_ticket() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(hold.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -663,6 +753,7 @@ pub fn refund_removes_event_fee_if_all_event_tickets_refunded() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -0,0 +1,190 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::organization_email_templates;
+use bigneon_api::extractors::*;
+use bigneon_api::models::{OrganizationEmailTemplatePathParameters, PathParameters};
+use bigneon_api::utils::communication::Communication;
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn template_path(
+    organization: &Organization,
+    template_kind: &str,
+) -> Path<OrganizationEmailTemplatePathParameters> {
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "template_kind"]);
+    let mut path =
+        Path::<OrganizationEmailTemplatePathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.template_kind = template_kind.to_string();
+    path
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let template = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateKinds::Refund,
+        OrganizationEmailTemplateEditableAttributes {
+            footer_text: Some(Some("Thanks for coming".to_string())),
+            ..Default::default()
+        },
+    )
+    .commit(connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        organization_email_templates::index((database.connection.clone().into(), path, auth_user))
+            .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_templates: Vec<OrganizationEmailTemplate> = serde_json::from_str(&body).unwrap();
+    assert_eq!(found_templates, vec![template]);
+}
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    // First call creates the override
+    let json = Json(OrganizationEmailTemplateEditableAttributes {
+        template_id: Some(Some("d-custom".to_string())),
+        primary_color: Some(Some("#112233".to_string())),
+        ..Default::default()
+    });
+    let response: HttpResponse = organization_email_templates::update((
+        database.connection.clone().into(),
+        template_path(&organization, "PurchaseCompleted"),
+        json,
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Second call updates it
+    let json = Json(OrganizationEmailTemplateEditableAttributes {
+        primary_color: Some(Some("#445566".to_string())),
+        ..Default::default()
+    });
+    let response: HttpResponse = organization_email_templates::update((
+        database.connection.clone().into(),
+        template_path(&organization, "PurchaseCompleted"),
+        json,
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let template: OrganizationEmailTemplate = serde_json::from_str(&body).unwrap();
+    assert_eq!(template.template_id, Some("d-custom".to_string()));
+    assert_eq!(template.primary_color, Some("#445566".to_string()));
+    assert_eq!(
+        OrganizationEmailTemplate::find_for_organization(organization.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn update_requires_org_write() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let json = Json(OrganizationEmailTemplateEditableAttributes {
+        template_id: Some(Some("d-custom".to_string())),
+        ..Default::default()
+    });
+    let response: HttpResponse = organization_email_templates::update((
+        database.connection.clone().into(),
+        template_path(&organization, "Refund"),
+        json,
+        auth_user,
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateKinds::TransferTickets,
+        Default::default(),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let response: HttpResponse = organization_email_templates::destroy((
+        database.connection.clone().into(),
+        template_path(&organization, "TransferTickets"),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        OrganizationEmailTemplate::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn sample_communication() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateKinds::PurchaseCompleted,
+        OrganizationEmailTemplateEditableAttributes {
+            template_id: Some(Some("d-custom".to_string())),
+            logo_url: Some(Some("https://example.com/logo.png".to_string())),
+            reply_to_email: Some(Some("box-office@example.com".to_string())),
+            ..Default::default()
+        },
+    )
+    .commit(connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let response: HttpResponse = organization_email_templates::sample_communication((
+        database.connection.clone().into(),
+        template_path(&organization, "PurchaseCompleted"),
+        auth_user,
+        state,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let communication: Communication = serde_json::from_str(&body).unwrap();
+    assert_eq!(communication.template_id, Some("d-custom".to_string()));
+    assert_eq!(
+        communication.reply_to.unwrap().get_first().unwrap(),
+        "box-office@example.com".to_string()
+    );
+    let template_data = &communication.template_data.unwrap()[0];
+    assert_eq!(
+        template_data.get("logo_url"),
+        Some(&"https://example.com/logo.png".to_string())
+    );
+    assert!(template_data.contains_key("item_breakdown"));
+}
@@ -115,6 +115,34 @@ fn update() {
     assert_eq!(refresh_token.claims.get_id().unwrap(), user.id);
 }
 
+#[test]
+fn update_with_two_factor() {
+    let database = TestDatabase::new();
+    let connection_object: BigNeonConnection = database.connection.clone().into();
+
+    let user = database.create_user().finish();
+    support::enable_two_factor(&user, &database);
+    let user = user
+        .create_password_reset_token(database.connection.get())
+        .unwrap();
+    let new_password = "newPassword";
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let json = Json(UpdatePasswordResetParameters {
+        password_reset_token: user.password_reset_token.unwrap(),
+        password: new_password.to_string(),
+    });
+    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+
+    // The password is reset but the user still has to sign in with their code
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.check_password(&new_password));
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(serde_json::from_str::<TokenResponse>(&body).is_err());
+}
+
 #[test]
 fn update_expired_token() {
     use bigneon_db::schema::users::dsl::*;
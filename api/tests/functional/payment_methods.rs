@@ -1,9 +1,13 @@
-use actix_web::{http::StatusCode, HttpResponse};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::controllers::payment_methods;
-use bigneon_db::models::{DisplayPaymentMethod, PaymentProviders, Roles};
+use bigneon_api::controllers::payment_methods::CreatePaymentMethodRequest;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::{DisplayPaymentMethod, PaymentMethod, PaymentProviders, Roles};
 use serde_json;
 use support;
 use support::database::TestDatabase;
+use support::test_request::TestRequest;
 
 #[test]
 fn index() {
@@ -31,3 +35,94 @@ fn index() {
     let body = support::unwrap_body_to_string(&response).unwrap();
     assert_eq!(body, payment_methods_expected_json);
 }
+
+#[test]
+fn create_unsupported_provider() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let json = Json(CreatePaymentMethodRequest {
+        provider: PaymentProviders::Globee,
+        token: "tok_visa".to_string(),
+        set_default: true,
+    });
+    let response: HttpResponse = payment_methods::create((
+        database.connection.clone().into(),
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn set_default() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let payment_method = database
+        .create_payment_method()
+        .with_user(&user)
+        .make_default()
+        .finish();
+    let payment_method2 = database.create_payment_method().with_user(&user).finish();
+
+    // Only the owner can change their default
+    let other_user = support::create_auth_user(Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = payment_method2.id;
+    let response: HttpResponse =
+        payment_methods::set_default((database.connection.clone().into(), path, other_user)).into();
+    support::expects_unauthorized(&response);
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = payment_method2.id;
+    let response: HttpResponse =
+        payment_methods::set_default((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_payment_method: DisplayPaymentMethod = serde_json::from_str(&body).unwrap();
+    assert!(display_payment_method.is_default);
+    assert!(
+        !PaymentMethod::find(payment_method.id, connection)
+            .unwrap()
+            .is_default
+    );
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let payment_method = database.create_payment_method().with_user(&user).finish();
+
+    let other_user = support::create_auth_user(Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = payment_method.id;
+    let response: HttpResponse = payment_methods::destroy((
+        database.connection.clone().into(),
+        path,
+        other_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = payment_method.id;
+    let response: HttpResponse = payment_methods::destroy((
+        database.connection.clone().into(),
+        path,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(PaymentMethod::find(payment_method.id, connection).is_err());
+}
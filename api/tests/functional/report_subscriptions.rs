@@ -0,0 +1,196 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::config::{Config, Environment};
+use bigneon_api::controllers::report_subscriptions::{self, CreateReportSubscriptionRequest};
+use bigneon_api::domain_events::executors::send_scheduled_report::SendScheduledReportExecutor;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_api::utils::communication::{CommAddress, Communication};
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::TicketCount,
+        ReportFrequencies::Daily,
+        "finance@example.com".to_string(),
+        auth_user.id(),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        report_subscriptions::index((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_subscriptions: Vec<ReportSubscription> = serde_json::from_str(&body).unwrap();
+    assert_eq!(found_subscriptions, vec![report_subscription]);
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateReportSubscriptionRequest {
+        event_id: None,
+        report_type: ReportTypes::TicketCount,
+        frequency: ReportFrequencies::Weekly,
+        email: "finance@example.com".to_string(),
+    });
+    let response: HttpResponse =
+        report_subscriptions::create((database.connection.clone().into(), path, json, auth_user))
+            .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let report_subscription: ReportSubscription = serde_json::from_str(&body).unwrap();
+    assert_eq!(report_subscription.organization_id, organization.id);
+    assert_eq!(report_subscription.report_type, ReportTypes::TicketCount);
+    assert_eq!(
+        ReportSubscription::find_for_organization(organization.id, connection).unwrap(),
+        vec![report_subscription]
+    );
+}
+
+#[test]
+fn create_financial_report() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+
+    // Financial reports require the same access as viewing them on demand
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateReportSubscriptionRequest {
+        event_id: None,
+        report_type: ReportTypes::WeeklySettlement,
+        frequency: ReportFrequencies::Weekly,
+        email: "finance@example.com".to_string(),
+    });
+    let response: HttpResponse =
+        report_subscriptions::create((database.connection.clone().into(), path, json, auth_user))
+            .into();
+    support::expects_unauthorized(&response);
+
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateReportSubscriptionRequest {
+        event_id: None,
+        report_type: ReportTypes::WeeklySettlement,
+        frequency: ReportFrequencies::Weekly,
+        email: "finance@example.com".to_string(),
+    });
+    let response: HttpResponse =
+        report_subscriptions::create((database.connection.clone().into(), path, json, auth_user))
+            .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::TicketCount,
+        ReportFrequencies::Daily,
+        "finance@example.com".to_string(),
+        auth_user.id(),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = report_subscription.id;
+    let response: HttpResponse =
+        report_subscriptions::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(ReportSubscription::find(report_subscription.id, connection).is_err());
+}
+
+#[test]
+fn send_scheduled_report() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::TicketCount,
+        ReportFrequencies::Daily,
+        "finance@example.com".to_string(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let now = Utc::now().naive_utc();
+    let domain_action = DomainAction::create(
+        None,
+        DomainActionTypes::SendScheduledReport,
+        None,
+        json!({ "report_subscription_id": report_subscription.id }),
+        Some(Tables::ReportSubscriptions.to_string()),
+        Some(report_subscription.id),
+        now,
+        now + Duration::days(1),
+        3,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let executor = SendScheduledReportExecutor::new(Config::new(Environment::Test));
+    executor
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+
+    let communications =
+        DomainAction::find_pending(Some(DomainActionTypes::Communication), connection).unwrap();
+    assert_eq!(communications.len(), 1);
+    let communication: Communication =
+        serde_json::from_value(communications[0].payload.clone()).unwrap();
+    assert_eq!(
+        communication.destinations,
+        CommAddress::from("finance@example.com".to_string())
+    );
+    assert_eq!(communication.attachments.len(), 1);
+    assert_eq!(
+        communication.attachments[0].filename,
+        report_subscription.file_name()
+    );
+    assert_eq!(communication.attachments[0].content_type, "text/csv");
+
+    let updated_subscription =
+        ReportSubscription::find(report_subscription.id, connection).unwrap();
+    assert_eq!(
+        updated_subscription.last_sent_at,
+        Some(report_subscription.next_send_at)
+    );
+    assert!(updated_subscription.next_send_at > report_subscription.next_send_at);
+}
@@ -0,0 +1,145 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::cart;
+use bigneon_api::controllers::resale_listings::{self, CreateResaleListingRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn purchased_ticket(database: &TestDatabase) -> (User, Event, TicketInstance) {
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                max_resale_markup_percent: Some(Some(10.0)),
+                ..Default::default()
+            },
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(seller.id, connection)
+        .unwrap()
+        .remove(0);
+    (seller, event, ticket)
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (seller, event, ticket) = purchased_ticket(&database);
+    ResaleListing::create(ticket.id, seller.id, 100)
+        .commit(connection)
+        .unwrap();
+    let expected_json = serde_json::to_string(
+        &ResaleListing::find_available_for_event(event.id, connection).unwrap(),
+    )
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+
+    let response: HttpResponse =
+        resale_listings::index((database.connection.clone().into(), path)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_json);
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (seller, _, ticket) = purchased_ticket(&database);
+    let auth_user = support::create_auth_user_from_user(&seller, Roles::User, None, &database);
+
+    let json = Json(CreateResaleListingRequest {
+        ticket_instance_id: ticket.id,
+        price_in_cents: 100,
+    });
+    let response: HttpResponse =
+        resale_listings::create((database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let resale_listing: ResaleListing = serde_json::from_str(&body).unwrap();
+    assert_eq!(resale_listing.ticket_instance_id, ticket.id);
+    assert_eq!(resale_listing.seller_user_id, seller.id);
+    assert_eq!(
+        ResaleListing::find_active_for_ticket_instance(ticket.id, connection).unwrap(),
+        Some(resale_listing)
+    );
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (seller, _, ticket) = purchased_ticket(&database);
+    let resale_listing = ResaleListing::create(ticket.id, seller.id, 100)
+        .commit(connection)
+        .unwrap();
+
+    // Only the seller can cancel the listing
+    let other_user = support::create_auth_user(Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = resale_listing.id;
+    let response: HttpResponse =
+        resale_listings::destroy((database.connection.clone().into(), path, other_user)).into();
+    support::expects_unauthorized(&response);
+
+    let auth_user = support::create_auth_user_from_user(&seller, Roles::User, None, &database);
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = resale_listing.id;
+    let response: HttpResponse =
+        resale_listings::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let resale_listing = ResaleListing::find(resale_listing.id, connection).unwrap();
+    assert_eq!(resale_listing.status, ResaleListingStatus::Cancelled);
+}
+
+#[test]
+fn add_to_cart() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (seller, _, ticket) = purchased_ticket(&database);
+    let resale_listing = ResaleListing::create(ticket.id, seller.id, 100)
+        .commit(connection)
+        .unwrap();
+    let buyer = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&buyer, Roles::User, None, &database);
+
+    let json = Json(cart::AddResaleListingRequest {
+        resale_listing_id: resale_listing.id,
+    });
+    let response: HttpResponse =
+        cart::add_resale_listing((database.connection.clone().into(), json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(buyer.id, connection)
+        .unwrap()
+        .unwrap();
+    let items = cart.items(connection).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].item_type, OrderItemTypes::Resale);
+    assert_eq!(items[0].unit_price_in_cents, 100);
+}
@@ -0,0 +1,107 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::resale_payouts;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn pending_payout(database: &TestDatabase) -> ResalePayout {
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                max_resale_markup_percent: Some(Some(10.0)),
+                ..Default::default()
+            },
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(seller.id, connection)
+        .unwrap()
+        .remove(0);
+    let listing = ResaleListing::create(ticket.id, seller.id, 100)
+        .commit(connection)
+        .unwrap();
+
+    let buyer = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    listing
+        .add_to_cart(&mut cart, buyer.id, connection)
+        .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("test".to_string()), buyer.id, total, connection)
+        .unwrap();
+    ResalePayout::find_for_resale_listing(listing.id, connection).unwrap()
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let payout = pending_payout(&database);
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response = resale_payouts::index((
+        database.connection.clone().into(),
+        query_parameters,
+        auth_user,
+    ))
+    .unwrap();
+    assert_eq!(response.payload().data, vec![payout]);
+}
+
+#[test]
+fn index_requires_admin() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response: HttpResponse = resale_payouts::index((
+        database.connection.clone().into(),
+        query_parameters,
+        auth_user,
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn mark_paid() {
+    let database = TestDatabase::new();
+    let payout = pending_payout(&database);
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = payout.id;
+    let response: HttpResponse =
+        resale_payouts::mark_paid((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let paid_payout: ResalePayout = serde_json::from_str(&body).unwrap();
+    assert_eq!(paid_payout.status, ResalePayoutStatus::Paid);
+    assert_eq!(paid_payout.paid_by_user_id, Some(user.id));
+}
@@ -0,0 +1,117 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::seat_maps;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+pub fn show() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap()
+        .add_row("A".to_string(), 5, Some(price_zone.id), connection)
+        .unwrap();
+    let expected_json = serde_json::to_string(&stage.seat_map(connection).unwrap()).unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = stage.id;
+
+    let response: HttpResponse = seat_maps::show((database.connection.clone().into(), path)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_json);
+}
+
+#[cfg(test)]
+mod create_price_zone_tests {
+    use super::*;
+    #[test]
+    fn create_price_zone_org_member() {
+        base::seat_maps::create_price_zone(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_price_zone_admin() {
+        base::seat_maps::create_price_zone(Roles::Admin, true);
+    }
+    #[test]
+    fn create_price_zone_user() {
+        base::seat_maps::create_price_zone(Roles::User, false);
+    }
+    #[test]
+    fn create_price_zone_org_owner() {
+        base::seat_maps::create_price_zone(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_price_zone_door_person() {
+        base::seat_maps::create_price_zone(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_price_zone_promoter() {
+        base::seat_maps::create_price_zone(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_price_zone_promoter_read_only() {
+        base::seat_maps::create_price_zone(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_price_zone_org_admin() {
+        base::seat_maps::create_price_zone(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_price_zone_box_office() {
+        base::seat_maps::create_price_zone(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod create_seating_section_tests {
+    use super::*;
+    #[test]
+    fn create_seating_section_org_member() {
+        base::seat_maps::create_seating_section(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_seating_section_admin() {
+        base::seat_maps::create_seating_section(Roles::Admin, true);
+    }
+    #[test]
+    fn create_seating_section_user() {
+        base::seat_maps::create_seating_section(Roles::User, false);
+    }
+    #[test]
+    fn create_seating_section_org_owner() {
+        base::seat_maps::create_seating_section(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_seating_section_door_person() {
+        base::seat_maps::create_seating_section(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_seating_section_promoter() {
+        base::seat_maps::create_seating_section(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_seating_section_promoter_read_only() {
+        base::seat_maps::create_seating_section(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_seating_section_org_admin() {
+        base::seat_maps::create_seating_section(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_seating_section_box_office() {
+        base::seat_maps::create_seating_section(Roles::OrgBoxOffice, false);
+    }
+}
@@ -0,0 +1,98 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::tax_rates::{self, CreateTaxRateRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let tax_rate = TaxRate::create(organization.id, None, "VAT".to_string(), 20.0, true)
+        .commit(auth_user.id(), connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        tax_rates::index((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_tax_rates: Vec<TaxRate> = serde_json::from_str(&body).unwrap();
+    assert_eq!(found_tax_rates, vec![tax_rate]);
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateTaxRateRequest {
+        region_id: None,
+        name: "Sales Tax".to_string(),
+        rate_percent: 8.5,
+        inclusive: false,
+    });
+    let response: HttpResponse =
+        tax_rates::create((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let tax_rate: TaxRate = serde_json::from_str(&body).unwrap();
+    assert_eq!(tax_rate.organization_id, organization.id);
+    assert_eq!(tax_rate.rate_percent, 8.5);
+    assert_eq!(
+        TaxRate::find_for_organization(organization.id, connection).unwrap(),
+        vec![tax_rate]
+    );
+}
+
+#[test]
+fn create_unauthorized() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateTaxRateRequest {
+        region_id: None,
+        name: "Sales Tax".to_string(),
+        rate_percent: 8.5,
+        inclusive: false,
+    });
+    let response: HttpResponse =
+        tax_rates::create((database.connection.clone().into(), path, json, auth_user)).into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let tax_rate = TaxRate::create(organization.id, None, "VAT".to_string(), 20.0, true)
+        .commit(auth_user.id(), connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = tax_rate.id;
+    let response: HttpResponse =
+        tax_rates::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(TaxRate::find(tax_rate.id, connection).is_err());
+}
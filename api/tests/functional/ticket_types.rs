@@ -202,6 +202,8 @@ pub fn create_with_validation_errors() {
         start_date: start_date2,
         end_date: end_date2,
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -266,6 +268,8 @@ pub fn create_with_validation_errors_on_ticket_pricing() {
         start_date: start_date2,
         end_date: end_date2,
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -332,6 +336,8 @@ pub fn create_with_overlapping_periods() {
         start_date,
         end_date: middle_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     ticket_pricing.push(CreateTicketPricingRequest {
         name: String::from("Base"),
@@ -339,6 +345,8 @@ pub fn create_with_overlapping_periods() {
         start_date: start_date,
         end_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -430,6 +438,8 @@ pub fn update_with_invalid_id() {
         end_date,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -498,6 +508,8 @@ pub fn update_with_validation_errors() {
         end_date: end_date2,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -577,6 +589,8 @@ pub fn update_with_validation_errors_on_ticket_pricing() {
         end_date: end_date2,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -660,6 +674,8 @@ pub fn update_with_overlapping_periods() {
         end_date,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     request_ticket_pricing.push(UpdateTicketPricingRequest {
         id: None,
@@ -668,6 +684,8 @@ pub fn update_with_overlapping_periods() {
         end_date: middle_date,
         price_in_cents: Some(15000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
+        rank: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -742,11 +760,13 @@ pub fn cancel_with_sold_tickets_and_hold() {
                 ticket_type_id: created_ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: created_ticket_type.id,
                 quantity: 5,
                 redemption_code: Some(hold.redemption_code),
+                seat_ids: None,
             },
         ],
         false,
@@ -785,3 +805,129 @@ pub fn cancel_with_sold_tickets_and_hold() {
     let valid_ticket_count = created_ticket_type.valid_ticket_count(conn).unwrap();
     assert_eq!(15, valid_ticket_count);
 }
+
+#[test]
+pub fn join_waitlist() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, conn).unwrap()[0];
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+
+    let test_request =
+        TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+
+    // Tickets are still available
+    let response: HttpResponse = ticket_types::join_waitlist((
+        database.connection.clone().into(),
+        path,
+        Json(JoinWaitlistRequest { quantity: 1 }),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let buyer = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, conn).unwrap();
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        conn,
+    )
+    .unwrap();
+
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    let response: HttpResponse = ticket_types::join_waitlist((
+        database.connection.clone().into(),
+        path,
+        Json(JoinWaitlistRequest { quantity: 1 }),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let waitlist_entry: WaitlistEntry = serde_json::from_str(&body).unwrap();
+    assert_eq!(waitlist_entry.ticket_type_id, ticket_type.id);
+    assert_eq!(waitlist_entry.user_id, auth_user.id());
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+}
+
+#[test]
+pub fn leave_waitlist() {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, conn).unwrap()[0];
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+
+    let test_request =
+        TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+
+    // Not on the waitlist
+    let response: HttpResponse = ticket_types::leave_waitlist((
+        database.connection.clone().into(),
+        path,
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let buyer = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, conn).unwrap();
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        conn,
+    )
+    .unwrap();
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, auth_user.id(), 1)
+        .commit(conn)
+        .unwrap();
+
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request).unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    let response: HttpResponse = ticket_types::leave_waitlist((
+        database.connection.clone().into(),
+        path,
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let waitlist_entry = WaitlistEntry::find(waitlist_entry.id, conn).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+}
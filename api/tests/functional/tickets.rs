@@ -54,6 +54,7 @@ pub fn index() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -67,6 +68,7 @@ pub fn index() {
             ticket_type_id: ticket_type2.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -116,6 +118,9 @@ pub fn index() {
         status: TicketInstanceStatus::Purchased,
         redeem_key: ticket.redeem_key,
         pending_transfer: false,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
     };
     assert_eq!(vec![expected_ticket.clone()], found_data.data);
     // Test without specified event
@@ -143,6 +148,9 @@ pub fn index() {
         status: TicketInstanceStatus::Purchased,
         redeem_key: ticket2.redeem_key,
         pending_transfer: false,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
     };
     assert_eq!(
         vec![
@@ -208,6 +216,7 @@ pub fn show() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -236,6 +245,9 @@ pub fn show() {
         status: TicketInstanceStatus::Purchased,
         redeem_key: ticket.redeem_key,
         pending_transfer: false,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
     };
 
     let expected_result = ShowTicketResponse {
@@ -396,6 +408,7 @@ fn ticket_transfer_authorization() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -520,6 +533,7 @@ fn receive_ticket_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -0,0 +1,224 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::controllers::two_factor_authentication::{
+    self, RecoveryCodesResponse, TwoFactorCodeRequest, TwoFactorEnrolmentResponse,
+};
+use bigneon_api::extractors::*;
+use bigneon_db::models::*;
+use bigneon_db::utils::totp;
+use chrono::Utc;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn require_two_factor(organization: &Organization, database: &TestDatabase) -> Organization {
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                two_factor_required: Some(true),
+                ..Default::default()
+            },
+            &"".to_string(),
+            database.connection.get(),
+        )
+        .unwrap()
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+
+    let response: HttpResponse =
+        two_factor_authentication::create((state, database.connection.clone().into(), auth_user))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let enrolment: TwoFactorEnrolmentResponse = serde_json::from_str(&body).unwrap();
+    assert!(enrolment.otpauth_url.starts_with("otpauth://totp/"));
+    assert!(enrolment.otpauth_url.contains("fake@localhost"));
+    assert!(enrolment
+        .otpauth_url
+        .contains(&format!("secret={}", enrolment.secret)));
+    // Two factor authentication is not enforced until the user confirms a code
+    assert!(!user.two_factor_enabled(database.connection.get()).unwrap());
+}
+
+#[test]
+fn confirm() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let secret = UserTwoFactorCredential::begin_enrolment(
+        user.id,
+        &state.config.api_keys_encryption_key,
+        database.connection.get(),
+    )
+    .unwrap();
+
+    let json = Json(TwoFactorCodeRequest {
+        code: totp::code_for_timestamp(&secret, Utc::now().timestamp()).unwrap(),
+    });
+    let response: HttpResponse = two_factor_authentication::confirm((
+        state,
+        database.connection.clone().into(),
+        json,
+        auth_user,
+    ))
+    .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let recovery_codes: RecoveryCodesResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(recovery_codes.recovery_codes.len(), 10);
+    assert!(user.two_factor_enabled(database.connection.get()).unwrap());
+}
+
+#[test]
+fn confirm_invalid_code() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    UserTwoFactorCredential::begin_enrolment(
+        user.id,
+        &state.config.api_keys_encryption_key,
+        database.connection.get(),
+    )
+    .unwrap();
+
+    let json = Json(TwoFactorCodeRequest {
+        code: "not a code".to_string(),
+    });
+    let response: HttpResponse = two_factor_authentication::confirm((
+        state,
+        database.connection.clone().into(),
+        json,
+        auth_user,
+    ))
+    .into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(!user.two_factor_enabled(database.connection.get()).unwrap());
+}
+
+#[test]
+fn regenerate_recovery_codes() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let (_, recovery_codes) = support::enable_two_factor(&user, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+
+    let json = Json(TwoFactorCodeRequest {
+        code: recovery_codes[0].clone(),
+    });
+    let response: HttpResponse = two_factor_authentication::regenerate_recovery_codes((
+        state,
+        database.connection.clone().into(),
+        json,
+        auth_user,
+    ))
+    .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let new_recovery_codes: RecoveryCodesResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(new_recovery_codes.recovery_codes.len(), 10);
+    assert!(!new_recovery_codes
+        .recovery_codes
+        .contains(&recovery_codes[1]));
+}
+
+#[test]
+fn disable() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let (_, recovery_codes) = support::enable_two_factor(&user, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+
+    let json = Json(TwoFactorCodeRequest {
+        code: recovery_codes[0].clone(),
+    });
+    let response: HttpResponse = two_factor_authentication::disable((
+        state,
+        database.connection.clone().into(),
+        json,
+        auth_user,
+    ))
+    .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!user.two_factor_enabled(database.connection.get()).unwrap());
+}
+
+#[test]
+fn disable_when_required_by_organization() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    require_two_factor(&organization, &database);
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let (_, recovery_codes) = support::enable_two_factor(&user, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+
+    let json = Json(TwoFactorCodeRequest {
+        code: recovery_codes[0].clone(),
+    });
+    let response: HttpResponse = two_factor_authentication::disable((
+        state,
+        database.connection.clone().into(),
+        json,
+        auth_user,
+    ))
+    .into();
+
+    support::expects_forbidden(
+        &response,
+        Some("Two factor authentication is required by one of your organizations"),
+    );
+    assert!(user.two_factor_enabled(database.connection.get()).unwrap());
+}
+
+#[test]
+fn financial_scopes_require_two_factor() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let organization = require_two_factor(&organization, &database);
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    assert!(auth_user
+        .requires_scope_for_organization(Scopes::OrgRead, &organization, connection)
+        .is_ok());
+    let response: HttpResponse = auth_user
+        .requires_scope_for_organization(Scopes::EventFinancialReports, &organization, connection)
+        .map(|_| HttpResponse::Ok().finish())
+        .into();
+    support::expects_forbidden(
+        &response,
+        Some("Two factor authentication must be enabled to access financial information"),
+    );
+
+    support::enable_two_factor(&user, &database);
+    assert!(auth_user
+        .requires_scope_for_organization(Scopes::EventFinancialReports, &organization, connection)
+        .is_ok());
+}
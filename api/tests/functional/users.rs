@@ -507,6 +507,7 @@ fn current_user_organization_owner() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:make-external-payment",
             "order:read",
             "order:read-own",
@@ -581,6 +582,7 @@ fn current_user_organization_member() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:read",
             "order:read-own",
             "order:refund",
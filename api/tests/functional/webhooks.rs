@@ -0,0 +1,172 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::webhooks::{
+    self, CreateWebhookSubscriptionRequest, CreatedWebhookSubscriptionResponse,
+};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateWebhookSubscriptionRequest {
+        url: "https://example.com/webhooks".to_string(),
+        event_types: vec![DomainEventTypes::OrderCompleted],
+    });
+    let response: HttpResponse = webhooks::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let created: CreatedWebhookSubscriptionResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        created.webhook_subscription.organization_id,
+        organization.id
+    );
+    assert_eq!(created.secret.len(), 32);
+
+    // The secret is stored encrypted
+    let webhook_subscriptions =
+        WebhookSubscription::find_for_organization(organization.id, connection).unwrap();
+    assert_eq!(webhook_subscriptions.len(), 1);
+    assert_eq!(webhook_subscriptions[0].id, created.webhook_subscription.id);
+    assert_ne!(webhook_subscriptions[0].secret, created.secret);
+}
+
+#[test]
+fn create_with_internal_url() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateWebhookSubscriptionRequest {
+        url: "https://169.254.169.254/latest/meta-data".to_string(),
+        event_types: vec![DomainEventTypes::OrderCompleted],
+    });
+    let response: HttpResponse = webhooks::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let webhook_subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec![DomainEventTypes::OrderCompleted],
+        auth_user.id(),
+    )
+    .commit(&"encryption_key".to_string(), connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        webhooks::index((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(!body.contains("secret"));
+    let webhook_subscriptions: Vec<DisplayWebhookSubscription> =
+        serde_json::from_str(&body).unwrap();
+    assert_eq!(webhook_subscriptions, vec![webhook_subscription.into()]);
+}
+
+#[test]
+fn create_unauthorized() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateWebhookSubscriptionRequest {
+        url: "https://example.com/webhooks".to_string(),
+        event_types: vec![DomainEventTypes::OrderCompleted],
+    });
+    let response: HttpResponse = webhooks::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn deliveries() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let webhook_subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec![DomainEventTypes::OrderCompleted],
+        auth_user.id(),
+    )
+    .commit(&"encryption_key".to_string(), connection)
+    .unwrap();
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::OrderCompleted,
+        "Order completed".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    WebhookSubscription::queue_deliveries(&domain_event, connection).unwrap();
+
+    let test_request = TestRequest::create_with_uri("/deliveries?");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = webhook_subscription.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response = webhooks::deliveries((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        auth_user,
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.payload().paging.total, 1);
+    assert_eq!(response.payload().data[0].domain_event_id, domain_event.id);
+}
@@ -18,6 +18,8 @@ extern crate serde_json;
 extern crate serde_derive;
 extern crate globee;
 extern crate jsonwebtoken as jwt;
+extern crate paypal;
+extern crate stripe;
 extern crate uuid;
 extern crate validator;
 
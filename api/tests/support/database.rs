@@ -115,6 +115,7 @@ impl TestDatabase {
                 ticket_type_id,
                 quantity,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
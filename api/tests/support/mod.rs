@@ -3,7 +3,10 @@ pub mod test_request;
 
 use actix_web::{http::StatusCode, Body::Binary, HttpResponse};
 use bigneon_api::auth::user::User as AuthUser;
-use bigneon_db::models::{Organization, Roles, User};
+use bigneon_api::config::{Config, Environment};
+use bigneon_db::models::{Organization, Roles, User, UserTwoFactorCredential};
+use bigneon_db::utils::totp;
+use chrono::Utc;
 use serde_json;
 use std::collections::HashMap;
 use std::str;
@@ -75,6 +78,25 @@ pub fn create_auth_user_from_user(
     }
 }
 
+/// Enables two factor authentication for the user, returning the secret and recovery codes
+pub fn enable_two_factor(user: &User, database: &TestDatabase) -> (String, Vec<String>) {
+    let connection = database.connection.get();
+    let encryption_key = Config::new(Environment::Test).api_keys_encryption_key;
+    let secret =
+        UserTwoFactorCredential::begin_enrolment(user.id, &encryption_key, connection).unwrap();
+    let credential = UserTwoFactorCredential::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+    let recovery_codes = credential
+        .confirm(
+            &totp::code_for_timestamp(&secret, Utc::now().timestamp()).unwrap(),
+            &encryption_key,
+            connection,
+        )
+        .unwrap();
+    (secret, recovery_codes)
+}
+
 pub fn expects_unauthorized(response: &HttpResponse) {
     let expected_json = HttpResponse::Unauthorized()
         .json(json!({"error": "User does not have the required permissions"}));
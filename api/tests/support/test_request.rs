@@ -19,14 +19,35 @@ impl TestRequest {
     }
 
     pub fn create_with_uri_custom_params(path: &str, params: Vec<&'static str>) -> TestRequest {
+        TestRequest::create_with_headers(path, params, vec![])
+    }
+
+    pub fn create_with_headers(
+        path: &str,
+        params: Vec<&'static str>,
+        headers: Vec<(&'static str, String)>,
+    ) -> TestRequest {
+        TestRequest::create_with_config(path, params, headers, |_| {})
+    }
+
+    /// Allows tests to point the config at local services such as payment provider mocks
+    pub fn create_with_config<F: FnOnce(&mut Config)>(
+        path: &str,
+        params: Vec<&'static str>,
+        headers: Vec<(&'static str, String)>,
+        configure: F,
+    ) -> TestRequest {
         let mut config = Config::new(Environment::Test);
         config.token_secret = "test_secret".into();
         config.token_issuer = "bn-api-test".into();
         config.api_keys_encryption_key = "test_encryption_key".to_string();
         config.google_recaptcha_secret_key = None;
+        config.stripe_webhook_secret = Some("test_stripe_webhook_secret".to_string());
+        config.communication_webhook_token = Some("test_communication_webhook_token".to_string());
         if config.spotify_auth_token.is_some() {
             spotify::SINGLETON.set_auth_token(&config.spotify_auth_token.clone().unwrap());
         }
+        configure(&mut config);
 
         let test_request = test::TestRequest::with_state(AppState::new(
             config.clone(),
@@ -44,6 +65,10 @@ impl TestRequest {
             request = request.param(param, "0f85443e-9e70-45ba-bf28-0f59c183856f");
         }
 
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
         TestRequest {
             request: request.finish(),
             config,
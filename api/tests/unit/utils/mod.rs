@@ -0,0 +1 @@
+pub mod push_notifications;
@@ -0,0 +1,119 @@
+use bigneon_api::errors::*;
+use bigneon_api::utils::push_notifications::{self, PushNotificationResult, PushNotifier};
+use bigneon_db::prelude::*;
+use chrono::{Duration, Utc};
+use support::database::TestDatabase;
+
+struct TestPushNotifier {
+    invalid_token: String,
+    failing_token: Option<String>,
+}
+
+impl PushNotifier for TestPushNotifier {
+    fn send(
+        &self,
+        token: &PushNotificationToken,
+        _title: &str,
+        _body: &str,
+    ) -> Result<PushNotificationResult, BigNeonError> {
+        if token.token == self.invalid_token {
+            Ok(PushNotificationResult::InvalidToken)
+        } else if Some(&token.token) == self.failing_token.as_ref() {
+            Err(ApplicationError::new("Push notification could not be sent".to_string()).into())
+        } else {
+            Ok(PushNotificationResult::Delivered)
+        }
+    }
+}
+
+#[test]
+fn send_to_users() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    PushNotificationToken::create(user.id, "android".to_string(), "valid".to_string())
+        .commit(connection)
+        .unwrap();
+    PushNotificationToken::create(user.id, "ios".to_string(), "invalid".to_string())
+        .commit(connection)
+        .unwrap();
+    PushNotificationToken::create(user2.id, "ios".to_string(), "valid2".to_string())
+        .commit(connection)
+        .unwrap();
+
+    let notifier = TestPushNotifier {
+        invalid_token: "invalid".to_string(),
+        failing_token: None,
+    };
+    let failed_token_ids = push_notifications::send_to_users(
+        &notifier,
+        &[user.id, user2.id],
+        "Title",
+        "Body",
+        None,
+        connection,
+    )
+    .unwrap();
+    assert!(failed_token_ids.is_empty());
+
+    // Invalid token is pruned, the remaining tokens are marked as notified
+    let tokens = PushNotificationToken::find_by_user_id(user.id, connection).unwrap();
+    assert_eq!(1, tokens.len());
+    assert_eq!("valid", tokens[0].token);
+    assert!(tokens[0].last_notification_at.is_some());
+
+    let tokens = PushNotificationToken::find_by_user_id(user2.id, connection).unwrap();
+    assert_eq!(1, tokens.len());
+    assert!(tokens[0].last_notification_at.is_some());
+}
+
+#[test]
+fn send_to_users_with_failures() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let delivered_token =
+        PushNotificationToken::create(user.id, "android".to_string(), "valid".to_string())
+            .commit(connection)
+            .unwrap();
+    let failing_token =
+        PushNotificationToken::create(user.id, "ios".to_string(), "failing".to_string())
+            .commit(connection)
+            .unwrap();
+    let sent_at = Utc::now().naive_utc() - Duration::minutes(1);
+
+    let notifier = TestPushNotifier {
+        invalid_token: "invalid".to_string(),
+        failing_token: Some("failing".to_string()),
+    };
+    let failed_token_ids =
+        push_notifications::send_to_users(&notifier, &[user.id], "Title", "Body", None, connection)
+            .unwrap();
+    assert_eq!(failed_token_ids, vec![failing_token.id]);
+
+    // Retrying skips the device that was already notified
+    let notifier = TestPushNotifier {
+        invalid_token: "valid".to_string(),
+        failing_token: None,
+    };
+    let failed_token_ids = push_notifications::send_to_users(
+        &notifier,
+        &[user.id],
+        "Title",
+        "Body",
+        Some(sent_at),
+        connection,
+    )
+    .unwrap();
+    assert!(failed_token_ids.is_empty());
+    let tokens = PushNotificationToken::find_by_user_id(user.id, connection).unwrap();
+    assert_eq!(2, tokens.len());
+    assert!(tokens.iter().any(|t| t.id == delivered_token.id));
+    assert!(tokens
+        .iter()
+        .find(|t| t.id == failing_token.id)
+        .unwrap()
+        .last_notification_at
+        .is_some());
+}
@@ -0,0 +1,20 @@
+DROP INDEX IF EXISTS index_ticket_instances_seat_id;
+ALTER TABLE ticket_instances
+    DROP seat_id;
+
+DROP INDEX IF EXISTS index_ticket_types_price_zone_id;
+ALTER TABLE ticket_types
+    DROP price_zone_id;
+
+DROP INDEX IF EXISTS index_seats_seating_section_id_row_name_seat_number;
+DROP INDEX IF EXISTS index_seats_price_zone_id;
+DROP INDEX IF EXISTS index_seats_seating_section_id;
+DROP TABLE IF EXISTS seats;
+
+DROP INDEX IF EXISTS index_seating_sections_stage_id_name;
+DROP INDEX IF EXISTS index_seating_sections_stage_id;
+DROP TABLE IF EXISTS seating_sections;
+
+DROP INDEX IF EXISTS index_price_zones_stage_id_name;
+DROP INDEX IF EXISTS index_price_zones_stage_id;
+DROP TABLE IF EXISTS price_zones;
@@ -0,0 +1,50 @@
+CREATE TABLE price_zones
+(
+    id         UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    stage_id   UUID      NOT NULL REFERENCES stages (id) ON DELETE CASCADE,
+    name       TEXT      NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_price_zones_stage_id ON price_zones (stage_id);
+CREATE UNIQUE INDEX index_price_zones_stage_id_name ON price_zones (stage_id, name);
+
+CREATE TABLE seating_sections
+(
+    id         UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    stage_id   UUID      NOT NULL REFERENCES stages (id) ON DELETE CASCADE,
+    name       TEXT      NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_seating_sections_stage_id ON seating_sections (stage_id);
+CREATE UNIQUE INDEX index_seating_sections_stage_id_name ON seating_sections (stage_id, name);
+
+CREATE TABLE seats
+(
+    id                 UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    seating_section_id UUID      NOT NULL REFERENCES seating_sections (id) ON DELETE CASCADE,
+    price_zone_id      UUID      NULL REFERENCES price_zones (id) ON DELETE SET NULL,
+    row_name           TEXT      NOT NULL,
+    seat_number        TEXT      NOT NULL,
+    created_at         TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_seats_seating_section_id ON seats (seating_section_id);
+CREATE INDEX index_seats_price_zone_id ON seats (price_zone_id);
+CREATE UNIQUE INDEX index_seats_seating_section_id_row_name_seat_number ON seats (
+    seating_section_id,
+    row_name,
+    seat_number
+);
+
+ALTER TABLE ticket_types
+    ADD price_zone_id UUID NULL REFERENCES price_zones (id);
+CREATE INDEX index_ticket_types_price_zone_id ON ticket_types (price_zone_id);
+
+ALTER TABLE ticket_instances
+    ADD seat_id UUID NULL REFERENCES seats (id);
+CREATE INDEX index_ticket_instances_seat_id ON ticket_instances (seat_id);
@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS index_resale_listings_ticket_instance_id_active;
+DROP INDEX IF EXISTS index_resale_listings_order_item_id;
+DROP INDEX IF EXISTS index_resale_listings_seller_user_id;
+DROP INDEX IF EXISTS index_resale_listings_ticket_type_id_status;
+DROP TABLE IF EXISTS resale_listings;
+
+ALTER TABLE organizations
+    DROP COLUMN max_resale_markup_percent,
+    DROP COLUMN resale_fee_percent;
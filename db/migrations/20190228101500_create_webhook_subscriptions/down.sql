@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS index_webhook_subscriptions_organization_id;
+DROP TABLE IF EXISTS webhook_subscriptions;
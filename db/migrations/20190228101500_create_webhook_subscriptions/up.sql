@@ -0,0 +1,14 @@
+CREATE TABLE webhook_subscriptions
+(
+    id              UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    url             TEXT      NOT NULL,
+    secret          TEXT      NOT NULL,
+    event_types     TEXT[]    NOT NULL,
+    created_by      UUID      NOT NULL REFERENCES users (id),
+    deleted_at      TIMESTAMP NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_webhook_subscriptions_organization_id ON webhook_subscriptions (organization_id);
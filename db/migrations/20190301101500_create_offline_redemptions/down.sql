@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_offline_redemptions_ticket_instance_id;
+DROP INDEX IF EXISTS index_offline_redemptions_event_id;
+DROP TABLE IF EXISTS offline_redemptions;
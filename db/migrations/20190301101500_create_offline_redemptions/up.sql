@@ -0,0 +1,15 @@
+CREATE TABLE offline_redemptions
+(
+    id                 UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    event_id           UUID      NOT NULL REFERENCES events (id),
+    ticket_instance_id UUID      NOT NULL REFERENCES ticket_instances (id),
+    device_id          TEXT      NOT NULL,
+    redeemed_by        UUID      NOT NULL REFERENCES users (id),
+    scanned_at         TIMESTAMP NOT NULL,
+    status             TEXT      NOT NULL,
+    created_at         TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_offline_redemptions_event_id ON offline_redemptions (event_id);
+CREATE INDEX index_offline_redemptions_ticket_instance_id ON offline_redemptions (ticket_instance_id);
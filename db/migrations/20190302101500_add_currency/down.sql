@@ -0,0 +1,11 @@
+ALTER TABLE settlement_transactions
+    DROP COLUMN currency;
+
+ALTER TABLE orders
+    DROP COLUMN currency;
+
+ALTER TABLE events
+    DROP COLUMN currency;
+
+ALTER TABLE organizations
+    DROP COLUMN currency;
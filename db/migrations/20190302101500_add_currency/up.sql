@@ -0,0 +1,16 @@
+ALTER TABLE organizations
+    ADD COLUMN currency TEXT NOT NULL DEFAULT 'usd';
+
+ALTER TABLE events
+    ADD COLUMN currency TEXT NOT NULL DEFAULT 'usd';
+
+-- Set once the first item is added to the cart, all items in an order share a currency
+ALTER TABLE orders
+    ADD COLUMN currency TEXT NULL;
+
+UPDATE orders
+SET currency = 'usd'
+WHERE EXISTS(SELECT 1 FROM order_items oi WHERE oi.order_id = orders.id);
+
+ALTER TABLE settlement_transactions
+    ADD COLUMN currency TEXT NOT NULL DEFAULT 'usd';
@@ -0,0 +1,10 @@
+ALTER TABLE order_items
+    DROP COLUMN IF EXISTS tax_in_cents;
+
+ALTER TABLE order_items
+    DROP COLUMN IF EXISTS tax_rate_id;
+
+DROP INDEX IF EXISTS index_tax_rates_organization_id_no_region;
+DROP INDEX IF EXISTS index_tax_rates_organization_id_region_id;
+DROP INDEX IF EXISTS index_tax_rates_organization_id;
+DROP TABLE IF EXISTS tax_rates;
@@ -0,0 +1,24 @@
+CREATE TABLE tax_rates
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID             NOT NULL REFERENCES organizations (id),
+    -- Applies to events at venues in this region, organization wide when null
+    region_id       UUID             NULL REFERENCES regions (id),
+    name            TEXT             NOT NULL,
+    rate_percent    REAL             NOT NULL CHECK (rate_percent >= 0),
+    -- Inclusive taxes are already part of the ticket price, exclusive taxes are added on top
+    inclusive       BOOLEAN          NOT NULL DEFAULT false,
+    created_at      TIMESTAMP        NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP        NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_tax_rates_organization_id ON tax_rates (organization_id);
+CREATE UNIQUE INDEX index_tax_rates_organization_id_region_id ON tax_rates (organization_id, region_id) WHERE region_id IS NOT NULL;
+CREATE UNIQUE INDEX index_tax_rates_organization_id_no_region ON tax_rates (organization_id) WHERE region_id IS NULL;
+
+ALTER TABLE order_items
+    ADD COLUMN tax_rate_id UUID NULL REFERENCES tax_rates (id);
+
+-- Tax per unit, for exclusive taxes this matches unit_price_in_cents
+ALTER TABLE order_items
+    ADD COLUMN tax_in_cents BIGINT NOT NULL DEFAULT 0;
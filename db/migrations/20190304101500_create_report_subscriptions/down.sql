@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_report_subscriptions_event_id;
+DROP INDEX IF EXISTS index_report_subscriptions_organization_id;
+DROP TABLE IF EXISTS report_subscriptions;
@@ -0,0 +1,18 @@
+CREATE TABLE report_subscriptions
+(
+    id              UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    event_id        UUID      NULL REFERENCES events (id),
+    report_type     TEXT      NOT NULL,
+    frequency       TEXT      NOT NULL,
+    email           TEXT      NOT NULL,
+    created_by      UUID      NOT NULL REFERENCES users (id),
+    next_send_at    TIMESTAMP NOT NULL,
+    last_sent_at    TIMESTAMP NULL,
+    deleted_at      TIMESTAMP NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_report_subscriptions_organization_id ON report_subscriptions (organization_id);
+CREATE INDEX index_report_subscriptions_event_id ON report_subscriptions (event_id);
@@ -0,0 +1,19 @@
+CREATE TABLE disputes
+(
+    id                        UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    payment_id                UUID      NOT NULL REFERENCES payments (id),
+    order_id                  UUID      NOT NULL REFERENCES orders (id),
+    external_reference        TEXT      NOT NULL,
+    amount                    BIGINT    NOT NULL,
+    reason                    TEXT      NULL,
+    status                    TEXT      NOT NULL,
+    evidence_due_by           TIMESTAMP NULL,
+    held_ticket_instance_ids  UUID[]    NOT NULL DEFAULT '{}',
+    closed_at                 TIMESTAMP NULL,
+    created_at                TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at                TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_disputes_payment_id ON disputes (payment_id);
+CREATE INDEX index_disputes_order_id ON disputes (order_id);
+CREATE UNIQUE INDEX index_disputes_external_reference ON disputes (external_reference);
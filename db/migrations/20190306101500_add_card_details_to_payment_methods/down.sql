@@ -0,0 +1,8 @@
+ALTER TABLE payment_methods
+    DROP COLUMN source_reference,
+    DROP COLUMN card_brand,
+    DROP COLUMN card_last_four,
+    DROP COLUMN card_exp_month,
+    DROP COLUMN card_exp_year;
+
+CREATE UNIQUE INDEX index_payment_methods_user_id_name on payment_methods(user_id, name);
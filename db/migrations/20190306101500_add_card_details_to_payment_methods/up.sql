@@ -0,0 +1,9 @@
+-- Users can save several cards per provider, each one is a source on the provider's customer
+DROP INDEX IF EXISTS index_payment_methods_user_id_name;
+
+ALTER TABLE payment_methods
+    ADD COLUMN source_reference TEXT NULL,
+    ADD COLUMN card_brand TEXT NULL,
+    ADD COLUMN card_last_four TEXT NULL,
+    ADD COLUMN card_exp_month INTEGER NULL,
+    ADD COLUMN card_exp_year INTEGER NULL;
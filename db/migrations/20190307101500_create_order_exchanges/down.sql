@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS index_order_exchanges_order_id;
+DROP TABLE IF EXISTS order_exchanges;
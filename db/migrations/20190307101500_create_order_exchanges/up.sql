@@ -0,0 +1,15 @@
+CREATE TABLE order_exchanges
+(
+    id                            UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    order_id                      UUID      NOT NULL REFERENCES orders (id),
+    ticket_type_id                UUID      NOT NULL REFERENCES ticket_types (id),
+    exchanged_ticket_instance_ids UUID[]    NOT NULL,
+    ticket_instance_ids           UUID[]    NOT NULL,
+    refunded_amount_in_cents      BIGINT    NOT NULL,
+    charged_amount_in_cents       BIGINT    NOT NULL,
+    created_by_user_id            UUID      NOT NULL REFERENCES users (id),
+    created_at                    TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at                    TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_order_exchanges_order_id ON order_exchanges (order_id);
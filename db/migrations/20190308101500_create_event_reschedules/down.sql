@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_event_reschedule_refunds_user_id;
+DROP INDEX IF EXISTS index_event_reschedule_refunds_event_reschedule_id;
+DROP TABLE IF EXISTS event_reschedule_refunds;
+DROP INDEX IF EXISTS index_event_reschedules_event_id;
+DROP TABLE IF EXISTS event_reschedules;
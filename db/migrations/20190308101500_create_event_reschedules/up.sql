@@ -0,0 +1,32 @@
+CREATE TABLE event_reschedules
+(
+    id                   UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    event_id             UUID      NOT NULL REFERENCES events (id),
+    previous_event_start TIMESTAMP NULL,
+    previous_door_time   TIMESTAMP NULL,
+    previous_event_end   TIMESTAMP NULL,
+    event_start          TIMESTAMP NOT NULL,
+    door_time            TIMESTAMP NULL,
+    event_end            TIMESTAMP NULL,
+    refund_deadline      TIMESTAMP NOT NULL,
+    created_by_user_id   UUID      NOT NULL REFERENCES users (id),
+    created_at           TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at           TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_reschedules_event_id ON event_reschedules (event_id);
+
+CREATE TABLE event_reschedule_refunds
+(
+    id                       UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    event_reschedule_id      UUID      NOT NULL REFERENCES event_reschedules (id),
+    user_id                  UUID      NOT NULL REFERENCES users (id),
+    order_id                 UUID      NOT NULL REFERENCES orders (id),
+    ticket_instance_ids      UUID[]    NOT NULL,
+    refunded_amount_in_cents BIGINT    NOT NULL,
+    created_at               TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at               TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_reschedule_refunds_event_reschedule_id ON event_reschedule_refunds (event_reschedule_id);
+CREATE INDEX index_event_reschedule_refunds_user_id ON event_reschedule_refunds (user_id);
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS index_event_cancellation_orders_order_id;
+DROP INDEX IF EXISTS index_event_cancellation_orders_event_cancellation_id_order_id;
+DROP TABLE IF EXISTS event_cancellation_orders;
+DROP INDEX IF EXISTS index_event_cancellations_event_id;
+DROP TABLE IF EXISTS event_cancellations;
+
+ALTER TABLE organizations
+    DROP COLUMN refund_fees_on_cancellation;
@@ -0,0 +1,32 @@
+ALTER TABLE organizations
+    ADD COLUMN refund_fees_on_cancellation BOOLEAN NOT NULL DEFAULT true;
+
+CREATE TABLE event_cancellations
+(
+    id                 UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    event_id           UUID      NOT NULL REFERENCES events (id),
+    refund_fees        BOOLEAN   NOT NULL,
+    status             TEXT      NOT NULL DEFAULT 'InProgress',
+    created_by_user_id UUID      NOT NULL REFERENCES users (id),
+    completed_at       TIMESTAMP NULL,
+    created_at         TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_event_cancellations_event_id ON event_cancellations (event_id);
+
+CREATE TABLE event_cancellation_orders
+(
+    id                       UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    event_cancellation_id    UUID      NOT NULL REFERENCES event_cancellations (id),
+    order_id                 UUID      NOT NULL REFERENCES orders (id),
+    status                   TEXT      NOT NULL DEFAULT 'Pending',
+    attempt_count            BIGINT    NOT NULL DEFAULT 0,
+    refunded_amount_in_cents BIGINT    NOT NULL DEFAULT 0,
+    last_failure_reason      TEXT      NULL,
+    created_at               TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at               TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_event_cancellation_orders_event_cancellation_id_order_id ON event_cancellation_orders (event_cancellation_id, order_id);
+CREATE INDEX index_event_cancellation_orders_order_id ON event_cancellation_orders (order_id);
@@ -0,0 +1,44 @@
+CREATE TABLE bundles
+(
+    id              UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    name            TEXT      NOT NULL,
+    description     TEXT      NULL,
+    quantity        BIGINT    NOT NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_bundles_organization_id ON bundles (organization_id);
+
+CREATE TABLE bundle_ticket_types
+(
+    id             UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    bundle_id      UUID      NOT NULL REFERENCES bundles (id),
+    ticket_type_id UUID      NOT NULL REFERENCES ticket_types (id),
+    quantity       BIGINT    NOT NULL DEFAULT 1,
+    created_at     TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_bundle_ticket_types_bundle_id_ticket_type_id ON bundle_ticket_types (bundle_id, ticket_type_id);
+CREATE INDEX index_bundle_ticket_types_ticket_type_id ON bundle_ticket_types (ticket_type_id);
+
+CREATE TABLE bundle_pricing
+(
+    id             UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    bundle_id      UUID      NOT NULL REFERENCES bundles (id),
+    name           TEXT      NOT NULL,
+    price_in_cents BIGINT    NOT NULL,
+    start_date     TIMESTAMP NOT NULL,
+    end_date       TIMESTAMP NOT NULL,
+    created_at     TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_bundle_pricing_bundle_id ON bundle_pricing (bundle_id);
+
+ALTER TABLE order_items
+    ADD COLUMN bundle_id UUID NULL REFERENCES bundles (id);
+
+CREATE INDEX index_order_items_bundle_id ON order_items (bundle_id);
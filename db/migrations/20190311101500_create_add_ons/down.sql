@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS index_add_on_vouchers_user_id;
+DROP INDEX IF EXISTS index_add_on_vouchers_order_item_id;
+DROP INDEX IF EXISTS index_add_on_vouchers_add_on_id;
+DROP TABLE IF EXISTS add_on_vouchers;
+DROP INDEX IF EXISTS index_order_items_add_on_id;
+
+ALTER TABLE order_items
+    DROP COLUMN add_on_id;
+
+DROP INDEX IF EXISTS index_add_ons_event_id;
+DROP TABLE IF EXISTS add_ons;
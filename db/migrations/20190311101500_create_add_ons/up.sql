@@ -0,0 +1,37 @@
+CREATE TABLE add_ons
+(
+    id             UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    event_id       UUID      NOT NULL REFERENCES events (id),
+    name           TEXT      NOT NULL,
+    description    TEXT      NULL,
+    price_in_cents BIGINT    NOT NULL,
+    quantity       BIGINT    NOT NULL,
+    redeemable     BOOLEAN   NOT NULL DEFAULT true,
+    created_at     TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_add_ons_event_id ON add_ons (event_id);
+
+ALTER TABLE order_items
+    ADD COLUMN add_on_id UUID NULL REFERENCES add_ons (id);
+
+CREATE INDEX index_order_items_add_on_id ON order_items (add_on_id);
+
+CREATE TABLE add_on_vouchers
+(
+    id                  UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    add_on_id           UUID      NOT NULL REFERENCES add_ons (id),
+    order_item_id       UUID      NOT NULL REFERENCES order_items (id),
+    user_id             UUID      NOT NULL REFERENCES users (id),
+    redeem_key          TEXT      NOT NULL,
+    status              TEXT      NOT NULL DEFAULT 'Purchased',
+    redeemed_at         TIMESTAMP NULL,
+    redeemed_by_user_id UUID      NULL REFERENCES users (id),
+    created_at          TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_add_on_vouchers_add_on_id ON add_on_vouchers (add_on_id);
+CREATE INDEX index_add_on_vouchers_order_item_id ON add_on_vouchers (order_item_id);
+CREATE INDEX index_add_on_vouchers_user_id ON add_on_vouchers (user_id);
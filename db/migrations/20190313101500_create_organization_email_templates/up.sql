@@ -0,0 +1,15 @@
+CREATE TABLE organization_email_templates
+(
+    id              UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    template_kind   TEXT      NOT NULL,
+    template_id     TEXT      NULL,
+    logo_url        TEXT      NULL,
+    primary_color   TEXT      NULL,
+    reply_to_email  TEXT      NULL,
+    footer_text     TEXT      NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_organization_email_templates_organization_id_template_kind ON organization_email_templates (organization_id, template_kind);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_communication_logs_provider_message_id;
+DROP INDEX IF EXISTS index_communication_logs_main_table_id;
+DROP INDEX IF EXISTS index_communication_logs_destination;
+DROP TABLE IF EXISTS communication_logs;
@@ -0,0 +1,21 @@
+CREATE TABLE communication_logs
+(
+    id                  UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    domain_action_id    UUID      NULL,
+    channel             TEXT      NOT NULL,
+    template_id         TEXT      NULL,
+    title               TEXT      NOT NULL,
+    destination         TEXT      NOT NULL,
+    main_table          TEXT      NULL,
+    main_table_id       UUID      NULL,
+    provider_message_id TEXT      NULL,
+    status              TEXT      NOT NULL DEFAULT 'Sent',
+    status_updated_at   TIMESTAMP NULL,
+    payload             JSONB     NOT NULL,
+    created_at          TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_communication_logs_destination ON communication_logs (destination);
+CREATE INDEX index_communication_logs_main_table_id ON communication_logs (main_table_id);
+CREATE INDEX index_communication_logs_provider_message_id ON communication_logs (provider_message_id);
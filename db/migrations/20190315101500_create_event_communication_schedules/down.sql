@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS index_event_communication_schedules_organization_id;
+DROP TABLE IF EXISTS event_communication_schedules;
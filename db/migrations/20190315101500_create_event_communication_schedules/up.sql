@@ -0,0 +1,16 @@
+CREATE TABLE event_communication_schedules
+(
+    id              UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    timing          TEXT      NOT NULL,
+    offset_hours    INTEGER   NOT NULL CHECK (offset_hours >= 0),
+    channel         TEXT      NOT NULL,
+    title           TEXT      NOT NULL,
+    message         TEXT      NOT NULL,
+    template_id     TEXT      NULL,
+    deleted_at      TIMESTAMP NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_communication_schedules_organization_id ON event_communication_schedules (organization_id);
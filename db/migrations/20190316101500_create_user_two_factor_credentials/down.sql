@@ -0,0 +1,5 @@
+ALTER TABLE organizations
+    DROP COLUMN two_factor_required;
+
+DROP INDEX IF EXISTS index_user_two_factor_credentials_user_id;
+DROP TABLE IF EXISTS user_two_factor_credentials;
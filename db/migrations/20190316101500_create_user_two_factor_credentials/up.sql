@@ -0,0 +1,16 @@
+CREATE TABLE user_two_factor_credentials
+(
+    id                   UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    user_id              UUID      NOT NULL REFERENCES users (id),
+    secret               TEXT      NOT NULL,
+    recovery_code_hashes TEXT[]    NOT NULL DEFAULT '{}',
+    enabled_at           TIMESTAMP NULL,
+    last_used_step       BIGINT    NULL,
+    created_at           TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at           TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_user_two_factor_credentials_user_id ON user_two_factor_credentials (user_id);
+
+ALTER TABLE organizations
+    ADD COLUMN two_factor_required BOOLEAN NOT NULL DEFAULT false;
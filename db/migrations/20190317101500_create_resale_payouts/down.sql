@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_resale_payouts_status;
+DROP INDEX IF EXISTS index_resale_payouts_seller_user_id;
+DROP INDEX IF EXISTS index_resale_payouts_resale_listing_id;
+DROP TABLE IF EXISTS resale_payouts;
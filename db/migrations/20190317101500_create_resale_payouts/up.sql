@@ -0,0 +1,18 @@
+CREATE TABLE resale_payouts
+(
+    id                UUID PRIMARY KEY   DEFAULT gen_random_uuid() NOT NULL,
+    resale_listing_id UUID      NOT NULL REFERENCES resale_listings (id),
+    seller_user_id    UUID      NOT NULL REFERENCES users (id),
+    amount_in_cents   BIGINT    NOT NULL,
+    currency          TEXT      NOT NULL,
+    status            TEXT      NOT NULL DEFAULT 'Pending',
+    paid_at           TIMESTAMP NULL,
+    paid_by_user_id   UUID      NULL REFERENCES users (id),
+    created_at        TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at        TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- A listing is paid out at most once
+CREATE UNIQUE INDEX index_resale_payouts_resale_listing_id ON resale_payouts (resale_listing_id);
+CREATE INDEX index_resale_payouts_seller_user_id ON resale_payouts (seller_user_id);
+CREATE INDEX index_resale_payouts_status ON resale_payouts (status);
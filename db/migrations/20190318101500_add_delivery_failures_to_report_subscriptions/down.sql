@@ -0,0 +1,3 @@
+ALTER TABLE report_subscriptions
+    DROP COLUMN last_failed_at,
+    DROP COLUMN last_failure_reason;
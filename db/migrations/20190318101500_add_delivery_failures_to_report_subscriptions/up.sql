@@ -0,0 +1,3 @@
+ALTER TABLE report_subscriptions
+    ADD COLUMN last_failed_at TIMESTAMP NULL,
+    ADD COLUMN last_failure_reason TEXT NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE disputes
+    DROP COLUMN evidence_notes,
+    DROP COLUMN evidence_submitted_at;
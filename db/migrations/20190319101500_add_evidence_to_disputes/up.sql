@@ -0,0 +1,3 @@
+ALTER TABLE disputes
+    ADD COLUMN evidence_notes TEXT NULL,
+    ADD COLUMN evidence_submitted_at TIMESTAMP NULL;
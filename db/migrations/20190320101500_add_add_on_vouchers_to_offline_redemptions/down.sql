@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS index_offline_redemptions_add_on_voucher_id;
+
+DELETE FROM offline_redemptions WHERE ticket_instance_id IS NULL;
+
+ALTER TABLE offline_redemptions
+    DROP CONSTRAINT offline_redemptions_scanned_item,
+    DROP COLUMN add_on_voucher_id,
+    ALTER COLUMN ticket_instance_id SET NOT NULL;
@@ -0,0 +1,6 @@
+ALTER TABLE offline_redemptions
+    ALTER COLUMN ticket_instance_id DROP NOT NULL,
+    ADD COLUMN add_on_voucher_id UUID NULL REFERENCES add_on_vouchers (id),
+    ADD CONSTRAINT offline_redemptions_scanned_item CHECK ((ticket_instance_id IS NULL) <> (add_on_voucher_id IS NULL));
+
+CREATE INDEX index_offline_redemptions_add_on_voucher_id ON offline_redemptions (add_on_voucher_id);
@@ -0,0 +1,2 @@
+DROP FUNCTION current_ticket_pricing_id(UUID, BOOLEAN);
+DROP FUNCTION ticket_pricing_sold_quantity(UUID);
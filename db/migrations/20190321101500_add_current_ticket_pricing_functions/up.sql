@@ -0,0 +1,33 @@
+-- Tickets sold or held in unexpired carts at a ticket pricing
+CREATE OR REPLACE FUNCTION ticket_pricing_sold_quantity(UUID) RETURNS BIGINT AS $$
+BEGIN
+    RETURN (
+        SELECT CAST(COALESCE(SUM(oi.quantity - oi.refunded_quantity), 0) AS BIGINT)
+        FROM order_items oi
+        JOIN orders o ON o.id = oi.order_id
+        WHERE oi.ticket_pricing_id = $1
+        AND oi.item_type = 'Tickets'
+        AND (o.status IN ('Paid', 'PendingPayment') OR (o.status = 'Draft' AND o.expires_at > now()))
+    );
+END $$ LANGUAGE 'plpgsql';
+
+-- The ticket pricing that currently applies to a ticket type
+CREATE OR REPLACE FUNCTION current_ticket_pricing_id(UUID, BOOLEAN) RETURNS UUID AS $$
+BEGIN
+    RETURN (
+        -- $1 = ticket_type_id
+        -- $2 = box_office_pricing
+        SELECT tp.id
+        FROM ticket_pricing tp
+        WHERE tp.ticket_type_id = $1
+        AND tp.start_date <= now()
+        AND tp.end_date > now()
+        AND tp.status IN ('Default', 'Published')
+        AND ($2 OR tp.is_box_office_only = FALSE)
+        -- Quantity tiers only apply until they have sold through
+        AND (tp.quantity_limit IS NULL OR tp.quantity_limit > ticket_pricing_sold_quantity(tp.id))
+        -- Box Office Pricing, Published, Default, then quantity tiers in rank order before date only pricing
+        ORDER BY tp.is_box_office_only DESC, tp.status DESC, tp.quantity_limit IS NULL, tp.rank
+        LIMIT 1
+    );
+END $$ LANGUAGE 'plpgsql';
@@ -0,0 +1,3 @@
+ALTER TABLE user_two_factor_credentials
+    DROP COLUMN failed_attempts,
+    DROP COLUMN locked_until;
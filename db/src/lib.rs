@@ -19,6 +19,7 @@ extern crate backtrace;
 extern crate bigneon_http;
 extern crate chrono;
 extern crate chrono_tz;
+extern crate csv;
 extern crate hex;
 extern crate itertools;
 //#[macro_use]
@@ -33,6 +34,7 @@ extern crate macros;
 
 extern crate rand;
 extern crate ring;
+extern crate zip;
 #[macro_use]
 extern crate embed_dirs_derive;
 extern crate time;
@@ -46,6 +48,7 @@ extern crate serde_json;
 #[macro_use]
 extern crate validator_derive;
 extern crate tari_client;
+extern crate url;
 extern crate validator;
 pub mod models;
 pub mod schema;
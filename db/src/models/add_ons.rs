@@ -0,0 +1,354 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::{self, sql};
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use models::ticket_instances::generate_redeem_key;
+use models::*;
+use schema::{add_on_vouchers, add_ons, order_items, orders};
+use std::cmp;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A non-ticket product such as parking or merchandise sold alongside an event's tickets
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(Event)]
+#[table_name = "add_ons"]
+pub struct AddOn {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub quantity: i64,
+    pub redeemable: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "add_ons"]
+pub struct NewAddOn {
+    pub event_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub quantity: i64,
+    pub redeemable: bool,
+}
+
+/// Redeemed by door staff to collect a purchased add-on, one is issued per unit purchased
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(AddOn)]
+#[table_name = "add_on_vouchers"]
+pub struct AddOnVoucher {
+    pub id: Uuid,
+    pub add_on_id: Uuid,
+    pub order_item_id: Uuid,
+    pub user_id: Uuid,
+    pub redeem_key: String,
+    pub status: AddOnVoucherStatus,
+    pub redeemed_at: Option<NaiveDateTime>,
+    pub redeemed_by_user_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AddOnVoucherRedeemResults {
+    VoucherRedeemSuccess,
+    VoucherAlreadyRedeemed,
+    VoucherInvalid,
+}
+
+#[derive(Insertable)]
+#[table_name = "add_on_vouchers"]
+struct NewAddOnVoucher {
+    add_on_id: Uuid,
+    order_item_id: Uuid,
+    user_id: Uuid,
+    redeem_key: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct DisplayAddOn {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub available: i64,
+    pub redeemable: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct UpdateAddOnItem {
+    pub add_on_id: Uuid,
+    pub quantity: u32,
+}
+
+impl NewAddOn {
+    pub fn commit(
+        &self,
+        created_by_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<AddOn, DatabaseError> {
+        if self.name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Add-on name is required");
+        }
+        if self.price_in_cents < 0 {
+            return DatabaseError::validation_error("price_in_cents", "Price cannot be negative");
+        }
+        if self.quantity < 0 {
+            return DatabaseError::validation_error("quantity", "Quantity cannot be negative");
+        }
+
+        let add_on: AddOn = diesel::insert_into(add_ons::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create add-on")?;
+
+        DomainEvent::create(
+            DomainEventTypes::AddOnCreated,
+            "Add-on created".to_string(),
+            Tables::AddOns,
+            Some(add_on.id),
+            Some(created_by_user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(add_on)
+    }
+}
+
+impl AddOn {
+    pub fn create(
+        event_id: Uuid,
+        name: String,
+        description: Option<String>,
+        price_in_cents: i64,
+        quantity: i64,
+        redeemable: bool,
+    ) -> NewAddOn {
+        NewAddOn {
+            event_id,
+            name,
+            description,
+            price_in_cents,
+            quantity,
+            redeemable,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<AddOn, DatabaseError> {
+        add_ons::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load add-on")
+    }
+
+    pub fn find_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<AddOn>, DatabaseError> {
+        add_ons::table
+            .filter(add_ons::event_id.eq(event_id))
+            .order_by(add_ons::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load add-ons")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    /// Add-ons sold or held in unexpired carts
+    pub fn sold_quantity(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        order_items::table
+            .inner_join(orders::table)
+            .filter(order_items::add_on_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::AddOn))
+            .filter(
+                orders::status
+                    .eq_any(vec![OrderStatus::Paid, OrderStatus::PendingPayment])
+                    .or(orders::status
+                        .eq(OrderStatus::Draft)
+                        .and(orders::expires_at.gt(dsl::now.nullable()))),
+            )
+            .select(sql::<BigInt>(
+                "CAST(COALESCE(SUM(order_items.quantity - order_items.refunded_quantity), 0) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load sold add-on quantity")
+    }
+
+    pub fn available_quantity(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(cmp::max(0, self.quantity - self.sold_quantity(conn)?))
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayAddOn, DatabaseError> {
+        Ok(DisplayAddOn {
+            id: self.id,
+            event_id: self.event_id,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            price_in_cents: self.price_in_cents,
+            available: self.available_quantity(conn)?,
+            redeemable: self.redeemable,
+        })
+    }
+}
+
+impl AddOnVoucher {
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<AddOnVoucher, DatabaseError> {
+        add_on_vouchers::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load add-on voucher")
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<AddOnVoucher>, DatabaseError> {
+        add_on_vouchers::table
+            .filter(add_on_vouchers::user_id.eq(user_id))
+            .order_by(add_on_vouchers::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load add-on vouchers")
+    }
+
+    pub fn find_for_order_item(
+        order_item_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<AddOnVoucher>, DatabaseError> {
+        add_on_vouchers::table
+            .filter(add_on_vouchers::order_item_id.eq(order_item_id))
+            .order_by(add_on_vouchers::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load add-on vouchers")
+    }
+
+    /// Issues a voucher per unit of a paid add-on order item that is redeemed at the door
+    pub(crate) fn issue_for_order_item(
+        order_item: &OrderItem,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<AddOnVoucher>, DatabaseError> {
+        let add_on = match order_item.add_on_id {
+            Some(add_on_id) => AddOn::find(add_on_id, conn)?,
+            None => return DatabaseError::no_results("Order item does not have a valid add-on"),
+        };
+        if !add_on.redeemable {
+            return Ok(vec![]);
+        }
+
+        let vouchers: Vec<NewAddOnVoucher> = (0..order_item.quantity)
+            .map(|_| NewAddOnVoucher {
+                add_on_id: add_on.id,
+                order_item_id: order_item.id,
+                user_id,
+                redeem_key: generate_redeem_key(9),
+            })
+            .collect();
+        diesel::insert_into(add_on_vouchers::table)
+            .values(&vouchers)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create add-on vouchers")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        AddOn::find(self.add_on_id, conn)?.event(conn)
+    }
+
+    /// Vouchers of the event's add-ons that can be scanned at the door
+    pub fn find_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<AddOnVoucher>, DatabaseError> {
+        add_on_vouchers::table
+            .inner_join(add_ons::table)
+            .filter(add_ons::event_id.eq(event_id))
+            .filter(add_on_vouchers::status.eq_any(vec![
+                AddOnVoucherStatus::Purchased,
+                AddOnVoucherStatus::Redeemed,
+            ]))
+            .order_by(add_on_vouchers::id)
+            .select(add_on_vouchers::all_columns)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load add-on vouchers")
+    }
+
+    /// Voids a voucher of a refunded add-on unit so it can no longer be redeemed. Refunds of
+    /// add-ons whose vouchers were all redeemed leave the vouchers as they are.
+    pub(crate) fn void_for_refund(
+        order_item: &OrderItem,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let voucher_id: Option<Uuid> = add_on_vouchers::table
+            .filter(add_on_vouchers::order_item_id.eq(order_item.id))
+            .filter(add_on_vouchers::status.eq(AddOnVoucherStatus::Purchased))
+            .order_by(add_on_vouchers::created_at)
+            .select(add_on_vouchers::id)
+            .for_update()
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load add-on voucher")?;
+        if let Some(voucher_id) = voucher_id {
+            diesel::update(add_on_vouchers::table.filter(add_on_vouchers::id.eq(voucher_id)))
+                .set((
+                    add_on_vouchers::status.eq(AddOnVoucherStatus::Voided),
+                    add_on_vouchers::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not void add-on voucher")?;
+        }
+        Ok(())
+    }
+
+    pub fn redeem(
+        &self,
+        redeem_key: &str,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<AddOnVoucherRedeemResults, DatabaseError> {
+        if self.status == AddOnVoucherStatus::Voided || self.redeem_key != redeem_key {
+            return Ok(AddOnVoucherRedeemResults::VoucherInvalid);
+        }
+
+        // Only a purchased voucher is redeemed so concurrent scans cannot both succeed
+        let rows_affected = diesel::update(
+            add_on_vouchers::table
+                .filter(add_on_vouchers::id.eq(self.id))
+                .filter(add_on_vouchers::status.eq(AddOnVoucherStatus::Purchased)),
+        )
+        .set((
+            add_on_vouchers::status.eq(AddOnVoucherStatus::Redeemed),
+            add_on_vouchers::redeemed_at.eq(dsl::now.nullable()),
+            add_on_vouchers::redeemed_by_user_id.eq(current_user_id),
+            add_on_vouchers::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not redeem add-on voucher")?;
+        if rows_affected == 0 {
+            return Ok(AddOnVoucherRedeemResults::VoucherAlreadyRedeemed);
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::AddOnVoucherRedeemed,
+            "Add-on voucher redeemed".to_string(),
+            Tables::AddOnVouchers,
+            Some(self.id),
+            Some(current_user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(AddOnVoucherRedeemResults::VoucherRedeemSuccess)
+    }
+}
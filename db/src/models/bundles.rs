@@ -0,0 +1,469 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::{self, sql};
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use models::*;
+use schema::{bundle_pricing, bundle_ticket_types, bundles, order_items, orders};
+use std::cmp;
+use utils::errors::*;
+use uuid::Uuid;
+use validators;
+
+/// A product sold by an organization that includes tickets for one or more events, such as a
+/// festival or season pass
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(Organization)]
+#[table_name = "bundles"]
+pub struct Bundle {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub quantity: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "bundles"]
+pub struct NewBundle {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub quantity: i64,
+}
+
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(Bundle)]
+#[table_name = "bundle_ticket_types"]
+pub struct BundleTicketType {
+    pub id: Uuid,
+    pub bundle_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub quantity: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "bundle_ticket_types"]
+pub struct NewBundleTicketType {
+    pub bundle_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub quantity: i64,
+}
+
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(Bundle)]
+#[table_name = "bundle_pricing"]
+pub struct BundlePricing {
+    pub id: Uuid,
+    pub bundle_id: Uuid,
+    pub name: String,
+    pub price_in_cents: i64,
+    pub start_date: NaiveDateTime,
+    pub end_date: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "bundle_pricing"]
+pub struct NewBundlePricing {
+    pub bundle_id: Uuid,
+    pub name: String,
+    pub price_in_cents: i64,
+    pub start_date: NaiveDateTime,
+    pub end_date: NaiveDateTime,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct DisplayBundle {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub quantity: i64,
+    pub available: i64,
+    pub price_in_cents: Option<i64>,
+    pub ticket_types: Vec<BundleTicketType>,
+    pub pricing: Vec<BundlePricing>,
+}
+
+impl NewBundle {
+    pub fn commit(
+        &self,
+        created_by_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Bundle, DatabaseError> {
+        if self.name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Bundle name is required");
+        }
+        if self.quantity < 0 {
+            return DatabaseError::validation_error("quantity", "Quantity cannot be negative");
+        }
+
+        let bundle: Bundle = diesel::insert_into(bundles::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create bundle")?;
+
+        DomainEvent::create(
+            DomainEventTypes::BundleCreated,
+            "Bundle created".to_string(),
+            Tables::Bundles,
+            Some(bundle.id),
+            Some(created_by_user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(bundle)
+    }
+}
+
+impl Bundle {
+    pub fn create(
+        organization_id: Uuid,
+        name: String,
+        description: Option<String>,
+        quantity: i64,
+    ) -> NewBundle {
+        NewBundle {
+            organization_id,
+            name,
+            description,
+            quantity,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Bundle, DatabaseError> {
+        bundles::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load bundle")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Bundle>, DatabaseError> {
+        bundles::table
+            .filter(bundles::organization_id.eq(organization_id))
+            .order_by(bundles::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load bundles")
+    }
+
+    /// Includes `quantity` tickets of the ticket type in each bundle sold. All included events
+    /// must belong to the bundle's organization and be priced in the same currency
+    pub fn add_ticket_type(
+        &self,
+        ticket_type_id: Uuid,
+        quantity: i64,
+        conn: &PgConnection,
+    ) -> Result<BundleTicketType, DatabaseError> {
+        if quantity <= 0 {
+            return DatabaseError::validation_error("quantity", "Quantity must be at least 1");
+        }
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let event = Event::find(ticket_type.event_id, conn)?;
+        if event.organization_id != self.organization_id {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Ticket type must belong to an event of the bundle's organization",
+            );
+        }
+        for included in self.ticket_types(conn)? {
+            let included_event = Event::find(
+                TicketType::find(included.ticket_type_id, conn)?.event_id,
+                conn,
+            )?;
+            if included_event.currency != event.currency {
+                return DatabaseError::validation_error(
+                    "ticket_type_id",
+                    "All events in a bundle must be priced in the same currency",
+                );
+            }
+        }
+
+        diesel::insert_into(bundle_ticket_types::table)
+            .values(NewBundleTicketType {
+                bundle_id: self.id,
+                ticket_type_id,
+                quantity,
+            })
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not add ticket type to bundle",
+            )
+    }
+
+    pub fn add_pricing(
+        &self,
+        name: String,
+        start_date: NaiveDateTime,
+        end_date: NaiveDateTime,
+        price_in_cents: i64,
+        conn: &PgConnection,
+    ) -> Result<BundlePricing, DatabaseError> {
+        validators::append_validation_error(
+            Ok(()),
+            "start_date",
+            validators::start_date_valid(start_date, end_date),
+        )?;
+        if price_in_cents < 0 {
+            return DatabaseError::validation_error("price_in_cents", "Price cannot be negative");
+        }
+        if self
+            .pricing(conn)?
+            .iter()
+            .any(|p| p.start_date < end_date && start_date < p.end_date)
+        {
+            return DatabaseError::validation_error(
+                "start_date",
+                "Bundle pricing periods cannot overlap",
+            );
+        }
+
+        diesel::insert_into(bundle_pricing::table)
+            .values(NewBundlePricing {
+                bundle_id: self.id,
+                name,
+                price_in_cents,
+                start_date,
+                end_date,
+            })
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create bundle pricing")
+    }
+
+    pub fn ticket_types(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<BundleTicketType>, DatabaseError> {
+        bundle_ticket_types::table
+            .filter(bundle_ticket_types::bundle_id.eq(self.id))
+            .order_by(bundle_ticket_types::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load bundle ticket types")
+    }
+
+    pub fn pricing(&self, conn: &PgConnection) -> Result<Vec<BundlePricing>, DatabaseError> {
+        bundle_pricing::table
+            .filter(bundle_pricing::bundle_id.eq(self.id))
+            .order_by(bundle_pricing::start_date)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load bundle pricing")
+    }
+
+    pub fn current_pricing(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Option<BundlePricing>, DatabaseError> {
+        bundle_pricing::table
+            .filter(bundle_pricing::bundle_id.eq(self.id))
+            .filter(bundle_pricing::start_date.le(dsl::now))
+            .filter(bundle_pricing::end_date.gt(dsl::now))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load bundle pricing")
+    }
+
+    /// Bundles are charged fees using the organization's fee schedule
+    pub fn fee_schedule(&self, conn: &PgConnection) -> Result<FeeSchedule, DatabaseError> {
+        let organization = Organization::find(self.organization_id, conn)?;
+        FeeSchedule::find(organization.fee_schedule_id, conn)
+    }
+
+    /// Bundles sold or held in unexpired carts
+    pub fn sold_quantity(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        order_items::table
+            .inner_join(orders::table)
+            .filter(order_items::bundle_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Bundle))
+            .filter(
+                orders::status
+                    .eq_any(vec![OrderStatus::Paid, OrderStatus::PendingPayment])
+                    .or(orders::status
+                        .eq(OrderStatus::Draft)
+                        .and(orders::expires_at.gt(dsl::now.nullable()))),
+            )
+            .select(sql::<BigInt>(
+                "CAST(COALESCE(SUM(order_items.quantity - order_items.refunded_quantity), 0) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load sold bundle quantity")
+    }
+
+    pub fn available_quantity(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(cmp::max(0, self.quantity - self.sold_quantity(conn)?))
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayBundle, DatabaseError> {
+        Ok(DisplayBundle {
+            id: self.id,
+            organization_id: self.organization_id,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            quantity: self.quantity,
+            available: self.available_quantity(conn)?,
+            price_in_cents: self.current_pricing(conn)?.map(|p| p.price_in_cents),
+            ticket_types: self.ticket_types(conn)?,
+            pricing: self.pricing(conn)?,
+        })
+    }
+
+    /// Replaces any of this bundle already in the cart with `quantity` bundles, reserving the
+    /// included tickets of every event for the buyer until the cart expires
+    pub fn add_to_cart(
+        &self,
+        cart: &mut Order,
+        quantity: u32,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<OrderItem>, DatabaseError> {
+        if cart.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Bundles can only be added to a cart");
+        }
+
+        cart.lock_version(conn)?;
+        for item in cart.items(conn)? {
+            if item.item_type == OrderItemTypes::Bundle && item.bundle_id == Some(self.id) {
+                cart.remove_bundle_item(&item, current_user_id, conn)?;
+            }
+        }
+        if quantity == 0 {
+            if cart.items(conn)?.is_empty() {
+                cart.remove_expiry(current_user_id, conn)?;
+            }
+            cart.update_fees(conn)?;
+            return Ok(None);
+        }
+
+        let pricing = match self.current_pricing(conn)? {
+            Some(pricing) => pricing,
+            None => {
+                return DatabaseError::business_process_error("Bundle is not currently on sale")
+            }
+        };
+        self.lock(conn)?;
+        if self.available_quantity(conn)? < quantity as i64 {
+            return DatabaseError::validation_error("quantity", "Not enough bundles are available");
+        }
+        let bundle_ticket_types = self.ticket_types(conn)?;
+        if bundle_ticket_types.is_empty() {
+            return DatabaseError::business_process_error("Bundle does not include any tickets");
+        }
+        let (remainder_in_cents, ticket_prices) =
+            self.allocate_price(pricing.price_in_cents, &bundle_ticket_types, conn)?;
+
+        if cart.expires_at.is_none() {
+            cart.set_expiry(Some(current_user_id), None, conn)?;
+        }
+
+        let bundle_item = NewBundleOrderItem {
+            order_id: cart.id,
+            item_type: OrderItemTypes::Bundle,
+            ticket_type_id: None,
+            event_id: None,
+            quantity: quantity as i64,
+            unit_price_in_cents: remainder_in_cents,
+            parent_id: None,
+            bundle_id: self.id,
+        }
+        .commit(conn)?;
+
+        for (index, (bundle_ticket_type, ticket_type, unit_price_in_cents)) in
+            ticket_prices.into_iter().enumerate()
+        {
+            if index == 0 {
+                let event = Event::find(ticket_type.event_id, conn)?;
+                cart.set_currency(&event.currency, conn)?;
+            }
+            // Included tickets are charged fees through the bundle item
+            let ticket_quantity = quantity as i64 * bundle_ticket_type.quantity;
+            let tickets_item = NewBundleOrderItem {
+                order_id: cart.id,
+                item_type: OrderItemTypes::Tickets,
+                ticket_type_id: Some(ticket_type.id),
+                event_id: Some(ticket_type.event_id),
+                quantity: ticket_quantity,
+                unit_price_in_cents,
+                parent_id: Some(bundle_item.id),
+                bundle_id: self.id,
+            }
+            .commit(conn)?;
+            TicketInstance::reserve_tickets(
+                &tickets_item,
+                cart.expires_at,
+                ticket_type.id,
+                None,
+                ticket_quantity as u32,
+                conn,
+            )?;
+        }
+
+        cart.update_fees(conn)?;
+
+        Ok(Some(bundle_item))
+    }
+
+    /// Splits the bundle price between the included tickets in proportion to their current
+    /// prices, or evenly if none are priced, so each event is credited with its share of the
+    /// sale. Returns the cents left over from rounding, which stay on the bundle item, and the
+    /// unit price of each included ticket type.
+    fn allocate_price(
+        &self,
+        price_in_cents: i64,
+        bundle_ticket_types: &[BundleTicketType],
+        conn: &PgConnection,
+    ) -> Result<(i64, Vec<(BundleTicketType, TicketType, i64)>), DatabaseError> {
+        let mut weighted = Vec::new();
+        for bundle_ticket_type in bundle_ticket_types {
+            let ticket_type = TicketType::find(bundle_ticket_type.ticket_type_id, conn)?;
+            let weight = ticket_type
+                .current_ticket_pricing(false, conn)
+                .optional()?
+                .map(|p| p.price_in_cents)
+                .unwrap_or(0);
+            weighted.push((bundle_ticket_type.clone(), ticket_type, weight));
+        }
+        if weighted.iter().all(|&(_, _, weight)| weight <= 0) {
+            for entry in weighted.iter_mut() {
+                entry.2 = 1;
+            }
+        }
+        let total_weight: i64 = weighted
+            .iter()
+            .map(|&(ref bundle_ticket_type, _, weight)| bundle_ticket_type.quantity * weight)
+            .sum();
+
+        let mut remainder_in_cents = price_in_cents;
+        let mut ticket_prices = Vec::new();
+        for (bundle_ticket_type, ticket_type, weight) in weighted {
+            let unit_price_in_cents = price_in_cents * weight / total_weight;
+            remainder_in_cents -= unit_price_in_cents * bundle_ticket_type.quantity;
+            ticket_prices.push((bundle_ticket_type, ticket_type, unit_price_in_cents));
+        }
+        Ok((remainder_in_cents, ticket_prices))
+    }
+
+    /// Locks the bundle until the transaction ends so concurrent carts cannot oversell it
+    fn lock(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        bundles::table
+            .find(self.id)
+            .for_update()
+            .first::<Bundle>(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::QueryError, "Could not lock bundle")
+    }
+}
@@ -0,0 +1,199 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use models::*;
+use schema::communication_logs;
+use serde_json;
+use utils::errors::*;
+use utils::text;
+use uuid::Uuid;
+
+/// One message sent to one destination, kept so support can see what a fan was sent
+#[derive(Clone, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "communication_logs"]
+pub struct CommunicationLog {
+    pub id: Uuid,
+    pub domain_action_id: Option<Uuid>,
+    pub channel: CommunicationChannelType,
+    pub template_id: Option<String>,
+    pub title: String,
+    pub destination: String,
+    pub main_table: Option<String>,
+    pub main_table_id: Option<Uuid>,
+    pub provider_message_id: Option<String>,
+    pub status: CommunicationLogStatus,
+    pub status_updated_at: Option<NaiveDateTime>,
+    /// The queued communication, used to resend it
+    pub payload: serde_json::Value,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "communication_logs"]
+pub struct NewCommunicationLog {
+    pub domain_action_id: Option<Uuid>,
+    pub channel: CommunicationChannelType,
+    pub template_id: Option<String>,
+    pub title: String,
+    pub destination: String,
+    pub main_table: Option<String>,
+    pub main_table_id: Option<Uuid>,
+    pub payload: serde_json::Value,
+    pub status: CommunicationLogStatus,
+}
+
+impl NewCommunicationLog {
+    pub fn commit(&self, conn: &PgConnection) -> Result<CommunicationLog, DatabaseError> {
+        diesel::insert_into(communication_logs::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create communication log")
+    }
+}
+
+impl CommunicationLog {
+    pub fn create(
+        domain_action_id: Option<Uuid>,
+        channel: CommunicationChannelType,
+        template_id: Option<String>,
+        title: String,
+        destination: String,
+        main_table: Option<String>,
+        main_table_id: Option<Uuid>,
+        payload: serde_json::Value,
+    ) -> NewCommunicationLog {
+        NewCommunicationLog {
+            domain_action_id,
+            channel,
+            template_id,
+            title,
+            destination,
+            main_table,
+            main_table_id,
+            payload,
+            status: CommunicationLogStatus::Sent,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<CommunicationLog, DatabaseError> {
+        communication_logs::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load communication log")
+    }
+
+    pub fn find_by_provider_message_id(
+        provider_message_id: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<CommunicationLog>, DatabaseError> {
+        communication_logs::table
+            .filter(communication_logs::provider_message_id.eq(provider_message_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load communication log")
+    }
+
+    /// Searches by destination or title, optionally narrowed to the order or ticket it relates to
+    pub fn search(
+        query: Option<String>,
+        main_table_id: Option<Uuid>,
+        status: Option<CommunicationLogStatus>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<CommunicationLog>, DatabaseError> {
+        let query_like = query.map(|q| format!("%{}%", text::escape_control_chars(&q)));
+        let total: i64 = CommunicationLog::filtered(&query_like, main_table_id, status)
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count communication logs")?;
+
+        let logs = CommunicationLog::filtered(&query_like, main_table_id, status)
+            .order_by(communication_logs::created_at.desc())
+            .limit(limit as i64)
+            .offset((page * limit) as i64)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not search communication logs")?;
+
+        let mut payload = Payload::new(logs, Paging::new(page, limit));
+        payload.paging.total = total as u64;
+        Ok(payload)
+    }
+
+    fn filtered<'a>(
+        query_like: &'a Option<String>,
+        main_table_id: Option<Uuid>,
+        status: Option<CommunicationLogStatus>,
+    ) -> communication_logs::BoxedQuery<'a, Pg> {
+        let mut query = communication_logs::table.into_boxed();
+        if let Some(query_like) = query_like {
+            query = query.filter(
+                communication_logs::destination
+                    .ilike(query_like)
+                    .or(communication_logs::title.ilike(query_like)),
+            );
+        }
+        if let Some(main_table_id) = main_table_id {
+            query = query.filter(communication_logs::main_table_id.eq(main_table_id));
+        }
+        if let Some(status) = status {
+            query = query.filter(communication_logs::status.eq(status));
+        }
+        query
+    }
+
+    pub fn set_provider_message_id(
+        &self,
+        provider_message_id: &str,
+        conn: &PgConnection,
+    ) -> Result<CommunicationLog, DatabaseError> {
+        diesel::update(self)
+            .set((
+                communication_logs::provider_message_id.eq(provider_message_id),
+                communication_logs::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update communication log")
+    }
+
+    /// Records a delivery status reported by the provider. Provider events can arrive out of
+    /// order, so a status is never replaced by one that comes earlier in a message's life
+    pub fn update_status(
+        &self,
+        status: CommunicationLogStatus,
+        provider_message_id: Option<&str>,
+        conn: &PgConnection,
+    ) -> Result<CommunicationLog, DatabaseError> {
+        if status_rank(status) < status_rank(self.status) {
+            return Ok(self.clone());
+        }
+        let provider_message_id = provider_message_id
+            .map(|id| id.to_string())
+            .or(self.provider_message_id.clone());
+        diesel::update(self)
+            .set((
+                communication_logs::status.eq(status),
+                communication_logs::provider_message_id.eq(provider_message_id),
+                communication_logs::status_updated_at.eq(dsl::now.nullable()),
+                communication_logs::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update communication log")
+    }
+}
+
+fn status_rank(status: CommunicationLogStatus) -> u8 {
+    match status {
+        CommunicationLogStatus::Sent => 0,
+        CommunicationLogStatus::Delivered => 1,
+        CommunicationLogStatus::Opened => 2,
+        CommunicationLogStatus::Bounced
+        | CommunicationLogStatus::Dropped
+        | CommunicationLogStatus::Failed => 3,
+        // Nothing was handed to a provider so there is no later status
+        CommunicationLogStatus::Blocked => 4,
+    }
+}
@@ -0,0 +1,312 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Nullable, Text, Timestamp, Uuid as dUuid};
+use models::*;
+use schema::disputes;
+use std::collections::HashMap;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(Payment)]
+#[belongs_to(Order)]
+#[table_name = "disputes"]
+pub struct Dispute {
+    pub id: Uuid,
+    pub payment_id: Uuid,
+    pub order_id: Uuid,
+    pub external_reference: String,
+    pub amount: i64,
+    pub reason: Option<String>,
+    pub status: DisputeStatus,
+    pub evidence_due_by: Option<NaiveDateTime>,
+    pub held_ticket_instance_ids: Vec<Uuid>,
+    pub closed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub evidence_notes: Option<String>,
+    pub evidence_submitted_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "disputes"]
+pub struct NewDispute {
+    pub payment_id: Uuid,
+    pub order_id: Uuid,
+    pub external_reference: String,
+    pub amount: i64,
+    pub reason: Option<String>,
+    pub evidence_due_by: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct DisplayDispute {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub payment_id: Uuid,
+    #[sql_type = "dUuid"]
+    pub order_id: Uuid,
+    #[sql_type = "Text"]
+    pub external_reference: String,
+    #[sql_type = "BigInt"]
+    pub amount: i64,
+    #[sql_type = "Nullable<Text>"]
+    pub reason: Option<String>,
+    #[sql_type = "Text"]
+    pub status: DisputeStatus,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub evidence_due_by: Option<NaiveDateTime>,
+    #[sql_type = "Array<dUuid>"]
+    pub held_ticket_instance_ids: Vec<Uuid>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub closed_at: Option<NaiveDateTime>,
+    #[sql_type = "Timestamp"]
+    pub created_at: NaiveDateTime,
+    #[sql_type = "Timestamp"]
+    pub updated_at: NaiveDateTime,
+    #[sql_type = "Nullable<Text>"]
+    pub evidence_notes: Option<String>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub evidence_submitted_at: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<Text>"]
+    pub currency: Option<String>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub paid_at: Option<NaiveDateTime>,
+    #[sql_type = "dUuid"]
+    pub user_id: Uuid,
+    #[sql_type = "Nullable<Text>"]
+    pub first_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub last_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub email: Option<String>,
+}
+
+impl NewDispute {
+    /// Records the dispute and holds the order's tickets by nullifying them until it is resolved
+    pub fn commit(&self, conn: &PgConnection) -> Result<Dispute, DatabaseError> {
+        if self.amount <= 0 {
+            return DatabaseError::validation_error("amount", "Amount must be greater than 0");
+        }
+
+        let mut held_ticket_instance_ids = Vec::new();
+        for ticket_instance_id in TicketInstance::find_ids_for_order(self.order_id, conn)? {
+            let ticket_instance = TicketInstance::find(ticket_instance_id, conn)?;
+            if ticket_instance.status == TicketInstanceStatus::Purchased {
+                ticket_instance.nullify(None, conn)?;
+                held_ticket_instance_ids.push(ticket_instance.id);
+            }
+        }
+
+        let dispute: Dispute = diesel::insert_into(disputes::table)
+            .values((
+                self,
+                disputes::status.eq(DisputeStatus::Open),
+                disputes::held_ticket_instance_ids.eq(held_ticket_instance_ids),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create dispute")?;
+
+        DomainEvent::create(
+            DomainEventTypes::DisputeCreated,
+            "Dispute opened".to_string(),
+            Tables::Disputes,
+            Some(dispute.id),
+            None,
+            Some(json!({
+                "payment_id": dispute.payment_id,
+                "order_id": dispute.order_id,
+                "amount": dispute.amount,
+                "held_ticket_instance_ids": dispute.held_ticket_instance_ids
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(dispute)
+    }
+}
+
+impl Dispute {
+    pub fn create(
+        payment: &Payment,
+        external_reference: String,
+        amount: i64,
+        reason: Option<String>,
+        evidence_due_by: Option<NaiveDateTime>,
+    ) -> NewDispute {
+        NewDispute {
+            payment_id: payment.id,
+            order_id: payment.order_id,
+            external_reference,
+            amount,
+            reason,
+            evidence_due_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Dispute, DatabaseError> {
+        disputes::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load dispute")
+    }
+
+    pub fn find_by_external_reference(
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<Dispute>, DatabaseError> {
+        disputes::table
+            .filter(disputes::external_reference.eq(external_reference))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load dispute")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        status: Option<DisputeStatus>,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplayDispute>, DatabaseError> {
+        let query = include_str!("../queries/retrieve_disputes_for_organization.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(organization_id)
+            .bind::<Nullable<Text>, _>(status.map(|s| s.to_string()))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load disputes")
+    }
+
+    /// Amounts withheld from and returned to each of an organization's events for disputes in a
+    /// settlement period, as `(withheld, returned)`. Disputes opened in the period are withheld
+    /// while undecided and disputes opened before the period are returned once won.
+    pub fn amounts_per_event(
+        organization_id: Uuid,
+        start_time: NaiveDateTime,
+        end_time: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<HashMap<Uuid, (i64, i64)>, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "dUuid"]
+            event_id: Uuid,
+            #[sql_type = "BigInt"]
+            withheld_in_cents: i64,
+            #[sql_type = "BigInt"]
+            returned_in_cents: i64,
+        }
+
+        let query = include_str!("../queries/dispute_amounts_per_event.sql");
+        let rows: Vec<R> = diesel::sql_query(query)
+            .bind::<dUuid, _>(organization_id)
+            .bind::<Timestamp, _>(start_time)
+            .bind::<Timestamp, _>(end_time)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load disputed amounts")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.event_id, (r.withheld_in_cents, r.returned_in_cents)))
+            .collect())
+    }
+
+    /// Records the evidence sent to the payment provider and marks the dispute as awaiting the
+    /// provider's decision
+    pub fn submit_evidence(
+        &self,
+        evidence_notes: String,
+        conn: &PgConnection,
+    ) -> Result<Dispute, DatabaseError> {
+        if self.is_closed() {
+            return DatabaseError::business_process_error("Dispute has already been closed");
+        }
+
+        let dispute: Dispute = diesel::update(self)
+            .set((
+                disputes::evidence_notes.eq(Some(evidence_notes)),
+                disputes::evidence_submitted_at.eq(Some(Utc::now().naive_utc())),
+                disputes::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update dispute")?;
+        dispute.update_status(DisputeStatus::EvidenceSubmitted, conn)
+    }
+
+    /// Whether the disputed order includes events of the organization
+    pub fn belongs_to_organization(
+        &self,
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        Ok(Order::find(self.order_id, conn)?
+            .organizations(conn)?
+            .iter()
+            .any(|organization| organization.id == organization_id))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.status == DisputeStatus::Won || self.status == DisputeStatus::Lost
+    }
+
+    /// Moves the dispute to a new status. Held tickets are returned to their owners when the
+    /// dispute is won; tickets of a lost dispute remain nullified to be refunded with the order.
+    pub fn update_status(
+        &self,
+        status: DisputeStatus,
+        conn: &PgConnection,
+    ) -> Result<Dispute, DatabaseError> {
+        if self.status == status {
+            return Ok(self.clone());
+        }
+        if self.is_closed() {
+            return DatabaseError::business_process_error("Dispute has already been closed");
+        }
+
+        let closed_at = match status {
+            DisputeStatus::Won | DisputeStatus::Lost => Some(Utc::now().naive_utc()),
+            _ => None,
+        };
+        let dispute: Dispute = diesel::update(self)
+            .set((
+                disputes::status.eq(status),
+                disputes::closed_at.eq(closed_at),
+                disputes::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update dispute")?;
+
+        if status == DisputeStatus::Won {
+            let refunded_ticket_ids: Vec<Uuid> = RefundedTicket::find_by_ticket_instance_ids(
+                self.held_ticket_instance_ids.clone(),
+                conn,
+            )?
+            .into_iter()
+            .filter(|r| r.ticket_refunded_at.is_some())
+            .map(|r| r.ticket_instance_id)
+            .collect();
+            for ticket_instance_id in self.held_ticket_instance_ids.iter() {
+                let ticket_instance = TicketInstance::find(*ticket_instance_id, conn)?;
+                if ticket_instance.status == TicketInstanceStatus::Nullified
+                    && !refunded_ticket_ids.contains(ticket_instance_id)
+                {
+                    ticket_instance.reinstate(None, conn)?;
+                }
+            }
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::DisputeStatusUpdated,
+            format!("Dispute status updated to {}", status),
+            Tables::Disputes,
+            Some(self.id),
+            None,
+            Some(json!({ "old_status": self.status, "new_status": status })),
+        )
+        .commit(conn)?;
+
+        Ok(dispute)
+    }
+}
@@ -5,6 +5,7 @@ use diesel::prelude::*;
 use models::enums::*;
 use schema::*;
 use serde_json;
+use std::cmp;
 use utils::errors::*;
 use uuid::Uuid;
 
@@ -155,8 +156,9 @@ impl DomainAction {
 
     /// Use this method if there was a transient failure in performing the action. In
     /// general, it is assumed that the action will succeed at a later stage. If the
-    /// action should not be retried, use `errored` instead. If the number of retries
-    /// is exceeded, the status will changed to `RetriedExceeded`.
+    /// action should not be retried, use `errored` instead. Webhook deliveries are retried
+    /// with a backoff that doubles with each attempt. If the number of retries is exceeded,
+    /// the status will changed to `RetriedExceeded`.
     pub fn set_failed(
         &self,
         reason: &str,
@@ -173,6 +175,19 @@ impl DomainAction {
                 ))
                 .get_result(conn)
                 .to_db_error(ErrorCode::UpdateError, "Could not update Domain Action")
+        } else if self.domain_action_type == DomainActionTypes::SendWebhook {
+            // Subscriber endpoints that are down are given longer to recover with each attempt
+            let backoff_in_minutes = 2i64.pow(cmp::min(self.attempt_count, 6) as u32);
+            diesel::update(self)
+                .set((
+                    domain_actions::last_failure_reason.eq(reason),
+                    domain_actions::attempt_count.eq(self.attempt_count + 1),
+                    domain_actions::blocked_until
+                        .eq(Utc::now().naive_utc() + Duration::minutes(backoff_in_minutes)),
+                    domain_actions::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update Domain Action")
         } else {
             // Intentionally leave checked out
             diesel::update(self)
@@ -68,6 +68,13 @@ impl DomainEvent {
             .to_db_error(ErrorCode::QueryError, "Could not load domain events")
     }
 
+    pub fn find_by_id(id: Uuid, conn: &PgConnection) -> Result<DomainEvent, DatabaseError> {
+        domain_events::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load domain event")
+    }
+
     pub fn find_unpublished(
         limit: u32,
         conn: &PgConnection,
@@ -68,16 +68,29 @@ macro_rules! string_enum {
     }
 }
 
+string_enum! { AddOnVoucherStatus [Purchased, Redeemed, Voided] }
 string_enum! { AssetStatus [Unsynced] }
-string_enum! { CartItemStatus [CodeExpired, HoldExpired, TicketNullified, TicketNotReserved, Valid] }
+string_enum! { CartItemStatus [CodeExpired, HoldExpired, ResaleListingUnavailable, TicketNullified, TicketNotReserved, Valid] }
 string_enum! { CodeTypes [Access, Discount] }
 string_enum! { CommunicationChannelType [Email, Sms, Push]}
+string_enum! { CommunicationLogStatus [Sent, Delivered, Opened, Bounced, Dropped, Failed, Blocked] }
+string_enum! { DisputeStatus [Open, EvidenceSubmitted, Won, Lost] }
 string_enum! { DomainEventTypes [
+    AddOnCreated,
+    AddOnVoucherRedeemed,
+    BundleCreated,
+    DisputeCreated,
+    DisputeStatusUpdated,
+    EventCancellationOrderRefunded,
+    EventCancelled,
+    EventRescheduled,
+    EventRescheduleRefunded,
     FeeScheduleCreated,
     OrderBehalfOfUserChanged,
     OrderCompleted,
     OrderCreated,
     OrderStatusUpdated,
+    OrderTicketsExchanged,
     OrderUpdated,
     OrganizationCreated,
     PaymentCancelled,
@@ -86,17 +99,26 @@ string_enum! { DomainEventTypes [
     PaymentRefund,
     PaymentProviderIPN,
     PaymentMethodCreated,
+    PaymentMethodDeleted,
     PaymentMethodUpdated,
     PaymentUpdated,
     UserLogin,
     UserRegistration,
     LostPassword,
     PurchaseCompleted,
+    ResaleListingCancelled,
+    ResaleListingCreated,
+    ResaleListingRefunded,
+    ResaleListingSold,
+    ResalePayoutPaid,
+    TaxRateCreated,
+    TaxRateDeleted,
     TransferTicketStarted,
     TransferTicketCompleted,
     TicketInstanceNullified,
     TicketInstancePurchased,
-    TicketInstanceRedeemed
+    TicketInstanceRedeemed,
+    WaitlistTicketsOffered
 ]}
 string_enum! { DomainActionTypes [
     // Email/SMS/Push Communication
@@ -105,10 +127,27 @@ string_enum! { DomainActionTypes [
     MarketingContactsCreateEventList,
     MarketingContactsBulkEventFanListImport,
     PaymentProviderIPN,
-    SendPurchaseCompletedCommunication
+    // Event cancellation refunds
+    ProcessEventCancellation,
+    ProcessStripeWebhook,
+    // Waitlist
+    ProcessWaitlist,
+    // Reminders and follow-ups configured by the organization, sent around each event
+    SendEventCommunication,
+    // Push notification reminders sent to ticket holders on the day of the event
+    SendEventDayReminders,
+    SendPurchaseCompletedCommunication,
+    // Reports
+    SendScheduledReport,
+    // Webhooks
+    SendWebhook
 
 ]}
 string_enum! { DomainActionStatus [Pending, RetriesExceeded, Errored, Success, Cancelled]}
+string_enum! { EmailTemplateKinds [PurchaseCompleted, Refund, TransferTickets] }
+string_enum! { EventCancellationStatus [InProgress, Completed, CompletedWithFailures] }
+string_enum! { EventCancellationOrderStatus [Pending, Refunded, Failed] }
+string_enum! { EventCommunicationTimings [BeforeDoors, AfterEvent] }
 string_enum! { EventStatus [Draft,Closed,Published,Offline]}
 string_enum! { EventSearchSortField [ Name, EventStart]}
 string_enum! { EventOverrideStatus [PurchaseTickets,SoldOut,OnSaleSoon,TicketsAtTheDoor,Free,Rescheduled,Cancelled,OffSale,Ended]}
@@ -116,22 +155,28 @@ string_enum! { EventTypes [ Music, Conference]}
 string_enum! { FanSortField [FirstName, LastName, Email, Phone, Orders, FirstOrder, LastOrder, Revenue] }
 string_enum! { HistoryType [Purchase]}
 string_enum! { HoldTypes [Discount, Comp] }
+string_enum! { OfflineRedemptionStatus [Redeemed, AlreadyRedeemed, Invalid] }
 string_enum! { OrderStatus [Cancelled, Draft, Paid, PendingPayment] }
-string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees]}
+string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Resale, Tax, Bundle, AddOn]}
 string_enum! { OrderTypes [Cart, BackOffice] }
 string_enum! { PaymentMethods [External, CreditCard, Provider] }
-string_enum! { PaymentProviders [External, Globee, Stripe] }
-string_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn] }
+string_enum! { PaymentProviders [External, Globee, Paypal, Stripe] }
+string_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn, Disputed] }
 string_enum! { PastOrUpcoming [Past,Upcoming]}
+string_enum! { ReportFrequencies [Daily, Weekly] }
+string_enum! { ReportTypes [EventSummary, TicketCount, WeeklySettlement] }
+string_enum! { ResaleListingStatus [Active, Sold, Cancelled, Refunded] }
+string_enum! { ResalePayoutStatus [Pending, Paid, Cancelled] }
 string_enum! { Roles [Admin, DoorPerson, OrgMember, OrgOwner, OrgAdmin, OrgBoxOffice, Promoter, PromoterReadOnly, User] }
 string_enum! { SettlementStatus[PendingSettlement, RequiresAudit, SettledInFull] }
 string_enum! { SettlementTransactionType[OrderItem, Manual, Report] }
 string_enum! { SortingDir[ Asc, Desc ] }
-string_enum! { Tables [Events, FeeSchedules, Orders, Organizations, Payments, PaymentMethods, TicketInstances, Users] }
+string_enum! { Tables [AddOns, AddOnVouchers, Bundles, Disputes, EventCancellations, Events, FeeSchedules, Orders, Organizations, Payments, PaymentMethods, ReportSubscriptions, ResaleListings, ResalePayouts, TaxRates, TicketInstances, TicketTypes, Users, WaitlistEntries, WebhookSubscriptions] }
 string_enum! { TicketInstanceStatus [Available, Reserved, Purchased, Redeemed, Nullified]}
 string_enum! { TicketPricingStatus [Published, Deleted, Default] }
 string_enum! { TicketTypeStatus [NoActivePricing, Published, SoldOut, Cancelled] }
 string_enum! { SoldOutBehavior[ ShowSoldOut, Hide ]}
+string_enum! { WaitlistEntryStatus [Waiting, Offered, Purchased, Expired, Cancelled] }
 
 impl Roles {
     pub fn get_event_limited_roles() -> Vec<Roles> {
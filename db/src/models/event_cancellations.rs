@@ -0,0 +1,339 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_cancellation_orders, event_cancellations, order_items, orders};
+use time::Duration;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Attempts made at refunding an order before it is left for manual follow up
+pub const EVENT_CANCELLATION_MAX_REFUND_ATTEMPTS: i64 = 3;
+
+/// Refunding of every paid order for a cancelled event, processed in batches by a domain action
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Event)]
+#[table_name = "event_cancellations"]
+pub struct EventCancellation {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub refund_fees: bool,
+    pub status: EventCancellationStatus,
+    pub created_by_user_id: Uuid,
+    pub completed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "event_cancellations"]
+pub struct NewEventCancellation {
+    pub event_id: Uuid,
+    pub refund_fees: bool,
+    pub created_by_user_id: Uuid,
+}
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(EventCancellation)]
+#[table_name = "event_cancellation_orders"]
+pub struct EventCancellationOrder {
+    pub id: Uuid,
+    pub event_cancellation_id: Uuid,
+    pub order_id: Uuid,
+    pub status: EventCancellationOrderStatus,
+    pub attempt_count: i64,
+    pub refunded_amount_in_cents: i64,
+    pub last_failure_reason: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "event_cancellation_orders"]
+pub struct NewEventCancellationOrder {
+    pub event_cancellation_id: Uuid,
+    pub order_id: Uuid,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct EventCancellationProgress {
+    pub event_cancellation: EventCancellation,
+    pub order_count: i64,
+    pub pending_order_count: i64,
+    pub refunded_order_count: i64,
+    pub failed_order_count: i64,
+    pub refunded_amount_in_cents: i64,
+    pub failed_orders: Vec<EventCancellationOrder>,
+}
+
+impl NewEventCancellation {
+    /// Records the paid orders for the event and queues them for refunding
+    pub fn commit(self, conn: &PgConnection) -> Result<EventCancellation, DatabaseError> {
+        let cancellation: EventCancellation = diesel::insert_into(event_cancellations::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create event cancellation",
+            )?;
+
+        let order_ids: Vec<Uuid> = orders::table
+            .inner_join(order_items::table.on(order_items::order_id.eq(orders::id)))
+            .filter(order_items::event_id.eq(cancellation.event_id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tickets))
+            .filter(orders::status.eq(OrderStatus::Paid))
+            .select(orders::id)
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load orders for event")?;
+        let cancellation_orders: Vec<NewEventCancellationOrder> = order_ids
+            .iter()
+            .map(|order_id| NewEventCancellationOrder {
+                event_cancellation_id: cancellation.id,
+                order_id: *order_id,
+            })
+            .collect();
+        if !cancellation_orders.is_empty() {
+            diesel::insert_into(event_cancellation_orders::table)
+                .values(&cancellation_orders)
+                .execute(conn)
+                .to_db_error(
+                    ErrorCode::InsertError,
+                    "Could not create event cancellation orders",
+                )?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::EventCancelled,
+            "Event was cancelled".to_string(),
+            Tables::Events,
+            Some(cancellation.event_id),
+            Some(cancellation.created_by_user_id),
+            Some(json!({
+                "event_cancellation_id": cancellation.id,
+                "refund_fees": cancellation.refund_fees,
+                "order_count": order_ids.len(),
+            })),
+        )
+        .commit(conn)?;
+
+        cancellation.queue_processing(Utc::now().naive_utc(), conn)?;
+
+        Ok(cancellation)
+    }
+}
+
+impl EventCancellation {
+    pub fn create(
+        event_id: Uuid,
+        refund_fees: bool,
+        created_by_user_id: Uuid,
+    ) -> NewEventCancellation {
+        NewEventCancellation {
+            event_id,
+            refund_fees,
+            created_by_user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventCancellation, DatabaseError> {
+        event_cancellations::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event cancellation")
+    }
+
+    pub fn find_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<EventCancellation, DatabaseError> {
+        event_cancellations::table
+            .filter(event_cancellations::event_id.eq(event_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event cancellation")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn queue_processing(
+        &self,
+        scheduled_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<DomainAction, DatabaseError> {
+        DomainAction::create(
+            None,
+            DomainActionTypes::ProcessEventCancellation,
+            None,
+            json!({ "event_cancellation_id": self.id }),
+            Some(Tables::EventCancellations.to_string()),
+            Some(self.id),
+            scheduled_at,
+            scheduled_at + Duration::days(1),
+            3,
+        )
+        .commit(conn)
+    }
+
+    pub fn orders(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventCancellationOrder>, DatabaseError> {
+        event_cancellation_orders::table
+            .filter(event_cancellation_orders::event_cancellation_id.eq(self.id))
+            .order_by(event_cancellation_orders::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load event cancellation orders",
+            )
+    }
+
+    /// Next orders to refund, orders that have not failed before are refunded first
+    pub fn pending_orders(
+        &self,
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventCancellationOrder>, DatabaseError> {
+        event_cancellation_orders::table
+            .filter(event_cancellation_orders::event_cancellation_id.eq(self.id))
+            .filter(event_cancellation_orders::status.eq(EventCancellationOrderStatus::Pending))
+            .order_by((
+                event_cancellation_orders::attempt_count,
+                event_cancellation_orders::created_at,
+            ))
+            .limit(limit)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load event cancellation orders",
+            )
+    }
+
+    /// Marks the cancellation as completed once no orders are left to refund
+    pub fn complete_if_finished(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<EventCancellation, DatabaseError> {
+        let orders = self.orders(conn)?;
+        if orders
+            .iter()
+            .any(|o| o.status == EventCancellationOrderStatus::Pending)
+        {
+            return Ok(self.clone());
+        }
+
+        let status = if orders
+            .iter()
+            .any(|o| o.status == EventCancellationOrderStatus::Failed)
+        {
+            EventCancellationStatus::CompletedWithFailures
+        } else {
+            EventCancellationStatus::Completed
+        };
+        diesel::update(self)
+            .set((
+                event_cancellations::status.eq(status),
+                event_cancellations::completed_at.eq(dsl::now.nullable()),
+                event_cancellations::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update event cancellation",
+            )
+    }
+
+    pub fn progress(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<EventCancellationProgress, DatabaseError> {
+        let orders = self.orders(conn)?;
+        let count = |status: EventCancellationOrderStatus| {
+            orders.iter().filter(|o| o.status == status).count() as i64
+        };
+
+        Ok(EventCancellationProgress {
+            event_cancellation: self.clone(),
+            order_count: orders.len() as i64,
+            pending_order_count: count(EventCancellationOrderStatus::Pending),
+            refunded_order_count: count(EventCancellationOrderStatus::Refunded),
+            failed_order_count: count(EventCancellationOrderStatus::Failed),
+            refunded_amount_in_cents: orders.iter().map(|o| o.refunded_amount_in_cents).sum(),
+            failed_orders: orders
+                .iter()
+                .filter(|o| o.status == EventCancellationOrderStatus::Failed)
+                .cloned()
+                .collect(),
+        })
+    }
+}
+
+impl EventCancellationOrder {
+    pub fn order(&self, conn: &PgConnection) -> Result<Order, DatabaseError> {
+        Order::find(self.order_id, conn)
+    }
+
+    pub fn mark_refunded(
+        &self,
+        refunded_amount_in_cents: i64,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<EventCancellationOrder, DatabaseError> {
+        let cancellation_order: EventCancellationOrder = diesel::update(self)
+            .set((
+                event_cancellation_orders::status.eq(EventCancellationOrderStatus::Refunded),
+                event_cancellation_orders::attempt_count.eq(self.attempt_count + 1),
+                event_cancellation_orders::refunded_amount_in_cents.eq(refunded_amount_in_cents),
+                event_cancellation_orders::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update event cancellation order",
+            )?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventCancellationOrderRefunded,
+            "Order refunded after event was cancelled".to_string(),
+            Tables::Orders,
+            Some(cancellation_order.order_id),
+            Some(current_user_id),
+            Some(json!({
+                "event_cancellation_id": cancellation_order.event_cancellation_id,
+                "refunded_amount_in_cents": refunded_amount_in_cents,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(cancellation_order)
+    }
+
+    /// Records a failed refund attempt, the order is retried until it runs out of attempts
+    pub fn mark_failed(
+        &self,
+        reason: &str,
+        conn: &PgConnection,
+    ) -> Result<EventCancellationOrder, DatabaseError> {
+        let attempt_count = self.attempt_count + 1;
+        let status = if attempt_count >= EVENT_CANCELLATION_MAX_REFUND_ATTEMPTS {
+            EventCancellationOrderStatus::Failed
+        } else {
+            EventCancellationOrderStatus::Pending
+        };
+        diesel::update(self)
+            .set((
+                event_cancellation_orders::status.eq(status),
+                event_cancellation_orders::attempt_count.eq(attempt_count),
+                event_cancellation_orders::last_failure_reason.eq(reason),
+                event_cancellation_orders::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update event cancellation order",
+            )
+    }
+}
@@ -0,0 +1,204 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_communication_schedules, events};
+use time::Duration;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Longest an organization can schedule a communication away from its event, 30 days
+const MAX_OFFSET_HOURS: i32 = 720;
+
+/// A reminder or follow-up an organization sends to ticket holders of each of its events
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Organization)]
+#[table_name = "event_communication_schedules"]
+pub struct EventCommunicationSchedule {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub timing: EventCommunicationTimings,
+    /// Hours before doors open for reminders, or after the event ends for follow-ups
+    pub offset_hours: i32,
+    pub channel: CommunicationChannelType,
+    /// The title and message can include {event_name}, {venue_name}, {door_time} and
+    /// {event_start}, times are shown in the venue's timezone
+    pub title: String,
+    pub message: String,
+    /// SendGrid template used for emails instead of the plain message when set
+    pub template_id: Option<String>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "event_communication_schedules"]
+pub struct NewEventCommunicationSchedule {
+    pub organization_id: Uuid,
+    pub timing: EventCommunicationTimings,
+    pub offset_hours: i32,
+    pub channel: CommunicationChannelType,
+    pub title: String,
+    pub message: String,
+    pub template_id: Option<String>,
+}
+
+impl NewEventCommunicationSchedule {
+    pub fn commit(&self, conn: &PgConnection) -> Result<EventCommunicationSchedule, DatabaseError> {
+        if self.offset_hours < 0 || self.offset_hours > MAX_OFFSET_HOURS {
+            return DatabaseError::validation_error(
+                "offset_hours",
+                "Offset must be between 0 and 720 hours",
+            );
+        }
+        if self.channel == CommunicationChannelType::Sms {
+            return DatabaseError::validation_error(
+                "channel",
+                "Event communications can only be sent by email or push notification",
+            );
+        }
+        if self.title.trim().is_empty() {
+            return DatabaseError::validation_error("title", "Title is required");
+        }
+        if self.message.trim().is_empty() {
+            return DatabaseError::validation_error("message", "Message is required");
+        }
+
+        let schedule: EventCommunicationSchedule =
+            diesel::insert_into(event_communication_schedules::table)
+                .values(self)
+                .get_result(conn)
+                .to_db_error(
+                    ErrorCode::InsertError,
+                    "Could not create event communication schedule",
+                )?;
+
+        // Events already published are sent the new communication as well
+        for event in schedule.upcoming_events(conn)? {
+            event.queue_scheduled_communication(&schedule, conn)?;
+        }
+
+        Ok(schedule)
+    }
+}
+
+impl EventCommunicationSchedule {
+    pub fn create(
+        organization_id: Uuid,
+        timing: EventCommunicationTimings,
+        offset_hours: i32,
+        channel: CommunicationChannelType,
+        title: String,
+        message: String,
+        template_id: Option<String>,
+    ) -> NewEventCommunicationSchedule {
+        NewEventCommunicationSchedule {
+            organization_id,
+            timing,
+            offset_hours,
+            channel,
+            title,
+            message,
+            template_id,
+        }
+    }
+
+    pub fn find(
+        id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<EventCommunicationSchedule, DatabaseError> {
+        event_communication_schedules::table
+            .filter(event_communication_schedules::id.eq(id))
+            .filter(event_communication_schedules::deleted_at.is_null())
+            .first(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load event communication schedule",
+            )
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventCommunicationSchedule>, DatabaseError> {
+        event_communication_schedules::table
+            .filter(event_communication_schedules::organization_id.eq(organization_id))
+            .filter(event_communication_schedules::deleted_at.is_null())
+            .order_by(event_communication_schedules::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load event communication schedules",
+            )
+    }
+
+    /// Removes the schedule, communications already queued for it are skipped when sent
+    pub fn destroy(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((
+                event_communication_schedules::deleted_at.eq(dsl::now.nullable()),
+                event_communication_schedules::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not delete event communication schedule",
+            )?;
+        Ok(())
+    }
+
+    /// When the communication is due for the event, reminders are timed from doors opening
+    /// and follow-ups from the end of the event, falling back to the event start
+    pub fn send_at(&self, event: &Event) -> Option<NaiveDateTime> {
+        let offset = Duration::hours(self.offset_hours as i64);
+        match self.timing {
+            EventCommunicationTimings::BeforeDoors => {
+                event.door_time.or(event.event_start).map(|t| t - offset)
+            }
+            EventCommunicationTimings::AfterEvent => {
+                event.event_end.or(event.event_start).map(|t| t + offset)
+            }
+        }
+    }
+
+    pub fn title_for(&self, event: &Event, venue: &Option<Venue>) -> String {
+        EventCommunicationSchedule::render(&self.title, event, venue)
+    }
+
+    pub fn message_for(&self, event: &Event, venue: &Option<Venue>) -> String {
+        EventCommunicationSchedule::render(&self.message, event, venue)
+    }
+
+    fn render(text: &str, event: &Event, venue: &Option<Venue>) -> String {
+        let format_time =
+            |time: Option<NaiveDateTime>| match Event::localized_time_from_venue(&time, venue) {
+                Some(localized) => localized.format("%A, %B %e at %l:%M %p %Z").to_string(),
+                None => time
+                    .map(|t| format!("{} UTC", t.format("%Y-%m-%d %H:%M")))
+                    .unwrap_or_default(),
+            };
+        text.replace("{event_name}", &event.name)
+            .replace(
+                "{venue_name}",
+                &venue.as_ref().map(|v| v.name.clone()).unwrap_or_default(),
+            )
+            .replace(
+                "{door_time}",
+                &format_time(event.door_time.or(event.event_start)),
+            )
+            .replace("{event_start}", &format_time(event.event_start))
+    }
+
+    fn upcoming_events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        events::table
+            .filter(events::organization_id.eq(self.organization_id))
+            .filter(events::status.eq(EventStatus::Published))
+            .filter(events::cancelled_at.is_null())
+            .filter(events::event_start.gt(now).or(events::event_end.gt(now)))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load upcoming events")
+    }
+}
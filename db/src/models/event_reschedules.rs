@@ -0,0 +1,244 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Nullable, Text, Uuid as dUuid};
+use models::*;
+use schema::{
+    event_reschedule_refunds, event_reschedules, order_items, orders, ticket_instances, wallets,
+};
+use std::collections::HashMap;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A change to an event's dates, ticket holders may opt out with a refund until the deadline
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Event)]
+#[table_name = "event_reschedules"]
+pub struct EventReschedule {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub previous_event_start: Option<NaiveDateTime>,
+    pub previous_door_time: Option<NaiveDateTime>,
+    pub previous_event_end: Option<NaiveDateTime>,
+    pub event_start: NaiveDateTime,
+    pub door_time: Option<NaiveDateTime>,
+    pub event_end: Option<NaiveDateTime>,
+    pub refund_deadline: NaiveDateTime,
+    pub created_by_user_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "event_reschedules"]
+pub struct NewEventReschedule {
+    pub event_id: Uuid,
+    pub previous_event_start: Option<NaiveDateTime>,
+    pub previous_door_time: Option<NaiveDateTime>,
+    pub previous_event_end: Option<NaiveDateTime>,
+    pub event_start: NaiveDateTime,
+    pub door_time: Option<NaiveDateTime>,
+    pub event_end: Option<NaiveDateTime>,
+    pub refund_deadline: NaiveDateTime,
+    pub created_by_user_id: Uuid,
+}
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(EventReschedule)]
+#[table_name = "event_reschedule_refunds"]
+pub struct EventRescheduleRefund {
+    pub id: Uuid,
+    pub event_reschedule_id: Uuid,
+    pub user_id: Uuid,
+    pub order_id: Uuid,
+    pub ticket_instance_ids: Vec<Uuid>,
+    pub refunded_amount_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "event_reschedule_refunds"]
+pub struct NewEventRescheduleRefund {
+    pub event_reschedule_id: Uuid,
+    pub user_id: Uuid,
+    pub order_id: Uuid,
+    pub ticket_instance_ids: Vec<Uuid>,
+    pub refunded_amount_in_cents: i64,
+}
+
+/// Ticket holder's response to a reschedule, holders without refunds kept their tickets
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct EventRescheduleReportRow {
+    #[sql_type = "dUuid"]
+    pub user_id: Uuid,
+    #[sql_type = "Nullable<Text>"]
+    pub first_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub last_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub email: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub phone: Option<String>,
+    #[sql_type = "BigInt"]
+    pub tickets_kept: i64,
+    #[sql_type = "BigInt"]
+    pub tickets_refunded: i64,
+    #[sql_type = "BigInt"]
+    pub refunded_amount_in_cents: i64,
+    #[sql_type = "Bool"]
+    pub refunded: bool,
+}
+
+impl NewEventReschedule {
+    pub fn commit(self, conn: &PgConnection) -> Result<EventReschedule, DatabaseError> {
+        let reschedule: EventReschedule = diesel::insert_into(event_reschedules::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event reschedule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventRescheduled,
+            "Event was rescheduled".to_string(),
+            Tables::Events,
+            Some(reschedule.event_id),
+            Some(reschedule.created_by_user_id),
+            Some(json!({
+                "event_reschedule_id": reschedule.id,
+                "previous_event_start": reschedule.previous_event_start,
+                "previous_door_time": reschedule.previous_door_time,
+                "previous_event_end": reschedule.previous_event_end,
+                "event_start": reschedule.event_start,
+                "door_time": reschedule.door_time,
+                "event_end": reschedule.event_end,
+                "refund_deadline": reschedule.refund_deadline,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(reschedule)
+    }
+}
+
+impl NewEventRescheduleRefund {
+    pub fn commit(self, conn: &PgConnection) -> Result<EventRescheduleRefund, DatabaseError> {
+        let refund: EventRescheduleRefund = diesel::insert_into(event_reschedule_refunds::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create event reschedule refund",
+            )?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventRescheduleRefunded,
+            "Tickets refunded after event was rescheduled".to_string(),
+            Tables::Orders,
+            Some(refund.order_id),
+            Some(refund.user_id),
+            Some(json!({
+                "event_reschedule_id": refund.event_reschedule_id,
+                "ticket_instance_ids": refund.ticket_instance_ids,
+                "refunded_amount_in_cents": refund.refunded_amount_in_cents,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(refund)
+    }
+}
+
+impl EventReschedule {
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventReschedule, DatabaseError> {
+        event_reschedules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event reschedule")
+    }
+
+    pub fn find_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventReschedule>, DatabaseError> {
+        event_reschedules::table
+            .filter(event_reschedules::event_id.eq(event_id))
+            .order_by(event_reschedules::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event reschedules")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn is_refundable(&self) -> bool {
+        Utc::now().naive_utc() < self.refund_deadline
+    }
+
+    /// Purchased tickets for the event held by the user on their own orders, grouped by order
+    pub fn refundable_tickets_for_user(
+        &self,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<HashMap<Uuid, Vec<Uuid>>, DatabaseError> {
+        let tickets: Vec<(Uuid, Uuid)> = ticket_instances::table
+            .inner_join(
+                order_items::table
+                    .on(ticket_instances::order_item_id.eq(order_items::id.nullable())),
+            )
+            .inner_join(orders::table.on(order_items::order_id.eq(orders::id)))
+            .inner_join(wallets::table.on(ticket_instances::wallet_id.eq(wallets::id)))
+            .filter(order_items::event_id.eq(self.event_id))
+            .filter(ticket_instances::status.eq(TicketInstanceStatus::Purchased))
+            .filter(wallets::user_id.eq(user_id))
+            .filter(
+                orders::on_behalf_of_user_id
+                    .eq(user_id)
+                    .or(orders::on_behalf_of_user_id
+                        .is_null()
+                        .and(orders::user_id.eq(user_id))),
+            )
+            .select((orders::id, ticket_instances::id))
+            .order_by(ticket_instances::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load refundable tickets")?;
+
+        let mut tickets_per_order: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for (order_id, ticket_instance_id) in tickets {
+            tickets_per_order
+                .entry(order_id)
+                .or_insert_with(|| Vec::new())
+                .push(ticket_instance_id);
+        }
+        Ok(tickets_per_order)
+    }
+
+    pub fn refunds(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventRescheduleRefund>, DatabaseError> {
+        event_reschedule_refunds::table
+            .filter(event_reschedule_refunds::event_reschedule_id.eq(self.id))
+            .order_by(event_reschedule_refunds::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load event reschedule refunds",
+            )
+    }
+
+    /// Current ticket holders for the event and holders who opted out with a refund
+    pub fn report(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventRescheduleReportRow>, DatabaseError> {
+        let query = include_str!("../queries/event_reschedule_report.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(self.id)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load event reschedule report",
+            )
+    }
+}
@@ -10,11 +10,12 @@ use diesel::sql_types::{BigInt, Bool, Date, Integer, Nullable, Text, Timestamp,
 use log::Level;
 use models::*;
 use schema::{
-    artists, event_artists, events, order_items, orders, organization_users, organizations,
-    payments, ticket_types, venues,
+    artists, assets, domain_actions, event_artists, events, order_items, orders,
+    organization_users, organizations, payments, ticket_instances, ticket_types, venues, wallets,
 };
 use serde_with::rust::double_option;
 use std::borrow::Cow;
+use std::cmp;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use time::Duration;
@@ -25,6 +26,9 @@ use validator::{Validate, ValidationErrors};
 use validators;
 use validators::*;
 
+/// Hours before the event starts that ticket holders are sent a push reminder
+const EVENT_DAY_REMINDER_HOURS: i64 = 6;
+
 #[derive(Associations, Identifiable, Queryable, AsChangeset)]
 #[belongs_to(Organization)]
 #[derive(Clone, QueryableByName, Serialize, Deserialize, PartialEq, Debug)]
@@ -60,6 +64,7 @@ pub struct Event {
     pub event_type: EventTypes,
     pub cover_image_url: Option<String>,
     pub private_access_code: Option<String>,
+    pub currency: String,
 }
 
 impl PartialOrd for Event {
@@ -107,6 +112,13 @@ pub struct NewEvent {
     pub override_status: Option<EventOverrideStatus>,
     pub event_end: Option<NaiveDateTime>,
     pub event_type: EventTypes,
+    #[validate(length(
+        min = "3",
+        max = "3",
+        message = "Currency must be a 3 letter currency code"
+    ))]
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 impl NewEvent {
@@ -128,6 +140,9 @@ impl NewEvent {
             }
             None => (),
         }
+        if new_event.currency.is_none() {
+            new_event.currency = Some(organization.currency.clone());
+        }
         diesel::insert_into(events::table)
             .values((
                 new_event,
@@ -190,6 +205,13 @@ pub struct EventEditableAttributes {
     pub private_access_code: Option<Option<String>>,
     pub sendgrid_list_id: Option<i64>,
     pub event_type: Option<EventTypes>,
+    #[validate(length(
+        min = "3",
+        max = "3",
+        message = "Currency must be a 3 letter currency code"
+    ))]
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Debug, Default, PartialEq, Serialize)]
@@ -277,13 +299,40 @@ impl Event {
             ),
         )?;
 
-        DatabaseError::wrap(
+        if let Some(ref currency) = event.currency {
+            if currency != &self.currency && self.has_order_items(conn)? {
+                return DatabaseError::validation_error(
+                    "currency",
+                    "Currency cannot be changed once tickets have been added to orders",
+                );
+            }
+        }
+
+        let updated_event: Event = DatabaseError::wrap(
             ErrorCode::UpdateError,
             "Could not update event",
             diesel::update(self)
                 .set((event, events::updated_at.eq(dsl::now)))
                 .get_result(conn),
-        )
+        )?;
+
+        // Reminders and follow-ups are timed from the event so they move with it
+        if updated_event.event_start != self.event_start
+            || updated_event.door_time != self.door_time
+            || updated_event.event_end != self.event_end
+        {
+            updated_event.requeue_scheduled_communications(conn)?;
+        }
+
+        Ok(updated_event)
+    }
+
+    fn has_order_items(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        diesel::select(dsl::exists(
+            order_items::table.filter(order_items::event_id.eq(self.id)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check event order items")
     }
 
     pub fn ticket_pricing_range_by_events(
@@ -307,16 +356,7 @@ impl Event {
                 min(tp.price_in_cents) as min_ticket_price,
                 max(tp.price_in_cents) as max_ticket_price
             FROM ticket_types tt
-            JOIN ticket_pricing tp on tp.id = (
-                select tp.id from ticket_pricing tp
-                where tp.ticket_type_id = tt.id
-                and tp.start_date < now()
-                and tp.end_date > now()
-                and tp.status in ('Default', 'Published')
-                and tp.is_box_office_only = case when $2 = false then false else tp.is_box_office_only end
-                order by tp.is_box_office_only desc, tp.status desc -- Box Office Pricing, Published, Default
-                limit 1
-            )
+            JOIN ticket_pricing tp on tp.id = current_ticket_pricing_id(tt.id, $2)
             where tt.event_id = ANY($1)
             and tt.is_private = false
             GROUP BY tt.event_id;
@@ -381,6 +421,7 @@ impl Event {
             ))
             .execute(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not un-publish record")?;
+        self.cancel_scheduled_communications(conn)?;
 
         Event::find(self.id, conn)
     }
@@ -429,7 +470,138 @@ impl Event {
                 .to_db_error(ErrorCode::UpdateError, "Could not publish record")?,
         };
 
-        Event::find(self.id, conn)
+        let event = Event::find(self.id, conn)?;
+        event.requeue_scheduled_communications(conn)?;
+        Ok(event)
+    }
+
+    /// Cancels the reminders and follow-ups waiting to be sent and queues them again from the
+    /// event's current times, nothing is queued unless the event is published
+    pub fn requeue_scheduled_communications(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.cancel_scheduled_communications(conn)?;
+        if self.status != EventStatus::Published || self.cancelled_at.is_some() {
+            return Ok(());
+        }
+
+        self.queue_event_day_reminders(conn)?;
+        for schedule in
+            EventCommunicationSchedule::find_for_organization(self.organization_id, conn)?
+        {
+            self.queue_scheduled_communication(&schedule, conn)?;
+        }
+        Ok(())
+    }
+
+    pub fn cancel_scheduled_communications(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        diesel::update(
+            domain_actions::table
+                .filter(domain_actions::domain_action_type.eq_any(vec![
+                    DomainActionTypes::SendEventCommunication,
+                    DomainActionTypes::SendEventDayReminders,
+                ]))
+                .filter(domain_actions::main_table.eq(Tables::Events.to_string()))
+                .filter(domain_actions::main_table_id.eq(self.id))
+                .filter(domain_actions::status.eq(DomainActionStatus::Pending)),
+        )
+        .set((
+            domain_actions::status.eq(DomainActionStatus::Cancelled),
+            domain_actions::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not cancel scheduled event communications",
+        )?;
+        Ok(())
+    }
+
+    /// Schedules one of the organization's reminders or follow-ups for the event, unless its
+    /// time has already passed
+    pub fn queue_scheduled_communication(
+        &self,
+        schedule: &EventCommunicationSchedule,
+        conn: &PgConnection,
+    ) -> Result<Option<DomainAction>, DatabaseError> {
+        let send_at = match schedule.send_at(self) {
+            Some(send_at) => send_at,
+            None => return Ok(None),
+        };
+        if send_at <= Utc::now().naive_utc() {
+            return Ok(None);
+        }
+        // Reminders are pointless once the event is over, follow-ups get a day to go out
+        let expires_at = match schedule.timing {
+            EventCommunicationTimings::BeforeDoors => cmp::max(
+                self.event_end.or(self.event_start).unwrap_or(send_at),
+                send_at + Duration::hours(1),
+            ),
+            EventCommunicationTimings::AfterEvent => send_at + Duration::days(1),
+        };
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::SendEventCommunication,
+            Some(schedule.channel),
+            json!({ "event_communication_schedule_id": schedule.id, "send_at": send_at }),
+            Some(Tables::Events.to_string()),
+            Some(self.id),
+            send_at,
+            expires_at,
+            3,
+        )
+        .commit(conn)
+        .map(Some)
+    }
+
+    /// Schedules the push reminder sent to ticket holders on the day of the event, the reminder
+    /// is skipped when sent if the event has since been rescheduled or cancelled
+    pub fn queue_event_day_reminders(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Option<DomainAction>, DatabaseError> {
+        let event_start = match self.event_start {
+            Some(event_start) => event_start,
+            None => return Ok(None),
+        };
+        let scheduled_at = event_start - Duration::hours(EVENT_DAY_REMINDER_HOURS);
+        if scheduled_at <= Utc::now().naive_utc() {
+            return Ok(None);
+        }
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::SendEventDayReminders,
+            Some(CommunicationChannelType::Push),
+            json!({ "event_start": event_start }),
+            Some(Tables::Events.to_string()),
+            Some(self.id),
+            scheduled_at,
+            event_start,
+            3,
+        )
+        .commit(conn)
+        .map(Some)
+    }
+
+    /// Users currently holding purchased tickets for the event
+    pub fn ticket_holder_ids(&self, conn: &PgConnection) -> Result<Vec<Uuid>, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table.inner_join(ticket_types::table))
+            .inner_join(wallets::table)
+            .filter(ticket_types::event_id.eq(self.id))
+            .filter(ticket_instances::status.eq(TicketInstanceStatus::Purchased))
+            .filter(wallets::user_id.is_not_null())
+            .select(wallets::user_id)
+            .distinct()
+            .load::<Option<Uuid>>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event ticket holders")
+            .map(|user_ids| user_ids.into_iter().filter_map(|u| u).collect())
     }
 
     pub fn find_by_order_item_ids(
@@ -466,11 +638,101 @@ impl Event {
             .to_db_error(ErrorCode::QueryError, "Error loading events")
     }
 
-    pub fn cancel(self, conn: &PgConnection) -> Result<Event, DatabaseError> {
-        diesel::update(&self)
+    /// Cancels the event and queues the refunding of its paid orders, fees are refunded according
+    /// to the organization's policy
+    pub fn cancel(
+        self,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Event, DatabaseError> {
+        if self.cancelled_at.is_some() {
+            return DatabaseError::business_process_error("Event has already been cancelled");
+        }
+
+        let event: Event = diesel::update(&self)
             .set(events::cancelled_at.eq(dsl::now.nullable()))
             .get_result(conn)
-            .to_db_error(ErrorCode::UpdateError, "Could not update event")
+            .to_db_error(ErrorCode::UpdateError, "Could not update event")?;
+        event.cancel_scheduled_communications(conn)?;
+
+        let organization = event.organization(conn)?;
+        EventCancellation::create(
+            event.id,
+            organization.refund_fees_on_cancellation,
+            current_user_id,
+        )
+        .commit(conn)?;
+
+        Ok(event)
+    }
+
+    pub fn cancellation(&self, conn: &PgConnection) -> Result<EventCancellation, DatabaseError> {
+        EventCancellation::find_for_event(self.id, conn)
+    }
+
+    /// Moves the event to new dates and flags it as rescheduled. Door time and event end keep
+    /// their offset from the event start when not given.
+    pub fn reschedule(
+        &self,
+        event_start: NaiveDateTime,
+        door_time: Option<NaiveDateTime>,
+        event_end: Option<NaiveDateTime>,
+        refund_deadline: NaiveDateTime,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(Event, EventReschedule), DatabaseError> {
+        if self.cancelled_at.is_some() {
+            return DatabaseError::business_process_error("Cancelled events cannot be rescheduled");
+        }
+        if refund_deadline <= Utc::now().naive_utc() {
+            return DatabaseError::validation_error(
+                "refund_deadline",
+                "Refund deadline must be in the future",
+            );
+        }
+
+        let door_time = door_time.or_else(|| match (self.event_start, self.door_time) {
+            (Some(previous_start), Some(previous_door_time)) => {
+                Some(event_start - previous_start.signed_duration_since(previous_door_time))
+            }
+            _ => None,
+        });
+        let event_end = event_end.or_else(|| match (self.event_start, self.event_end) {
+            (Some(previous_start), Some(previous_end)) => {
+                Some(event_start + previous_end.signed_duration_since(previous_start))
+            }
+            _ => None,
+        });
+
+        let event = self.update(
+            EventEditableAttributes {
+                event_start: Some(event_start),
+                door_time,
+                event_end,
+                override_status: Some(Some(EventOverrideStatus::Rescheduled)),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        let reschedule = NewEventReschedule {
+            event_id: self.id,
+            previous_event_start: self.event_start,
+            previous_door_time: self.door_time,
+            previous_event_end: self.event_end,
+            event_start,
+            door_time: event.door_time,
+            event_end: event.event_end,
+            refund_deadline,
+            created_by_user_id: current_user_id,
+        }
+        .commit(conn)?;
+
+        Ok((event, reschedule))
+    }
+
+    pub fn reschedules(&self, conn: &PgConnection) -> Result<Vec<EventReschedule>, DatabaseError> {
+        EventReschedule::find_for_event(self.id, conn)
     }
 
     pub fn get_all_events_ending_between(
@@ -0,0 +1,490 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use models::*;
+use schema::{events, venues};
+use std::collections::HashMap;
+use utils::errors::*;
+use utils::spreadsheets::{Cell, Worksheet};
+use uuid::Uuid;
+
+/// Data that can be exported as CSV or XLSX, one worksheet per table of rows
+pub trait Exportable {
+    fn to_worksheets(&self, conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError>;
+}
+
+/// Event names and venue timezones used to label rows and localize event times
+struct EventDetails {
+    events: HashMap<Uuid, (String, Option<String>)>,
+}
+
+impl EventDetails {
+    fn load(event_ids: Vec<Uuid>, conn: &PgConnection) -> Result<EventDetails, DatabaseError> {
+        let rows: Vec<(Uuid, String, Option<String>)> = events::table
+            .left_join(venues::table)
+            .filter(events::id.eq_any(event_ids))
+            .select((events::id, events::name, venues::timezone.nullable()))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event details")?;
+        Ok(EventDetails {
+            events: rows
+                .into_iter()
+                .map(|(id, name, timezone)| (id, (name, timezone)))
+                .collect(),
+        })
+    }
+
+    fn name(&self, event_id: Uuid) -> Cell {
+        Cell::optional_text(&self.events.get(&event_id).map(|e| e.0.clone()))
+    }
+
+    /// Event times are shown in the venue's timezone, falling back to UTC
+    fn localized(&self, event_id: Uuid, time: Option<NaiveDateTime>) -> Cell {
+        match self.events.get(&event_id).and_then(|e| e.1.clone()) {
+            Some(timezone) => match Event::localized_time(&time, &Some(timezone)) {
+                Some(localized) => Cell::Time(localized),
+                None => Cell::optional_utc(time),
+            },
+            None => Cell::optional_utc(time),
+        }
+    }
+}
+
+impl Exportable for Vec<TransactionReportRow> {
+    fn to_worksheets(&self, conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError> {
+        let event_details = EventDetails::load(self.iter().map(|r| r.event_id).collect(), conn)?;
+        let mut worksheet = Worksheet::new(
+            "Transactions",
+            &[
+                "Event",
+                "Event Start",
+                "Ticket",
+                "Item Type",
+                "Order ID",
+                "Transaction Date",
+                "Order Type",
+                "Payment Method",
+                "Payment Provider",
+                "Redemption Code",
+                "First Name",
+                "Last Name",
+                "Email",
+                "Quantity",
+                "Refunded Quantity",
+                "Actual Quantity",
+                "Unit Price",
+                "Gross",
+                "Company Fee",
+                "Client Fee",
+                "Gross Fee",
+                "Total Fees",
+                "Event Fee Company",
+                "Event Fee Client",
+                "Event Fee Gross",
+                "Total Event Fees",
+                "Tax",
+                "Total Tax",
+                "Resale Fee",
+            ],
+        );
+        for row in self {
+            worksheet.push(vec![
+                Cell::text(&row.event_name),
+                event_details.localized(row.event_id, row.event_start),
+                Cell::text(&row.ticket_name),
+                Cell::text(row.item_type),
+                Cell::text(row.order_id),
+                Cell::utc(row.transaction_date),
+                Cell::text(row.order_type),
+                Cell::optional_text(&row.payment_method),
+                Cell::optional_text(&row.payment_provider),
+                Cell::optional_text(&row.redemption_code),
+                Cell::text(&row.first_name),
+                Cell::text(&row.last_name),
+                Cell::text(&row.email),
+                Cell::Number(row.quantity),
+                Cell::Number(row.refunded_quantity),
+                Cell::Number(row.actual_quantity),
+                Cell::Money(row.unit_price_in_cents),
+                Cell::Money(row.gross),
+                Cell::Money(row.company_fee_in_cents),
+                Cell::Money(row.client_fee_in_cents),
+                Cell::Money(row.gross_fee_in_cents),
+                Cell::Money(row.gross_fee_in_cents_total),
+                Cell::Money(row.event_fee_company_in_cents),
+                Cell::Money(row.event_fee_client_in_cents),
+                Cell::Money(row.event_fee_gross_in_cents),
+                Cell::Money(row.event_fee_gross_in_cents_total),
+                Cell::Money(row.tax_in_cents),
+                Cell::Money(row.tax_in_cents_total),
+                Cell::Money(row.resale_fee_in_cents),
+            ]);
+        }
+        Ok(vec![worksheet])
+    }
+}
+
+fn event_summary_worksheets(
+    results: &[&EventSummarySalesResult],
+    conn: &PgConnection,
+) -> Result<Vec<Worksheet>, DatabaseError> {
+    let event_details = EventDetails::load(results.iter().map(|r| r.event_id).collect(), conn)?;
+    let mut sales = Worksheet::new(
+        "Sales",
+        &[
+            "Event",
+            "Ticket",
+            "Pricing",
+            "Currency",
+            "Price",
+            "Online",
+            "Box Office",
+            "Comps",
+            "Total Sold",
+            "Client Fees",
+            "Company Fees",
+            "Gross",
+        ],
+    );
+    let mut ticket_fees = Worksheet::new(
+        "Ticket Fees",
+        &[
+            "Event",
+            "Ticket",
+            "Pricing",
+            "Currency",
+            "Price",
+            "Total Sold",
+            "Comps",
+            "Online",
+            "Company Fee",
+            "Total Company Fees",
+            "Client Fee",
+            "Total Client Fees",
+        ],
+    );
+    let mut other_fees = Worksheet::new(
+        "Other Fees",
+        &[
+            "Event",
+            "Currency",
+            "Unit Price",
+            "Company Fee",
+            "Total Company Fees",
+            "Client Fee",
+            "Total Client Fees",
+        ],
+    );
+    for result in results {
+        for row in result.sales.iter() {
+            sales.push(vec![
+                event_details.name(row.event_id),
+                Cell::text(&row.ticket_name),
+                Cell::text(&row.pricing_name),
+                Cell::text(&result.currency),
+                Cell::Money(row.price_in_cents),
+                Cell::Number(row.online_count),
+                Cell::Number(row.box_office_count),
+                Cell::Number(row.comp_count),
+                Cell::Number(row.total_sold),
+                Cell::Money(row.total_client_fee_in_cents),
+                Cell::Money(row.total_company_fee_in_cents),
+                Cell::Money(row.total_gross_income_in_cents),
+            ]);
+        }
+        for row in result.ticket_fees.iter() {
+            ticket_fees.push(vec![
+                event_details.name(row.event_id),
+                Cell::text(&row.ticket_name),
+                Cell::text(&row.pricing_name),
+                Cell::text(&result.currency),
+                Cell::Money(row.price_in_cents),
+                Cell::Number(row.total_sold),
+                Cell::Number(row.comp_count),
+                Cell::Number(row.online_count),
+                Cell::Money(row.company_fee_in_cents),
+                Cell::Money(row.total_company_fee_in_cents),
+                Cell::Money(row.client_fee_in_cents),
+                Cell::Money(row.total_client_fee_in_cents),
+            ]);
+        }
+        for row in result.other_fees.iter() {
+            other_fees.push(vec![
+                event_details.name(row.event_id),
+                Cell::text(&result.currency),
+                Cell::Money(row.unit_price_in_cents),
+                Cell::Money(row.company_fee_in_cents),
+                Cell::Money(row.total_company_fee_in_cents),
+                Cell::Money(row.client_fee_in_cents),
+                Cell::Money(row.total_client_fee_in_cents),
+            ]);
+        }
+    }
+    Ok(vec![sales, ticket_fees, other_fees])
+}
+
+impl Exportable for EventSummarySalesResult {
+    fn to_worksheets(&self, conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError> {
+        event_summary_worksheets(&[self], conn)
+    }
+}
+
+impl Exportable for Vec<CurrencySummarySalesResult> {
+    fn to_worksheets(&self, conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError> {
+        let results: Vec<&EventSummarySalesResult> =
+            self.iter().flat_map(|r| r.events.iter()).collect();
+        event_summary_worksheets(&results, conn)
+    }
+}
+
+impl Exportable for TicketSalesAndCounts {
+    fn to_worksheets(&self, _conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError> {
+        let mut counts = Worksheet::new(
+            "Ticket Counts",
+            &[
+                "Event",
+                "Ticket",
+                "Status",
+                "Allocated",
+                "Available",
+                "Reserved",
+                "Purchased",
+                "Redeemed",
+                "Nullified",
+                "Refunded",
+                "Comps",
+                "Comps Available",
+                "Comps Redeemed",
+                "Holds",
+                "Holds Available",
+                "Holds Redeemed",
+            ],
+        );
+        for row in self.counts.iter() {
+            counts.push(vec![
+                Cell::optional_text(&row.event_name),
+                Cell::optional_text(&row.ticket_name),
+                Cell::optional_text(&row.ticket_status),
+                Cell::Number(row.allocation_count),
+                Cell::Number(row.available_for_purchase_count),
+                Cell::Number(row.reserved_count),
+                Cell::Number(row.purchased_count),
+                Cell::Number(row.redeemed_count),
+                Cell::Number(row.nullified_count),
+                Cell::Number(row.total_refunded_count),
+                Cell::Number(row.comp_count),
+                Cell::Number(row.comp_available_count),
+                Cell::Number(row.comp_redeemed_count),
+                Cell::Number(row.hold_count),
+                Cell::Number(row.hold_available_count),
+                Cell::Number(row.hold_redeemed_count),
+            ]);
+        }
+        let mut worksheets = vec![counts];
+
+        if !self.sales.is_empty() {
+            let mut sales = Worksheet::new(
+                "Ticket Sales",
+                &[
+                    "Event",
+                    "Ticket",
+                    "Pricing",
+                    "Hold",
+                    "Price",
+                    "Online Sold",
+                    "Box Office Sold",
+                    "Comps",
+                    "Online Refunded",
+                    "Box Office Refunded",
+                    "Online Sales",
+                    "Box Office Sales",
+                    "Online Fees",
+                    "Box Office Fees",
+                ],
+            );
+            for row in self.sales.iter() {
+                sales.push(vec![
+                    Cell::optional_text(&row.event_name),
+                    Cell::optional_text(&row.ticket_name),
+                    Cell::optional_text(&row.ticket_pricing_name),
+                    Cell::optional_text(&row.hold_name),
+                    Cell::optional_money(row.ticket_pricing_price_in_cents),
+                    Cell::Number(row.online_sale_count),
+                    Cell::Number(row.box_office_sale_count),
+                    Cell::Number(row.comp_sale_count),
+                    Cell::Number(row.online_refunded_count),
+                    Cell::Number(row.box_office_refunded_count),
+                    Cell::Money(row.online_sales_in_cents),
+                    Cell::Money(row.box_office_sales_in_cents),
+                    Cell::Money(row.total_online_fees_in_cents),
+                    Cell::Money(row.total_box_office_fees_in_cents),
+                ]);
+            }
+            worksheets.push(sales);
+        }
+        Ok(worksheets)
+    }
+}
+
+const RECONCILIATION_HEADERS: &[&str] = &[
+    "Payment Method",
+    "Payment Provider",
+    "Quantity",
+    "Unit Price",
+    "Client Fees",
+    "Event Fees",
+    "Sales Total",
+    "Refund Quantity",
+    "Refund Unit Price",
+    "Refund Client Fees",
+    "Refund Event Fees",
+    "Refund Total",
+    "Total",
+];
+
+impl Exportable for Vec<ReconciliationSummaryResult> {
+    fn to_worksheets(&self, _conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError> {
+        let mut worksheet = Worksheet::new("Reconciliation", RECONCILIATION_HEADERS);
+        for row in self {
+            worksheet.push(vec![
+                Cell::text(row.payment_method),
+                Cell::text(&row.payment_provider),
+                Cell::Number(row.quantity),
+                Cell::Money(row.unit_price_in_cents),
+                Cell::Money(row.client_fee_in_cents),
+                Cell::Money(row.event_fee_in_cents),
+                Cell::Money(row.sales_total),
+                Cell::Number(row.refund_quantity),
+                Cell::Money(row.refund_unit_price_in_cents),
+                Cell::Money(row.refund_client_fee_in_cents),
+                Cell::Money(row.refund_event_fee_in_cents),
+                Cell::Money(row.refund_total),
+                Cell::Money(row.total),
+            ]);
+        }
+        Ok(vec![worksheet])
+    }
+}
+
+impl Exportable for Vec<ReconciliationDetailEventResult> {
+    fn to_worksheets(&self, conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError> {
+        let event_details = EventDetails::load(self.iter().map(|r| r.event_id).collect(), conn)?;
+        let mut headers = vec!["Event", "Event Start"];
+        headers.extend(RECONCILIATION_HEADERS);
+        let mut worksheet = Worksheet::new("Reconciliation Details", &headers);
+        for event in self {
+            for row in event.entries.iter() {
+                worksheet.push(vec![
+                    Cell::text(&event.event_name),
+                    event_details.localized(event.event_id, event.event_start),
+                    Cell::text(row.payment_method),
+                    Cell::text(&row.payment_provider),
+                    Cell::Number(row.quantity),
+                    Cell::Money(row.unit_price_in_cents),
+                    Cell::Money(
+                        row.client_fee_in_cents
+                            .iter()
+                            .map(|f| f.client_fee_in_cents)
+                            .sum(),
+                    ),
+                    Cell::Money(row.event_fee_in_cents),
+                    Cell::Money(row.sales_total),
+                    Cell::Number(row.refund_quantity),
+                    Cell::Money(row.refund_unit_price_in_cents),
+                    Cell::Money(
+                        row.refund_client_fee_in_cents
+                            .iter()
+                            .map(|f| f.client_fee_in_cents)
+                            .sum(),
+                    ),
+                    Cell::Money(row.refund_event_fee_in_cents),
+                    Cell::Money(row.refund_total),
+                    Cell::Money(row.total),
+                ]);
+            }
+        }
+        Ok(vec![worksheet])
+    }
+}
+
+impl Exportable for Vec<GuestListItem> {
+    fn to_worksheets(&self, conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError> {
+        let event_details =
+            EventDetails::load(self.iter().map(|g| g.ticket.event_id).collect(), conn)?;
+        let mut worksheet = Worksheet::new(
+            "Guests",
+            &[
+                "Event",
+                "Event Start",
+                "Door Time",
+                "Ticket Type",
+                "Section",
+                "Row",
+                "Seat",
+                "First Name",
+                "Last Name",
+                "Email",
+                "Phone",
+                "Status",
+                "Price",
+                "Redeemed At",
+                "Order ID",
+                "Ticket ID",
+            ],
+        );
+        for guest in self {
+            let ticket = &guest.ticket;
+            worksheet.push(vec![
+                Cell::text(&ticket.event_name),
+                event_details.localized(ticket.event_id, ticket.event_start),
+                event_details.localized(ticket.event_id, ticket.door_time),
+                Cell::text(&ticket.ticket_type),
+                Cell::optional_text(&ticket.section_name),
+                Cell::optional_text(&ticket.row_name),
+                Cell::optional_text(&ticket.seat_number),
+                Cell::optional_text(&ticket.first_name),
+                Cell::optional_text(&ticket.last_name),
+                Cell::optional_text(&ticket.email),
+                Cell::optional_text(&ticket.phone),
+                Cell::text(ticket.status),
+                Cell::Money(ticket.price_in_cents),
+                event_details.localized(ticket.event_id, ticket.redeem_date),
+                Cell::text(ticket.order_id),
+                Cell::text(ticket.id),
+            ]);
+        }
+        Ok(vec![worksheet])
+    }
+}
+
+impl Exportable for Vec<DisplayFan> {
+    fn to_worksheets(&self, _conn: &PgConnection) -> Result<Vec<Worksheet>, DatabaseError> {
+        let mut worksheet = Worksheet::new(
+            "Fans",
+            &[
+                "First Name",
+                "Last Name",
+                "Email",
+                "Phone",
+                "Orders",
+                "Revenue",
+                "First Order",
+                "Last Order",
+                "Joined",
+            ],
+        );
+        for fan in self {
+            worksheet.push(vec![
+                Cell::optional_text(&fan.first_name),
+                Cell::optional_text(&fan.last_name),
+                Cell::optional_text(&fan.email),
+                Cell::optional_text(&fan.phone),
+                Cell::Number(fan.order_count.unwrap_or(0) as i64),
+                Cell::optional_money(fan.revenue_in_cents),
+                Cell::optional_utc(fan.first_order_time),
+                Cell::optional_utc(fan.last_order_time),
+                Cell::utc(fan.created_at),
+            ]);
+        }
+        Ok(vec![worksheet])
+    }
+}
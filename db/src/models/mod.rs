@@ -1,12 +1,20 @@
+pub use self::add_ons::*;
 pub use self::artists::*;
 pub use self::assets::*;
+pub use self::bundles::*;
 pub use self::codes::*;
+pub use self::communication_logs::*;
+pub use self::disputes::*;
 pub use self::domain_actions::*;
 pub use self::domain_events::*;
 pub use self::enums::*;
 pub use self::event_artists::*;
+pub use self::event_cancellations::*;
+pub use self::event_communication_schedules::*;
 pub use self::event_interest::*;
+pub use self::event_reschedules::*;
 pub use self::events::*;
+pub use self::exports::*;
 pub use self::external_logins::FACEBOOK_SITE;
 pub use self::external_logins::*;
 pub use self::fans::*;
@@ -15,46 +23,68 @@ pub use self::fee_schedules::*;
 pub use self::for_display::*;
 pub use self::history_item::*;
 pub use self::holds::*;
+pub use self::offline_redemptions::*;
+pub use self::order_exchanges::*;
 pub use self::order_items::*;
 pub use self::orders::*;
+pub use self::organization_email_templates::*;
 pub use self::organization_invites::*;
 pub use self::organization_users::*;
 pub use self::organizations::*;
 pub use self::paging::*;
 pub use self::payment_methods::*;
 pub use self::payments::*;
+pub use self::price_zones::*;
 pub use self::push_notification_tokens::*;
 pub use self::redeemable_ticket::*;
+pub use self::refund_plans::*;
 pub use self::refunded_tickets::*;
 pub use self::regions::*;
+pub use self::report_subscriptions::*;
 pub use self::reports::*;
+pub use self::resale_listings::*;
+pub use self::resale_payouts::*;
 pub use self::scopes::*;
+pub use self::seating_sections::*;
+pub use self::seats::*;
 pub use self::settlement_transactions::*;
 pub use self::settlements::*;
 pub use self::stages::*;
+pub use self::tax_rates::*;
 pub use self::ticket_instances::RedeemResults;
 pub use self::ticket_instances::*;
 pub use self::ticket_pricing::*;
 pub use self::ticket_type_codes::*;
 pub use self::ticket_types::*;
+pub use self::user_two_factor_credentials::*;
 pub use self::users::*;
 pub use self::venues::*;
+pub use self::waitlist_entries::*;
 pub use self::wallets::*;
+pub use self::webhook_subscriptions::*;
 
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 
 pub mod concerns;
 
+mod add_ons;
 mod artists;
 mod assets;
+mod bundles;
 mod codes;
+mod communication_logs;
+mod disputes;
 mod domain_actions;
 mod domain_events;
 pub mod enums;
 mod event_artists;
+mod event_cancellations;
+mod event_communication_schedules;
 mod event_interest;
+mod event_reschedules;
 mod events;
+mod exports;
 mod external_logins;
 mod fans;
 mod fee_schedule_ranges;
@@ -62,30 +92,44 @@ mod fee_schedules;
 mod for_display;
 mod history_item;
 mod holds;
+mod offline_redemptions;
+mod order_exchanges;
 mod order_items;
 mod orders;
+mod organization_email_templates;
 mod organization_invites;
 mod organization_users;
 mod organizations;
 mod paging;
 mod payment_methods;
 mod payments;
+mod price_zones;
 mod push_notification_tokens;
 mod redeemable_ticket;
+mod refund_plans;
 mod refunded_tickets;
 mod regions;
+mod report_subscriptions;
 mod reports;
+mod resale_listings;
+mod resale_payouts;
 pub mod scopes;
+mod seating_sections;
+mod seats;
 mod settlement_transactions;
 mod settlements;
 mod stages;
+mod tax_rates;
 mod ticket_instances;
 mod ticket_pricing;
 mod ticket_type_codes;
 mod ticket_types;
+mod user_two_factor_credentials;
 mod users;
 mod venues;
+mod waitlist_entries;
 mod wallets;
+mod webhook_subscriptions;
 
 pub fn deserialize_unless_blank<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
 where
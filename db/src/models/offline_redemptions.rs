@@ -0,0 +1,447 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use hex;
+use models::*;
+use ring::{digest, pbkdf2};
+use schema::{add_ons, assets, offline_redemptions, ticket_instances, ticket_types};
+use tari_client::*;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(Event)]
+#[belongs_to(TicketInstance)]
+#[table_name = "offline_redemptions"]
+pub struct OfflineRedemption {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub ticket_instance_id: Option<Uuid>,
+    pub device_id: String,
+    pub redeemed_by: Uuid,
+    pub scanned_at: NaiveDateTime,
+    pub status: OfflineRedemptionStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub add_on_voucher_id: Option<Uuid>,
+}
+
+#[derive(Insertable, Clone)]
+#[table_name = "offline_redemptions"]
+pub struct NewOfflineRedemption {
+    pub event_id: Uuid,
+    pub ticket_instance_id: Option<Uuid>,
+    pub add_on_voucher_id: Option<Uuid>,
+    pub device_id: String,
+    pub redeemed_by: Uuid,
+    pub scanned_at: NaiveDateTime,
+    pub status: OfflineRedemptionStatus,
+}
+
+/// A single scan recorded by a device while it was offline, of either a ticket or an add-on
+/// voucher
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct OfflineScan {
+    #[serde(default)]
+    pub ticket_instance_id: Option<Uuid>,
+    #[serde(default)]
+    pub add_on_voucher_id: Option<Uuid>,
+    pub redeem_key: String,
+    pub scanned_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ScanConflict {
+    pub ticket_instance_id: Option<Uuid>,
+    pub add_on_voucher_id: Option<Uuid>,
+    pub scanned_at: NaiveDateTime,
+    pub status: OfflineRedemptionStatus,
+    pub previous_device_id: Option<String>,
+    pub previous_scanned_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ScannerSyncResult {
+    pub redeemed: Vec<Uuid>,
+    pub redeemed_add_on_vouchers: Vec<Uuid>,
+    pub conflicts: Vec<ScanConflict>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ScannerManifestTicket {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub redeem_key_hash: String,
+    pub status: TicketInstanceStatus,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ScannerManifestAddOnVoucher {
+    pub id: Uuid,
+    pub add_on_id: Uuid,
+    pub redeem_key_hash: String,
+    pub status: AddOnVoucherStatus,
+}
+
+/// PBKDF2 iterations used to hash redeem keys in scanner manifests. Redeem keys are short enough
+/// to guess, so each guess is made expensive to keep the hashes from being reversed.
+pub const REDEEM_KEY_HASH_ITERATIONS: u32 = 10_000;
+
+/// Everything a door scanner needs to validate tickets and add-on vouchers without a connection.
+/// Redeem keys are only included as slow hashes salted with the ticket or voucher id, which makes
+/// recovering keys from a copied manifest costly but not impossible, so manifests should still be
+/// kept to scanning devices. The manifest is signed with a dedicated scanner key so devices can
+/// verify it was issued by Big Neon.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ScannerManifest {
+    pub event_id: Uuid,
+    pub generated_at: NaiveDateTime,
+    pub redeem_key_hash_iterations: u32,
+    pub tickets: Vec<ScannerManifestTicket>,
+    pub add_on_vouchers: Vec<ScannerManifestAddOnVoucher>,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl NewOfflineRedemption {
+    pub fn commit(&self, conn: &PgConnection) -> Result<OfflineRedemption, DatabaseError> {
+        diesel::insert_into(offline_redemptions::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not record offline redemption",
+            )
+    }
+}
+
+impl OfflineRedemption {
+    pub fn find_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OfflineRedemption>, DatabaseError> {
+        offline_redemptions::table
+            .filter(offline_redemptions::event_id.eq(event_id))
+            .order_by(offline_redemptions::scanned_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load offline redemptions")
+    }
+
+    fn find_redeemed(
+        scan: &OfflineScan,
+        conn: &PgConnection,
+    ) -> Result<Option<OfflineRedemption>, DatabaseError> {
+        let mut query = offline_redemptions::table
+            .filter(offline_redemptions::status.eq(OfflineRedemptionStatus::Redeemed))
+            .into_boxed();
+        query = match (scan.ticket_instance_id, scan.add_on_voucher_id) {
+            (Some(ticket_instance_id), _) => {
+                query.filter(offline_redemptions::ticket_instance_id.eq(ticket_instance_id))
+            }
+            (None, Some(add_on_voucher_id)) => {
+                query.filter(offline_redemptions::add_on_voucher_id.eq(add_on_voucher_id))
+            }
+            (None, None) => return Ok(None),
+        };
+        query
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load offline redemption")
+    }
+
+    /// Applies scans uploaded by a device in the order they were made. Tickets and vouchers
+    /// already redeemed by another device (or online) are reported as conflicts along with the
+    /// earlier scan. Uploading the same scans again is safe, so devices can retry a failed sync.
+    pub fn reconcile(
+        event_id: Uuid,
+        device_id: &str,
+        scans: Vec<OfflineScan>,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ScannerSyncResult, DatabaseError> {
+        let mut scans = scans;
+        scans.sort_by_key(|s| s.scanned_at);
+
+        let mut result = ScannerSyncResult::default();
+        for scan in scans {
+            let status = match (scan.ticket_instance_id, scan.add_on_voucher_id) {
+                (Some(ticket_instance_id), None) => OfflineRedemption::redeem_ticket(
+                    event_id,
+                    ticket_instance_id,
+                    &scan,
+                    user_id,
+                    conn,
+                )?,
+                (None, Some(add_on_voucher_id)) => OfflineRedemption::redeem_add_on_voucher(
+                    event_id,
+                    add_on_voucher_id,
+                    &scan,
+                    user_id,
+                    conn,
+                )?,
+                _ => None,
+            };
+            let status = match status {
+                Some(status) => status,
+                None => {
+                    result.conflicts.push(ScanConflict {
+                        ticket_instance_id: scan.ticket_instance_id,
+                        add_on_voucher_id: scan.add_on_voucher_id,
+                        scanned_at: scan.scanned_at,
+                        status: OfflineRedemptionStatus::Invalid,
+                        previous_device_id: None,
+                        previous_scanned_at: None,
+                    });
+                    continue;
+                }
+            };
+
+            let previous = match status {
+                OfflineRedemptionStatus::AlreadyRedeemed => {
+                    OfflineRedemption::find_redeemed(&scan, conn)?
+                }
+                _ => None,
+            };
+            // A repeated upload of a scan that was already applied is not a conflict
+            if let Some(ref previous) = previous {
+                if previous.device_id == device_id && previous.scanned_at == scan.scanned_at {
+                    continue;
+                }
+            }
+
+            NewOfflineRedemption {
+                event_id,
+                ticket_instance_id: scan.ticket_instance_id,
+                add_on_voucher_id: scan.add_on_voucher_id,
+                device_id: device_id.to_string(),
+                redeemed_by: user_id,
+                scanned_at: scan.scanned_at,
+                status,
+            }
+            .commit(conn)?;
+
+            match (status, scan.ticket_instance_id, scan.add_on_voucher_id) {
+                (OfflineRedemptionStatus::Redeemed, Some(ticket_instance_id), _) => {
+                    result.redeemed.push(ticket_instance_id)
+                }
+                (OfflineRedemptionStatus::Redeemed, None, Some(add_on_voucher_id)) => {
+                    result.redeemed_add_on_vouchers.push(add_on_voucher_id)
+                }
+                _ => result.conflicts.push(ScanConflict {
+                    ticket_instance_id: scan.ticket_instance_id,
+                    add_on_voucher_id: scan.add_on_voucher_id,
+                    scanned_at: scan.scanned_at,
+                    status,
+                    previous_device_id: previous.as_ref().map(|p| p.device_id.clone()),
+                    previous_scanned_at: previous.map(|p| p.scanned_at),
+                }),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Redeems a scanned ticket, `None` if the ticket is not for the event
+    fn redeem_ticket(
+        event_id: Uuid,
+        ticket_instance_id: Uuid,
+        scan: &OfflineScan,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<OfflineRedemptionStatus>, DatabaseError> {
+        let ticket_event_id: Option<Uuid> = ticket_instances::table
+            .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+            .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+            .filter(ticket_instances::id.eq(ticket_instance_id))
+            .select(ticket_types::event_id)
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
+        if ticket_event_id != Some(event_id) {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            match TicketInstance::redeem_ticket(
+                ticket_instance_id,
+                scan.redeem_key.clone(),
+                user_id,
+                conn,
+            )? {
+                RedeemResults::TicketRedeemSuccess => OfflineRedemptionStatus::Redeemed,
+                RedeemResults::TicketAlreadyRedeemed => OfflineRedemptionStatus::AlreadyRedeemed,
+                RedeemResults::TicketInvalid => OfflineRedemptionStatus::Invalid,
+            },
+        ))
+    }
+
+    /// Redeems a scanned add-on voucher, `None` if the voucher is not for the event
+    fn redeem_add_on_voucher(
+        event_id: Uuid,
+        add_on_voucher_id: Uuid,
+        scan: &OfflineScan,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<OfflineRedemptionStatus>, DatabaseError> {
+        let voucher = match AddOnVoucher::find(add_on_voucher_id, conn).optional()? {
+            Some(voucher) => voucher,
+            None => return Ok(None),
+        };
+        let voucher_event_id: Uuid = add_ons::table
+            .find(voucher.add_on_id)
+            .select(add_ons::event_id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load add-on")?;
+        if voucher_event_id != event_id {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            match voucher.redeem(&scan.redeem_key, user_id, conn)? {
+                AddOnVoucherRedeemResults::VoucherRedeemSuccess => {
+                    OfflineRedemptionStatus::Redeemed
+                }
+                AddOnVoucherRedeemResults::VoucherAlreadyRedeemed => {
+                    OfflineRedemptionStatus::AlreadyRedeemed
+                }
+                AddOnVoucherRedeemResults::VoucherInvalid => OfflineRedemptionStatus::Invalid,
+            },
+        ))
+    }
+}
+
+impl ScannerManifest {
+    pub fn for_event(
+        event: &Event,
+        signing_secret_key: &String,
+        conn: &PgConnection,
+    ) -> Result<ScannerManifest, DatabaseError> {
+        let tickets: Vec<(Uuid, Uuid, Option<String>, TicketInstanceStatus)> =
+            ticket_instances::table
+                .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+                .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+                .filter(ticket_types::event_id.eq(event.id))
+                .filter(ticket_instances::status.eq_any(vec![
+                    TicketInstanceStatus::Purchased,
+                    TicketInstanceStatus::Redeemed,
+                ]))
+                .filter(ticket_instances::redeem_key.is_not_null())
+                .order_by(ticket_instances::id)
+                .select((
+                    ticket_instances::id,
+                    ticket_types::id,
+                    ticket_instances::redeem_key,
+                    ticket_instances::status,
+                ))
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load tickets for manifest")?;
+
+        let tickets: Vec<ScannerManifestTicket> = tickets
+            .into_iter()
+            .map(
+                |(id, ticket_type_id, redeem_key, status)| ScannerManifestTicket {
+                    id,
+                    ticket_type_id,
+                    redeem_key_hash: ScannerManifest::hash_redeem_key(
+                        id,
+                        &redeem_key.unwrap_or_default(),
+                    ),
+                    status,
+                },
+            )
+            .collect();
+
+        let add_on_vouchers: Vec<ScannerManifestAddOnVoucher> =
+            AddOnVoucher::find_for_event(event.id, conn)?
+                .into_iter()
+                .map(|voucher| ScannerManifestAddOnVoucher {
+                    id: voucher.id,
+                    add_on_id: voucher.add_on_id,
+                    redeem_key_hash: ScannerManifest::hash_redeem_key(
+                        voucher.id,
+                        &voucher.redeem_key,
+                    ),
+                    status: voucher.status,
+                })
+                .collect();
+
+        let generated_at = Utc::now().naive_utc();
+        let secret_key = convert_hexstring_to_bytes(signing_secret_key);
+        let public_key = convert_bytes_to_hexstring(&cryptographic_public_key(&secret_key)?);
+        let message = ScannerManifest::signing_message(
+            event.id,
+            generated_at,
+            REDEEM_KEY_HASH_ITERATIONS,
+            &tickets,
+            &add_on_vouchers,
+        );
+        let signature =
+            convert_bytes_to_hexstring(&cryptographic_signature(&message, &secret_key)?);
+
+        Ok(ScannerManifest {
+            event_id: event.id,
+            generated_at,
+            redeem_key_hash_iterations: REDEEM_KEY_HASH_ITERATIONS,
+            tickets,
+            add_on_vouchers,
+            public_key,
+            signature,
+        })
+    }
+
+    /// Hex encoded PBKDF2-HMAC-SHA256 of the redeem key, as found in the ticket QR code, salted
+    /// with the id of the ticket or add-on voucher it belongs to
+    pub fn hash_redeem_key(id: Uuid, redeem_key: &str) -> String {
+        let mut hash = [0u8; digest::SHA256_OUTPUT_LEN];
+        pbkdf2::derive(
+            &digest::SHA256,
+            REDEEM_KEY_HASH_ITERATIONS,
+            id.as_bytes(),
+            redeem_key.as_bytes(),
+            &mut hash,
+        );
+        hex::encode(hash)
+    }
+
+    /// The signed message is the event id, generation timestamp and hash iterations followed by
+    /// the id, redeem key hash and status of every ticket and then every add-on voucher in
+    /// manifest order
+    pub fn signing_message(
+        event_id: Uuid,
+        generated_at: NaiveDateTime,
+        redeem_key_hash_iterations: u32,
+        tickets: &[ScannerManifestTicket],
+        add_on_vouchers: &[ScannerManifestAddOnVoucher],
+    ) -> String {
+        let mut message = event_id.to_string();
+        message.push_str(&generated_at.timestamp().to_string());
+        message.push_str(&redeem_key_hash_iterations.to_string());
+        for ticket in tickets {
+            message.push_str(&ticket.id.to_string());
+            message.push_str(&ticket.redeem_key_hash);
+            message.push_str(&ticket.status.to_string());
+        }
+        for voucher in add_on_vouchers {
+            message.push_str(&voucher.id.to_string());
+            message.push_str(&voucher.redeem_key_hash);
+            message.push_str(&voucher.status.to_string());
+        }
+        message
+    }
+
+    /// Devices should also check the public key matches the one they were provisioned with
+    pub fn verify(&self) -> bool {
+        cryptographic_verify(
+            &convert_hexstring_to_bytes(&self.signature),
+            &ScannerManifest::signing_message(
+                self.event_id,
+                self.generated_at,
+                self.redeem_key_hash_iterations,
+                &self.tickets,
+                &self.add_on_vouchers,
+            ),
+            &convert_hexstring_to_bytes(&self.public_key),
+        )
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::order_exchanges;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Purchased tickets on an order swapped for tickets of another ticket type
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Order)]
+#[table_name = "order_exchanges"]
+pub struct OrderExchange {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub exchanged_ticket_instance_ids: Vec<Uuid>,
+    pub ticket_instance_ids: Vec<Uuid>,
+    /// Price, fees and tax of the exchanged tickets credited back to the order
+    pub refunded_amount_in_cents: i64,
+    /// Price, fees and tax of the new tickets
+    pub charged_amount_in_cents: i64,
+    pub created_by_user_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "order_exchanges"]
+pub struct NewOrderExchange {
+    pub order_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub exchanged_ticket_instance_ids: Vec<Uuid>,
+    pub ticket_instance_ids: Vec<Uuid>,
+    pub refunded_amount_in_cents: i64,
+    pub charged_amount_in_cents: i64,
+    pub created_by_user_id: Uuid,
+}
+
+impl NewOrderExchange {
+    pub fn commit(self, conn: &PgConnection) -> Result<OrderExchange, DatabaseError> {
+        let exchange: OrderExchange = diesel::insert_into(order_exchanges::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order exchange")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrderTicketsExchanged,
+            "Tickets were exchanged".to_string(),
+            Tables::Orders,
+            Some(exchange.order_id),
+            Some(exchange.created_by_user_id),
+            Some(json!({
+                "order_exchange_id": exchange.id,
+                "ticket_type_id": exchange.ticket_type_id,
+                "exchanged_ticket_instance_ids": exchange.exchanged_ticket_instance_ids,
+                "ticket_instance_ids": exchange.ticket_instance_ids,
+                "refunded_amount_in_cents": exchange.refunded_amount_in_cents,
+                "charged_amount_in_cents": exchange.charged_amount_in_cents,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(exchange)
+    }
+}
+
+impl OrderExchange {
+    pub fn find_for_order(
+        order_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrderExchange>, DatabaseError> {
+        order_exchanges::table
+            .filter(order_exchanges::order_id.eq(order_id))
+            .order_by(order_exchanges::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load order exchanges")
+    }
+
+    /// Amount still owed by the buyer for the exchange, negative when it is owed to the buyer
+    pub fn balance_in_cents(&self) -> i64 {
+        self.charged_amount_in_cents - self.refunded_amount_in_cents
+    }
+}
@@ -39,6 +39,10 @@ pub struct OrderItem {
     pub(crate) company_fee_in_cents: i64,
     pub(crate) client_fee_in_cents: i64,
     pub refunded_quantity: i64,
+    pub tax_rate_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+    pub bundle_id: Option<Uuid>,
+    pub add_on_id: Option<Uuid>,
 }
 
 impl OrderItem {
@@ -51,6 +55,24 @@ impl OrderItem {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve order item fees")
     }
 
+    pub fn find_tax_item(&self, conn: &PgConnection) -> Result<Option<OrderItem>, DatabaseError> {
+        order_items::table
+            .filter(order_items::parent_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tax))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve order item tax")
+    }
+
+    /// Amount paid for one unit of this item, including the tax charged on it
+    pub fn unit_price_with_tax_in_cents(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self.unit_price_in_cents
+            + match self.find_tax_item(conn)? {
+                Some(tax_item) => tax_item.unit_price_in_cents,
+                None => 0,
+            })
+    }
+
     pub(crate) fn refund_one_unit(
         &mut self,
         refund_fees: bool,
@@ -69,13 +91,26 @@ impl OrderItem {
         self.refunded_quantity += 1;
 
         let mut refund_amount_in_cents = self.unit_price_in_cents;
-        // Refund fees if ticket is being refunded
-        if refund_fees && self.item_type == OrderItemTypes::Tickets {
+        // Refund fees if ticket or bundle is being refunded
+        if refund_fees
+            && (self.item_type == OrderItemTypes::Tickets
+                || self.item_type == OrderItemTypes::Bundle)
+        {
             let fee_item = self.find_fee_item(conn)?;
             if let Some(mut fee_item) = fee_item {
                 refund_amount_in_cents += fee_item.refund_one_unit(true, conn)? as i64;
             }
         }
+        // Tax is refunded along with the item it was charged on, so tax on fees is only
+        // refunded when the fees are
+        if let Some(mut tax_item) = self.find_tax_item(conn)? {
+            refund_amount_in_cents += tax_item.refund_one_unit(false, conn)? as i64;
+        }
+
+        // A refunded add-on can no longer be collected with its voucher
+        if self.item_type == OrderItemTypes::AddOn {
+            AddOnVoucher::void_for_refund(self, conn)?;
+        }
 
         diesel::update(order_items::table.filter(order_items::id.eq(self.id)))
             .set((
@@ -113,27 +148,37 @@ impl OrderItem {
     ) -> Result<(), DatabaseError> {
         if self.item_type == OrderItemTypes::PerUnitFees
             || self.item_type == OrderItemTypes::EventFees
+            || self.item_type == OrderItemTypes::Tax
         {
             return Ok(());
         }
 
+        // Tickets included in a bundle are charged fees through the bundle item
+        if self.item_type == OrderItemTypes::Tickets && self.bundle_id.is_some() {
+            return Ok(());
+        }
+
         let fee_item = self.find_fee_item(conn)?;
 
-        let ticket_type = match self.ticket_type_id {
-            Some(ticket_type_id) => TicketType::find(ticket_type_id, conn)?,
-            None => {
+        let fee_schedule = match (self.item_type, self.bundle_id, self.ticket_type_id) {
+            (OrderItemTypes::Bundle, Some(bundle_id), _) => {
+                Bundle::find(bundle_id, conn)?.fee_schedule(conn)?
+            }
+            (_, _, Some(ticket_type_id)) => {
+                TicketType::find(ticket_type_id, conn)?.fee_schedule(conn)?
+            }
+            _ => {
                 return DatabaseError::no_results("Order item does not have a valid ticket type");
             }
         };
 
-        let fee_schedule_ranges = ticket_type.fee_schedule(conn)?.ranges(conn)?;
+        let fee_schedule_ranges = fee_schedule.ranges(conn)?;
+        let unit_price_in_cents = self.fee_unit_price_in_cents(conn)?;
 
         if fee_schedule_ranges.len() > 0
-            && self.unit_price_in_cents >= fee_schedule_ranges[0].min_price_in_cents
+            && unit_price_in_cents >= fee_schedule_ranges[0].min_price_in_cents
         {
-            let fee_schedule_range = ticket_type
-                .fee_schedule(conn)?
-                .get_range(self.unit_price_in_cents, conn)?;
+            let fee_schedule_range = fee_schedule.get_range(unit_price_in_cents, conn)?;
 
             // If the hold is a comp, then there are no fees.
             if let Some(hold_id) = self.hold_id {
@@ -179,6 +224,23 @@ impl OrderItem {
         }
     }
 
+    /// Price the fee is charged on, bundles are charged on the full bundle price which is
+    /// allocated across their included tickets
+    fn fee_unit_price_in_cents(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        if self.item_type != OrderItemTypes::Bundle || self.quantity == 0 {
+            return Ok(self.unit_price_in_cents);
+        }
+        let included_price_in_cents: i64 = order_items::table
+            .filter(order_items::parent_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tickets))
+            .select(dsl::sql::<BigInt>(
+                "CAST(COALESCE(SUM(unit_price_in_cents * quantity), 0) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load bundle price")?;
+        Ok(self.unit_price_in_cents + included_price_in_cents / self.quantity)
+    }
+
     pub(crate) fn update(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         self.validate_record(conn)?;
         diesel::update(self)
@@ -388,16 +450,25 @@ impl OrderItem {
            oi.quantity,
            oi.refunded_quantity,
            oi.unit_price_in_cents,
+           oi.tax_in_cents,
            oi.item_type,
            CASE
              WHEN item_type = 'PerUnitFees' THEN 'Ticket Fees'
              WHEN item_type = 'EventFees' THEN 'Event Fees - ' || e.name
+             WHEN item_type = 'Tax' THEN COALESCE(tr.name, 'Tax')
+                 || CASE WHEN oi.unit_price_in_cents = 0 THEN ' (included)' ELSE '' END
+             WHEN item_type = 'Resale' THEN 'Resale - ' || e.name || ' - ' || tt.name
+             WHEN item_type = 'Bundle' THEN 'Bundle - ' || b.name
+             WHEN item_type = 'AddOn' THEN e.name || ' - ' || ao.name
              ELSE e.name || ' - ' || tt.name
            END AS description,
            COALESCE(h.redemption_code, c.redemption_code) as redemption_code,
            CASE
              -- Null prevents serialization
              WHEN o.status <> 'Draft' THEN null
+             WHEN item_type = 'Resale' AND NOT EXISTS (
+                 SELECT 1 FROM resale_listings rl WHERE rl.order_item_id = oi.id AND rl.status = 'Active'
+             ) THEN 'ResaleListingUnavailable'
              WHEN item_type <> 'Tickets' THEN 'Valid'
              WHEN ti.status = 'Nullified' THEN 'TicketNullified'
              WHEN oit.count <> oi.quantity OR ti.reserved_until < now() THEN 'TicketNotReserved'
@@ -411,7 +482,7 @@ impl OrderItem {
            LEFT JOIN events e ON oi.event_id = e.id
            LEFT JOIN users u on u.id = $3
            LEFT JOIN organization_users ou ON ou.organization_id = e.organization_id and ou.user_id = $3
-           LEFT JOIN ticket_types tt ON tp.ticket_type_id = tt.id
+           LEFT JOIN ticket_types tt ON tt.id = COALESCE(tp.ticket_type_id, oi.ticket_type_id)
            LEFT JOIN holds h ON oi.hold_id = h.id
            LEFT JOIN ticket_instances ti ON ti.id = (
                SELECT ti.id
@@ -423,6 +494,9 @@ impl OrderItem {
                LIMIT 1
            )
            LEFT JOIN codes c ON oi.code_id = c.id
+           LEFT JOIN tax_rates tr ON oi.tax_rate_id = tr.id
+           LEFT JOIN bundles b ON oi.bundle_id = b.id
+           LEFT JOIN add_ons ao ON oi.add_on_id = ao.id
            LEFT JOIN (
                SELECT count(ti.id) as count, oi.id
                FROM order_items oi
@@ -577,6 +651,90 @@ impl NewFeesOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewTaxOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub tax_rate_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+    pub parent_id: Option<Uuid>,
+}
+
+impl NewTaxOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewResaleOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub ticket_type_id: Uuid,
+}
+
+impl NewResaleOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable)]
+#[table_name = "order_items"]
+pub(crate) struct NewBundleOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub ticket_type_id: Option<Uuid>,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub parent_id: Option<Uuid>,
+    pub bundle_id: Uuid,
+}
+
+impl NewBundleOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable)]
+#[table_name = "order_items"]
+pub(crate) struct NewAddOnOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub add_on_id: Uuid,
+}
+
+impl NewAddOnOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
 #[derive(Deserialize, Queryable, QueryableByName, Serialize)]
 pub struct DisplayOrderItem {
     #[sql_type = "dUuid"]
@@ -593,6 +751,8 @@ pub struct DisplayOrderItem {
     pub refunded_quantity: i64,
     #[sql_type = "BigInt"]
     pub unit_price_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents: i64,
     #[sql_type = "Text"]
     pub item_type: OrderItemTypes,
     #[sql_type = "Text"]
@@ -46,6 +46,7 @@ pub struct Order {
     pub checkout_url_expires: Option<NaiveDateTime>,
     pub create_user_agent: Option<String>,
     pub purchase_user_agent: Option<String>,
+    pub currency: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -78,7 +79,7 @@ pub struct UpdateOrderAttributes {
     pub note: Option<Option<String>>,
 }
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
 pub struct RefundItem {
     pub order_item_id: Uuid,
     pub ticket_instance_id: Option<Uuid>,
@@ -97,6 +98,8 @@ pub struct OrderDetailsLineItem {
     #[sql_type = "BigInt"]
     pub fees_price_in_cents: i64,
     #[sql_type = "BigInt"]
+    pub tax_price_in_cents: i64,
+    #[sql_type = "BigInt"]
     pub total_price_in_cents: i64,
     #[sql_type = "Text"]
     pub status: String,
@@ -137,6 +140,17 @@ impl Order {
             );
         }
 
+        // delete the tax charged on children order items
+        let child_ids: Vec<Uuid> = order_items::table
+            .filter(order_items::parent_id.eq(item_id))
+            .select(order_items::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load child order items")?;
+        diesel::delete(order_items::table.filter(order_items::parent_id.eq_any(child_ids)))
+            .execute(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::DeleteError, "Could not delete child order item")?;
+
         // delete children order items
         diesel::delete(order_items::table.filter(order_items::parent_id.eq(item_id)))
             .execute(conn)
@@ -149,6 +163,23 @@ impl Order {
             .to_db_error(ErrorCode::DeleteError, "Could not delete order item")
     }
 
+    /// Removes a bundle and its included tickets from the cart, releasing the reserved tickets
+    pub(crate) fn remove_bundle_item(
+        &self,
+        bundle_item: &OrderItem,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        for item in self.items(conn)? {
+            if item.item_type == OrderItemTypes::Tickets && item.parent_id == Some(bundle_item.id) {
+                // Use calculated quantity as reserved may have been taken in the meantime
+                let quantity = item.calculate_quantity(conn)?;
+                TicketInstance::release_tickets(&item, quantity as u32, user_id, conn)?;
+            }
+        }
+        self.destroy_item(bundle_item.id, conn)
+    }
+
     pub fn main_event_id(&self, conn: &PgConnection) -> Result<Uuid, DatabaseError> {
         for item in self.items(conn)? {
             if let Some(event_id) = item.event_id {
@@ -165,6 +196,7 @@ impl Order {
         user_id: Uuid,
         conn: &PgConnection,
     ) -> Result<u32, DatabaseError> {
+        let refund_items = self.expand_bundle_refund_items(refund_items, conn)?;
         let mut total_to_be_refunded: u32 = 0;
         for refund_item in refund_items {
             let mut order_item = OrderItem::find(refund_item.order_item_id, conn)?;
@@ -180,6 +212,19 @@ impl Order {
                 None => None,
             };
 
+            // Refunding a resale purchase reverses the sale, there are no fees to refund
+            if order_item.item_type == OrderItemTypes::Resale {
+                ResaleListing::refund_sale(&order_item, user_id, conn)?;
+                total_to_be_refunded += order_item.refund_one_unit(false, conn)?;
+                continue;
+            }
+
+            if order_item.item_type == OrderItemTypes::Tax {
+                return DatabaseError::business_process_error(
+                    "Tax is refunded along with the ticket it was charged on",
+                );
+            }
+
             if order_item.item_type == OrderItemTypes::Tickets
                 || order_item.item_type == OrderItemTypes::PerUnitFees
             {
@@ -205,6 +250,15 @@ impl Order {
                             return DatabaseError::business_process_error(
                                 "Ticket was transferred so ineligible for refund",
                             );
+                        } else if ResaleListing::find_active_for_ticket_instance(
+                            ticket_instance.id,
+                            conn,
+                        )?
+                        .is_some()
+                        {
+                            return DatabaseError::business_process_error(
+                                "Tickets listed for resale cannot be refunded",
+                            );
                         }
 
                         let only_refund_fees = order_item.item_type == OrderItemTypes::PerUnitFees;
@@ -235,6 +289,351 @@ impl Order {
         Ok(total_to_be_refunded)
     }
 
+    /// Adds the included tickets to each bundle being refunded, a refund item for a bundle item
+    /// refunds one bundle. Tickets of the bundle already listed are used first, the rest are
+    /// picked from the bundle's tickets not yet refunded. Bundle tickets cannot be refunded
+    /// without their bundle.
+    pub fn expand_bundle_refund_items(
+        &self,
+        refund_items: Vec<RefundItem>,
+        conn: &PgConnection,
+    ) -> Result<Vec<RefundItem>, DatabaseError> {
+        let order_items = self.items(conn)?;
+        let mut bundles_refunded: HashMap<Uuid, i64> = HashMap::new();
+        for refund_item in &refund_items {
+            if let Some(order_item) = order_items
+                .iter()
+                .find(|i| i.id == refund_item.order_item_id)
+            {
+                if order_item.item_type == OrderItemTypes::Bundle {
+                    *bundles_refunded.entry(order_item.id).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut expanded = refund_items.clone();
+        for order_item in order_items
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tickets && i.bundle_id.is_some())
+        {
+            let listed: Vec<Uuid> = refund_items
+                .iter()
+                .filter(|r| r.order_item_id == order_item.id)
+                .filter_map(|r| r.ticket_instance_id)
+                .collect();
+            let bundle_item = order_item
+                .parent_id
+                .and_then(|parent_id| order_items.iter().find(|i| i.id == parent_id));
+            let required = match bundle_item {
+                Some(bundle_item) if bundle_item.quantity > 0 => {
+                    bundles_refunded.get(&bundle_item.id).cloned().unwrap_or(0)
+                        * (order_item.quantity / bundle_item.quantity)
+                }
+                _ => 0,
+            };
+            if listed.len() as i64 > required {
+                return DatabaseError::business_process_error(
+                    "Bundle tickets can only be refunded with their bundle",
+                );
+            }
+            if listed.len() as i64 == required {
+                continue;
+            }
+
+            let mut ticket_instances: Vec<TicketInstance> =
+                TicketInstance::find_for_order_item(order_item.id, conn)?
+                    .into_iter()
+                    .filter(|t| {
+                        !listed.contains(&t.id)
+                            && (t.status == TicketInstanceStatus::Purchased
+                                || t.status == TicketInstanceStatus::Redeemed)
+                    })
+                    .collect();
+            let refunded_ids: Vec<Uuid> = RefundedTicket::find_by_ticket_instance_ids(
+                ticket_instances.iter().map(|t| t.id).collect(),
+                conn,
+            )?
+            .into_iter()
+            .filter(|r| r.ticket_refunded_at.is_some())
+            .map(|r| r.ticket_instance_id)
+            .collect();
+            ticket_instances.retain(|t| !refunded_ids.contains(&t.id));
+            ticket_instances.sort_by_key(|t| t.id);
+
+            let missing = (required - listed.len() as i64) as usize;
+            if ticket_instances.len() < missing {
+                return DatabaseError::business_process_error(
+                    "Not enough tickets remain to refund the bundle",
+                );
+            }
+            for ticket_instance in ticket_instances.into_iter().take(missing) {
+                expanded.push(RefundItem {
+                    order_item_id: order_item.id,
+                    ticket_instance_id: Some(ticket_instance.id),
+                });
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// Refunds every ticket left on the order after the payment provider returned the money
+    /// outside of Big Neon, e.g. a dashboard refund or a lost chargeback. The tickets are
+    /// nullified rather than released since the inventory was not planned to be resold. Tickets
+    /// held by nullifying them while a dispute was open are refunded as well.
+    pub fn refund_all_tickets(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<RefundedTicket>, DatabaseError> {
+        let mut refunded_tickets = Vec::new();
+        for mut order_item in self.items(conn)? {
+            if order_item.item_type != OrderItemTypes::Tickets {
+                continue;
+            }
+
+            for ticket_instance in TicketInstance::find_for_order_item(order_item.id, conn)? {
+                if ticket_instance.status == TicketInstanceStatus::Available
+                    || ticket_instance.status == TicketInstanceStatus::Reserved
+                {
+                    continue;
+                }
+
+                let mut refunded_ticket =
+                    RefundedTicket::find_or_create_by_ticket_instance(&ticket_instance, conn)?;
+                if refunded_ticket.ticket_refunded_at.is_some() {
+                    continue;
+                }
+                let refund_fees = refunded_ticket.fee_refunded_at.is_none();
+                refunded_ticket.mark_refunded(false, conn)?;
+                order_item.refund_one_unit(refund_fees, conn)?;
+                ResaleListing::withdraw_for_ticket_instance(
+                    ticket_instance.id,
+                    current_user_id,
+                    conn,
+                )?;
+                if ticket_instance.status != TicketInstanceStatus::Nullified {
+                    ticket_instance.nullify(current_user_id, conn)?;
+                }
+                refunded_tickets.push(refunded_ticket);
+            }
+        }
+
+        for mut event_fee_item in self.event_fee_items_with_no_associated_items(conn)? {
+            event_fee_item.refund_one_unit(true, conn)?;
+        }
+
+        Ok(refunded_tickets)
+    }
+
+    /// Refunds and nullifies the tickets left on the order for a cancelled event. Per ticket and
+    /// event fees are only refunded when `refund_fees` is set, tax is always refunded with the
+    /// ticket. Returns the amount owed to the buyer.
+    pub fn refund_cancelled_event_tickets(
+        &self,
+        event_id: Uuid,
+        refund_fees: bool,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<u32, DatabaseError> {
+        let mut total_to_be_refunded: u32 = 0;
+        for mut order_item in self.items(conn)? {
+            if order_item.event_id != Some(event_id) {
+                continue;
+            }
+            // Add-ons for a cancelled event can no longer be used so every remaining unit is refunded
+            if order_item.item_type == OrderItemTypes::AddOn {
+                while order_item.refunded_quantity < order_item.quantity {
+                    total_to_be_refunded += order_item.refund_one_unit(refund_fees, conn)?;
+                }
+                continue;
+            }
+            if order_item.item_type != OrderItemTypes::Tickets {
+                continue;
+            }
+
+            for ticket_instance in TicketInstance::find_for_order_item(order_item.id, conn)? {
+                if ticket_instance.status == TicketInstanceStatus::Available
+                    || ticket_instance.status == TicketInstanceStatus::Reserved
+                {
+                    continue;
+                }
+
+                let mut refunded_ticket =
+                    RefundedTicket::find_or_create_by_ticket_instance(&ticket_instance, conn)?;
+                if refunded_ticket.ticket_refunded_at.is_some() {
+                    continue;
+                }
+                let refund_ticket_fees = refund_fees && refunded_ticket.fee_refunded_at.is_none();
+                if refund_ticket_fees {
+                    refunded_ticket.mark_refunded(false, conn)?;
+                } else {
+                    refunded_ticket.mark_ticket_refunded(conn)?;
+                }
+                total_to_be_refunded += order_item.refund_one_unit(refund_ticket_fees, conn)?;
+                ResaleListing::withdraw_for_ticket_instance(
+                    ticket_instance.id,
+                    current_user_id,
+                    conn,
+                )?;
+                if ticket_instance.status != TicketInstanceStatus::Nullified {
+                    ticket_instance.nullify(current_user_id, conn)?;
+                }
+            }
+        }
+
+        if refund_fees {
+            for mut event_fee_item in self.event_fee_items_with_no_associated_items(conn)? {
+                if event_fee_item.event_id == Some(event_id) {
+                    total_to_be_refunded += event_fee_item.refund_one_unit(true, conn)?;
+                }
+            }
+        }
+
+        Ok(total_to_be_refunded)
+    }
+
+    /// Swaps purchased tickets for tickets of another ticket type of the same organization. The
+    /// exchanged tickets are refunded and released, the new tickets are added to the order at the
+    /// current price with their fees and tax. Settling the difference with the buyer is left to
+    /// the caller using the returned exchange.
+    pub fn exchange_tickets(
+        &mut self,
+        ticket_instance_ids: &[Uuid],
+        ticket_type_id: Uuid,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<OrderExchange, DatabaseError> {
+        self.lock_version(conn)?;
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let event = Event::find(ticket_type.event_id, conn)?;
+        if let Some(ref currency) = self.currency {
+            if currency != &event.currency {
+                return DatabaseError::business_process_error(
+                    "Tickets can only be exchanged for tickets in the same currency",
+                );
+            }
+        }
+
+        let plan = RefundPlan::for_tickets(self, ticket_instance_ids, &[], conn)?;
+        let mut exchanged_ticket_instance_ids = vec![];
+        for item in &plan.items {
+            let order_item = OrderItem::find(item.order_item_id, conn)?;
+            if order_item.ticket_type_id == Some(ticket_type.id) {
+                return DatabaseError::business_process_error(
+                    "Tickets are already of the requested ticket type",
+                );
+            }
+            if let Some(event_id) = order_item.event_id {
+                if Event::find(event_id, conn)?.organization_id != event.organization_id {
+                    return DatabaseError::business_process_error(
+                        "Tickets can only be exchanged for events of the same organization",
+                    );
+                }
+            }
+            if let Some(ticket_instance_id) = item.ticket_instance_id {
+                if TicketInstance::find(ticket_instance_id, conn)?.status
+                    != TicketInstanceStatus::Purchased
+                {
+                    return DatabaseError::business_process_error(
+                        "Only purchased tickets that have not been redeemed can be exchanged",
+                    );
+                }
+                exchanged_ticket_instance_ids.push(ticket_instance_id);
+            }
+        }
+
+        let refunded_amount_in_cents = self.refund(plan.items, current_user_id, conn)? as i64;
+
+        let ticket_pricing = TicketPricing::get_current_ticket_pricing(
+            ticket_type.id,
+            self.box_office_pricing,
+            conn,
+        )?;
+        let order_item = NewTicketsOrderItem {
+            order_id: self.id,
+            item_type: OrderItemTypes::Tickets,
+            quantity: exchanged_ticket_instance_ids.len() as i64,
+            ticket_type_id: ticket_type.id,
+            ticket_pricing_id: ticket_pricing.id,
+            event_id: Some(event.id),
+            unit_price_in_cents: ticket_pricing.price_in_cents,
+            hold_id: None,
+            code_id: None,
+        }
+        .commit(conn)?;
+        TicketInstance::reserve_tickets(
+            &order_item,
+            Some(Utc::now().naive_utc() + Duration::minutes(CART_EXPIRY_TIME_MINUTES)),
+            ticket_type.id,
+            None,
+            exchanged_ticket_instance_ids.len() as u32,
+            conn,
+        )?;
+
+        let mut new_item_ids = vec![order_item.id];
+        if !self.box_office_pricing {
+            order_item.update_fees(self, conn)?;
+
+            // The event fee is charged again if it was refunded with the exchanged tickets or the
+            // tickets are for an event not yet on the order
+            let has_event_fee = self.items(conn)?.iter().any(|i| {
+                i.item_type == OrderItemTypes::EventFees
+                    && i.event_id == Some(event.id)
+                    && i.refunded_quantity < i.quantity
+            });
+            if !has_event_fee && event.fee_in_cents > 0 && order_item.unit_price_in_cents > 0 {
+                let event_fee_item = NewFeesOrderItem {
+                    order_id: self.id,
+                    item_type: OrderItemTypes::EventFees,
+                    event_id: Some(event.id),
+                    unit_price_in_cents: event.client_fee_in_cents + event.company_fee_in_cents,
+                    fee_schedule_range_id: None,
+                    company_fee_in_cents: event.company_fee_in_cents,
+                    client_fee_in_cents: event.client_fee_in_cents,
+                    quantity: 1,
+                    parent_id: None,
+                }
+                .commit(conn)?;
+                new_item_ids.push(event_fee_item.id);
+            }
+        }
+        if order_item.unit_price_in_cents > 0 {
+            if let Some(tax_rate) = TaxRate::find_for_event(&event, conn)? {
+                self.add_tax_item(&order_item, &tax_rate, conn)?;
+            }
+        }
+
+        TicketInstance::mark_as_purchased(
+            &order_item,
+            self.on_behalf_of_user_id.unwrap_or(self.user_id),
+            conn,
+        )?;
+
+        let charged_amount_in_cents = self
+            .items(conn)?
+            .iter()
+            .filter(|i| new_item_ids.contains(&i.id) || i.parent_id == Some(order_item.id))
+            .map(|i| i.unit_price_in_cents * i.quantity)
+            .sum();
+        NewOrderExchange {
+            order_id: self.id,
+            ticket_type_id: ticket_type.id,
+            exchanged_ticket_instance_ids,
+            ticket_instance_ids: TicketInstance::find_for_order_item(order_item.id, conn)?
+                .iter()
+                .map(|t| t.id)
+                .collect(),
+            refunded_amount_in_cents,
+            charged_amount_in_cents,
+            created_by_user_id: current_user_id,
+        }
+        .commit(conn)
+    }
+
+    pub fn exchanges(&self, conn: &PgConnection) -> Result<Vec<OrderExchange>, DatabaseError> {
+        OrderExchange::find_for_order(self.id, conn)
+    }
+
     fn event_fee_items_with_no_associated_items(
         &self,
         conn: &PgConnection,
@@ -507,6 +906,20 @@ impl Order {
         Ok(())
     }
 
+    /// Tickets are reserved until the cart expires. Carts holding a waitlist offer are kept until
+    /// the offer lapses, other tickets added to them are only reserved for the usual cart time.
+    fn reservation_expires_at(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Option<NaiveDateTime>, DatabaseError> {
+        if self.expires_at.is_some() && WaitlistEntry::has_offer_for_order(self.id, conn)? {
+            let cart_expires_at =
+                Utc::now().naive_utc() + Duration::minutes(CART_EXPIRY_TIME_MINUTES);
+            return Ok(self.expires_at.map(|e| cmp::min(e, cart_expires_at)));
+        }
+        Ok(self.expires_at)
+    }
+
     /// Removes the expiry time for an order. This can only be done when there are no
     /// tickets in the order, otherwise the tickets will remain reserved until the expiry
     pub fn remove_expiry(
@@ -599,7 +1012,20 @@ impl Order {
         self.lock_version(conn)?;
 
         for mut current_line in self.items(conn)? {
-            if current_line.item_type != OrderItemTypes::Tickets {
+            if current_line.item_type == OrderItemTypes::Resale {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            }
+            if current_line.item_type == OrderItemTypes::Bundle {
+                self.remove_bundle_item(&current_line, user_id, conn)?;
+                continue;
+            }
+            if current_line.item_type == OrderItemTypes::AddOn {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            }
+            if current_line.item_type != OrderItemTypes::Tickets || current_line.bundle_id.is_some()
+            {
                 continue;
             }
             // Use calculated quantity as reserved may have been taken in the meantime no longer pointing to this order item
@@ -627,7 +1053,7 @@ impl Order {
             self.update_box_office_pricing(box_office_pricing, current_user_id, conn)?;
         }
 
-        let current_items = self.items(conn)?;
+        let mut current_items = self.items(conn)?;
 
         #[derive(Debug)]
         struct LimitCheck {
@@ -649,6 +1075,15 @@ impl Order {
 
         let mut mapped = vec![];
         for (index, item) in items.iter().enumerate() {
+            if let Some(ref seat_ids) = item.seat_ids {
+                if seat_ids.len() as u32 != item.quantity {
+                    return DatabaseError::validation_error(
+                        "seat_ids",
+                        "Number of seats selected must match the quantity requested",
+                    );
+                }
+            }
+
             mapped.push(match &item.redemption_code {
                 Some(r) => match Hold::find_by_redemption_code(r, conn).optional()? {
                     Some(hold) => MatchData {
@@ -690,8 +1125,62 @@ impl Order {
             });
         }
 
+        // Quantity tiers can split tickets of the same type across several cart items, these are
+        // replaced as a whole when the requested quantity changes so each tier is recalculated
+        for match_data in mapped.iter_mut() {
+            if match_data.update_order_item.seat_ids.is_some() {
+                continue;
+            }
+            let group: Vec<OrderItem> = current_items
+                .iter()
+                .filter(|i| {
+                    i.item_type == OrderItemTypes::Tickets
+                        && i.bundle_id.is_none()
+                        && i.ticket_type_id == Some(match_data.update_order_item.ticket_type_id)
+                        && i.hold_id == match_data.hold_id
+                        && i.code_id == match_data.code_id
+                })
+                .cloned()
+                .collect();
+            if group.is_empty()
+                || (group.len() == 1
+                    && !TicketPricing::has_quantity_tiers(
+                        match_data.update_order_item.ticket_type_id,
+                        conn,
+                    )?)
+            {
+                continue;
+            }
+
+            let group_quantity: i64 = group.iter().map(|i| i.quantity).sum();
+            if group_quantity == match_data.update_order_item.quantity as i64 {
+                match_data.index = None;
+            } else {
+                for item in &group {
+                    TicketInstance::release_tickets(
+                        item,
+                        item.quantity as u32,
+                        current_user_id,
+                        conn,
+                    )?;
+                    self.destroy_item(item.id, conn)?;
+                }
+            }
+            current_items.retain(|i| !group.iter().any(|g| g.id == i.id));
+        }
+
         for mut current_line in current_items {
-            if current_line.item_type != OrderItemTypes::Tickets {
+            if current_line.item_type == OrderItemTypes::Resale && remove_others {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            }
+            if current_line.item_type == OrderItemTypes::Bundle && remove_others {
+                self.remove_bundle_item(&current_line, current_user_id, conn)?;
+                continue;
+            }
+            // Tickets included in a bundle are only changed through the bundle
+            if current_line.item_type != OrderItemTypes::Tickets || current_line.bundle_id.is_some()
+            {
                 continue;
             }
 
@@ -708,7 +1197,57 @@ impl Order {
                 if let Some(match_data) = matching_result {
                     jlog!(Level::Debug, "Found an existing cart item, replacing");
                     index_to_remove = match_data.index;
-                    if current_line.quantity as u32 > match_data.update_order_item.quantity {
+                    if let Some(ref seat_ids) = match_data.update_order_item.seat_ids {
+                        jlog!(Level::Debug, "Replacing the seats of cart item");
+                        let reserved_tickets =
+                            TicketInstance::find_for_order_item(current_line.id, conn)?;
+                        for ticket in reserved_tickets.iter().filter(|ticket| {
+                            ticket
+                                .seat_id
+                                .map(|seat_id| !seat_ids.contains(&seat_id))
+                                .unwrap_or(true)
+                        }) {
+                            ticket.release(
+                                TicketInstanceStatus::Reserved,
+                                current_user_id,
+                                conn,
+                            )?;
+                        }
+
+                        let new_seat_ids: Vec<Uuid> = seat_ids
+                            .iter()
+                            .filter(|seat_id| {
+                                !reserved_tickets
+                                    .iter()
+                                    .any(|ticket| ticket.seat_id == Some(**seat_id))
+                            })
+                            .cloned()
+                            .collect();
+                        if !new_seat_ids.is_empty() {
+                            let ticket_type =
+                                TicketType::find(current_line.ticket_type_id.unwrap(), conn)?;
+                            check_ticket_limits.push(LimitCheck {
+                                limit_per_person: ticket_type.limit_per_person.clone(),
+                                ticket_type_id: ticket_type.id.clone(),
+                                event_id: ticket_type.event_id.clone(),
+                            });
+                            TicketInstance::reserve_seats(
+                                &current_line,
+                                self.reservation_expires_at(conn)?,
+                                ticket_type.id,
+                                match_data.hold_id,
+                                &new_seat_ids,
+                                conn,
+                            )?;
+                        }
+
+                        current_line.quantity = seat_ids.len() as i64;
+                        current_line.update(conn)?;
+                        if current_line.quantity == 0 {
+                            jlog!(Level::Debug, "Cart item has 0 quantity, deleting it");
+                            self.destroy_item(current_line.id, conn)?;
+                        }
+                    } else if current_line.quantity as u32 > match_data.update_order_item.quantity {
                         jlog!(Level::Debug, "Reducing quantity of cart item");
                         TicketInstance::release_tickets(
                             &current_line,
@@ -733,7 +1272,6 @@ impl Order {
                         let ticket_pricing = TicketPricing::get_current_ticket_pricing(
                             ticket_type_id,
                             box_office_pricing,
-                            false,
                             conn,
                         )?;
                         let ticket_type = TicketType::find(ticket_type_id, conn)?;
@@ -764,7 +1302,8 @@ impl Order {
                             let order_item = NewTicketsOrderItem {
                                 order_id: self.id,
                                 item_type: OrderItemTypes::Tickets,
-                                quantity: match_data.update_order_item.quantity as i64,
+                                quantity: match_data.update_order_item.quantity as i64
+                                    - current_line.quantity,
                                 ticket_type_id: ticket_type.id,
                                 ticket_pricing_id: ticket_pricing.id,
                                 event_id: Some(ticket_type.event_id),
@@ -775,7 +1314,7 @@ impl Order {
                             .commit(conn)?;
                             TicketInstance::reserve_tickets(
                                 &order_item,
-                                self.expires_at,
+                                self.reservation_expires_at(conn)?,
                                 ticket_type_id,
                                 match_data.hold_id,
                                 match_data.update_order_item.quantity
@@ -785,7 +1324,7 @@ impl Order {
                         } else {
                             TicketInstance::reserve_tickets(
                                 &current_line,
-                                self.expires_at,
+                                self.reservation_expires_at(conn)?,
                                 ticket_type_id,
                                 match_data.hold_id,
                                 match_data.update_order_item.quantity
@@ -818,26 +1357,12 @@ impl Order {
             self.set_expiry(Some(current_user_id), None, conn)?;
         }
 
-        for match_data in mapped {
-            if match_data.update_order_item.quantity == 0 || match_data.index.is_none() {
-                continue;
-            }
-
-            jlog!(Level::Debug, "Adding new cart items");
-            let ticket_pricing = TicketPricing::get_current_ticket_pricing(
-                match_data.update_order_item.ticket_type_id,
-                box_office_pricing,
-                false,
-                conn,
-            )?;
-            let ticket_type = TicketType::find(match_data.update_order_item.ticket_type_id, conn)?;
-
-            check_ticket_limits.push(LimitCheck {
-                limit_per_person: ticket_type.limit_per_person.clone(),
-                ticket_type_id: ticket_type.id.clone(),
-                event_id: ticket_type.event_id.clone(),
-            });
-
+        let order_id = self.id;
+        let add_tickets_item = |match_data: &MatchData,
+                                ticket_pricing: &TicketPricing,
+                                event_id: Uuid,
+                                quantity: u32|
+         -> Result<OrderItem, DatabaseError> {
             let mut price_in_cents = ticket_pricing.price_in_cents;
             if let Some(h) = match_data.hold.as_ref() {
                 let discount = h.discount_in_cents;
@@ -851,27 +1376,84 @@ impl Order {
             }
 
             // TODO: Move this to an external processer
-            let order_item = NewTicketsOrderItem {
-                order_id: self.id,
+            NewTicketsOrderItem {
+                order_id,
                 item_type: OrderItemTypes::Tickets,
-                quantity: match_data.update_order_item.quantity as i64,
-                ticket_type_id: ticket_type.id,
+                quantity: quantity as i64,
+                ticket_type_id: ticket_pricing.ticket_type_id,
                 ticket_pricing_id: ticket_pricing.id,
-                event_id: Some(ticket_type.event_id),
+                event_id: Some(event_id),
                 unit_price_in_cents: price_in_cents,
                 hold_id: match_data.hold_id,
                 code_id: match_data.code_id,
             }
-            .commit(conn)?;
+            .commit(conn)
+        };
 
-            TicketInstance::reserve_tickets(
-                &order_item,
-                self.expires_at,
-                match_data.update_order_item.ticket_type_id,
-                match_data.hold_id,
-                match_data.update_order_item.quantity,
-                conn,
-            )?;
+        for match_data in mapped {
+            if match_data.update_order_item.quantity == 0 || match_data.index.is_none() {
+                continue;
+            }
+
+            jlog!(Level::Debug, "Adding new cart items");
+            let ticket_type = TicketType::find(match_data.update_order_item.ticket_type_id, conn)?;
+            let event = Event::find(ticket_type.event_id, conn)?;
+            self.set_currency(&event.currency, conn)?;
+
+            check_ticket_limits.push(LimitCheck {
+                limit_per_person: ticket_type.limit_per_person.clone(),
+                ticket_type_id: ticket_type.id.clone(),
+                event_id: ticket_type.event_id.clone(),
+            });
+
+            if let Some(ref seat_ids) = match_data.update_order_item.seat_ids {
+                let ticket_pricing = TicketPricing::get_current_ticket_pricing(
+                    ticket_type.id,
+                    box_office_pricing,
+                    conn,
+                )?;
+                let order_item = add_tickets_item(
+                    &match_data,
+                    &ticket_pricing,
+                    event.id,
+                    seat_ids.len() as u32,
+                )?;
+                TicketInstance::reserve_seats(
+                    &order_item,
+                    self.reservation_expires_at(conn)?,
+                    ticket_type.id,
+                    match_data.hold_id,
+                    seat_ids,
+                    conn,
+                )?;
+                continue;
+            }
+
+            // A quantity tier only covers its remaining tickets, the rest are priced at the
+            // pricing that applies once it has sold through
+            let mut remaining = match_data.update_order_item.quantity;
+            while remaining > 0 {
+                let ticket_pricing = TicketPricing::get_current_ticket_pricing(
+                    ticket_type.id,
+                    box_office_pricing,
+                    conn,
+                )?;
+                let quantity = match ticket_pricing.remaining_quantity(conn)? {
+                    Some(tier_quantity) => cmp::min(remaining as i64, tier_quantity) as u32,
+                    None => remaining,
+                };
+                let order_item =
+                    add_tickets_item(&match_data, &ticket_pricing, event.id, quantity)?;
+                TicketInstance::reserve_tickets(
+                    &order_item,
+                    self.reservation_expires_at(conn)?,
+                    ticket_type.id,
+                    match_data.hold_id,
+                    quantity,
+                    conn,
+                )?;
+                remaining -= quantity;
+            }
         }
 
         // if the cart is empty at this point, it is effectively a new cart, remove expiration
@@ -916,6 +1498,168 @@ impl Order {
         Ok(())
     }
 
+    /// Adds tickets to the cart as new items without changing the items already in it. The
+    /// tickets are held until `reserved_until` rather than for the usual cart time.
+    pub(crate) fn add_tickets(
+        &mut self,
+        current_user_id: Uuid,
+        ticket_type: &TicketType,
+        quantity: u32,
+        reserved_until: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+
+        jlog!(Debug, "Adding tickets to order", {"order_id": self.id, "ticket_type_id": ticket_type.id, "quantity": quantity, "user_id": current_user_id});
+
+        let event = Event::find(ticket_type.event_id, conn)?;
+        self.set_currency(&event.currency, conn)?;
+        if self.expires_at.is_none() {
+            self.set_expiry(Some(current_user_id), None, conn)?;
+        }
+
+        let mut remaining = quantity;
+        while remaining > 0 {
+            let ticket_pricing = TicketPricing::get_current_ticket_pricing(
+                ticket_type.id,
+                self.box_office_pricing,
+                conn,
+            )?;
+            let item_quantity = match ticket_pricing.remaining_quantity(conn)? {
+                Some(tier_quantity) => cmp::min(remaining as i64, tier_quantity) as u32,
+                None => remaining,
+            };
+            let order_item = NewTicketsOrderItem {
+                order_id: self.id,
+                item_type: OrderItemTypes::Tickets,
+                quantity: item_quantity as i64,
+                ticket_type_id: ticket_type.id,
+                ticket_pricing_id: ticket_pricing.id,
+                event_id: Some(event.id),
+                unit_price_in_cents: ticket_pricing.price_in_cents,
+                hold_id: None,
+                code_id: None,
+            }
+            .commit(conn)?;
+            TicketInstance::reserve_tickets(
+                &order_item,
+                Some(reserved_until),
+                ticket_type.id,
+                None,
+                item_quantity,
+                conn,
+            )?;
+            remaining -= item_quantity;
+        }
+
+        if ticket_type.limit_per_person > 0 {
+            let quantities_ordered =
+                Order::quantity_for_user_for_event(&self.user_id, &event.id, conn)?;
+            if quantities_ordered
+                .get(&ticket_type.id)
+                .map(|q| q > &ticket_type.limit_per_person)
+                .unwrap_or(false)
+            {
+                return DatabaseError::validation_error(
+                    "quantity",
+                    "Exceeded limit per person per event",
+                );
+            }
+        }
+
+        self.update_fees(conn)
+    }
+
+    /// Sets the quantities of add-ons in the cart, add-ons are held for the buyer until the cart
+    /// expires
+    pub fn update_add_on_quantities(
+        &mut self,
+        current_user_id: Uuid,
+        items: &[UpdateAddOnItem],
+        remove_others: bool,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+
+        jlog!(Debug, "Update order add-on quantities", {"items": items, "remove_others": remove_others, "user_id": current_user_id});
+
+        let current_items: Vec<OrderItem> = self
+            .items(conn)?
+            .into_iter()
+            .filter(|i| i.item_type == OrderItemTypes::AddOn)
+            .collect();
+        for mut current_line in current_items {
+            match items
+                .iter()
+                .find(|i| Some(i.add_on_id) == current_line.add_on_id)
+            {
+                Some(item) if item.quantity == 0 => self.destroy_item(current_line.id, conn)?,
+                Some(item) => {
+                    let add_on = AddOn::find(item.add_on_id, conn)?;
+                    if (item.quantity as i64) > current_line.quantity
+                        && add_on.available_quantity(conn)?
+                            < item.quantity as i64 - current_line.quantity
+                    {
+                        return DatabaseError::validation_error(
+                            "quantity",
+                            "Not enough add-ons are available",
+                        );
+                    }
+                    current_line.quantity = item.quantity as i64;
+                    current_line.update(conn)?;
+                }
+                None if remove_others => self.destroy_item(current_line.id, conn)?,
+                None => {}
+            }
+        }
+
+        let current_add_on_ids: Vec<Uuid> = self
+            .items(conn)?
+            .iter()
+            .filter_map(|i| i.add_on_id)
+            .collect();
+        for item in items {
+            if item.quantity == 0 || current_add_on_ids.contains(&item.add_on_id) {
+                continue;
+            }
+
+            let add_on = AddOn::find(item.add_on_id, conn)?;
+            let event = add_on.event(conn)?;
+            if event.status == EventStatus::Draft {
+                return DatabaseError::business_process_error("Event has not been published");
+            }
+            if add_on.available_quantity(conn)? < item.quantity as i64 {
+                return DatabaseError::validation_error(
+                    "quantity",
+                    "Not enough add-ons are available",
+                );
+            }
+            if self.expires_at.is_none() {
+                self.set_expiry(Some(current_user_id), None, conn)?;
+            }
+            self.set_currency(&event.currency, conn)?;
+
+            NewAddOnOrderItem {
+                order_id: self.id,
+                item_type: OrderItemTypes::AddOn,
+                event_id: Some(add_on.event_id),
+                quantity: item.quantity as i64,
+                unit_price_in_cents: add_on.price_in_cents,
+                add_on_id: add_on.id,
+            }
+            .commit(conn)?;
+        }
+
+        // if the cart is empty at this point, it is effectively a new cart, remove expiration
+        if self.items(conn)?.len() == 0 && self.expires_at.is_some() {
+            self.remove_expiry(current_user_id, conn)?;
+        }
+
+        self.update_fees(conn)?;
+
+        Ok(())
+    }
+
     pub fn has_items(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
         select(exists(
             order_items::table.filter(order_items::order_id.eq(self.id)),
@@ -932,16 +1676,28 @@ impl Order {
 
         for o in items {
             match o.item_type {
-                OrderItemTypes::EventFees => self.destroy_item(o.id, conn)?,
+                OrderItemTypes::EventFees | OrderItemTypes::Tax => self.destroy_item(o.id, conn)?,
                 _ => {}
             }
         }
 
+        self.update_fee_items(conn)?;
+        // Fees are taxed along with the price so taxes are added once the fees are known
+        self.update_taxes(conn)
+    }
+
+    fn update_fee_items(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         // Box office purchased tickets do not have fees at this time
         if self.box_office_pricing {
             return Ok(());
         }
 
+        for item in self.items(conn)? {
+            if item.item_type == OrderItemTypes::Bundle {
+                item.update_fees(&self, conn)?;
+            }
+        }
+
         for ((event_id, hold_id), items) in self
             .items(conn)?
             .iter()
@@ -967,7 +1723,8 @@ impl Order {
                 match o.item_type {
                     OrderItemTypes::Tickets => {
                         o.update_fees(&self, conn)?;
-                        if o.unit_price_in_cents > 0 {
+                        // Bundles are charged fees through the bundle item
+                        if o.unit_price_in_cents > 0 && o.bundle_id.is_none() {
                             all_zero_price = false;
                         }
                     }
@@ -1001,6 +1758,63 @@ impl Order {
         Ok(())
     }
 
+    /// Adds a tax item for each ticket, add-on and fee item using the rate for the event's venue
+    /// region
+    fn update_taxes(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut tax_rates: HashMap<Uuid, Option<TaxRate>> = HashMap::new();
+        for item in self.items(conn)? {
+            let event_id = match item.event_id {
+                Some(event_id) => event_id,
+                None => continue,
+            };
+            if (item.item_type != OrderItemTypes::Tickets
+                && item.item_type != OrderItemTypes::AddOn
+                && item.item_type != OrderItemTypes::PerUnitFees
+                && item.item_type != OrderItemTypes::EventFees)
+                || item.unit_price_in_cents == 0
+            {
+                continue;
+            }
+
+            if !tax_rates.contains_key(&event_id) {
+                let event = Event::find(event_id, conn)?;
+                tax_rates.insert(event_id, TaxRate::find_for_event(&event, conn)?);
+            }
+            let tax_rate = match tax_rates[&event_id] {
+                Some(ref tax_rate) => tax_rate,
+                None => continue,
+            };
+
+            self.add_tax_item(&item, tax_rate, conn)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_tax_item(
+        &self,
+        item: &OrderItem,
+        tax_rate: &TaxRate,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let tax_in_cents = tax_rate.tax_in_cents(item.unit_price_in_cents);
+        if tax_in_cents == 0 {
+            return Ok(());
+        }
+        NewTaxOrderItem {
+            order_id: self.id,
+            item_type: OrderItemTypes::Tax,
+            event_id: item.event_id,
+            quantity: item.quantity,
+            unit_price_in_cents: tax_rate.charge_in_cents(item.unit_price_in_cents),
+            tax_rate_id: Some(tax_rate.id),
+            tax_in_cents,
+            parent_id: Some(item.id),
+        }
+        .commit(conn)?;
+        Ok(())
+    }
+
     pub fn quantity_for_user_for_event(
         user_id: &Uuid,
         event_id: &Uuid,
@@ -1120,7 +1934,15 @@ impl Order {
                             * (COALESCE(oi.company_fee_in_cents, 0) + COALESCE(oi.client_fee_in_cents, 0))
                         END
                     )
-                AS BIGINT) as fees_in_cents
+                AS BIGINT) as fees_in_cents,
+                CAST(
+                    SUM(
+                        CASE WHEN oi.item_type = 'Tax'
+                        THEN oi.tax_in_cents * (oi.quantity - oi.refunded_quantity)
+                        ELSE 0
+                        END
+                    )
+                AS BIGINT) as tax_in_cents
             FROM orders o
             JOIN users u on u.id = COALESCE(o.on_behalf_of_user_id, o.user_id)
             LEFT JOIN order_items oi ON o.id = oi.order_id
@@ -1128,7 +1950,7 @@ impl Order {
             LEFT JOIN events e ON e.id = oi.event_id
             LEFT JOIN venues v ON v.id = e.venue_id
             WHERE o.id = $1
-            AND (oi.item_type = 'Tickets' OR oi.item_type = 'EventFees')
+            AND (oi.item_type = 'Tickets' OR oi.item_type = 'EventFees' OR oi.item_type = 'Tax')
             GROUP BY o.id, o.user_id, u.first_name, u.last_name
             ;
         "#;
@@ -1153,6 +1975,8 @@ impl Order {
             unit_price_in_cents: i64,
             #[sql_type = "BigInt"]
             fees_in_cents: i64,
+            #[sql_type = "BigInt"]
+            tax_in_cents: i64,
         }
 
         let order_metadata: R = diesel::sql_query(query)
@@ -1188,6 +2012,10 @@ impl Order {
                 "fees_in_cents".to_string(),
                 order_metadata.fees_in_cents.to_string(),
             ),
+            (
+                "tax_in_cents".to_string(),
+                order_metadata.tax_in_cents.to_string(),
+            ),
         ])
     }
 
@@ -1294,6 +2122,11 @@ impl Order {
                         provider: PaymentProviders::Globee,
                         display_name: "Pay with crypto".to_string(),
                     }),
+                    PaymentProviders::Paypal => Some(AllowedPaymentMethod {
+                        method: "Provider".to_string(),
+                        provider: PaymentProviders::Paypal,
+                        display_name: "PayPal".to_string(),
+                    }),
                     _ => None,
                 })
                 .collect();
@@ -1308,6 +2141,8 @@ impl Order {
             items,
             limited_tickets_remaining,
             total_in_cents: self.calculate_total(conn)?,
+            tax_in_cents: self.calculate_tax(conn)?,
+            currency: self.currency.clone(),
             seconds_until_expiry,
             user_id: self.user_id,
             note: self.note.clone(),
@@ -1571,6 +2406,26 @@ impl Order {
                     conn,
                 )?;
             }
+            for item in order_items
+                .iter()
+                .filter(|oi| oi.item_type == OrderItemTypes::Resale)
+            {
+                ResaleListing::complete_sale(
+                    item,
+                    self.on_behalf_of_user_id.unwrap_or(self.user_id),
+                    conn,
+                )?;
+            }
+            for item in order_items
+                .iter()
+                .filter(|oi| oi.item_type == OrderItemTypes::AddOn)
+            {
+                AddOnVoucher::issue_for_order_item(
+                    item,
+                    self.on_behalf_of_user_id.unwrap_or(self.user_id),
+                    conn,
+                )?;
+            }
 
             let ticket_ids = TicketInstance::find_ids_for_order(self.id, conn)?;
             let domain_event = DomainEvent::create(
@@ -1598,6 +2453,8 @@ impl Order {
                 3,
             )
             .commit(conn)?;
+
+            WaitlistEntry::mark_purchased_for_order(self.id, conn)?;
         };
         jlog!(Debug, "Order was checked for completion but was short", {"required_amount": total_required, "total_paid": total_paid, "order_id": self.id});
         Ok(())
@@ -1651,16 +2508,63 @@ impl Order {
         self.lock_version(conn)?;
 
         let order_items = self.order_items_in_invalid_state(conn)?;
+        let mut removed_bundle_item_ids: Vec<Uuid> = vec![];
         for item in order_items {
-            // Use calculated quantity as reserved may have been taken in the meantime
-            let quantity = item.calculate_quantity(conn)?;
-            TicketInstance::release_tickets(&item, quantity as u32, user_id, conn)?;
+            // An invalid ticket in a bundle invalidates the whole bundle
+            if let (Some(_), Some(parent_id)) = (item.bundle_id, item.parent_id) {
+                if !removed_bundle_item_ids.contains(&parent_id) {
+                    let bundle_item = OrderItem::find(parent_id, conn)?;
+                    self.remove_bundle_item(&bundle_item, user_id, conn)?;
+                    removed_bundle_item_ids.push(parent_id);
+                }
+                continue;
+            }
+            if item.item_type == OrderItemTypes::Tickets {
+                // Use calculated quantity as reserved may have been taken in the meantime
+                let quantity = item.calculate_quantity(conn)?;
+                TicketInstance::release_tickets(&item, quantity as u32, user_id, conn)?;
+            }
             self.destroy_item(item.id, conn)?;
         }
 
         Ok(())
     }
 
+    /// Orders are paid in a single currency, so items can only be added when they are priced in
+    /// the currency of the items already in the order
+    pub(crate) fn set_currency(
+        &mut self,
+        currency: &str,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.currency.as_ref().map(|c| c.as_str()) == Some(currency) {
+            return Ok(());
+        }
+        if self.currency.is_some()
+            && self.items(conn)?.iter().any(|i| {
+                i.item_type == OrderItemTypes::Tickets
+                    || i.item_type == OrderItemTypes::Resale
+                    || i.item_type == OrderItemTypes::Bundle
+                    || i.item_type == OrderItemTypes::AddOn
+            })
+        {
+            return DatabaseError::validation_error(
+                "currency",
+                "All items in an order must be priced in the same currency",
+            );
+        }
+
+        diesel::update(&*self)
+            .set((
+                orders::currency.eq(currency),
+                orders::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update order currency")?;
+        self.currency = Some(currency.to_string());
+        Ok(())
+    }
+
     fn update_box_office_pricing(
         &mut self,
         box_office_pricing: bool,
@@ -1752,6 +2656,16 @@ impl Order {
         Ok(total)
     }
 
+    /// Tax charged on the order including tax already part of the ticket price
+    pub fn calculate_tax(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self
+            .items(conn)?
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tax)
+            .map(|i| i.tax_in_cents * (i.quantity - i.refunded_quantity))
+            .sum())
+    }
+
     /// Updates the lock version in the database and forces a Concurrency error if
     /// another process has updated it
     pub fn lock_version(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
@@ -1842,6 +2756,8 @@ pub struct DisplayOrder {
     pub items: Vec<DisplayOrderItem>,
     pub limited_tickets_remaining: Vec<TicketsRemaining>,
     pub total_in_cents: i64,
+    pub tax_in_cents: i64,
+    pub currency: Option<String>,
     pub user_id: Uuid,
     pub note: Option<String>,
     pub order_number: String,
@@ -1883,6 +2799,8 @@ pub struct UpdateOrderItem {
     pub ticket_type_id: Uuid,
     pub quantity: u32,
     pub redemption_code: Option<String>,
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
 }
 
 #[test]
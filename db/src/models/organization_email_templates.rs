@@ -0,0 +1,147 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::organization_email_templates;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::Validate;
+use validators;
+
+/// Organization specific branding applied to one kind of transactional email
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Organization)]
+#[table_name = "organization_email_templates"]
+pub struct OrganizationEmailTemplate {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub template_kind: EmailTemplateKinds,
+    /// Replaces the default SendGrid template when set
+    pub template_id: Option<String>,
+    pub logo_url: Option<String>,
+    pub primary_color: Option<String>,
+    pub reply_to_email: Option<String>,
+    pub footer_text: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone, Validate)]
+#[table_name = "organization_email_templates"]
+pub struct NewOrganizationEmailTemplate {
+    pub organization_id: Uuid,
+    pub template_kind: EmailTemplateKinds,
+    pub template_id: Option<String>,
+    #[validate(url(message = "Logo URL is invalid"))]
+    pub logo_url: Option<String>,
+    #[validate(custom = "validators::validate_hex_color")]
+    pub primary_color: Option<String>,
+    #[validate(email(message = "Reply to email is invalid"))]
+    pub reply_to_email: Option<String>,
+    pub footer_text: Option<String>,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Serialize, Validate)]
+#[table_name = "organization_email_templates"]
+pub struct OrganizationEmailTemplateEditableAttributes {
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub template_id: Option<Option<String>>,
+    #[validate(url(message = "Logo URL is invalid"))]
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub logo_url: Option<Option<String>>,
+    #[validate(custom = "validators::validate_hex_color")]
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub primary_color: Option<Option<String>>,
+    #[validate(email(message = "Reply to email is invalid"))]
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub reply_to_email: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub footer_text: Option<Option<String>>,
+}
+
+impl NewOrganizationEmailTemplate {
+    pub fn commit(&self, conn: &PgConnection) -> Result<OrganizationEmailTemplate, DatabaseError> {
+        self.validate()?;
+        diesel::insert_into(organization_email_templates::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create organization email template",
+            )
+    }
+}
+
+impl OrganizationEmailTemplate {
+    pub fn create(
+        organization_id: Uuid,
+        template_kind: EmailTemplateKinds,
+        attributes: OrganizationEmailTemplateEditableAttributes,
+    ) -> NewOrganizationEmailTemplate {
+        NewOrganizationEmailTemplate {
+            organization_id,
+            template_kind,
+            template_id: attributes.template_id.unwrap_or(None),
+            logo_url: attributes.logo_url.unwrap_or(None),
+            primary_color: attributes.primary_color.unwrap_or(None),
+            reply_to_email: attributes.reply_to_email.unwrap_or(None),
+            footer_text: attributes.footer_text.unwrap_or(None),
+        }
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationEmailTemplate>, DatabaseError> {
+        organization_email_templates::table
+            .filter(organization_email_templates::organization_id.eq(organization_id))
+            .order_by(organization_email_templates::template_kind)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load organization email templates",
+            )
+    }
+
+    pub fn find_by_organization_and_kind(
+        organization_id: Uuid,
+        template_kind: EmailTemplateKinds,
+        conn: &PgConnection,
+    ) -> Result<Option<OrganizationEmailTemplate>, DatabaseError> {
+        organization_email_templates::table
+            .filter(organization_email_templates::organization_id.eq(organization_id))
+            .filter(organization_email_templates::template_kind.eq(template_kind))
+            .first(conn)
+            .optional()
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load organization email template",
+            )
+    }
+
+    pub fn update(
+        &self,
+        attributes: OrganizationEmailTemplateEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<OrganizationEmailTemplate, DatabaseError> {
+        attributes.validate()?;
+        diesel::update(self)
+            .set((
+                attributes,
+                organization_email_templates::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update organization email template",
+            )
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self).execute(conn).to_db_error(
+            ErrorCode::DeleteError,
+            "Could not delete organization email template",
+        )
+    }
+}
@@ -11,6 +11,7 @@ use schema::{
     assets, events, fee_schedules, order_items, organization_users, organizations, ticket_types,
     users, venues,
 };
+use serde_with::rust::double_option;
 use std::collections::HashMap;
 use utils::encryption::*;
 use utils::errors::*;
@@ -51,6 +52,12 @@ pub struct Organization {
     pub allowed_payment_providers: Vec<PaymentProviders>,
     pub timezone: Option<String>,
     pub cc_fee_percent: f32,
+    pub max_resale_markup_percent: Option<f32>,
+    pub resale_fee_percent: f32,
+    pub currency: String,
+    pub refund_fees_on_cancellation: bool,
+    /// Members holding financial scopes must enable two factor authentication to use them
+    pub two_factor_required: bool,
 }
 
 #[derive(Serialize)]
@@ -82,6 +89,8 @@ pub struct NewOrganization {
     pub allowed_payment_providers: Option<Vec<PaymentProviders>>,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub timezone: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Default, Serialize, Clone)]
@@ -173,6 +182,13 @@ pub struct OrganizationEditableAttributes {
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub timezone: Option<String>,
     pub cc_fee_percent: Option<f32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub max_resale_markup_percent: Option<Option<f32>>,
+    pub resale_fee_percent: Option<f32>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    pub refund_fees_on_cancellation: Option<bool>,
+    pub two_factor_required: Option<bool>,
 }
 
 impl Organization {
@@ -644,7 +660,11 @@ impl Organization {
                 ),
                 sql::<BigInt>("count(*) over()"),
             ))
-            .order_by(sql::<()>(&format!("{} {}", sort_column, sort_direction)));
+            // Ordered by user id as well so pages are stable when exporting every fan
+            .order_by(sql::<()>(&format!(
+                "{} {}, 7",
+                sort_column, sort_direction
+            )));
 
         let query = query.limit(limit as i64).offset((limit * page) as i64);
 
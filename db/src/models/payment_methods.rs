@@ -19,6 +19,22 @@ pub struct PaymentMethod {
     pub provider_data: serde_json::Value,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// The card on the provider's customer, when not set the customer's default card is charged
+    pub source_reference: Option<String>,
+    pub card_brand: Option<String>,
+    pub card_last_four: Option<String>,
+    pub card_exp_month: Option<i32>,
+    pub card_exp_year: Option<i32>,
+}
+
+/// Details of a card saved with a payment provider
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CardDetails {
+    pub source_reference: String,
+    pub brand: Option<String>,
+    pub last_four: Option<String>,
+    pub exp_month: Option<i32>,
+    pub exp_year: Option<i32>,
 }
 
 #[derive(AsChangeset, Default, Deserialize)]
@@ -43,9 +59,21 @@ impl PaymentMethod {
             is_default,
             provider,
             provider_data: data,
+            source_reference: None,
+            card_brand: None,
+            card_last_four: None,
+            card_exp_month: None,
+            card_exp_year: None,
         }
     }
 
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
+        payment_methods::table
+            .filter(payment_methods::id.eq(id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load payment method")
+    }
+
     pub fn find_default_for_user(
         user_id: Uuid,
         conn: &PgConnection,
@@ -74,7 +102,7 @@ impl PaymentMethod {
         }
 
         query
-            .order_by(payment_methods::name)
+            .order_by((payment_methods::name, payment_methods::created_at))
             .load(conn)
             .to_db_error(
                 ErrorCode::QueryError,
@@ -107,6 +135,89 @@ impl PaymentMethod {
             query.get_result(conn),
         )
     }
+
+    /// Makes this the payment method used when checking out without selecting one
+    pub fn set_default(
+        &self,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<PaymentMethod, DatabaseError> {
+        PaymentMethod::clear_default_for_user(self.user_id, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentMethodUpdated,
+            "Payment method was set as default".to_string(),
+            Tables::PaymentMethods,
+            Some(self.id),
+            Some(current_user_id),
+            Some(json!({"is_default": true})),
+        )
+        .commit(conn)?;
+
+        diesel::update(self)
+            .set((
+                payment_methods::is_default.eq(true),
+                payment_methods::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not set default payment method",
+            )
+    }
+
+    /// Removes the payment method, when it was the default the user's most recently saved
+    /// payment method becomes the default
+    pub fn destroy(&self, current_user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        DomainEvent::create(
+            DomainEventTypes::PaymentMethodDeleted,
+            "Payment method was deleted".to_string(),
+            Tables::PaymentMethods,
+            Some(self.id),
+            Some(current_user_id),
+            Some(self.provider_data.clone()),
+        )
+        .commit(conn)?;
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove payment method")?;
+
+        if self.is_default {
+            let replacement = payment_methods::table
+                .filter(payment_methods::user_id.eq(self.user_id))
+                .order_by(payment_methods::created_at.desc())
+                .first::<PaymentMethod>(conn)
+                .optional()
+                .to_db_error(
+                    ErrorCode::QueryError,
+                    "Could not load payment methods for user",
+                )?;
+            if let Some(replacement) = replacement {
+                replacement.set_default(current_user_id, conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear_default_for_user(user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(
+            payment_methods::table
+                .filter(payment_methods::user_id.eq(user_id))
+                .filter(payment_methods::is_default.eq(true)),
+        )
+        .set((
+            payment_methods::is_default.eq(false),
+            payment_methods::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not clear default payment method",
+        )?;
+        Ok(())
+    }
 }
 
 impl ForDisplay<DisplayPaymentMethod> for PaymentMethod {
@@ -123,14 +234,32 @@ pub struct NewPaymentMethod {
     is_default: bool,
     provider: String,
     provider_data: serde_json::Value,
+    source_reference: Option<String>,
+    card_brand: Option<String>,
+    card_last_four: Option<String>,
+    card_exp_month: Option<i32>,
+    card_exp_year: Option<i32>,
 }
 
 impl NewPaymentMethod {
+    pub fn with_card_details(mut self, card: CardDetails) -> NewPaymentMethod {
+        self.source_reference = Some(card.source_reference);
+        self.card_brand = card.brand;
+        self.card_last_four = card.last_four;
+        self.card_exp_month = card.exp_month;
+        self.card_exp_year = card.exp_year;
+        self
+    }
+
     pub fn commit(
         self,
         current_user_id: Uuid,
         conn: &PgConnection,
     ) -> Result<PaymentMethod, DatabaseError> {
+        if self.is_default {
+            PaymentMethod::clear_default_for_user(self.user_id, conn)?;
+        }
+
         let payment_method = diesel::insert_into(payment_methods::table)
             .values(self)
             .get_result::<PaymentMethod>(conn)
@@ -152,15 +281,25 @@ impl NewPaymentMethod {
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct DisplayPaymentMethod {
+    pub id: Uuid,
     pub name: PaymentProviders,
     pub is_default: bool,
+    pub card_brand: Option<String>,
+    pub card_last_four: Option<String>,
+    pub card_exp_month: Option<i32>,
+    pub card_exp_year: Option<i32>,
 }
 
 impl From<PaymentMethod> for DisplayPaymentMethod {
     fn from(payment_method: PaymentMethod) -> Self {
         DisplayPaymentMethod {
+            id: payment_method.id,
             name: payment_method.name,
             is_default: payment_method.is_default,
+            card_brand: payment_method.card_brand,
+            card_last_four: payment_method.card_last_four,
+            card_exp_month: payment_method.card_exp_month,
+            card_exp_year: payment_method.card_exp_year,
         }
     }
 }
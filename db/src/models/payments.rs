@@ -93,12 +93,66 @@ impl Payment {
             .expect_single()
     }
 
+    /// Finds the original payment made through a provider, ignoring refunds logged against it
+    pub fn find_by_external_reference(
+        provider: PaymentProviders,
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<Payment>, DatabaseError> {
+        payments::table
+            .filter(payments::provider.eq(provider))
+            .filter(payments::external_reference.eq(external_reference))
+            .filter(payments::amount.ge(0))
+            .order_by(payments::created_at)
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not find payment")
+    }
+
+    /// Total of the refunds logged against this payment
+    pub fn refunded_amount(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let refunds: Vec<i64> = payments::table
+            .filter(payments::order_id.eq(self.order_id))
+            .filter(payments::external_reference.eq(&self.external_reference))
+            .filter(payments::status.eq(PaymentStatus::Refunded))
+            .filter(payments::amount.lt(0))
+            .select(payments::amount)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load payment refunds")?;
+        Ok(-refunds.iter().sum::<i64>())
+    }
+
     pub fn log_refund(
         &self,
         current_user_id: Uuid,
         refund_amount: u32,
         refund_data: Option<serde_json::Value>,
         conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.create_refund(
+            Some(current_user_id),
+            refund_amount as i64,
+            refund_data,
+            conn,
+        )
+    }
+
+    /// Logs a refund the payment provider made outside of Big Neon, e.g. from its dashboard
+    pub fn log_provider_refund(
+        &self,
+        refund_amount: i64,
+        refund_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.create_refund(None, refund_amount, Some(refund_data), conn)
+    }
+
+    fn create_refund(
+        &self,
+        current_user_id: Option<Uuid>,
+        refund_amount: i64,
+        refund_data: Option<serde_json::Value>,
+        conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         Payment::create(
             self.order_id,
@@ -107,18 +161,18 @@ impl Payment {
             self.payment_method,
             self.provider.clone(),
             self.external_reference.clone(),
-            -(refund_amount as i64),
+            -refund_amount,
             refund_data.clone(),
             None,
         )
-        .commit(Some(current_user_id), conn)?;
+        .commit(current_user_id, conn)?;
 
         DomainEvent::create(
             DomainEventTypes::PaymentRefund,
             "Payment was refunded".to_string(),
             Tables::Payments,
             Some(self.id),
-            Some(current_user_id),
+            current_user_id,
             refund_data,
         )
         .commit(conn)?;
@@ -256,7 +310,7 @@ impl Payment {
     ) -> Result<(), DatabaseError> {
         use models::enums::PaymentStatus::*;
         match self.status {
-            Completed | Authorized | Refunded | PendingConfirmation => {
+            Completed | Authorized | Refunded | PendingConfirmation | Disputed => {
                 DatabaseError::business_process_error("Could not mark payment as cancelled because it is in a status that doesn't allow cancelling")
             }
             Requested | Unpaid | Draft | Unknown | PendingIpn => {
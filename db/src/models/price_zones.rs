@@ -0,0 +1,73 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::price_zones;
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Stage)]
+#[table_name = "price_zones"]
+pub struct PriceZone {
+    pub id: Uuid,
+    pub stage_id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "price_zones"]
+pub struct NewPriceZone {
+    pub stage_id: Uuid,
+    pub name: String,
+}
+
+impl NewPriceZone {
+    pub fn commit(&self, conn: &PgConnection) -> Result<PriceZone, DatabaseError> {
+        diesel::insert_into(price_zones::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create price zone")
+    }
+}
+
+impl PriceZone {
+    pub fn create(stage_id: Uuid, name: String) -> NewPriceZone {
+        NewPriceZone { stage_id, name }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PriceZone, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::QueryError,
+            "Error loading price zone",
+            price_zones::table.find(id).first::<PriceZone>(conn),
+        )
+    }
+
+    pub fn find_by_stage_id(
+        stage_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<PriceZone>, DatabaseError> {
+        price_zones::table
+            .filter(price_zones::stage_id.eq(stage_id))
+            .order_by(price_zones::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load price zones")
+    }
+
+    pub fn stage(&self, conn: &PgConnection) -> Result<Stage, DatabaseError> {
+        Stage::find(self.stage_id, conn)
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Failed to delete price zone",
+            diesel::delete(self).execute(conn),
+        )
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::prelude::*;
 use diesel;
+use diesel::dsl;
 use diesel::prelude::*;
 use schema::push_notification_tokens;
 use utils::errors::ConvertToDatabaseError;
@@ -64,6 +65,16 @@ impl PushNotificationToken {
             )
     }
 
+    pub fn mark_notified(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(push_notification_tokens::table.find(self.id))
+            .set(push_notification_tokens::last_notification_at.eq(dsl::now.nullable()))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update push_notification_token",
+            )
+    }
+
     pub fn remove(
         user_id: Uuid,
         push_notification_tokens_id: Uuid,
@@ -0,0 +1,257 @@
+use diesel::prelude::*;
+use models::*;
+use schema::payments;
+use std::collections::HashMap;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// The amount to refund against a single payment on the order
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct PaymentRefundPlan {
+    pub payment_id: Uuid,
+    pub payment_method: PaymentMethods,
+    pub provider: PaymentProviders,
+    pub external_reference: Option<String>,
+    pub amount_in_cents: i64,
+}
+
+/// Order items to refund for a set of tickets and how the refund is split between the payments
+/// made on the order
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RefundPlan {
+    pub order_id: Uuid,
+    pub items: Vec<RefundItem>,
+    pub amount_in_cents: i64,
+    pub payments: Vec<PaymentRefundPlan>,
+}
+
+impl RefundPlan {
+    /// Plans the refund of tickets including their fees and tax, and of the per ticket fees only
+    /// for `fee_ticket_instance_ids`. Event fees are included once no tickets remain for the event.
+    pub fn for_tickets(
+        order: &Order,
+        ticket_instance_ids: &[Uuid],
+        fee_ticket_instance_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<RefundPlan, DatabaseError> {
+        if order.status != OrderStatus::Paid {
+            return DatabaseError::business_process_error("Order must be paid to refund tickets");
+        }
+
+        let mut ticket_instance_ids = ticket_instance_ids.to_vec();
+        ticket_instance_ids.sort();
+        ticket_instance_ids.dedup();
+        let mut fee_ticket_instance_ids: Vec<Uuid> = fee_ticket_instance_ids
+            .iter()
+            .filter(|id| !ticket_instance_ids.contains(id))
+            .cloned()
+            .collect();
+        fee_ticket_instance_ids.sort();
+        fee_ticket_instance_ids.dedup();
+        if ticket_instance_ids.is_empty() && fee_ticket_instance_ids.is_empty() {
+            return DatabaseError::validation_error(
+                "ticket_instance_ids",
+                "At least one ticket is required",
+            );
+        }
+
+        let mut all_ids = ticket_instance_ids.clone();
+        all_ids.extend(fee_ticket_instance_ids.iter());
+        let refunded_tickets: HashMap<Uuid, RefundedTicket> =
+            RefundedTicket::find_by_ticket_instance_ids(all_ids, conn)?
+                .into_iter()
+                .map(|r| (r.ticket_instance_id, r))
+                .collect();
+
+        let mut items = vec![];
+        let mut amount_in_cents = 0;
+        let mut tickets_refunded_per_event: HashMap<Uuid, i64> = HashMap::new();
+        for (id, fees_only) in ticket_instance_ids
+            .iter()
+            .map(|id| (*id, false))
+            .chain(fee_ticket_instance_ids.iter().map(|id| (*id, true)))
+        {
+            let order_item = RefundPlan::order_item_for_ticket(order, id, conn)?;
+            let refunded_ticket = refunded_tickets.get(&id);
+            let fee_refunded = refunded_ticket
+                .map(|r| r.fee_refunded_at.is_some())
+                .unwrap_or(false);
+            if refunded_ticket
+                .map(|r| r.ticket_refunded_at.is_some())
+                .unwrap_or(false)
+                || (fees_only && fee_refunded)
+            {
+                return DatabaseError::business_process_error("Already refunded");
+            }
+
+            let fee_item = order_item.find_fee_item(conn)?;
+            if fees_only {
+                match fee_item {
+                    Some(fee_item) => {
+                        amount_in_cents += fee_item.unit_price_with_tax_in_cents(conn)?;
+                        items.push(RefundItem {
+                            order_item_id: fee_item.id,
+                            ticket_instance_id: Some(id),
+                        });
+                    }
+                    None => {
+                        return DatabaseError::business_process_error(
+                            "Ticket does not have fees to refund",
+                        );
+                    }
+                }
+                continue;
+            }
+
+            amount_in_cents += order_item.unit_price_with_tax_in_cents(conn)?;
+            if let Some(fee_item) = fee_item {
+                if !fee_refunded {
+                    amount_in_cents += fee_item.unit_price_with_tax_in_cents(conn)?;
+                }
+            }
+            if let Some(event_id) = order_item.event_id {
+                *tickets_refunded_per_event.entry(event_id).or_insert(0) += 1;
+            }
+            items.push(RefundItem {
+                order_item_id: order_item.id,
+                ticket_instance_id: Some(id),
+            });
+        }
+
+        // Event fees are refunded with the last remaining ticket for the event
+        let order_items = order.items(conn)?;
+        for event_fee_item in order_items.iter().filter(|i| {
+            i.item_type == OrderItemTypes::EventFees && i.refunded_quantity < i.quantity
+        }) {
+            let remaining: i64 = order_items
+                .iter()
+                .filter(|i| {
+                    i.event_id == event_fee_item.event_id
+                        && (i.item_type == OrderItemTypes::Tickets
+                            || i.item_type == OrderItemTypes::Resale)
+                })
+                .map(|i| i.quantity - i.refunded_quantity)
+                .sum();
+            let refunding = event_fee_item
+                .event_id
+                .and_then(|event_id| tickets_refunded_per_event.get(&event_id))
+                .cloned()
+                .unwrap_or(0);
+            if refunding > 0 && remaining == refunding {
+                amount_in_cents += event_fee_item.unit_price_with_tax_in_cents(conn)?;
+            }
+        }
+
+        let payments = RefundPlan::allocate(order, amount_in_cents, conn)?;
+        Ok(RefundPlan {
+            order_id: order.id,
+            items,
+            amount_in_cents,
+            payments,
+        })
+    }
+
+    /// Splits an amount between the completed payments on the order, refunding each payment up
+    /// to the balance left after earlier refunds
+    pub fn allocate(
+        order: &Order,
+        amount_in_cents: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<PaymentRefundPlan>, DatabaseError> {
+        let payments: Vec<Payment> = payments::table
+            .filter(payments::order_id.eq(order.id))
+            .order_by(payments::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading payments")?;
+
+        // Negative payments / refunds cancel out remaining payment balance
+        let mut remaining_balances: HashMap<Option<String>, i64> = HashMap::new();
+        for payment in payments.iter() {
+            // Ignore payments that were only authorized
+            if payment.status == PaymentStatus::Authorized {
+                continue;
+            }
+            *remaining_balances
+                .entry(payment.external_reference.clone())
+                .or_insert(0) += payment.amount;
+        }
+
+        let mut result = vec![];
+        let mut allocated = 0;
+        for payment in payments.iter() {
+            if allocated >= amount_in_cents {
+                break;
+            }
+            if payment.status != PaymentStatus::Completed {
+                continue;
+            }
+
+            let remaining_balance = remaining_balances
+                .get(&payment.external_reference)
+                .cloned()
+                .unwrap_or(0);
+            if remaining_balance <= 0 {
+                continue;
+            }
+
+            let amount = (amount_in_cents - allocated).min(remaining_balance);
+            remaining_balances.insert(
+                payment.external_reference.clone(),
+                remaining_balance - amount,
+            );
+            allocated += amount;
+            result.push(PaymentRefundPlan {
+                payment_id: payment.id,
+                payment_method: payment.payment_method,
+                provider: payment.provider.clone(),
+                external_reference: payment.external_reference.clone(),
+                amount_in_cents: amount,
+            });
+        }
+
+        if allocated < amount_in_cents {
+            return DatabaseError::business_process_error(&format!(
+                "Unable to refund amount owed, {} available, {} due",
+                allocated, amount_in_cents
+            ));
+        }
+        Ok(result)
+    }
+
+    fn order_item_for_ticket(
+        order: &Order,
+        ticket_instance_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<OrderItem, DatabaseError> {
+        let ticket_instance = TicketInstance::find(ticket_instance_id, conn)?;
+        let order_item = match ticket_instance.order_item_id {
+            Some(order_item_id) => OrderItem::find(order_item_id, conn)?,
+            None => {
+                return DatabaseError::business_process_error(
+                    "Ticket is not associated with an order",
+                );
+            }
+        };
+        if order_item.order_id != order.id {
+            return DatabaseError::business_process_error("Ticket does not belong to this order");
+        }
+        // Checked here as well as by `Order::refund` so the plan is known to be valid before
+        // anything is refunded
+        if order_item.bundle_id.is_some() {
+            return DatabaseError::business_process_error(
+                "Bundle tickets can only be refunded with their bundle",
+            );
+        }
+        if ticket_instance.was_transferred(conn)? {
+            return DatabaseError::business_process_error(
+                "Ticket was transferred so ineligible for refund",
+            );
+        }
+        if ResaleListing::find_active_for_ticket_instance(ticket_instance.id, conn)?.is_some() {
+            return DatabaseError::business_process_error(
+                "Tickets listed for resale cannot be refunded",
+            );
+        }
+        Ok(order_item)
+    }
+}
@@ -65,14 +65,28 @@ impl RefundedTicket {
         &mut self,
         just_fee: bool,
         conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.update_refunded_at(!just_fee, true, conn)
+    }
+
+    /// Marks the ticket as refunded while its fee is kept
+    pub fn mark_ticket_refunded(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        self.update_refunded_at(true, false, conn)
+    }
+
+    fn update_refunded_at(
+        &mut self,
+        ticket: bool,
+        fee: bool,
+        conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         self.updated_at = Utc::now().naive_utc();
 
-        if !just_fee && self.ticket_refunded_at.is_none() {
+        if ticket && self.ticket_refunded_at.is_none() {
             self.ticket_refunded_at = Some(self.updated_at);
         }
 
-        if self.fee_refunded_at.is_none() {
+        if fee && self.fee_refunded_at.is_none() {
             self.fee_refunded_at = Some(self.updated_at);
         }
 
@@ -0,0 +1,311 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono_tz::Tz;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{domain_actions, report_subscriptions};
+use utils::errors::*;
+use utils::spreadsheets;
+use uuid::Uuid;
+use validator::Validate;
+
+const REPORT_DELIVERY_MAX_ATTEMPTS: i64 = 3;
+
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(Organization)]
+#[table_name = "report_subscriptions"]
+pub struct ReportSubscription {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub report_type: ReportTypes,
+    pub frequency: ReportFrequencies,
+    pub email: String,
+    pub created_by: Uuid,
+    pub next_send_at: NaiveDateTime,
+    pub last_sent_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub last_failed_at: Option<NaiveDateTime>,
+    pub last_failure_reason: Option<String>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone, Validate)]
+#[table_name = "report_subscriptions"]
+pub struct NewReportSubscription {
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub report_type: ReportTypes,
+    pub frequency: ReportFrequencies,
+    #[validate(email(message = "Email is invalid"))]
+    pub email: String,
+    pub created_by: Uuid,
+}
+
+impl NewReportSubscription {
+    pub fn commit(&self, conn: &PgConnection) -> Result<ReportSubscription, DatabaseError> {
+        self.validate()?;
+        match self.event_id {
+            Some(event_id) => {
+                if Event::find(event_id, conn)?.organization_id != self.organization_id {
+                    return DatabaseError::validation_error(
+                        "event_id",
+                        "Event does not belong to this organization",
+                    );
+                }
+            }
+            None => {
+                if self.report_type == ReportTypes::EventSummary {
+                    return DatabaseError::validation_error(
+                        "event_id",
+                        "Event is required for event summary reports",
+                    );
+                }
+            }
+        }
+
+        let organization = Organization::find(self.organization_id, conn)?;
+        let next_send_at = ReportSubscription::next_send_at_after(
+            self.frequency,
+            &organization.timezone,
+            Utc::now().naive_utc(),
+        );
+        let report_subscription: ReportSubscription =
+            diesel::insert_into(report_subscriptions::table)
+                .values((self, report_subscriptions::next_send_at.eq(next_send_at)))
+                .get_result(conn)
+                .to_db_error(
+                    ErrorCode::InsertError,
+                    "Could not create report subscription",
+                )?;
+        report_subscription.queue_delivery(conn)?;
+
+        Ok(report_subscription)
+    }
+}
+
+impl ReportSubscription {
+    pub fn create(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        report_type: ReportTypes,
+        frequency: ReportFrequencies,
+        email: String,
+        created_by: Uuid,
+    ) -> NewReportSubscription {
+        NewReportSubscription {
+            organization_id,
+            event_id,
+            report_type,
+            frequency,
+            email,
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ReportSubscription, DatabaseError> {
+        report_subscriptions::table
+            .filter(report_subscriptions::id.eq(id))
+            .filter(report_subscriptions::deleted_at.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load report subscription")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<ReportSubscription>, DatabaseError> {
+        report_subscriptions::table
+            .filter(report_subscriptions::organization_id.eq(organization_id))
+            .filter(report_subscriptions::deleted_at.is_null())
+            .order_by(report_subscriptions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load report subscriptions")
+    }
+
+    /// Removes the subscription and cancels the delivery waiting to be sent
+    pub fn destroy(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((
+                report_subscriptions::deleted_at.eq(dsl::now.nullable()),
+                report_subscriptions::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not delete report subscription",
+            )?;
+
+        diesel::update(
+            domain_actions::table
+                .filter(
+                    domain_actions::domain_action_type.eq(DomainActionTypes::SendScheduledReport),
+                )
+                .filter(domain_actions::main_table.eq(Tables::ReportSubscriptions.to_string()))
+                .filter(domain_actions::main_table_id.eq(self.id))
+                .filter(domain_actions::status.eq(DomainActionStatus::Pending)),
+        )
+        .set((
+            domain_actions::status.eq(DomainActionStatus::Cancelled),
+            domain_actions::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not cancel report deliveries")?;
+        Ok(())
+    }
+
+    /// Reports are sent at midnight in the organization's timezone, daily or on Mondays
+    pub fn next_send_at_after(
+        frequency: ReportFrequencies,
+        timezone: &Option<String>,
+        after: NaiveDateTime,
+    ) -> NaiveDateTime {
+        let tz: Tz = timezone
+            .as_ref()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(chrono_tz::UTC);
+        let mut date = tz.from_utc_datetime(&after).date().naive_local().succ();
+        if frequency == ReportFrequencies::Weekly {
+            while date.weekday() != Weekday::Mon {
+                date = date.succ();
+            }
+        }
+
+        let local_midnight = date.and_hms(0, 0, 0);
+        match tz.from_local_datetime(&local_midnight).earliest() {
+            Some(send_at) => send_at.naive_utc(),
+            // Midnight does not exist on days where daylight saving starts at midnight
+            None => tz
+                .from_local_datetime(&(local_midnight + Duration::hours(1)))
+                .earliest()
+                .map(|send_at| send_at.naive_utc())
+                .unwrap_or(local_midnight),
+        }
+    }
+
+    /// The period covered by the report sent at `next_send_at`
+    pub fn report_period(&self) -> (NaiveDateTime, NaiveDateTime) {
+        let start = match self.frequency {
+            ReportFrequencies::Daily => self.next_send_at - Duration::days(1),
+            ReportFrequencies::Weekly => self.next_send_at - Duration::days(7),
+        };
+        (start, self.next_send_at)
+    }
+
+    pub fn file_name(&self) -> String {
+        let (_, end) = self.report_period();
+        let name = match self.report_type {
+            ReportTypes::EventSummary => "event_summary",
+            ReportTypes::TicketCount => "ticket_count",
+            ReportTypes::WeeklySettlement => "weekly_settlement",
+        };
+        format!("{}_{}.csv", name, end.format("%Y-%m-%d"))
+    }
+
+    /// Renders the report for the current period as CSV
+    pub fn render_csv(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
+        let (start, end) = self.report_period();
+        let worksheets = match self.report_type {
+            ReportTypes::EventSummary => {
+                let event_id = match self.event_id {
+                    Some(event_id) => event_id,
+                    None => {
+                        return DatabaseError::business_process_error(
+                            "Event is required for event summary reports",
+                        );
+                    }
+                };
+                Report::summary_event_report(event_id, Some(start), Some(end), conn)?
+                    .to_worksheets(conn)?
+            }
+            ReportTypes::TicketCount => {
+                Report::ticket_count_report(self.event_id, Some(self.organization_id), conn)?
+                    .to_worksheets(conn)?
+            }
+            ReportTypes::WeeklySettlement => Report::organization_summary_report(
+                self.organization_id,
+                Some(start),
+                Some(end),
+                conn,
+            )?
+            .to_worksheets(conn)?,
+        };
+        spreadsheets::to_csv(&worksheets)
+    }
+
+    /// Records that the report for the current period was sent and queues the next delivery
+    pub fn record_delivery(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<ReportSubscription, DatabaseError> {
+        let report_subscription: ReportSubscription = diesel::update(self)
+            .set((
+                report_subscriptions::last_sent_at.eq(self.next_send_at),
+                report_subscriptions::next_send_at.eq(self.following_send_at(conn)?),
+                report_subscriptions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update report subscription",
+            )?;
+        report_subscription.queue_delivery(conn)?;
+
+        Ok(report_subscription)
+    }
+
+    /// Records that the report for the current period could not be sent so the organization can
+    /// see it, and queues the next delivery so the subscription keeps running
+    pub fn record_failed_delivery(
+        &self,
+        reason: &str,
+        conn: &PgConnection,
+    ) -> Result<ReportSubscription, DatabaseError> {
+        let report_subscription: ReportSubscription = diesel::update(self)
+            .set((
+                report_subscriptions::last_failed_at.eq(dsl::now.nullable()),
+                report_subscriptions::last_failure_reason.eq(reason),
+                report_subscriptions::next_send_at.eq(self.following_send_at(conn)?),
+                report_subscriptions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update report subscription",
+            )?;
+        report_subscription.queue_delivery(conn)?;
+
+        Ok(report_subscription)
+    }
+
+    fn following_send_at(&self, conn: &PgConnection) -> Result<NaiveDateTime, DatabaseError> {
+        let organization = Organization::find(self.organization_id, conn)?;
+        // Skip periods that were missed rather than sending a backlog of reports
+        let after = self.next_send_at.max(Utc::now().naive_utc());
+        Ok(ReportSubscription::next_send_at_after(
+            self.frequency,
+            &organization.timezone,
+            after,
+        ))
+    }
+
+    fn queue_delivery(&self, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
+        DomainAction::create(
+            None,
+            DomainActionTypes::SendScheduledReport,
+            Some(CommunicationChannelType::Email),
+            json!({ "report_subscription_id": self.id }),
+            Some(Tables::ReportSubscriptions.to_string()),
+            Some(self.id),
+            self.next_send_at,
+            self.next_send_at + Duration::days(1),
+            REPORT_DELIVERY_MAX_ATTEMPTS,
+        )
+        .commit(conn)
+    }
+}
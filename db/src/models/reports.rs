@@ -4,6 +4,7 @@ use diesel::prelude::*;
 use diesel::sql_types::{BigInt, Nullable, Text, Timestamp, Uuid as dUuid};
 use itertools::Itertools;
 use models::*;
+use schema::events;
 use std::collections::HashMap;
 use utils::errors::*;
 use uuid::Uuid;
@@ -166,6 +167,10 @@ pub struct TransactionReportRow {
     pub event_fee_gross_in_cents: i64,
     #[sql_type = "BigInt"]
     pub event_fee_gross_in_cents_total: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents_total: i64,
     #[sql_type = "Nullable<dUuid>"]
     pub fee_range_id: Option<Uuid>,
     #[sql_type = "Text"]
@@ -192,20 +197,33 @@ pub struct TransactionReportRow {
     pub email: String,
     #[sql_type = "Nullable<Timestamp>"]
     pub event_start: Option<NaiveDateTime>,
+    #[sql_type = "Text"]
+    pub item_type: OrderItemTypes,
+    #[sql_type = "BigInt"]
+    pub resale_fee_in_cents: i64,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct EventSummarySalesResult {
     pub event_id: Uuid,
+    pub currency: String,
     pub sales: Vec<EventSummarySalesRow>,
     pub ticket_fees: Vec<EventSummaryFeesRow>,
     pub other_fees: Vec<EventSummaryOtherFees>,
 }
 
+/// Event summaries for an organization, totals for different currencies cannot be combined
+#[derive(Serialize, Deserialize)]
+pub struct CurrencySummarySalesResult {
+    pub currency: String,
+    pub events: Vec<EventSummarySalesResult>,
+}
+
 impl Default for EventSummarySalesResult {
     fn default() -> Self {
         EventSummarySalesResult {
             event_id: Uuid::nil(),
+            currency: String::new(),
             sales: vec![],
             ticket_fees: vec![],
             other_fees: vec![],
@@ -458,6 +476,7 @@ impl Report {
                     ..Default::default()
                 };
                 event_summary.event_id = event_id;
+                event_summary.currency = Event::find(event_id, conn)?.currency;
                 event_summary
             }
             false => results.pop().unwrap(),
@@ -471,8 +490,27 @@ impl Report {
         start: Option<NaiveDateTime>,
         end: Option<NaiveDateTime>,
         conn: &PgConnection,
-    ) -> Result<Vec<EventSummarySalesResult>, DatabaseError> {
-        Report::summary_event_report_core(None, Some(organization_id), start, end, conn)
+    ) -> Result<Vec<CurrencySummarySalesResult>, DatabaseError> {
+        let mut event_results =
+            Report::summary_event_report_core(None, Some(organization_id), start, end, conn)?;
+        event_results.sort_by(|a, b| a.currency.cmp(&b.currency));
+
+        let mut result: Vec<CurrencySummarySalesResult> = vec![];
+        for event_result in event_results {
+            let same_currency = result
+                .last()
+                .map(|group| group.currency == event_result.currency)
+                .unwrap_or(false);
+            if same_currency {
+                result.last_mut().unwrap().events.push(event_result);
+            } else {
+                result.push(CurrencySummarySalesResult {
+                    currency: event_result.currency.clone(),
+                    events: vec![event_result],
+                });
+            }
+        }
+        Ok(result)
     }
 
     fn summary_event_report_core(
@@ -501,6 +539,15 @@ impl Report {
             return Ok(empty_result);
         }
 
+        let event_ids: Vec<Uuid> = sales_rows.iter().map(|row| row.event_id).unique().collect();
+        let currencies: HashMap<Uuid, String> = events::table
+            .filter(events::id.eq_any(event_ids))
+            .select((events::id, events::currency))
+            .load::<(Uuid, String)>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event currencies")?
+            .into_iter()
+            .collect();
+
         let sales_rows = sales_rows.into_iter().group_by(|row| row.event_id);
 
         //Now get the transaction fees results
@@ -550,6 +597,7 @@ impl Report {
         for (event_id, sales) in sales_rows.into_iter() {
             result.push(EventSummarySalesResult {
                 event_id,
+                currency: currencies.get(&event_id).cloned().unwrap_or_default(),
                 sales: sales.into_iter().collect_vec(),
                 ticket_fees: fees_hash.get(&event_id).unwrap_or(&vec![]).to_vec(),
                 other_fees: other_fees_hash.get(&event_id).unwrap_or(&vec![]).to_vec(),
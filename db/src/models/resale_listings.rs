@@ -0,0 +1,468 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::expression::sql_literal::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool};
+use models::*;
+use schema::{resale_listings, ticket_types};
+use utils::errors::*;
+use uuid::Uuid;
+
+// A listing added to a cart is held for that cart until it expires
+const NOT_RESERVED_IN_CART: &str = "NOT EXISTS (
+    SELECT 1
+    FROM order_items oi
+    JOIN orders o ON o.id = oi.order_id
+    WHERE oi.id = resale_listings.order_item_id
+    AND o.status IN ('Draft', 'PendingPayment')
+    AND o.expires_at > now()
+)";
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(TicketType)]
+#[table_name = "resale_listings"]
+pub struct ResaleListing {
+    pub id: Uuid,
+    pub ticket_instance_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub face_value_in_cents: i64,
+    pub price_in_cents: i64,
+    pub resale_fee_in_cents: i64,
+    pub status: ResaleListingStatus,
+    pub order_item_id: Option<Uuid>,
+    pub sold_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "resale_listings"]
+pub struct NewResaleListing {
+    pub ticket_instance_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub price_in_cents: i64,
+}
+
+#[derive(Queryable, Serialize, Deserialize, PartialEq, Debug)]
+pub struct DisplayResaleListing {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub ticket_type_name: String,
+    pub face_value_in_cents: i64,
+    pub price_in_cents: i64,
+}
+
+impl NewResaleListing {
+    pub fn commit(&self, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        if self.price_in_cents < 0 {
+            return DatabaseError::validation_error("price_in_cents", "Price must not be negative");
+        }
+
+        // Only purchased tickets held by the seller can be listed
+        TicketInstance::verify_tickets_belong_to_user(
+            self.seller_user_id,
+            &[self.ticket_instance_id],
+            conn,
+        )?;
+        if ResaleListing::find_active_for_ticket_instance(self.ticket_instance_id, conn)?.is_some()
+        {
+            return DatabaseError::validation_error(
+                "ticket_instance_id",
+                "Ticket is already listed for resale",
+            );
+        }
+
+        let ticket = TicketInstance::find(self.ticket_instance_id, conn)?;
+        let ticket_type = ticket.ticket_type(conn)?;
+        let organization = Event::find(ticket_type.event_id, conn)?.organization(conn)?;
+        let max_markup_percent = match organization.max_resale_markup_percent {
+            Some(percent) => percent,
+            None => {
+                return DatabaseError::validation_error(
+                    "ticket_instance_id",
+                    "Resale is not enabled for this event",
+                );
+            }
+        };
+
+        let face_value_in_cents = match ticket.order_item_id {
+            Some(order_item_id) => OrderItem::find(order_item_id, conn)?.unit_price_in_cents,
+            None => 0,
+        };
+        let max_price_in_cents = face_value_in_cents
+            + (face_value_in_cents as f64 * max_markup_percent as f64 / 100.0).floor() as i64;
+        if self.price_in_cents > max_price_in_cents {
+            return DatabaseError::validation_error(
+                "price_in_cents",
+                "Price exceeds the maximum resale price for this ticket",
+            );
+        }
+        let resale_fee_in_cents =
+            (self.price_in_cents as f64 * organization.resale_fee_percent as f64 / 100.0).round()
+                as i64;
+
+        let listing: ResaleListing = diesel::insert_into(resale_listings::table)
+            .values((
+                self,
+                resale_listings::ticket_type_id.eq(ticket_type.id),
+                resale_listings::face_value_in_cents.eq(face_value_in_cents),
+                resale_listings::resale_fee_in_cents.eq(resale_fee_in_cents),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create resale listing")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingCreated,
+            "Ticket listed for resale".to_string(),
+            Tables::ResaleListings,
+            Some(listing.id),
+            Some(self.seller_user_id),
+            Some(json!({
+                "ticket_instance_id": listing.ticket_instance_id,
+                "price_in_cents": listing.price_in_cents,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+}
+
+impl ResaleListing {
+    pub fn create(
+        ticket_instance_id: Uuid,
+        seller_user_id: Uuid,
+        price_in_cents: i64,
+    ) -> NewResaleListing {
+        NewResaleListing {
+            ticket_instance_id,
+            seller_user_id,
+            price_in_cents,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        resale_listings::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale listing")
+    }
+
+    pub fn find_active_for_ticket_instance(
+        ticket_instance_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .filter(resale_listings::ticket_instance_id.eq(ticket_instance_id))
+            .filter(resale_listings::status.eq(ResaleListingStatus::Active))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load resale listing")
+    }
+
+    /// Active listings for an event that are not currently held in someone else's cart
+    pub fn find_available_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplayResaleListing>, DatabaseError> {
+        resale_listings::table
+            .inner_join(ticket_types::table)
+            .filter(ticket_types::event_id.eq(event_id))
+            .filter(resale_listings::status.eq(ResaleListingStatus::Active))
+            .filter(sql::<Bool>(NOT_RESERVED_IN_CART))
+            .order_by(resale_listings::price_in_cents)
+            .select((
+                resale_listings::id,
+                resale_listings::ticket_type_id,
+                ticket_types::name,
+                resale_listings::face_value_in_cents,
+                resale_listings::price_in_cents,
+            ))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale listings")
+    }
+
+    pub fn is_available(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        diesel::select(dsl::exists(
+            resale_listings::table
+                .filter(resale_listings::id.eq(self.id))
+                .filter(resale_listings::status.eq(ResaleListingStatus::Active))
+                .filter(sql::<Bool>(NOT_RESERVED_IN_CART)),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check if resale listing is available",
+        )
+    }
+
+    /// The amount credited to the seller once the listing is sold
+    pub fn seller_proceeds_in_cents(&self) -> i64 {
+        self.price_in_cents - self.resale_fee_in_cents
+    }
+
+    pub fn cancel(
+        &self,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ResaleListing, DatabaseError> {
+        if self.status != ResaleListingStatus::Active || !self.is_available(conn)? {
+            return DatabaseError::business_process_error(
+                "Resale listing can no longer be cancelled",
+            );
+        }
+
+        let listing: ResaleListing = diesel::update(self)
+            .set((
+                resale_listings::status.eq(ResaleListingStatus::Cancelled),
+                resale_listings::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not cancel resale listing")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingCancelled,
+            "Resale listing cancelled".to_string(),
+            Tables::ResaleListings,
+            Some(listing.id),
+            Some(current_user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
+    /// Adds the listing to a cart, holding it for the buyer until the cart expires
+    pub fn add_to_cart(
+        &self,
+        cart: &mut Order,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<OrderItem, DatabaseError> {
+        if cart.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error(
+                "Resale listings can only be added to a cart",
+            );
+        }
+        if cart.user_id == self.seller_user_id {
+            return DatabaseError::validation_error(
+                "resale_listing_id",
+                "Sellers cannot purchase their own resale listings",
+            );
+        }
+        if !self.is_available(conn)? {
+            return DatabaseError::business_process_error("Resale listing is no longer available");
+        }
+
+        cart.lock_version(conn)?;
+        if cart.expires_at.is_none() {
+            cart.set_expiry(Some(current_user_id), None, conn)?;
+        }
+
+        let ticket_type = TicketType::find(self.ticket_type_id, conn)?;
+        let event = Event::find(ticket_type.event_id, conn)?;
+        cart.set_currency(&event.currency, conn)?;
+        let order_item = NewResaleOrderItem {
+            order_id: cart.id,
+            item_type: OrderItemTypes::Resale,
+            event_id: Some(ticket_type.event_id),
+            quantity: 1,
+            unit_price_in_cents: self.price_in_cents,
+            ticket_type_id: ticket_type.id,
+        }
+        .commit(conn)?;
+
+        let rows_affected = diesel::update(
+            resale_listings::table
+                .filter(resale_listings::id.eq(self.id))
+                .filter(resale_listings::updated_at.eq(self.updated_at)),
+        )
+        .set((
+            resale_listings::order_item_id.eq(order_item.id),
+            resale_listings::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not reserve resale listing")?;
+        if rows_affected != 1 {
+            return DatabaseError::concurrency_error("Could not reserve resale listing");
+        }
+
+        Ok(order_item)
+    }
+
+    /// Completes the sale of the listing held by a paid order item, moving the ticket into the
+    /// buyer's wallet using the ticket transfer process
+    pub(crate) fn complete_sale(
+        order_item: &OrderItem,
+        buyer_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ResaleListing, DatabaseError> {
+        let listing: ResaleListing = resale_listings::table
+            .filter(resale_listings::order_item_id.eq(order_item.id))
+            .filter(resale_listings::status.eq(ResaleListingStatus::Active))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load resale listing")?
+            .ok_or_else(|| {
+                DatabaseError::new(
+                    ErrorCode::BusinessProcessError,
+                    Some("Resale listing is no longer available".to_string()),
+                )
+            })?;
+
+        // Mark as sold first, listed tickets cannot be transferred
+        let listing: ResaleListing = diesel::update(&listing)
+            .set((
+                resale_listings::status.eq(ResaleListingStatus::Sold),
+                resale_listings::sold_at.eq(Some(Utc::now().naive_utc())),
+                resale_listings::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update resale listing")?;
+
+        TicketInstance::direct_transfer(
+            listing.seller_user_id,
+            &[listing.ticket_instance_id],
+            buyer_user_id,
+            conn,
+        )?;
+
+        let currency = match order_item.order(conn)?.currency {
+            Some(currency) => currency,
+            None => {
+                Event::find(
+                    TicketType::find(listing.ticket_type_id, conn)?.event_id,
+                    conn,
+                )?
+                .currency
+            }
+        };
+        let payout = ResalePayout::create_for_listing(&listing, currency, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingSold,
+            "Resale listing sold".to_string(),
+            Tables::ResaleListings,
+            Some(listing.id),
+            Some(buyer_user_id),
+            Some(json!({
+                "order_item_id": order_item.id,
+                "price_in_cents": listing.price_in_cents,
+                "resale_fee_in_cents": listing.resale_fee_in_cents,
+                "seller_proceeds_in_cents": listing.seller_proceeds_in_cents(),
+                "resale_payout_id": payout.id,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
+    /// Reverses the sale of the listing held by a refunded order item. The ticket goes back to
+    /// the seller and their payout is cancelled, so a sale can only be refunded while the buyer
+    /// still holds the unredeemed ticket and before the seller has been paid.
+    pub(crate) fn refund_sale(
+        order_item: &OrderItem,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ResaleListing, DatabaseError> {
+        let listing = match ResaleListing::find_for_order_item(order_item.id, conn)? {
+            Some(ref listing) if listing.status == ResaleListingStatus::Sold => listing.clone(),
+            _ => return DatabaseError::business_process_error("Resale listing has not been sold"),
+        };
+        ResalePayout::find_for_resale_listing(listing.id, conn)?.cancel(conn)?;
+
+        let order = order_item.order(conn)?;
+        TicketInstance::direct_transfer(
+            order.on_behalf_of_user_id.unwrap_or(order.user_id),
+            &[listing.ticket_instance_id],
+            listing.seller_user_id,
+            conn,
+        )?;
+
+        let listing: ResaleListing = diesel::update(&listing)
+            .set((
+                resale_listings::status.eq(ResaleListingStatus::Refunded),
+                resale_listings::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update resale listing")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingRefunded,
+            "Resale purchase refunded".to_string(),
+            Tables::ResaleListings,
+            Some(listing.id),
+            Some(current_user_id),
+            Some(json!({ "order_item_id": order_item.id })),
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
+    /// Withdraws the active listing of a ticket that is being refunded or nullified, whether or
+    /// not it is currently held in a cart
+    pub(crate) fn withdraw_for_ticket_instance(
+        ticket_instance_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if let Some(listing) =
+            ResaleListing::find_active_for_ticket_instance(ticket_instance_id, conn)?
+        {
+            diesel::update(&listing)
+                .set((
+                    resale_listings::status.eq(ResaleListingStatus::Cancelled),
+                    resale_listings::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not cancel resale listing")?;
+
+            DomainEvent::create(
+                DomainEventTypes::ResaleListingCancelled,
+                "Resale listing withdrawn".to_string(),
+                Tables::ResaleListings,
+                Some(listing.id),
+                current_user_id,
+                None,
+            )
+            .commit(conn)?;
+        }
+        Ok(())
+    }
+
+    pub fn find_for_order_item(
+        order_item_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .filter(resale_listings::order_item_id.eq(order_item_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load resale listing")
+    }
+
+    /// Total resale fees earned on an event's listings sold in the given period
+    pub fn fees_for_event(
+        event_id: Uuid,
+        start_time: NaiveDateTime,
+        end_time: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        resale_listings::table
+            .inner_join(ticket_types::table)
+            .filter(ticket_types::event_id.eq(event_id))
+            .filter(resale_listings::status.eq(ResaleListingStatus::Sold))
+            .filter(resale_listings::sold_at.ge(start_time))
+            .filter(resale_listings::sold_at.le(end_time))
+            .select(sql::<BigInt>(
+                "CAST(COALESCE(SUM(resale_listings.resale_fee_in_cents), 0) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale fees")
+    }
+}
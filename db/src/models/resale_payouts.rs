@@ -0,0 +1,158 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::resale_payouts;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// The proceeds owed to a seller for a sold resale listing. Payouts are created when the sale
+/// completes and are paid to the seller outside of Big Neon.
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(ResaleListing)]
+#[table_name = "resale_payouts"]
+pub struct ResalePayout {
+    pub id: Uuid,
+    pub resale_listing_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub amount_in_cents: i64,
+    pub currency: String,
+    pub status: ResalePayoutStatus,
+    pub paid_at: Option<NaiveDateTime>,
+    pub paid_by_user_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "resale_payouts"]
+struct NewResalePayout {
+    resale_listing_id: Uuid,
+    seller_user_id: Uuid,
+    amount_in_cents: i64,
+    currency: String,
+}
+
+impl ResalePayout {
+    pub(crate) fn create_for_listing(
+        listing: &ResaleListing,
+        currency: String,
+        conn: &PgConnection,
+    ) -> Result<ResalePayout, DatabaseError> {
+        diesel::insert_into(resale_payouts::table)
+            .values(NewResalePayout {
+                resale_listing_id: listing.id,
+                seller_user_id: listing.seller_user_id,
+                amount_in_cents: listing.seller_proceeds_in_cents(),
+                currency,
+            })
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create resale payout")
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ResalePayout, DatabaseError> {
+        resale_payouts::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale payout")
+    }
+
+    pub fn find_for_resale_listing(
+        resale_listing_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ResalePayout, DatabaseError> {
+        resale_payouts::table
+            .filter(resale_payouts::resale_listing_id.eq(resale_listing_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale payout")
+    }
+
+    pub fn find_by_status(
+        status: ResalePayoutStatus,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<ResalePayout>, DatabaseError> {
+        let total: i64 = resale_payouts::table
+            .filter(resale_payouts::status.eq(status))
+            .select(dsl::count(resale_payouts::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale payouts")?;
+        let payouts = resale_payouts::table
+            .filter(resale_payouts::status.eq(status))
+            .order_by(resale_payouts::created_at)
+            .limit(limit as i64)
+            .offset((page * limit) as i64)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load resale payouts")?;
+
+        let mut payload = Payload::from_data(payouts, page, limit);
+        payload.paging.total = total as u64;
+        Ok(payload)
+    }
+
+    /// Records that the seller has been paid
+    pub fn mark_paid(
+        &self,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ResalePayout, DatabaseError> {
+        let payout = self.update_status(ResalePayoutStatus::Paid, Some(current_user_id), conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResalePayoutPaid,
+            "Resale payout paid".to_string(),
+            Tables::ResalePayouts,
+            Some(payout.id),
+            Some(current_user_id),
+            Some(json!({
+                "resale_listing_id": payout.resale_listing_id,
+                "amount_in_cents": payout.amount_in_cents,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(payout)
+    }
+
+    /// Cancels the payout of a sale that was refunded to the buyer
+    pub(crate) fn cancel(&self, conn: &PgConnection) -> Result<ResalePayout, DatabaseError> {
+        self.update_status(ResalePayoutStatus::Cancelled, None, conn)
+    }
+
+    /// Only pending payouts can change status, the guard on the update stops a payout being both
+    /// paid and cancelled by concurrent requests
+    fn update_status(
+        &self,
+        status: ResalePayoutStatus,
+        paid_by_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<ResalePayout, DatabaseError> {
+        let paid_at = if status == ResalePayoutStatus::Paid {
+            Some(Utc::now().naive_utc())
+        } else {
+            None
+        };
+        diesel::update(
+            resale_payouts::table
+                .filter(resale_payouts::id.eq(self.id))
+                .filter(resale_payouts::status.eq(ResalePayoutStatus::Pending)),
+        )
+        .set((
+            resale_payouts::status.eq(status),
+            resale_payouts::paid_at.eq(paid_at),
+            resale_payouts::paid_by_user_id.eq(paid_by_user_id),
+            resale_payouts::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not update resale payout")?
+        .ok_or_else(|| {
+            DatabaseError::new(
+                ErrorCode::BusinessProcessError,
+                Some("Resale payout has already been paid or cancelled".to_string()),
+            )
+        })
+    }
+}
@@ -24,6 +24,7 @@ pub enum Scopes {
     EventWrite,
     HoldRead,
     HoldWrite,
+    OrderExchange,
     OrderMakeExternalPayment,
     OrderRead,
     OrderReadOwn,
@@ -48,6 +49,21 @@ pub enum Scopes {
     VenueWrite,
 }
 
+impl Scopes {
+    /// Scopes that expose or move money, organizations can require two factor authentication
+    /// before members use them
+    pub fn is_financial(&self) -> bool {
+        match self {
+            Scopes::EventFinancialReports
+            | Scopes::OrderExchange
+            | Scopes::OrderMakeExternalPayment
+            | Scopes::OrderRefund
+            | Scopes::OrgFinancialReports => true,
+            _ => false,
+        }
+    }
+}
+
 impl Serialize for Scopes {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -77,6 +93,7 @@ impl fmt::Display for Scopes {
             Scopes::EventViewGuests => "event:view-guests",
             Scopes::HoldRead => "hold:read",
             Scopes::HoldWrite => "hold:write",
+            Scopes::OrderExchange => "order:exchange",
             Scopes::OrderRead => "order:read",
             Scopes::OrderMakeExternalPayment => "order:make-external-payment",
             Scopes::OrderReadOwn => "order:read-own",
@@ -127,6 +144,7 @@ impl FromStr for Scopes {
             "hold:read" => Scopes::HoldRead,
             "hold:write" => Scopes::HoldWrite,
             "order:read" => Scopes::OrderRead,
+            "order:exchange" => Scopes::OrderExchange,
             "order:make-external-payment" => Scopes::OrderMakeExternalPayment,
             "order:read-own" => Scopes::OrderReadOwn,
             "order:refund" => Scopes::OrderRefund,
@@ -195,6 +213,7 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
             let mut roles = vec![
                 Scopes::DashboardRead,
                 Scopes::EventViewGuests,
+                Scopes::OrderExchange,
                 Scopes::OrderMakeExternalPayment,
                 Scopes::BoxOfficeTicketRead,
             ];
@@ -248,6 +267,7 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
                 Scopes::EventWrite,
                 Scopes::HoldRead,
                 Scopes::HoldWrite,
+                Scopes::OrderExchange,
                 Scopes::OrderRead,
                 Scopes::OrderRefund,
                 Scopes::OrgRead,
@@ -319,6 +339,7 @@ fn get_scopes_for_role_test() {
             Scopes::EventWrite,
             Scopes::HoldRead,
             Scopes::HoldWrite,
+            Scopes::OrderExchange,
             Scopes::OrderMakeExternalPayment,
             Scopes::OrderRead,
             Scopes::OrderReadOwn,
@@ -374,6 +395,7 @@ fn get_scopes_test() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:make-external-payment",
             "order:read",
             "order:read-own",
@@ -420,6 +442,7 @@ fn get_scopes_test() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:make-external-payment",
             "order:read",
             "order:read-own",
@@ -469,6 +492,7 @@ fn get_scopes_test() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:make-external-payment",
             "order:read",
             "order:read-own",
@@ -501,3 +525,11 @@ fn from_str() {
     let s: Scopes = "ticket:read".parse().unwrap();
     assert_eq!(Scopes::TicketRead, s);
 }
+
+#[test]
+fn is_financial() {
+    assert!(Scopes::OrderRefund.is_financial());
+    assert!(Scopes::OrderExchange.is_financial());
+    assert!(Scopes::OrgFinancialReports.is_financial());
+    assert!(!Scopes::OrgRead.is_financial());
+}
@@ -0,0 +1,159 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{seating_sections, seats, ticket_instances};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(Stage)]
+#[table_name = "seating_sections"]
+pub struct SeatingSection {
+    pub id: Uuid,
+    pub stage_id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "seating_sections"]
+pub struct NewSeatingSection {
+    pub stage_id: Uuid,
+    pub name: String,
+}
+
+impl NewSeatingSection {
+    pub fn commit(&self, conn: &PgConnection) -> Result<SeatingSection, DatabaseError> {
+        diesel::insert_into(seating_sections::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seating section")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySeatingSection {
+    pub id: Uuid,
+    pub name: String,
+    pub seats: Vec<Seat>,
+}
+
+impl SeatingSection {
+    pub fn create(stage_id: Uuid, name: String) -> NewSeatingSection {
+        NewSeatingSection { stage_id, name }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<SeatingSection, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::QueryError,
+            "Error loading seating section",
+            seating_sections::table
+                .find(id)
+                .first::<SeatingSection>(conn),
+        )
+    }
+
+    pub fn find_by_stage_id(
+        stage_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<SeatingSection>, DatabaseError> {
+        seating_sections::table
+            .filter(seating_sections::stage_id.eq(stage_id))
+            .order_by(seating_sections::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seating sections")
+    }
+
+    /// Adds a row of `seat_count` seats numbered from 1 to this section
+    pub fn add_row(
+        &self,
+        row_name: String,
+        seat_count: u32,
+        price_zone_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Seat>, DatabaseError> {
+        if seat_count == 0 {
+            return DatabaseError::validation_error("seat_count", "Row must contain seats");
+        }
+
+        if let Some(price_zone_id) = price_zone_id {
+            if PriceZone::find(price_zone_id, conn)?.stage_id != self.stage_id {
+                return DatabaseError::validation_error(
+                    "price_zone_id",
+                    "Price zone does not belong to this stage",
+                );
+            }
+        }
+
+        let new_seats: Vec<NewSeat> = (1..=seat_count)
+            .map(|seat_number| {
+                Seat::create(
+                    self.id,
+                    price_zone_id,
+                    row_name.clone(),
+                    seat_number.to_string(),
+                )
+            })
+            .collect();
+
+        diesel::insert_into(seats::table)
+            .values(&new_seats)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seats")
+    }
+
+    pub fn seats(&self, conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        Seat::find_by_seating_section_id(self.id, conn)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplaySeatingSection, DatabaseError> {
+        Ok(DisplaySeatingSection {
+            id: self.id,
+            name: self.name.clone(),
+            seats: self.seats(conn)?,
+        })
+    }
+
+    /// Deletes the section and its seats. Seats still paired with tickets that are not nullified
+    /// have to be released by moving the ticket type off the price zone first.
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let seat_ids: Vec<Option<Uuid>> = seats::table
+            .filter(seats::seating_section_id.eq(self.id))
+            .select(seats::id.nullable())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load seats")?;
+
+        let assigned_count: i64 = ticket_instances::table
+            .filter(ticket_instances::seat_id.eq_any(&seat_ids))
+            .filter(ticket_instances::status.ne(TicketInstanceStatus::Nullified))
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not check seat assignments")?;
+        if assigned_count > 0 {
+            return DatabaseError::business_process_error(
+                "Seating section cannot be deleted while its seats are assigned to tickets",
+            );
+        }
+
+        diesel::update(ticket_instances::table.filter(ticket_instances::seat_id.eq_any(&seat_ids)))
+            .set((
+                ticket_instances::seat_id.eq(None::<Uuid>),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not clear seat assignments")?;
+
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Failed to delete seating section",
+            diesel::delete(self).execute(conn),
+        )
+    }
+}
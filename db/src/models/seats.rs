@@ -0,0 +1,121 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Text, Uuid as dUuid};
+use models::*;
+use schema::{assets, seating_sections, seats, ticket_instances};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(SeatingSection)]
+#[belongs_to(PriceZone)]
+#[table_name = "seats"]
+pub struct Seat {
+    pub id: Uuid,
+    pub seating_section_id: Uuid,
+    pub price_zone_id: Option<Uuid>,
+    pub row_name: String,
+    pub seat_number: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "seats"]
+pub struct NewSeat {
+    pub seating_section_id: Uuid,
+    pub price_zone_id: Option<Uuid>,
+    pub row_name: String,
+    pub seat_number: String,
+}
+
+impl NewSeat {
+    pub fn commit(&self, conn: &PgConnection) -> Result<Seat, DatabaseError> {
+        diesel::insert_into(seats::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seat")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, QueryableByName, Serialize)]
+pub struct DisplaySeatAvailability {
+    #[sql_type = "dUuid"]
+    pub seat_id: Uuid,
+    #[sql_type = "Text"]
+    pub section_name: String,
+    #[sql_type = "Text"]
+    pub row_name: String,
+    #[sql_type = "Text"]
+    pub seat_number: String,
+    #[sql_type = "Bool"]
+    pub available: bool,
+}
+
+impl Seat {
+    pub fn create(
+        seating_section_id: Uuid,
+        price_zone_id: Option<Uuid>,
+        row_name: String,
+        seat_number: String,
+    ) -> NewSeat {
+        NewSeat {
+            seating_section_id,
+            price_zone_id,
+            row_name,
+            seat_number,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Seat, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::QueryError,
+            "Error loading seat",
+            seats::table.find(id).first::<Seat>(conn),
+        )
+    }
+
+    pub fn find_by_seating_section_id(
+        seating_section_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Seat>, DatabaseError> {
+        seats::table
+            .filter(seats::seating_section_id.eq(seating_section_id))
+            .order_by((seats::row_name, seats::seat_number))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seats")
+    }
+
+    /// Lists the seats bound to a ticket type's inventory along with whether they can be
+    /// added to a cart
+    pub fn find_for_ticket_type_for_display(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplaySeatAvailability>, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+            .inner_join(seats::table.on(ticket_instances::seat_id.eq(seats::id.nullable())))
+            .inner_join(
+                seating_sections::table.on(seats::seating_section_id.eq(seating_sections::id)),
+            )
+            .filter(assets::ticket_type_id.eq(ticket_type_id))
+            .filter(ticket_instances::status.ne(TicketInstanceStatus::Nullified))
+            .select((
+                seats::id,
+                seating_sections::name,
+                seats::row_name,
+                seats::seat_number,
+                sql::<Bool>(
+                    "ticket_instances.hold_id IS NULL AND (ticket_instances.status = 'Available'
+                    OR (ticket_instances.status = 'Reserved' AND ticket_instances.reserved_until < now()))",
+                ),
+            ))
+            .order_by((seating_sections::name, seats::row_name, seats::seat_number))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seat availability")
+    }
+}
@@ -20,6 +20,7 @@ pub struct SettlementTransaction {
     pub comment: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
 }
 
 #[derive(Clone, Default, Insertable, Serialize, Deserialize, PartialEq, Debug)]
@@ -32,6 +33,7 @@ pub struct NewSettlementTransaction {
     pub transaction_type: Option<SettlementTransactionType>,
     pub value_in_cents: i64,
     pub comment: Option<String>,
+    pub currency: Option<String>,
 }
 
 impl NewSettlementTransaction {
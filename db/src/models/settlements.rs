@@ -38,6 +38,7 @@ pub struct PendingSettlement {
     pub only_finished_events: bool,
     pub sales_per_event: HashMap<Uuid, TicketSalesAndCounts>,
     pub transactions: Vec<NewSettlementTransaction>,
+    pub totals_per_currency: HashMap<String, i64>,
 }
 
 #[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
@@ -66,6 +67,7 @@ pub struct DisplaySettlement {
     pub settlement: Settlement,
     pub transactions: Vec<SettlementTransaction>,
     pub events: Vec<Event>,
+    pub totals_per_currency: HashMap<String, i64>,
 }
 
 impl NewSettlementRequest {
@@ -107,8 +109,14 @@ impl NewSettlementRequest {
         let new_adjustments = self.adjustments.clone().unwrap_or(vec![]);
 
         for new_adjustment in new_adjustments {
+            // Adjustments are in the currency of their event unless specified
+            let currency = match new_adjustment.currency.clone() {
+                Some(currency) => currency,
+                None => Event::find(new_adjustment.event_id, conn)?.currency,
+            };
             let new_adjustment_transaction = NewSettlementTransaction {
                 settlement_id: Some(settlement.id.clone()),
+                currency: Some(currency),
                 ..new_adjustment
             };
             let _adjustment_transaction = new_adjustment_transaction.commit(conn)?;
@@ -122,6 +130,18 @@ impl NewSettlementRequest {
         user_id: Uuid,
         conn: &PgConnection,
     ) -> Result<PendingSettlement, DatabaseError> {
+        let transactions = Settlement::create_base_transactions(
+            None,
+            organization_id,
+            self.start_utc.clone(),
+            self.end_utc.clone(),
+            conn,
+        )?;
+        let totals_per_currency = Settlement::totals_per_currency(
+            transactions
+                .iter()
+                .map(|t| (t.currency.clone().unwrap_or_default(), t.value_in_cents)),
+        );
         let pending_settlement = PendingSettlement {
             organization_id,
             user_id,
@@ -136,13 +156,8 @@ impl NewSettlementRequest {
                 self.end_utc.clone(),
                 conn,
             )?,
-            transactions: Settlement::create_base_transactions(
-                None,
-                organization_id,
-                self.start_utc.clone(),
-                self.end_utc.clone(),
-                conn,
-            )?,
+            transactions,
+            totals_per_currency,
         };
         Ok(pending_settlement)
     }
@@ -211,13 +226,31 @@ impl Settlement {
         unique_events.dedup();
 
         let events = Event::find_by_ids(unique_events, conn)?;
+        let totals_per_currency = Settlement::totals_per_currency(
+            transactions
+                .iter()
+                .map(|t| (t.currency.clone(), t.value_in_cents)),
+        );
         let settlement = self;
         Ok(DisplaySettlement {
             settlement,
             transactions,
             events,
+            totals_per_currency,
         })
     }
+
+    /// Settlement values summed per currency, since amounts in different currencies are paid
+    /// out separately
+    pub fn totals_per_currency<I: Iterator<Item = (String, i64)>>(
+        values: I,
+    ) -> HashMap<String, i64> {
+        let mut totals: HashMap<String, i64> = HashMap::new();
+        for (currency, value_in_cents) in values {
+            *totals.entry(currency).or_insert(0) += value_in_cents;
+        }
+        totals
+    }
     pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
         diesel::delete(settlements::table.filter(settlements::id.eq(self.id)))
             .execute(conn)
@@ -245,8 +278,11 @@ impl Settlement {
     ) -> Result<Vec<NewSettlementTransaction>, DatabaseError> {
         let settlement_id = settlement_id.unwrap_or(Uuid::default());
         let counts_by_event = Settlement::get_counts(organization_id, start_time, end_time, conn)?;
+        let mut disputed_amounts =
+            Dispute::amounts_per_event(organization_id, start_time, end_time, conn)?;
         let mut results = vec![];
         for (event_id, counts) in counts_by_event.iter() {
+            let currency = Event::find(*event_id, conn)?.currency;
             let mut face_value = 0;
             let mut service_fee_value = 0;
             for row in counts.sales.iter() {
@@ -261,6 +297,7 @@ impl Settlement {
                 transaction_type: Some(SettlementTransactionType::Report),
                 value_in_cents: face_value,
                 comment: Some("Face Amount Owed To Client".to_string()),
+                currency: Some(currency.clone()),
             });
             results.push(NewSettlementTransaction {
                 settlement_id: Some(settlement_id.clone()),
@@ -270,11 +307,92 @@ impl Settlement {
                 transaction_type: Some(SettlementTransactionType::Report),
                 value_in_cents: service_fee_value,
                 comment: Some("Service Fee Revenue Share".to_string()),
+                currency: Some(currency.clone()),
             });
+            let resale_fee_value =
+                ResaleListing::fees_for_event(*event_id, start_time, end_time, conn)?;
+            if resale_fee_value > 0 {
+                results.push(NewSettlementTransaction {
+                    settlement_id: Some(settlement_id.clone()),
+                    event_id: event_id.clone().to_owned(),
+                    order_item_id: None,
+                    settlement_status: Some(SettlementStatus::PendingSettlement),
+                    transaction_type: Some(SettlementTransactionType::Report),
+                    value_in_cents: resale_fee_value,
+                    comment: Some("Resale Fee Owed To Client".to_string()),
+                    currency: Some(currency.clone()),
+                });
+            }
+            let tax_value = TaxRate::collected_for_event(*event_id, start_time, end_time, conn)?;
+            if tax_value > 0 {
+                results.push(NewSettlementTransaction {
+                    settlement_id: Some(settlement_id.clone()),
+                    event_id: event_id.clone().to_owned(),
+                    order_item_id: None,
+                    settlement_status: Some(SettlementStatus::PendingSettlement),
+                    transaction_type: Some(SettlementTransactionType::Report),
+                    value_in_cents: tax_value,
+                    comment: Some("Tax Collected".to_string()),
+                    currency: Some(currency.clone()),
+                });
+            }
+            if let Some(disputed_amount) = disputed_amounts.remove(event_id) {
+                Settlement::push_dispute_transactions(
+                    &mut results,
+                    settlement_id,
+                    *event_id,
+                    disputed_amount,
+                    &currency,
+                );
+            }
+        }
+        // Disputes can be opened or won for events without sales in the period
+        for (event_id, disputed_amount) in disputed_amounts {
+            let currency = Event::find(event_id, conn)?.currency;
+            Settlement::push_dispute_transactions(
+                &mut results,
+                settlement_id,
+                event_id,
+                disputed_amount,
+                &currency,
+            );
         }
         Ok(results)
     }
 
+    fn push_dispute_transactions(
+        results: &mut Vec<NewSettlementTransaction>,
+        settlement_id: Uuid,
+        event_id: Uuid,
+        (withheld_value, returned_value): (i64, i64),
+        currency: &str,
+    ) {
+        if withheld_value > 0 {
+            results.push(NewSettlementTransaction {
+                settlement_id: Some(settlement_id),
+                event_id,
+                order_item_id: None,
+                settlement_status: Some(SettlementStatus::PendingSettlement),
+                transaction_type: Some(SettlementTransactionType::Report),
+                value_in_cents: -withheld_value,
+                comment: Some("Disputed Amount Withheld".to_string()),
+                currency: Some(currency.to_string()),
+            });
+        }
+        if returned_value > 0 {
+            results.push(NewSettlementTransaction {
+                settlement_id: Some(settlement_id),
+                event_id,
+                order_item_id: None,
+                settlement_status: Some(SettlementStatus::PendingSettlement),
+                transaction_type: Some(SettlementTransactionType::Report),
+                value_in_cents: returned_value,
+                comment: Some("Disputed Amount Returned".to_string()),
+                currency: Some(currency.to_string()),
+            });
+        }
+    }
+
     pub fn index(
         organization_id: Uuid,
         limit: Option<u32>,
@@ -44,6 +44,13 @@ pub struct NewStage {
     pub capacity: Option<i64>,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySeatMap {
+    pub stage_id: Uuid,
+    pub price_zones: Vec<PriceZone>,
+    pub seating_sections: Vec<DisplaySeatingSection>,
+}
+
 impl NewStage {
     pub fn commit(&self, connection: &PgConnection) -> Result<Stage, DatabaseError> {
         diesel::insert_into(stages::table)
@@ -88,6 +95,19 @@ impl Stage {
             .to_db_error(ErrorCode::QueryError, "Unable to load all stages")
     }
 
+    pub fn seat_map(&self, conn: &PgConnection) -> Result<DisplaySeatMap, DatabaseError> {
+        let mut seating_sections = Vec::new();
+        for seating_section in SeatingSection::find_by_stage_id(self.id, conn)? {
+            seating_sections.push(seating_section.for_display(conn)?);
+        }
+
+        Ok(DisplaySeatMap {
+            stage_id: self.id,
+            price_zones: PriceZone::find_by_stage_id(self.id, conn)?,
+            seating_sections,
+        })
+    }
+
     pub fn update(
         &self,
         attributes: StageEditableAttributes,
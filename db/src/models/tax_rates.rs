@@ -0,0 +1,196 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use models::*;
+use schema::{order_items, orders, tax_rates, venues};
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Organization)]
+#[table_name = "tax_rates"]
+pub struct TaxRate {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub region_id: Option<Uuid>,
+    pub name: String,
+    pub rate_percent: f32,
+    pub inclusive: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "tax_rates"]
+pub struct NewTaxRate {
+    pub organization_id: Uuid,
+    pub region_id: Option<Uuid>,
+    pub name: String,
+    pub rate_percent: f32,
+    pub inclusive: bool,
+}
+
+impl NewTaxRate {
+    pub fn commit(
+        &self,
+        created_by_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<TaxRate, DatabaseError> {
+        if self.name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Tax name is required");
+        }
+        if self.rate_percent < 0.0 || self.rate_percent > 100.0 {
+            return DatabaseError::validation_error(
+                "rate_percent",
+                "Tax rate must be between 0 and 100 percent",
+            );
+        }
+
+        let tax_rate: TaxRate = diesel::insert_into(tax_rates::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create tax rate")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TaxRateCreated,
+            "Tax rate created".to_string(),
+            Tables::TaxRates,
+            Some(tax_rate.id),
+            Some(created_by_user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(tax_rate)
+    }
+}
+
+impl TaxRate {
+    pub fn create(
+        organization_id: Uuid,
+        region_id: Option<Uuid>,
+        name: String,
+        rate_percent: f32,
+        inclusive: bool,
+    ) -> NewTaxRate {
+        NewTaxRate {
+            organization_id,
+            region_id,
+            name,
+            rate_percent,
+            inclusive,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TaxRate, DatabaseError> {
+        tax_rates::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rate")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<TaxRate>, DatabaseError> {
+        tax_rates::table
+            .filter(tax_rates::organization_id.eq(organization_id))
+            .order_by(tax_rates::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rates")
+    }
+
+    /// The rate for the region of the event's venue, falling back to the organization wide rate
+    pub fn find_for_event(
+        event: &Event,
+        conn: &PgConnection,
+    ) -> Result<Option<TaxRate>, DatabaseError> {
+        let region_id: Option<Uuid> = match event.venue_id {
+            Some(venue_id) => Some(
+                venues::table
+                    .filter(venues::id.eq(venue_id))
+                    .select(venues::region_id)
+                    .first(conn)
+                    .to_db_error(ErrorCode::QueryError, "Could not load venue region")?,
+            ),
+            None => None,
+        };
+
+        tax_rates::table
+            .filter(tax_rates::organization_id.eq(event.organization_id))
+            .filter(
+                tax_rates::region_id
+                    .is_null()
+                    .or(tax_rates::region_id.eq(region_id)),
+            )
+            .order_by(tax_rates::region_id.is_null())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rate for event")
+    }
+
+    /// Tax due on a unit price, inclusive taxes are extracted from the price
+    pub fn tax_in_cents(&self, price_in_cents: i64) -> i64 {
+        let rate = self.rate_percent as f64 / 100.0;
+        if self.inclusive {
+            price_in_cents - (price_in_cents as f64 / (1.0 + rate)).round() as i64
+        } else {
+            (price_in_cents as f64 * rate).round() as i64
+        }
+    }
+
+    /// Amount added to the order for a unit price, zero when tax is included in the price
+    pub fn charge_in_cents(&self, price_in_cents: i64) -> i64 {
+        if self.inclusive {
+            0
+        } else {
+            self.tax_in_cents(price_in_cents)
+        }
+    }
+
+    /// Tax collected for an event on orders paid within the given period, net of refunds
+    pub fn collected_for_event(
+        event_id: Uuid,
+        start_time: NaiveDateTime,
+        end_time: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        order_items::table
+            .inner_join(orders::table)
+            .filter(order_items::event_id.eq(event_id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tax))
+            .filter(orders::status.eq(OrderStatus::Paid))
+            .filter(orders::paid_at.ge(start_time))
+            .filter(orders::paid_at.le(end_time))
+            .select(sql::<BigInt>(
+                "CAST(COALESCE(SUM(order_items.tax_in_cents * (order_items.quantity - order_items.refunded_quantity)), 0) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax collected")
+    }
+
+    pub fn destroy(&self, user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        // Orders keep their tax lines, only detach them from the rate
+        diesel::update(order_items::table.filter(order_items::tax_rate_id.eq(self.id)))
+            .set(order_items::tax_rate_id.eq(None::<Uuid>))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not detach tax rate")?;
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete tax rate")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TaxRateDeleted,
+            "Tax rate deleted".to_string(),
+            Tables::TaxRates,
+            Some(self.id),
+            Some(user_id),
+            None,
+        )
+        .commit(conn)?;
+        Ok(())
+    }
+}
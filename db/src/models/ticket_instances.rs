@@ -11,7 +11,8 @@ use models::*;
 use rand;
 use rand::Rng;
 use schema::{
-    assets, events, order_items, orders, ticket_instances, ticket_types, users, venues, wallets,
+    assets, events, order_items, orders, seating_sections, seats, ticket_instances, ticket_types,
+    users, venues, wallets,
 };
 use std::cmp;
 use tari_client::*;
@@ -35,6 +36,7 @@ pub struct TicketInstance {
     pub status: TicketInstanceStatus,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
+    pub seat_id: Option<Uuid>,
 }
 
 impl TicketInstance {
@@ -85,7 +87,9 @@ impl TicketInstance {
         }
 
         if new_status == TicketInstanceStatus::Nullified {
-            tickets[0].create_nullified_domain_event(user_id, conn)?;
+            tickets[0].create_nullified_domain_event(Some(user_id), conn)?;
+        } else {
+            WaitlistEntry::queue_offers(self.ticket_type(conn)?.id, conn)?;
         }
 
         Ok(())
@@ -122,6 +126,10 @@ impl TicketInstance {
             .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
             .inner_join(wallets::table.on(ticket_instances::wallet_id.eq(wallets::id)))
             .inner_join(events::table.on(ticket_types::event_id.eq(events::id)))
+            .left_join(seats::table.on(ticket_instances::seat_id.eq(seats::id.nullable())))
+            .left_join(
+                seating_sections::table.on(seats::seating_section_id.eq(seating_sections::id)),
+            )
             .filter(ticket_instances::id.eq(id))
             .select((
                 ticket_instances::id,
@@ -156,6 +164,9 @@ impl TicketInstance {
                         AS BOOLEAN)
                              AS pending_transfer",
                 ),
+                seating_sections::name.nullable(),
+                seats::row_name.nullable(),
+                seats::seat_number.nullable(),
             ))
             .first::<DisplayTicketIntermediary>(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
@@ -213,6 +224,10 @@ impl TicketInstance {
                 .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
                 .inner_join(wallets::table.on(ticket_instances::wallet_id.eq(wallets::id)))
                 .inner_join(events::table.on(ticket_types::event_id.eq(events::id)))
+                .left_join(seats::table.on(ticket_instances::seat_id.eq(seats::id.nullable())))
+                .left_join(
+                    seating_sections::table.on(seats::seating_section_id.eq(seating_sections::id)),
+                )
                 .filter(events::event_start.ge(
                     start_time.unwrap_or_else(|| NaiveDate::from_ymd(1970, 1, 1).and_hms(0, 0, 0)),
                 ))
@@ -259,6 +274,9 @@ impl TicketInstance {
                         END AS BOOLEAN)
                              AS pending_transfer",
                 ),
+                seating_sections::name.nullable(),
+                seats::row_name.nullable(),
+                seats::seat_number.nullable(),
             ))
             .order_by(events::event_start.asc())
             .then_order_by(events::name.asc())
@@ -335,6 +353,17 @@ impl TicketInstance {
             Some("Expiration date was not set on cart prior to reserving tickets".to_string()),
         ))?;
 
+        // Seated ticket types are only sold by picking seats through `reserve_seats`
+        if TicketType::find(ticket_type_id, conn)?
+            .price_zone_id
+            .is_some()
+        {
+            return DatabaseError::validation_error(
+                "seat_ids",
+                "Seats must be selected for this ticket type",
+            );
+        }
+
         let query = include_str!("../queries/reserve_tickets.sql");
         let q = diesel::sql_query(query)
             .bind::<sql_types::Uuid, _>(order_item.id)
@@ -356,6 +385,80 @@ impl TicketInstance {
         Ok(tickets)
     }
 
+    pub fn reserve_seats(
+        order_item: &OrderItem,
+        expires_at: Option<NaiveDateTime>,
+        ticket_type_id: Uuid,
+        ticket_holding_id: Option<Uuid>,
+        seat_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let order_expires_at = expires_at.ok_or(DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some("Expiration date was not set on cart prior to reserving seats".to_string()),
+        ))?;
+
+        let query = include_str!("../queries/reserve_seats.sql");
+        let q = diesel::sql_query(query)
+            .bind::<sql_types::Uuid, _>(order_item.id)
+            .bind::<sql_types::Timestamp, _>(order_expires_at)
+            .bind::<sql_types::Uuid, _>(ticket_type_id)
+            .bind::<sql_types::Nullable<sql_types::Uuid>, _>(ticket_holding_id)
+            .bind::<Array<dUuid>, _>(seat_ids);
+        let tickets: Vec<TicketInstance> = q
+            .get_results(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not reserve seats")?;
+
+        if tickets.len() != seat_ids.len() {
+            return DatabaseError::validation_error(
+                "seat_ids",
+                "One or more of the selected seats are no longer available",
+            );
+        }
+
+        Ok(tickets)
+    }
+
+    pub(crate) fn clear_seats(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let available_ids: Vec<Uuid> = ticket_instances::table
+            .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+            .filter(assets::ticket_type_id.eq(ticket_type_id))
+            .filter(ticket_instances::status.eq(TicketInstanceStatus::Available))
+            .select(ticket_instances::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load available tickets")?;
+
+        diesel::update(ticket_instances::table.filter(ticket_instances::id.eq_any(available_ids)))
+            .set((
+                ticket_instances::seat_id.eq(None::<Uuid>),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not clear seat assignments")?;
+
+        Ok(())
+    }
+
+    /// Pairs the ticket type's available tickets that do not have a seat yet with the free seats
+    /// of the price zone. Existing seat assignments are left as they are.
+    pub(crate) fn assign_seats(
+        ticket_type_id: Uuid,
+        event_id: Uuid,
+        price_zone_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let query = include_str!("../queries/assign_seats_to_ticket_type.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(ticket_type_id)
+            .bind::<dUuid, _>(price_zone_id)
+            .bind::<dUuid, _>(event_id)
+            .get_results(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not assign seats to tickets")
+    }
+
     pub fn release_tickets(
         order_item: &OrderItem,
         quantity: u32,
@@ -395,8 +498,10 @@ impl TicketInstance {
 
         if new_status == TicketInstanceStatus::Nullified {
             for ticket in &tickets {
-                ticket.create_nullified_domain_event(user_id, conn)?;
+                ticket.create_nullified_domain_event(Some(user_id), conn)?;
             }
+        } else if let Some(ticket_type_id) = order_item.ticket_type_id {
+            WaitlistEntry::queue_offers(ticket_type_id, conn)?;
         }
 
         Ok(tickets)
@@ -454,6 +559,8 @@ impl TicketInstance {
             );
         }
 
+        WaitlistEntry::queue_offers(ticket_type_id, conn)?;
+
         Ok(tickets)
     }
 
@@ -626,6 +733,13 @@ impl TicketInstance {
             .first(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
 
+        // Listed tickets are locked until the listing is cancelled or sold to a new holder
+        if ticket.status == TicketInstanceStatus::Purchased
+            && ResaleListing::find_active_for_ticket_instance(ticket.id, conn)?.is_some()
+        {
+            return Ok(RedeemResults::TicketInvalid);
+        }
+
         if ticket.status == TicketInstanceStatus::Purchased
             && ticket.redeem_key.is_some()
             && ticket.redeem_key.unwrap() == redeem_key
@@ -667,6 +781,10 @@ impl TicketInstance {
             .inner_join(wallets::table.on(ticket_instances::wallet_id.eq(wallets::id)))
             .inner_join(events::table.on(ticket_types::event_id.eq(events::id)))
             .left_join(venues::table.on(events::venue_id.eq(venues::id.nullable())))
+            .left_join(seats::table.on(ticket_instances::seat_id.eq(seats::id.nullable())))
+            .left_join(
+                seating_sections::table.on(seats::seating_section_id.eq(seating_sections::id)),
+            )
             .inner_join(users::table.on(sql(
                 "coalesce(orders.on_behalf_of_user_id, wallets.user_id) = users.id",
             )))
@@ -699,6 +817,9 @@ impl TicketInstance {
                 events::event_start,
                 events::venue_id,
                 venues::name.nullable(),
+                seating_sections::name.nullable(),
+                seats::row_name.nullable(),
+                seats::seat_number.nullable(),
             ))
             .first::<RedeemableTicket>(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
@@ -741,7 +862,7 @@ impl TicketInstance {
         Ok(())
     }
 
-    fn verify_tickets_belong_to_user(
+    pub(crate) fn verify_tickets_belong_to_user(
         user_id: Uuid,
         ticket_ids: &[Uuid],
         conn: &PgConnection,
@@ -786,6 +907,14 @@ impl TicketInstance {
         //Confirm that tickets are purchased and owned by user
         let (wallet_id, ticket_ids_and_updated_at) =
             TicketInstance::verify_tickets_belong_to_user(user_id, ticket_ids, conn)?;
+        for ticket_id in ticket_ids {
+            if ResaleListing::find_active_for_ticket_instance(*ticket_id, conn)?.is_some() {
+                return Err(DatabaseError::new(
+                    ErrorCode::BusinessProcessError,
+                    Some("Tickets listed for resale cannot be transferred".to_string()),
+                ));
+            }
+        }
 
         //Generate transfer_key and store keys and set transfer_expiry date
         let transfer_key = Uuid::new_v4();
@@ -931,9 +1060,64 @@ impl TicketInstance {
         Ok(tickets)
     }
 
+    /// Nullifies a sold ticket so it can no longer be redeemed, keeping its order item so the
+    /// refund can still be traced back to the purchase
+    pub fn nullify(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(self.id)))
+            .set((
+                ticket_instances::status.eq(TicketInstanceStatus::Nullified),
+                ticket_instances::redeem_key.eq(None::<String>),
+                ticket_instances::transfer_key.eq(None::<Uuid>),
+                ticket_instances::transfer_expiry_date.eq(None::<NaiveDateTime>),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not nullify ticket")?;
+
+        self.create_nullified_domain_event(current_user_id, conn)
+    }
+
+    /// Returns a ticket nullified while its purchase was in question to its owner
+    pub fn reinstate(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.status != TicketInstanceStatus::Nullified || self.order_item_id.is_none() {
+            return DatabaseError::business_process_error(
+                "Only nullified tickets that were purchased can be reinstated",
+            );
+        }
+
+        let key = generate_redeem_key(9);
+        diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(self.id)))
+            .set((
+                ticket_instances::status.eq(TicketInstanceStatus::Purchased),
+                ticket_instances::redeem_key.eq(&key),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not reinstate ticket")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketInstancePurchased,
+            "Ticket reinstated".to_string(),
+            Tables::TicketInstances,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "order_item_id": self.order_item_id, "redeem_key": key })),
+        )
+        .commit(conn)?;
+        Ok(())
+    }
+
     fn create_nullified_domain_event(
         &self,
-        user_id: Uuid,
+        user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         DomainEvent::create(
@@ -941,7 +1125,7 @@ impl TicketInstance {
             "Ticket nullified".to_string(),
             Tables::TicketInstances,
             Some(self.id),
-            Some(user_id),
+            user_id,
             None,
         )
         .commit(conn)?;
@@ -964,7 +1148,7 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Could not nullify tickets")?;
 
         for ticket_instance in &updated_ticket_instances {
-            ticket_instance.create_nullified_domain_event(user_id, conn)?;
+            ticket_instance.create_nullified_domain_event(Some(user_id), conn)?;
         }
         Ok(updated_ticket_instances)
     }
@@ -988,6 +1172,9 @@ pub struct DisplayTicket {
     pub status: TicketInstanceStatus,
     pub redeem_key: Option<String>,
     pub pending_transfer: bool,
+    pub section_name: Option<String>,
+    pub row_name: Option<String>,
+    pub seat_number: Option<String>,
 }
 
 #[derive(Queryable, QueryableByName)]
@@ -1018,6 +1205,12 @@ pub struct DisplayTicketIntermediary {
     pub event_start: Option<NaiveDateTime>,
     #[sql_type = "Bool"]
     pub pending_transfer: bool,
+    #[sql_type = "Nullable<Text>"]
+    pub section_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub row_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_number: Option<String>,
 }
 
 impl From<DisplayTicketIntermediary> for DisplayTicket {
@@ -1051,6 +1244,9 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             status: ticket_intermediary.status.clone(),
             pending_transfer: ticket_intermediary.pending_transfer,
             redeem_key,
+            section_name: ticket_intermediary.section_name.clone(),
+            row_name: ticket_intermediary.row_name.clone(),
+            seat_number: ticket_intermediary.seat_number.clone(),
         }
     }
 }
@@ -1111,7 +1307,7 @@ pub enum RedeemResults {
     TicketInvalid,
 }
 
-fn generate_redeem_key(len: u32) -> String {
+pub(crate) fn generate_redeem_key(len: u32) -> String {
     let hash_char_list = vec![
         '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J',
         'K', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
@@ -2,16 +2,19 @@ use chrono::NaiveDateTime;
 use diesel;
 use diesel::dsl::{self, select};
 use diesel::prelude::*;
-use diesel::sql_types::{Bool, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Bool, Nullable, Timestamp, Uuid as dUuid};
 use models::{TicketPricingStatus, TicketType};
 use schema::{order_items, ticket_pricing};
 use std::borrow::Cow;
+use std::cmp;
 use utils::errors::*;
 use uuid::Uuid;
 use validator::*;
 use validators::{self, *};
 
-sql_function!(fn ticket_pricing_no_overlapping_periods(id: dUuid, ticket_type_id: dUuid, start_date: Timestamp, end_date: Timestamp, is_box_office_only: Bool, is_default_status: Bool) -> Bool);
+sql_function!(fn ticket_pricing_no_overlapping_periods(id: dUuid, ticket_type_id: dUuid, start_date: Timestamp, end_date: Timestamp, is_box_office_only: Bool, is_default_status: Bool, is_quantity_tier: Bool) -> Bool);
+sql_function!(fn ticket_pricing_sold_quantity(id: dUuid) -> BigInt);
+sql_function!(fn current_ticket_pricing_id(ticket_type_id: dUuid, box_office_pricing: Bool) -> Nullable<dUuid>);
 
 #[derive(Clone, Identifiable, Associations, Queryable, PartialEq, Debug)]
 #[belongs_to(TicketType)]
@@ -27,6 +30,10 @@ pub struct TicketPricing {
     pub is_box_office_only: bool,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
+    /// Number of tickets sold at this price before the next tier applies, `None` for date only pricing
+    pub quantity_limit: Option<i64>,
+    /// Order in which quantity tiers within the same dates are sold through
+    pub rank: i32,
 }
 
 #[derive(AsChangeset, Clone, Default, Deserialize)]
@@ -37,6 +44,8 @@ pub struct TicketPricingEditableAttributes {
     pub start_date: Option<NaiveDateTime>,
     pub end_date: Option<NaiveDateTime>,
     pub is_box_office_only: Option<bool>,
+    pub quantity_limit: Option<Option<i64>>,
+    pub rank: Option<i32>,
 }
 
 impl TicketPricing {
@@ -57,6 +66,35 @@ impl TicketPricing {
             end_date,
             price_in_cents,
             is_box_office_only,
+            quantity_limit: None,
+            rank: 0,
+        }
+    }
+
+    /// Pricing that applies to the first `quantity_limit` tickets sold within its dates, once sold
+    /// through the tier with the next `rank` applies, followed by the regular date based pricing
+    pub fn create_tier(
+        ticket_type_id: Uuid,
+        name: String,
+        start_date: NaiveDateTime,
+        end_date: NaiveDateTime,
+        price_in_cents: i64,
+        quantity_limit: i64,
+        rank: i32,
+        is_box_office_only: bool,
+    ) -> NewTicketPricing {
+        NewTicketPricing {
+            quantity_limit: Some(quantity_limit),
+            rank,
+            ..TicketPricing::create(
+                ticket_type_id,
+                name,
+                start_date,
+                end_date,
+                price_in_cents,
+                is_box_office_only,
+                None,
+            )
         }
     }
 
@@ -72,6 +110,11 @@ impl TicketPricing {
                 attributes.end_date.unwrap_or(self.end_date),
             ),
         );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "ticket_pricing.quantity_limit",
+            validate_quantity_limit(attributes.quantity_limit.unwrap_or(self.quantity_limit)),
+        );
         Ok(validation_errors?)
     }
 
@@ -90,17 +133,21 @@ impl TicketPricing {
                 .to_db_error(ErrorCode::UpdateError, "Could not update ticket_pricing")
         } else {
             // Orders affected, create new ticket pricing and delete old
-            let new_ticket_pricing = TicketPricing::create(
-                self.ticket_type_id,
-                attributes.name.unwrap_or(self.name.clone()),
-                attributes.start_date.unwrap_or(self.start_date),
-                attributes.end_date.unwrap_or(self.end_date),
-                attributes.price_in_cents.unwrap(),
-                attributes
-                    .is_box_office_only
-                    .unwrap_or(self.is_box_office_only),
-                Some(self.status),
-            );
+            let new_ticket_pricing = NewTicketPricing {
+                quantity_limit: attributes.quantity_limit.unwrap_or(self.quantity_limit),
+                rank: attributes.rank.unwrap_or(self.rank),
+                ..TicketPricing::create(
+                    self.ticket_type_id,
+                    attributes.name.unwrap_or(self.name.clone()),
+                    attributes.start_date.unwrap_or(self.start_date),
+                    attributes.end_date.unwrap_or(self.end_date),
+                    attributes.price_in_cents.unwrap(),
+                    attributes
+                        .is_box_office_only
+                        .unwrap_or(self.is_box_office_only),
+                    Some(self.status),
+                )
+            };
             self.destroy(conn)?;
             new_ticket_pricing.commit(conn)
         }
@@ -147,6 +194,7 @@ impl TicketPricing {
         end_date: NaiveDateTime,
         is_box_office_only: bool,
         status: TicketPricingStatus,
+        quantity_limit: Option<i64>,
         conn: &PgConnection,
     ) -> Result<Result<(), ValidationError>, DatabaseError> {
         let is_default = status == TicketPricingStatus::Default;
@@ -157,6 +205,7 @@ impl TicketPricing {
             end_date,
             is_box_office_only,
             is_default,
+            quantity_limit.is_some(),
         ))
         .get_result::<bool>(conn)
         .to_db_error(
@@ -178,6 +227,44 @@ impl TicketPricing {
         Ok(Ok(()))
     }
 
+    pub fn has_quantity_tiers(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        select(dsl::exists(
+            ticket_pricing::table
+                .filter(ticket_pricing::ticket_type_id.eq(ticket_type_id))
+                .filter(ticket_pricing::status.eq(TicketPricingStatus::Published))
+                .filter(ticket_pricing::quantity_limit.is_not_null()),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check ticket type for quantity tiers",
+        )
+    }
+
+    /// Tickets sold or held in unexpired carts at this price
+    pub fn sold_quantity(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        select(ticket_pricing_sold_quantity(self.id))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load sold quantity for ticket pricing",
+            )
+    }
+
+    /// Tickets left in a quantity tier, `None` if the pricing is not limited by quantity
+    pub fn remaining_quantity(&self, conn: &PgConnection) -> Result<Option<i64>, DatabaseError> {
+        match self.quantity_limit {
+            Some(quantity_limit) => Ok(Some(cmp::max(
+                0,
+                quantity_limit - self.sold_quantity(conn)?,
+            ))),
+            None => Ok(None),
+        }
+    }
+
     fn affected_order_count(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
         order_items::table
             .filter(order_items::ticket_pricing_id.eq(self.id))
@@ -228,62 +315,57 @@ impl TicketPricing {
             .to_db_error(ErrorCode::QueryError, "Error loading ticket pricing")
     }
 
+    /// Pricing currently applied to a ticket type as chosen by the `current_ticket_pricing_id` SQL
+    /// function: box office only pricing when requested, then published quantity tiers that have
+    /// not sold through in rank order, then published date based pricing and finally the default.
+    /// Overlapping date based periods are an error as either could apply.
     pub fn get_current_ticket_pricing(
         ticket_type_id: Uuid,
         box_office_pricing: bool,
-        get_default_pricing: bool,
         conn: &PgConnection,
     ) -> Result<TicketPricing, DatabaseError> {
-        let mut ticket_pricing_status = TicketPricingStatus::Published;
-        if get_default_pricing {
-            ticket_pricing_status = TicketPricingStatus::Default
-        }
-
-        let mut query = ticket_pricing::table
-            .filter(ticket_pricing::ticket_type_id.eq(ticket_type_id))
-            .filter(ticket_pricing::status.eq(ticket_pricing_status))
-            .filter(ticket_pricing::start_date.le(dsl::now))
-            .filter(ticket_pricing::end_date.gt(dsl::now))
-            .into_boxed();
-
-        if box_office_pricing {
-            // Use is_box_office_only pricing, fall back to regular pricing if not set
-            query = query
-                .order(ticket_pricing::is_box_office_only.desc())
-                .limit(1);
-        } else {
-            query = query.filter(ticket_pricing::is_box_office_only.eq(false));
-        }
-
-        let mut price_points = query
-            .load(conn)
-            .to_db_error(ErrorCode::QueryError, "Could not load Ticket Pricing")?;
+        let ticket_pricing_id: Option<Uuid> = select(current_ticket_pricing_id(
+            ticket_type_id,
+            box_office_pricing,
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not load Ticket Pricing")?;
 
-        if price_points.len() > 1 {
-            return Err(DatabaseError::new(
-                ErrorCode::MultipleResultsWhenOneExpected,
-                Some("Expected a single ticket pricing period but multiple were found".to_string()),
-            ));
-        } else if price_points.len() == 0 {
-            if get_default_pricing == false {
-                return TicketPricing::get_current_ticket_pricing(
-                    ticket_type_id,
-                    box_office_pricing,
-                    true,
-                    &conn,
-                );
-            } else {
-                return Err(DatabaseError::new(
-                    ErrorCode::NoResults,
-                    Some("No ticket pricing found".to_string()),
-                ));
+        match ticket_pricing_id {
+            Some(id) => {
+                let ticket_pricing = TicketPricing::find(id, conn)?;
+                if !box_office_pricing
+                    && ticket_pricing.status == TicketPricingStatus::Published
+                    && ticket_pricing.quantity_limit.is_none()
+                {
+                    let overlapping_count: i64 = ticket_pricing::table
+                        .filter(ticket_pricing::ticket_type_id.eq(ticket_type_id))
+                        .filter(ticket_pricing::id.ne(id))
+                        .filter(ticket_pricing::status.eq(TicketPricingStatus::Published))
+                        .filter(ticket_pricing::is_box_office_only.eq(false))
+                        .filter(ticket_pricing::quantity_limit.is_null())
+                        .filter(ticket_pricing::start_date.le(dsl::now))
+                        .filter(ticket_pricing::end_date.gt(dsl::now))
+                        .count()
+                        .get_result(conn)
+                        .to_db_error(ErrorCode::QueryError, "Could not load Ticket Pricing")?;
+                    if overlapping_count > 0 {
+                        return Err(DatabaseError::new(
+                            ErrorCode::MultipleResultsWhenOneExpected,
+                            Some(
+                                "Expected a single ticket pricing period but multiple were found"
+                                    .to_string(),
+                            ),
+                        ));
+                    }
+                }
+                Ok(ticket_pricing)
             }
+            None => Err(DatabaseError::new(
+                ErrorCode::NoResults,
+                Some("No ticket pricing found".to_string()),
+            )),
         }
-
-        price_points.pop().ok_or(DatabaseError::new(
-            ErrorCode::NoResults,
-            Some("No ticket pricing found".to_string()),
-        ))
     }
 }
 
@@ -297,6 +379,8 @@ pub struct NewTicketPricing {
     is_box_office_only: bool,
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
+    quantity_limit: Option<i64>,
+    rank: i32,
 }
 
 impl NewTicketPricing {
@@ -318,6 +402,12 @@ impl NewTicketPricing {
             ),
         );
 
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "ticket_pricing.quantity_limit",
+            validate_quantity_limit(self.quantity_limit),
+        );
+
         Ok(validation_errors?)
     }
 
@@ -329,3 +419,15 @@ impl NewTicketPricing {
             .to_db_error(ErrorCode::InsertError, "Could not create ticket pricing")
     }
 }
+
+fn validate_quantity_limit(quantity_limit: Option<i64>) -> Result<(), ValidationError> {
+    match quantity_limit {
+        Some(quantity_limit) => validators::validate_greater_than(
+            quantity_limit,
+            1,
+            "number_must_be_positive",
+            "Tier quantity must be positive",
+        ),
+        None => Ok(()),
+    }
+}
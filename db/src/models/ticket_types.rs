@@ -34,6 +34,7 @@ pub struct TicketType {
     pub cancelled_at: Option<NaiveDateTime>,
     pub sold_out_behavior: SoldOutBehavior,
     pub is_private: bool,
+    pub price_zone_id: Option<Uuid>,
 }
 
 impl PartialOrd for TicketType {
@@ -161,6 +162,56 @@ impl TicketType {
         Ok(result)
     }
 
+    /// Binds this ticket type to a price zone, pairing the seats in that zone with the ticket
+    /// type's unsold inventory. Passing `None` turns the ticket type back into general admission.
+    pub fn set_price_zone(
+        &self,
+        price_zone_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TicketType, DatabaseError> {
+        if let Some(price_zone_id) = price_zone_id {
+            let stage = PriceZone::find(price_zone_id, conn)?.stage(conn)?;
+            let event = Event::find(self.event_id, conn)?;
+            if event.venue_id != Some(stage.venue_id) {
+                return DatabaseError::validation_error(
+                    "price_zone_id",
+                    "Price zone does not belong to the event's venue",
+                );
+            }
+        }
+
+        let result: TicketType = diesel::update(self)
+            .set((
+                ticket_types::price_zone_id.eq(price_zone_id),
+                ticket_types::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update ticket_types")?;
+
+        TicketInstance::clear_seats(self.id, conn)?;
+        if let Some(price_zone_id) = price_zone_id {
+            TicketInstance::assign_seats(self.id, self.event_id, price_zone_id, conn)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Pairs newly issued tickets with the remaining seats of the price zone without moving the
+    /// seats already assigned to the ticket type's inventory.
+    pub fn assign_seats_to_new_tickets(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if let Some(price_zone_id) = self.price_zone_id {
+            TicketInstance::assign_seats(self.id, self.event_id, price_zone_id, conn)?;
+        }
+        Ok(())
+    }
+
+    pub fn seats(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplaySeatAvailability>, DatabaseError> {
+        Seat::find_for_ticket_type_for_display(self.id, conn)
+    }
+
     pub fn find_for_code(
         code_id: Uuid,
         conn: &PgConnection,
@@ -215,6 +266,7 @@ impl TicketType {
                     ticket_pricing.end_date,
                     ticket_pricing.is_box_office_only,
                     ticket_pricing.status,
+                    ticket_pricing.quantity_limit,
                     conn,
                 )?,
             );
@@ -356,7 +408,7 @@ impl TicketType {
         box_office_pricing: bool,
         conn: &PgConnection,
     ) -> Result<TicketPricing, DatabaseError> {
-        TicketPricing::get_current_ticket_pricing(self.id, box_office_pricing, false, conn)
+        TicketPricing::get_current_ticket_pricing(self.id, box_office_pricing, conn)
     }
 
     pub fn ticket_pricing(&self, conn: &PgConnection) -> Result<Vec<TicketPricing>, DatabaseError> {
@@ -431,6 +483,30 @@ impl TicketType {
         )
         .commit(conn)
     }
+
+    pub fn add_ticket_pricing_tier(
+        &self,
+        name: String,
+        start_date: NaiveDateTime,
+        end_date: NaiveDateTime,
+        price_in_cents: i64,
+        quantity_limit: i64,
+        rank: i32,
+        is_box_office_only: bool,
+        conn: &PgConnection,
+    ) -> Result<TicketPricing, DatabaseError> {
+        TicketPricing::create_tier(
+            self.id,
+            name,
+            start_date,
+            end_date,
+            price_in_cents,
+            quantity_limit,
+            rank,
+            is_box_office_only,
+        )
+        .commit(conn)
+    }
 }
 
 #[derive(Insertable)]
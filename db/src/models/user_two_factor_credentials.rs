@@ -0,0 +1,305 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use hex;
+use models::*;
+use ring::digest;
+use schema::user_two_factor_credentials;
+use utils::encryption::*;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use utils::totp;
+use uuid::Uuid;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+/// Invalid codes allowed before the credential is locked
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+const LOCKOUT_MINUTES: i64 = 15;
+
+/// A user's authenticator app secret, two factor authentication is only enforced once the
+/// user has confirmed a code from the app and `enabled_at` is set
+#[derive(Clone, Associations, Identifiable, Queryable, PartialEq, Debug)]
+#[belongs_to(User)]
+#[table_name = "user_two_factor_credentials"]
+pub struct UserTwoFactorCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Encrypted with the API keys encryption key
+    pub secret: String,
+    /// SHA-256 hashes of the unused recovery codes
+    pub recovery_code_hashes: Vec<String>,
+    pub enabled_at: Option<NaiveDateTime>,
+    /// Time step of the last accepted code, codes can only be used once
+    pub last_used_step: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Invalid codes entered since the last valid code or lockout
+    pub failed_attempts: i32,
+    pub locked_until: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_two_factor_credentials"]
+struct NewUserTwoFactorCredential {
+    user_id: Uuid,
+    secret: String,
+}
+
+impl UserTwoFactorCredential {
+    /// Starts enrolment with a new secret, replacing any enrolment the user did not confirm.
+    /// The plain secret is returned to be shown to the user once
+    pub fn begin_enrolment(
+        user_id: Uuid,
+        encryption_key: &String,
+        conn: &PgConnection,
+    ) -> Result<String, DatabaseError> {
+        if let Some(existing) = UserTwoFactorCredential::find_by_user_id(user_id, conn)? {
+            if existing.is_enabled() {
+                return DatabaseError::business_process_error(
+                    "Two factor authentication is already enabled",
+                );
+            }
+            existing.destroy(conn)?;
+        }
+
+        let secret = totp::generate_secret();
+        diesel::insert_into(user_two_factor_credentials::table)
+            .values(NewUserTwoFactorCredential {
+                user_id,
+                secret: encrypt(&secret, encryption_key)?,
+            })
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create two factor credential",
+            )?;
+        Ok(secret)
+    }
+
+    pub fn find_by_user_id(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<UserTwoFactorCredential>, DatabaseError> {
+        user_two_factor_credentials::table
+            .filter(user_two_factor_credentials::user_id.eq(user_id))
+            .first(conn)
+            .optional()
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load two factor credential",
+            )
+    }
+
+    pub fn find_enabled_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<UserTwoFactorCredential>, DatabaseError> {
+        Ok(UserTwoFactorCredential::find_by_user_id(user_id, conn)?.filter(|c| c.is_enabled()))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// Enables two factor authentication once the user proves their app generates valid codes,
+    /// returning the recovery codes to be shown to the user once
+    pub fn confirm(
+        &self,
+        code: &str,
+        encryption_key: &String,
+        conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        if self.is_enabled() {
+            return DatabaseError::business_process_error(
+                "Two factor authentication is already enabled",
+            );
+        }
+        if !self.verify_code(code, encryption_key, conn)? {
+            return DatabaseError::validation_error("code", "Code is invalid");
+        }
+
+        let (codes, hashes) = UserTwoFactorCredential::generate_recovery_codes();
+        diesel::update(self)
+            .set((
+                user_two_factor_credentials::enabled_at.eq(dsl::now.nullable()),
+                user_two_factor_credentials::recovery_code_hashes.eq(hashes),
+                user_two_factor_credentials::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not enable two factor authentication",
+            )?;
+        Ok(codes)
+    }
+
+    /// Replaces all recovery codes, any unused codes stop working
+    pub fn regenerate_recovery_codes(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let (codes, hashes) = UserTwoFactorCredential::generate_recovery_codes();
+        diesel::update(self)
+            .set((
+                user_two_factor_credentials::recovery_code_hashes.eq(hashes),
+                user_two_factor_credentials::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update recovery codes")?;
+        Ok(codes)
+    }
+
+    /// No codes are accepted while locked after too many invalid codes
+    pub fn is_locked(&self) -> bool {
+        self.locked_until
+            .map(|locked_until| locked_until > Utc::now().naive_utc())
+            .unwrap_or(false)
+    }
+
+    /// Accepts either a code from the authenticator app or an unused recovery code. Invalid
+    /// codes are counted and the credential is locked once too many have been entered
+    pub fn verify(
+        &self,
+        code: &str,
+        encryption_key: &String,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        if self.is_locked() {
+            return Ok(false);
+        }
+        if self.verify_code(code, encryption_key, conn)? || self.use_recovery_code(code, conn)? {
+            diesel::update(self)
+                .filter(user_two_factor_credentials::failed_attempts.gt(0))
+                .set(user_two_factor_credentials::failed_attempts.eq(0))
+                .execute(conn)
+                .to_db_error(
+                    ErrorCode::UpdateError,
+                    "Could not update two factor credential",
+                )?;
+            return Ok(true);
+        }
+
+        self.record_failed_attempt(conn)?;
+        Ok(false)
+    }
+
+    fn record_failed_attempt(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let credential: UserTwoFactorCredential = diesel::update(self)
+            .set((
+                user_two_factor_credentials::failed_attempts
+                    .eq(user_two_factor_credentials::failed_attempts + 1),
+                user_two_factor_credentials::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update two factor credential",
+            )?;
+        if credential.failed_attempts >= MAX_FAILED_ATTEMPTS {
+            diesel::update(self)
+                .set((
+                    user_two_factor_credentials::failed_attempts.eq(0),
+                    user_two_factor_credentials::locked_until
+                        .eq(Utc::now().naive_utc() + Duration::minutes(LOCKOUT_MINUTES)),
+                ))
+                .execute(conn)
+                .to_db_error(
+                    ErrorCode::UpdateError,
+                    "Could not lock two factor credential",
+                )?;
+        }
+        Ok(())
+    }
+
+    /// Checks a code from the authenticator app, a code is rejected if it or a later one has
+    /// already been accepted
+    pub fn verify_code(
+        &self,
+        code: &str,
+        encryption_key: &String,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let secret = decrypt(&self.secret, encryption_key)?;
+        let step = match totp::verify(&secret, code, Utc::now().timestamp()) {
+            Some(step) => step,
+            None => return Ok(false),
+        };
+
+        let updated = diesel::update(self)
+            .filter(
+                user_two_factor_credentials::last_used_step
+                    .is_null()
+                    .or(user_two_factor_credentials::last_used_step.lt(step)),
+            )
+            .set((
+                user_two_factor_credentials::last_used_step.eq(step),
+                user_two_factor_credentials::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update two factor credential",
+            )?;
+        Ok(updated == 1)
+    }
+
+    /// Consumes a recovery code, each code can only be used once
+    pub fn use_recovery_code(
+        &self,
+        code: &str,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        if !self.is_enabled() {
+            return Ok(false);
+        }
+        let hash = UserTwoFactorCredential::hash_recovery_code(code);
+        let remaining: Vec<String> = self
+            .recovery_code_hashes
+            .iter()
+            .filter(|h| **h != hash)
+            .cloned()
+            .collect();
+        if remaining.len() == self.recovery_code_hashes.len() {
+            return Ok(false);
+        }
+
+        let updated = diesel::update(self)
+            .filter(user_two_factor_credentials::recovery_code_hashes.contains(vec![hash]))
+            .set((
+                user_two_factor_credentials::recovery_code_hashes.eq(remaining),
+                user_two_factor_credentials::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update recovery codes")?;
+        Ok(updated == 1)
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self).execute(conn).to_db_error(
+            ErrorCode::DeleteError,
+            "Could not delete two factor credential",
+        )
+    }
+
+    fn generate_recovery_codes() -> (Vec<String>, Vec<String>) {
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| random_alpha_string(RECOVERY_CODE_LENGTH).to_lowercase())
+            .collect();
+        let hashes = codes
+            .iter()
+            .map(|c| UserTwoFactorCredential::hash_recovery_code(c))
+            .collect();
+        (codes, hashes)
+    }
+
+    fn hash_recovery_code(code: &str) -> String {
+        let normalized: String = code
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        hex::encode(digest::digest(&digest::SHA256, normalized.as_bytes()).as_ref())
+    }
+}
@@ -381,6 +381,14 @@ impl User {
         )
     }
 
+    pub fn find_by_ids(user_ids: &[Uuid], conn: &PgConnection) -> Result<Vec<User>, DatabaseError> {
+        users::table
+            .filter(users::id.eq_any(user_ids))
+            .order_by(users::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading users")
+    }
+
     pub fn find_by_email(email: &str, conn: &PgConnection) -> Result<User, DatabaseError> {
         let lower_email = email.to_lowercase();
         DatabaseError::wrap(
@@ -501,6 +509,10 @@ impl User {
         Ok(events_by_organization)
     }
 
+    pub fn two_factor_enabled(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        Ok(UserTwoFactorCredential::find_enabled_for_user(self.id, conn)?.is_some())
+    }
+
     pub fn get_roles_by_organization(
         &self,
         conn: &PgConnection,
@@ -579,7 +591,12 @@ impl User {
                 Some("No payment method found for user".to_string()),
             ))
         } else {
-            Ok(payment_methods.remove(0))
+            // Prefer the default when the user has saved several cards with the provider
+            let index = payment_methods
+                .iter()
+                .position(|p| p.is_default)
+                .unwrap_or(0);
+            Ok(payment_methods.remove(index))
         }
     }
 
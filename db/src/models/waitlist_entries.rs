@@ -0,0 +1,445 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use log::Level::{Debug, Info};
+use models::*;
+use schema::{assets, domain_actions, order_items, orders, ticket_instances, waitlist_entries};
+use std::cmp;
+use time::Duration;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// How long a waitlisted user has to check out tickets that have been offered to them
+pub const WAITLIST_OFFER_EXPIRY_TIME_HOURS: i64 = 24;
+
+#[derive(
+    Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug,
+)]
+#[belongs_to(TicketType)]
+#[belongs_to(User)]
+#[table_name = "waitlist_entries"]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i32,
+    pub status: WaitlistEntryStatus,
+    pub order_id: Option<Uuid>,
+    pub offer_expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "waitlist_entries"]
+pub struct NewWaitlistEntry {
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i32,
+}
+
+impl NewWaitlistEntry {
+    pub fn commit(&self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.quantity <= 0 {
+            return DatabaseError::validation_error("quantity", "Quantity must be at least 1");
+        }
+
+        let ticket_type = TicketType::find(self.ticket_type_id, conn)?;
+        if ticket_type.sold_out_behavior != SoldOutBehavior::ShowSoldOut
+            || ticket_type.valid_available_ticket_count(conn)? > 0
+        {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Waitlist is only available for sold out ticket types",
+            );
+        }
+
+        if WaitlistEntry::find_active_for_user(self.ticket_type_id, self.user_id, conn)?.is_some() {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "User is already on the waitlist for this ticket type",
+            );
+        }
+
+        let entry: WaitlistEntry = diesel::insert_into(waitlist_entries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not join waitlist")?;
+
+        // Tickets held in carts return to inventory when the carts expire
+        WaitlistEntry::schedule_revisit(self.ticket_type_id, conn)?;
+
+        Ok(entry)
+    }
+}
+
+impl WaitlistEntry {
+    pub fn create(ticket_type_id: Uuid, user_id: Uuid, quantity: i32) -> NewWaitlistEntry {
+        NewWaitlistEntry {
+            ticket_type_id,
+            user_id,
+            quantity,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        waitlist_entries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entry")
+    }
+
+    pub fn find_active_for_user(
+        ticket_type_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::user_id.eq(user_id))
+            .filter(waitlist_entries::status.eq_any(vec![
+                WaitlistEntryStatus::Waiting,
+                WaitlistEntryStatus::Offered,
+            ]))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entry")
+    }
+
+    pub fn find_by_ticket_type_id(
+        ticket_type_id: Uuid,
+        status: WaitlistEntryStatus,
+        conn: &PgConnection,
+    ) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::status.eq(status))
+            .order_by(waitlist_entries::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entries")
+    }
+
+    pub fn has_offer_for_order(order_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        diesel::select(dsl::exists(
+            waitlist_entries::table
+                .filter(waitlist_entries::order_id.eq(order_id))
+                .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Offered)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not load waitlist entries")
+    }
+
+    /// Leaves the waitlist. Tickets reserved for an outstanding offer are released from the
+    /// user's cart and offered to the next person waiting.
+    pub fn cancel(&self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        let entry = self.update_status(WaitlistEntryStatus::Cancelled, conn)?;
+        if self.status == WaitlistEntryStatus::Offered {
+            self.release_offered_tickets(conn)?;
+            WaitlistEntry::queue_offers(self.ticket_type_id, conn)?;
+        }
+        Ok(entry)
+    }
+
+    fn release_offered_tickets(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut cart = match self.order_id {
+            Some(order_id) => Order::find(order_id, conn)?,
+            None => return Ok(()),
+        };
+        // Reservations on an expired or completed cart have already been released or purchased
+        if cart.status != OrderStatus::Draft
+            || cart
+                .expires_at
+                .map(|e| e < Utc::now().naive_utc())
+                .unwrap_or(true)
+        {
+            return Ok(());
+        }
+
+        let quantity_in_cart = WaitlistEntry::quantity_in_cart(&cart, self.ticket_type_id, conn)?;
+        cart.update_quantities(
+            self.user_id,
+            &[UpdateOrderItem {
+                ticket_type_id: self.ticket_type_id,
+                quantity: cmp::max(quantity_in_cart - self.quantity as i64, 0) as u32,
+                redemption_code: None,
+                seat_ids: None,
+            }],
+            false,
+            false,
+            conn,
+        )
+    }
+
+    fn quantity_in_cart(
+        cart: &Order,
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        Ok(cart
+            .items(conn)?
+            .iter()
+            .filter(|i| {
+                i.item_type == OrderItemTypes::Tickets
+                    && i.ticket_type_id == Some(ticket_type_id)
+                    && i.hold_id.is_none()
+                    && i.code_id.is_none()
+            })
+            .map(|i| i.quantity)
+            .sum())
+    }
+
+    fn update_status(
+        &self,
+        status: WaitlistEntryStatus,
+        conn: &PgConnection,
+    ) -> Result<WaitlistEntry, DatabaseError> {
+        diesel::update(self)
+            .set((
+                waitlist_entries::status.eq(status),
+                waitlist_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")
+    }
+
+    /// Queues the waitlist of a ticket type for processing when tickets are returned to inventory.
+    /// Nothing is queued if no one is waiting or processing is already due. The revisit scheduled
+    /// for when outstanding offers lapse does not count as it is not due yet.
+    pub fn queue_offers(ticket_type_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let waiting_count: i64 = waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting))
+            .select(dsl::count(waitlist_entries::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count waitlist entries")?;
+        let due_count: i64 = domain_actions::table
+            .filter(domain_actions::domain_action_type.eq(DomainActionTypes::ProcessWaitlist))
+            .filter(domain_actions::status.eq(DomainActionStatus::Pending))
+            .filter(domain_actions::scheduled_at.le(dsl::now))
+            .filter(domain_actions::expires_at.gt(dsl::now))
+            .filter(domain_actions::main_table.eq(Tables::TicketTypes.to_string()))
+            .filter(domain_actions::main_table_id.eq(ticket_type_id))
+            .select(dsl::count(domain_actions::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading domain actions")?;
+        if waiting_count == 0 || due_count > 0 {
+            return Ok(());
+        }
+
+        WaitlistEntry::schedule_processing(ticket_type_id, Utc::now().naive_utc(), conn)
+    }
+
+    fn schedule_processing(
+        ticket_type_id: Uuid,
+        scheduled_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        DomainAction::create(
+            None,
+            DomainActionTypes::ProcessWaitlist,
+            None,
+            json!({ "ticket_type_id": ticket_type_id }),
+            Some(Tables::TicketTypes.to_string()),
+            Some(ticket_type_id),
+            scheduled_at,
+            scheduled_at + Duration::days(1),
+            3,
+        )
+        .commit(conn)?;
+        Ok(())
+    }
+
+    /// Closes out offers that have been purchased or have lapsed, then reserves the available
+    /// inventory for waiting users in the order they joined. Returns the entries that were offered
+    /// tickets so that they can be notified.
+    pub fn make_offers(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        for entry in WaitlistEntry::find_by_ticket_type_id(
+            ticket_type_id,
+            WaitlistEntryStatus::Offered,
+            conn,
+        )? {
+            if entry.offer_expires_at.map(|e| e < now).unwrap_or(true) {
+                jlog!(Debug, "Waitlist offer expired", {"waitlist_entry_id": entry.id});
+                entry.update_status(WaitlistEntryStatus::Expired, conn)?;
+            }
+        }
+
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let offer_expires_at = now + Duration::hours(WAITLIST_OFFER_EXPIRY_TIME_HOURS);
+        let mut offered_entries = Vec::new();
+        for entry in WaitlistEntry::find_by_ticket_type_id(
+            ticket_type_id,
+            WaitlistEntryStatus::Waiting,
+            conn,
+        )? {
+            // Honour the order of the queue; later entries cannot jump ahead of a larger request
+            if ticket_type.valid_available_ticket_count(conn)? < entry.quantity as u32 {
+                break;
+            }
+
+            // Each offer runs in a savepoint so a failed reservation does not undo earlier offers
+            let mut offer_error = None;
+            let result = conn.transaction::<_, diesel::result::Error, _>(|| {
+                entry
+                    .offer(&ticket_type, offer_expires_at, conn)
+                    .map_err(|e| {
+                        offer_error = Some(e);
+                        diesel::result::Error::RollbackTransaction
+                    })
+            });
+            match result {
+                Ok(offered_entry) => offered_entries.push(offered_entry),
+                Err(e) => {
+                    let error = offer_error.map(|e| e.to_string()).unwrap_or(e.to_string());
+                    jlog!(Info, "Could not make waitlist offer", {"waitlist_entry_id": entry.id, "error": error});
+                    entry.update_status(WaitlistEntryStatus::Expired, conn)?;
+                }
+            }
+        }
+
+        WaitlistEntry::schedule_revisit(ticket_type_id, conn)?;
+
+        Ok(offered_entries)
+    }
+
+    /// Lapsed cart reservations and offers return tickets to inventory without anything being
+    /// released, so the queue is revisited when the next one of the ticket type lapses. Nothing is
+    /// scheduled if no one is waiting on the queue or a revisit is already due by then.
+    fn schedule_revisit(ticket_type_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let outstanding_count: i64 = waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::status.eq_any(vec![
+                WaitlistEntryStatus::Waiting,
+                WaitlistEntryStatus::Offered,
+            ]))
+            .select(dsl::count(waitlist_entries::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count waitlist entries")?;
+        if outstanding_count == 0 {
+            return Ok(());
+        }
+
+        let next_lapse_at: Option<NaiveDateTime> = ticket_instances::table
+            .inner_join(assets::table)
+            .filter(assets::ticket_type_id.eq(ticket_type_id))
+            .filter(ticket_instances::status.eq(TicketInstanceStatus::Reserved))
+            .filter(ticket_instances::hold_id.is_null())
+            .filter(ticket_instances::reserved_until.gt(dsl::now.nullable()))
+            .select(dsl::min(ticket_instances::reserved_until))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load reserved tickets")?;
+        let next_lapse_at = match next_lapse_at {
+            Some(next_lapse_at) => next_lapse_at,
+            None => return Ok(()),
+        };
+
+        let scheduled_count: i64 = domain_actions::table
+            .filter(domain_actions::domain_action_type.eq(DomainActionTypes::ProcessWaitlist))
+            .filter(domain_actions::status.eq(DomainActionStatus::Pending))
+            .filter(domain_actions::scheduled_at.gt(dsl::now))
+            .filter(domain_actions::scheduled_at.le(next_lapse_at))
+            .filter(domain_actions::main_table.eq(Tables::TicketTypes.to_string()))
+            .filter(domain_actions::main_table_id.eq(ticket_type_id))
+            .select(dsl::count(domain_actions::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading domain actions")?;
+        if scheduled_count > 0 {
+            return Ok(());
+        }
+
+        WaitlistEntry::schedule_processing(ticket_type_id, next_lapse_at, conn)
+    }
+
+    fn offer(
+        &self,
+        ticket_type: &TicketType,
+        offer_expires_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<WaitlistEntry, DatabaseError> {
+        let user = User::find(self.user_id, conn)?;
+        let mut cart = Order::find_or_create_cart(&user, conn)?;
+        // The offered tickets are added alongside whatever the user already has in their cart and
+        // held until the offer lapses, the cart is kept alive as long
+        cart.add_tickets(
+            user.id,
+            ticket_type,
+            self.quantity as u32,
+            offer_expires_at,
+            conn,
+        )?;
+        diesel::update(
+            orders::table
+                .filter(orders::id.eq(cart.id))
+                .filter(orders::expires_at.lt(offer_expires_at)),
+        )
+        .set((
+            orders::expires_at.eq(offer_expires_at),
+            orders::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update expiry time")?;
+
+        let entry: WaitlistEntry = diesel::update(self)
+            .set((
+                waitlist_entries::status.eq(WaitlistEntryStatus::Offered),
+                waitlist_entries::order_id.eq(cart.id),
+                waitlist_entries::offer_expires_at.eq(offer_expires_at),
+                waitlist_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistTicketsOffered,
+            "Waitlisted tickets offered".into(),
+            Tables::WaitlistEntries,
+            Some(entry.id),
+            None,
+            Some(json!({
+                "ticket_type_id": ticket_type.id,
+                "order_id": cart.id,
+                "quantity": entry.quantity,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(entry)
+    }
+
+    /// Marks offers fulfilled by the given order as purchased
+    pub fn mark_purchased_for_order(
+        order_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let ticket_type_ids: Vec<Option<Uuid>> = order_items::table
+            .filter(order_items::order_id.eq(order_id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tickets))
+            .select(order_items::ticket_type_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load order items")?;
+
+        diesel::update(
+            waitlist_entries::table
+                .filter(waitlist_entries::order_id.eq(order_id))
+                .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Offered))
+                .filter(
+                    waitlist_entries::ticket_type_id
+                        .nullable()
+                        .eq_any(ticket_type_ids),
+                ),
+        )
+        .set((
+            waitlist_entries::status.eq(WaitlistEntryStatus::Purchased),
+            waitlist_entries::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entries")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Text, Uuid as dUuid};
+use hex;
+use models::*;
+use ring::{digest, hmac};
+use schema::{domain_actions, domain_events, webhook_subscriptions};
+use serde_json;
+use time::Duration;
+use utils::encryption::*;
+use utils::errors::*;
+use utils::network;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Failed deliveries are retried with backoff until this many attempts have been made
+const WEBHOOK_DELIVERY_MAX_ATTEMPTS: i64 = 8;
+const WEBHOOK_DELIVERY_EXPIRY_DAYS: i64 = 3;
+
+#[derive(Clone, Associations, Identifiable, Queryable, PartialEq, Debug)]
+#[belongs_to(Organization)]
+#[table_name = "webhook_subscriptions"]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub url: String,
+    /// Encrypted with the API keys encryption key, the plain secret is only shown on creation
+    pub secret: String,
+    pub event_types: Vec<DomainEventTypes>,
+    pub created_by: Uuid,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone, Validate)]
+#[table_name = "webhook_subscriptions"]
+pub struct NewWebhookSubscription {
+    pub organization_id: Uuid,
+    #[validate(url(message = "Webhook URL is invalid"))]
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<DomainEventTypes>,
+    pub created_by: Uuid,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct DisplayWebhookSubscription {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub url: String,
+    pub event_types: Vec<DomainEventTypes>,
+    pub created_by: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<WebhookSubscription> for DisplayWebhookSubscription {
+    fn from(webhook_subscription: WebhookSubscription) -> Self {
+        DisplayWebhookSubscription {
+            id: webhook_subscription.id,
+            organization_id: webhook_subscription.organization_id,
+            url: webhook_subscription.url,
+            event_types: webhook_subscription.event_types,
+            created_by: webhook_subscription.created_by,
+            created_at: webhook_subscription.created_at,
+            updated_at: webhook_subscription.updated_at,
+        }
+    }
+}
+
+#[derive(Queryable, Serialize, Deserialize, PartialEq, Debug)]
+pub struct DisplayWebhookDelivery {
+    pub id: Uuid,
+    pub domain_event_id: Uuid,
+    pub event_type: DomainEventTypes,
+    pub status: DomainActionStatus,
+    pub attempt_count: i64,
+    pub max_attempt_count: i64,
+    pub last_failure_reason: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl NewWebhookSubscription {
+    pub fn commit(
+        &self,
+        encryption_key: &String,
+        conn: &PgConnection,
+    ) -> Result<WebhookSubscription, DatabaseError> {
+        self.validate()?;
+        if self.event_types.is_empty() {
+            return DatabaseError::validation_error(
+                "event_types",
+                "At least one event type is required",
+            );
+        }
+        // Deliveries are made from inside our network, internal hosts cannot be subscribed
+        if let Err(message) = network::validate_public_https_url(&self.url) {
+            return DatabaseError::validation_error("url", message);
+        }
+
+        let mut new_subscription = self.clone();
+        new_subscription.secret = encrypt(&self.secret, encryption_key)?;
+        diesel::insert_into(webhook_subscriptions::table)
+            .values(new_subscription)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create webhook subscription",
+            )
+    }
+}
+
+impl WebhookSubscription {
+    pub fn create(
+        organization_id: Uuid,
+        url: String,
+        event_types: Vec<DomainEventTypes>,
+        created_by: Uuid,
+    ) -> NewWebhookSubscription {
+        NewWebhookSubscription {
+            organization_id,
+            url,
+            secret: random_alpha_string(32),
+            event_types,
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WebhookSubscription, DatabaseError> {
+        webhook_subscriptions::table
+            .filter(webhook_subscriptions::id.eq(id))
+            .filter(webhook_subscriptions::deleted_at.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook subscription")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<WebhookSubscription>, DatabaseError> {
+        webhook_subscriptions::table
+            .filter(webhook_subscriptions::organization_id.eq(organization_id))
+            .filter(webhook_subscriptions::deleted_at.is_null())
+            .order_by(webhook_subscriptions::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load webhook subscriptions",
+            )
+    }
+
+    /// Removes the subscription and cancels any deliveries still waiting to be sent
+    pub fn destroy(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((
+                webhook_subscriptions::deleted_at.eq(dsl::now.nullable()),
+                webhook_subscriptions::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not delete webhook subscription",
+            )?;
+
+        diesel::update(
+            domain_actions::table
+                .filter(domain_actions::domain_action_type.eq(DomainActionTypes::SendWebhook))
+                .filter(domain_actions::main_table.eq(Tables::WebhookSubscriptions.to_string()))
+                .filter(domain_actions::main_table_id.eq(self.id))
+                .filter(domain_actions::status.eq(DomainActionStatus::Pending)),
+        )
+        .set((
+            domain_actions::status.eq(DomainActionStatus::Cancelled),
+            domain_actions::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not cancel webhook deliveries",
+        )?;
+        Ok(())
+    }
+
+    /// Queues a delivery for every subscription interested in this event. Events from before a
+    /// subscription was created are not delivered to it, so historical events are not backfilled.
+    pub fn queue_deliveries(
+        domain_event: &DomainEvent,
+        conn: &PgConnection,
+    ) -> Result<Vec<DomainAction>, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "dUuid"]
+            organization_id: Uuid,
+        }
+
+        let main_id = match domain_event.main_id {
+            Some(main_id) => main_id,
+            None => return Ok(vec![]),
+        };
+        let organization_ids: Vec<Uuid> = diesel::sql_query(include_str!(
+            "../queries/organization_ids_for_domain_event.sql"
+        ))
+        .bind::<Text, _>(domain_event.main_table.to_string())
+        .bind::<dUuid, _>(main_id)
+        .load::<R>(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not load organizations for domain event",
+        )?
+        .into_iter()
+        .map(|r| r.organization_id)
+        .collect();
+        if organization_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let subscriptions: Vec<WebhookSubscription> = webhook_subscriptions::table
+            .filter(webhook_subscriptions::organization_id.eq_any(organization_ids))
+            .filter(webhook_subscriptions::deleted_at.is_null())
+            .filter(webhook_subscriptions::created_at.le(domain_event.created_at))
+            .filter(webhook_subscriptions::event_types.contains(vec![domain_event.event_type]))
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load webhook subscriptions",
+            )?;
+
+        let now = Utc::now().naive_utc();
+        let mut actions = vec![];
+        for subscription in subscriptions {
+            actions.push(
+                DomainAction::create(
+                    Some(domain_event.id),
+                    DomainActionTypes::SendWebhook,
+                    None,
+                    json!({ "domain_event_id": domain_event.id }),
+                    Some(Tables::WebhookSubscriptions.to_string()),
+                    Some(subscription.id),
+                    now,
+                    now + Duration::days(WEBHOOK_DELIVERY_EXPIRY_DAYS),
+                    WEBHOOK_DELIVERY_MAX_ATTEMPTS,
+                )
+                .commit(conn)?,
+            );
+        }
+        Ok(actions)
+    }
+
+    /// The body sent to the subscriber for a domain event
+    pub fn payload_for(&self, domain_event: &DomainEvent) -> serde_json::Value {
+        json!({
+            "id": domain_event.id,
+            "event_type": domain_event.event_type,
+            "display_text": domain_event.display_text,
+            "main_table": domain_event.main_table,
+            "main_id": domain_event.main_id,
+            "event_data": domain_event.event_data,
+            "created_at": domain_event.created_at,
+        })
+    }
+
+    /// Hex encoded HMAC-SHA256 of the body using the subscription secret, allowing subscribers
+    /// to verify that deliveries originated from Big Neon
+    pub fn sign(&self, body: &str, encryption_key: &String) -> Result<String, DatabaseError> {
+        let secret = decrypt(&self.secret, encryption_key)?;
+        let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+        Ok(hex::encode(hmac::sign(&key, body.as_bytes()).as_ref()))
+    }
+
+    pub fn deliveries(
+        &self,
+        limit: u32,
+        page: u32,
+        conn: &PgConnection,
+    ) -> Result<(Vec<DisplayWebhookDelivery>, i64), DatabaseError> {
+        let query = domain_actions::table
+            .inner_join(domain_events::table)
+            .filter(domain_actions::domain_action_type.eq(DomainActionTypes::SendWebhook))
+            .filter(domain_actions::main_table.eq(Tables::WebhookSubscriptions.to_string()))
+            .filter(domain_actions::main_table_id.eq(self.id));
+
+        let total: i64 = query
+            .clone()
+            .count()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count webhook deliveries")?;
+
+        let deliveries = query
+            .order_by(domain_actions::created_at.desc())
+            .select((
+                domain_actions::id,
+                domain_events::id,
+                domain_events::event_type,
+                domain_actions::status,
+                domain_actions::attempt_count,
+                domain_actions::max_attempt_count,
+                domain_actions::last_failure_reason,
+                domain_actions::created_at,
+                domain_actions::updated_at,
+            ))
+            .limit(limit as i64)
+            .offset(limit as i64 * page as i64)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook deliveries")?;
+
+        Ok((deliveries, total))
+    }
+}
@@ -0,0 +1,45 @@
+-- Seats are paired with the ticket type's available inventory in token order so that each seat
+-- can only be sold once for the event
+WITH available_instances AS (
+         SELECT t.id, row_number() OVER (ORDER BY t.token_id) AS position
+         FROM ticket_instances AS t
+                INNER JOIN assets AS a ON t.asset_id = a.id
+         WHERE a.ticket_type_id = $1
+           AND t.status = 'Available'
+           AND t.seat_id IS NULL
+     ),
+     available_seats AS (
+         SELECT s.id, row_number() OVER (ORDER BY ss.name, s.row_name, s.seat_number) AS position
+         FROM seats AS s
+                INNER JOIN seating_sections AS ss ON s.seating_section_id = ss.id
+         WHERE s.price_zone_id = $2
+           AND NOT EXISTS(SELECT 1
+                          FROM ticket_instances AS t
+                                 INNER JOIN assets AS a ON t.asset_id = a.id
+                                 INNER JOIN ticket_types AS tt ON a.ticket_type_id = tt.id
+                          WHERE t.seat_id = s.id
+                            AND tt.event_id = $3
+                            AND t.status <> 'Nullified')
+     )
+UPDATE ticket_instances
+SET
+    seat_id = available_seats.id,
+    updated_at = now()
+FROM available_instances
+       INNER JOIN available_seats ON available_instances.position = available_seats.position
+WHERE ticket_instances.id = available_instances.id
+    RETURNING
+      ticket_instances.id,
+      ticket_instances.asset_id,
+      ticket_instances.token_id,
+      ticket_instances.hold_id,
+      ticket_instances.order_item_id,
+      ticket_instances.wallet_id,
+      ticket_instances.reserved_until,
+      ticket_instances.status,
+      ticket_instances.redeem_key,
+      ticket_instances.transfer_key,
+      ticket_instances.transfer_expiry_date,
+      ticket_instances.seat_id,
+      ticket_instances.created_at,
+      ticket_instances.updated_at;
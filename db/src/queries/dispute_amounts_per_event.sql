@@ -0,0 +1,20 @@
+-- Splits each dispute across the events on its order in proportion to the order item totals.
+-- Lost disputes on orders paid within the period are left out as their refunded tickets are
+-- already excluded from the period's sales.
+SELECT oi.event_id,
+       CAST(COALESCE(SUM(d.amount * oi.item_total / t.order_total) FILTER (WHERE d.created_at >= $2 AND d.created_at <= $3 AND (d.status IN ('Open', 'EvidenceSubmitted') OR (d.status = 'Lost' AND o.paid_at < $2))), 0) AS BIGINT) AS withheld_in_cents,
+       CAST(COALESCE(SUM(d.amount * oi.item_total / t.order_total) FILTER (WHERE d.created_at < $2 AND d.status = 'Won' AND d.closed_at >= $2 AND d.closed_at <= $3), 0) AS BIGINT)                                             AS returned_in_cents
+FROM disputes d
+       INNER JOIN orders o ON o.id = d.order_id
+       INNER JOIN (SELECT order_id, event_id, SUM(unit_price_in_cents * quantity) AS item_total
+                   FROM order_items
+                   WHERE event_id IS NOT NULL
+                   GROUP BY order_id, event_id) oi ON oi.order_id = d.order_id
+       INNER JOIN (SELECT order_id, SUM(unit_price_in_cents * quantity) AS order_total
+                   FROM order_items
+                   WHERE event_id IS NOT NULL
+                   GROUP BY order_id) t ON t.order_id = d.order_id
+       INNER JOIN events e ON e.id = oi.event_id
+WHERE e.organization_id = $1
+  AND t.order_total > 0
+GROUP BY oi.event_id
@@ -0,0 +1,32 @@
+SELECT u.id                                                  AS user_id,
+       u.first_name                                          AS first_name,
+       u.last_name                                           AS last_name,
+       u.email                                               AS email,
+       u.phone                                               AS phone,
+       cast(coalesce(kept.quantity, 0) AS BigInt)            AS tickets_kept,
+       cast(coalesce(refunded.quantity, 0) AS BigInt)        AS tickets_refunded,
+       cast(coalesce(refunded.amount_in_cents, 0) AS BigInt) AS refunded_amount_in_cents,
+       refunded.user_id IS NOT NULL                          AS refunded
+FROM users u
+       LEFT JOIN (
+                 SELECT w.user_id, count(ti.id) AS quantity
+                 FROM ticket_instances ti
+                        INNER JOIN wallets w ON ti.wallet_id = w.id
+                        INNER JOIN assets a ON ti.asset_id = a.id
+                        INNER JOIN ticket_types tt ON a.ticket_type_id = tt.id
+                        INNER JOIN event_reschedules er ON er.event_id = tt.event_id
+                 WHERE er.id = $1
+                   AND ti.status IN ('Purchased', 'Redeemed')
+                 GROUP BY w.user_id
+                 ) kept ON kept.user_id = u.id
+       LEFT JOIN (
+                 SELECT err.user_id,
+                        sum(array_length(err.ticket_instance_ids, 1)) AS quantity,
+                        sum(err.refunded_amount_in_cents)             AS amount_in_cents
+                 FROM event_reschedule_refunds err
+                 WHERE err.event_reschedule_id = $1
+                 GROUP BY err.user_id
+                 ) refunded ON refunded.user_id = u.id
+WHERE kept.user_id IS NOT NULL
+   OR refunded.user_id IS NOT NULL
+ORDER BY u.last_name, u.first_name, u.id
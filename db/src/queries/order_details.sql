@@ -4,7 +4,8 @@ SELECT
     description,
     ticket_price_in_cents,
     fees_price_in_cents,
-    (ticket_price_in_cents + fees_price_in_cents) as total_price_in_cents,
+    tax_price_in_cents,
+    (ticket_price_in_cents + fees_price_in_cents + tax_price_in_cents) as total_price_in_cents,
     status,
     status IN ('Purchased', 'Redeemed') as refundable
 FROM (
@@ -25,6 +26,14 @@ FROM (
             WHEN fi.unit_price_in_cents is null THEN oi.company_fee_in_cents + oi.client_fee_in_cents
             ELSE fi.unit_price_in_cents
         END as fees_price_in_cents,
+        CASE
+            WHEN oi.quantity = oi.refunded_quantity or rt.ticket_refunded_at is not null THEN 0
+            ELSE COALESCE(txi.unit_price_in_cents, 0)
+        END +
+        CASE
+            WHEN oi.quantity = oi.refunded_quantity or rt.fee_refunded_at is not null THEN 0
+            ELSE COALESCE(ftxi.unit_price_in_cents, 0)
+        END as tax_price_in_cents,
         CASE
             WHEN oi.quantity = oi.refunded_quantity or rt.ticket_refunded_at is not null THEN 'Refunded'
             WHEN w.user_id <> o.user_id THEN 'Transferred'
@@ -52,6 +61,8 @@ FROM (
        LEFT JOIN wallets w on ti.wallet_id = w.id
        LEFT JOIN refunded_tickets rt on rt.ticket_instance_id = ti.id
        LEFT JOIN order_items fi on fi.parent_id = oi.id and fi.item_type = 'PerUnitFees'
+       LEFT JOIN order_items txi on txi.parent_id = oi.id and txi.item_type = 'Tax'
+       LEFT JOIN order_items ftxi on ftxi.parent_id = fi.id and ftxi.item_type = 'Tax'
        WHERE e.organization_id = ANY($2)
        AND o.status in ('Paid', 'PartiallyPaid')
        AND (
@@ -12,11 +12,27 @@ LEFT JOIN (
     GROUP BY oi.id
 ) oit on oit.id = oi.id
 WHERE oi.order_id = $1
-AND item_type = 'Tickets'
 AND (
-    ti.status = 'Nullified'
-    OR ti.reserved_until < now()
-    OR c.end_date < now()
-    OR h.end_at < now()
-    OR oit.count <> oi.quantity
+    (
+        item_type = 'Tickets'
+        AND (
+            ti.status = 'Nullified'
+            OR ti.reserved_until < now()
+            OR c.end_date < now()
+            OR h.end_at < now()
+            OR oit.count <> oi.quantity
+        )
+    )
+    OR (
+        item_type = 'Resale'
+        AND NOT EXISTS (
+            SELECT 1 FROM resale_listings rl WHERE rl.order_item_id = oi.id AND rl.status = 'Active'
+        )
+    )
+    OR (
+        item_type = 'AddOn'
+        AND EXISTS (
+            SELECT 1 FROM orders o WHERE o.id = oi.order_id AND o.expires_at < now()
+        )
+    )
 )
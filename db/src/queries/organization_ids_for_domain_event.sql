@@ -0,0 +1,39 @@
+-- Resolves the organizations a domain event belongs to from its main table and id
+SELECT DISTINCT e.organization_id
+FROM events e
+WHERE ($1 = 'Events' AND e.id = $2)
+OR ($1 = 'TicketTypes' AND e.id IN (SELECT tt.event_id FROM ticket_types tt WHERE tt.id = $2))
+OR ($1 = 'TicketInstances' AND e.id IN (
+    SELECT tt.event_id
+    FROM ticket_instances ti
+    JOIN assets a ON a.id = ti.asset_id
+    JOIN ticket_types tt ON tt.id = a.ticket_type_id
+    WHERE ti.id = $2
+))
+OR ($1 = 'Orders' AND e.id IN (SELECT oi.event_id FROM order_items oi WHERE oi.order_id = $2))
+OR ($1 = 'Payments' AND e.id IN (
+    SELECT oi.event_id
+    FROM payments p
+    JOIN order_items oi ON oi.order_id = p.order_id
+    WHERE p.id = $2
+))
+OR ($1 = 'ResaleListings' AND e.id IN (
+    SELECT tt.event_id
+    FROM resale_listings rl
+    JOIN ticket_types tt ON tt.id = rl.ticket_type_id
+    WHERE rl.id = $2
+))
+OR ($1 = 'WaitlistEntries' AND e.id IN (
+    SELECT tt.event_id
+    FROM waitlist_entries we
+    JOIN ticket_types tt ON tt.id = we.ticket_type_id
+    WHERE we.id = $2
+))
+UNION
+SELECT o.id AS organization_id
+FROM organizations o
+WHERE $1 = 'Organizations' AND o.id = $2
+UNION
+SELECT tr.organization_id
+FROM tax_rates tr
+WHERE $1 = 'TaxRates' AND tr.id = $2;
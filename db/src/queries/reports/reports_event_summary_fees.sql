@@ -15,7 +15,7 @@ SELECT oi.event_id,
        CAST(COALESCE(AVG(oi_fees.client_fee_in_cents * (oi_fees.quantity - oi.refunded_quantity)), 0) AS BIGINT)  AS client_fee_in_cents
 FROM orders
        LEFT JOIN order_items oi on orders.id = oi.order_id
-       LEFT JOIN order_items oi_fees on oi.id = oi_fees.parent_id
+       LEFT JOIN order_items oi_fees on oi.id = oi_fees.parent_id AND oi_fees.item_type = 'PerUnitFees'
        LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
        LEFT JOIN ticket_pricing tp ON (oi.ticket_pricing_id = tp.id)
        LEFT JOIN (SELECT order_id, ARRAY_TO_STRING(ARRAY_AGG(DISTINCT p.payment_method), ', ') AS payment_method FROM payments p GROUP BY p.payment_method, p.order_id) AS p on orders.id = p.order_id
@@ -33,7 +33,7 @@ FROM (
                 tt.name                                                        AS ticket_name
          FROM orders
                   LEFT JOIN order_items oi ON orders.id = oi.order_id
-                  LEFT JOIN order_items oi_fees ON oi.id = oi_fees.parent_id
+                  LEFT JOIN order_items oi_fees ON oi.id = oi_fees.parent_id AND oi_fees.item_type = 'PerUnitFees'
                   LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
                   LEFT JOIN ticket_pricing tp ON (oi.ticket_pricing_id = tp.id)
                   LEFT JOIN holds h ON oi.hold_id = h.id
@@ -1,5 +1,5 @@
 SELECT e.name                                                                                                           AS event_name,
-       tt.name                                                                                                          AS ticket_name,
+       COALESCE(tt.name, ao.name)                                                                                       AS ticket_name,
        CAST(oi.quantity AS BIGINT)                                                                                      AS quantity,
        CAST(COALESCE(oi.refunded_quantity, 0) AS BIGINT)                                                                AS refunded_quantity,
        CAST(oi.quantity - COALESCE(oi.refunded_quantity, 0) AS BIGINT)                                                  AS actual_quantity,
@@ -18,6 +18,13 @@ SELECT e.name
        CAST(
              (COALESCE(oi_event_fees.client_fee_in_cents, 0) + COALESCE(oi_event_fees.company_fee_in_cents, 0)) *
              (COALESCE(oi_event_fees.quantity, 0) - COALESCE(oi_event_fees.refunded_quantity, 0)) AS BIGINT)            AS event_fee_gross_in_cents_total,
+       CAST(COALESCE(oi_tax.tax_in_cents, 0) + COALESCE(oi_fee_tax.tax_in_cents, 0) AS BIGINT)                          AS tax_in_cents,
+       CAST(COALESCE(oi_tax.tax_in_cents, 0) *
+            (COALESCE(oi_tax.quantity, 0) - COALESCE(oi_tax.refunded_quantity, 0)) +
+            COALESCE(oi_fee_tax.tax_in_cents, 0) *
+            (COALESCE(oi_fee_tax.quantity, 0) - COALESCE(oi_fee_tax.refunded_quantity, 0)) +
+            COALESCE(oi_event_fee_tax.tax_in_cents, 0) *
+            (COALESCE(oi_event_fee_tax.quantity, 0) - COALESCE(oi_event_fee_tax.refunded_quantity, 0)) AS BIGINT)       AS tax_in_cents_total,
        oi_fees.fee_schedule_range_id                                                                                    AS fee_range_id,
        orders.paid_at                                                                                                   AS transaction_date,
        orders.order_type,
@@ -32,26 +39,39 @@ SELECT e.name
              (COALESCE(oi_fees.quantity, 0) - COALESCE(oi_fees.refunded_quantity, 0)) *
              COALESCE(oi_fees.unit_price_in_cents, 0) +
              (COALESCE(oi_event_fees.quantity, 0) - COALESCE(oi_event_fees.refunded_quantity, 0)) *
-             COALESCE(oi_event_fees.unit_price_in_cents, 0)
+             COALESCE(oi_event_fees.unit_price_in_cents, 0) +
+             (COALESCE(oi_tax.quantity, 0) - COALESCE(oi_tax.refunded_quantity, 0)) *
+             COALESCE(oi_tax.unit_price_in_cents, 0) +
+             (COALESCE(oi_fee_tax.quantity, 0) - COALESCE(oi_fee_tax.refunded_quantity, 0)) *
+             COALESCE(oi_fee_tax.unit_price_in_cents, 0) +
+             (COALESCE(oi_event_fee_tax.quantity, 0) - COALESCE(oi_event_fee_tax.refunded_quantity, 0)) *
+             COALESCE(oi_event_fee_tax.unit_price_in_cents, 0)
          AS BIGINT)                                                                                                     AS gross,
        COALESCE(u.first_name, '')                                                                                       AS first_name,
        COALESCE(u.last_name, '')                                                                                        AS last_name,
        COALESCE(u.phone, '')                                                                                            AS phone,
        COALESCE(u.email, '')                                                                                            AS email,
-       e.event_start                                                                                                    AS event_start
+       e.event_start                                                                                                    AS event_start,
+       oi.item_type                                                                                                     AS item_type,
+       CAST(COALESCE(rl.resale_fee_in_cents, 0) AS BIGINT)                                                              AS resale_fee_in_cents
 
 FROM orders
-       LEFT JOIN order_items oi on (orders.id = oi.order_id AND oi.item_type = 'Tickets')
-       LEFT JOIN order_items oi_fees on oi.id = oi_fees.parent_id
+       LEFT JOIN order_items oi on (orders.id = oi.order_id AND oi.item_type IN ('Tickets', 'Resale', 'AddOn'))
+       LEFT JOIN order_items oi_fees on (oi.id = oi_fees.parent_id AND oi_fees.item_type = 'PerUnitFees')
+       LEFT JOIN order_items oi_tax on (oi.id = oi_tax.parent_id AND oi_tax.item_type = 'Tax')
+       LEFT JOIN order_items oi_fee_tax on (oi_fees.id = oi_fee_tax.parent_id AND oi_fee_tax.item_type = 'Tax')
        LEFT JOIN order_items oi_event_fees ON (oi_event_fees.item_type = 'EventFees' AND orders.id = oi_event_fees.order_id)
+       LEFT JOIN order_items oi_event_fee_tax on (oi_event_fees.id = oi_event_fee_tax.parent_id AND oi_event_fee_tax.item_type = 'Tax')
        LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
+       LEFT JOIN add_ons ao ON (oi.add_on_id = ao.id)
        LEFT JOIN (SELECT order_id, ARRAY_TO_STRING(ARRAY_AGG(DISTINCT p.payment_method), ', ') AS payment_method, ARRAY_TO_STRING(ARRAY_AGG(DISTINCT p.provider), ', ') AS payment_provider FROM payments p GROUP BY p.payment_method, p.order_id) AS p on orders.id = p.order_id
        LEFT JOIN holds h on oi.hold_id = h.id
        LEFT JOIN events e on oi.event_id = e.id
        LEFT JOIN users u on orders.user_id = u.id
+       LEFT JOIN resale_listings rl on (rl.order_item_id = oi.id AND rl.status = 'Sold')
 WHERE orders.status = 'Paid'
   AND ($1 IS NULL OR oi.event_id = $1)
   AND ($2 IS NULL OR e.organization_id = $2)
   AND ($3 IS NULL OR orders.paid_at >= $3)
   AND ($4 IS NULL OR orders.paid_at <= $4)
-  AND (oi.item_type = 'Tickets');
\ No newline at end of file
+  AND (oi.item_type IN ('Tickets', 'Resale', 'AddOn'));
\ No newline at end of file
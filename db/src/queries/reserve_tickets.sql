@@ -24,5 +24,6 @@ WHERE id IN (SELECT t.id
       redeem_key,
       transfer_key,
       transfer_expiry_date,
+      seat_id,
       created_at,
       updated_at;
@@ -0,0 +1,30 @@
+SELECT d.id,
+       d.payment_id,
+       d.order_id,
+       d.external_reference,
+       d.amount,
+       d.reason,
+       d.status,
+       d.evidence_due_by,
+       d.held_ticket_instance_ids,
+       d.closed_at,
+       d.created_at,
+       d.updated_at,
+       d.evidence_notes,
+       d.evidence_submitted_at,
+       o.currency,
+       o.paid_at,
+       u.id         AS user_id,
+       u.first_name AS first_name,
+       u.last_name  AS last_name,
+       u.email      AS email
+FROM disputes d
+       INNER JOIN orders o ON o.id = d.order_id
+       INNER JOIN users u ON u.id = coalesce(o.on_behalf_of_user_id, o.user_id)
+WHERE EXISTS(SELECT 1
+             FROM order_items oi
+                    INNER JOIN events e ON e.id = oi.event_id
+             WHERE oi.order_id = d.order_id
+               AND e.organization_id = $1)
+  AND ($2 IS NULL OR d.status = $2)
+ORDER BY d.created_at DESC
@@ -1,3 +1,32 @@
+table! {
+    add_on_vouchers (id) {
+        id -> Uuid,
+        add_on_id -> Uuid,
+        order_item_id -> Uuid,
+        user_id -> Uuid,
+        redeem_key -> Text,
+        status -> Text,
+        redeemed_at -> Nullable<Timestamp>,
+        redeemed_by_user_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    add_ons (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        price_in_cents -> Int8,
+        quantity -> Int8,
+        redeemable -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     artists (id) {
         id -> Uuid,
@@ -33,6 +62,42 @@ table! {
     }
 }
 
+table! {
+    bundle_pricing (id) {
+        id -> Uuid,
+        bundle_id -> Uuid,
+        name -> Text,
+        price_in_cents -> Int8,
+        start_date -> Timestamp,
+        end_date -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    bundle_ticket_types (id) {
+        id -> Uuid,
+        bundle_id -> Uuid,
+        ticket_type_id -> Uuid,
+        quantity -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    bundles (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        quantity -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     codes (id) {
         id -> Uuid,
@@ -50,6 +115,44 @@ table! {
     }
 }
 
+table! {
+    communication_logs (id) {
+        id -> Uuid,
+        domain_action_id -> Nullable<Uuid>,
+        channel -> Text,
+        template_id -> Nullable<Text>,
+        title -> Text,
+        destination -> Text,
+        main_table -> Nullable<Text>,
+        main_table_id -> Nullable<Uuid>,
+        provider_message_id -> Nullable<Text>,
+        status -> Text,
+        status_updated_at -> Nullable<Timestamp>,
+        payload -> Jsonb,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    disputes (id) {
+        id -> Uuid,
+        payment_id -> Uuid,
+        order_id -> Uuid,
+        external_reference -> Text,
+        amount -> Int8,
+        reason -> Nullable<Text>,
+        status -> Text,
+        evidence_due_by -> Nullable<Timestamp>,
+        held_ticket_instance_ids -> Array<Uuid>,
+        closed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        evidence_notes -> Nullable<Text>,
+        evidence_submitted_at -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     domain_actions (id) {
         id -> Uuid,
@@ -101,6 +204,49 @@ table! {
     }
 }
 
+table! {
+    event_cancellation_orders (id) {
+        id -> Uuid,
+        event_cancellation_id -> Uuid,
+        order_id -> Uuid,
+        status -> Text,
+        attempt_count -> Int8,
+        refunded_amount_in_cents -> Int8,
+        last_failure_reason -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_cancellations (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        refund_fees -> Bool,
+        status -> Text,
+        created_by_user_id -> Uuid,
+        completed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_communication_schedules (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        timing -> Text,
+        offset_hours -> Int4,
+        channel -> Text,
+        title -> Text,
+        message -> Text,
+        template_id -> Nullable<Text>,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     event_interest (id) {
         id -> Uuid,
@@ -111,6 +257,36 @@ table! {
     }
 }
 
+table! {
+    event_reschedule_refunds (id) {
+        id -> Uuid,
+        event_reschedule_id -> Uuid,
+        user_id -> Uuid,
+        order_id -> Uuid,
+        ticket_instance_ids -> Array<Uuid>,
+        refunded_amount_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_reschedules (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        previous_event_start -> Nullable<Timestamp>,
+        previous_door_time -> Nullable<Timestamp>,
+        previous_event_end -> Nullable<Timestamp>,
+        event_start -> Timestamp,
+        door_time -> Nullable<Timestamp>,
+        event_end -> Nullable<Timestamp>,
+        refund_deadline -> Timestamp,
+        created_by_user_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     events (id) {
         id -> Uuid,
@@ -142,6 +318,7 @@ table! {
         event_type -> Text,
         cover_image_url -> Nullable<Text>,
         private_access_code -> Nullable<Text>,
+        currency -> Text,
     }
 }
 
@@ -200,6 +377,36 @@ table! {
     }
 }
 
+table! {
+    offline_redemptions (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        ticket_instance_id -> Nullable<Uuid>,
+        device_id -> Text,
+        redeemed_by -> Uuid,
+        scanned_at -> Timestamp,
+        status -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        add_on_voucher_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    order_exchanges (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        ticket_type_id -> Uuid,
+        exchanged_ticket_instance_ids -> Array<Uuid>,
+        ticket_instance_ids -> Array<Uuid>,
+        refunded_amount_in_cents -> Int8,
+        charged_amount_in_cents -> Int8,
+        created_by_user_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     order_items (id) {
         id -> Uuid,
@@ -219,6 +426,10 @@ table! {
         company_fee_in_cents -> Int8,
         client_fee_in_cents -> Int8,
         refunded_quantity -> Int8,
+        tax_rate_id -> Nullable<Uuid>,
+        tax_in_cents -> Int8,
+        bundle_id -> Nullable<Uuid>,
+        add_on_id -> Nullable<Uuid>,
     }
 }
 
@@ -241,6 +452,22 @@ table! {
         checkout_url_expires -> Nullable<Timestamp>,
         create_user_agent -> Nullable<Text>,
         purchase_user_agent -> Nullable<Text>,
+        currency -> Nullable<Text>,
+    }
+}
+
+table! {
+    organization_email_templates (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        template_kind -> Text,
+        template_id -> Nullable<Text>,
+        logo_url -> Nullable<Text>,
+        primary_color -> Nullable<Text>,
+        reply_to_email -> Nullable<Text>,
+        footer_text -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -283,6 +510,11 @@ table! {
         allowed_payment_providers -> Array<Text>,
         timezone -> Nullable<Text>,
         cc_fee_percent -> Float4,
+        max_resale_markup_percent -> Nullable<Float4>,
+        resale_fee_percent -> Float4,
+        currency -> Text,
+        refund_fees_on_cancellation -> Bool,
+        two_factor_required -> Bool,
     }
 }
 
@@ -308,6 +540,11 @@ table! {
         provider_data -> Json,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        source_reference -> Nullable<Text>,
+        card_brand -> Nullable<Text>,
+        card_last_four -> Nullable<Text>,
+        card_exp_month -> Nullable<Int4>,
+        card_exp_year -> Nullable<Int4>,
     }
 }
 
@@ -328,6 +565,16 @@ table! {
     }
 }
 
+table! {
+    price_zones (id) {
+        id -> Uuid,
+        stage_id -> Uuid,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     push_notification_tokens (id) {
         id -> Uuid,
@@ -360,6 +607,79 @@ table! {
     }
 }
 
+table! {
+    report_subscriptions (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        event_id -> Nullable<Uuid>,
+        report_type -> Text,
+        frequency -> Text,
+        email -> Text,
+        created_by -> Uuid,
+        next_send_at -> Timestamp,
+        last_sent_at -> Nullable<Timestamp>,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        last_failed_at -> Nullable<Timestamp>,
+        last_failure_reason -> Nullable<Text>,
+    }
+}
+
+table! {
+    resale_listings (id) {
+        id -> Uuid,
+        ticket_instance_id -> Uuid,
+        ticket_type_id -> Uuid,
+        seller_user_id -> Uuid,
+        face_value_in_cents -> Int8,
+        price_in_cents -> Int8,
+        resale_fee_in_cents -> Int8,
+        status -> Text,
+        order_item_id -> Nullable<Uuid>,
+        sold_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    resale_payouts (id) {
+        id -> Uuid,
+        resale_listing_id -> Uuid,
+        seller_user_id -> Uuid,
+        amount_in_cents -> Int8,
+        currency -> Text,
+        status -> Text,
+        paid_at -> Nullable<Timestamp>,
+        paid_by_user_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    seating_sections (id) {
+        id -> Uuid,
+        stage_id -> Uuid,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    seats (id) {
+        id -> Uuid,
+        seating_section_id -> Uuid,
+        price_zone_id -> Nullable<Uuid>,
+        row_name -> Text,
+        seat_number -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     settlements (id) {
         id -> Uuid,
@@ -387,6 +707,7 @@ table! {
         comment -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        currency -> Text,
     }
 }
 
@@ -402,6 +723,19 @@ table! {
     }
 }
 
+table! {
+    tax_rates (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        region_id -> Nullable<Uuid>,
+        name -> Text,
+        rate_percent -> Float4,
+        inclusive -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     ticket_instances (id) {
         id -> Uuid,
@@ -417,6 +751,7 @@ table! {
         status -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        seat_id -> Nullable<Uuid>,
     }
 }
 
@@ -432,6 +767,8 @@ table! {
         is_box_office_only -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        quantity_limit -> Nullable<Int8>,
+        rank -> Int4,
     }
 }
 
@@ -462,6 +799,22 @@ table! {
         cancelled_at -> Nullable<Timestamp>,
         sold_out_behavior -> Text,
         is_private -> Bool,
+        price_zone_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    user_two_factor_credentials (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        secret -> Text,
+        recovery_code_hashes -> Array<Text>,
+        enabled_at -> Nullable<Timestamp>,
+        last_used_step -> Nullable<Int8>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        failed_attempts -> Int4,
+        locked_until -> Nullable<Timestamp>,
     }
 }
 
@@ -513,6 +866,20 @@ table! {
     }
 }
 
+table! {
+    waitlist_entries (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        user_id -> Uuid,
+        quantity -> Int4,
+        status -> Text,
+        order_id -> Nullable<Uuid>,
+        offer_expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     wallets (id) {
         id -> Uuid,
@@ -527,29 +894,73 @@ table! {
     }
 }
 
+table! {
+    webhook_subscriptions (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        url -> Text,
+        secret -> Text,
+        event_types -> Array<Text>,
+        created_by -> Uuid,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+joinable!(add_on_vouchers -> add_ons (add_on_id));
+joinable!(add_on_vouchers -> order_items (order_item_id));
+joinable!(add_ons -> events (event_id));
 joinable!(artists -> organizations (organization_id));
 joinable!(assets -> ticket_types (ticket_type_id));
+joinable!(bundle_pricing -> bundles (bundle_id));
+joinable!(bundle_ticket_types -> bundles (bundle_id));
+joinable!(bundle_ticket_types -> ticket_types (ticket_type_id));
+joinable!(bundles -> organizations (organization_id));
 joinable!(codes -> events (event_id));
+joinable!(disputes -> orders (order_id));
+joinable!(disputes -> payments (payment_id));
 joinable!(domain_actions -> domain_events (domain_event_id));
 joinable!(domain_events -> users (user_id));
 joinable!(event_artists -> artists (artist_id));
 joinable!(event_artists -> events (event_id));
 joinable!(event_artists -> stages (stage_id));
+joinable!(event_cancellation_orders -> event_cancellations (event_cancellation_id));
+joinable!(event_cancellation_orders -> orders (order_id));
+joinable!(event_cancellations -> events (event_id));
+joinable!(event_cancellations -> users (created_by_user_id));
+joinable!(event_communication_schedules -> organizations (organization_id));
 joinable!(event_interest -> events (event_id));
 joinable!(event_interest -> users (user_id));
+joinable!(event_reschedule_refunds -> event_reschedules (event_reschedule_id));
+joinable!(event_reschedule_refunds -> orders (order_id));
+joinable!(event_reschedule_refunds -> users (user_id));
+joinable!(event_reschedules -> events (event_id));
+joinable!(event_reschedules -> users (created_by_user_id));
 joinable!(events -> organizations (organization_id));
 joinable!(events -> venues (venue_id));
 joinable!(external_logins -> users (user_id));
 joinable!(fee_schedule_ranges -> fee_schedules (fee_schedule_id));
 joinable!(holds -> events (event_id));
 joinable!(holds -> ticket_types (ticket_type_id));
+joinable!(offline_redemptions -> add_on_vouchers (add_on_voucher_id));
+joinable!(offline_redemptions -> events (event_id));
+joinable!(offline_redemptions -> ticket_instances (ticket_instance_id));
+joinable!(offline_redemptions -> users (redeemed_by));
+joinable!(order_exchanges -> orders (order_id));
+joinable!(order_exchanges -> ticket_types (ticket_type_id));
+joinable!(order_exchanges -> users (created_by_user_id));
+joinable!(order_items -> add_ons (add_on_id));
+joinable!(order_items -> bundles (bundle_id));
 joinable!(order_items -> codes (code_id));
 joinable!(order_items -> events (event_id));
 joinable!(order_items -> fee_schedule_ranges (fee_schedule_range_id));
 joinable!(order_items -> holds (hold_id));
 joinable!(order_items -> orders (order_id));
+joinable!(order_items -> tax_rates (tax_rate_id));
 joinable!(order_items -> ticket_pricing (ticket_pricing_id));
 joinable!(order_items -> ticket_types (ticket_type_id));
+joinable!(organization_email_templates -> organizations (organization_id));
 joinable!(organization_invites -> organizations (organization_id));
 joinable!(organization_users -> organizations (organization_id));
 joinable!(organization_users -> users (user_id));
@@ -557,57 +968,104 @@ joinable!(organizations -> fee_schedules (fee_schedule_id));
 joinable!(payment_methods -> users (user_id));
 joinable!(payments -> orders (order_id));
 joinable!(payments -> users (created_by));
+joinable!(price_zones -> stages (stage_id));
 joinable!(push_notification_tokens -> users (user_id));
 joinable!(refunded_tickets -> order_items (order_item_id));
 joinable!(refunded_tickets -> ticket_instances (ticket_instance_id));
+joinable!(report_subscriptions -> events (event_id));
+joinable!(report_subscriptions -> organizations (organization_id));
+joinable!(report_subscriptions -> users (created_by));
+joinable!(resale_listings -> order_items (order_item_id));
+joinable!(resale_listings -> ticket_instances (ticket_instance_id));
+joinable!(resale_listings -> ticket_types (ticket_type_id));
+joinable!(resale_listings -> users (seller_user_id));
+joinable!(resale_payouts -> resale_listings (resale_listing_id));
+joinable!(seating_sections -> stages (stage_id));
+joinable!(seats -> price_zones (price_zone_id));
+joinable!(seats -> seating_sections (seating_section_id));
 joinable!(settlement_transactions -> events (event_id));
 joinable!(settlement_transactions -> settlements (settlement_id));
 joinable!(settlements -> organizations (organization_id));
 joinable!(settlements -> users (user_id));
+joinable!(tax_rates -> organizations (organization_id));
+joinable!(tax_rates -> regions (region_id));
 joinable!(ticket_instances -> assets (asset_id));
 joinable!(ticket_instances -> holds (hold_id));
 joinable!(ticket_instances -> order_items (order_item_id));
+joinable!(ticket_instances -> seats (seat_id));
 joinable!(ticket_instances -> wallets (wallet_id));
 joinable!(ticket_pricing -> ticket_types (ticket_type_id));
 joinable!(ticket_type_codes -> codes (code_id));
 joinable!(ticket_type_codes -> ticket_types (ticket_type_id));
 joinable!(ticket_types -> events (event_id));
+joinable!(ticket_types -> price_zones (price_zone_id));
+joinable!(user_two_factor_credentials -> users (user_id));
 joinable!(venues -> organizations (organization_id));
 joinable!(venues -> regions (region_id));
+joinable!(waitlist_entries -> orders (order_id));
+joinable!(waitlist_entries -> ticket_types (ticket_type_id));
+joinable!(waitlist_entries -> users (user_id));
 joinable!(wallets -> organizations (organization_id));
 joinable!(wallets -> users (user_id));
+joinable!(webhook_subscriptions -> organizations (organization_id));
+joinable!(webhook_subscriptions -> users (created_by));
 
 allow_tables_to_appear_in_same_query!(
+    add_on_vouchers,
+    add_ons,
     artists,
     assets,
+    bundle_pricing,
+    bundle_ticket_types,
+    bundles,
     codes,
+    communication_logs,
+    disputes,
     domain_actions,
     domain_events,
     event_artists,
+    event_cancellation_orders,
+    event_cancellations,
+    event_communication_schedules,
     event_interest,
+    event_reschedule_refunds,
+    event_reschedules,
     events,
     external_logins,
     fee_schedule_ranges,
     fee_schedules,
     holds,
+    offline_redemptions,
+    order_exchanges,
     order_items,
     orders,
+    organization_email_templates,
     organization_invites,
     organizations,
     organization_users,
     payment_methods,
     payments,
+    price_zones,
     push_notification_tokens,
     refunded_tickets,
     regions,
+    report_subscriptions,
+    resale_listings,
+    resale_payouts,
+    seating_sections,
+    seats,
     settlements,
     settlement_transactions,
     stages,
+    tax_rates,
     ticket_instances,
     ticket_pricing,
     ticket_type_codes,
     ticket_types,
+    user_two_factor_credentials,
     users,
     venues,
+    waitlist_entries,
     wallets,
+    webhook_subscriptions,
 );
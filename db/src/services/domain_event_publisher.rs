@@ -32,6 +32,7 @@ where
                 }
             }
         }
+        WebhookSubscription::queue_deliveries(&event, conn)?;
         event.mark_as_published(conn)?;
         Ok(())
     }
@@ -90,6 +90,7 @@ impl<'a> OrderBuilder<'a> {
                 ticket_type_id: self.ticket_type_id.unwrap(),
                 quantity: self.quantity,
                 redemption_code,
+                seat_ids: None,
             }],
             false,
             false,
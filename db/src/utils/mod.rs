@@ -4,8 +4,11 @@ pub mod hash;
 pub mod iterators;
 mod math;
 pub mod migration;
+pub mod network;
 pub mod passwords;
 pub mod rand;
+pub mod spreadsheets;
 pub mod text;
+pub mod totp;
 
 pub use self::math::*;
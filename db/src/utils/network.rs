@@ -0,0 +1,115 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+use url::{Host, Url};
+
+/// Checks a URL that requests will be made to on behalf of a user. Only https URLs for hosts
+/// outside of our own network are accepted, returning the reason the URL was refused otherwise.
+pub fn validate_public_https_url(url: &str) -> Result<Url, &'static str> {
+    let url = Url::parse(url).map_err(|_| "URL is invalid")?;
+    if url.scheme() != "https" {
+        return Err("URL must use https");
+    }
+    let is_public = match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_right_matches('.').to_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => is_public_ipv4(&ip),
+        Some(Host::Ipv6(ip)) => is_public_ipv6(&ip),
+        None => false,
+    };
+    if !is_public {
+        return Err("URL must be for a public host");
+    }
+    Ok(url)
+}
+
+/// Resolves the host of a URL checked by `validate_public_https_url`, refusing hosts that resolve
+/// to an address inside our own network at the time of the request. Requests must be made to the
+/// returned addresses rather than resolving the host again, which could give a different answer.
+pub fn resolve_public_host(url: &Url) -> Result<Vec<IpAddr>, &'static str> {
+    let host = url.host_str().ok_or("URL must be for a public host")?;
+    let port = url.port_or_known_default().unwrap_or(443);
+    let host = host.trim_left_matches('[').trim_right_matches(']');
+    let addresses: Vec<IpAddr> = (host, port)
+        .to_socket_addrs()
+        .map_err(|_| "URL host could not be resolved")?
+        .map(|a| a.ip())
+        .collect();
+    if addresses.is_empty() || !addresses.iter().all(is_public_ip) {
+        return Err("URL must be for a public host");
+    }
+    Ok(addresses)
+}
+
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // 0.0.0.0/8 and the 100.64.0.0/10 shared address space
+        || octets[0] == 0
+        || (octets[0] == 100 && octets[1] & 0xc0 == 64))
+}
+
+fn is_public_ipv6(ip: &Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    // IPv4 mapped addresses, ::ffff:a.b.c.d
+    if segments[..5].iter().all(|s| *s == 0) && segments[5] == 0xffff {
+        return is_public_ipv4(&Ipv4Addr::new(
+            (segments[6] >> 8) as u8,
+            segments[6] as u8,
+            (segments[7] >> 8) as u8,
+            segments[7] as u8,
+        ));
+    }
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        // Unique local fc00::/7 and link local fe80::/10
+        || segments[0] & 0xfe00 == 0xfc00
+        || segments[0] & 0xffc0 == 0xfe80)
+}
+
+#[test]
+fn validate_public_https_url_accepts_public_hosts() {
+    assert!(validate_public_https_url("https://example.com/webhooks").is_ok());
+    assert!(validate_public_https_url("https://8.8.8.8/webhooks").is_ok());
+    assert!(validate_public_https_url("https://[2001:4860:4860::8888]/webhooks").is_ok());
+}
+
+#[test]
+fn validate_public_https_url_rejects_internal_hosts() {
+    assert_eq!(
+        validate_public_https_url("http://example.com/webhooks").unwrap_err(),
+        "URL must use https"
+    );
+    for url in &[
+        "https://localhost/webhooks",
+        "https://api.localhost/webhooks",
+        "https://127.0.0.1/webhooks",
+        "https://10.0.0.1/webhooks",
+        "https://172.16.0.1/webhooks",
+        "https://192.168.1.1/webhooks",
+        "https://169.254.169.254/latest/meta-data",
+        "https://0.0.0.0/webhooks",
+        "https://[::1]/webhooks",
+        "https://[fe80::1]/webhooks",
+        "https://[fd00::1]/webhooks",
+        "https://[::ffff:127.0.0.1]/webhooks",
+    ] {
+        assert_eq!(
+            validate_public_https_url(url).unwrap_err(),
+            "URL must be for a public host",
+            "{}",
+            url
+        );
+    }
+}
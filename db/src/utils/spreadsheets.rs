@@ -0,0 +1,423 @@
+use chrono::prelude::*;
+use chrono_tz::Tz;
+use csv;
+use std::io::{Cursor, Write};
+use utils::errors::*;
+use zip::result::ZipError;
+use zip::write::{FileOptions, ZipWriter};
+
+// Indexes into the cell formats of STYLES_XML
+const MONEY_STYLE: usize = 1;
+const TIME_STYLE: usize = 2;
+
+const CONTENT_TYPES_XML_START: &'static str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+<Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>";
+
+const ROOT_RELS_XML: &'static str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+</Relationships>";
+
+const STYLES_XML: &'static str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+<numFmts count=\"1\"><numFmt numFmtId=\"164\" formatCode=\"yyyy-mm-dd hh:mm:ss\"/></numFmts>\
+<fonts count=\"1\"><font><sz val=\"11\"/><name val=\"Calibri\"/></font></fonts>\
+<fills count=\"2\"><fill><patternFill patternType=\"none\"/></fill><fill><patternFill patternType=\"gray125\"/></fill></fills>\
+<borders count=\"1\"><border><left/><right/><top/><bottom/><diagonal/></border></borders>\
+<cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>\
+<cellXfs count=\"3\">\
+<xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>\
+<xf numFmtId=\"4\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+<xf numFmtId=\"164\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>\
+</cellXfs>\
+<cellStyles count=\"1\"><cellStyle name=\"Normal\" xfId=\"0\" builtinId=\"0\"/></cellStyles>\
+</styleSheet>";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Empty,
+    Text(String),
+    Number(i64),
+    /// An amount in cents, exported in major currency units
+    Money(i64),
+    /// Exported in the timezone of the date time
+    Time(DateTime<Tz>),
+}
+
+impl Cell {
+    pub fn text<T: ToString>(value: T) -> Cell {
+        Cell::Text(value.to_string())
+    }
+
+    pub fn optional_text<T: ToString>(value: &Option<T>) -> Cell {
+        match value {
+            Some(value) => Cell::Text(value.to_string()),
+            None => Cell::Empty,
+        }
+    }
+
+    pub fn optional_money(value: Option<i64>) -> Cell {
+        value.map(Cell::Money).unwrap_or(Cell::Empty)
+    }
+
+    pub fn utc(value: NaiveDateTime) -> Cell {
+        Cell::Time(Tz::UTC.from_utc_datetime(&value))
+    }
+
+    pub fn optional_utc(value: Option<NaiveDateTime>) -> Cell {
+        value.map(Cell::utc).unwrap_or(Cell::Empty)
+    }
+
+    pub fn optional_time(value: Option<DateTime<Tz>>) -> Cell {
+        value.map(Cell::Time).unwrap_or(Cell::Empty)
+    }
+
+    pub fn to_csv_field(&self) -> String {
+        match self {
+            Cell::Empty => String::new(),
+            Cell::Text(value) => value.clone(),
+            Cell::Number(value) => value.to_string(),
+            Cell::Money(value) => format_cents(*value),
+            Cell::Time(value) => value.format("%Y-%m-%d %H:%M:%S %Z").to_string(),
+        }
+    }
+}
+
+/// Formats cents as a decimal amount, e.g. 1050 as 10.50
+pub fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.abs();
+    format!("{}{}.{:02}", sign, cents / 100, cents % 100)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Worksheet {
+    pub name: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Worksheet {
+    pub fn new(name: &str, headers: &[&str]) -> Worksheet {
+        Worksheet {
+            name: name.to_string(),
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    /// Renders the rows as CSV, starting with the header line when `include_headers` is set so
+    /// large exports can be written a page of rows at a time
+    pub fn to_csv_page(&self, include_headers: bool) -> Result<Vec<u8>, DatabaseError> {
+        let mut writer = csv_writer(vec![]);
+        if include_headers {
+            writer.write_record(&self.headers).map_err(csv_error)?;
+        }
+        for row in self.rows.iter() {
+            writer
+                .write_record(row.iter().map(|c| c.to_csv_field()))
+                .map_err(csv_error)?;
+        }
+        writer.into_inner().map_err(csv_error)
+    }
+
+    fn to_xlsx_sheet(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+             <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+             <sheetData>",
+        );
+        let headers: Vec<Cell> = self.headers.iter().map(Cell::text).collect();
+        for (index, row) in Some(&headers)
+            .into_iter()
+            .chain(self.rows.iter())
+            .enumerate()
+        {
+            let row_number = index + 1;
+            xml.push_str(&format!("<row r=\"{}\">", row_number));
+            for (column, cell) in row.iter().enumerate() {
+                let reference = format!("{}{}", column_name(column), row_number);
+                let value = match cell {
+                    Cell::Empty => continue,
+                    Cell::Text(value) => format!(
+                        "<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                        reference,
+                        xml_escape(value)
+                    ),
+                    Cell::Number(value) => format!("<c r=\"{}\"><v>{}</v></c>", reference, value),
+                    Cell::Money(value) => format!(
+                        "<c r=\"{}\" s=\"{}\"><v>{}</v></c>",
+                        reference,
+                        MONEY_STYLE,
+                        format_cents(*value)
+                    ),
+                    Cell::Time(value) => format!(
+                        "<c r=\"{}\" s=\"{}\"><v>{}</v></c>",
+                        reference,
+                        TIME_STYLE,
+                        excel_serial_date(value.naive_local())
+                    ),
+                };
+                xml.push_str(&value);
+            }
+            xml.push_str("</row>");
+        }
+        xml.push_str("</sheetData></worksheet>");
+        xml
+    }
+}
+
+/// Renders worksheets as CSV, additional worksheets follow a blank line and their name
+pub fn to_csv(worksheets: &[Worksheet]) -> Result<String, DatabaseError> {
+    let mut data = vec![];
+    for (i, worksheet) in worksheets.iter().enumerate() {
+        if worksheets.len() > 1 {
+            if i > 0 {
+                data.extend_from_slice(b"\r\n");
+            }
+            let mut writer = csv_writer(data);
+            writer
+                .write_record(&[worksheet.name.as_str()])
+                .map_err(csv_error)?;
+            data = writer.into_inner().map_err(csv_error)?;
+        }
+        data.extend(worksheet.to_csv_page(true)?);
+    }
+    String::from_utf8(data).map_err(|e| {
+        DatabaseError::new(
+            ErrorCode::InternalError,
+            Some(format!("Could not render CSV: {}", e)),
+        )
+    })
+}
+
+/// Renders worksheets as an Office Open XML workbook. Unlike CSV the workbook can only be written
+/// once all of its rows are known.
+pub fn to_xlsx(worksheets: &[Worksheet]) -> Result<Vec<u8>, DatabaseError> {
+    let mut content_types = String::from(CONTENT_TYPES_XML_START);
+    let mut workbook = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+         <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+         xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>",
+    );
+    let mut workbook_rels = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+         <Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/>",
+    );
+
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    for (index, worksheet) in worksheets.iter().enumerate() {
+        let sheet_id = index + 1;
+        // rId1 is taken by the styles
+        let relationship_id = index + 2;
+        content_types.push_str(&format!(
+            "<Override PartName=\"/xl/worksheets/sheet{}.xml\" \
+             ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+            sheet_id
+        ));
+        workbook.push_str(&format!(
+            "<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>",
+            xml_escape(&sheet_name(&worksheet.name)),
+            sheet_id,
+            relationship_id
+        ));
+        workbook_rels.push_str(&format!(
+            "<Relationship Id=\"rId{}\" \
+             Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" \
+             Target=\"worksheets/sheet{}.xml\"/>",
+            relationship_id, sheet_id
+        ));
+        write_zip_file(
+            &mut zip,
+            &format!("xl/worksheets/sheet{}.xml", sheet_id),
+            &worksheet.to_xlsx_sheet(),
+        )?;
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    workbook_rels.push_str("</Relationships>");
+
+    write_zip_file(&mut zip, "[Content_Types].xml", &content_types)?;
+    write_zip_file(&mut zip, "_rels/.rels", ROOT_RELS_XML)?;
+    write_zip_file(&mut zip, "xl/workbook.xml", &workbook)?;
+    write_zip_file(&mut zip, "xl/_rels/workbook.xml.rels", &workbook_rels)?;
+    write_zip_file(&mut zip, "xl/styles.xml", STYLES_XML)?;
+    Ok(zip.finish().map_err(xlsx_error)?.into_inner())
+}
+
+fn write_zip_file(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    contents: &str,
+) -> Result<(), DatabaseError> {
+    zip.start_file(name, FileOptions::default())
+        .map_err(xlsx_error)?;
+    zip.write_all(contents.as_bytes()).map_err(xlsx_error)
+}
+
+fn csv_writer(data: Vec<u8>) -> csv::Writer<Vec<u8>> {
+    // Flexible as the worksheet name line has fewer fields than the rows
+    csv::WriterBuilder::new()
+        .flexible(true)
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(data)
+}
+
+fn csv_error<E: ToString>(error: E) -> DatabaseError {
+    DatabaseError::new(
+        ErrorCode::InternalError,
+        Some(format!("Could not render CSV: {}", error.to_string())),
+    )
+}
+
+fn xlsx_error<E: Into<ZipError>>(error: E) -> DatabaseError {
+    let error = error.into();
+    DatabaseError::new(
+        ErrorCode::InternalError,
+        Some(format!("Could not render XLSX: {}", error)),
+    )
+}
+
+/// Days since 1899-12-30, the epoch spreadsheet applications use for dates
+fn excel_serial_date(value: NaiveDateTime) -> f64 {
+    let epoch = NaiveDate::from_ymd(1899, 12, 30).and_hms(0, 0, 0);
+    value.signed_duration_since(epoch).num_seconds() as f64 / 86_400.0
+}
+
+/// Spreadsheet column letters, e.g. 0 as A and 27 as AB
+fn column_name(index: usize) -> String {
+    let mut index = index + 1;
+    let mut name = vec![];
+    while index > 0 {
+        let remainder = (index - 1) % 26;
+        name.insert(0, (b'A' + remainder as u8) as char);
+        index = (index - 1) / 26;
+    }
+    name.into_iter().collect()
+}
+
+/// Escapes markup and drops the control characters XML cannot contain
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Sheet names are limited to 31 characters and cannot contain some punctuation
+fn sheet_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !"[]:*?/\\".contains(*c))
+        .take(31)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_cents_as_decimal() {
+        assert_eq!(format_cents(1050), "10.50");
+        assert_eq!(format_cents(5), "0.05");
+        assert_eq!(format_cents(-1999), "-19.99");
+    }
+
+    #[test]
+    fn to_csv_with_cells() {
+        let mut worksheet = Worksheet::new("Sales", &["Name", "Total", "Count", "Date"]);
+        worksheet.push(vec![
+            Cell::text("VIP, Early"),
+            Cell::Money(1050),
+            Cell::Number(3),
+            Cell::utc(NaiveDate::from_ymd(2019, 3, 1).and_hms(18, 30, 0)),
+        ]);
+        worksheet.push(vec![
+            Cell::Empty,
+            Cell::Money(-5),
+            Cell::Number(0),
+            Cell::Empty,
+        ]);
+        assert_eq!(
+            to_csv(&[worksheet]).unwrap(),
+            "Name,Total,Count,Date\r\n\"VIP, Early\",10.50,3,2019-03-01 18:30:00 UTC\r\n,-0.05,0,\r\n"
+        );
+    }
+
+    #[test]
+    fn to_csv_with_multiple_worksheets() {
+        let mut sales = Worksheet::new("Sales", &["Name", "Total"]);
+        sales.push(vec![Cell::text("\"General\""), Cell::Money(1050)]);
+        let counts = Worksheet::new("Counts", &["Name", "Count"]);
+        assert_eq!(
+            to_csv(&[sales, counts]).unwrap(),
+            "Sales\r\nName,Total\r\n\"\"\"General\"\"\",10.50\r\n\r\nCounts\r\nName,Count\r\n"
+        );
+    }
+
+    #[test]
+    fn to_csv_page_without_headers() {
+        let mut worksheet = Worksheet::new("Fans", &["Name"]);
+        worksheet.push(vec![Cell::text("Line\nBreak")]);
+        assert_eq!(
+            worksheet.to_csv_page(false).unwrap(),
+            b"\"Line\nBreak\"\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn to_xlsx_contains_worksheets() {
+        let mut worksheet = Worksheet::new("Sales: [All]", &["Name", "Total", "Date"]);
+        worksheet.push(vec![
+            Cell::text("<General>"),
+            Cell::Money(1050),
+            Cell::utc(NaiveDate::from_ymd(2019, 3, 1).and_hms(18, 30, 0)),
+        ]);
+
+        let data = to_xlsx(&[worksheet.clone(), Worksheet::new("Counts", &["Name"])]).unwrap();
+        assert_eq!(&data[0..4], &[0x50, 0x4b, 0x03, 0x04]);
+    }
+
+    #[test]
+    fn xlsx_column_names() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(column_name(702), "AAA");
+    }
+
+    #[test]
+    fn to_xlsx_sheet_escapes_text() {
+        let mut worksheet = Worksheet::new("Sales", &["Name", "Total"]);
+        worksheet.push(vec![Cell::text("<General> & \u{1}VIP"), Cell::Money(1050)]);
+        let xml = worksheet.to_xlsx_sheet();
+        assert!(xml.contains("<t xml:space=\"preserve\">&lt;General&gt; &amp; VIP</t>"));
+        assert!(xml.contains("<c r=\"B2\" s=\"1\"><v>10.50</v></c>"));
+    }
+
+    #[test]
+    fn excel_serial_dates() {
+        assert_eq!(
+            excel_serial_date(NaiveDate::from_ymd(1900, 1, 1).and_hms(12, 0, 0)),
+            2.5
+        );
+    }
+}
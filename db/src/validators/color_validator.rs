@@ -0,0 +1,16 @@
+use std::borrow::Cow;
+use validator::ValidationError;
+use validators::*;
+
+/// Accepts six digit hex colors such as `#1a2B3c`
+pub fn validate_hex_color(color: &str) -> Result<(), ValidationError> {
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        let mut validation_error = create_validation_error("color", "Color must be a hex color");
+        validation_error.add_param(Cow::from("color"), &color);
+        return Err(validation_error);
+    }
+    Ok(())
+}
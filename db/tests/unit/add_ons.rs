@@ -0,0 +1,267 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+
+fn parking_add_on(project: &TestProject, quantity: i64, redeemable: bool) -> (AddOn, Event) {
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let add_on = AddOn::create(
+        event.id,
+        "Parking".to_string(),
+        None,
+        1500,
+        quantity,
+        redeemable,
+    )
+    .commit(creator.id, connection)
+    .unwrap();
+    (add_on, event)
+}
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (add_on, event) = parking_add_on(&project, 10, true);
+    assert_eq!(add_on.event_id, event.id);
+    assert_eq!(
+        AddOn::find_for_event(event.id, connection).unwrap(),
+        vec![add_on.clone()]
+    );
+
+    let display_add_on = add_on.for_display(connection).unwrap();
+    assert_eq!(display_add_on.available, 10);
+    assert_eq!(display_add_on.price_in_cents, 1500);
+
+    let creator = project.create_user().finish();
+    assert!(
+        AddOn::create(event.id, "".to_string(), None, 1500, 10, true)
+            .commit(creator.id, connection)
+            .is_err()
+    );
+    assert!(
+        AddOn::create(event.id, "T-shirt".to_string(), None, -1, 10, true)
+            .commit(creator.id, connection)
+            .is_err()
+    );
+}
+
+#[test]
+fn update_add_on_quantities() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (add_on, _) = parking_add_on(&project, 3, true);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    cart.update_add_on_quantities(
+        user.id,
+        &[UpdateAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 2,
+        }],
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let add_on_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::AddOn)
+        .unwrap();
+    assert_eq!(add_on_item.quantity, 2);
+    assert_eq!(add_on_item.unit_price_in_cents, 1500);
+    assert_eq!(add_on_item.add_on_id, Some(add_on.id));
+    assert!(cart.expires_at.is_some());
+    assert_eq!(add_on.available_quantity(connection).unwrap(), 1);
+
+    // Inventory is limited to the add-on quantity
+    assert!(cart
+        .update_add_on_quantities(
+            user.id,
+            &[UpdateAddOnItem {
+                add_on_id: add_on.id,
+                quantity: 4,
+            }],
+            false,
+            connection,
+        )
+        .is_err());
+
+    let mut cart = Order::find(cart.id, connection).unwrap();
+    cart.update_add_on_quantities(user.id, &[], true, connection)
+        .unwrap();
+    assert!(cart.items(connection).unwrap().is_empty());
+    assert_eq!(add_on.available_quantity(connection).unwrap(), 3);
+}
+
+#[test]
+fn purchase_issues_vouchers() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (add_on, _) = parking_add_on(&project, 10, true);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_add_on_quantities(
+        user.id,
+        &[UpdateAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 2,
+        }],
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let vouchers = AddOnVoucher::find_for_user(user.id, connection).unwrap();
+    assert_eq!(vouchers.len(), 2);
+    assert_eq!(vouchers[0].status, AddOnVoucherStatus::Purchased);
+    assert_eq!(add_on.available_quantity(connection).unwrap(), 8);
+}
+
+#[test]
+fn purchase_of_non_redeemable_add_on_issues_no_vouchers() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (add_on, _) = parking_add_on(&project, 10, false);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_add_on_quantities(
+        user.id,
+        &[UpdateAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 1,
+        }],
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    assert!(AddOnVoucher::find_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (add_on, event) = parking_add_on(&project, 10, true);
+    let user = project.create_user().finish();
+    let door_person = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_add_on_quantities(
+        user.id,
+        &[UpdateAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 1,
+        }],
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+
+    let voucher = AddOnVoucher::find_for_user(user.id, connection)
+        .unwrap()
+        .remove(0);
+    assert_eq!(voucher.event(connection).unwrap().id, event.id);
+    assert_eq!(
+        voucher
+            .redeem("invalid", door_person.id, connection)
+            .unwrap(),
+        AddOnVoucherRedeemResults::VoucherInvalid
+    );
+    assert_eq!(
+        voucher
+            .redeem(&voucher.redeem_key, door_person.id, connection)
+            .unwrap(),
+        AddOnVoucherRedeemResults::VoucherRedeemSuccess
+    );
+    // A concurrent scan holding the voucher as purchased cannot redeem it again
+    assert_eq!(voucher.status, AddOnVoucherStatus::Purchased);
+    assert_eq!(
+        voucher
+            .redeem(&voucher.redeem_key, door_person.id, connection)
+            .unwrap(),
+        AddOnVoucherRedeemResults::VoucherAlreadyRedeemed
+    );
+
+    let voucher = AddOnVoucher::find(voucher.id, connection).unwrap();
+    assert_eq!(voucher.status, AddOnVoucherStatus::Redeemed);
+    assert_eq!(voucher.redeemed_by_user_id, Some(door_person.id));
+    assert_eq!(
+        voucher
+            .redeem(&voucher.redeem_key, door_person.id, connection)
+            .unwrap(),
+        AddOnVoucherRedeemResults::VoucherAlreadyRedeemed
+    );
+}
+
+#[test]
+fn refund_voids_voucher() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (add_on, _) = parking_add_on(&project, 10, true);
+    let user = project.create_user().finish();
+    let door_person = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_add_on_quantities(
+        user.id,
+        &[UpdateAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 2,
+        }],
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+    let add_on_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::AddOn)
+        .unwrap();
+
+    cart.refund(
+        vec![RefundItem {
+            order_item_id: add_on_item.id,
+            ticket_instance_id: None,
+        }],
+        user.id,
+        connection,
+    )
+    .unwrap();
+
+    let vouchers = AddOnVoucher::find_for_user(user.id, connection).unwrap();
+    let voided: Vec<&AddOnVoucher> = vouchers
+        .iter()
+        .filter(|v| v.status == AddOnVoucherStatus::Voided)
+        .collect();
+    assert_eq!(voided.len(), 1);
+    assert_eq!(
+        voided[0]
+            .redeem(&voided[0].redeem_key, door_person.id, connection)
+            .unwrap(),
+        AddOnVoucherRedeemResults::VoucherInvalid
+    );
+    assert_eq!(add_on.available_quantity(connection).unwrap(), 9);
+}
@@ -0,0 +1,247 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use time::Duration;
+
+fn festival_bundle(project: &TestProject, quantity: i64) -> (Bundle, Vec<Event>) {
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let events: Vec<Event> = (0..2)
+        .map(|_| {
+            project
+                .create_event()
+                .with_organization(&organization)
+                .with_tickets()
+                .with_ticket_pricing()
+                .finish()
+        })
+        .collect();
+
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), None, quantity)
+        .commit(creator.id, connection)
+        .unwrap();
+    for event in &events {
+        let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+        bundle
+            .add_ticket_type(ticket_type.id, 1, connection)
+            .unwrap();
+    }
+    bundle
+        .add_pricing(
+            "Early Bird".to_string(),
+            Utc::now().naive_utc() - Duration::days(1),
+            Utc::now().naive_utc() + Duration::days(2),
+            5000,
+            connection,
+        )
+        .unwrap();
+    (bundle, events)
+}
+
+#[test]
+fn add_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, _) = festival_bundle(&project, 10);
+    assert_eq!(bundle.ticket_types(connection).unwrap().len(), 2);
+
+    // Ticket types of other organizations cannot be included
+    let other_event = project.create_event().with_tickets().finish();
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+    assert!(bundle
+        .add_ticket_type(other_ticket_type.id, 1, connection)
+        .is_err());
+}
+
+#[test]
+fn add_pricing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, _) = festival_bundle(&project, 10);
+    assert_eq!(
+        bundle
+            .current_pricing(connection)
+            .unwrap()
+            .unwrap()
+            .price_in_cents,
+        5000
+    );
+
+    // Pricing periods cannot overlap
+    assert!(bundle
+        .add_pricing(
+            "Overlapping".to_string(),
+            Utc::now().naive_utc(),
+            Utc::now().naive_utc() + Duration::days(5),
+            6000,
+            connection,
+        )
+        .is_err());
+    bundle
+        .add_pricing(
+            "Standard".to_string(),
+            Utc::now().naive_utc() + Duration::days(2),
+            Utc::now().naive_utc() + Duration::days(5),
+            6000,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(bundle.pricing(connection).unwrap().len(), 2);
+}
+
+#[test]
+fn add_to_cart() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, events) = festival_bundle(&project, 10);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    let bundle_item = bundle
+        .add_to_cart(&mut cart, 2, user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(bundle_item.item_type, OrderItemTypes::Bundle);
+    assert_eq!(bundle_item.quantity, 2);
+    // The bundle price is allocated across the included tickets
+    assert_eq!(bundle_item.unit_price_in_cents, 0);
+    assert!(bundle_item.find_fee_item(connection).unwrap().is_some());
+
+    let items = cart.items(connection).unwrap();
+    for event in &events {
+        let tickets_item = items
+            .iter()
+            .find(|i| i.item_type == OrderItemTypes::Tickets && i.event_id == Some(event.id))
+            .unwrap();
+        assert_eq!(tickets_item.parent_id, Some(bundle_item.id));
+        assert_eq!(tickets_item.unit_price_in_cents, 2500);
+        assert_eq!(tickets_item.calculate_quantity(connection).unwrap(), 2);
+        assert!(tickets_item.find_fee_item(connection).unwrap().is_none());
+    }
+    assert_eq!(bundle.available_quantity(connection).unwrap(), 8);
+
+    // Adding the bundle again replaces the existing quantity
+    bundle
+        .add_to_cart(&mut cart, 1, user.id, connection)
+        .unwrap();
+    assert_eq!(bundle.available_quantity(connection).unwrap(), 9);
+    assert_eq!(
+        cart.items(connection)
+            .unwrap()
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Bundle)
+            .count(),
+        1
+    );
+
+    cart.clear_cart(user.id, connection).unwrap();
+    assert!(cart.items(connection).unwrap().is_empty());
+    assert_eq!(bundle.available_quantity(connection).unwrap(), 10);
+}
+
+#[test]
+fn add_to_cart_inventory_limit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, _) = festival_bundle(&project, 1);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    assert!(bundle
+        .add_to_cart(&mut cart, 2, user.id, connection)
+        .is_err());
+
+    let mut cart = Order::find(cart.id, connection).unwrap();
+    bundle
+        .add_to_cart(&mut cart, 1, user.id, connection)
+        .unwrap();
+
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    assert!(bundle
+        .add_to_cart(&mut cart2, 1, user2.id, connection)
+        .is_err());
+}
+
+#[test]
+fn purchase_issues_tickets_for_each_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, events) = festival_bundle(&project, 10);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    bundle
+        .add_to_cart(&mut cart, 1, user.id, connection)
+        .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    assert!(total > 5000);
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+    for event in &events {
+        let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+        let event_tickets = cart.tickets(ticket_type.id, connection).unwrap();
+        assert_eq!(event_tickets.len(), 1);
+        assert_eq!(event_tickets[0].status, TicketInstanceStatus::Purchased);
+    }
+    assert_eq!(bundle.available_quantity(connection).unwrap(), 9);
+}
+
+#[test]
+fn refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, _) = festival_bundle(&project, 10);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let bundle_item = bundle
+        .add_to_cart(&mut cart, 2, user.id, connection)
+        .unwrap()
+        .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+
+    // Bundle tickets cannot be refunded on their own
+    let ticket = &TicketInstance::find_for_user(user.id, connection).unwrap()[0];
+    assert!(cart
+        .refund(
+            vec![RefundItem {
+                order_item_id: ticket.order_item_id.unwrap(),
+                ticket_instance_id: Some(ticket.id),
+            }],
+            user.id,
+            connection,
+        )
+        .is_err());
+
+    let refund_amount = cart
+        .refund(
+            vec![RefundItem {
+                order_item_id: bundle_item.id,
+                ticket_instance_id: None,
+            }],
+            user.id,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(refund_amount as i64, total / 2);
+    assert_eq!(cart.calculate_total(connection).unwrap(), total / 2);
+    assert_eq!(bundle.available_quantity(connection).unwrap(), 9);
+    // One ticket for each event was refunded with the bundle
+    assert_eq!(
+        TicketInstance::find_for_user(user.id, connection)
+            .unwrap()
+            .iter()
+            .filter(|t| t.status == TicketInstanceStatus::Purchased)
+            .count(),
+        2
+    );
+}
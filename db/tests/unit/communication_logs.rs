@@ -0,0 +1,145 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+fn create_log(
+    destination: &str,
+    main_table_id: Option<Uuid>,
+    connection: &PgConnection,
+) -> CommunicationLog {
+    CommunicationLog::create(
+        None,
+        CommunicationChannelType::Email,
+        Some("d-template".to_string()),
+        "Purchase Completed".to_string(),
+        destination.to_string(),
+        main_table_id.map(|_| Tables::Orders.to_string()),
+        main_table_id,
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap()
+}
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let order_id = Uuid::new_v4();
+    let log = create_log("fan@example.com", Some(order_id), connection);
+
+    assert_eq!(log.destination, "fan@example.com".to_string());
+    assert_eq!(log.main_table, Some(Tables::Orders.to_string()));
+    assert_eq!(log.main_table_id, Some(order_id));
+    assert_eq!(log.status, CommunicationLogStatus::Sent);
+    assert_eq!(log.provider_message_id, None);
+    assert_eq!(log, CommunicationLog::find(log.id, connection).unwrap());
+}
+
+#[test]
+fn create_blocked() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let mut new_log = CommunicationLog::create(
+        None,
+        CommunicationChannelType::Email,
+        None,
+        "Purchase Completed".to_string(),
+        "fan@example.com".to_string(),
+        None,
+        None,
+        json!({}),
+    );
+    new_log.status = CommunicationLogStatus::Blocked;
+    let log = new_log.commit(connection).unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Blocked);
+
+    // Provider events never apply to a communication that was not sent
+    let log = log
+        .update_status(CommunicationLogStatus::Delivered, None, connection)
+        .unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Blocked);
+}
+
+#[test]
+fn find_by_provider_message_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let log = create_log("fan@example.com", None, connection);
+    assert_eq!(
+        CommunicationLog::find_by_provider_message_id("SM123", connection).unwrap(),
+        None
+    );
+
+    let log = log.set_provider_message_id("SM123", connection).unwrap();
+    assert_eq!(
+        CommunicationLog::find_by_provider_message_id("SM123", connection).unwrap(),
+        Some(log)
+    );
+}
+
+#[test]
+fn search() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let order_id = Uuid::new_v4();
+    let log = create_log("fan1@example.com", Some(order_id), connection);
+    let log2 = create_log("fan2@example.com", Some(order_id), connection);
+    let log3 = create_log("fan1@example.com", None, connection);
+    let log3 = log3
+        .update_status(CommunicationLogStatus::Bounced, None, connection)
+        .unwrap();
+
+    let found =
+        CommunicationLog::search(Some("fan1".to_string()), None, None, 0, 100, connection).unwrap();
+    assert_eq!(found.paging.total, 2);
+    assert!(found.data.contains(&log));
+    assert!(found.data.contains(&log3));
+
+    let found = CommunicationLog::search(None, Some(order_id), None, 0, 100, connection).unwrap();
+    assert_eq!(found.paging.total, 2);
+    assert!(found.data.contains(&log));
+    assert!(found.data.contains(&log2));
+
+    let found = CommunicationLog::search(
+        None,
+        None,
+        Some(CommunicationLogStatus::Bounced),
+        0,
+        100,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(found.data, vec![log3]);
+
+    let found = CommunicationLog::search(None, None, None, 0, 1, connection).unwrap();
+    assert_eq!(found.paging.total, 3);
+    assert_eq!(found.data.len(), 1);
+}
+
+#[test]
+fn update_status() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let log = create_log("fan@example.com", None, connection);
+
+    let log = log
+        .update_status(CommunicationLogStatus::Opened, Some("message1"), connection)
+        .unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Opened);
+    assert_eq!(log.provider_message_id, Some("message1".to_string()));
+    assert!(log.status_updated_at.is_some());
+
+    // A delivered event arriving after the open does not move the status back
+    let log = log
+        .update_status(CommunicationLogStatus::Delivered, None, connection)
+        .unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Opened);
+    assert_eq!(log.provider_message_id, Some("message1".to_string()));
+
+    let log = log
+        .update_status(CommunicationLogStatus::Bounced, None, connection)
+        .unwrap();
+    assert_eq!(log.status, CommunicationLogStatus::Bounced);
+}
@@ -0,0 +1,252 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use time::Duration;
+
+fn disputed_order(project: &TestProject, event: &Event) -> (User, Order, Payment) {
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let payment = Payment::find_by_order(order.id, "blah", connection).unwrap();
+    (user, order, payment)
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let (_, order, payment) = disputed_order(&project, &event);
+
+    let dispute = Dispute::create(
+        &payment,
+        "dp_1".to_string(),
+        payment.amount,
+        Some("fraudulent".to_string()),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Open);
+    assert_eq!(dispute.order_id, order.id);
+    assert_eq!(dispute.held_ticket_instance_ids.len(), 2);
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, connection).unwrap() {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+    assert_eq!(
+        Dispute::find_by_external_reference("dp_1", connection).unwrap(),
+        Some(dispute)
+    );
+
+    let result = Dispute::create(&payment, "dp_2".to_string(), 0, None, None).commit(connection);
+    assert!(result.is_err());
+}
+
+#[test]
+fn update_status_won_reinstates_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let (_, order, payment) = disputed_order(&project, &event);
+    let dispute = Dispute::create(&payment, "dp_1".to_string(), payment.amount, None, None)
+        .commit(connection)
+        .unwrap();
+
+    let dispute = dispute
+        .update_status(DisputeStatus::EvidenceSubmitted, connection)
+        .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::EvidenceSubmitted);
+    assert!(dispute.closed_at.is_none());
+
+    let dispute = dispute
+        .update_status(DisputeStatus::Won, connection)
+        .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::Won);
+    assert!(dispute.closed_at.is_some());
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, connection).unwrap() {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+        assert!(ticket.redeem_key.is_some());
+    }
+
+    // Closed disputes cannot be reopened
+    assert!(dispute
+        .update_status(DisputeStatus::Open, connection)
+        .is_err());
+}
+
+#[test]
+fn update_status_lost_keeps_tickets_for_refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let (_, order, payment) = disputed_order(&project, &event);
+    let dispute = Dispute::create(&payment, "dp_1".to_string(), payment.amount, None, None)
+        .commit(connection)
+        .unwrap();
+
+    let dispute = dispute
+        .update_status(DisputeStatus::Lost, connection)
+        .unwrap();
+    assert!(dispute.closed_at.is_some());
+    let refunded_tickets = order.refund_all_tickets(None, connection).unwrap();
+    assert_eq!(refunded_tickets.len(), 2);
+    for ticket_id in TicketInstance::find_ids_for_order(order.id, connection).unwrap() {
+        let ticket = TicketInstance::find(ticket_id, connection).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+}
+
+#[test]
+fn submit_evidence() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let (_, _, payment) = disputed_order(&project, &event);
+    let dispute = Dispute::create(&payment, "dp_1".to_string(), payment.amount, None, None)
+        .commit(connection)
+        .unwrap();
+
+    let dispute = dispute
+        .submit_evidence("Signed delivery receipt".to_string(), connection)
+        .unwrap();
+    assert_eq!(dispute.status, DisputeStatus::EvidenceSubmitted);
+    assert_eq!(
+        dispute.evidence_notes,
+        Some("Signed delivery receipt".to_string())
+    );
+    assert!(dispute.evidence_submitted_at.is_some());
+
+    let dispute = dispute
+        .update_status(DisputeStatus::Won, connection)
+        .unwrap();
+    let result = dispute.submit_evidence("Late evidence".to_string(), connection);
+    assert!(result.is_err());
+}
+
+#[test]
+fn belongs_to_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let other_organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let (_, _, payment) = disputed_order(&project, &event);
+    let dispute = Dispute::create(&payment, "dp_1".to_string(), payment.amount, None, None)
+        .commit(connection)
+        .unwrap();
+
+    assert!(dispute
+        .belongs_to_organization(organization.id, connection)
+        .unwrap());
+    assert!(!dispute
+        .belongs_to_organization(other_organization.id, connection)
+        .unwrap());
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let (user, _, payment) = disputed_order(&project, &event);
+    let (_, _, other_payment) = disputed_order(&project, &other_event);
+    let dispute = Dispute::create(&payment, "dp_1".to_string(), payment.amount, None, None)
+        .commit(connection)
+        .unwrap();
+    Dispute::create(
+        &other_payment,
+        "dp_2".to_string(),
+        other_payment.amount,
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let disputes = Dispute::find_for_organization(organization.id, None, connection).unwrap();
+    assert_eq!(disputes.len(), 1);
+    assert_eq!(disputes[0].id, dispute.id);
+    assert_eq!(disputes[0].user_id, user.id);
+    assert_eq!(disputes[0].email, user.email);
+
+    let disputes =
+        Dispute::find_for_organization(organization.id, Some(DisputeStatus::Open), connection)
+            .unwrap();
+    assert_eq!(disputes.len(), 1);
+    let disputes =
+        Dispute::find_for_organization(organization.id, Some(DisputeStatus::Won), connection)
+            .unwrap();
+    assert!(disputes.is_empty());
+}
+
+#[test]
+fn amounts_per_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let (_, _, payment) = disputed_order(&project, &event);
+    let (_, _, won_payment) = disputed_order(&project, &event);
+    Dispute::create(&payment, "dp_1".to_string(), 1000, None, None)
+        .commit(connection)
+        .unwrap();
+    Dispute::create(&won_payment, "dp_2".to_string(), 1000, None, None)
+        .commit(connection)
+        .unwrap()
+        .update_status(DisputeStatus::Won, connection)
+        .unwrap();
+
+    let start_time = Utc::now().naive_utc() - Duration::days(1);
+    let end_time = Utc::now().naive_utc() + Duration::days(1);
+    let amounts =
+        Dispute::amounts_per_event(organization.id, start_time, end_time, connection).unwrap();
+    assert_eq!(amounts.get(&event.id), Some(&(1000, 0)));
+
+    let transactions = Settlement::create_base_transactions(
+        None,
+        organization.id,
+        start_time,
+        end_time,
+        connection,
+    )
+    .unwrap();
+    let withheld: Vec<&NewSettlementTransaction> = transactions
+        .iter()
+        .filter(|t| t.comment == Some("Disputed Amount Withheld".to_string()))
+        .collect();
+    assert_eq!(withheld.len(), 1);
+    assert_eq!(withheld[0].event_id, event.id);
+    assert_eq!(withheld[0].value_in_cents, -1000);
+
+    // Nothing is withheld outside of the period the dispute was opened in
+    let amounts = Dispute::amounts_per_event(
+        organization.id,
+        end_time,
+        end_time + Duration::days(7),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(amounts.get(&event.id), Some(&(0, 0)));
+}
@@ -49,3 +49,43 @@ fn has_pending_action() {
     .unwrap();
     assert!(result);
 }
+
+#[test]
+fn set_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let now = Utc::now().naive_utc();
+    let create_action = |action_type| {
+        DomainAction::create(
+            None,
+            action_type,
+            None,
+            json!({}),
+            None,
+            None,
+            now,
+            now + Duration::days(1),
+            8,
+        )
+        .commit(connection)
+        .unwrap()
+    };
+
+    // Other actions are retried once the current checkout lapses
+    let domain_action = create_action(DomainActionTypes::Communication);
+    domain_action.set_busy(60, connection).unwrap();
+    let blocked_until = DomainAction::find(domain_action.id, connection)
+        .unwrap()
+        .blocked_until;
+    let domain_action = domain_action.set_failed("failed", connection).unwrap();
+    assert_eq!(domain_action.attempt_count, 1);
+    assert_eq!(domain_action.blocked_until, blocked_until);
+
+    // Webhook deliveries back off
+    let domain_action = create_action(DomainActionTypes::SendWebhook);
+    domain_action.set_busy(60, connection).unwrap();
+    let domain_action = domain_action.set_failed("failed", connection).unwrap();
+    let domain_action = domain_action.set_failed("failed", connection).unwrap();
+    assert_eq!(domain_action.attempt_count, 2);
+    assert!(domain_action.blocked_until > now + Duration::seconds(110));
+}
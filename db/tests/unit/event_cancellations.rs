@@ -0,0 +1,254 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+fn event_with_fees(project: &TestProject, refund_fees_on_cancellation: bool) -> Event {
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_event_fee()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    if !refund_fees_on_cancellation {
+        organization
+            .update(
+                OrganizationEditableAttributes {
+                    refund_fees_on_cancellation: Some(false),
+                    ..Default::default()
+                },
+                &"encryption_key".to_string(),
+                connection,
+            )
+            .unwrap();
+    }
+    project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish()
+}
+
+fn assert_tickets_nullified(order: &Order, event: &Event, connection: &PgConnection) {
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    for ticket in order.tickets(ticket_type.id, connection).unwrap() {
+        assert_eq!(ticket.status, TicketInstanceStatus::Nullified);
+    }
+}
+
+fn fees_in_cents(order: &Order, connection: &PgConnection) -> i64 {
+    order
+        .items(connection)
+        .unwrap()
+        .iter()
+        .filter(|i| {
+            i.item_type == OrderItemTypes::PerUnitFees || i.item_type == OrderItemTypes::EventFees
+        })
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum()
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = event_with_fees(&project, true);
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let order2 = project
+        .create_order()
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    // Unpaid carts are not refunded
+    project
+        .create_order()
+        .for_event(&event)
+        .quantity(1)
+        .finish();
+
+    let event = event.cancel(user.id, connection).unwrap();
+    assert!(event.cancelled_at.is_some());
+
+    let cancellation = event.cancellation(connection).unwrap();
+    assert_eq!(cancellation.status, EventCancellationStatus::InProgress);
+    assert!(cancellation.refund_fees);
+    assert_eq!(cancellation.created_by_user_id, user.id);
+    let mut order_ids: Vec<Uuid> = cancellation
+        .orders(connection)
+        .unwrap()
+        .iter()
+        .map(|o| o.order_id)
+        .collect();
+    order_ids.sort();
+    let mut expected_order_ids = vec![order.id, order2.id];
+    expected_order_ids.sort();
+    assert_eq!(order_ids, expected_order_ids);
+    assert!(DomainAction::has_pending_action(
+        DomainActionTypes::ProcessEventCancellation,
+        Tables::EventCancellations.to_string(),
+        cancellation.id,
+        connection,
+    )
+    .unwrap());
+
+    // Events can only be cancelled once
+    assert!(event.cancel(user.id, connection).is_err());
+}
+
+#[test]
+fn refund_cancelled_event_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = event_with_fees(&project, true);
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+
+    let amount_refunded = order
+        .refund_cancelled_event_tickets(event.id, true, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(amount_refunded as i64, total);
+    assert_eq!(order.calculate_total(connection).unwrap(), 0);
+    assert_tickets_nullified(&order, &event, connection);
+
+    // Already refunded tickets are skipped
+    let amount_refunded = order
+        .refund_cancelled_event_tickets(event.id, true, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(amount_refunded, 0);
+}
+
+#[test]
+fn refund_cancelled_event_tickets_without_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = event_with_fees(&project, false);
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let fees = fees_in_cents(&order, connection);
+    assert!(fees > 0);
+
+    let amount_refunded = order
+        .refund_cancelled_event_tickets(event.id, false, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(amount_refunded as i64, total - fees);
+    assert_eq!(order.calculate_total(connection).unwrap(), fees);
+    assert_tickets_nullified(&order, &event, connection);
+}
+
+#[test]
+fn refund_cancelled_event_add_ons() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = event_with_fees(&project, true);
+    let add_on = AddOn::create(event.id, "Parking".to_string(), None, 1500, 10, false)
+        .commit(user.id, connection)
+        .unwrap();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_add_on_quantities(
+        user.id,
+        &[UpdateAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 2,
+        }],
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+
+    let amount_refunded = cart
+        .refund_cancelled_event_tickets(event.id, true, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(amount_refunded as i64, total);
+    assert_eq!(cart.calculate_total(connection).unwrap(), 0);
+    assert_eq!(add_on.available_quantity(connection).unwrap(), 10);
+}
+
+#[test]
+fn progress() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = event_with_fees(&project, false);
+    project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+
+    let event = event.cancel(user.id, connection).unwrap();
+    let cancellation = event.cancellation(connection).unwrap();
+    assert!(!cancellation.refund_fees);
+    let cancellation_orders = cancellation.orders(connection).unwrap();
+    cancellation_orders[0]
+        .mark_refunded(1500, user.id, connection)
+        .unwrap();
+
+    // Failed refunds are retried until they run out of attempts
+    let mut failed_order = cancellation_orders[1].clone();
+    for attempt in 1..=EVENT_CANCELLATION_MAX_REFUND_ATTEMPTS {
+        assert_eq!(
+            cancellation.pending_orders(10, connection).unwrap(),
+            vec![failed_order.clone()]
+        );
+        failed_order = failed_order
+            .mark_failed("Card declined", connection)
+            .unwrap();
+        assert_eq!(failed_order.attempt_count, attempt);
+    }
+    assert_eq!(failed_order.status, EventCancellationOrderStatus::Failed);
+    assert!(cancellation
+        .pending_orders(10, connection)
+        .unwrap()
+        .is_empty());
+
+    let cancellation = cancellation.complete_if_finished(connection).unwrap();
+    assert_eq!(
+        cancellation.status,
+        EventCancellationStatus::CompletedWithFailures
+    );
+    assert!(cancellation.completed_at.is_some());
+
+    let progress = cancellation.progress(connection).unwrap();
+    assert_eq!(progress.order_count, 2);
+    assert_eq!(progress.pending_order_count, 0);
+    assert_eq!(progress.refunded_order_count, 1);
+    assert_eq!(progress.failed_order_count, 1);
+    assert_eq!(progress.refunded_amount_in_cents, 1500);
+    assert_eq!(progress.failed_orders, vec![failed_order]);
+    assert_eq!(
+        progress.failed_orders[0].last_failure_reason,
+        Some("Card declined".to_string())
+    );
+}
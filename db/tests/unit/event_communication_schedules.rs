@@ -0,0 +1,249 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+fn create_schedule(
+    organization_id: Uuid,
+    timing: EventCommunicationTimings,
+    offset_hours: i32,
+    connection: &PgConnection,
+) -> EventCommunicationSchedule {
+    EventCommunicationSchedule::create(
+        organization_id,
+        timing,
+        offset_hours,
+        CommunicationChannelType::Email,
+        "See you soon at {event_name}".to_string(),
+        "Doors open {door_time} at {venue_name}".to_string(),
+        None,
+    )
+    .commit(connection)
+    .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event_start = Utc::now().naive_utc() + Duration::days(7);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(event_start)
+        .finish();
+    let draft_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_status(EventStatus::Draft)
+        .with_event_start(event_start)
+        .finish();
+
+    let schedule = create_schedule(
+        organization.id,
+        EventCommunicationTimings::AfterEvent,
+        24,
+        connection,
+    );
+    assert_eq!(schedule.organization_id, organization.id);
+    assert_eq!(
+        EventCommunicationSchedule::find_for_organization(organization.id, connection).unwrap(),
+        vec![schedule.clone()]
+    );
+
+    // Published upcoming events are sent the new communication
+    assert!(DomainAction::has_pending_action(
+        DomainActionTypes::SendEventCommunication,
+        Tables::Events.to_string(),
+        event.id,
+        connection,
+    )
+    .unwrap());
+    assert!(!DomainAction::has_pending_action(
+        DomainActionTypes::SendEventCommunication,
+        Tables::Events.to_string(),
+        draft_event.id,
+        connection,
+    )
+    .unwrap());
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let result = EventCommunicationSchedule::create(
+        organization.id,
+        EventCommunicationTimings::BeforeDoors,
+        -2,
+        CommunicationChannelType::Email,
+        "Reminder".to_string(),
+        "See you soon".to_string(),
+        None,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("offset_hours"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = EventCommunicationSchedule::create(
+        organization.id,
+        EventCommunicationTimings::AfterEvent,
+        2,
+        CommunicationChannelType::Sms,
+        "Thanks for coming".to_string(),
+        "Tell us how it went".to_string(),
+        None,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("channel"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let schedule = create_schedule(
+        organization.id,
+        EventCommunicationTimings::AfterEvent,
+        12,
+        connection,
+    );
+
+    schedule.destroy(connection).unwrap();
+    assert!(EventCommunicationSchedule::find(schedule.id, connection).is_err());
+    assert!(
+        EventCommunicationSchedule::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn send_at() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event_start = NaiveDate::from_ymd(2054, 7, 8).and_hms(20, 0, 0);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(event_start)
+        .finish();
+    let event = event
+        .update(
+            EventEditableAttributes {
+                door_time: Some(event_start - Duration::hours(1)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    let reminder = create_schedule(
+        organization.id,
+        EventCommunicationTimings::BeforeDoors,
+        2,
+        connection,
+    );
+    assert_eq!(
+        reminder.send_at(&event),
+        Some(event_start - Duration::hours(3))
+    );
+
+    // Follow-ups fall back to the event start when the event has no end
+    let follow_up = create_schedule(
+        organization.id,
+        EventCommunicationTimings::AfterEvent,
+        12,
+        connection,
+    );
+    assert_eq!(
+        follow_up.send_at(&event),
+        Some(event_start + Duration::hours(12))
+    );
+    let event = event
+        .update(
+            EventEditableAttributes {
+                event_end: Some(event_start + Duration::hours(4)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        follow_up.send_at(&event),
+        Some(event_start + Duration::hours(16))
+    );
+}
+
+#[test]
+fn title_for_and_message_for() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let venue = project
+        .create_venue()
+        .with_name("The Venue".to_string())
+        .with_timezone("America/New_York".to_string())
+        .finish();
+    let event_start = NaiveDate::from_ymd(2030, 7, 8).and_hms(23, 0, 0);
+    let event = project
+        .create_event()
+        .with_name("Show".to_string())
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_event_start(event_start)
+        .finish();
+    let event = event
+        .update(
+            EventEditableAttributes {
+                door_time: Some(event_start - Duration::hours(1)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let schedule = create_schedule(
+        organization.id,
+        EventCommunicationTimings::BeforeDoors,
+        24,
+        connection,
+    );
+
+    let venue = Some(venue);
+    assert_eq!(
+        schedule.title_for(&event, &venue),
+        "See you soon at Show".to_string()
+    );
+    // Door time is shown in the venue's timezone
+    assert_eq!(
+        schedule.message_for(&event, &venue),
+        "Doors open Monday, July  8 at  6:00 PM EDT at The Venue".to_string()
+    );
+    assert_eq!(
+        schedule.message_for(&event, &None),
+        "Doors open 2030-07-08 22:00 UTC at ".to_string()
+    );
+}
@@ -0,0 +1,184 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use time::Duration;
+
+#[test]
+fn reschedule() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().finish();
+    let previous_start = event.event_start.unwrap();
+    let event = event
+        .update(
+            EventEditableAttributes {
+                door_time: Some(previous_start - Duration::hours(1)),
+                event_end: Some(previous_start + Duration::hours(3)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    let event_start = NaiveDate::from_ymd(2030, 1, 2).and_hms(20, 0, 0);
+    let refund_deadline = Utc::now().naive_utc() + Duration::days(7);
+    let (updated_event, reschedule) = event
+        .reschedule(
+            event_start,
+            None,
+            None,
+            refund_deadline,
+            user.id,
+            connection,
+        )
+        .unwrap();
+
+    // Door time and event end keep their offset from the start
+    assert_eq!(updated_event.event_start, Some(event_start));
+    assert_eq!(
+        updated_event.door_time,
+        Some(event_start - Duration::hours(1))
+    );
+    assert_eq!(
+        updated_event.event_end,
+        Some(event_start + Duration::hours(3))
+    );
+    assert_eq!(
+        updated_event.override_status,
+        Some(EventOverrideStatus::Rescheduled)
+    );
+
+    assert_eq!(reschedule.event_id, event.id);
+    assert_eq!(reschedule.previous_event_start, event.event_start);
+    assert_eq!(reschedule.previous_door_time, event.door_time);
+    assert_eq!(reschedule.previous_event_end, event.event_end);
+    assert_eq!(reschedule.event_start, event_start);
+    assert_eq!(reschedule.refund_deadline, refund_deadline);
+    assert!(reschedule.is_refundable());
+    assert_eq!(
+        updated_event.reschedules(connection).unwrap(),
+        vec![reschedule]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Events,
+        Some(event.id),
+        Some(DomainEventTypes::EventRescheduled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn reschedule_with_invalid_data() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().finish();
+    let event_start = NaiveDate::from_ymd(2030, 1, 2).and_hms(20, 0, 0);
+
+    // Refund deadline has already passed
+    let result = event.reschedule(
+        event_start,
+        None,
+        None,
+        Utc::now().naive_utc() - Duration::days(1),
+        user.id,
+        connection,
+    );
+    assert!(result.is_err());
+
+    // Cancelled events cannot be rescheduled
+    let event = event.cancel(user.id, connection).unwrap();
+    let result = event.reschedule(
+        event_start,
+        None,
+        None,
+        Utc::now().naive_utc() + Duration::days(1),
+        user.id,
+        connection,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn refundable_tickets_for_user_and_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user2)
+        .quantity(1)
+        .is_paid()
+        .finish();
+
+    let (_, reschedule) = event
+        .reschedule(
+            NaiveDate::from_ymd(2030, 1, 2).and_hms(20, 0, 0),
+            None,
+            None,
+            Utc::now().naive_utc() + Duration::days(7),
+            creator.id,
+            connection,
+        )
+        .unwrap();
+
+    let tickets_per_order = reschedule
+        .refundable_tickets_for_user(user.id, connection)
+        .unwrap();
+    assert_eq!(tickets_per_order.len(), 1);
+    assert_eq!(tickets_per_order[&order.id].len(), 2);
+    assert!(reschedule
+        .refundable_tickets_for_user(creator.id, connection)
+        .unwrap()
+        .is_empty());
+
+    // Both holders kept their tickets
+    let report = reschedule.report(connection).unwrap();
+    assert_eq!(report.len(), 2);
+    assert!(report.iter().all(|r| r.tickets_kept > 0 && !r.refunded));
+
+    // Refund one of the holders
+    let ticket_instance_ids = tickets_per_order[&order.id].clone();
+    let plan = RefundPlan::for_tickets(&order, &ticket_instance_ids, &[], connection).unwrap();
+    NewEventRescheduleRefund {
+        event_reschedule_id: reschedule.id,
+        user_id: user.id,
+        order_id: order.id,
+        ticket_instance_ids,
+        refunded_amount_in_cents: plan.amount_in_cents,
+    }
+    .commit(connection)
+    .unwrap();
+    order.refund(plan.items, user.id, connection).unwrap();
+
+    let report = reschedule.report(connection).unwrap();
+    assert_eq!(report.len(), 2);
+    let refunded_row = report.iter().find(|r| r.user_id == user.id).unwrap();
+    assert!(refunded_row.refunded);
+    assert_eq!(refunded_row.tickets_kept, 0);
+    assert_eq!(refunded_row.tickets_refunded, 2);
+    assert_eq!(refunded_row.refunded_amount_in_cents, plan.amount_in_cents);
+    let kept_row = report.iter().find(|r| r.user_id == user2.id).unwrap();
+    assert!(!kept_row.refunded);
+    assert_eq!(kept_row.tickets_kept, 1);
+    assert_eq!(reschedule.refunds(connection).unwrap().len(), 1);
+}
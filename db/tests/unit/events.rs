@@ -54,6 +54,63 @@ fn update() {
     );
 }
 
+#[test]
+fn update_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(
+            &project
+                .create_fee_schedule()
+                .finish(project.create_user().finish().id),
+        )
+        .finish();
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                currency: Some("eur".to_string()),
+                ..Default::default()
+            },
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    // Events are priced in the organization currency unless specified
+    assert_eq!(event.currency, "eur".to_string());
+
+    let event = event
+        .update(
+            EventEditableAttributes {
+                currency: Some("gbp".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(event.currency, "gbp".to_string());
+
+    // Currency is fixed once tickets have been added to orders
+    project
+        .create_order()
+        .for_event(&event)
+        .quantity(1)
+        .finish();
+    let result = event.update(
+        EventEditableAttributes {
+            currency: Some("usd".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    assert!(result.is_err());
+}
+
 #[test]
 fn guest_list() {
     let project = TestProject::new();
@@ -157,6 +214,152 @@ fn publish() {
     assert!(event.publish_date.is_some());
 }
 
+#[test]
+fn queue_event_day_reminders() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event_start = NaiveDate::from_ymd(2054, 7, 8).and_hms(20, 0, 0);
+    let event = project
+        .create_event()
+        .with_event_start(event_start)
+        .finish();
+
+    let domain_action = event
+        .queue_event_day_reminders(connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        domain_action.domain_action_type,
+        DomainActionTypes::SendEventDayReminders
+    );
+    assert_eq!(domain_action.main_table_id, Some(event.id));
+    assert_eq!(domain_action.scheduled_at, event_start - Duration::hours(6));
+    assert_eq!(domain_action.expires_at, event_start);
+
+    // No reminder once the reminder time has passed
+    let event = project
+        .create_event()
+        .with_event_start(Utc::now().naive_utc() + Duration::hours(1))
+        .finish();
+    assert!(event
+        .queue_event_day_reminders(connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn ticket_holder_ids() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    assert!(event.ticket_holder_ids(connection).unwrap().is_empty());
+
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    // Unpaid orders do not hold tickets
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user2)
+        .quantity(1)
+        .finish();
+
+    assert_eq!(event.ticket_holder_ids(connection).unwrap(), vec![user.id]);
+}
+
+#[test]
+fn requeue_scheduled_communications() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let venue = project.create_venue().finish();
+    let user = project.create_user().finish();
+    let event_start = NaiveDate::from_ymd(2054, 7, 8).and_hms(20, 0, 0);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_status(EventStatus::Draft)
+        .with_event_start(event_start)
+        .finish();
+    let schedule = EventCommunicationSchedule::create(
+        organization.id,
+        EventCommunicationTimings::AfterEvent,
+        12,
+        CommunicationChannelType::Email,
+        "Thanks for coming".to_string(),
+        "Tell us how {event_name} went".to_string(),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let has_pending_communication = |event: &Event| {
+        DomainAction::has_pending_action(
+            DomainActionTypes::SendEventCommunication,
+            Tables::Events.to_string(),
+            event.id,
+            connection,
+        )
+        .unwrap()
+    };
+    assert!(!has_pending_communication(&event));
+
+    // Publishing queues the day reminder and the organization's communications
+    let event = event.publish(connection).unwrap();
+    assert!(has_pending_communication(&event));
+    assert!(DomainAction::has_pending_action(
+        DomainActionTypes::SendEventDayReminders,
+        Tables::Events.to_string(),
+        event.id,
+        connection,
+    )
+    .unwrap());
+
+    // Moving the event replaces the queued communication with one for the new time
+    let queued = event
+        .queue_scheduled_communication(&schedule, connection)
+        .unwrap()
+        .unwrap();
+    let new_start = event_start + Duration::days(2);
+    let event = event
+        .update(
+            EventEditableAttributes {
+                event_start: Some(new_start),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        DomainAction::find(queued.id, connection).unwrap().status,
+        DomainActionStatus::Cancelled
+    );
+    assert!(has_pending_communication(&event));
+    let requeued = event
+        .queue_scheduled_communication(&schedule, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(requeued.scheduled_at, new_start + Duration::hours(12));
+
+    // Unpublished and cancelled events are not sent anything
+    event.unpublish(connection).unwrap();
+    assert!(!has_pending_communication(&event));
+    let event = Event::find(event.id, connection)
+        .unwrap()
+        .publish(connection)
+        .unwrap();
+    assert!(has_pending_communication(&event));
+    let event = event.cancel(user.id, connection).unwrap();
+    assert!(!has_pending_communication(&event));
+}
+
 #[test]
 fn publish_in_future() {
     //create event
@@ -321,7 +524,7 @@ fn cancel() {
         .with_venue(&venue)
         .finish();
 
-    let event = event.cancel(&project.get_connection()).unwrap();
+    let event = event.cancel(user.id, &project.get_connection()).unwrap();
     assert!(!event.cancelled_at.is_none());
 }
 
@@ -355,6 +558,7 @@ fn get_sales_by_date_range() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -375,6 +579,7 @@ fn get_sales_by_date_range() {
                 ticket_type_id: ticket_type.id,
                 quantity: 5,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -511,11 +716,13 @@ fn find_by_order_item_ids() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1434,7 +1641,7 @@ fn find_active_for_venue() {
         .add_artist(artist1.id, project.get_connection())
         .unwrap();
     //Cancel first event
-    event.cancel(connection).unwrap();
+    event.cancel(user.id, connection).unwrap();
 
     //find all active events via venue
     let found_events =
@@ -0,0 +1,77 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::spreadsheets::Cell;
+
+#[test]
+fn guest_list_to_worksheets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_name("Guest Event".to_string())
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+
+    let guests = event.guest_list("", connection).unwrap();
+    let worksheets = guests.to_worksheets(connection).unwrap();
+    assert_eq!(worksheets.len(), 1);
+    let worksheet = &worksheets[0];
+    assert_eq!(worksheet.name, "Guests");
+    assert_eq!(worksheet.rows.len(), 2);
+    for row in worksheet.rows.iter() {
+        assert_eq!(row.len(), worksheet.headers.len());
+        assert_eq!(row[0], Cell::text("Guest Event"));
+        assert_eq!(row[9], Cell::optional_text(&user.email));
+    }
+}
+
+#[test]
+fn fans_to_worksheets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+
+    let fans = organization
+        .search_fans(
+            None,
+            0,
+            100,
+            FanSortField::FirstName,
+            SortingDir::Asc,
+            connection,
+        )
+        .unwrap();
+    let worksheets = fans.data.to_worksheets(connection).unwrap();
+    assert_eq!(worksheets.len(), 1);
+    let worksheet = &worksheets[0];
+    assert_eq!(worksheet.name, "Fans");
+    assert_eq!(worksheet.rows.len(), 1);
+    assert_eq!(worksheet.rows[0][0], Cell::optional_text(&user.first_name));
+    assert_eq!(worksheet.rows[0][4], Cell::Number(1));
+    assert_eq!(
+        worksheet.rows[0][5],
+        Cell::optional_money(fans.data[0].revenue_in_cents)
+    );
+}
@@ -225,6 +225,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(comp.redemption_code),
+            seat_ids: None,
         }],
         false,
         false,
@@ -275,11 +276,13 @@ fn remove_available_quantity() {
                 ticket_type_id: ticket_type.id,
                 quantity: 4,
                 redemption_code: Some(hold.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: Some(child_hold.redemption_code.clone()),
+                seat_ids: None,
             },
         ],
         false,
@@ -299,6 +302,7 @@ fn remove_available_quantity() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(hold.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
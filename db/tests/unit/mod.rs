@@ -1,29 +1,50 @@
+pub mod add_ons;
 pub mod artists;
 pub mod assets;
+pub mod bundles;
 pub mod codes;
+pub mod communication_logs;
 pub mod comps;
 pub mod concerns;
+pub mod disputes;
 pub mod domain_actions;
 pub mod domain_events;
 pub mod event_artists;
+pub mod event_cancellations;
+pub mod event_communication_schedules;
 pub mod event_interest;
+pub mod event_reschedules;
 pub mod events;
+pub mod exports;
 pub mod fee_schedule_ranges;
 pub mod fee_schedules;
 pub mod holds;
+pub mod offline_redemptions;
+pub mod order_exchanges;
 pub mod order_items;
 pub mod orders;
+pub mod organization_email_templates;
 pub mod organization_invites;
 pub mod organization_users;
 pub mod organizations;
 pub mod payment_methods;
+pub mod price_zones;
 pub mod push_notification_tokens;
 pub mod refunded_tickets;
+pub mod refund_plans;
 pub mod regions;
+pub mod report_subscriptions;
+pub mod resale_listings;
+pub mod resale_payouts;
+pub mod seating_sections;
 pub mod stages;
+pub mod tax_rates;
 pub mod ticket_instances;
 pub mod ticket_pricing;
 pub mod ticket_type_codes;
 pub mod ticket_types;
+pub mod user_two_factor_credentials;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
+pub mod webhook_subscriptions;
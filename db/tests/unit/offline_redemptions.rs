@@ -0,0 +1,252 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use time::Duration;
+
+const SCANNER_SECRET_KEY: &str = "1b23337c6215bce99f6bc74426c997d9c47c73688b8238a31a9960b89b93d613";
+
+fn event_with_purchased_tickets(
+    project: &TestProject,
+    quantity: u32,
+) -> (User, Event, Vec<TicketInstance>) {
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(admin.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(quantity)
+        .is_paid()
+        .finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+
+    (admin, event, tickets)
+}
+
+#[test]
+fn scanner_manifest() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (admin, event, tickets) = event_with_purchased_tickets(&project, 2);
+    let ticket = &tickets[0];
+    TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        admin.id,
+        connection,
+    )
+    .unwrap();
+
+    let manifest =
+        ScannerManifest::for_event(&event, &SCANNER_SECRET_KEY.to_string(), connection).unwrap();
+    assert_eq!(manifest.event_id, event.id);
+    assert_eq!(manifest.tickets.len(), 2);
+    let manifest_ticket = manifest.tickets.iter().find(|t| t.id == ticket.id).unwrap();
+    assert_eq!(manifest_ticket.status, TicketInstanceStatus::Redeemed);
+    assert_eq!(
+        manifest_ticket.ticket_type_id,
+        ticket.ticket_type(connection).unwrap().id
+    );
+    assert_eq!(
+        manifest_ticket.redeem_key_hash,
+        ScannerManifest::hash_redeem_key(ticket.id, ticket.redeem_key.as_ref().unwrap())
+    );
+    assert_ne!(
+        &manifest_ticket.redeem_key_hash,
+        ticket.redeem_key.as_ref().unwrap()
+    );
+    assert_eq!(
+        manifest.redeem_key_hash_iterations,
+        REDEEM_KEY_HASH_ITERATIONS
+    );
+    // Salted per ticket, the same key hashes differently for another ticket
+    assert_ne!(
+        manifest_ticket.redeem_key_hash,
+        ScannerManifest::hash_redeem_key(tickets[1].id, ticket.redeem_key.as_ref().unwrap())
+    );
+    assert!(manifest.verify());
+
+    // Signed with the scanner key rather than the organization wallet
+    let wallet = Wallet::find_default_for_organization(event.organization_id, connection).unwrap();
+    assert_ne!(manifest.public_key, wallet.public_key);
+
+    // Tampering invalidates the signature
+    let mut tampered = manifest.clone();
+    tampered.tickets[0].status = TicketInstanceStatus::Purchased;
+    tampered.tickets[1].status = TicketInstanceStatus::Purchased;
+    assert!(!tampered.verify());
+}
+
+#[test]
+fn reconcile() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (admin, event, tickets) = event_with_purchased_tickets(&project, 2);
+    let other_event = project.create_event().finish();
+    let now = NaiveDateTime::from_timestamp(Utc::now().timestamp(), 0);
+
+    let scans = vec![
+        OfflineScan {
+            ticket_instance_id: Some(tickets[0].id),
+            add_on_voucher_id: None,
+            redeem_key: tickets[0].redeem_key.clone().unwrap(),
+            scanned_at: now,
+        },
+        OfflineScan {
+            ticket_instance_id: Some(tickets[1].id),
+            add_on_voucher_id: None,
+            redeem_key: "WrongKey".to_string(),
+            scanned_at: now,
+        },
+    ];
+    let result =
+        OfflineRedemption::reconcile(event.id, "door-1", scans.clone(), admin.id, connection)
+            .unwrap();
+    assert_eq!(result.redeemed, vec![tickets[0].id]);
+    assert_eq!(result.conflicts.len(), 1);
+    assert_eq!(result.conflicts[0].ticket_instance_id, Some(tickets[1].id));
+    assert_eq!(result.conflicts[0].status, OfflineRedemptionStatus::Invalid);
+    assert_eq!(
+        TicketInstance::find(tickets[0].id, connection)
+            .unwrap()
+            .status,
+        TicketInstanceStatus::Redeemed
+    );
+
+    // Retrying the same upload does not report the device's own scan as a conflict
+    let result = OfflineRedemption::reconcile(
+        event.id,
+        "door-1",
+        vec![scans[0].clone()],
+        admin.id,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, ScannerSyncResult::default());
+
+    // The same ticket scanned at another door is reported with the earlier scan
+    let scans = vec![OfflineScan {
+        ticket_instance_id: Some(tickets[0].id),
+        add_on_voucher_id: None,
+        redeem_key: tickets[0].redeem_key.clone().unwrap(),
+        scanned_at: now + Duration::minutes(5),
+    }];
+    let result =
+        OfflineRedemption::reconcile(event.id, "door-2", scans, admin.id, connection).unwrap();
+    assert!(result.redeemed.is_empty());
+    assert_eq!(
+        result.conflicts,
+        vec![ScanConflict {
+            ticket_instance_id: Some(tickets[0].id),
+            add_on_voucher_id: None,
+            scanned_at: now + Duration::minutes(5),
+            status: OfflineRedemptionStatus::AlreadyRedeemed,
+            previous_device_id: Some("door-1".to_string()),
+            previous_scanned_at: Some(now),
+        }]
+    );
+
+    // Tickets for another event are rejected
+    let scans = vec![OfflineScan {
+        ticket_instance_id: Some(tickets[1].id),
+        add_on_voucher_id: None,
+        redeem_key: tickets[1].redeem_key.clone().unwrap(),
+        scanned_at: now,
+    }];
+    let result =
+        OfflineRedemption::reconcile(other_event.id, "door-1", scans, admin.id, connection)
+            .unwrap();
+    assert!(result.redeemed.is_empty());
+    assert_eq!(result.conflicts[0].status, OfflineRedemptionStatus::Invalid);
+    assert_eq!(
+        TicketInstance::find(tickets[1].id, connection)
+            .unwrap()
+            .status,
+        TicketInstanceStatus::Purchased
+    );
+
+    let redemptions = OfflineRedemption::find_for_event(event.id, connection).unwrap();
+    assert_eq!(redemptions.len(), 3);
+}
+
+#[test]
+fn reconcile_add_on_vouchers() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (admin, event, _) = event_with_purchased_tickets(&project, 1);
+    let add_on = AddOn::create(event.id, "Parking".to_string(), None, 1500, 10, true)
+        .commit(admin.id, connection)
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_add_on_quantities(
+        user.id,
+        &[UpdateAddOnItem {
+            add_on_id: add_on.id,
+            quantity: 1,
+        }],
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+    let voucher = AddOnVoucher::find_for_user(user.id, connection)
+        .unwrap()
+        .remove(0);
+
+    let manifest =
+        ScannerManifest::for_event(&event, &SCANNER_SECRET_KEY.to_string(), connection).unwrap();
+    assert_eq!(manifest.add_on_vouchers.len(), 1);
+    assert_eq!(
+        manifest.add_on_vouchers[0].redeem_key_hash,
+        ScannerManifest::hash_redeem_key(voucher.id, &voucher.redeem_key)
+    );
+    assert!(manifest.verify());
+
+    let now = NaiveDateTime::from_timestamp(Utc::now().timestamp(), 0);
+    let scans = vec![OfflineScan {
+        ticket_instance_id: None,
+        add_on_voucher_id: Some(voucher.id),
+        redeem_key: voucher.redeem_key.clone(),
+        scanned_at: now,
+    }];
+    let result =
+        OfflineRedemption::reconcile(event.id, "door-1", scans, admin.id, connection).unwrap();
+    assert_eq!(result.redeemed_add_on_vouchers, vec![voucher.id]);
+    assert!(result.conflicts.is_empty());
+    assert_eq!(
+        AddOnVoucher::find(voucher.id, connection).unwrap().status,
+        AddOnVoucherStatus::Redeemed
+    );
+
+    // Scanned at another door it is reported with the earlier scan
+    let scans = vec![OfflineScan {
+        ticket_instance_id: None,
+        add_on_voucher_id: Some(voucher.id),
+        redeem_key: voucher.redeem_key.clone(),
+        scanned_at: now + Duration::minutes(5),
+    }];
+    let result =
+        OfflineRedemption::reconcile(event.id, "door-2", scans, admin.id, connection).unwrap();
+    assert_eq!(result.conflicts.len(), 1);
+    assert_eq!(
+        result.conflicts[0].status,
+        OfflineRedemptionStatus::AlreadyRedeemed
+    );
+    assert_eq!(
+        result.conflicts[0].previous_device_id,
+        Some("door-1".to_string())
+    );
+}
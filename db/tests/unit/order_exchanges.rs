@@ -0,0 +1,153 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use time::Duration;
+
+fn paid_order_and_vip_ticket_type(project: &TestProject) -> (Order, OrderItem, TicketType) {
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_event_fee()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let vip_ticket_type = event
+        .add_ticket_type(
+            "VIP".to_string(),
+            None,
+            10,
+            Utc::now().naive_utc() - Duration::days(1),
+            Utc::now().naive_utc() + Duration::days(2),
+            event.issuer_wallet(connection).unwrap().id,
+            None,
+            0,
+            500,
+            SoldOutBehavior::ShowSoldOut,
+            false,
+            connection,
+        )
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+
+    let order = Order::find(cart.id, connection).unwrap();
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    (order, order_item, vip_ticket_type)
+}
+
+#[test]
+fn exchange_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (mut order, order_item, vip_ticket_type) = paid_order_and_vip_ticket_type(&project);
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    let total_before = order.calculate_total(connection).unwrap();
+    let user = project.create_user().finish();
+
+    let exchange = order
+        .exchange_tickets(&[tickets[0].id], vip_ticket_type.id, user.id, connection)
+        .unwrap();
+    assert_eq!(exchange.order_id, order.id);
+    assert_eq!(exchange.ticket_type_id, vip_ticket_type.id);
+    assert_eq!(exchange.exchanged_ticket_instance_ids, vec![tickets[0].id]);
+    assert_eq!(exchange.ticket_instance_ids.len(), 1);
+    assert_eq!(
+        exchange.refunded_amount_in_cents,
+        order_item.unit_price_in_cents + fee_item.unit_price_in_cents
+    );
+
+    // New ticket is purchased for the buyer at the VIP price
+    let new_ticket = TicketInstance::find(exchange.ticket_instance_ids[0], connection).unwrap();
+    assert_eq!(new_ticket.status, TicketInstanceStatus::Purchased);
+    let new_item = OrderItem::find(new_ticket.order_item_id.unwrap(), connection).unwrap();
+    assert_eq!(new_item.ticket_type_id, Some(vip_ticket_type.id));
+    assert_eq!(new_item.unit_price_in_cents, 500);
+    let new_fee_item = new_item.find_fee_item(connection).unwrap().unwrap();
+    assert_eq!(
+        exchange.charged_amount_in_cents,
+        500 + new_fee_item.unit_price_in_cents
+    );
+
+    // Order total reflects the balance still owed for the exchange
+    assert!(exchange.balance_in_cents() > 0);
+    assert_eq!(
+        order.calculate_total(connection).unwrap(),
+        total_before + exchange.balance_in_cents()
+    );
+    assert_eq!(order.exchanges(connection).unwrap(), vec![exchange]);
+
+    let domain_events = DomainEvent::find(
+        Tables::Orders,
+        Some(order.id),
+        Some(DomainEventTypes::OrderTicketsExchanged),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn exchange_tickets_with_invalid_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (mut order, order_item, vip_ticket_type) = paid_order_and_vip_ticket_type(&project);
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    let user = project.create_user().finish();
+
+    // Same ticket type
+    let result = order.exchange_tickets(
+        &[tickets[0].id],
+        order_item.ticket_type_id.unwrap(),
+        user.id,
+        connection,
+    );
+    assert!(result.is_err());
+
+    // Ticket type from another organization
+    let other_event = project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+    let result =
+        order.exchange_tickets(&[tickets[0].id], other_ticket_type.id, user.id, connection);
+    assert!(result.is_err());
+
+    // Already exchanged tickets cannot be exchanged again
+    order
+        .exchange_tickets(&[tickets[0].id], vip_ticket_type.id, user.id, connection)
+        .unwrap();
+    let result = order.exchange_tickets(&[tickets[0].id], vip_ticket_type.id, user.id, connection);
+    assert!(result.is_err());
+}
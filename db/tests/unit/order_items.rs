@@ -28,6 +28,7 @@ fn find_fee_item() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -67,6 +68,7 @@ fn order() {
             ticket_type_id: ticket.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -117,11 +119,13 @@ fn code() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -176,6 +180,7 @@ fn confirm_code_valid() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -242,6 +247,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 6,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -275,6 +281,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -293,6 +300,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -331,6 +339,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type2.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -364,6 +373,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type2.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code),
+            seat_ids: None,
         }],
         false,
         false,
@@ -390,6 +400,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code),
+            seat_ids: None,
         }],
         false,
         false,
@@ -438,6 +449,7 @@ fn calculate_quantity() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
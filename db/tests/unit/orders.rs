@@ -63,6 +63,7 @@ fn add_tickets() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -99,6 +100,7 @@ fn add_tickets() {
             ticket_type_id: ticket.id,
             quantity: 15,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -116,6 +118,82 @@ fn add_tickets() {
     assert_eq!(order_item.calculate_quantity(connection), Ok(15));
 }
 
+#[test]
+fn add_tickets_after_price_change() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 10,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // New pricing applies to the extra tickets only
+    let items = cart.items(connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type.id))
+        .unwrap();
+    TicketPricing::find(order_item.ticket_pricing_id.unwrap(), connection)
+        .unwrap()
+        .destroy(connection)
+        .unwrap();
+    let new_pricing = ticket_type
+        .add_ticket_pricing(
+            "Price change".to_string(),
+            Utc::now().naive_utc() - Duration::days(1),
+            Utc::now().naive_utc() + Duration::days(2),
+            200,
+            false,
+            None,
+            connection,
+        )
+        .unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 15,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let ticket_items: Vec<&OrderItem> = items
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    assert_eq!(ticket_items.len(), 2);
+    assert_eq!(ticket_items.iter().map(|i| i.quantity).sum::<i64>(), 15);
+    let new_item = ticket_items
+        .iter()
+        .find(|i| i.ticket_pricing_id == Some(new_pricing.id))
+        .unwrap();
+    assert_eq!(new_item.quantity, 5);
+    assert_eq!(new_item.calculate_quantity(connection), Ok(5));
+    assert_eq!(new_item.unit_price_in_cents, 200);
+}
+
 #[test]
 fn add_tickets_below_min_fee() {
     let project = TestProject::new();
@@ -157,6 +235,7 @@ fn add_tickets_below_min_fee() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -213,16 +292,19 @@ fn events() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type3.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -276,11 +358,13 @@ fn purchase_metadata() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -320,6 +404,7 @@ fn purchase_metadata() {
         "fees_in_cents".to_string(),
         (event_fee * 2 + fee_in_cents * 3).to_string(),
     ));
+    expected.push(("tax_in_cents".to_string(), 0.to_string()));
     assert_eq!(expected, cart.purchase_metadata(connection).unwrap());
 }
 
@@ -361,6 +446,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -420,6 +506,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -447,6 +534,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(hold.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -495,11 +583,13 @@ fn partially_visible_order() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -571,6 +661,7 @@ fn details() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -780,6 +871,7 @@ fn refund() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -838,6 +930,130 @@ fn refund() {
     assert_eq!(fee_item.refunded_quantity, 1);
 }
 
+#[test]
+fn refund_with_tax() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    TaxRate::create(organization.id, None, "Sales Tax".to_string(), 10.0, false)
+        .commit(creator.id, connection)
+        .unwrap();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+        .unwrap();
+
+    let items = cart.items(&connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type.id))
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let tax_item = order_item.find_tax_item(connection).unwrap().unwrap();
+    let fee_tax_item = fee_item.find_tax_item(connection).unwrap().unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+
+    // Tax items cannot be refunded on their own
+    assert!(cart
+        .refund(
+            vec![RefundItem {
+                order_item_id: tax_item.id,
+                ticket_instance_id: None,
+            }],
+            user.id,
+            connection,
+        )
+        .is_err());
+
+    // Fees are taxed along with the ticket price
+    assert!(tax_item.unit_price_in_cents > 0);
+    assert_eq!(fee_tax_item.tax_in_cents, fee_item.unit_price_in_cents / 10);
+    let refund_amount = order_item.unit_price_in_cents
+        + fee_item.unit_price_in_cents
+        + tax_item.unit_price_in_cents
+        + fee_tax_item.unit_price_in_cents;
+    assert_eq!(
+        cart.refund(
+            vec![RefundItem {
+                order_item_id: order_item.id,
+                ticket_instance_id: Some(tickets[0].id),
+            }],
+            user.id,
+            connection,
+        )
+        .unwrap(),
+        refund_amount as u32
+    );
+
+    let tax_item = order_item.find_tax_item(connection).unwrap().unwrap();
+    assert_eq!(tax_item.refunded_quantity, 1);
+    let fee_tax_item = fee_item.find_tax_item(connection).unwrap().unwrap();
+    assert_eq!(fee_tax_item.refunded_quantity, 1);
+    assert_eq!(
+        cart.calculate_tax(connection).unwrap(),
+        tax_item.tax_in_cents + fee_tax_item.tax_in_cents
+    );
+
+    // Refunding the fees only refunds the tax charged on them
+    assert_eq!(
+        cart.refund(
+            vec![RefundItem {
+                order_item_id: fee_item.id,
+                ticket_instance_id: Some(tickets[1].id),
+            }],
+            user.id,
+            connection,
+        )
+        .unwrap(),
+        (fee_item.unit_price_in_cents + fee_tax_item.unit_price_in_cents) as u32
+    );
+    assert_eq!(
+        cart.calculate_tax(connection).unwrap(),
+        tax_item.tax_in_cents
+    );
+
+    // The ticket is then refunded without its fees
+    assert_eq!(
+        cart.refund(
+            vec![RefundItem {
+                order_item_id: order_item.id,
+                ticket_instance_id: Some(tickets[1].id),
+            }],
+            user.id,
+            connection,
+        )
+        .unwrap(),
+        (order_item.unit_price_in_cents + tax_item.unit_price_in_cents) as u32
+    );
+    assert_eq!(cart.calculate_tax(connection).unwrap(), 0);
+}
+
 #[test]
 fn organizations() {
     let project = TestProject::new();
@@ -876,11 +1092,13 @@ fn organizations() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -913,6 +1131,7 @@ fn payments() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -934,6 +1153,230 @@ fn payments() {
     assert_eq!(payments, vec![500, 1500]);
 }
 
+#[test]
+fn add_seats() {
+    let project = TestProject::new();
+    let creator = project.create_user().finish();
+    let connection = project.get_connection();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    let seats = SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap()
+        .add_row("A".to_string(), 4, Some(price_zone.id), connection)
+        .unwrap();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    ticket_type
+        .set_price_zone(Some(price_zone.id), connection)
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: Some(vec![seats[0].id, seats[1].id]),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let mut reserved_seat_ids: Vec<Uuid> =
+        TicketInstance::find_for_order_item(order_item.id, connection)
+            .unwrap()
+            .iter()
+            .map(|t| t.seat_id.unwrap())
+            .collect();
+    reserved_seat_ids.sort();
+    let mut expected_seat_ids = vec![seats[0].id, seats[1].id];
+    expected_seat_ids.sort();
+    assert_eq!(reserved_seat_ids, expected_seat_ids);
+    assert_eq!(order_item.quantity, 2);
+
+    // Swap one of the seats for another
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: Some(vec![seats[1].id, seats[2].id]),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let mut reserved_seat_ids: Vec<Uuid> =
+        TicketInstance::find_for_order_item(order_item.id, connection)
+            .unwrap()
+            .iter()
+            .map(|t| t.seat_id.unwrap())
+            .collect();
+    reserved_seat_ids.sort();
+    let mut expected_seat_ids = vec![seats[1].id, seats[2].id];
+    expected_seat_ids.sort();
+    assert_eq!(reserved_seat_ids, expected_seat_ids);
+    assert_eq!(order_item.quantity, 2);
+    let available_seat_ids: Vec<Uuid> = ticket_type
+        .seats(connection)
+        .unwrap()
+        .iter()
+        .filter(|s| s.available)
+        .map(|s| s.seat_id)
+        .collect();
+    assert!(available_seat_ids.contains(&seats[0].id));
+    assert!(!available_seat_ids.contains(&seats[1].id));
+
+    // Another user cannot reserve a seat already in a cart
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    let result = cart2.update_quantities(
+        user2.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: Some(vec![seats[2].id]),
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("seat_ids"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn add_seats_with_mismatched_quantity() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let result = cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: Some(vec![Uuid::new_v4()]),
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("seat_ids"));
+                assert_eq!(
+                    &errors["seat_ids"][0].message.clone().unwrap().into_owned(),
+                    "Number of seats selected must match the quantity requested"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn add_tickets_without_seats_for_seated_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap()
+        .add_row("A".to_string(), 4, Some(price_zone.id), connection)
+        .unwrap();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    ticket_type
+        .set_price_zone(Some(price_zone.id), connection)
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let result = cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("seat_ids"));
+                assert_eq!(
+                    &errors["seat_ids"][0].message.clone().unwrap().into_owned(),
+                    "Seats must be selected for this ticket type"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn add_tickets_with_increment() {
     let project = TestProject::new();
@@ -965,6 +1408,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -991,6 +1435,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1004,6 +1449,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1019,6 +1465,90 @@ fn add_tickets_with_increment() {
     assert_eq!(order_item.quantity, 12);
 }
 
+#[test]
+fn add_tickets_in_different_currencies() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let other_event = other_event
+        .update(
+            EventEditableAttributes {
+                currency: Some("eur".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    assert_eq!(cart.currency, None);
+
+    cart.update_quantities(
+        user.id,
+        &vec![UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.currency, Some(event.currency.clone()));
+
+    let result = cart.update_quantities(
+        user.id,
+        &vec![UpdateOrderItem {
+            ticket_type_id: other_ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(
+                    errors["currency"][0].message.clone().unwrap().into_owned(),
+                    "All items in an order must be priced in the same currency"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Replacing the cart contents allows switching currency
+    let mut cart = Order::find(cart.id, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &vec![UpdateOrderItem {
+            ticket_type_id: other_ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.currency, Some("eur".to_string()));
+    assert_eq!(
+        Order::find(cart.id, connection).unwrap().currency,
+        Some("eur".to_string())
+    );
+}
+
 #[test]
 fn clear_cart() {
     let project = TestProject::new();
@@ -1045,6 +1575,7 @@ fn clear_cart() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1099,6 +1630,7 @@ fn replace_tickets_for_box_office() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1138,6 +1670,7 @@ fn replace_tickets_for_box_office() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         true,
         true,
@@ -1190,6 +1723,7 @@ fn replace_tickets() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1226,6 +1760,7 @@ fn replace_tickets() {
             ticket_type_id: ticket.id,
             quantity: 15,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -1276,6 +1811,7 @@ fn replace_tickets_with_code_pricing() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -1312,6 +1848,7 @@ fn replace_tickets_with_code_pricing() {
             ticket_type_id: ticket_type.id,
             quantity: 15,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         true,
@@ -1347,6 +1884,7 @@ fn remove_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1384,6 +1922,7 @@ fn remove_tickets() {
                 ticket_type_id: ticket_type.id,
                 quantity: 6,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1413,6 +1952,7 @@ fn remove_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 0,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1441,6 +1981,7 @@ fn clear_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1480,6 +2021,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 8,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1499,6 +2041,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1519,6 +2062,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1560,6 +2104,7 @@ fn find_item() {
                 ticket_type_id: ticket_type.id,
                 quantity: 5,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1574,6 +2119,7 @@ fn find_item() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1667,6 +2213,7 @@ fn has_items() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1715,6 +2262,7 @@ fn calculate_cart_total() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1731,6 +2279,7 @@ fn calculate_cart_total() {
             ticket_type_id: ticket.id,
             quantity: 30,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1759,6 +2308,7 @@ fn add_external_payment() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1815,6 +2365,7 @@ fn add_external_payment_for_expired_code() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -1967,6 +2518,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1981,6 +2533,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type2.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2021,6 +2574,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type3.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2058,6 +2612,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type4.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2086,6 +2641,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type5.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -2108,6 +2664,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type5.id,
             quantity: 1,
             redemption_code: Some(hold.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -2293,11 +2850,13 @@ fn for_display_with_organization_id_and_event_id_filters() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -2415,6 +2974,118 @@ fn for_display_with_organization_id_and_event_id_filters() {
     assert_eq!(display_order.items.len(), 4); // 2 tickets, 2 fees
 }
 
+#[test]
+fn adding_tax() {
+    let project = TestProject::new();
+    let creator = project.create_user().finish();
+    let connection = project.get_connection();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    TaxRate::create(organization.id, None, "Sales Tax".to_string(), 5.0, false)
+        .commit(creator.id, connection)
+        .unwrap();
+    let tax_rate = TaxRate::create(
+        organization.id,
+        Some(region.id),
+        "VAT".to_string(),
+        20.0,
+        true,
+    )
+    .commit(creator.id, connection)
+    .unwrap();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let tax_item = order_item.find_tax_item(connection).unwrap().unwrap();
+    assert_eq!(tax_item.tax_rate_id, Some(tax_rate.id));
+    assert_eq!(tax_item.quantity, 2);
+    assert_eq!(
+        tax_item.tax_in_cents,
+        tax_rate.tax_in_cents(order_item.unit_price_in_cents)
+    );
+    // Inclusive tax does not change the total
+    assert_eq!(tax_item.unit_price_in_cents, 0);
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let fee_tax_item = fee_item.find_tax_item(connection).unwrap().unwrap();
+    assert_eq!(
+        fee_tax_item.tax_in_cents,
+        tax_rate.tax_in_cents(fee_item.unit_price_in_cents)
+    );
+    assert_eq!(
+        cart.calculate_tax(connection).unwrap(),
+        (tax_item.tax_in_cents + fee_tax_item.tax_in_cents) * 2
+    );
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        (order_item.unit_price_in_cents + fee_item.unit_price_in_cents) * 2
+    );
+
+    // Changing the quantity recalculates the tax line
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 3,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let tax_items: Vec<OrderItem> = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tax)
+        .collect();
+    assert_eq!(tax_items.len(), 2);
+    assert!(tax_items.iter().all(|i| i.quantity == 3));
+
+    let display_order = cart.for_display(None, user.id, connection).unwrap();
+    assert_eq!(
+        display_order.tax_in_cents,
+        tax_items.iter().map(|i| i.tax_in_cents * 3).sum::<i64>()
+    );
+    let display_tax_item = display_order
+        .items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tax)
+        .unwrap();
+    assert_eq!(display_tax_item.description, "VAT (included)");
+}
+
 #[test]
 fn adding_event_fees() {
     let project = TestProject::new();
@@ -2467,6 +3138,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket1.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2491,6 +3163,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket1.id,
             quantity: 15,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2503,6 +3176,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket2.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2528,6 +3202,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket3.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2552,6 +3227,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket4.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -0,0 +1,163 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let template = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateKinds::PurchaseCompleted,
+        OrganizationEmailTemplateEditableAttributes {
+            logo_url: Some(Some("https://example.com/logo.png".to_string())),
+            primary_color: Some(Some("#ff00aa".to_string())),
+            reply_to_email: Some(Some("tickets@example.com".to_string())),
+            ..Default::default()
+        },
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(template.organization_id, organization.id);
+    assert_eq!(
+        template.template_kind,
+        EmailTemplateKinds::PurchaseCompleted
+    );
+    assert_eq!(template.template_id, None);
+    assert_eq!(template.primary_color, Some("#ff00aa".to_string()));
+    assert_eq!(
+        template.reply_to_email,
+        Some("tickets@example.com".to_string())
+    );
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let result = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateKinds::Refund,
+        OrganizationEmailTemplateEditableAttributes {
+            logo_url: Some(Some("not a url".to_string())),
+            primary_color: Some(Some("red".to_string())),
+            reply_to_email: Some(Some("not an email".to_string())),
+            ..Default::default()
+        },
+    )
+    .commit(connection);
+
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("logo_url"));
+                assert!(errors.contains_key("primary_color"));
+                assert!(errors.contains_key("reply_to_email"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_by_organization_and_kind() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+
+    let template = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateKinds::TransferTickets,
+        OrganizationEmailTemplateEditableAttributes {
+            template_id: Some(Some("d-123".to_string())),
+            ..Default::default()
+        },
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        OrganizationEmailTemplate::find_by_organization_and_kind(
+            organization.id,
+            EmailTemplateKinds::TransferTickets,
+            connection
+        )
+        .unwrap(),
+        Some(template.clone())
+    );
+    assert_eq!(
+        OrganizationEmailTemplate::find_by_organization_and_kind(
+            organization.id,
+            EmailTemplateKinds::Refund,
+            connection
+        )
+        .unwrap(),
+        None
+    );
+    assert_eq!(
+        OrganizationEmailTemplate::find_by_organization_and_kind(
+            organization2.id,
+            EmailTemplateKinds::TransferTickets,
+            connection
+        )
+        .unwrap(),
+        None
+    );
+    assert_eq!(
+        OrganizationEmailTemplate::find_for_organization(organization.id, connection).unwrap(),
+        vec![template]
+    );
+}
+
+#[test]
+fn update_and_destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let template = OrganizationEmailTemplate::create(
+        organization.id,
+        EmailTemplateKinds::Refund,
+        OrganizationEmailTemplateEditableAttributes {
+            footer_text: Some(Some("Old footer".to_string())),
+            ..Default::default()
+        },
+    )
+    .commit(connection)
+    .unwrap();
+
+    let template = template
+        .update(
+            OrganizationEmailTemplateEditableAttributes {
+                footer_text: Some(None),
+                primary_color: Some(Some("#000000".to_string())),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(template.footer_text, None);
+    assert_eq!(template.primary_color, Some("#000000".to_string()));
+
+    let result = template.update(
+        OrganizationEmailTemplateEditableAttributes {
+            primary_color: Some(Some("#00000".to_string())),
+            ..Default::default()
+        },
+        connection,
+    );
+    assert!(result.is_err());
+
+    template.destroy(connection).unwrap();
+    assert!(
+        OrganizationEmailTemplate::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
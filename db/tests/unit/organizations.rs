@@ -142,11 +142,13 @@ fn find_by_order_item_ids() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -212,6 +214,7 @@ fn has_fan() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -625,6 +628,7 @@ pub fn get_scopes_for_user() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:make-external-payment",
             "order:read",
             "order:read-own",
@@ -672,6 +676,7 @@ pub fn get_scopes_for_user() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:make-external-payment",
             "order:read",
             "order:read-own",
@@ -706,6 +711,7 @@ pub fn get_scopes_for_user() {
             "event:scan",
             "event:view-guests",
             "hold:read",
+            "order:exchange",
             "order:make-external-payment",
             "org:read-events",
             "redeem:ticket",
@@ -799,6 +805,7 @@ pub fn get_scopes_for_user() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:read",
             "order:read-own",
             "order:refund",
@@ -840,6 +847,7 @@ pub fn get_scopes_for_user() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:make-external-payment",
             "order:read",
             "order:read-own",
@@ -941,6 +949,7 @@ fn search_fans() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -192,3 +192,126 @@ fn find_for_user() {
             .unwrap();
     assert!(found_payment_methods.is_empty());
 }
+
+#[test]
+fn create_with_card_details() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let payment_method = project
+        .create_payment_method()
+        .with_user(&user)
+        .make_default()
+        .finish();
+
+    let card = CardDetails {
+        source_reference: "card_example".to_string(),
+        brand: Some("Visa".to_string()),
+        last_four: Some("4242".to_string()),
+        exp_month: Some(12),
+        exp_year: Some(2030),
+    };
+    let payment_method2 = PaymentMethod::create(
+        user.id,
+        PaymentProviders::Stripe,
+        true,
+        "cus_example".into(),
+        "abc".into(),
+    )
+    .with_card_details(card)
+    .commit(user.id, connection)
+    .unwrap();
+    assert_eq!(
+        payment_method2.source_reference,
+        Some("card_example".to_string())
+    );
+    assert_eq!(payment_method2.card_last_four, Some("4242".to_string()));
+    assert_eq!(payment_method2.card_exp_year, Some(2030));
+
+    // New default replaces the previous default
+    assert!(payment_method2.is_default);
+    let payment_method = PaymentMethod::find(payment_method.id, connection).unwrap();
+    assert!(!payment_method.is_default);
+    assert_eq!(
+        PaymentMethod::find_default_for_user(user.id, connection).unwrap(),
+        payment_method2
+    );
+
+    // Both cards are saved for the provider, the default is preferred
+    assert_eq!(
+        PaymentMethod::find_for_user(user.id, Some(PaymentProviders::Stripe), connection)
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        user.payment_method(PaymentProviders::Stripe, connection)
+            .unwrap(),
+        payment_method2
+    );
+}
+
+#[test]
+fn set_default() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let payment_method = project
+        .create_payment_method()
+        .with_user(&user)
+        .make_default()
+        .finish();
+    let payment_method2 = project.create_payment_method().with_user(&user).finish();
+    assert!(!payment_method2.is_default);
+
+    let payment_method2 = payment_method2.set_default(user.id, connection).unwrap();
+    assert!(payment_method2.is_default);
+    let payment_method = PaymentMethod::find(payment_method.id, connection).unwrap();
+    assert!(!payment_method.is_default);
+    assert_eq!(
+        PaymentMethod::find_default_for_user(user.id, connection).unwrap(),
+        payment_method2
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::PaymentMethods,
+        Some(payment_method2.id),
+        Some(DomainEventTypes::PaymentMethodUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let payment_method = project.create_payment_method().with_user(&user).finish();
+    let payment_method2 = project
+        .create_payment_method()
+        .with_user(&user)
+        .make_default()
+        .finish();
+
+    // Removing the default promotes the remaining payment method
+    payment_method2.destroy(user.id, connection).unwrap();
+    assert!(PaymentMethod::find(payment_method2.id, connection).is_err());
+    let payment_method = PaymentMethod::find(payment_method.id, connection).unwrap();
+    assert!(payment_method.is_default);
+
+    let domain_events = DomainEvent::find(
+        Tables::PaymentMethods,
+        Some(payment_method2.id),
+        Some(DomainEventTypes::PaymentMethodDeleted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    payment_method.destroy(user.id, connection).unwrap();
+    assert!(PaymentMethod::find_for_user(user.id, None, connection)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,59 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(price_zone.name, "Orchestra".to_string());
+    assert_eq!(price_zone.stage_id, stage.id);
+    assert_eq!(
+        PriceZone::find(price_zone.id, connection).unwrap(),
+        price_zone
+    );
+}
+
+#[test]
+fn find_by_stage_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let stage2 = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    let price_zone2 = PriceZone::create(stage.id, "Balcony".to_string())
+        .commit(connection)
+        .unwrap();
+    PriceZone::create(stage2.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        PriceZone::find_by_stage_id(stage.id, connection).unwrap(),
+        vec![price_zone2, price_zone]
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(price_zone.destroy(connection).unwrap(), 1);
+    assert!(PriceZone::find_by_stage_id(stage.id, connection)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,195 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use uuid::Uuid;
+
+fn paid_order(project: &TestProject, payment_amounts: Option<i64>) -> (Order, OrderItem) {
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_event_fee()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    match payment_amounts {
+        // Split the total between two payments
+        Some(first_amount) => {
+            cart.add_external_payment(Some("First".to_string()), user.id, first_amount, connection)
+                .unwrap();
+            cart.add_external_payment(
+                Some("Second".to_string()),
+                user.id,
+                total - first_amount,
+                connection,
+            )
+            .unwrap();
+        }
+        None => {
+            cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+                .unwrap();
+        }
+    }
+    let cart = Order::find(cart.id, connection).unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    (cart, order_item)
+}
+
+#[test]
+fn for_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (order, order_item) = paid_order(&project, None);
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+
+    // A single ticket is refunded with its fee, the event fee remains for the other ticket
+    let plan = RefundPlan::for_tickets(&order, &[tickets[0].id], &[], connection).unwrap();
+    assert_eq!(
+        plan.items,
+        vec![RefundItem {
+            order_item_id: order_item.id,
+            ticket_instance_id: Some(tickets[0].id),
+        }]
+    );
+    let expected_amount = order_item.unit_price_in_cents + fee_item.unit_price_in_cents;
+    assert_eq!(plan.amount_in_cents, expected_amount);
+    assert_eq!(plan.payments.len(), 1);
+    assert_eq!(plan.payments[0].amount_in_cents, expected_amount);
+
+    // Fees only
+    let plan = RefundPlan::for_tickets(&order, &[], &[tickets[1].id], connection).unwrap();
+    assert_eq!(
+        plan.items,
+        vec![RefundItem {
+            order_item_id: fee_item.id,
+            ticket_instance_id: Some(tickets[1].id),
+        }]
+    );
+    assert_eq!(plan.amount_in_cents, fee_item.unit_price_in_cents);
+
+    // Planned amount matches the amount refunded
+    let user = project.create_user().finish();
+    let refunded = order.refund(plan.items, user.id, connection).unwrap();
+    assert_eq!(refunded as i64, fee_item.unit_price_in_cents);
+
+    // Fees cannot be refunded twice
+    assert!(RefundPlan::for_tickets(&order, &[], &[tickets[1].id], connection).is_err());
+
+    // Refunding the remaining tickets includes the event fee but not the refunded fee
+    let event_fee_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::EventFees)
+        .unwrap();
+    let plan =
+        RefundPlan::for_tickets(&order, &[tickets[0].id, tickets[1].id], &[], connection).unwrap();
+    assert_eq!(
+        plan.amount_in_cents,
+        order_item.unit_price_in_cents * 2
+            + fee_item.unit_price_in_cents
+            + event_fee_item.unit_price_in_cents
+    );
+    let refunded = order.refund(plan.items, user.id, connection).unwrap();
+    assert_eq!(refunded as i64, plan.amount_in_cents);
+}
+
+#[test]
+fn for_tickets_with_invalid_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (order, _) = paid_order(&project, None);
+    let (_, other_order_item) = paid_order(&project, None);
+    let other_tickets =
+        TicketInstance::find_for_order_item(other_order_item.id, connection).unwrap();
+
+    assert!(RefundPlan::for_tickets(&order, &[], &[], connection).is_err());
+    assert!(RefundPlan::for_tickets(&order, &[other_tickets[0].id], &[], connection).is_err());
+    assert!(RefundPlan::for_tickets(&order, &[Uuid::new_v4()], &[], connection).is_err());
+}
+
+#[test]
+fn for_tickets_listed_for_resale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (order, order_item) = paid_order(&project, None);
+    let event = Event::find(order_item.event_id.unwrap(), connection).unwrap();
+    event
+        .organization(connection)
+        .unwrap()
+        .update(
+            OrganizationEditableAttributes {
+                max_resale_markup_percent: Some(Some(10.0)),
+                resale_fee_percent: Some(5.0),
+                ..Default::default()
+            },
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    ResaleListing::create(tickets[0].id, order.user_id, order_item.unit_price_in_cents)
+        .commit(connection)
+        .unwrap();
+
+    // The plan is rejected before anything is refunded
+    let result = RefundPlan::for_tickets(&order, &[tickets[0].id], &[], connection);
+    assert_eq!(
+        result.unwrap_err().error_code,
+        ErrorCode::BusinessProcessError
+    );
+    assert!(RefundPlan::for_tickets(&order, &[tickets[1].id], &[], connection).is_ok());
+}
+
+#[test]
+fn allocate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (order, _) = paid_order(&project, Some(100));
+    let total: i64 = order.calculate_total(connection).unwrap();
+
+    // The refund is split across both payments
+    let payments = RefundPlan::allocate(&order, total, connection).unwrap();
+    assert_eq!(payments.len(), 2);
+    assert_eq!(
+        payments.iter().map(|p| p.amount_in_cents).sum::<i64>(),
+        total
+    );
+    let first = payments
+        .iter()
+        .find(|p| p.external_reference == Some("First".to_string()))
+        .unwrap();
+    assert_eq!(first.amount_in_cents, 100);
+    assert_eq!(first.payment_method, PaymentMethods::External);
+
+    // More than was paid cannot be refunded
+    assert!(RefundPlan::allocate(&order, total + 1, connection).is_err());
+}
@@ -0,0 +1,286 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::WeeklySettlement,
+        ReportFrequencies::Weekly,
+        "finance@example.com".to_string(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(report_subscription.organization_id, organization.id);
+    assert!(report_subscription.next_send_at > Utc::now().naive_utc());
+    assert_eq!(report_subscription.next_send_at.weekday(), Weekday::Mon);
+    assert_eq!(
+        ReportSubscription::find_for_organization(organization.id, connection).unwrap(),
+        vec![report_subscription.clone()]
+    );
+    assert!(DomainAction::has_pending_action(
+        DomainActionTypes::SendScheduledReport,
+        Tables::ReportSubscriptions.to_string(),
+        report_subscription.id,
+        connection,
+    )
+    .unwrap());
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let other_event = project.create_event().finish();
+
+    let result = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::EventSummary,
+        ReportFrequencies::Daily,
+        "not an email".to_string(),
+        user.id,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("email"));
+                assert_eq!(
+                    errors["email"][0].message.clone().unwrap().into_owned(),
+                    "Email is invalid"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::EventSummary,
+        ReportFrequencies::Daily,
+        "finance@example.com".to_string(),
+        user.id,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(
+                    errors["event_id"][0].message.clone().unwrap().into_owned(),
+                    "Event is required for event summary reports"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = ReportSubscription::create(
+        organization.id,
+        Some(other_event.id),
+        ReportTypes::EventSummary,
+        ReportFrequencies::Daily,
+        "finance@example.com".to_string(),
+        user.id,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(
+                    errors["event_id"][0].message.clone().unwrap().into_owned(),
+                    "Event does not belong to this organization"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn next_send_at_after() {
+    // Wednesday
+    let after = NaiveDate::from_ymd(2019, 3, 6).and_hms(23, 0, 0);
+    assert_eq!(
+        ReportSubscription::next_send_at_after(ReportFrequencies::Daily, &None, after),
+        NaiveDate::from_ymd(2019, 3, 7).and_hms(0, 0, 0)
+    );
+    assert_eq!(
+        ReportSubscription::next_send_at_after(
+            ReportFrequencies::Daily,
+            &Some("Africa/Johannesburg".to_string()),
+            after
+        ),
+        NaiveDate::from_ymd(2019, 3, 7).and_hms(22, 0, 0)
+    );
+    // Daylight saving time has started by the following Monday
+    assert_eq!(
+        ReportSubscription::next_send_at_after(
+            ReportFrequencies::Weekly,
+            &Some("America/New_York".to_string()),
+            after
+        ),
+        NaiveDate::from_ymd(2019, 3, 11).and_hms(4, 0, 0)
+    );
+    // Unknown timezones fall back to UTC
+    assert_eq!(
+        ReportSubscription::next_send_at_after(
+            ReportFrequencies::Weekly,
+            &Some("Not/A_Timezone".to_string()),
+            after
+        ),
+        NaiveDate::from_ymd(2019, 3, 11).and_hms(0, 0, 0)
+    );
+}
+
+#[test]
+fn render_csv() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_name("Event Name".to_string())
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        Some(event.id),
+        ReportTypes::TicketCount,
+        ReportFrequencies::Daily,
+        "finance@example.com".to_string(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let csv = report_subscription.render_csv(connection).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines.contains(
+        &"Event,Ticket,Status,Allocated,Available,Reserved,Purchased,Redeemed,Nullified,Refunded,Comps,Comps Available,Comps Redeemed,Holds,Holds Available,Holds Redeemed"
+    ));
+    assert!(lines.iter().any(|l| l.starts_with("Event Name,")));
+    assert!(report_subscription.file_name().starts_with("ticket_count_"));
+}
+
+#[test]
+fn record_delivery() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::WeeklySettlement,
+        ReportFrequencies::Daily,
+        "finance@example.com".to_string(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let updated_subscription = report_subscription.record_delivery(connection).unwrap();
+    assert_eq!(
+        updated_subscription.last_sent_at,
+        Some(report_subscription.next_send_at)
+    );
+    assert_eq!(
+        updated_subscription.next_send_at,
+        report_subscription.next_send_at + chrono::Duration::days(1)
+    );
+    let (start, end) = updated_subscription.report_period();
+    assert_eq!(start, report_subscription.next_send_at);
+    assert_eq!(end, updated_subscription.next_send_at);
+}
+
+#[test]
+fn record_failed_delivery() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::WeeklySettlement,
+        ReportFrequencies::Daily,
+        "finance@example.com".to_string(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let updated_subscription = report_subscription
+        .record_failed_delivery("Could not send", connection)
+        .unwrap();
+    assert!(updated_subscription.last_failed_at.is_some());
+    assert_eq!(
+        updated_subscription.last_failure_reason,
+        Some("Could not send".to_string())
+    );
+    assert_eq!(updated_subscription.last_sent_at, None);
+    // The next report is still scheduled
+    assert_eq!(
+        updated_subscription.next_send_at,
+        report_subscription.next_send_at + chrono::Duration::days(1)
+    );
+    assert!(DomainAction::has_pending_action(
+        DomainActionTypes::SendScheduledReport,
+        Tables::ReportSubscriptions.to_string(),
+        report_subscription.id,
+        connection,
+    )
+    .unwrap());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let report_subscription = ReportSubscription::create(
+        organization.id,
+        None,
+        ReportTypes::TicketCount,
+        ReportFrequencies::Weekly,
+        "finance@example.com".to_string(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    report_subscription.destroy(connection).unwrap();
+    assert!(ReportSubscription::find(report_subscription.id, connection).is_err());
+    assert!(
+        ReportSubscription::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(!DomainAction::has_pending_action(
+        DomainActionTypes::SendScheduledReport,
+        Tables::ReportSubscriptions.to_string(),
+        report_subscription.id,
+        connection,
+    )
+    .unwrap());
+}
@@ -0,0 +1,364 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+fn purchased_ticket(project: &TestProject, resale_enabled: bool) -> (User, TicketInstance, i64) {
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    if resale_enabled {
+        organization
+            .update(
+                OrganizationEditableAttributes {
+                    max_resale_markup_percent: Some(Some(10.0)),
+                    resale_fee_percent: Some(5.0),
+                    ..Default::default()
+                },
+                &"encryption_key".to_string(),
+                connection,
+            )
+            .unwrap();
+    }
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(seller.id, connection)
+        .unwrap()
+        .remove(0);
+    let face_value = OrderItem::find(ticket.order_item_id.unwrap(), connection)
+        .unwrap()
+        .unit_price_in_cents;
+
+    (seller, ticket, face_value)
+}
+
+fn assert_validation_error(
+    result: Result<ResaleListing, DatabaseError>,
+    field: &str,
+    message: &str,
+) {
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key(field));
+                assert_eq!(
+                    errors[field][0].message.clone().unwrap().into_owned(),
+                    message
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (seller, ticket, face_value) = purchased_ticket(&project, true);
+
+    let price = face_value + face_value / 10;
+    let listing = ResaleListing::create(ticket.id, seller.id, price)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(listing.ticket_instance_id, ticket.id);
+    assert_eq!(
+        listing.ticket_type_id,
+        ticket.ticket_type(connection).unwrap().id
+    );
+    assert_eq!(listing.seller_user_id, seller.id);
+    assert_eq!(listing.face_value_in_cents, face_value);
+    assert_eq!(listing.price_in_cents, price);
+    assert_eq!(
+        listing.resale_fee_in_cents,
+        (price as f64 * 0.05).round() as i64
+    );
+    assert_eq!(listing.status, ResaleListingStatus::Active);
+    assert_eq!(
+        ResaleListing::find(listing.id, connection).unwrap(),
+        listing
+    );
+
+    // Listed tickets cannot be transferred
+    let receiver = project.create_user().finish();
+    assert!(
+        TicketInstance::direct_transfer(seller.id, &[ticket.id], receiver.id, connection).is_err()
+    );
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+
+    // Resale not enabled for the organization
+    let (seller, ticket, face_value) = purchased_ticket(&project, false);
+    assert_validation_error(
+        ResaleListing::create(ticket.id, seller.id, face_value).commit(connection),
+        "ticket_instance_id",
+        "Resale is not enabled for this event",
+    );
+
+    // Price above the markup cap
+    let (seller, ticket, face_value) = purchased_ticket(&project, true);
+    assert_validation_error(
+        ResaleListing::create(ticket.id, seller.id, face_value * 2).commit(connection),
+        "price_in_cents",
+        "Price exceeds the maximum resale price for this ticket",
+    );
+
+    // Already listed
+    ResaleListing::create(ticket.id, seller.id, face_value)
+        .commit(connection)
+        .unwrap();
+    assert_validation_error(
+        ResaleListing::create(ticket.id, seller.id, face_value).commit(connection),
+        "ticket_instance_id",
+        "Ticket is already listed for resale",
+    );
+
+    // Seller does not own the ticket
+    let other_user = project.create_user().finish();
+    assert!(ResaleListing::create(ticket.id, other_user.id, face_value)
+        .commit(connection)
+        .is_err());
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (seller, ticket, face_value) = purchased_ticket(&project, true);
+    let listing = ResaleListing::create(ticket.id, seller.id, face_value)
+        .commit(connection)
+        .unwrap();
+
+    let listing = listing.cancel(seller.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Cancelled);
+    assert!(
+        ResaleListing::find_active_for_ticket_instance(ticket.id, connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn add_to_cart() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (seller, ticket, face_value) = purchased_ticket(&project, true);
+    let listing = ResaleListing::create(ticket.id, seller.id, face_value)
+        .commit(connection)
+        .unwrap();
+    let event_id = ticket.ticket_type(connection).unwrap().event_id;
+    assert_eq!(
+        ResaleListing::find_available_for_event(event_id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Sellers cannot buy their own listing
+    let mut seller_cart = Order::find_or_create_cart(&seller, connection).unwrap();
+    assert!(listing
+        .add_to_cart(&mut seller_cart, seller.id, connection)
+        .is_err());
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    let order_item = listing
+        .add_to_cart(&mut cart, buyer.id, connection)
+        .unwrap();
+    assert_eq!(order_item.item_type, OrderItemTypes::Resale);
+    assert_eq!(order_item.unit_price_in_cents, face_value);
+    assert_eq!(cart.calculate_total(connection).unwrap(), face_value);
+    assert!(cart.expires_at.is_some());
+
+    // Held for the buyer's cart
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.order_item_id, Some(order_item.id));
+    assert!(!listing.is_available(connection).unwrap());
+    assert!(
+        ResaleListing::find_available_for_event(event_id, connection)
+            .unwrap()
+            .is_empty()
+    );
+    let other_buyer = project.create_user().finish();
+    let mut other_cart = Order::find_or_create_cart(&other_buyer, connection).unwrap();
+    assert!(listing
+        .add_to_cart(&mut other_cart, other_buyer.id, connection)
+        .is_err());
+
+    // Released when the cart is cleared
+    cart.clear_cart(buyer.id, connection).unwrap();
+    assert!(ResaleListing::find(listing.id, connection)
+        .unwrap()
+        .is_available(connection)
+        .unwrap());
+}
+
+#[test]
+fn complete_sale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (seller, ticket, face_value) = purchased_ticket(&project, true);
+    let listing = ResaleListing::create(ticket.id, seller.id, face_value)
+        .commit(connection)
+        .unwrap();
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    let order_item = listing
+        .add_to_cart(&mut cart, buyer.id, connection)
+        .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("test".to_string()), buyer.id, total, connection)
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Sold);
+    assert_eq!(listing.order_item_id, Some(order_item.id));
+    assert!(listing.sold_at.is_some());
+    assert_eq!(
+        listing.seller_proceeds_in_cents(),
+        face_value - listing.resale_fee_in_cents
+    );
+
+    // Ticket now belongs to the buyer
+    let buyer_tickets = TicketInstance::find_for_user(buyer.id, connection).unwrap();
+    assert_eq!(buyer_tickets.len(), 1);
+    assert_eq!(buyer_tickets[0].id, ticket.id);
+    assert!(TicketInstance::find_for_user(seller.id, connection)
+        .unwrap()
+        .is_empty());
+
+    // The seller is owed the proceeds
+    let payout = ResalePayout::find_for_resale_listing(listing.id, connection).unwrap();
+    assert_eq!(payout.seller_user_id, seller.id);
+    assert_eq!(payout.amount_in_cents, listing.seller_proceeds_in_cents());
+    assert_eq!(payout.status, ResalePayoutStatus::Pending);
+}
+
+#[test]
+fn refund_sale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (seller, ticket, face_value) = purchased_ticket(&project, true);
+    let listing = ResaleListing::create(ticket.id, seller.id, face_value)
+        .commit(connection)
+        .unwrap();
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    let order_item = listing
+        .add_to_cart(&mut cart, buyer.id, connection)
+        .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("test".to_string()), buyer.id, total, connection)
+        .unwrap();
+
+    let refund_items = vec![RefundItem {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let refund_amount = cart.refund(refund_items, buyer.id, connection).unwrap();
+    assert_eq!(refund_amount as i64, face_value);
+
+    // The sale is reversed, the ticket goes back to the seller who is no longer owed anything
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Refunded);
+    let payout = ResalePayout::find_for_resale_listing(listing.id, connection).unwrap();
+    assert_eq!(payout.status, ResalePayoutStatus::Cancelled);
+    assert!(TicketInstance::find_for_user(buyer.id, connection)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        TicketInstance::find_for_user(seller.id, connection).unwrap()[0].id,
+        ticket.id
+    );
+}
+
+#[test]
+fn refund_sale_after_payout() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let (seller, ticket, face_value) = purchased_ticket(&project, true);
+    let listing = ResaleListing::create(ticket.id, seller.id, face_value)
+        .commit(connection)
+        .unwrap();
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    let order_item = listing
+        .add_to_cart(&mut cart, buyer.id, connection)
+        .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("test".to_string()), buyer.id, total, connection)
+        .unwrap();
+    ResalePayout::find_for_resale_listing(listing.id, connection)
+        .unwrap()
+        .mark_paid(admin.id, connection)
+        .unwrap();
+
+    let refund_items = vec![RefundItem {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let result = cart.refund(refund_items, buyer.id, connection);
+    assert_eq!(
+        result.unwrap_err().error_code,
+        ErrorCode::BusinessProcessError
+    );
+}
+
+#[test]
+fn listed_tickets_are_locked() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (seller, ticket, face_value) = purchased_ticket(&project, true);
+    ResaleListing::create(ticket.id, seller.id, face_value)
+        .commit(connection)
+        .unwrap();
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+
+    let result = TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        seller.id,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketInvalid);
+
+    let order_item = OrderItem::find(ticket.order_item_id.unwrap(), connection).unwrap();
+    let order = order_item.order(connection).unwrap();
+    let refund_items = vec![RefundItem {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let result = order.refund(refund_items, seller.id, connection);
+    assert_eq!(
+        result.unwrap_err().error_code,
+        ErrorCode::BusinessProcessError
+    );
+    assert_eq!(
+        TicketInstance::find(ticket.id, connection).unwrap().status,
+        TicketInstanceStatus::Purchased
+    );
+}
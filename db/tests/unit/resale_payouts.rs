@@ -0,0 +1,91 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+fn sold_listing(project: &TestProject) -> ResaleListing {
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                max_resale_markup_percent: Some(Some(0.0)),
+                resale_fee_percent: Some(10.0),
+                ..Default::default()
+            },
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(seller.id, connection)
+        .unwrap()
+        .remove(0);
+    let face_value = OrderItem::find(ticket.order_item_id.unwrap(), connection)
+        .unwrap()
+        .unit_price_in_cents;
+    let listing = ResaleListing::create(ticket.id, seller.id, face_value)
+        .commit(connection)
+        .unwrap();
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    listing
+        .add_to_cart(&mut cart, buyer.id, connection)
+        .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("test".to_string()), buyer.id, total, connection)
+        .unwrap();
+    ResaleListing::find(listing.id, connection).unwrap()
+}
+
+#[test]
+fn find_by_status() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let listing = sold_listing(&project);
+    let payout = ResalePayout::find_for_resale_listing(listing.id, connection).unwrap();
+
+    let pending =
+        ResalePayout::find_by_status(ResalePayoutStatus::Pending, 0, 100, connection).unwrap();
+    assert_eq!(pending.data, vec![payout]);
+    assert_eq!(pending.paging.total, 1);
+    assert!(
+        ResalePayout::find_by_status(ResalePayoutStatus::Paid, 0, 100, connection)
+            .unwrap()
+            .data
+            .is_empty()
+    );
+}
+
+#[test]
+fn mark_paid() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let listing = sold_listing(&project);
+    let payout = ResalePayout::find_for_resale_listing(listing.id, connection).unwrap();
+
+    let paid_payout = payout.mark_paid(admin.id, connection).unwrap();
+    assert_eq!(paid_payout.status, ResalePayoutStatus::Paid);
+    assert_eq!(paid_payout.paid_by_user_id, Some(admin.id));
+    assert!(paid_payout.paid_at.is_some());
+
+    // A payout can only be paid once
+    let result = payout.mark_paid(admin.id, connection);
+    assert_eq!(
+        result.unwrap_err().error_code,
+        ErrorCode::BusinessProcessError
+    );
+}
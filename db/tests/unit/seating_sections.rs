@@ -0,0 +1,143 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let seating_section = SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(seating_section.name, "Section A".to_string());
+    assert_eq!(seating_section.stage_id, stage.id);
+    assert_eq!(
+        SeatingSection::find(seating_section.id, connection).unwrap(),
+        seating_section
+    );
+}
+
+#[test]
+fn add_row() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    let seating_section = SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap();
+
+    let seats = seating_section
+        .add_row("A".to_string(), 3, Some(price_zone.id), connection)
+        .unwrap();
+    assert_eq!(seats.len(), 3);
+    assert_eq!(
+        seats
+            .iter()
+            .map(|s| s.seat_number.clone())
+            .collect::<Vec<String>>(),
+        vec!["1".to_string(), "2".to_string(), "3".to_string()]
+    );
+    assert!(seats.iter().all(|s| s.row_name == "A".to_string()
+        && s.price_zone_id == Some(price_zone.id)
+        && s.seating_section_id == seating_section.id));
+    assert_eq!(seating_section.seats(connection).unwrap(), seats);
+}
+
+#[test]
+fn add_row_with_price_zone_from_other_stage() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let stage2 = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage2.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    let seating_section = SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap();
+
+    let result = seating_section.add_row("A".to_string(), 3, Some(price_zone.id), connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("price_zone_id"));
+                assert_eq!(
+                    &errors["price_zone_id"][0]
+                        .message
+                        .clone()
+                        .unwrap()
+                        .into_owned(),
+                    "Price zone does not belong to this stage"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let seating_section = SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap();
+    seating_section
+        .add_row("A".to_string(), 3, None, connection)
+        .unwrap();
+
+    assert_eq!(seating_section.destroy(connection).unwrap(), 1);
+    assert!(SeatingSection::find_by_stage_id(stage.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn destroy_with_assigned_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    let seating_section = SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap();
+    seating_section
+        .add_row("A".to_string(), 3, Some(price_zone.id), connection)
+        .unwrap();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type = ticket_type
+        .set_price_zone(Some(price_zone.id), connection)
+        .unwrap();
+
+    let result = seating_section.destroy(connection);
+    assert_eq!(
+        result.unwrap_err().error_code,
+        ErrorCode::BusinessProcessError
+    );
+
+    // Seats are released once the ticket type is no longer seated
+    ticket_type.set_price_zone(None, connection).unwrap();
+    assert_eq!(seating_section.destroy(connection).unwrap(), 1);
+}
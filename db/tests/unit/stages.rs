@@ -127,3 +127,32 @@ fn destroy() {
     let all_stages = vec![stage_1, stage_2];
     assert_eq!(venue_1_stages, all_stages);
 }
+
+#[test]
+fn seat_map() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    let seating_section = SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap();
+    let seats = seating_section
+        .add_row("A".to_string(), 2, Some(price_zone.id), connection)
+        .unwrap();
+
+    let seat_map = stage.seat_map(connection).unwrap();
+    assert_eq!(seat_map.stage_id, stage.id);
+    assert_eq!(seat_map.price_zones, vec![price_zone]);
+    assert_eq!(
+        seat_map.seating_sections,
+        vec![DisplaySeatingSection {
+            id: seating_section.id,
+            name: seating_section.name.clone(),
+            seats,
+        }]
+    );
+}
@@ -0,0 +1,147 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let tax_rate = TaxRate::create(organization.id, None, "VAT".to_string(), 20.0, true)
+        .commit(user.id, connection)
+        .unwrap();
+    assert_eq!(tax_rate.organization_id, organization.id);
+    assert_eq!(tax_rate.rate_percent, 20.0);
+    assert!(tax_rate.inclusive);
+    assert_eq!(
+        TaxRate::find_for_organization(organization.id, connection).unwrap(),
+        vec![tax_rate]
+    );
+
+    // Only one organization wide rate is allowed
+    assert!(
+        TaxRate::create(organization.id, None, "Sales Tax".to_string(), 5.0, false)
+            .commit(user.id, connection)
+            .is_err()
+    );
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let result = TaxRate::create(organization.id, None, "VAT".to_string(), 120.0, false)
+        .commit(user.id, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("rate_percent"));
+                assert_eq!(
+                    errors["rate_percent"][0]
+                        .message
+                        .clone()
+                        .unwrap()
+                        .into_owned(),
+                    "Tax rate must be between 0 and 100 percent"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let region = project.create_region().finish();
+    let other_region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let other_venue = project.create_venue().with_region(&other_region).finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .finish();
+    let other_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&other_venue)
+        .finish();
+    assert_eq!(TaxRate::find_for_event(&event, connection).unwrap(), None);
+
+    let organization_rate =
+        TaxRate::create(organization.id, None, "Sales Tax".to_string(), 5.0, false)
+            .commit(user.id, connection)
+            .unwrap();
+    let region_rate = TaxRate::create(
+        organization.id,
+        Some(region.id),
+        "State Tax".to_string(),
+        8.5,
+        false,
+    )
+    .commit(user.id, connection)
+    .unwrap();
+
+    assert_eq!(
+        TaxRate::find_for_event(&event, connection).unwrap(),
+        Some(region_rate)
+    );
+    assert_eq!(
+        TaxRate::find_for_event(&other_event, connection).unwrap(),
+        Some(organization_rate)
+    );
+}
+
+#[test]
+fn tax_in_cents() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let region = project.create_region().finish();
+
+    let exclusive = TaxRate::create(organization.id, None, "Sales Tax".to_string(), 8.5, false)
+        .commit(user.id, connection)
+        .unwrap();
+    assert_eq!(exclusive.tax_in_cents(1000), 85);
+    assert_eq!(exclusive.charge_in_cents(1000), 85);
+
+    let inclusive = TaxRate::create(
+        organization.id,
+        Some(region.id),
+        "VAT".to_string(),
+        20.0,
+        true,
+    )
+    .commit(user.id, connection)
+    .unwrap();
+    assert_eq!(inclusive.tax_in_cents(1200), 200);
+    assert_eq!(inclusive.charge_in_cents(1200), 0);
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let tax_rate = TaxRate::create(organization.id, None, "VAT".to_string(), 20.0, true)
+        .commit(user.id, connection)
+        .unwrap();
+
+    tax_rate.destroy(user.id, connection).unwrap();
+    assert!(TaxRate::find(tax_rate.id, connection).is_err());
+    assert!(TaxRate::find_for_organization(organization.id, connection)
+        .unwrap()
+        .is_empty());
+}
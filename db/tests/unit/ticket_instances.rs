@@ -321,6 +321,7 @@ fn release() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -506,6 +507,7 @@ fn find() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -534,6 +536,9 @@ fn find() {
         status: TicketInstanceStatus::Reserved,
         redeem_key: ticket.redeem_key,
         pending_transfer: false,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
     };
     assert_eq!(
         (display_event, None, expected_ticket),
@@ -573,6 +578,7 @@ fn find_show_no_token() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -608,6 +614,9 @@ fn find_show_no_token() {
         status: TicketInstanceStatus::Purchased,
         redeem_key: None,
         pending_transfer: false,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
     };
     let (found_event, found_user, found_ticket) =
         TicketInstance::find_for_display(ticket.id, connection).unwrap();
@@ -662,6 +671,7 @@ fn find_for_user() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -694,6 +704,7 @@ fn release_tickets() {
                 ticket_type_id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -751,6 +762,7 @@ fn release_tickets_cancelled_ticket_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -827,6 +839,7 @@ fn mark_as_purchased() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1061,6 +1074,7 @@ fn authorize_ticket_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1123,6 +1137,7 @@ fn receive_ticket_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1,7 +1,11 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
-use bigneon_db::utils::errors::ErrorCode::ValidationError;
-use chrono::NaiveDate;
+use bigneon_db::schema::ticket_pricing;
+use bigneon_db::utils::errors::ErrorCode::{self, ValidationError};
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use time::Duration;
 
 #[test]
 fn create() {
@@ -102,6 +106,7 @@ fn ticket_pricing_no_overlapping_periods() {
         end_date1,
         false,
         TicketPricingStatus::Published,
+        None,
         project.get_connection()
     )
     .unwrap()
@@ -113,6 +118,7 @@ fn ticket_pricing_no_overlapping_periods() {
         end_date2,
         false,
         TicketPricingStatus::Published,
+        None,
         project.get_connection()
     )
     .unwrap()
@@ -126,6 +132,7 @@ fn ticket_pricing_no_overlapping_periods() {
         end_date3,
         false,
         TicketPricingStatus::Published,
+        None,
         project.get_connection()
     )
     .unwrap()
@@ -350,6 +357,7 @@ fn update() {
         start_date: Some(update_start_date),
         end_date: Some(update_end_date),
         is_box_office_only: Some(false),
+        ..Default::default()
     };
     let updated_ticket_pricing = ticket_pricing
         .update(update_parameters, connection)
@@ -394,6 +402,7 @@ fn update_with_affected_orders() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -418,6 +427,7 @@ fn update_with_affected_orders() {
         start_date: Some(update_start_date),
         end_date: Some(update_end_date),
         is_box_office_only: Some(false),
+        ..Default::default()
     };
     let updated_ticket_pricing = ticket_pricing
         .update(update_parameters, connection)
@@ -549,7 +559,6 @@ fn get_current_ticket_pricing() {
     let ticket_pricing = TicketPricing::get_current_ticket_pricing(
         ticket_types[0].id,
         false,
-        false,
         project.get_connection(),
     )
     .unwrap();
@@ -557,6 +566,213 @@ fn get_current_ticket_pricing() {
     assert_eq!(ticket_pricing.name, "Standard".to_string())
 }
 
+#[test]
+fn get_current_ticket_pricing_with_overlapping_periods() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+
+    // Validation prevents overlapping periods so the early bird period is extended directly
+    diesel::update(
+        ticket_pricing::table
+            .filter(ticket_pricing::ticket_type_id.eq(ticket_type.id))
+            .filter(ticket_pricing::name.eq("Early bird")),
+    )
+    .set(ticket_pricing::end_date.eq(Utc::now().naive_utc() + Duration::days(1)))
+    .execute(connection)
+    .unwrap();
+
+    let result = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, connection);
+    assert_eq!(
+        result.unwrap_err().error_code,
+        ErrorCode::MultipleResultsWhenOneExpected
+    );
+}
+
+#[test]
+fn get_current_ticket_pricing_with_quantity_tiers() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let start_date = Utc::now().naive_utc() - Duration::days(1);
+    let end_date = Utc::now().naive_utc() + Duration::days(1);
+    let second_tier = ticket_type
+        .add_ticket_pricing_tier(
+            "Second Tier".to_string(),
+            start_date,
+            end_date,
+            130,
+            3,
+            2,
+            false,
+            connection,
+        )
+        .unwrap();
+    let first_tier = ticket_type
+        .add_ticket_pricing_tier(
+            "First Tier".to_string(),
+            start_date,
+            end_date,
+            120,
+            2,
+            1,
+            false,
+            connection,
+        )
+        .unwrap();
+    // Tiers may overlap each other and date based pricing
+    assert!(TicketPricing::ticket_pricing_no_overlapping_periods(
+        first_tier.id,
+        ticket_type.id,
+        start_date,
+        end_date,
+        false,
+        TicketPricingStatus::Published,
+        first_tier.quantity_limit,
+        connection
+    )
+    .unwrap()
+    .is_ok());
+
+    assert_eq!(
+        TicketPricing::get_current_ticket_pricing(ticket_type.id, false, connection).unwrap(),
+        first_tier
+    );
+    assert_eq!(
+        event
+            .current_ticket_pricing_range(false, connection)
+            .unwrap(),
+        (Some(120), Some(120))
+    );
+
+    // Carts spanning tiers are split into an item per tier
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 4,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let first_tier_item = items
+        .iter()
+        .find(|i| i.ticket_pricing_id == Some(first_tier.id))
+        .unwrap();
+    assert_eq!(first_tier_item.quantity, 2);
+    assert_eq!(first_tier_item.unit_price_in_cents, 120);
+    let second_tier_item = items
+        .iter()
+        .find(|i| i.ticket_pricing_id == Some(second_tier.id))
+        .unwrap();
+    assert_eq!(second_tier_item.quantity, 2);
+    assert_eq!(second_tier_item.unit_price_in_cents, 130);
+    assert_eq!(first_tier.remaining_quantity(connection).unwrap(), Some(0));
+    assert_eq!(second_tier.remaining_quantity(connection).unwrap(), Some(1));
+    assert_eq!(
+        TicketPricing::get_current_ticket_pricing(ticket_type.id, false, connection).unwrap(),
+        second_tier
+    );
+
+    // Once all tiers are sold through the date based pricing applies
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 6,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let ticket_items: Vec<&OrderItem> = items
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    assert_eq!(ticket_items.len(), 3);
+    assert_eq!(ticket_items.iter().map(|i| i.quantity).sum::<i64>(), 6);
+    let standard_item = ticket_items
+        .iter()
+        .find(|i| {
+            i.ticket_pricing_id != Some(first_tier.id)
+                && i.ticket_pricing_id != Some(second_tier.id)
+        })
+        .unwrap();
+    assert_eq!(standard_item.quantity, 1);
+    assert_eq!(standard_item.unit_price_in_cents, 150);
+    assert_eq!(
+        event
+            .current_ticket_pricing_range(false, connection)
+            .unwrap(),
+        (Some(150), Some(150))
+    );
+
+    // Releasing tickets frees up the tier
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let ticket_items: Vec<&OrderItem> = items
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    assert_eq!(ticket_items.len(), 1);
+    assert_eq!(ticket_items[0].ticket_pricing_id, Some(first_tier.id));
+    assert_eq!(ticket_items[0].quantity, 1);
+}
+
+#[test]
+fn create_tier_with_invalid_quantity() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let result = ticket_type.add_ticket_pricing_tier(
+        "First Tier".to_string(),
+        Utc::now().naive_utc() - Duration::days(1),
+        Utc::now().naive_utc() + Duration::days(1),
+        120,
+        0,
+        1,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_pricing.quantity_limit"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn get_current_ticket_capacity() {
     let project = TestProject::new();
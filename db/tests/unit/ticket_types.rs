@@ -123,6 +123,7 @@ fn valid_unsold_ticket_count() {
             ticket_type_id: ticket_type.id,
             quantity: 50,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -154,6 +155,7 @@ fn valid_unsold_ticket_count() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -610,6 +612,7 @@ pub fn remaining_ticket_count() {
                 ticket_type_id: ticket_type.id,
                 quantity: 20,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -630,6 +633,7 @@ pub fn remaining_ticket_count() {
                 ticket_type_id: ticket_type.id,
                 quantity: 16,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -667,6 +671,83 @@ fn update() {
     assert_eq!(updated_ticket_type.end_date, update_end_date);
 }
 
+#[test]
+fn set_price_zone() {
+    let db = TestProject::new();
+    let connection = db.get_connection();
+    let venue = db.create_venue().finish();
+    let stage = db.create_stage().with_venue_id(venue.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    let seating_section = SeatingSection::create(stage.id, "Section A".to_string())
+        .commit(connection)
+        .unwrap();
+    let seats = seating_section
+        .add_row("A".to_string(), 3, Some(price_zone.id), connection)
+        .unwrap();
+    let event = db
+        .create_event()
+        .with_venue(&venue)
+        .with_a_specific_number_of_tickets(5)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+
+    let ticket_type = ticket_type
+        .set_price_zone(Some(price_zone.id), connection)
+        .unwrap();
+    assert_eq!(ticket_type.price_zone_id, Some(price_zone.id));
+
+    let seat_availability = ticket_type.seats(connection).unwrap();
+    assert_eq!(seat_availability.len(), 3);
+    assert!(seat_availability.iter().all(|s| s.available));
+    let mut seat_ids: Vec<Uuid> = seat_availability.iter().map(|s| s.seat_id).collect();
+    let mut expected_seat_ids: Vec<Uuid> = seats.iter().map(|s| s.id).collect();
+    seat_ids.sort();
+    expected_seat_ids.sort();
+    assert_eq!(seat_ids, expected_seat_ids);
+
+    // Clearing the price zone releases the seats
+    let ticket_type = ticket_type.set_price_zone(None, connection).unwrap();
+    assert_eq!(ticket_type.price_zone_id, None);
+    assert!(ticket_type.seats(connection).unwrap().is_empty());
+}
+
+#[test]
+fn set_price_zone_from_other_venue() {
+    let db = TestProject::new();
+    let connection = db.get_connection();
+    let venue = db.create_venue().finish();
+    let venue2 = db.create_venue().finish();
+    let stage = db.create_stage().with_venue_id(venue2.id).finish();
+    let price_zone = PriceZone::create(stage.id, "Orchestra".to_string())
+        .commit(connection)
+        .unwrap();
+    let event = db.create_event().with_venue(&venue).with_tickets().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+
+    let result = ticket_type.set_price_zone(Some(price_zone.id), connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("price_zone_id"));
+                assert_eq!(
+                    &errors["price_zone_id"][0]
+                        .message
+                        .clone()
+                        .unwrap()
+                        .into_owned(),
+                    "Price zone does not belong to the event's venue"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn cancel() {
     let db = TestProject::new();
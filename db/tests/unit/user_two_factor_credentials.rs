@@ -0,0 +1,228 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use bigneon_db::utils::totp;
+use chrono::Utc;
+
+fn current_code(secret: &str) -> String {
+    totp::code_for_timestamp(secret, Utc::now().timestamp()).unwrap()
+}
+
+fn enable_two_factor(user: &User, project: &TestProject) -> (String, Vec<String>) {
+    let connection = project.get_connection();
+    let encryption_key = "encryption_key".to_string();
+    let secret =
+        UserTwoFactorCredential::begin_enrolment(user.id, &encryption_key, connection).unwrap();
+    let credential = UserTwoFactorCredential::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+    let recovery_codes = credential
+        .confirm(&current_code(&secret), &encryption_key, connection)
+        .unwrap();
+    (secret, recovery_codes)
+}
+
+#[test]
+fn begin_enrolment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let encryption_key = "encryption_key".to_string();
+
+    let secret =
+        UserTwoFactorCredential::begin_enrolment(user.id, &encryption_key, connection).unwrap();
+    let credential = UserTwoFactorCredential::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_ne!(credential.secret, secret);
+    assert!(!credential.is_enabled());
+    assert!(!user.two_factor_enabled(connection).unwrap());
+    assert_eq!(
+        UserTwoFactorCredential::find_enabled_for_user(user.id, connection).unwrap(),
+        None
+    );
+
+    // Restarting enrolment replaces the unconfirmed secret
+    let new_secret =
+        UserTwoFactorCredential::begin_enrolment(user.id, &encryption_key, connection).unwrap();
+    assert_ne!(new_secret, secret);
+    let new_credential = UserTwoFactorCredential::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_ne!(new_credential.id, credential.id);
+}
+
+#[test]
+fn begin_enrolment_when_already_enabled() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    enable_two_factor(&user, &project);
+
+    let result = UserTwoFactorCredential::begin_enrolment(
+        user.id,
+        &"encryption_key".to_string(),
+        connection,
+    );
+    assert_eq!(
+        result.unwrap_err().error_code,
+        ErrorCode::BusinessProcessError
+    );
+}
+
+#[test]
+fn confirm() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let encryption_key = "encryption_key".to_string();
+    let secret =
+        UserTwoFactorCredential::begin_enrolment(user.id, &encryption_key, connection).unwrap();
+    let credential = UserTwoFactorCredential::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+
+    let result = credential.confirm("000000", &encryption_key, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("code"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+    assert!(!user.two_factor_enabled(connection).unwrap());
+
+    let recovery_codes = credential
+        .confirm(&current_code(&secret), &encryption_key, connection)
+        .unwrap();
+    assert_eq!(recovery_codes.len(), 10);
+    assert!(user.two_factor_enabled(connection).unwrap());
+    let credential = UserTwoFactorCredential::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(credential.recovery_code_hashes.len(), 10);
+    assert!(!credential.recovery_code_hashes.contains(&recovery_codes[0]));
+}
+
+#[test]
+fn verify() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let encryption_key = "encryption_key".to_string();
+    let (secret, recovery_codes) = enable_two_factor(&user, &project);
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+
+    // The code used to confirm enrolment cannot be used again
+    let used_code =
+        totp::code_for_timestamp(&secret, credential.last_used_step.unwrap() * 30).unwrap();
+    assert!(!credential
+        .verify(&used_code, &encryption_key, connection)
+        .unwrap());
+    assert!(!credential
+        .verify("not a code", &encryption_key, connection)
+        .unwrap());
+
+    assert!(credential
+        .verify(
+            &recovery_codes[0].to_uppercase(),
+            &encryption_key,
+            connection
+        )
+        .unwrap());
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(credential.recovery_code_hashes.len(), 9);
+    assert!(!credential
+        .verify(&recovery_codes[0], &encryption_key, connection)
+        .unwrap());
+}
+
+#[test]
+fn verify_locks_after_failed_attempts() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let encryption_key = "encryption_key".to_string();
+    let (_, recovery_codes) = enable_two_factor(&user, &project);
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+
+    // A valid code resets the count of invalid codes
+    for _ in 0..4 {
+        assert!(!credential
+            .verify("not a code", &encryption_key, connection)
+            .unwrap());
+    }
+    assert!(credential
+        .verify(&recovery_codes[0], &encryption_key, connection)
+        .unwrap());
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(credential.failed_attempts, 0);
+    assert!(!credential.is_locked());
+
+    for _ in 0..5 {
+        assert!(!credential
+            .verify("not a code", &encryption_key, connection)
+            .unwrap());
+    }
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert!(credential.is_locked());
+
+    // Valid codes are refused while locked
+    assert!(!credential
+        .verify(&recovery_codes[1], &encryption_key, connection)
+        .unwrap());
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(credential.recovery_code_hashes.len(), 9);
+}
+
+#[test]
+fn regenerate_recovery_codes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (_, recovery_codes) = enable_two_factor(&user, &project);
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+
+    let new_recovery_codes = credential.regenerate_recovery_codes(connection).unwrap();
+    assert_eq!(new_recovery_codes.len(), 10);
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert!(!credential
+        .use_recovery_code(&recovery_codes[0], connection)
+        .unwrap());
+    assert!(credential
+        .use_recovery_code(&new_recovery_codes[0], connection)
+        .unwrap());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    enable_two_factor(&user, &project);
+    let credential = UserTwoFactorCredential::find_enabled_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+
+    credential.destroy(connection).unwrap();
+    assert!(!user.two_factor_enabled(connection).unwrap());
+}
@@ -185,6 +185,7 @@ fn get_profile_for_organization() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -288,6 +289,7 @@ fn get_profile_for_organization() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -346,6 +348,7 @@ fn get_profile_for_organization() {
             ticket_type_id: ticket_type2.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -440,6 +443,7 @@ fn get_profile_for_organization() {
             ticket_type_id: ticket_type2.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -504,6 +508,7 @@ fn get_profile_for_organization() {
             ticket_type_id: ticket_type3.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -613,6 +618,7 @@ fn get_history_for_organization() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -658,6 +664,7 @@ fn get_history_for_organization() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -724,6 +731,22 @@ fn find() {
     );
 }
 
+#[test]
+fn find_by_ids() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    project.create_user().finish();
+
+    let found_users = User::find_by_ids(&[user.id, user2.id, Uuid::new_v4()], connection).unwrap();
+    let mut found_ids: Vec<Uuid> = found_users.iter().map(|u| u.id).collect();
+    found_ids.sort();
+    let mut expected_ids = vec![user.id, user2.id];
+    expected_ids.sort();
+    assert_eq!(found_ids, expected_ids);
+}
+
 #[test]
 fn get_event_ids_by_organization() {
     let project = TestProject::new();
@@ -1166,6 +1189,7 @@ fn get_scopes_by_organization() {
             Scopes::EventWrite,
             Scopes::HoldRead,
             Scopes::HoldWrite,
+            Scopes::OrderExchange,
             Scopes::OrderMakeExternalPayment,
             Scopes::OrderRead,
             Scopes::OrderReadOwn,
@@ -1205,6 +1229,7 @@ fn get_scopes_by_organization() {
             Scopes::EventWrite,
             Scopes::HoldRead,
             Scopes::HoldWrite,
+            Scopes::OrderExchange,
             Scopes::OrderRead,
             Scopes::OrderReadOwn,
             Scopes::OrderRefund,
@@ -1280,6 +1305,7 @@ fn get_global_scopes() {
             "event:write",
             "hold:read",
             "hold:write",
+            "order:exchange",
             "order:make-external-payment",
             "order:read",
             "order:read-own",
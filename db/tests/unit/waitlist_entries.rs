@@ -0,0 +1,505 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+
+fn sold_out_ticket_type(project: &TestProject) -> (TicketType, User, Order) {
+    let creator = project.create_user().finish();
+    let connection = project.get_connection();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_a_specific_number_of_tickets(2)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event
+        .ticket_types(true, None, connection)
+        .unwrap()
+        .remove(0);
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    (ticket_type, user, cart)
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (ticket_type, _, _) = sold_out_ticket_type(&project);
+    let user = project.create_user().finish();
+
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(waitlist_entry.ticket_type_id, ticket_type.id);
+    assert_eq!(waitlist_entry.user_id, user.id);
+    assert_eq!(waitlist_entry.quantity, 1);
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+    assert_eq!(
+        WaitlistEntry::find(waitlist_entry.id, connection).unwrap(),
+        waitlist_entry
+    );
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    // Tickets are still available
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let result = WaitlistEntry::create(ticket_type.id, user.id, 1).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+                assert_eq!(
+                    errors["ticket_type_id"][0]
+                        .message
+                        .clone()
+                        .unwrap()
+                        .into_owned(),
+                    "Waitlist is only available for sold out ticket types"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Already on the waitlist
+    let (ticket_type, _, _) = sold_out_ticket_type(&project);
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let result = WaitlistEntry::create(ticket_type.id, user.id, 2).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+                assert_eq!(
+                    errors["ticket_type_id"][0]
+                        .message
+                        .clone()
+                        .unwrap()
+                        .into_owned(),
+                    "User is already on the waitlist for this ticket type"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Invalid quantity
+    let user2 = project.create_user().finish();
+    let result = WaitlistEntry::create(ticket_type.id, user2.id, 0).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("quantity"));
+                assert_eq!(
+                    errors["quantity"][0].message.clone().unwrap().into_owned(),
+                    "Quantity must be at least 1"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (ticket_type, _, _) = sold_out_ticket_type(&project);
+    let user = project.create_user().finish();
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    let waitlist_entry = waitlist_entry.cancel(connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+    assert!(
+        WaitlistEntry::find_active_for_user(ticket_type.id, user.id, connection)
+            .unwrap()
+            .is_none()
+    );
+
+    // Can rejoin after cancelling
+    assert!(WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .is_ok());
+}
+
+#[test]
+fn queue_offers_on_release() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (ticket_type, buyer, mut cart) = sold_out_ticket_type(&project);
+    let user = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    assert!(
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessWaitlist), connection)
+            .unwrap()
+            .iter()
+            .all(|a| a.main_table_id != Some(ticket_type.id))
+    );
+
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert!(
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessWaitlist), connection)
+            .unwrap()
+            .iter()
+            .any(|a| a.main_table_id == Some(ticket_type.id))
+    );
+}
+
+#[test]
+fn commit_schedules_processing_when_carts_expire() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (ticket_type, _, _) = sold_out_ticket_type(&project);
+    let user = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    // Processing is scheduled for when the cart holding the tickets expires
+    assert!(DomainAction::has_pending_action(
+        DomainActionTypes::ProcessWaitlist,
+        Tables::TicketTypes.to_string(),
+        ticket_type.id,
+        connection,
+    )
+    .unwrap());
+    assert!(
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessWaitlist), connection)
+            .unwrap()
+            .iter()
+            .all(|a| a.main_table_id != Some(ticket_type.id))
+    );
+}
+
+#[test]
+fn make_offers() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (ticket_type, buyer, mut cart) = sold_out_ticket_type(&project);
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let waitlist_entry2 = WaitlistEntry::create(ticket_type.id, user2.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    // Nothing available yet
+    assert!(WaitlistEntry::make_offers(ticket_type.id, connection)
+        .unwrap()
+        .is_empty());
+
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // First in line receives the released ticket
+    let offered = WaitlistEntry::make_offers(ticket_type.id, connection).unwrap();
+    assert_eq!(offered.len(), 1);
+    assert_eq!(offered[0].id, waitlist_entry.id);
+    assert_eq!(offered[0].status, WaitlistEntryStatus::Offered);
+    assert!(offered[0].offer_expires_at.is_some());
+
+    let user_cart = Order::find_cart_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(offered[0].order_id, Some(user_cart.id));
+    assert_eq!(user_cart.expires_at, offered[0].offer_expires_at);
+    let order_item = user_cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    assert_eq!(order_item.ticket_type_id, Some(ticket_type.id));
+    assert_eq!(order_item.quantity, 1);
+
+    let waitlist_entry2 = WaitlistEntry::find(waitlist_entry2.id, connection).unwrap();
+    assert_eq!(waitlist_entry2.status, WaitlistEntryStatus::Waiting);
+}
+
+#[test]
+fn make_offers_only_extends_offered_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (ticket_type, buyer, mut cart) = sold_out_ticket_type(&project);
+    let user = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    // Unrelated tickets already in the waitlisted user's cart
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+    let mut user_cart = Order::find_or_create_cart(&user, connection).unwrap();
+    user_cart
+        .update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: other_ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let cart_expires_at = user_cart.expires_at.unwrap();
+
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let offered = WaitlistEntry::make_offers(ticket_type.id, connection).unwrap();
+    let offer_expires_at = offered[0].offer_expires_at.unwrap();
+
+    let user_cart = Order::find(user_cart.id, connection).unwrap();
+    assert_eq!(user_cart.expires_at, Some(offer_expires_at));
+    for item in user_cart.items(connection).unwrap() {
+        if item.item_type != OrderItemTypes::Tickets {
+            continue;
+        }
+        let expected = if item.ticket_type_id == Some(ticket_type.id) {
+            offer_expires_at
+        } else {
+            cart_expires_at
+        };
+        for ticket in TicketInstance::find_for_order_item(item.id, connection).unwrap() {
+            assert_eq!(ticket.reserved_until, Some(expected));
+        }
+    }
+
+    // Tickets added while the offer is outstanding are held for the usual cart time
+    let mut user_cart = user_cart;
+    user_cart
+        .update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: other_ticket_type.id,
+                quantity: 2,
+                redemption_code: None,
+                seat_ids: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let other_item = user_cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.ticket_type_id == Some(other_ticket_type.id))
+        .unwrap();
+    for ticket in TicketInstance::find_for_order_item(other_item.id, connection).unwrap() {
+        assert!(ticket.reserved_until.unwrap() < offer_expires_at);
+    }
+}
+
+#[test]
+fn make_offers_keeps_cart_items() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(3)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event
+        .ticket_types(true, None, connection)
+        .unwrap()
+        .remove(0);
+    let user = project.create_user().finish();
+    let mut user_cart = Order::find_or_create_cart(&user, connection).unwrap();
+    user_cart
+        .update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let cart_item = user_cart.items(connection).unwrap().remove(0);
+    let cart_tickets = TicketInstance::find_for_order_item(cart_item.id, connection).unwrap();
+
+    let buyer = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let offered = WaitlistEntry::make_offers(ticket_type.id, connection).unwrap();
+    assert_eq!(offered.len(), 1);
+
+    // The offered ticket is added as its own item, the ticket already in the cart is kept
+    let items: Vec<OrderItem> = user_cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    assert_eq!(items.len(), 2);
+    assert!(items
+        .iter()
+        .any(|i| i.id == cart_item.id && i.quantity == 1));
+    assert_eq!(
+        TicketInstance::find_for_order_item(cart_item.id, connection).unwrap(),
+        cart_tickets
+    );
+}
+
+#[test]
+fn cancel_offered() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (ticket_type, buyer, mut cart) = sold_out_ticket_type(&project);
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let waitlist_entry2 = WaitlistEntry::create(ticket_type.id, user2.id, 1)
+        .commit(connection)
+        .unwrap();
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let offered = WaitlistEntry::make_offers(ticket_type.id, connection)
+        .unwrap()
+        .remove(0);
+    assert_eq!(
+        ticket_type
+            .valid_available_ticket_count(connection)
+            .unwrap(),
+        0
+    );
+
+    // Leaving releases the offered tickets and queues an offer to the next in line
+    let waitlist_entry = offered.cancel(connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+    let user_cart = Order::find(offered.order_id.unwrap(), connection).unwrap();
+    assert!(user_cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .all(|i| i.ticket_type_id != Some(ticket_type.id)));
+    assert_eq!(
+        ticket_type
+            .valid_available_ticket_count(connection)
+            .unwrap(),
+        1
+    );
+
+    let offered = WaitlistEntry::make_offers(ticket_type.id, connection).unwrap();
+    assert_eq!(offered.len(), 1);
+    assert_eq!(offered[0].id, waitlist_entry2.id);
+}
@@ -0,0 +1,299 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let new_webhook_subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec![DomainEventTypes::OrderCompleted],
+        user.id,
+    );
+    assert_eq!(new_webhook_subscription.secret.len(), 32);
+    let webhook_subscription = new_webhook_subscription
+        .commit(&"encryption_key".to_string(), connection)
+        .unwrap();
+    assert_eq!(webhook_subscription.organization_id, organization.id);
+    assert_eq!(
+        webhook_subscription.event_types,
+        vec![DomainEventTypes::OrderCompleted]
+    );
+    // The secret is stored encrypted
+    assert_ne!(webhook_subscription.secret, new_webhook_subscription.secret);
+    assert_eq!(
+        WebhookSubscription::find_for_organization(organization.id, connection).unwrap(),
+        vec![webhook_subscription]
+    );
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let result = WebhookSubscription::create(
+        organization.id,
+        "not a url".to_string(),
+        vec![DomainEventTypes::OrderCompleted],
+        user.id,
+    )
+    .commit(&"encryption_key".to_string(), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("url"));
+                assert_eq!(
+                    errors["url"][0].message.clone().unwrap().into_owned(),
+                    "Webhook URL is invalid"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    for (url, message) in &[
+        ("http://example.com/webhooks", "URL must use https"),
+        (
+            "https://localhost/webhooks",
+            "URL must be for a public host",
+        ),
+        ("https://10.0.0.1/webhooks", "URL must be for a public host"),
+        (
+            "https://169.254.169.254/latest",
+            "URL must be for a public host",
+        ),
+    ] {
+        let result = WebhookSubscription::create(
+            organization.id,
+            url.to_string(),
+            vec![DomainEventTypes::OrderCompleted],
+            user.id,
+        )
+        .commit(&"encryption_key".to_string(), connection);
+        match result {
+            Ok(_) => panic!("Expected validation error"),
+            Err(error) => match &error.error_code {
+                ValidationError { errors } => {
+                    assert!(errors.contains_key("url"));
+                    assert_eq!(
+                        errors["url"][0].message.clone().unwrap().into_owned(),
+                        *message
+                    );
+                }
+                _ => panic!("Expected validation error"),
+            },
+        }
+    }
+
+    let result = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec![],
+        user.id,
+    )
+    .commit(&"encryption_key".to_string(), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_types"));
+                assert_eq!(
+                    errors["event_types"][0]
+                        .message
+                        .clone()
+                        .unwrap()
+                        .into_owned(),
+                    "At least one event type is required"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn sign() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let webhook_subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec![DomainEventTypes::OrderCompleted],
+        user.id,
+    )
+    .commit(&"encryption_key".to_string(), connection)
+    .unwrap();
+
+    let encryption_key = "encryption_key".to_string();
+    let signature = webhook_subscription.sign("{}", &encryption_key).unwrap();
+    assert_eq!(signature.len(), 64);
+    assert_eq!(
+        signature,
+        webhook_subscription.sign("{}", &encryption_key).unwrap()
+    );
+    assert_ne!(
+        signature,
+        webhook_subscription
+            .sign("{\"a\":1}", &encryption_key)
+            .unwrap()
+    );
+}
+
+#[test]
+fn queue_deliveries() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let other_event = project.create_event().finish();
+    let webhook_subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec![DomainEventTypes::TicketInstanceRedeemed],
+        user.id,
+    )
+    .commit(&"encryption_key".to_string(), connection)
+    .unwrap();
+
+    // Matching event type for the organization
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::TicketInstanceRedeemed,
+        "Ticket redeemed".to_string(),
+        Tables::Events,
+        Some(event.id),
+        Some(user.id),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let actions = WebhookSubscription::queue_deliveries(&domain_event, connection).unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        actions[0].domain_action_type,
+        DomainActionTypes::SendWebhook
+    );
+    assert_eq!(actions[0].domain_event_id, Some(domain_event.id));
+    assert_eq!(actions[0].main_table_id, Some(webhook_subscription.id));
+
+    // Event type not subscribed to
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::OrderCompleted,
+        "Order completed".to_string(),
+        Tables::Events,
+        Some(event.id),
+        Some(user.id),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert!(
+        WebhookSubscription::queue_deliveries(&domain_event, connection)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Event belonging to another organization
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::TicketInstanceRedeemed,
+        "Ticket redeemed".to_string(),
+        Tables::Events,
+        Some(other_event.id),
+        Some(user.id),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert!(
+        WebhookSubscription::queue_deliveries(&domain_event, connection)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Event from before the subscription was created
+    let mut domain_event = DomainEvent::create(
+        DomainEventTypes::TicketInstanceRedeemed,
+        "Ticket redeemed".to_string(),
+        Tables::Events,
+        Some(event.id),
+        Some(user.id),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    domain_event.created_at = webhook_subscription.created_at - Duration::days(1);
+    assert!(
+        WebhookSubscription::queue_deliveries(&domain_event, connection)
+            .unwrap()
+            .is_empty()
+    );
+
+    let (deliveries, total) = webhook_subscription.deliveries(20, 0, connection).unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(deliveries[0].id, actions[0].id);
+    assert_eq!(
+        deliveries[0].event_type,
+        DomainEventTypes::TicketInstanceRedeemed
+    );
+    assert_eq!(deliveries[0].status, DomainActionStatus::Pending);
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let webhook_subscription = WebhookSubscription::create(
+        organization.id,
+        "https://example.com/webhooks".to_string(),
+        vec![DomainEventTypes::OrderCompleted],
+        user.id,
+    )
+    .commit(&"encryption_key".to_string(), connection)
+    .unwrap();
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::OrderCompleted,
+        "Order completed".to_string(),
+        Tables::Events,
+        Some(event.id),
+        Some(user.id),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let actions = WebhookSubscription::queue_deliveries(&domain_event, connection).unwrap();
+
+    webhook_subscription.destroy(connection).unwrap();
+    assert!(WebhookSubscription::find(webhook_subscription.id, connection).is_err());
+    assert!(
+        WebhookSubscription::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        DomainAction::find(actions[0].id, connection)
+            .unwrap()
+            .status,
+        DomainActionStatus::Cancelled
+    );
+}
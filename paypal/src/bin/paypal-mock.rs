@@ -0,0 +1,18 @@
+//! Runs the PayPal mock server so that checkout can be used locally without a sandbox account.
+//! Point `PAYPAL_BASE_URL` at the address it listens on.
+extern crate paypal;
+
+use paypal::MockServer;
+use std::env;
+use std::thread;
+
+fn main() {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8089".to_string());
+    let server = MockServer::start_on(&address).expect("Could not start PayPal mock server");
+    println!("PayPal mock server listening on {}", server.base_url);
+    loop {
+        thread::park();
+    }
+}
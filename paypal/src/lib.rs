@@ -0,0 +1,24 @@
+#![deny(unreachable_patterns)]
+#![deny(unused_variables)]
+#![deny(unused_imports)]
+// Unused results is more often than not an error
+#![deny(unused_must_use)]
+extern crate log;
+#[macro_use]
+extern crate logging;
+extern crate reqwest;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+#[macro_use]
+extern crate serde_derive;
+
+pub use self::mock_server::MockServer;
+pub use self::order::*;
+pub use self::paypal_client::PaypalClient;
+pub use self::paypal_error::PaypalError;
+
+mod mock_server;
+mod order;
+mod paypal_client;
+mod paypal_error;
@@ -0,0 +1,508 @@
+use serde_json;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use Amount;
+use CreateOrderRequest;
+
+/// A stand-in for the PayPal REST API for development and tests without network access. It
+/// supports the calls made by `PaypalClient`, keeping orders in memory. Buyers approve an order
+/// by visiting its approve link, which redirects straight back to the order's `return_url`.
+#[derive(Clone)]
+pub struct MockServer {
+    pub base_url: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    next_id: u64,
+    orders: HashMap<String, MockOrder>,
+    captures: HashMap<String, MockCapture>,
+}
+
+struct MockOrder {
+    status: String,
+    request: CreateOrderRequest,
+    capture_id: Option<String>,
+    capture_status: String,
+}
+
+struct MockCapture {
+    status: String,
+    amount: Amount,
+    refunded_in_cents: i64,
+}
+
+struct MockRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    authorized: bool,
+    body: Value,
+}
+
+struct MockResponse {
+    status: u16,
+    body: Value,
+    location: Option<String>,
+}
+
+impl MockResponse {
+    fn json(status: u16, body: Value) -> MockResponse {
+        MockResponse {
+            status,
+            body,
+            location: None,
+        }
+    }
+
+    fn error(status: u16, name: &str, issue: &str) -> MockResponse {
+        MockResponse::json(
+            status,
+            json!({ "name": name, "details": [{ "issue": issue }] }),
+        )
+    }
+}
+
+impl MockServer {
+    /// Starts a server on a free local port
+    pub fn start() -> MockServer {
+        MockServer::start_on("127.0.0.1:0").expect("Could not start PayPal mock server")
+    }
+
+    pub fn start_on(address: &str) -> io::Result<MockServer> {
+        let listener = TcpListener::bind(address)?;
+        let server = MockServer {
+            base_url: format!("http://{}/", listener.local_addr()?),
+            state: Arc::new(Mutex::new(MockState::default())),
+        };
+
+        let handler = server.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if let Ok(stream) = stream {
+                    let handler = handler.clone();
+                    thread::spawn(move || handler.handle(stream));
+                }
+            }
+        });
+        Ok(server)
+    }
+
+    /// Approves an order as the buyer would on the PayPal checkout page
+    pub fn approve(&self, order_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.orders.get_mut(order_id) {
+            Some(order) => {
+                if order.status != "CREATED" {
+                    return false;
+                }
+                order.status = "APPROVED".to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the status the order's capture will have, e.g. `PENDING` for a payment held for review
+    /// or `DECLINED` for a payment the buyer's funding source refused
+    pub fn set_capture_status(&self, order_id: &str, status: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.orders.get_mut(order_id) {
+            Some(order) => {
+                order.capture_status = status.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Total refunded against a capture
+    pub fn refunded_in_cents(&self, capture_id: &str) -> Option<i64> {
+        let state = self.state.lock().unwrap();
+        state.captures.get(capture_id).map(|c| c.refunded_in_cents)
+    }
+
+    fn handle(&self, stream: TcpStream) {
+        let mut reader = BufReader::new(match stream.try_clone() {
+            Ok(stream) => stream,
+            Err(_) => return,
+        });
+        let response = match read_request(&mut reader) {
+            Ok(request) => self.route(request),
+            Err(_) => MockResponse::error(400, "INVALID_REQUEST", "MALFORMED_REQUEST"),
+        };
+        let _ = write_response(stream, response);
+    }
+
+    fn route(&self, request: MockRequest) -> MockResponse {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        if segments == ["checkoutnow"] {
+            return self.checkout_page(&request);
+        }
+        if !request.authorized {
+            return MockResponse::error(401, "AUTHENTICATION_FAILURE", "INVALID_TOKEN");
+        }
+
+        let mut state = self.state.lock().unwrap();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["v1", "oauth2", "token"]) => MockResponse::json(
+                200,
+                json!({
+                    "access_token": "mock_access_token",
+                    "token_type": "Bearer",
+                    "expires_in": 32400
+                }),
+            ),
+            ("POST", ["v2", "checkout", "orders"]) => {
+                let create_request: CreateOrderRequest =
+                    match serde_json::from_value(request.body.clone()) {
+                        Ok(create_request) => create_request,
+                        Err(_) => {
+                            return MockResponse::error(
+                                400,
+                                "INVALID_REQUEST",
+                                "MALFORMED_REQUEST_JSON",
+                            );
+                        }
+                    };
+                let id = state.next_id("ORDER");
+                state.orders.insert(
+                    id.clone(),
+                    MockOrder {
+                        status: "CREATED".to_string(),
+                        request: create_request,
+                        capture_id: None,
+                        capture_status: "COMPLETED".to_string(),
+                    },
+                );
+                MockResponse::json(201, state.order_json(&id, &self.base_url))
+            }
+            ("GET", ["v2", "checkout", "orders", id]) => match state.orders.get(*id) {
+                Some(_) => MockResponse::json(200, state.order_json(id, &self.base_url)),
+                None => MockResponse::error(404, "RESOURCE_NOT_FOUND", "INVALID_RESOURCE_ID"),
+            },
+            ("PATCH", ["v2", "checkout", "orders", id]) => {
+                let description = request.body[0]["value"].as_str().map(|d| d.to_string());
+                match state.orders.get_mut(*id) {
+                    Some(order) => {
+                        for unit in order.request.purchase_units.iter_mut() {
+                            unit.description = description.clone();
+                        }
+                        MockResponse::json(204, Value::Null)
+                    }
+                    None => MockResponse::error(404, "RESOURCE_NOT_FOUND", "INVALID_RESOURCE_ID"),
+                }
+            }
+            ("POST", ["v2", "checkout", "orders", id, "capture"]) => {
+                let (amount, capture_status) = match state.orders.get(*id) {
+                    Some(ref order) if order.status == "APPROVED" => (
+                        order
+                            .request
+                            .purchase_units
+                            .get(0)
+                            .and_then(|p| p.amount.clone()),
+                        order.capture_status.clone(),
+                    ),
+                    Some(ref order) if order.status == "COMPLETED" => {
+                        return MockResponse::error(
+                            422,
+                            "UNPROCESSABLE_ENTITY",
+                            "ORDER_ALREADY_CAPTURED",
+                        );
+                    }
+                    Some(_) => {
+                        return MockResponse::error(
+                            422,
+                            "UNPROCESSABLE_ENTITY",
+                            "ORDER_NOT_APPROVED",
+                        );
+                    }
+                    None => {
+                        return MockResponse::error(
+                            404,
+                            "RESOURCE_NOT_FOUND",
+                            "INVALID_RESOURCE_ID",
+                        );
+                    }
+                };
+                let capture_id = state.next_id("CAPTURE");
+                state.captures.insert(
+                    capture_id.clone(),
+                    MockCapture {
+                        status: capture_status,
+                        amount: amount.unwrap_or(Amount::from_cents(0, "USD")),
+                        refunded_in_cents: 0,
+                    },
+                );
+                if let Some(order) = state.orders.get_mut(*id) {
+                    order.status = "COMPLETED".to_string();
+                    order.capture_id = Some(capture_id);
+                }
+                MockResponse::json(201, state.order_json(id, &self.base_url))
+            }
+            ("POST", ["v2", "payments", "captures", id, "refund"]) => {
+                let refund_id = state.next_id("REFUND");
+                let capture = match state.captures.get_mut(*id) {
+                    Some(capture) => capture,
+                    None => {
+                        return MockResponse::error(
+                            404,
+                            "RESOURCE_NOT_FOUND",
+                            "INVALID_RESOURCE_ID",
+                        );
+                    }
+                };
+                let captured_in_cents = capture.amount.to_cents().unwrap_or(0);
+                let amount_in_cents =
+                    match serde_json::from_value::<Amount>(request.body["amount"].clone()) {
+                        Ok(amount) => amount.to_cents().unwrap_or(0),
+                        Err(_) => captured_in_cents - capture.refunded_in_cents,
+                    };
+                if amount_in_cents <= 0
+                    || capture.refunded_in_cents + amount_in_cents > captured_in_cents
+                {
+                    return MockResponse::error(
+                        422,
+                        "UNPROCESSABLE_ENTITY",
+                        "REFUND_AMOUNT_EXCEEDED",
+                    );
+                }
+                capture.refunded_in_cents += amount_in_cents;
+                MockResponse::json(
+                    201,
+                    json!({
+                        "id": refund_id,
+                        "status": "COMPLETED",
+                        "amount": Amount::from_cents(amount_in_cents, &capture.amount.currency_code)
+                    }),
+                )
+            }
+            _ => MockResponse::error(404, "RESOURCE_NOT_FOUND", "INVALID_RESOURCE_ID"),
+        }
+    }
+
+    /// The page buyers are sent to, approving the order and returning them to Big Neon
+    fn checkout_page(&self, request: &MockRequest) -> MockResponse {
+        let order_id = request.query.get("token").cloned().unwrap_or_default();
+        if !self.approve(&order_id) {
+            return MockResponse::error(404, "RESOURCE_NOT_FOUND", "INVALID_RESOURCE_ID");
+        }
+        let state = self.state.lock().unwrap();
+        let return_url = &state.orders[&order_id]
+            .request
+            .application_context
+            .return_url;
+        let separator = if return_url.contains('?') { "&" } else { "?" };
+        MockResponse {
+            status: 302,
+            body: Value::Null,
+            location: Some(format!(
+                "{}{}token={}&PayerID=MOCKPAYER",
+                return_url, separator, order_id
+            )),
+        }
+    }
+}
+
+impl MockState {
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("MOCK{}{:08}", prefix, self.next_id)
+    }
+
+    fn order_json(&self, id: &str, base_url: &str) -> Value {
+        let order = &self.orders[id];
+        let mut purchase_units = json!(order.request.purchase_units);
+        if let Some(ref capture_id) = order.capture_id {
+            let capture = &self.captures[capture_id];
+            purchase_units[0]["payments"] = json!({
+                "captures": [{
+                    "id": capture_id,
+                    "status": if capture.refunded_in_cents > 0 { "PARTIALLY_REFUNDED" } else { capture.status.as_str() },
+                    "amount": capture.amount
+                }]
+            });
+        }
+        json!({
+            "id": id,
+            "status": order.status,
+            "purchase_units": purchase_units,
+            "links": [
+                { "href": format!("{}checkoutnow?token={}", base_url, id), "rel": "approve", "method": "GET" },
+                { "href": format!("{}v2/checkout/orders/{}", base_url, id), "rel": "self", "method": "GET" }
+            ]
+        })
+    }
+}
+
+fn read_request<R: BufRead>(reader: &mut R) -> io::Result<MockRequest> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Invalid HTTP request");
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or_else(invalid)?.to_string();
+    let target = parts.next().ok_or_else(invalid)?;
+    let mut target_parts = target.splitn(2, '?');
+    let path = target_parts.next().unwrap_or("/").to_string();
+    let query = target_parts
+        .next()
+        .unwrap_or("")
+        .split('&')
+        .filter_map(|pair| {
+            let mut key_value = pair.splitn(2, '=');
+            match (key_value.next(), key_value.next()) {
+                (Some(key), Some(value)) => Some((key.to_string(), value.to_string())),
+                _ => None,
+            }
+        })
+        .collect();
+
+    let mut content_length = 0;
+    let mut authorized = false;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let mut header = line.splitn(2, ':');
+        let name = header.next().unwrap_or("").trim().to_lowercase();
+        let value = header.next().unwrap_or("").trim();
+        match name.as_str() {
+            "content-length" => content_length = value.parse().map_err(|_| invalid())?,
+            "authorization" => authorized = !value.is_empty(),
+            _ => (),
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(MockRequest {
+        method,
+        path,
+        query,
+        authorized,
+        body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+    })
+}
+
+fn write_response(mut stream: TcpStream, response: MockResponse) -> io::Result<()> {
+    let body = match response.body {
+        Value::Null => String::new(),
+        ref body => body.to_string(),
+    };
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason_phrase(response.status),
+        body.len()
+    );
+    if let Some(location) = response.location {
+        head.push_str(&format!("Location: {}\r\n", location));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body.as_bytes())?;
+    stream.flush()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        302 => "Found",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        422 => "Unprocessable Entity",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use PaypalClient;
+
+    fn client(server: &MockServer) -> PaypalClient {
+        PaypalClient::new(
+            "client_id".to_string(),
+            "secret".to_string(),
+            server.base_url.clone(),
+        )
+    }
+
+    fn order_request() -> CreateOrderRequest {
+        CreateOrderRequest::new(
+            2500,
+            "USD",
+            "order-1".to_string(),
+            "http://localhost/return?success=true".to_string(),
+            "http://localhost/return?success=false".to_string(),
+        )
+    }
+
+    #[test]
+    fn create_approve_and_capture() {
+        let server = MockServer::start();
+        let client = client(&server);
+
+        let order = client.create_order(&order_request()).unwrap();
+        assert_eq!(order.status, "CREATED");
+        assert!(order.approve_url().unwrap().starts_with(&server.base_url));
+
+        // Orders cannot be captured before the buyer approves them
+        let error = client.capture_order(&order.id).unwrap_err();
+        assert_eq!(error.error_code, Some("ORDER_NOT_APPROVED".to_string()));
+
+        assert!(server.approve(&order.id));
+        let order = client.capture_order(&order.id).unwrap();
+        assert_eq!(order.status, "COMPLETED");
+        let captures = order.captures();
+        assert_eq!(captures.len(), 1);
+        assert_eq!(captures[0].amount.as_ref().unwrap().to_cents(), Some(2500));
+
+        client
+            .update_order_description(&order.id, "Event: Test")
+            .unwrap();
+        let order = client.get_order(&order.id).unwrap();
+        assert_eq!(
+            order.purchase_units[0].description,
+            Some("Event: Test".to_string())
+        );
+    }
+
+    #[test]
+    fn refund_capture() {
+        let server = MockServer::start();
+        let client = client(&server);
+        let order = client.create_order(&order_request()).unwrap();
+        server.approve(&order.id);
+        let order = client.capture_order(&order.id).unwrap();
+        let capture_id = order.captures()[0].id.clone();
+
+        let refund = client
+            .refund_capture(&capture_id, Some(Amount::from_cents(1000, "USD")), None)
+            .unwrap();
+        assert_eq!(refund.status, "COMPLETED");
+        assert_eq!(server.refunded_in_cents(&capture_id), Some(1000));
+
+        // The remainder is refunded when no amount is given
+        let refund = client.refund_capture(&capture_id, None, None).unwrap();
+        assert_eq!(refund.amount.unwrap().to_cents(), Some(1500));
+
+        let error = client
+            .refund_capture(&capture_id, Some(Amount::from_cents(1, "USD")), None)
+            .unwrap_err();
+        assert_eq!(error.error_code, Some("REFUND_AMOUNT_EXCEEDED".to_string()));
+    }
+}
@@ -0,0 +1,198 @@
+/// A PayPal Checkout order. Only the fields used by Big Neon are mapped.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Order {
+    pub id: String,
+    pub status: String,
+    #[serde(default)]
+    pub purchase_units: Vec<PurchaseUnit>,
+    #[serde(default)]
+    pub links: Vec<Link>,
+}
+
+impl Order {
+    /// The page the buyer is sent to for approving the order
+    pub fn approve_url(&self) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|l| l.rel == "approve")
+            .map(|l| l.href.as_str())
+    }
+
+    /// Captures made against the order once it has been approved and captured
+    pub fn captures(&self) -> Vec<&Capture> {
+        self.purchase_units
+            .iter()
+            .filter_map(|p| p.payments.as_ref())
+            .flat_map(|p| p.captures.iter())
+            .collect()
+    }
+
+    /// The total of the order's captures in cents, `None` if a capture has no amount or was made
+    /// in a different currency
+    pub fn captured_amount(&self, currency_code: &str) -> Option<i64> {
+        let mut total = 0;
+        for capture in self.captures() {
+            let amount = capture.amount.as_ref()?;
+            if !amount.currency_code.eq_ignore_ascii_case(currency_code) {
+                return None;
+            }
+            total += amount.to_cents()?;
+        }
+        Some(total)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PurchaseUnit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_id: Option<String>,
+    /// A reference used to link the order back to Big Neon
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payments: Option<PurchaseUnitPayments>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PurchaseUnitPayments {
+    #[serde(default)]
+    pub captures: Vec<Capture>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Capture {
+    pub id: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Refund {
+    pub id: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Link {
+    pub href: String,
+    pub rel: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+}
+
+/// PayPal amounts are decimal strings in the major unit of the currency
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Amount {
+    pub currency_code: String,
+    pub value: String,
+}
+
+impl Amount {
+    pub fn from_cents(amount_in_cents: i64, currency_code: &str) -> Amount {
+        Amount {
+            currency_code: currency_code.to_uppercase(),
+            value: format!("{}.{:02}", amount_in_cents / 100, amount_in_cents % 100),
+        }
+    }
+
+    pub fn to_cents(&self) -> Option<i64> {
+        let mut parts = self.value.splitn(2, '.');
+        let whole = parts.next()?.parse::<i64>().ok()?;
+        let fraction = match parts.next() {
+            Some(fraction) => format!("{:0<2}", fraction).get(0..2)?.parse::<i64>().ok()?,
+            None => 0,
+        };
+        Some(whole * 100 + fraction)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CreateOrderRequest {
+    pub intent: String,
+    pub purchase_units: Vec<PurchaseUnit>,
+    pub application_context: ApplicationContext,
+}
+
+impl CreateOrderRequest {
+    /// An order captured once the buyer has approved it and returned to `return_url`
+    pub fn new(
+        amount_in_cents: i64,
+        currency_code: &str,
+        custom_id: String,
+        return_url: String,
+        cancel_url: String,
+    ) -> CreateOrderRequest {
+        CreateOrderRequest {
+            intent: "CAPTURE".to_string(),
+            purchase_units: vec![PurchaseUnit {
+                custom_id: Some(custom_id),
+                amount: Some(Amount::from_cents(amount_in_cents, currency_code)),
+                ..Default::default()
+            }],
+            application_context: ApplicationContext {
+                return_url,
+                cancel_url,
+                user_action: Some("PAY_NOW".to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ApplicationContext {
+    pub return_url: String,
+    pub cancel_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_action: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn amount_cents() {
+        let amount = Amount::from_cents(1505, "usd");
+        assert_eq!(amount.currency_code, "USD");
+        assert_eq!(amount.value, "15.05");
+        assert_eq!(amount.to_cents(), Some(1505));
+        assert_eq!(Amount::from_cents(7, "USD").value, "0.07");
+
+        let amount = Amount {
+            currency_code: "USD".to_string(),
+            value: "12.5".to_string(),
+        };
+        assert_eq!(amount.to_cents(), Some(1250));
+        let amount = Amount {
+            currency_code: "USD".to_string(),
+            value: "12".to_string(),
+        };
+        assert_eq!(amount.to_cents(), Some(1200));
+    }
+
+    #[test]
+    fn deserialize_order() {
+        let order: Order = serde_json::from_str(
+            r#"{"id":"5O190127TN364715T","status":"COMPLETED","links":[{"href":"https://www.paypal.com/checkoutnow?token=5O190127TN364715T","rel":"approve","method":"GET"}],"purchase_units":[{"reference_id":"default","payments":{"captures":[{"id":"3C679366HH908993F","status":"COMPLETED","amount":{"currency_code":"USD","value":"100.00"}}]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            order.approve_url(),
+            Some("https://www.paypal.com/checkoutnow?token=5O190127TN364715T")
+        );
+        let captures = order.captures();
+        assert_eq!(captures.len(), 1);
+        assert_eq!(captures[0].id, "3C679366HH908993F");
+        assert_eq!(captures[0].amount.as_ref().unwrap().to_cents(), Some(10000));
+        assert_eq!(order.captured_amount("usd"), Some(10000));
+        assert_eq!(order.captured_amount("EUR"), None);
+    }
+}
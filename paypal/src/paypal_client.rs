@@ -0,0 +1,145 @@
+use log::Level::Debug;
+use reqwest;
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json;
+use Amount;
+use CreateOrderRequest;
+use Order;
+use PaypalError;
+use Refund;
+
+#[derive(Clone)]
+pub struct PaypalClient {
+    client_id: String,
+    secret: String,
+    base_url: String,
+}
+
+impl PaypalClient {
+    /// Creates a new PayPal client
+    /// base_url: Live: https://api.paypal.com/, sandbox: https://api.sandbox.paypal.com/
+    pub fn new(client_id: String, secret: String, base_url: String) -> PaypalClient {
+        PaypalClient {
+            client_id,
+            secret,
+            base_url: if base_url.ends_with("/") {
+                base_url
+            } else {
+                format!("{}/", base_url)
+            },
+        }
+    }
+
+    pub fn create_order(&self, request: &CreateOrderRequest) -> Result<Order, PaypalError> {
+        jlog!(Debug, "Creating PayPal order", { "request": request });
+        self.send(Method::POST, "v2/checkout/orders", Some(request))
+    }
+
+    pub fn get_order(&self, id: &str) -> Result<Order, PaypalError> {
+        self.send::<(), _>(Method::GET, &format!("v2/checkout/orders/{}", id), None)
+    }
+
+    /// Takes the payment for an order the buyer has approved
+    pub fn capture_order(&self, id: &str) -> Result<Order, PaypalError> {
+        jlog!(Debug, "Capturing PayPal order", { "id": id });
+        self.send(
+            Method::POST,
+            &format!("v2/checkout/orders/{}/capture", id),
+            Some(&json!({})),
+        )
+    }
+
+    pub fn update_order_description(&self, id: &str, description: &str) -> Result<(), PaypalError> {
+        let patch = json!([{
+            "op": "replace",
+            "path": "/purchase_units/@reference_id=='default'/description",
+            "value": description
+        }]);
+        let mut resp = self
+            .request(Method::PATCH, &format!("v2/checkout/orders/{}", id))?
+            .json(&patch)
+            .send()?;
+        match resp.status() {
+            StatusCode::OK | StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(PaypalError::from_response(&mut resp)),
+        }
+    }
+
+    /// Refunds a capture, in full when no amount is given. A refund retried with the same
+    /// `request_id` is only made once
+    pub fn refund_capture(
+        &self,
+        capture_id: &str,
+        amount: Option<Amount>,
+        request_id: Option<&str>,
+    ) -> Result<Refund, PaypalError> {
+        jlog!(Debug, "Refunding PayPal capture", { "capture_id": capture_id, "amount": &amount });
+        let body = match amount {
+            Some(amount) => json!({ "amount": amount }),
+            None => json!({}),
+        };
+        let mut request = self.request(
+            Method::POST,
+            &format!("v2/payments/captures/{}/refund", capture_id),
+        )?;
+        if let Some(request_id) = request_id {
+            request = request.header("PayPal-Request-Id", request_id);
+        }
+        self.send_request(request, Some(&body))
+    }
+
+    fn access_token(&self) -> Result<String, PaypalError> {
+        #[derive(Deserialize)]
+        struct R {
+            access_token: String,
+        }
+
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .post(&format!("{}v1/oauth2/token", self.base_url))
+            .basic_auth(&self.client_id, Some(&self.secret))
+            .form(&[("grant_type", "client_credentials")])
+            .send()?;
+        match resp.status() {
+            StatusCode::OK => Ok(resp.json::<R>()?.access_token),
+            _ => Err(PaypalError::from_response(&mut resp)),
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder, PaypalError> {
+        let access_token = self.access_token()?;
+        Ok(reqwest::Client::new()
+            .request(method, &format!("{}{}", self.base_url, path))
+            .header("Authorization", format!("Bearer {}", access_token)))
+    }
+
+    fn send<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, PaypalError> {
+        let request = self.request(method, path)?;
+        self.send_request(request, body)
+    }
+
+    fn send_request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+        body: Option<&B>,
+    ) -> Result<T, PaypalError> {
+        let mut request = request.header("Prefer", "return=representation");
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let mut resp = request.send()?;
+        if !resp.status().is_success() {
+            return Err(PaypalError::from_response(&mut resp));
+        }
+        let value: serde_json::Value = resp.json()?;
+        jlog!(Debug, "Response from PayPal", { "response": &value });
+        Ok(serde_json::from_value(value)?)
+    }
+}
@@ -0,0 +1,93 @@
+use reqwest;
+use serde_json;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct PaypalError {
+    pub description: String,
+    pub cause: Option<Arc<dyn Error>>,
+    /// The issue reported by PayPal, e.g. `ORDER_NOT_APPROVED`
+    pub error_code: Option<String>,
+}
+
+impl Error for PaypalError {
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+unsafe impl Send for PaypalError {}
+unsafe impl Sync for PaypalError {}
+
+impl fmt::Display for PaypalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match &self.cause {
+            Some(c) => write!(f, "{} caused by: {}", self.description, c.description()),
+            None => write!(f, "{}", self.description),
+        }
+    }
+}
+
+impl PaypalError {
+    pub fn from_response(response: &mut reqwest::Response) -> PaypalError {
+        let response_text = response
+            .text()
+            .unwrap_or("<Error reading response body>".to_string());
+
+        #[derive(Deserialize)]
+        struct Detail {
+            issue: String,
+        }
+        #[derive(Deserialize)]
+        struct R {
+            name: Option<String>,
+            #[serde(default)]
+            details: Vec<Detail>,
+        }
+        let error_code = serde_json::from_str::<R>(&response_text)
+            .ok()
+            .and_then(|r| match r.details.into_iter().next() {
+                Some(detail) => Some(detail.issue),
+                None => r.name,
+            });
+        PaypalError {
+            description: format!(
+                "Error calling PayPal: HTTP Code {}: Body:{}",
+                response.status(),
+                response_text
+            ),
+            cause: None,
+            error_code,
+        }
+    }
+
+    pub fn unexpected_response(description: &str) -> PaypalError {
+        PaypalError {
+            description: format!("Unexpected response from PayPal: {}", description),
+            cause: None,
+            error_code: None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for PaypalError {
+    fn from(r: reqwest::Error) -> Self {
+        PaypalError {
+            description: format!("Error calling PayPal: reqwest error {}", r),
+            cause: Some(Arc::new(r)),
+            error_code: None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for PaypalError {
+    fn from(r: serde_json::Error) -> Self {
+        PaypalError {
+            description: format!("Error deserializing response:{}", r),
+            cause: Some(Arc::new(r)),
+            error_code: None,
+        }
+    }
+}
@@ -25,6 +25,7 @@ export BLOCK_EXTERNAL_COMMS=1
 export TWILIO_ACCOUNT_ID=" "
 export TWILIO_API_KEY=" "
 export API_KEYS_ENCRYPTION_KEY="test_key"
+export SCANNER_MANIFEST_SECRET_KEY="1b23337c6215bce99f6bc74426c997d9c47c73688b8238a31a9960b89b93d613"
 export GLOBEE_API_KEY="GDFOzMkPAw79a8TCAHKkiknJB6bEYgbb"
 export GLOBEE_BASE_URL="https://test.globee.com/payment-api/v1/"
 export IPN_BASE_URL="TEST"
@@ -0,0 +1,32 @@
+use reqwest;
+use serde_json;
+use StripeError;
+
+/// A card saved as a source on a customer
+#[derive(Clone, Debug, Deserialize)]
+pub struct Card {
+    pub id: String,
+    pub brand: Option<String>,
+    pub last4: Option<String>,
+    pub exp_month: Option<i32>,
+    pub exp_year: Option<i32>,
+    #[serde(skip)]
+    pub raw_data: String,
+}
+
+impl Card {
+    pub fn to_json(&self) -> String {
+        self.raw_data.clone()
+    }
+
+    pub fn from_response(mut resp: reqwest::Response) -> Result<Card, StripeError> {
+        let raw: String = resp.text()?;
+        Card::from_value(serde_json::from_str(&raw)?)
+    }
+
+    pub(crate) fn from_value(value: serde_json::Value) -> Result<Card, StripeError> {
+        let mut card: Card = serde_json::from_value(value.clone())?;
+        card.raw_data = value.to_string();
+        Ok(card)
+    }
+}
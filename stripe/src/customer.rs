@@ -1,9 +1,12 @@
 use reqwest;
 use serde_json;
+use Card;
 use StripeError;
 
 pub struct Customer {
     pub id: String,
+    /// The card charged when no source is given
+    pub default_card: Option<Card>,
     pub raw_data: String,
 }
 
@@ -14,12 +17,31 @@ impl Customer {
     pub fn from_response(mut resp: reqwest::Response) -> Result<Customer, StripeError> {
         let raw: String = resp.text()?;
         #[derive(Deserialize)]
+        struct Sources {
+            #[serde(default)]
+            data: Vec<serde_json::Value>,
+        }
+        #[derive(Deserialize)]
         struct R {
             id: String,
+            default_source: Option<String>,
+            sources: Option<Sources>,
         }
         let result: R = serde_json::from_str(&raw)?;
+        let default_card = match (result.default_source, result.sources) {
+            (Some(default_source), Some(sources)) => match sources
+                .data
+                .into_iter()
+                .find(|s| s["id"].as_str() == Some(default_source.as_str()))
+            {
+                Some(source) => Some(Card::from_value(source)?),
+                None => None,
+            },
+            _ => None,
+        };
         Ok(Customer {
             id: result.id,
+            default_card,
             raw_data: raw,
         })
     }
@@ -1,4 +1,5 @@
 use reqwest;
+use Card;
 use ChargeResult;
 use Customer;
 use RefundResult;
@@ -22,7 +23,7 @@ impl StripeClient {
         description: &str,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeResult, StripeError> {
-        self.create_charge(token, amount, currency, description, true, metadata)
+        self.create_charge(token, None, amount, currency, description, true, metadata)
     }
 
     pub fn auth(
@@ -33,7 +34,28 @@ impl StripeClient {
         description: &str,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeResult, StripeError> {
-        self.create_charge(token, amount, currency, description, false, metadata)
+        self.create_charge(token, None, amount, currency, description, false, metadata)
+    }
+
+    /// Authorizes a charge against one of the cards saved on a customer
+    pub fn auth_customer_source(
+        &self,
+        customer_id: &str,
+        source_id: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<ChargeResult, StripeError> {
+        self.create_charge(
+            customer_id,
+            Some(source_id),
+            amount,
+            currency,
+            description,
+            false,
+            metadata,
+        )
     }
 
     pub fn update_metadata(
@@ -63,6 +85,7 @@ impl StripeClient {
     fn create_charge(
         &self,
         token: &str,
+        customer_source: Option<&str>,
         amount: i64,
         currency: &str,
         description: &str,
@@ -83,6 +106,9 @@ impl StripeClient {
             ),
             ("capture".to_string(), capture.to_string()),
         ];
+        if let Some(customer_source) = customer_source {
+            params.push(("source".to_string(), customer_source.to_string()));
+        }
 
         for key_value in metadata {
             params.push((format!("metadata[{}]", key_value.0), key_value.1));
@@ -118,10 +144,13 @@ impl StripeClient {
         }
     }
 
+    /// Refunds part of a charge, Stripe makes a refund retried with the same `idempotency_key`
+    /// only once
     pub fn partial_refund(
         &self,
         charge_id: &str,
         amount: u32,
+        idempotency_key: Option<&str>,
     ) -> Result<RefundResult, StripeError> {
         let params = vec![
             ("charge".to_string(), charge_id.to_string()),
@@ -129,11 +158,13 @@ impl StripeClient {
         ];
 
         let client = reqwest::Client::new();
-        let mut resp = client
+        let mut request = client
             .post("https://api.stripe.com/v1/refunds")
-            .basic_auth(&self.api_key, Some(""))
-            .form(&params)
-            .send()?;
+            .basic_auth(&self.api_key, Some(""));
+        if let Some(idempotency_key) = idempotency_key {
+            request = request.header("Idempotency-Key", idempotency_key);
+        }
+        let mut resp = request.form(&params).send()?;
         match resp.status() {
             reqwest::StatusCode::OK => {
                 return RefundResult::from_response(resp);
@@ -219,4 +250,48 @@ impl StripeClient {
             _ => return Err(StripeError::from_response(&mut resp)),
         }
     }
+
+    /// Saves another card on the customer, leaving the existing cards in place
+    pub fn add_customer_source(
+        &self,
+        customer_id: &str,
+        source: &str,
+    ) -> Result<Card, StripeError> {
+        let params = vec![("source".to_string(), source.to_string())];
+
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .post(&format!(
+                "https://api.stripe.com/v1/customers/{}/sources",
+                customer_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .form(&params)
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return Card::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
+    pub fn delete_customer_source(
+        &self,
+        customer_id: &str,
+        source_id: &str,
+    ) -> Result<(), StripeError> {
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .delete(&format!(
+                "https://api.stripe.com/v1/customers/{}/sources/{}",
+                customer_id, source_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => Ok(()),
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
 }
@@ -0,0 +1,139 @@
+use hex;
+use ring::{digest, hmac};
+use serde_json;
+use std::time::{SystemTime, UNIX_EPOCH};
+use StripeError;
+
+/// Signed events older than this are rejected so that captured requests cannot be replayed
+const SIGNATURE_TOLERANCE_SECONDS: i64 = 300;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub created: i64,
+    pub data: WebhookEventData,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebhookEventData {
+    pub object: serde_json::Value,
+}
+
+impl WebhookEvent {
+    /// Parses a webhook body after checking it against the `Stripe-Signature` header
+    pub fn construct(
+        payload: &str,
+        signature_header: &str,
+        secret: &str,
+    ) -> Result<WebhookEvent, StripeError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        verify_signature(payload, signature_header, secret, now)?;
+        Ok(serde_json::from_str(payload)?)
+    }
+
+    /// The charge the event relates to, for charge and dispute events
+    pub fn charge_id(&self) -> Option<String> {
+        let object = &self.data.object;
+        match object["object"].as_str() {
+            Some("charge") => object["id"].as_str().map(|id| id.to_string()),
+            Some("dispute") => object["charge"].as_str().map(|id| id.to_string()),
+            _ => None,
+        }
+    }
+}
+
+pub fn verify_signature(
+    payload: &str,
+    signature_header: &str,
+    secret: &str,
+    now: i64,
+) -> Result<(), StripeError> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in signature_header.split(',') {
+        let mut key_value = part.trim().splitn(2, '=');
+        match (key_value.next(), key_value.next()) {
+            (Some("t"), Some(value)) => timestamp = value.parse::<i64>().ok(),
+            (Some("v1"), Some(value)) => signatures.push(value),
+            _ => (),
+        }
+    }
+
+    let timestamp = match timestamp {
+        Some(timestamp) => timestamp,
+        None => return Err(invalid_signature("Signature header has no timestamp")),
+    };
+    if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECONDS {
+        return Err(invalid_signature(
+            "Signature timestamp is outside the tolerance",
+        ));
+    }
+
+    let key = hmac::VerificationKey::new(&digest::SHA256, secret.as_bytes());
+    let signed_payload = format!("{}.{}", timestamp, payload);
+    for signature in signatures {
+        if let Ok(signature) = hex::decode(signature) {
+            if hmac::verify(&key, signed_payload.as_bytes(), &signature).is_ok() {
+                return Ok(());
+            }
+        }
+    }
+    Err(invalid_signature(
+        "No signatures found matching the payload",
+    ))
+}
+
+/// Builds a `Stripe-Signature` header the way Stripe does, for sending test events
+pub fn signature_header(payload: &str, secret: &str, timestamp: i64) -> String {
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    let signature = hmac::sign(&key, format!("{}.{}", timestamp, payload).as_bytes());
+    format!("t={},v1={}", timestamp, hex::encode(signature.as_ref()))
+}
+
+fn invalid_signature(description: &str) -> StripeError {
+    StripeError {
+        description: format!("Invalid webhook signature: {}", description),
+        cause: None,
+        error_code: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PAYLOAD: &str = r#"{"id":"evt_1","type":"charge.refunded","created":1551700000,"data":{"object":{"id":"ch_1","object":"charge"}}}"#;
+
+    #[test]
+    fn verify_signature_matches() {
+        let header = signature_header(PAYLOAD, "whsec_test", 1551700000);
+        assert!(verify_signature(PAYLOAD, &header, "whsec_test", 1551700010).is_ok());
+
+        // Additional signatures are present while secrets are being rolled
+        let header = format!("{},v1=0123,v0=abcd", header);
+        assert!(verify_signature(PAYLOAD, &header, "whsec_test", 1551700010).is_ok());
+    }
+
+    #[test]
+    fn verify_signature_rejects_invalid() {
+        let header = signature_header(PAYLOAD, "whsec_test", 1551700000);
+        assert!(verify_signature(PAYLOAD, &header, "whsec_other", 1551700000).is_err());
+        assert!(verify_signature("{}", &header, "whsec_test", 1551700000).is_err());
+        assert!(verify_signature(PAYLOAD, &header, "whsec_test", 1551701000).is_err());
+        assert!(verify_signature(PAYLOAD, "v1=abcd", "whsec_test", 1551700000).is_err());
+    }
+
+    #[test]
+    fn deserialize_event() {
+        let event: WebhookEvent = serde_json::from_str(PAYLOAD).unwrap();
+        assert_eq!(event.id, "evt_1");
+        assert_eq!(event.event_type, "charge.refunded");
+        assert_eq!(event.data.object["id"], "ch_1");
+        assert_eq!(event.charge_id(), Some("ch_1".to_string()));
+    }
+}
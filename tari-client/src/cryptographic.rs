@@ -48,6 +48,13 @@ pub fn cryptographic_keypair() -> (Vec<u8>, Vec<u8>) {
     (secret_key_bytes, public_key_bytes)
 }
 
+pub fn cryptographic_public_key(secret_key: &Vec<u8>) -> Result<Vec<u8>, TariError> {
+    let secp = Secp256k1::new();
+    let secp_secret_key = SecretKey::from_slice(&secp, &secret_key)?;
+    let secp_public_key = PublicKey::from_secret_key(&secp, &secp_secret_key);
+    Ok(secp_public_key.serialize().to_vec())
+}
+
 pub fn cryptographic_hash(input_msg: &String) -> Vec<u8> {
     //H=RIPEMD160(SHA256(message))
     //Hash Message using Sha256